@@ -18,7 +18,7 @@ decrypt() {
   local ciphertext="$1"
   local ascii
   local plaintext
-  ascii=$(echo -n "$ciphertext" | cargo run --quiet -- decrypt one-time-pad --key "$KEY" --raw-input --raw-key)
+  ascii=$(echo -n "$ciphertext" | cargo run --quiet -- decrypt one-time-pad --key "$KEY" --raw-input --key-format hex)
   plaintext=$(echo -n "$ascii" | cargo run --quiet -- hex --raw --ascii)
   echo "$plaintext"
 }
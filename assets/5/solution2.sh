@@ -22,7 +22,7 @@ decrypt() {
   local key="$2"
   local ascii
   local plaintext
-  ascii=$(echo -n "$ciphertext" | cargo run --quiet -- decrypt one-time-pad --key "$key" --raw-input --raw-key)
+  ascii=$(echo -n "$ciphertext" | cargo run --quiet -- decrypt one-time-pad --key "$key" --raw-input --key-format hex)
   plaintext=$(echo -n "$ascii" | cargo run --quiet -- hex --raw --ascii)
   echo "$plaintext"
 }
@@ -0,0 +1,289 @@
+use clap::ValueEnum;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  transposition::ColumnarTransposition, DecryptCipher, EncryptCipher,
+};
+
+/// The size of the Polybius square used for fractionation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AdfgvxVariant {
+  /// 5x5 square over the letters A-Z with I and J merged.
+  Adfgx,
+  /// 6x6 square over the letters A-Z and the digits 0-9.
+  #[default]
+  Adfgvx,
+}
+
+impl AdfgvxVariant {
+  pub fn coordinates(&self) -> &'static str {
+    match self {
+      AdfgvxVariant::Adfgx => "ADFGX",
+      AdfgvxVariant::Adfgvx => "ADFGVX",
+    }
+  }
+
+  pub fn alphabet(&self) -> &'static str {
+    match self {
+      AdfgvxVariant::Adfgx => "ABCDEFGHIKLMNOPQRSTUVWXYZ",
+      AdfgvxVariant::Adfgvx => "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+    }
+  }
+
+  /// Maps a character onto the square's alphabet, if it can be encoded.
+  pub fn normalize(&self, c: char) -> Option<char> {
+    let c = c.to_ascii_uppercase();
+    match self {
+      AdfgvxVariant::Adfgx if c == 'J' => Some('I'),
+      AdfgvxVariant::Adfgx => c.is_ascii_uppercase().then_some(c),
+      AdfgvxVariant::Adfgvx => {
+        (c.is_ascii_uppercase() || c.is_ascii_digit()).then_some(c)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AdfgvxConfig {
+  pub polybius_key: String,
+  pub transposition_key: String,
+  pub variant: AdfgvxVariant,
+}
+
+impl AdfgvxConfig {
+  pub fn new(
+    polybius_key: &str,
+    transposition_key: &str,
+    variant: AdfgvxVariant,
+  ) -> Self {
+    Self {
+      polybius_key: polybius_key.to_string(),
+      transposition_key: transposition_key.to_string(),
+      variant,
+    }
+  }
+}
+
+impl From<&EncryptCipher> for AdfgvxConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Adfgvx {
+        polybius_key,
+        key,
+        variant,
+        ..
+      } => Self::new(polybius_key, key.key(), *variant),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for AdfgvxConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Adfgvx {
+        polybius_key,
+        key,
+        variant,
+        ..
+      } => Self::new(polybius_key, key.key(), *variant),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A keyword-mixed Polybius square.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PolybiusSquare {
+  variant: AdfgvxVariant,
+  cells: Vec<char>,
+}
+
+impl PolybiusSquare {
+  /// Fills the square with the deduplicated keyword followed by the
+  /// remaining characters of the alphabet.
+  pub fn new(keyword: &str, variant: AdfgvxVariant) -> Self {
+    let mut cells = Vec::new();
+    let keyword = keyword.chars().filter_map(|c| variant.normalize(c));
+
+    for c in keyword.chain(variant.alphabet().chars()) {
+      if !cells.contains(&c) {
+        cells.push(c);
+      }
+    }
+
+    Self { variant, cells }
+  }
+
+  pub fn fractionate(&self, c: char) -> Option<[char; 2]> {
+    let coordinates: Vec<char> = self.variant.coordinates().chars().collect();
+    let size = coordinates.len();
+    let c = self.variant.normalize(c)?;
+    let index = self.cells.iter().position(|&cell| cell == c)?;
+    Some([coordinates[index / size], coordinates[index % size]])
+  }
+
+  pub fn combine(&self, row: char, column: char) -> Option<char> {
+    let coordinates = self.variant.coordinates();
+    let size = coordinates.len();
+    let row = coordinates.find(row)?;
+    let column = coordinates.find(column)?;
+    self.cells.get(row * size + column).copied()
+  }
+}
+
+pub struct Adfgvx;
+
+impl Adfgvx {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AdfgvxConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let cipher = Self::encrypt_text(line, &config)?;
+      writeln!(output, "{cipher}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AdfgvxConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let plaintext = Self::decrypt_text(line, &config)?;
+      writeln!(output, "{plaintext}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn encrypt_text(text: &str, config: &AdfgvxConfig) -> Result<String> {
+    let square = PolybiusSquare::new(&config.polybius_key, config.variant);
+    let fractionated: String = text
+      .chars()
+      .filter_map(|c| square.fractionate(c))
+      .flatten()
+      .collect();
+
+    ColumnarTransposition::encrypt_text(
+      &fractionated,
+      &config.transposition_key,
+    )
+  }
+
+  pub fn decrypt_text(text: &str, config: &AdfgvxConfig) -> Result<String> {
+    let square = PolybiusSquare::new(&config.polybius_key, config.variant);
+    let coordinates = config.variant.coordinates();
+    let cipher: String = text
+      .chars()
+      .map(|c| c.to_ascii_uppercase())
+      .filter(|&c| coordinates.contains(c))
+      .collect();
+
+    if cipher.len() % 2 != 0 {
+      let message = "Ciphertext must contain an even number of symbols";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let fractionated: Vec<char> =
+      ColumnarTransposition::decrypt_text(&cipher, &config.transposition_key)?
+        .chars()
+        .collect();
+
+    let plaintext = fractionated
+      .chunks(2)
+      .filter_map(|pair| square.combine(pair[0], pair[1]))
+      .collect();
+
+    Ok(plaintext)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  const ADFGVX_SQUARE: &str = "NA1C3H8TB2OME5WRPD4F6G7I9J0KLQSUVXYZ";
+  const ADFGX_SQUARE: &str = "BTALPDHOZKQFVSNGICUXMREWY";
+
+  #[test]
+  fn test_polybius_square_from_keyword() {
+    let square = PolybiusSquare::new("PLAYFAIR", AdfgvxVariant::Adfgx);
+    let cells: String = square.cells.iter().collect();
+    assert_eq!(cells, "PLAYFIRBCDEGHKMNOQSTUVWXZ");
+  }
+
+  #[test]
+  fn test_polybius_square_merges_i_and_j() {
+    let square = PolybiusSquare::new("", AdfgvxVariant::Adfgx);
+    assert_eq!(square.fractionate('J'), square.fractionate('I'));
+  }
+
+  #[test]
+  fn test_adfgvx_historical_example() {
+    let config =
+      AdfgvxConfig::new(ADFGVX_SQUARE, "PRIVACY", AdfgvxVariant::Adfgvx);
+    let cipher = Adfgvx::encrypt_text("attack at 1200am", &config).unwrap();
+    assert_eq!(cipher, "DGDDDAGDDGAFADDFDADVDVFAADVX");
+  }
+
+  #[test]
+  fn test_adfgvx_decrypt_historical_example() {
+    let config =
+      AdfgvxConfig::new(ADFGVX_SQUARE, "PRIVACY", AdfgvxVariant::Adfgvx);
+    let plaintext =
+      Adfgvx::decrypt_text("DGDDD AGDDG AFADD FDADV DVFAA DVX", &config)
+        .unwrap();
+    assert_eq!(plaintext, "ATTACKAT1200AM");
+  }
+
+  #[test]
+  fn test_adfgx_historical_example() {
+    let config = AdfgvxConfig::new(ADFGX_SQUARE, "CARGO", AdfgvxVariant::Adfgx);
+    let cipher = Adfgvx::encrypt_text("ATTACK AT ONCE", &config).unwrap();
+    assert_eq!(cipher, "FAXDFADDDGDGFFFAFAXAFAFX");
+  }
+
+  #[test]
+  fn test_adfgx_decrypt_historical_example() {
+    let config = AdfgvxConfig::new(ADFGX_SQUARE, "CARGO", AdfgvxVariant::Adfgx);
+    let plaintext =
+      Adfgvx::decrypt_text("FAXDF ADDDG DGFFF AFAXA FAFX", &config).unwrap();
+    assert_eq!(plaintext, "ATTACKATONCE");
+  }
+
+  #[test]
+  fn test_decrypt_rejects_odd_length() {
+    let config = AdfgvxConfig::new("", "KEY", AdfgvxVariant::Adfgvx);
+    assert!(Adfgvx::decrypt_text("ADF", &config).is_err());
+  }
+
+  #[test]
+  fn test_encrypt_and_decrypt_lines() -> Result<()> {
+    let mut input = Cursor::new("Meet me at 9\nat the old bridge\n");
+    let mut cipher = Vec::new();
+    let config = AdfgvxConfig::new("KRYPTOS", "GERMAN", AdfgvxVariant::Adfgvx);
+    Adfgvx::encrypt(&mut input, &mut cipher, config)?;
+
+    let mut input = Cursor::new(cipher);
+    let mut output = Vec::new();
+    let config = AdfgvxConfig::new("KRYPTOS", "GERMAN", AdfgvxVariant::Adfgvx);
+    Adfgvx::decrypt(&mut input, &mut output, config)?;
+
+    let plaintext = String::from_utf8(output).unwrap();
+    assert_eq!(plaintext, "MEETMEAT9\nATTHEOLDBRIDGE\n");
+    Ok(())
+  }
+}
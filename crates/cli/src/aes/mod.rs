@@ -0,0 +1,379 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::sync::LazyLock;
+
+use crate::{hex::Hex, Command};
+
+/// Block and key size of AES-128 in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Number of rounds of AES-128.
+const ROUNDS: usize = 10;
+
+/// Irreducible polynomial `x^8 + x^4 + x^3 + x + 1` of the byte field.
+const POLYNOMIAL: u8 = 0x1b;
+
+/// The S-box maps every byte to its inverse in GF(2^8), followed by an
+/// affine transformation that removes the fixed points.
+static SBOX: LazyLock<[u8; 256]> = LazyLock::new(|| {
+  std::array::from_fn(|byte| {
+    let inverse = Aes::inverse(byte as u8);
+    inverse
+      ^ inverse.rotate_left(1)
+      ^ inverse.rotate_left(2)
+      ^ inverse.rotate_left(3)
+      ^ inverse.rotate_left(4)
+      ^ 0x63
+  })
+});
+
+static INVERSE_SBOX: LazyLock<[u8; 256]> = LazyLock::new(|| {
+  let mut inverse = [0; 256];
+  for (byte, &substituted) in SBOX.iter().enumerate() {
+    inverse[substituted as usize] = byte as u8;
+  }
+  inverse
+});
+
+/// The state after applying one operation of a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AesStep {
+  pub round: usize,
+  pub operation: &'static str,
+  /// Bytes in column-major order as in FIPS 197.
+  pub state: [u8; BLOCK_SIZE],
+}
+
+impl AesStep {
+  /// Writes the state as a 4x4 matrix with one row per line.
+  pub fn write<W: Write>(&self, output: &mut W) -> Result<()> {
+    writeln!(output, "round {} {}", self.round, self.operation)?;
+    for row in 0..4 {
+      let bytes: Vec<String> = (0..4)
+        .map(|column| format!("{:02x}", self.state[column * 4 + row]))
+        .collect();
+      writeln!(output, "  {}", bytes.join(" "))?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AesConfig {
+  /// Key as 32 hex digits.
+  pub key: String,
+  pub decrypt: bool,
+  /// Write the state after every operation before each result.
+  pub trace: bool,
+}
+
+impl AesConfig {
+  pub fn new(key: String, decrypt: bool, trace: bool) -> Self {
+    Self {
+      key,
+      decrypt,
+      trace,
+    }
+  }
+}
+
+impl From<&Command> for AesConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Aes {
+        key,
+        decrypt,
+        trace,
+        ..
+      } => Self::new(key.clone().unwrap_or_default(), *decrypt, *trace),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// AES-128 for tracing by hand, which makes no attempt to resist timing
+/// attacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aes {
+  round_keys: [[u8; BLOCK_SIZE]; ROUNDS + 1],
+}
+
+impl Aes {
+  pub fn new(key: &[u8; BLOCK_SIZE]) -> Self {
+    Self {
+      round_keys: Self::expand_key(key),
+    }
+  }
+
+  /// Reads hex blocks and writes each encrypted or decrypted block as
+  /// hex on its own line, preceded by its trace if enabled.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AesConfig,
+  ) -> Result<()> {
+    let key: [u8; BLOCK_SIZE] =
+      Hex::parse_hex(&config.key)?.bytes.try_into().map_err(|_| {
+        let message = format!("The key must be {BLOCK_SIZE} bytes");
+        Error::new(ErrorKind::InvalidInput, message)
+      })?;
+    let aes = Self::new(&key);
+
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let digits: String = content.split_whitespace().collect();
+    let bytes = Hex::parse_hex(&digits)?.bytes;
+
+    if bytes.len() % BLOCK_SIZE != 0 {
+      let message = format!("The input must be whole {BLOCK_SIZE} byte blocks");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    for (index, block) in bytes.chunks_exact(BLOCK_SIZE).enumerate() {
+      let block = block.try_into().unwrap();
+      let steps = match config.decrypt {
+        true => aes.decrypt_steps(&block),
+        false => aes.encrypt_steps(&block),
+      };
+
+      if config.trace {
+        writeln!(output, "block {index}")?;
+        for step in &steps {
+          step.write(output)?;
+        }
+      }
+
+      let result = steps.last().map(|step| step.state).unwrap_or(block);
+      writeln!(output, "{}", Hex::new(result.to_vec()))?;
+    }
+
+    Ok(())
+  }
+
+  pub fn encrypt_block(&self, block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    self.encrypt_steps(block).last().unwrap().state
+  }
+
+  pub fn decrypt_block(&self, block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    self.decrypt_steps(block).last().unwrap().state
+  }
+
+  /// The input, followed by the state after every operation, ending in
+  /// the ciphertext. The last round skips MixColumns.
+  pub fn encrypt_steps(&self, block: &[u8; BLOCK_SIZE]) -> Vec<AesStep> {
+    let mut state = *block;
+    let mut steps = Vec::new();
+    let mut record = |round, operation, state: &[u8; BLOCK_SIZE]| {
+      steps.push(AesStep {
+        round,
+        operation,
+        state: *state,
+      })
+    };
+
+    record(0, "input", &state);
+    Self::add_round_key(&mut state, &self.round_keys[0]);
+    record(0, "AddRoundKey", &state);
+
+    for round in 1..=ROUNDS {
+      Self::sub_bytes(&mut state, &SBOX);
+      record(round, "SubBytes", &state);
+      Self::shift_rows(&mut state);
+      record(round, "ShiftRows", &state);
+      if round < ROUNDS {
+        Self::mix_columns(&mut state);
+        record(round, "MixColumns", &state);
+      }
+      Self::add_round_key(&mut state, &self.round_keys[round]);
+      record(round, "AddRoundKey", &state);
+    }
+
+    steps
+  }
+
+  /// The inverse cipher of FIPS 197, which undoes the rounds in reverse.
+  pub fn decrypt_steps(&self, block: &[u8; BLOCK_SIZE]) -> Vec<AesStep> {
+    let mut state = *block;
+    let mut steps = Vec::new();
+    let mut record = |round, operation, state: &[u8; BLOCK_SIZE]| {
+      steps.push(AesStep {
+        round,
+        operation,
+        state: *state,
+      })
+    };
+
+    record(0, "input", &state);
+    Self::add_round_key(&mut state, &self.round_keys[ROUNDS]);
+    record(0, "AddRoundKey", &state);
+
+    for round in 1..=ROUNDS {
+      Self::inverse_shift_rows(&mut state);
+      record(round, "InvShiftRows", &state);
+      Self::sub_bytes(&mut state, &INVERSE_SBOX);
+      record(round, "InvSubBytes", &state);
+      Self::add_round_key(&mut state, &self.round_keys[ROUNDS - round]);
+      record(round, "AddRoundKey", &state);
+      if round < ROUNDS {
+        Self::inverse_mix_columns(&mut state);
+        record(round, "InvMixColumns", &state);
+      }
+    }
+
+    steps
+  }
+
+  /// Every round key word is the previous word XOR the word four before,
+  /// where every fourth word is first rotated, substituted and XORed
+  /// with a round constant.
+  pub fn expand_key(key: &[u8; BLOCK_SIZE]) -> [[u8; BLOCK_SIZE]; ROUNDS + 1] {
+    let mut words: Vec<[u8; 4]> = key
+      .chunks_exact(4)
+      .map(|word| word.try_into().unwrap())
+      .collect();
+    let mut constant = 1;
+
+    for index in 4..4 * (ROUNDS + 1) {
+      let mut word = words[index - 1];
+      if index % 4 == 0 {
+        word.rotate_left(1);
+        word = word.map(|byte| SBOX[byte as usize]);
+        word[0] ^= constant;
+        constant = Self::double(constant);
+      }
+      let before = words[index - 4];
+      words.push(std::array::from_fn(|i| word[i] ^ before[i]));
+    }
+
+    std::array::from_fn(|round| {
+      std::array::from_fn(|i| words[round * 4 + i / 4][i % 4])
+    })
+  }
+
+  fn add_round_key(state: &mut [u8; BLOCK_SIZE], key: &[u8; BLOCK_SIZE]) {
+    for (byte, key) in state.iter_mut().zip(key) {
+      *byte ^= key;
+    }
+  }
+
+  fn sub_bytes(state: &mut [u8; BLOCK_SIZE], sbox: &[u8; 256]) {
+    for byte in state.iter_mut() {
+      *byte = sbox[*byte as usize];
+    }
+  }
+
+  /// Rotates row `r` left by `r` bytes.
+  fn shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    let before = *state;
+    for column in 0..4 {
+      for row in 0..4 {
+        state[column * 4 + row] = before[(column + row) % 4 * 4 + row];
+      }
+    }
+  }
+
+  fn inverse_shift_rows(state: &mut [u8; BLOCK_SIZE]) {
+    let before = *state;
+    for column in 0..4 {
+      for row in 0..4 {
+        state[(column + row) % 4 * 4 + row] = before[column * 4 + row];
+      }
+    }
+  }
+
+  /// Multiplies every column with the circulant matrix of 2, 3, 1, 1.
+  fn mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    Self::multiply_columns(state, [2, 3, 1, 1]);
+  }
+
+  /// Multiplies every column with the circulant matrix of 14, 11, 13, 9.
+  fn inverse_mix_columns(state: &mut [u8; BLOCK_SIZE]) {
+    Self::multiply_columns(state, [14, 11, 13, 9]);
+  }
+
+  fn multiply_columns(state: &mut [u8; BLOCK_SIZE], coefficients: [u8; 4]) {
+    for column in state.chunks_exact_mut(4) {
+      let before: [u8; 4] = column.try_into().unwrap();
+      for (row, byte) in column.iter_mut().enumerate() {
+        *byte = (0..4).fold(0, |sum, i| {
+          sum ^ Self::multiply(coefficients[(4 + i - row) % 4], before[i])
+        });
+      }
+    }
+  }
+
+  /// Multiplication by `x` in GF(2^8).
+  fn double(byte: u8) -> u8 {
+    match byte & 0x80 {
+      0 => byte << 1,
+      _ => (byte << 1) ^ POLYNOMIAL,
+    }
+  }
+
+  fn multiply(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+      if b & 1 == 1 {
+        product ^= a;
+      }
+      a = Self::double(a);
+      b >>= 1;
+    }
+    product
+  }
+
+  /// `a^254`, which is the inverse of `a` since the multiplicative group
+  /// has order 255, and maps zero to zero.
+  fn inverse(byte: u8) -> u8 {
+    (0..254).fold(1, |power, _| Self::multiply(power, byte))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn block(hex: &str) -> [u8; BLOCK_SIZE] {
+    Hex::parse_hex(hex).unwrap().bytes.try_into().unwrap()
+  }
+
+  #[test]
+  fn test_sbox() {
+    assert_eq!(SBOX[0x00], 0x63);
+    assert_eq!(SBOX[0x53], 0xed);
+    assert_eq!(INVERSE_SBOX[0xed], 0x53);
+  }
+
+  #[test]
+  fn test_expand_key() {
+    let keys = Aes::expand_key(&block("2b7e151628aed2a6abf7158809cf4f3c"));
+    assert_eq!(keys[1], block("a0fafe1788542cb123a339392a6c7605"));
+    assert_eq!(keys[10], block("d014f9a8c9ee2589e13f0cc8b6630ca6"));
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let aes = Aes::new(&block("000102030405060708090a0b0c0d0e0f"));
+    let plaintext = block("00112233445566778899aabbccddeeff");
+    let ciphertext = aes.encrypt_block(&plaintext);
+    assert_eq!(ciphertext, block("69c4e0d86a7b0430d8cdb78070b4c55a"));
+    assert_eq!(aes.decrypt_block(&ciphertext), plaintext);
+  }
+
+  #[test]
+  fn test_trace() -> Result<()> {
+    let mut output = Vec::new();
+    let key = "2b7e151628aed2a6abf7158809cf4f3c".to_string();
+    let input = "3243f6a8885a308d313198a2e0370734";
+    let config = AesConfig::new(key, false, true);
+    Aes::execute(&mut input.as_bytes(), &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    let expected = "round 1 SubBytes\n  d4 e0 b8 1e\n  27 bf b4 41\n  \
+                    11 98 5d 52\n  ae f1 e5 30\n";
+    assert!(output.starts_with("block 0\nround 0 input\n"));
+    assert!(output.contains(expected));
+    assert!(!output.contains("round 10 MixColumns"));
+    assert!(output.ends_with("3925841d02dc09fbdc118597196a0b32\n"));
+    Ok(())
+  }
+}
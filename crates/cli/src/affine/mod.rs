@@ -0,0 +1,346 @@
+use rayon::prelude::*;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  alphabet::Alphabet,
+  frequency_analysis::{FrequencyAnalyzer, Language},
+  multiplicative::Multiplicative,
+  report::CrackReport,
+  DecryptCipher, EncryptCipher,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AffineConfig {
+  /// The multiplier and shift, if known.
+  pub key: Option<(usize, usize)>,
+  /// The symbols that are mapped, `A..=Z` by default.
+  pub alphabet: Alphabet,
+  /// The language whose letter frequencies the key is recovered with.
+  pub language: Language,
+}
+
+impl AffineConfig {
+  pub fn new(key: Option<(usize, usize)>) -> Self {
+    Self {
+      key,
+      ..Self::default()
+    }
+  }
+}
+
+impl From<&EncryptCipher> for AffineConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Affine {
+        multiplier, shift, ..
+      } => Self::new(Some((*multiplier, *shift))),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for AffineConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Affine {
+        multiplier, shift, ..
+      } => Self::new(multiplier.zip(*shift)),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A candidate decryption found while brute forcing the key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffineCandidate {
+  pub multiplier: usize,
+  pub shift: usize,
+  pub plaintext: String,
+  pub score: f32,
+  pub language: Language,
+}
+
+impl From<AffineCandidate> for CrackReport {
+  fn from(candidate: AffineCandidate) -> Self {
+    let key = Some(Affine::key(candidate.multiplier, candidate.shift));
+    let method = "frequency analysis";
+    CrackReport::new_in(candidate.plaintext, key, method, candidate.language)
+  }
+}
+
+pub struct Affine;
+
+impl Affine {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AffineConfig,
+  ) -> Result<()> {
+    let key = config.key.unwrap_or_default();
+    let (multiplier, shift) = Self::validate(key, &config.alphabet)?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let cipher =
+        Self::encrypt_text_in(line, multiplier, shift, &config.alphabet);
+      writeln!(output, "{cipher}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Decrypts every line with the configured key, or with the key whose
+  /// plaintext looks most like the language if none is given.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AffineConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`Affine::decrypt`] and reports the key
+  /// along with the method that found it.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &AffineConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let alphabet = &config.alphabet;
+    let key = config.key.map(|key| Self::validate(key, alphabet));
+    let key = key.transpose()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let reports = content.lines().map(|line| match key {
+      Some((multiplier, shift)) => {
+        let plaintext =
+          Self::decrypt_text_in(line, multiplier, shift, alphabet);
+        let key = Some(Self::key(multiplier, shift));
+        CrackReport::new_in(plaintext, key, "key", config.language)
+      }
+      None => match Self::rank(line, config).into_iter().next() {
+        Some(candidate) => candidate.into(),
+        None => {
+          let method = "frequency analysis";
+          CrackReport::new_in(String::new(), None, method, config.language)
+        }
+      },
+    });
+    Ok(reports.collect())
+  }
+
+  /// Maps every letter `x` to `a * x + b mod 26`, keeping its case. Other
+  /// characters are left as they are.
+  pub fn encrypt_text(text: &str, multiplier: usize, shift: usize) -> String {
+    Self::encrypt_text_in(text, multiplier, shift, &Alphabet::default())
+  }
+
+  /// Maps every symbol of the alphabet at `x` to the one at `a * x + b`
+  /// modulo the size of the alphabet.
+  pub fn encrypt_text_in(
+    text: &str,
+    multiplier: usize,
+    shift: usize,
+    alphabet: &Alphabet,
+  ) -> String {
+    let modulus = alphabet.len();
+    text
+      .chars()
+      .map(|c| {
+        Self::map(c, alphabet, |x| Self::apply(x, multiplier, shift, modulus))
+      })
+      .collect()
+  }
+
+  /// Maps every letter `y` back to `(y - b) / a mod 26`.
+  pub fn decrypt_text(text: &str, multiplier: usize, shift: usize) -> String {
+    Self::decrypt_text_in(text, multiplier, shift, &Alphabet::default())
+  }
+
+  /// Maps every symbol of the alphabet at `y` back to the one at
+  /// `(y - b) / a` modulo the size of the alphabet.
+  pub fn decrypt_text_in(
+    text: &str,
+    multiplier: usize,
+    shift: usize,
+    alphabet: &Alphabet,
+  ) -> String {
+    let modulus = alphabet.len();
+    let inverse = Multiplicative::inverse_mod(multiplier, modulus).unwrap_or(1);
+    text
+      .chars()
+      .map(|c| {
+        Self::map(c, alphabet, |y| {
+          (y + modulus - shift % modulus) * inverse % modulus
+        })
+      })
+      .collect()
+  }
+
+  /// Decrypts the text with every key of the alphabet, 312 for `A..=Z`,
+  /// and ranks the candidates by how close their letters are to the
+  /// language, best first.
+  pub fn rank(text: &str, config: &AffineConfig) -> Vec<AffineCandidate> {
+    let alphabet = &config.alphabet;
+    let keys: Vec<(usize, usize)> = Self::keys(alphabet.len()).collect();
+    let mut candidates: Vec<AffineCandidate> = keys
+      .into_par_iter()
+      .map(|(multiplier, shift)| {
+        let plaintext =
+          Self::decrypt_text_in(text, multiplier, shift, alphabet);
+        let mut bytes = plaintext.as_bytes();
+        let score =
+          FrequencyAnalyzer::score_text_in(&mut bytes, config.language)
+            .unwrap_or(f32::MAX);
+        AffineCandidate {
+          multiplier,
+          shift,
+          plaintext,
+          score,
+          language: config.language,
+        }
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| {
+      a.score
+        .total_cmp(&b.score)
+        .then((a.multiplier, a.shift).cmp(&(b.multiplier, b.shift)))
+    });
+    candidates
+  }
+
+  /// Every multiplier coprime to the size of the alphabet with every
+  /// shift.
+  pub fn keys(modulus: usize) -> impl Iterator<Item = (usize, usize)> {
+    (1..modulus)
+      .filter(move |&a| Multiplicative::inverse_mod(a, modulus).is_some())
+      .flat_map(move |a| (0..modulus).map(move |b| (a, b)))
+  }
+
+  /// The position `a * x + b` of the letter at `x` in an alphabet of the
+  /// given size.
+  pub fn apply(
+    x: usize,
+    multiplier: usize,
+    shift: usize,
+    modulus: usize,
+  ) -> usize {
+    (multiplier * x + shift) % modulus
+  }
+
+  pub fn key(multiplier: usize, shift: usize) -> String {
+    format!("a={multiplier} b={shift}")
+  }
+
+  /// Moves a symbol of the alphabet from its position `x` to `f(x)`,
+  /// keeping its case.
+  fn map(c: char, alphabet: &Alphabet, f: impl Fn(usize) -> usize) -> char {
+    match alphabet.index(c) {
+      Some(x) => alphabet.shift(c, f(x) as isize - x as isize),
+      None => c,
+    }
+  }
+
+  fn validate(
+    (multiplier, shift): (usize, usize),
+    alphabet: &Alphabet,
+  ) -> Result<(usize, usize)> {
+    let modulus = alphabet.len();
+    if Multiplicative::inverse_mod(multiplier, modulus).is_none() {
+      let multipliers: Vec<usize> = (1..modulus)
+        .filter(|&a| Multiplicative::inverse_mod(a, modulus).is_some())
+        .collect();
+      let message = format!(
+        "The multiplier {multiplier} is not coprime to {modulus}, valid \
+         multipliers are {multipliers:?} modulo {modulus}"
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    Ok((multiplier, shift))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_encrypt_text() {
+    assert_eq!(
+      Affine::encrypt_text("Affine cipher!", 5, 8),
+      "Ihhwvc swfrcp!"
+    );
+    assert_eq!(
+      Affine::decrypt_text("Ihhwvc swfrcp!", 5, 8),
+      "Affine cipher!"
+    );
+    assert_eq!(Affine::keys(26).count(), 312);
+  }
+
+  #[test]
+  fn test_brute_force_decrypt() -> Result<()> {
+    let text = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG AND RUNS AWAY \
+      FROM THE HUNTER WHO FOLLOWS IT INTO THE FOREST";
+    let cipher = Affine::encrypt_text(text, 7, 3);
+    let best = &Affine::rank(&cipher, &AffineConfig::default())[0];
+    assert_eq!((best.multiplier, best.shift), (7, 3));
+
+    let mut output = Vec::new();
+    let config = AffineConfig::default();
+    Affine::decrypt(&mut Cursor::new(cipher), &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}\n"));
+
+    let config = AffineConfig::new(Some((13, 1)));
+    let result =
+      Affine::encrypt(&mut Cursor::new("A"), &mut Vec::new(), config);
+    assert!(result.is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_alphabet() -> Result<()> {
+    let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")?;
+    let cipher = Affine::encrypt_text_in("AGENT 007", 5, 8, &alphabet);
+    assert_eq!(cipher, "IC2B5 443");
+    assert_eq!(
+      Affine::decrypt_text_in(&cipher, 5, 8, &alphabet),
+      "AGENT 007"
+    );
+
+    let config = AffineConfig {
+      key: Some((6, 1)),
+      alphabet,
+      ..AffineConfig::default()
+    };
+    let result =
+      Affine::encrypt(&mut Cursor::new("A"), &mut Vec::new(), config);
+    assert!(result.unwrap_err().to_string().contains("coprime to 36"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_russian() {
+    let alphabet = Language::Russian.alphabet();
+    let plaintext = "В начале было Слово, и Слово было у Бога, и Слово было \
+      Бог. Оно было в начале у Бога. Все через Него начало быть, и без \
+      Него ничто не начало быть, что начало быть";
+    let cipher = Affine::encrypt_text_in(plaintext, 5, 3, &alphabet);
+
+    let config = AffineConfig {
+      alphabet,
+      language: Language::Russian,
+      ..AffineConfig::default()
+    };
+    let best = CrackReport::from(Affine::rank(&cipher, &config).remove(0));
+    assert_eq!(best.plaintext, plaintext);
+    assert_eq!(best.key.as_deref(), Some("a=5 b=3"));
+    assert!(best.confidence.is_some());
+  }
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{Read, Result, Write};
+use std::path::PathBuf;
+
+use crate::text::spacing::Spacing;
+use crate::Command;
+
+/// Counts of the letters A to Z.
+type Letters = [u8; 26];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnagramConfig {
+  /// Wordlist the words are taken from instead of common English words.
+  pub dictionary: Option<PathBuf>,
+  /// Whether phrases may leave letters unused.
+  pub partial: bool,
+  /// Most words of a phrase.
+  pub max_words: usize,
+  /// Shortest word of a phrase.
+  pub min_length: usize,
+  /// Most phrases printed per input line.
+  pub limit: usize,
+}
+
+impl AnagramConfig {
+  pub fn new(
+    dictionary: Option<PathBuf>,
+    partial: bool,
+    max_words: usize,
+    min_length: usize,
+    limit: usize,
+  ) -> Self {
+    Self {
+      dictionary,
+      partial,
+      max_words,
+      min_length,
+      limit,
+    }
+  }
+}
+
+impl From<&Command> for AnagramConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Anagram {
+        dictionary,
+        partial,
+        max_words,
+        min_length,
+        limit,
+        ..
+      } => Self::new(
+        dictionary.clone(),
+        *partial,
+        *max_words,
+        *min_length,
+        *limit,
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A phrase formed from the letters and the letters it leaves unused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnagramMatch {
+  pub words: Vec<String>,
+  pub leftover: String,
+}
+
+impl AnagramMatch {
+  pub fn phrase(&self) -> String {
+    self.words.join(" ")
+  }
+}
+
+pub struct Anagram;
+
+impl Anagram {
+  /// Prints the phrases that can be formed from the letters of every
+  /// line, with an empty line between the results of two lines.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AnagramConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let wordlist = match &config.dictionary {
+      Some(path) => fs::read_to_string(path)?,
+      None => Spacing::words().collect::<Vec<_>>().join("\n"),
+    };
+    let words = Self::read_words(&wordlist, config.min_length);
+
+    let lines: Vec<&str> = content
+      .lines()
+      .filter(|line| line.contains(|c: char| c.is_ascii_alphabetic()))
+      .collect();
+    for (i, line) in lines.iter().enumerate() {
+      if i > 0 {
+        writeln!(output)?;
+      }
+      for found in Self::search(line, &words, &config) {
+        let phrase = found.phrase();
+        match found.leftover.is_empty() {
+          true => writeln!(output, "{phrase}")?,
+          false => writeln!(output, "{phrase} [{}]", found.leftover)?,
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The distinct lowercase words of a wordlist with at least
+  /// `min_length` letters. Words with other characters are skipped.
+  pub fn read_words(wordlist: &str, min_length: usize) -> Vec<String> {
+    wordlist
+      .split_whitespace()
+      .filter(|word| word.chars().all(|c| c.is_ascii_alphabetic()))
+      .filter(|word| word.len() >= min_length.max(1))
+      .map(|word| word.to_ascii_lowercase())
+      .collect::<BTreeSet<_>>()
+      .into_iter()
+      .collect()
+  }
+
+  /// Finds the phrases of up to `max_words` words that use exactly the
+  /// letters of the text, or any part of them in partial mode. Every
+  /// combination of words is reported once, in alphabetical order.
+  ///
+  /// Complete anagrams are sorted by their number of words, partial ones
+  /// by the number of letters they use, most first.
+  pub fn search(
+    text: &str,
+    words: &[String],
+    config: &AnagramConfig,
+  ) -> Vec<AnagramMatch> {
+    let available = Self::count(text);
+    let candidates: Vec<(&str, Letters)> = words
+      .iter()
+      .map(|word| (word.as_str(), Self::count(word)))
+      .filter(|(_, counts)| Self::fits(counts, &available))
+      .collect();
+
+    let mut found = Vec::new();
+    let mut phrase = Vec::new();
+    Self::extend(&candidates, 0, available, &mut phrase, config, &mut found);
+
+    found.sort_by(|a, b| {
+      a.leftover
+        .len()
+        .cmp(&b.leftover.len())
+        .then(a.words.len().cmp(&b.words.len()))
+        .then(a.words.cmp(&b.words))
+    });
+    found.truncate(config.limit);
+    found
+  }
+
+  fn extend<'a>(
+    candidates: &[(&'a str, Letters)],
+    start: usize,
+    remaining: Letters,
+    phrase: &mut Vec<&'a str>,
+    config: &AnagramConfig,
+    found: &mut Vec<AnagramMatch>,
+  ) {
+    let complete = remaining.iter().all(|&count| count == 0);
+    if !phrase.is_empty() && (complete || config.partial) {
+      found.push(AnagramMatch {
+        words: phrase.iter().map(|word| word.to_string()).collect(),
+        leftover: Self::letters(&remaining),
+      });
+    }
+    if complete || phrase.len() >= config.max_words {
+      return;
+    }
+
+    for (i, (word, counts)) in candidates.iter().enumerate().skip(start) {
+      if !Self::fits(counts, &remaining) {
+        continue;
+      }
+      let mut rest = remaining;
+      for (left, used) in rest.iter_mut().zip(counts) {
+        *left -= used;
+      }
+      phrase.push(word);
+      Self::extend(candidates, i, rest, phrase, config, found);
+      phrase.pop();
+    }
+  }
+
+  fn count(text: &str) -> Letters {
+    let mut counts = [0; 26];
+    for c in text.bytes().filter(u8::is_ascii_alphabetic) {
+      counts[(c.to_ascii_lowercase() - b'a') as usize] += 1;
+    }
+    counts
+  }
+
+  fn fits(counts: &Letters, available: &Letters) -> bool {
+    counts
+      .iter()
+      .zip(available)
+      .all(|(used, left)| used <= left)
+  }
+
+  fn letters(counts: &Letters) -> String {
+    counts
+      .iter()
+      .enumerate()
+      .flat_map(|(i, &count)| {
+        std::iter::repeat_n((b'a' + i as u8) as char, count as usize)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn config(partial: bool, max_words: usize) -> AnagramConfig {
+    AnagramConfig::new(None, partial, max_words, 1, 100)
+  }
+
+  #[test]
+  fn test_complete_anagrams() {
+    let words = Anagram::read_words("listen silent is lent lens enlist a", 1);
+    let found = Anagram::search("TINSEL", &words, &config(false, 2));
+    let phrases: Vec<String> = found.iter().map(AnagramMatch::phrase).collect();
+    assert_eq!(phrases, ["enlist", "listen", "silent", "is lent"]);
+  }
+
+  #[test]
+  fn test_partial_anagrams() {
+    let words = Anagram::read_words("attack at dawn cat", 2);
+    let found = Anagram::search("tacktadawnx", &words, &config(true, 3));
+    assert_eq!(found[0].phrase(), "attack dawn");
+    assert_eq!(found[0].leftover, "x");
+    assert!(found.iter().any(|found| found.phrase() == "cat"));
+    assert!(found.iter().all(|found| found.words.len() <= 3));
+  }
+}
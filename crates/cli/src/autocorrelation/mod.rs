@@ -0,0 +1,12 @@
+pub use cryptology_core::autocorrelation::*;
+
+use crate::Command;
+
+impl From<&Command> for AutocorrelationConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Autocorrelation { max_shift, .. } => Self::new(*max_shift),
+      _ => Self::default(),
+    }
+  }
+}
@@ -0,0 +1,252 @@
+use clap::ValueEnum;
+
+use std::{
+  fs,
+  io::{Error, ErrorKind, Read, Result, Write},
+  path::PathBuf,
+};
+
+use crate::Command;
+
+/// The alphabet used to assign the five-symbol codes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BaconVariant {
+  /// Original 24-letter alphabet where I/J and U/V share a code.
+  #[value(name = "24")]
+  Classic,
+  /// 26-letter alphabet with a distinct code for every letter.
+  #[default]
+  #[value(name = "26")]
+  Complete,
+}
+
+impl BaconVariant {
+  pub fn alphabet(&self) -> &'static str {
+    match self {
+      BaconVariant::Classic => "ABCDEFGHIKLMNOPQRSTUWXYZ",
+      BaconVariant::Complete => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    }
+  }
+
+  fn normalize(&self, c: char) -> char {
+    match (self, c) {
+      (BaconVariant::Classic, 'J') => 'I',
+      (BaconVariant::Classic, 'V') => 'U',
+      _ => c,
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BaconConfig {
+  pub decode: bool,
+  pub variant: BaconVariant,
+  pub cover: Option<PathBuf>,
+  pub case: bool,
+}
+
+impl BaconConfig {
+  pub fn new(
+    decode: bool,
+    variant: BaconVariant,
+    cover: Option<PathBuf>,
+    case: bool,
+  ) -> Self {
+    Self {
+      decode,
+      variant,
+      cover,
+      case,
+    }
+  }
+}
+
+impl From<&Command> for BaconConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Bacon {
+        decode,
+        variant,
+        cover,
+        case,
+        ..
+      } => Self::new(*decode, *variant, cover.clone(), *case),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Bacon;
+
+impl Bacon {
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: BaconConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    if config.decode {
+      let symbols = if config.case {
+        Self::case_symbols(&content)
+      } else {
+        content.clone()
+      };
+      let plaintext = Self::decode(&symbols, config.variant);
+      writeln!(output, "{plaintext}")
+    } else if let Some(cover) = &config.cover {
+      let cover = fs::read_to_string(cover)?;
+      let symbols = Self::encode(&content, config.variant);
+      let stego = Self::hide(&symbols, &cover)?;
+      writeln!(output, "{stego}")
+    } else {
+      let symbols = Self::encode(&content, config.variant);
+      let groups: Vec<String> = symbols
+        .as_bytes()
+        .chunks(5)
+        .map(|group| String::from_utf8_lossy(group).into_owned())
+        .collect();
+      writeln!(output, "{}", groups.join(" "))
+    }
+  }
+
+  /// Encodes every letter of the text as a sequence of five `A`/`B` symbols.
+  pub fn encode(text: &str, variant: BaconVariant) -> String {
+    let alphabet = variant.alphabet();
+
+    text
+      .chars()
+      .filter(|c| c.is_ascii_alphabetic())
+      .filter_map(|c| alphabet.find(variant.normalize(c.to_ascii_uppercase())))
+      .flat_map(|index| {
+        (0..5)
+          .rev()
+          .map(move |bit| if index >> bit & 1 == 1 { 'B' } else { 'A' })
+      })
+      .collect()
+  }
+
+  /// Decodes groups of five `A`/`B` symbols, ignoring any other characters.
+  pub fn decode(symbols: &str, variant: BaconVariant) -> String {
+    let alphabet: Vec<char> = variant.alphabet().chars().collect();
+    let bits: Vec<usize> = symbols
+      .chars()
+      .filter_map(|c| match c.to_ascii_uppercase() {
+        'A' => Some(0),
+        'B' => Some(1),
+        _ => None,
+      })
+      .collect();
+
+    bits
+      .chunks_exact(5)
+      .map(|group| group.iter().fold(0, |acc, bit| acc << 1 | bit))
+      .map(|index| alphabet.get(index).copied().unwrap_or('?'))
+      .collect()
+  }
+
+  /// Writes the symbols into the letter case of a cover text.
+  ///
+  /// Lowercase letters carry an `A`, uppercase letters carry a `B`.
+  /// The cover text is cut off after the last letter carrying a symbol.
+  pub fn hide(symbols: &str, cover: &str) -> Result<String> {
+    let mut symbols = symbols.chars().peekable();
+    let mut stego = String::new();
+
+    for c in cover.chars() {
+      if symbols.peek().is_none() {
+        break;
+      }
+      if c.is_ascii_alphabetic() {
+        match symbols.next() {
+          Some('B') => stego.push(c.to_ascii_uppercase()),
+          _ => stego.push(c.to_ascii_lowercase()),
+        }
+      } else {
+        stego.push(c);
+      }
+    }
+
+    if symbols.peek().is_some() {
+      let message = "Cover text has too few letters to hide the message";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(stego)
+  }
+
+  /// Reads the `A`/`B` symbols from the letter case of a text.
+  pub fn case_symbols(text: &str) -> String {
+    text
+      .chars()
+      .filter(|c| c.is_ascii_alphabetic())
+      .map(|c| if c.is_ascii_uppercase() { 'B' } else { 'A' })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_encode_complete_alphabet() {
+    let symbols = Bacon::encode("Hello", BaconVariant::Complete);
+    assert_eq!(symbols, "AABBBAABAAABABBABABBABBBA");
+  }
+
+  #[test]
+  fn test_encode_classic_alphabet() {
+    let symbols = Bacon::encode("Hello", BaconVariant::Classic);
+    assert_eq!(symbols, "AABBBAABAAABABAABABAABBAB");
+  }
+
+  #[test]
+  fn test_classic_alphabet_merges_letters() {
+    let variant = BaconVariant::Classic;
+    assert_eq!(Bacon::encode("J", variant), Bacon::encode("I", variant));
+    assert_eq!(Bacon::encode("V", variant), Bacon::encode("U", variant));
+  }
+
+  #[test]
+  fn test_decode_complete_alphabet() {
+    let plaintext =
+      Bacon::decode("AABBB AABAA ABABB ABABB ABBBA", BaconVariant::Complete);
+    assert_eq!(plaintext, "HELLO");
+  }
+
+  #[test]
+  fn test_decode_classic_alphabet() {
+    let plaintext =
+      Bacon::decode("AABBB AABAA ABABA ABABA ABBAB", BaconVariant::Classic);
+    assert_eq!(plaintext, "HELLO");
+  }
+
+  #[test]
+  fn test_hide_and_reveal_in_cover_text() {
+    let symbols = Bacon::encode("hi", BaconVariant::Complete);
+    let stego =
+      Bacon::hide(&symbols, "The quick brown fox jumps over").unwrap();
+    assert_eq!(stego, "thE QUiCk br");
+
+    let revealed = Bacon::case_symbols(&stego);
+    assert_eq!(Bacon::decode(&revealed, BaconVariant::Complete), "HI");
+  }
+
+  #[test]
+  fn test_hide_rejects_short_cover_text() {
+    let symbols = Bacon::encode("secret", BaconVariant::Complete);
+    assert!(Bacon::hide(&symbols, "too short").is_err());
+  }
+
+  #[test]
+  fn test_execute_groups_symbols() -> Result<()> {
+    let mut input = Cursor::new("ab");
+    let mut output = Vec::new();
+    Bacon::execute(&mut input, &mut output, BaconConfig::default())?;
+    assert_eq!(String::from_utf8(output).unwrap(), "AAAAA AAAAB\n");
+    Ok(())
+  }
+}
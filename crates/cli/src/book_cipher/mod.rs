@@ -0,0 +1,432 @@
+use clap::ValueEnum;
+
+use std::{
+  collections::HashMap,
+  fmt::Display,
+  fs,
+  io::{Error, ErrorKind, Read, Result, Write},
+  path::PathBuf,
+};
+
+use crate::{error::CryptologyError, DecryptCipher, EncryptCipher};
+
+/// How a word of the key text is referenced in the ciphertext.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BookReferenceFormat {
+  /// Position of the word in the whole key text, e.g. `42`.
+  #[default]
+  Word,
+  /// Page, line and word position, e.g. `1:3:7`.
+  PageLineWord,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BookCipherConfig {
+  pub key_file: PathBuf,
+  pub format: BookReferenceFormat,
+  pub lines_per_page: Option<usize>,
+  pub words: bool,
+}
+
+impl BookCipherConfig {
+  pub fn new(
+    key_file: PathBuf,
+    format: BookReferenceFormat,
+    lines_per_page: Option<usize>,
+    words: bool,
+  ) -> Self {
+    Self {
+      key_file,
+      format,
+      lines_per_page,
+      words,
+    }
+  }
+}
+
+impl From<&EncryptCipher> for BookCipherConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::BookCipher {
+        key_file,
+        format,
+        lines_per_page,
+        words,
+        ..
+      } => Self::new(key_file.clone(), *format, *lines_per_page, *words),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for BookCipherConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::BookCipher {
+        key_file,
+        lines_per_page,
+        words,
+        ..
+      } => Self::new(
+        key_file.clone(),
+        BookReferenceFormat::default(),
+        *lines_per_page,
+        *words,
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The position of a word inside the key text. All positions start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookWord {
+  pub text: String,
+  pub index: usize,
+  pub page: usize,
+  pub line: usize,
+  pub word: usize,
+}
+
+impl BookWord {
+  pub fn reference(&self, format: BookReferenceFormat) -> String {
+    match format {
+      BookReferenceFormat::Word => self.index.to_string(),
+      BookReferenceFormat::PageLineWord => {
+        format!("{}:{}:{}", self.page, self.line, self.word)
+      }
+    }
+  }
+}
+
+impl Display for BookWord {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.text)
+  }
+}
+
+/// The words of a key text, numbered by position, page and line.
+///
+/// Pages are separated by form feeds and can additionally be split
+/// into a fixed number of lines.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BookKey {
+  pub words: Vec<BookWord>,
+}
+
+impl BookKey {
+  pub fn new(text: &str, lines_per_page: Option<usize>) -> Self {
+    let mut words = Vec::new();
+    let mut page = 0;
+
+    for sheet in text.split('\x0c') {
+      let lines: Vec<&str> = sheet.lines().collect();
+      let size = lines_per_page.unwrap_or(lines.len()).max(1);
+
+      for lines in lines.chunks(size) {
+        page += 1;
+        for (line, content) in lines.iter().enumerate() {
+          let tokens = content
+            .split_whitespace()
+            .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|token| !token.is_empty());
+
+          for (word, text) in tokens.enumerate() {
+            words.push(BookWord {
+              text: text.to_string(),
+              index: words.len() + 1,
+              page,
+              line: line + 1,
+              word: word + 1,
+            });
+          }
+        }
+      }
+    }
+
+    Self { words }
+  }
+
+  pub fn lookup(&self, reference: &str) -> Result<&BookWord> {
+    let numbers: Vec<usize> = reference
+      .split(':')
+      .map(|number| number.parse::<usize>())
+      .collect::<std::result::Result<_, _>>()
+      .map_err(|_| {
+        let message = format!("Invalid book reference '{reference}'");
+        Error::new(ErrorKind::InvalidInput, message)
+      })?;
+
+    let word = match numbers.as_slice() {
+      [index] => self.words.iter().find(|word| word.index == *index),
+      [page, line, word] => self.words.iter().find(|candidate| {
+        candidate.page == *page
+          && candidate.line == *line
+          && candidate.word == *word
+      }),
+      _ => None,
+    };
+
+    word.ok_or_else(|| {
+      let message = format!("Book reference '{reference}' is not in the key");
+      Error::new(ErrorKind::InvalidInput, message)
+    })
+  }
+}
+
+pub struct BookCipher;
+
+impl BookCipher {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: BookCipherConfig,
+  ) -> Result<()> {
+    let key = Self::read_key(&config)?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let cipher = if config.words {
+        Self::encrypt_words(line, &key, config.format)?
+      } else {
+        Self::encrypt_letters(line, &key, config.format)?
+      };
+      writeln!(output, "{cipher}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: BookCipherConfig,
+  ) -> Result<()> {
+    let key = Self::read_key(&config)?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let plaintext = Self::decrypt_text(line, &key, config.words)?;
+      writeln!(output, "{plaintext}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Encodes every letter by a word of the key starting with that letter.
+  ///
+  /// Repeated letters cycle through all matching words so that the
+  /// ciphertext does not simply repeat the same reference.
+  pub fn encrypt_letters(
+    text: &str,
+    key: &BookKey,
+    format: BookReferenceFormat,
+  ) -> Result<String> {
+    let mut used: HashMap<char, usize> = HashMap::new();
+    let mut references = Vec::new();
+
+    for c in text.chars().filter(|c| c.is_alphanumeric()) {
+      let c = c.to_ascii_uppercase();
+      let candidates: Vec<&BookWord> = key
+        .words
+        .iter()
+        .filter(|word| {
+          word.text.chars().next().map(|c| c.to_ascii_uppercase()) == Some(c)
+        })
+        .collect();
+
+      if candidates.is_empty() {
+        let message = format!("No word in the key text starts with '{c}'");
+        return Err(Error::new(ErrorKind::InvalidInput, message));
+      }
+
+      let count = used.entry(c).or_insert(0);
+      references.push(candidates[*count % candidates.len()].reference(format));
+      *count += 1;
+    }
+
+    Ok(references.join(" "))
+  }
+
+  /// Encodes every word by an occurrence of the same word in the key.
+  pub fn encrypt_words(
+    text: &str,
+    key: &BookKey,
+    format: BookReferenceFormat,
+  ) -> Result<String> {
+    let mut used: HashMap<String, usize> = HashMap::new();
+    let mut references = Vec::new();
+
+    let words = text
+      .split_whitespace()
+      .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric()))
+      .filter(|token| !token.is_empty());
+
+    for word in words {
+      let lowercase = word.to_lowercase();
+      let candidates: Vec<&BookWord> = key
+        .words
+        .iter()
+        .filter(|candidate| candidate.text.to_lowercase() == lowercase)
+        .collect();
+
+      if candidates.is_empty() {
+        let message = format!("The word '{word}' is not in the key text");
+        return Err(Error::new(ErrorKind::InvalidInput, message));
+      }
+
+      let count = used.entry(lowercase).or_insert(0);
+      references.push(candidates[*count % candidates.len()].reference(format));
+      *count += 1;
+    }
+
+    Ok(references.join(" "))
+  }
+
+  /// Resolves whitespace or comma separated references against the key.
+  ///
+  /// Each reference yields its first letter, or the whole word if
+  /// `words` is set.
+  pub fn decrypt_text(
+    text: &str,
+    key: &BookKey,
+    words: bool,
+  ) -> Result<String> {
+    let references = text
+      .split(|c: char| c.is_whitespace() || c == ',')
+      .filter(|reference| !reference.is_empty());
+
+    let mut plaintext = Vec::new();
+
+    for reference in references {
+      let word = key.lookup(reference)?;
+      if words {
+        plaintext.push(word.text.clone());
+      } else if let Some(c) = word.text.chars().next() {
+        plaintext.push(c.to_uppercase().to_string());
+      }
+    }
+
+    let separator = if words { " " } else { "" };
+    Ok(plaintext.join(separator))
+  }
+
+  /// The words of the key file, reported by its path if it is unreadable.
+  fn read_key(config: &BookCipherConfig) -> Result<BookKey> {
+    let path = &config.key_file;
+    let text =
+      fs::read_to_string(path).map_err(|source| CryptologyError::Open {
+        path: path.clone(),
+        source,
+      })?;
+    Ok(BookKey::new(&text, config.lines_per_page))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const KEY: &str = "It was the best of times,\n\
+    it was the worst of times,\n\
+    \x0cit was the age of wisdom,\n\
+    it was the age of foolishness";
+
+  #[test]
+  fn test_book_key_positions() {
+    let key = BookKey::new(KEY, None);
+    let word = &key.words[15];
+    assert_eq!(word.text, "age");
+    assert_eq!(word.reference(BookReferenceFormat::Word), "16");
+    assert_eq!(word.reference(BookReferenceFormat::PageLineWord), "2:1:4");
+  }
+
+  #[test]
+  fn test_book_key_lines_per_page() {
+    let key = BookKey::new(KEY, Some(1));
+    let word = key.lookup("2:1:4").unwrap();
+    assert_eq!(word.text, "worst");
+  }
+
+  #[test]
+  fn test_encrypt_letters() {
+    let key = BookKey::new(KEY, None);
+    let cipher =
+      BookCipher::encrypt_letters("tow", &key, BookReferenceFormat::Word)
+        .unwrap();
+    assert_eq!(cipher, "3 5 2");
+  }
+
+  #[test]
+  fn test_encrypt_letters_cycles_through_words() {
+    let key = BookKey::new(KEY, None);
+    let cipher =
+      BookCipher::encrypt_letters("www", &key, BookReferenceFormat::Word)
+        .unwrap();
+    assert_eq!(cipher, "2 8 10");
+  }
+
+  #[test]
+  fn test_encrypt_letters_rejects_missing_letter() {
+    let key = BookKey::new(KEY, None);
+    let result =
+      BookCipher::encrypt_letters("xyz", &key, BookReferenceFormat::Word);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_encrypt_words_page_line_word() {
+    let key = BookKey::new(KEY, None);
+    let cipher = BookCipher::encrypt_words(
+      "the age of wisdom",
+      &key,
+      BookReferenceFormat::PageLineWord,
+    )
+    .unwrap();
+    assert_eq!(cipher, "1:1:3 2:1:4 1:1:5 2:1:6");
+  }
+
+  #[test]
+  fn test_decrypt_letters() {
+    let key = BookKey::new(KEY, None);
+    let plaintext = BookCipher::decrypt_text("3, 5, 2", &key, false).unwrap();
+    assert_eq!(plaintext, "TOW");
+  }
+
+  #[test]
+  fn test_decrypt_words() {
+    let key = BookKey::new(KEY, None);
+    let plaintext =
+      BookCipher::decrypt_text("1:1:3 2:1:4 1:1:5 2:1:6", &key, true).unwrap();
+    assert_eq!(plaintext, "the age of wisdom");
+  }
+
+  #[test]
+  fn test_decrypt_rejects_unknown_reference() {
+    let key = BookKey::new(KEY, None);
+    assert!(BookCipher::decrypt_text("100", &key, false).is_err());
+    assert!(BookCipher::decrypt_text("1:x:1", &key, false).is_err());
+  }
+
+  #[test]
+  fn test_missing_key_file() {
+    let config = BookCipherConfig {
+      key_file: PathBuf::from("missing-book.txt"),
+      ..BookCipherConfig::default()
+    };
+    let result = BookCipher::decrypt(&mut &b"1"[..], &mut Vec::new(), config);
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("missing-book.txt"));
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let key = BookKey::new(KEY, None);
+    let format = BookReferenceFormat::PageLineWord;
+    let cipher = BookCipher::encrypt_letters("Wait", &key, format).unwrap();
+    let plaintext = BookCipher::decrypt_text(&cipher, &key, false).unwrap();
+    assert_eq!(plaintext, "WAIT");
+  }
+}
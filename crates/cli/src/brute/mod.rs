@@ -0,0 +1,296 @@
+use clap::ValueEnum;
+use rayon::prelude::*;
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{Read, Result, Write};
+
+use crate::affine::{Affine, AffineConfig};
+use crate::caesar::{Caesar, CaesarConfig};
+use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::hex::Hex;
+use crate::rail_fence::RailFence;
+use crate::report::{CrackReport, OutputFormat};
+use crate::solve::AutoSolve;
+use crate::xor::repeating_key::RepeatingKeyXor;
+use crate::Command;
+
+/// A cheap cipher whose keys the brute command tries exhaustively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BruteMethod {
+  /// All 26 shifts of the alphabet.
+  Caesar,
+  /// All 312 pairs of a multiplier and a shift, `y = a * x + b`.
+  Affine,
+  /// The reversed alphabet.
+  Atbash,
+  /// All 256 single bytes, of hex or binary input.
+  Xor,
+  /// All numbers of rails of the zigzag up to the maximum.
+  RailFence,
+}
+
+impl BruteMethod {
+  pub const ALL: [BruteMethod; 5] = [
+    BruteMethod::Caesar,
+    BruteMethod::Affine,
+    BruteMethod::Atbash,
+    BruteMethod::Xor,
+    BruteMethod::RailFence,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      BruteMethod::Caesar => "caesar",
+      BruteMethod::Affine => "affine",
+      BruteMethod::Atbash => "atbash",
+      BruteMethod::Xor => "xor",
+      BruteMethod::RailFence => "rail-fence",
+    }
+  }
+}
+
+impl Display for BruteMethod {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BruteConfig {
+  /// The ciphers that are tried. All of them if empty.
+  pub methods: Vec<BruteMethod>,
+  /// Most rails of the rail fence.
+  pub rails: usize,
+  /// Most candidates printed.
+  pub top: usize,
+}
+
+impl BruteConfig {
+  pub fn new(methods: Vec<BruteMethod>, rails: usize, top: usize) -> Self {
+    Self {
+      methods,
+      rails,
+      top,
+    }
+  }
+}
+
+impl From<&Command> for BruteConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Brute {
+        methods,
+        rails,
+        top,
+        ..
+      } => Self::new(methods.clone(), *rails, top.get()),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Brute;
+
+impl Brute {
+  /// Tries every key of the cheap ciphers and writes the best candidates
+  /// across all of them, most confident first.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: BruteConfig,
+    format: OutputFormat,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let mut candidates = Self::candidates(&content, &config);
+    candidates.truncate(config.top);
+
+    match format {
+      OutputFormat::Text => {
+        for (rank, report) in candidates.iter().enumerate() {
+          let key = report.key.as_deref().unwrap_or("none");
+          let confidence = report.confidence.unwrap_or_default();
+          let plaintext = report.plaintext.replace(char::is_control, " ");
+          writeln!(
+            output,
+            "{}. {}, key {key}, confidence {confidence:.2}: {plaintext}",
+            rank + 1,
+            report.method
+          )?;
+        }
+      }
+      OutputFormat::Json => CrackReport::write_json(output, &candidates)?,
+    }
+
+    Ok(())
+  }
+
+  /// Decrypts the input with every key of the selected ciphers in
+  /// parallel and ranks all candidates together by their weighted
+  /// confidence. Equal candidates, such as XOR keys that only flip the
+  /// case, are ordered by their byte score.
+  ///
+  /// Single-byte XOR is only tried on hex or binary input and the letter
+  /// ciphers only on other input, unless they are selected explicitly.
+  pub fn candidates(content: &[u8], config: &BruteConfig) -> Vec<CrackReport> {
+    let hex = AutoSolve::hex(content);
+    let binary = hex.is_some()
+      || !content
+        .iter()
+        .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace());
+
+    let methods: Vec<BruteMethod> = match config.methods.is_empty() {
+      true => BruteMethod::ALL
+        .into_iter()
+        .filter(|method| binary == (*method == BruteMethod::Xor))
+        .collect(),
+      false => config.methods.clone(),
+    };
+
+    let text = String::from_utf8_lossy(content);
+    let text = text.trim_end();
+    let bytes = hex.unwrap_or_else(|| content.to_vec());
+
+    let mut candidates: Vec<(f32, f32, CrackReport)> = methods
+      .par_iter()
+      .flat_map(|method| match method {
+        BruteMethod::Caesar => Self::caesar(text),
+        BruteMethod::Affine => Self::affine(text),
+        BruteMethod::Atbash => Self::atbash(text),
+        BruteMethod::Xor => Self::xor(&bytes),
+        BruteMethod::RailFence => Self::rail_fence(text, config.rails),
+      })
+      .map(|report| {
+        let bytes = FrequencyAnalyzer::byte_score(report.plaintext.as_bytes());
+        (Self::weight(&report), bytes, report)
+      })
+      .collect();
+
+    candidates.sort_by(|(a, a_bytes, _), (b, b_bytes, _)| {
+      b.total_cmp(a).then(a_bytes.total_cmp(b_bytes))
+    });
+    candidates
+      .into_iter()
+      .map(|(_, _, report)| report)
+      .collect()
+  }
+
+  /// The confidence of a candidate times the share of its characters
+  /// that are letters or spaces. The share is the same for all keys of
+  /// the letter ciphers, but tells XOR keys apart whose few letters
+  /// happen to form English bigrams.
+  fn weight(report: &CrackReport) -> f32 {
+    let text = &report.plaintext;
+    let length = text.chars().count().max(1);
+    let letters = text
+      .chars()
+      .filter(|c| c.is_ascii_alphabetic() || *c == ' ')
+      .count();
+    report.confidence.unwrap_or_default() * letters as f32 / length as f32
+  }
+
+  fn caesar(text: &str) -> Vec<CrackReport> {
+    Caesar::rank(text, &CaesarConfig::default())
+      .into_iter()
+      .map(|mut report| {
+        report.method = BruteMethod::Caesar.name();
+        report
+      })
+      .collect()
+  }
+
+  /// The affine keys with a multiplier of 1 are Caesar shifts and left
+  /// out.
+  fn affine(text: &str) -> Vec<CrackReport> {
+    Affine::rank(text, &AffineConfig::default())
+      .into_iter()
+      .filter(|candidate| candidate.multiplier != 1)
+      .map(|candidate| {
+        let mut report = CrackReport::from(candidate);
+        report.method = BruteMethod::Affine.name();
+        report
+      })
+      .collect()
+  }
+
+  fn atbash(text: &str) -> Vec<CrackReport> {
+    let plaintext = text
+      .chars()
+      .map(|c| match c {
+        'a'..='z' => (b'z' - (c as u8 - b'a')) as char,
+        'A'..='Z' => (b'Z' - (c as u8 - b'A')) as char,
+        _ => c,
+      })
+      .collect();
+    vec![CrackReport::new(
+      plaintext,
+      None,
+      BruteMethod::Atbash.name(),
+    )]
+  }
+
+  fn xor(bytes: &[u8]) -> Vec<CrackReport> {
+    if bytes.is_empty() {
+      return Vec::new();
+    }
+    (0..=u8::MAX)
+      .into_par_iter()
+      .map(|key| {
+        let plaintext = RepeatingKeyXor::apply(bytes, &[key]);
+        CrackReport::new(
+          Hex::new(plaintext).to_ascii(),
+          Some(format!("{key:02x}")),
+          BruteMethod::Xor.name(),
+        )
+      })
+      .collect()
+  }
+
+  fn rail_fence(text: &str, rails: usize) -> Vec<CrackReport> {
+    RailFence::rank(text, rails)
+      .into_iter()
+      .map(|candidate| {
+        let mut report = CrackReport::from(candidate);
+        report.method = BruteMethod::RailFence.name();
+        report
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PLAINTEXT: &str = "it was a bright cold day in april and the clocks \
+    were striking thirteen";
+
+  #[test]
+  fn test_ranks_across_methods() {
+    let config = BruteConfig::new(vec![], 10, 10);
+
+    let affine = Affine::encrypt_text(PLAINTEXT, 5, 8);
+    let best = &Brute::candidates(affine.as_bytes(), &config)[0];
+    assert_eq!(best.method, "affine");
+    assert_eq!(best.key.as_deref(), Some("a=5 b=8"));
+    assert_eq!(best.plaintext, PLAINTEXT);
+
+    let fence = RailFence::encrypt_text(PLAINTEXT, 4);
+    let best = &Brute::candidates(fence.as_bytes(), &config)[0];
+    assert_eq!(best.method, "rail-fence");
+    assert_eq!(best.key.as_deref(), Some("4"));
+  }
+
+  #[test]
+  fn test_xor_only_for_hex() {
+    let config = BruteConfig::new(vec![], 10, 10);
+    let bytes = RepeatingKeyXor::apply(PLAINTEXT.as_bytes(), &[0x5a]);
+    let hex = Hex::new(bytes).to_string();
+
+    let candidates = Brute::candidates(hex.as_bytes(), &config);
+    assert!(candidates.iter().all(|report| report.method == "xor"));
+    assert_eq!(candidates[0].key.as_deref(), Some("5a"));
+    assert_eq!(candidates[0].plaintext, PLAINTEXT);
+  }
+}
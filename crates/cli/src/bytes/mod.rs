@@ -0,0 +1,180 @@
+use clap::ValueEnum;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::radix::{Base, Radix};
+use crate::Command;
+
+/// Operation on the bytes of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ByteOperation {
+  /// Reverse the order of all bytes.
+  Reverse,
+  /// Swap the byte order of every 16-bit word.
+  Swap16,
+  /// Swap the byte order of every 32-bit word.
+  Swap32,
+  /// Swap the byte order of every 64-bit word.
+  Swap64,
+  /// Reverse the order of the bits of every byte.
+  BitReverse,
+  /// Rotate the bits of every byte to the left.
+  Rotl,
+  /// Rotate the bits of every byte to the right.
+  Rotr,
+  /// Invert every bit.
+  Not,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BytesConfig {
+  pub operations: Vec<ByteOperation>,
+  /// Number of bits the rotations rotate by.
+  pub bits: u32,
+  pub from: Base,
+  pub to: Base,
+}
+
+impl BytesConfig {
+  pub fn new(
+    operations: Vec<ByteOperation>,
+    bits: u32,
+    from: Base,
+    to: Base,
+  ) -> Self {
+    Self {
+      operations,
+      bits,
+      from,
+      to,
+    }
+  }
+}
+
+impl From<&Command> for BytesConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Bytes {
+        operations,
+        bits,
+        from,
+        to,
+        ..
+      } => Self::new(operations.clone(), *bits, *from, *to),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Bytes;
+
+impl Bytes {
+  /// Applies the operations in the given order to the input.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: BytesConfig,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let mut bytes = Radix::parse_bytes(&content, config.from)?;
+    for &operation in &config.operations {
+      bytes = Self::apply(bytes, operation, config.bits)?;
+    }
+
+    output.write_all(&Radix::format_bytes(&bytes, config.to))
+  }
+
+  pub fn apply(
+    mut bytes: Vec<u8>,
+    operation: ByteOperation,
+    bits: u32,
+  ) -> Result<Vec<u8>> {
+    match operation {
+      ByteOperation::Reverse => bytes.reverse(),
+      ByteOperation::Swap16 => Self::swap(&mut bytes, 2)?,
+      ByteOperation::Swap32 => Self::swap(&mut bytes, 4)?,
+      ByteOperation::Swap64 => Self::swap(&mut bytes, 8)?,
+      ByteOperation::BitReverse => {
+        bytes.iter_mut().for_each(|b| *b = b.reverse_bits())
+      }
+      ByteOperation::Rotl => {
+        bytes.iter_mut().for_each(|b| *b = b.rotate_left(bits))
+      }
+      ByteOperation::Rotr => {
+        bytes.iter_mut().for_each(|b| *b = b.rotate_right(bits))
+      }
+      ByteOperation::Not => bytes.iter_mut().for_each(|b| *b = !*b),
+    }
+    Ok(bytes)
+  }
+
+  /// Reverses the bytes of every word of `width` bytes, which converts
+  /// between little and big endian words.
+  fn swap(bytes: &mut [u8], width: usize) -> Result<()> {
+    if bytes.len() % width != 0 {
+      let message = format!(
+        "The input has {} bytes, which is not a multiple of {width}",
+        bytes.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    bytes.chunks_mut(width).for_each(<[u8]>::reverse);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn apply(bytes: &[u8], operation: ByteOperation, bits: u32) -> Vec<u8> {
+    Bytes::apply(bytes.to_vec(), operation, bits).unwrap()
+  }
+
+  #[test]
+  fn test_reverse_and_swap() {
+    let bytes = [1, 2, 3, 4, 5, 6, 7, 8];
+    assert_eq!(
+      apply(&bytes, ByteOperation::Reverse, 0),
+      [8, 7, 6, 5, 4, 3, 2, 1]
+    );
+    assert_eq!(
+      apply(&bytes, ByteOperation::Swap16, 0),
+      [2, 1, 4, 3, 6, 5, 8, 7]
+    );
+    assert_eq!(
+      apply(&bytes, ByteOperation::Swap32, 0),
+      [4, 3, 2, 1, 8, 7, 6, 5]
+    );
+    assert_eq!(
+      apply(&bytes, ByteOperation::Swap64, 0),
+      [8, 7, 6, 5, 4, 3, 2, 1]
+    );
+  }
+
+  #[test]
+  fn test_swap_of_partial_word() {
+    let result = Bytes::apply(vec![1, 2, 3], ByteOperation::Swap16, 0);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_bit_operations() {
+    assert_eq!(apply(&[0b0000_0001], ByteOperation::BitReverse, 0), [0x80]);
+    assert_eq!(apply(&[0b1000_0001], ByteOperation::Rotl, 1), [0b11]);
+    assert_eq!(apply(&[0b1000_0001], ByteOperation::Rotr, 4), [0x18]);
+    assert_eq!(apply(&[0x0f, 0xff], ByteOperation::Not, 0), [0xf0, 0x00]);
+  }
+
+  #[test]
+  fn test_chained_operations() -> Result<()> {
+    let mut output = Vec::new();
+    let operations = vec![ByteOperation::Not, ByteOperation::Reverse];
+    let config = BytesConfig::new(operations, 1, Base::Hex, Base::Hex);
+    Bytes::execute(&mut "00ff10".as_bytes(), &mut output, config)?;
+    assert_eq!(output, b"ef00ff");
+    Ok(())
+  }
+}
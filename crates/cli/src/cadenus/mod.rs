@@ -0,0 +1,233 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  report::CrackReport, transposition::ColumnarTransposition, DecryptCipher,
+  EncryptCipher,
+};
+
+/// Labels of the 25 rows. A column is rotated upwards until the row of
+/// its key letter is on top. V and W share a row.
+const ROW_LABELS: &str = "AZYXVUTSRQPONMLKJIHGFEDCB";
+const ROWS: usize = ROW_LABELS.len();
+
+/// Letter that fills the last block.
+const PADDING: char = 'X';
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CadenusConfig {
+  pub key: String,
+}
+
+impl CadenusConfig {
+  pub fn new(key: &str) -> Self {
+    Self {
+      key: key.to_string(),
+    }
+  }
+}
+
+impl From<&EncryptCipher> for CadenusConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Cadenus { key, .. } => Self::new(key.key()),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for CadenusConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Cadenus { key, .. } => Self::new(key.key()),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The columns of a Cadenus grid in the order they are read out, with
+/// the rotation of each.
+struct Columns {
+  order: Vec<(usize, usize)>,
+}
+
+impl Columns {
+  fn new(key: &str) -> Result<Self> {
+    let key: Vec<char> = key
+      .chars()
+      .filter(char::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase())
+      .collect();
+    let keyword: String = key.iter().collect();
+
+    let order = ColumnarTransposition::column_order(&keyword)?
+      .into_iter()
+      .map(|column| {
+        let label = match key[column] {
+          'W' => 'V',
+          c => c,
+        };
+        (column, ROW_LABELS.find(label).unwrap_or_default())
+      })
+      .collect();
+
+    Ok(Self { order })
+  }
+
+  fn width(&self) -> usize {
+    self.order.len()
+  }
+
+  /// Cells of the plaintext grid in ciphertext order.
+  fn path(&self) -> impl Iterator<Item = usize> + '_ {
+    let width = self.width();
+    (0..ROWS).flat_map(move |row| {
+      self
+        .order
+        .iter()
+        .map(move |&(column, shift)| (row + shift) % ROWS * width + column)
+    })
+  }
+}
+
+pub struct Cadenus;
+
+impl Cadenus {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: CadenusConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      writeln!(output, "{}", Self::encrypt_text(line, &config.key)?)?;
+    }
+
+    Ok(())
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: CadenusConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`Cadenus::decrypt`] and reports the key
+  /// it was decrypted with.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &CadenusConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    content
+      .lines()
+      .map(|line| {
+        let plaintext = Self::decrypt_text(line, &config.key)?;
+        Ok(CrackReport::new(plaintext, Some(config.key.clone()), "key"))
+      })
+      .collect()
+  }
+
+  /// Writes the letters in rows as wide as the key, 25 rows per block,
+  /// and fills the last block with X. The columns of every block are
+  /// ordered by the key, rotated upwards by their key letter and read
+  /// row by row.
+  pub fn encrypt_text(text: &str, key: &str) -> Result<String> {
+    let columns = Columns::new(key)?;
+    let block = columns.width() * ROWS;
+    let mut letters = Self::letters(text);
+    letters.resize(letters.len().div_ceil(block) * block, PADDING);
+
+    Ok(
+      letters
+        .chunks(block)
+        .flat_map(|chunk| columns.path().map(|cell| chunk[cell]))
+        .collect(),
+    )
+  }
+
+  pub fn decrypt_text(text: &str, key: &str) -> Result<String> {
+    let columns = Columns::new(key)?;
+    let block = columns.width() * ROWS;
+    let letters = Self::letters(text);
+
+    if letters.len() % block != 0 {
+      let message = format!(
+        "The ciphertext must be a multiple of {block} letters, 25 per key \
+         letter"
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut plaintext = String::with_capacity(letters.len());
+    for chunk in letters.chunks(block) {
+      let mut grid = vec![PADDING; block];
+      for (cell, &letter) in columns.path().zip(chunk) {
+        grid[cell] = letter;
+      }
+      plaintext.extend(grid);
+    }
+
+    Ok(plaintext)
+  }
+
+  fn letters(text: &str) -> Vec<char> {
+    text
+      .chars()
+      .filter(char::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PLAINTEXT: &str = "ASEVERELIMITATIONONTHEUSEFULNESSOFTHECADENUSIS\
+    THATEVERYMESSAGEMUSTBEAMULTIPLEOFTWENTYFIVELETTERSLONG";
+  const CIPHER: &str = "SYSTRETOMTATTLUSOATLEEESFIYHEASDFNMSCHBHNEUVSNP\
+    MTOFARENUSEIEEIELTARLMENTIEETOGEVESITFAISLTNGEEUVOWUL";
+
+  #[test]
+  fn test_encrypt_text() -> Result<()> {
+    assert_eq!(Cadenus::encrypt_text(PLAINTEXT, "easy")?, CIPHER);
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_text() -> Result<()> {
+    assert_eq!(Cadenus::decrypt_text(CIPHER, "EASY")?, PLAINTEXT);
+    Ok(())
+  }
+
+  #[test]
+  fn test_padding_and_multiple_blocks() -> Result<()> {
+    let text = "WE ARE DISCOVERED FLEE AT ONCE ".repeat(4);
+    let cipher = Cadenus::encrypt_text(&text, "WAR")?;
+    assert_eq!(cipher.len(), 150);
+
+    let plaintext = Cadenus::decrypt_text(&cipher, "WAR")?;
+    assert!(plaintext.starts_with("WEAREDISCOVEREDFLEEATONCE"));
+    assert!(plaintext.ends_with(&format!("ONCE{}", "X".repeat(50))));
+    assert!(Cadenus::decrypt_text(&cipher[1..], "WAR").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_reports_key() -> Result<()> {
+    let config = CadenusConfig::new("EASY");
+    let reports = Cadenus::crack(&mut CIPHER.as_bytes(), &config)?;
+    assert_eq!(reports[0].plaintext, PLAINTEXT);
+    assert_eq!(reports[0].key.as_deref(), Some("EASY"));
+    Ok(())
+  }
+}
@@ -0,0 +1,230 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+
+use crate::Command;
+
+/// Reversed polynomial of CRC-32 as used by zip, PNG and ethernet.
+const CRC32_POLYNOMIAL: u32 = 0xedb88320;
+
+/// Largest prime below `2^16`.
+const ADLER_MODULUS: u32 = 65521;
+
+static CRC32_TABLE: LazyLock<[u32; 256]> = LazyLock::new(|| {
+  std::array::from_fn(|byte| {
+    (0..8).fold(byte as u32, |crc, _| match crc & 1 {
+      1 => (crc >> 1) ^ CRC32_POLYNOMIAL,
+      _ => crc >> 1,
+    })
+  })
+});
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChecksumAlgorithm {
+  #[default]
+  Crc32,
+  Adler32,
+  Fletcher16,
+  Fletcher32,
+}
+
+impl ChecksumAlgorithm {
+  pub fn checksum(&self, bytes: &[u8]) -> u32 {
+    match self {
+      ChecksumAlgorithm::Crc32 => Checksum::crc32(bytes),
+      ChecksumAlgorithm::Adler32 => Checksum::adler32(bytes),
+      ChecksumAlgorithm::Fletcher16 => Checksum::fletcher16(bytes),
+      ChecksumAlgorithm::Fletcher32 => Checksum::fletcher32(bytes),
+    }
+  }
+
+  /// Number of hex digits of the checksum.
+  fn width(&self) -> usize {
+    match self {
+      ChecksumAlgorithm::Fletcher16 => 4,
+      _ => 8,
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ChecksumConfig {
+  pub algorithm: ChecksumAlgorithm,
+  /// CRC-32 as hex that the input is forged to have.
+  pub forge: Option<String>,
+}
+
+impl ChecksumConfig {
+  pub fn new(algorithm: ChecksumAlgorithm, forge: Option<String>) -> Self {
+    Self { algorithm, forge }
+  }
+}
+
+impl From<&Command> for ChecksumConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Checksum {
+        algorithm, forge, ..
+      } => Self::new(*algorithm, forge.clone()),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Checksum;
+
+impl Checksum {
+  /// Writes the checksum of the input as hex.
+  ///
+  /// When forging, the input is written with four bytes appended that
+  /// give it the target CRC-32 instead.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ChecksumConfig,
+  ) -> Result<()> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+
+    let Some(target) = &config.forge else {
+      let checksum = config.algorithm.checksum(&bytes);
+      let width = config.algorithm.width();
+      return writeln!(output, "{checksum:0width$x}");
+    };
+
+    if config.algorithm != ChecksumAlgorithm::Crc32 {
+      let message = "Only CRC-32 checksums can be forged";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let target = u32::from_str_radix(target.trim(), 16).map_err(|_| {
+      let message = "The target CRC-32 must be 32-bit hex";
+      Error::new(ErrorKind::InvalidInput, message)
+    })?;
+
+    bytes.extend(Self::forge_crc32(&bytes, target));
+    output.write_all(&bytes)
+  }
+
+  pub fn crc32(bytes: &[u8]) -> u32 {
+    !Self::crc32_update(0xffffffff, bytes)
+  }
+
+  /// Adler-32 as used by zlib.
+  pub fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1, 0), |(a, b), &byte| {
+      let a = (a + byte as u32) % ADLER_MODULUS;
+      (a, (b + a) % ADLER_MODULUS)
+    });
+    (b << 16) | a
+  }
+
+  pub fn fletcher16(bytes: &[u8]) -> u32 {
+    let (sum1, sum2) = bytes.iter().fold((0, 0), |(sum1, sum2), &byte| {
+      let sum1 = (sum1 + byte as u32) % 255;
+      (sum1, (sum2 + sum1) % 255)
+    });
+    (sum2 << 8) | sum1
+  }
+
+  /// Fletcher-32 over little-endian 16-bit words, padding an odd input
+  /// with a zero byte.
+  pub fn fletcher32(bytes: &[u8]) -> u32 {
+    let (sum1, sum2) = bytes.chunks(2).fold((0, 0), |(sum1, sum2), word| {
+      let word = word[0] as u32 | (*word.get(1).unwrap_or(&0) as u32) << 8;
+      let sum1 = (sum1 + word) % 65535;
+      (sum1, (sum2 + sum1) % 65535)
+    });
+    (sum2 << 16) | sum1
+  }
+
+  /// Four bytes that give the message the target CRC-32 when appended.
+  ///
+  /// Appending bytes changes the CRC by a linear function of the bytes,
+  /// so the effect of every single bit is measured and the bits whose
+  /// effects sum up to the needed change are solved for by Gaussian
+  /// elimination over GF(2).
+  pub fn forge_crc32(message: &[u8], target: u32) -> [u8; 4] {
+    let register = Self::crc32_update(0xffffffff, message);
+    let crc =
+      |suffix: u32| !Self::crc32_update(register, &suffix.to_le_bytes());
+
+    let base = crc(0);
+    let mut rows: Vec<(u32, u32)> = (0..32)
+      .map(|bit| (crc(1 << bit) ^ base, 1 << bit))
+      .collect();
+    let mut change = target ^ base;
+    let mut suffix = 0;
+
+    for bit in 0..32 {
+      let Some(pivot) = (bit..32).find(|&row| rows[row].0 & (1 << bit) != 0)
+      else {
+        continue;
+      };
+      rows.swap(bit, pivot);
+
+      let (effect, bits) = rows[bit];
+      for (row, other) in rows.iter_mut().enumerate() {
+        if row != bit && other.0 & (1 << bit) != 0 {
+          other.0 ^= effect;
+          other.1 ^= bits;
+        }
+      }
+
+      if change & (1 << bit) != 0 {
+        change ^= effect;
+        suffix ^= bits;
+      }
+    }
+
+    suffix.to_le_bytes()
+  }
+
+  fn crc32_update(register: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(register, |crc, &byte| {
+      CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CHECK: &[u8] = b"123456789";
+
+  #[test]
+  fn test_checksums() {
+    assert_eq!(Checksum::crc32(CHECK), 0xcbf43926);
+    assert_eq!(Checksum::adler32(b"Wikipedia"), 0x11e60398);
+    assert_eq!(Checksum::fletcher16(b"abcde"), 0xc8f0);
+    assert_eq!(Checksum::fletcher32(b"abcde"), 0xf04fc729);
+    assert_eq!(Checksum::crc32(b""), 0);
+  }
+
+  #[test]
+  fn test_forge_crc32() {
+    for target in [0, 0xdeadbeef, Checksum::crc32(b"something else")] {
+      let mut forged = CHECK.to_vec();
+      forged.extend(Checksum::forge_crc32(CHECK, target));
+      assert_eq!(Checksum::crc32(&forged), target);
+    }
+  }
+
+  #[test]
+  fn test_execute() -> Result<()> {
+    let mut output = Vec::new();
+    let config = ChecksumConfig::new(ChecksumAlgorithm::Fletcher16, None);
+    Checksum::execute(&mut &b"abcde"[..], &mut output, config)?;
+    assert_eq!(output, b"c8f0\n");
+
+    let mut output = Vec::new();
+    let forge = Some(String::from("deadbeef"));
+    let config = ChecksumConfig::new(ChecksumAlgorithm::Crc32, forge);
+    Checksum::execute(&mut &CHECK[..], &mut output, config)?;
+    assert_eq!(&output[..9], CHECK);
+    assert_eq!(Checksum::crc32(&output), 0xdeadbeef);
+    Ok(())
+  }
+}
@@ -0,0 +1,291 @@
+use clap::ValueEnum;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  frequency_analysis::FrequencyAnalyzer, hex::Hex, vigenere::Vigenere, Command,
+};
+
+/// The cipher whose constraints a crib position has to satisfy.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CribCipher {
+  /// The shifts under the crib repeat with the period of the key.
+  #[default]
+  Vigenere,
+  /// The key bytes under the crib repeat with the period of the key.
+  /// The ciphertext is read as hex.
+  Xor,
+  /// No letter of the crib is encrypted to itself.
+  Enigma,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CribConfig {
+  pub cipher: CribCipher,
+  pub crib: String,
+  pub max_key_length: usize,
+  pub top: Option<usize>,
+}
+
+impl CribConfig {
+  pub fn new(
+    cipher: CribCipher,
+    crib: String,
+    max_key_length: usize,
+    top: Option<usize>,
+  ) -> Self {
+    Self {
+      cipher,
+      crib,
+      max_key_length,
+      top,
+    }
+  }
+}
+
+impl From<&Command> for CribConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Crib {
+        cipher,
+        crib,
+        max_key_length,
+        top,
+        ..
+      } => Self::new(
+        *cipher,
+        crib.clone(),
+        *max_key_length,
+        top.map(|top| top.get()),
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// An offset of the ciphertext where the crib fits the cipher.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribPosition {
+  /// Position of the crib among the letters, or bytes for XOR.
+  pub offset: usize,
+  /// The key implied by the crib, aligned to the start of the
+  /// ciphertext. Enigma positions imply no key.
+  pub key: Option<String>,
+  /// The ciphertext decrypted with the key, or the ciphertext under the
+  /// crib for Enigma.
+  pub text: String,
+  /// Bigram or byte score of the decryption, lower is better.
+  pub score: f32,
+}
+
+pub struct Crib;
+
+impl Crib {
+  /// Writes the positions where the crib fits, best first.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: CribConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let mut positions = match config.cipher {
+      CribCipher::Vigenere => {
+        let max_key_length = config.max_key_length.min(u8::MAX as usize);
+        Self::vigenere(&content, &config.crib, max_key_length as u8)
+      }
+      CribCipher::Xor => {
+        let cipher =
+          Hex::parse_hex(&content.split_whitespace().collect::<String>())?;
+        Self::xor(&cipher.bytes, config.crib.as_bytes(), config.max_key_length)
+      }
+      CribCipher::Enigma => Self::enigma(&content, &config.crib),
+    };
+    if let Some(top) = config.top {
+      positions.truncate(top);
+    }
+
+    if positions.is_empty() {
+      let message = "The crib fits at no position of the ciphertext";
+      return Err(Error::new(ErrorKind::NotFound, message));
+    }
+
+    for position in positions {
+      match position.key {
+        Some(key) => writeln!(
+          output,
+          "offset {}, key {key}: {}",
+          position.offset, position.text
+        )?,
+        None => {
+          writeln!(output, "offset {}: {}", position.offset, position.text)?
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Offsets where the crib implies a repeating Vigenere key, ranked by
+  /// the length of the key and then by the bigram score of the
+  /// decryption with it.
+  pub fn vigenere(
+    cipher: &str,
+    crib: &str,
+    max_key_length: u8,
+  ) -> Vec<CribPosition> {
+    let letters: Vec<u8> = cipher
+      .bytes()
+      .filter(u8::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase() - b'A')
+      .collect();
+
+    let mut positions: Vec<CribPosition> =
+      Vigenere::crib_keys(cipher, crib, max_key_length)
+        .into_iter()
+        .map(|found| {
+          let shifts: Vec<u8> = found.key.bytes().map(|c| c - b'A').collect();
+          let text: String = letters
+            .iter()
+            .zip(shifts.iter().cycle())
+            .map(|(&c, &shift)| (b'A' + (c + 26 - shift) % 26) as char)
+            .collect();
+          let score = FrequencyAnalyzer::bigram_score(&text);
+          CribPosition {
+            offset: found.offset,
+            key: Some(found.key),
+            text,
+            score,
+          }
+        })
+        .collect();
+
+    Self::rank(&mut positions);
+    positions
+  }
+
+  /// Offsets where the crib implies a repeating XOR key of at most
+  /// `max_key_length` bytes that it covers at least twice, ranked by the
+  /// length of the key and then by the byte score of the decryption.
+  pub fn xor(
+    cipher: &[u8],
+    crib: &[u8],
+    max_key_length: usize,
+  ) -> Vec<CribPosition> {
+    if crib.is_empty() || crib.len() > cipher.len() {
+      return Vec::new();
+    }
+
+    let max_period = max_key_length.min(crib.len() / 2);
+    let mut positions: Vec<CribPosition> = (0..=cipher.len() - crib.len())
+      .filter_map(|offset| {
+        let fragment: Vec<u8> = crib
+          .iter()
+          .zip(&cipher[offset..])
+          .map(|(p, c)| p ^ c)
+          .collect();
+
+        let period = (1..=max_period).find(|&period| {
+          (period..fragment.len()).all(|i| fragment[i] == fragment[i - period])
+        })?;
+
+        let key: Vec<u8> = (0..period)
+          .map(|j| fragment[(j + period - offset % period) % period])
+          .collect();
+        let plaintext: Vec<u8> = cipher
+          .iter()
+          .zip(key.iter().cycle())
+          .map(|(c, k)| c ^ k)
+          .collect();
+
+        Some(CribPosition {
+          offset,
+          score: FrequencyAnalyzer::byte_score(&plaintext),
+          key: Some(Hex::new(key).to_string()),
+          text: Hex::new(plaintext).to_ascii(),
+        })
+      })
+      .collect();
+
+    Self::rank(&mut positions);
+    positions
+  }
+
+  /// Sorts positions by the length of their key, then by their score.
+  fn rank(positions: &mut [CribPosition]) {
+    positions.sort_by(|a, b| {
+      let length = |p: &CribPosition| p.key.as_ref().map(String::len);
+      length(a)
+        .cmp(&length(b))
+        .then(a.score.total_cmp(&b.score))
+        .then(a.offset.cmp(&b.offset))
+    });
+  }
+
+  /// Offsets where no letter of the crib lies on the same letter of the
+  /// ciphertext, since an Enigma never encrypts a letter to itself. All
+  /// of them are equally possible and reported in order.
+  pub fn enigma(cipher: &str, crib: &str) -> Vec<CribPosition> {
+    let letters = |text: &str| -> Vec<char> {
+      text
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+    };
+    let (cipher, crib) = (letters(cipher), letters(crib));
+
+    if crib.is_empty() || crib.len() > cipher.len() {
+      return Vec::new();
+    }
+
+    (0..=cipher.len() - crib.len())
+      .filter(|&offset| crib.iter().zip(&cipher[offset..]).all(|(p, c)| p != c))
+      .map(|offset| CribPosition {
+        offset,
+        key: None,
+        text: cipher[offset..offset + crib.len()].iter().collect(),
+        score: 0.0,
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_vigenere_positions() {
+    let cipher = "LXFOPVEFRNHR";
+    let positions = Crib::vigenere(cipher, "ATTACKATDA", 20);
+    assert_eq!(positions[0].offset, 0);
+    assert_eq!(positions[0].key.as_deref(), Some("LEMON"));
+    assert_eq!(positions[0].text, "ATTACKATDAWN");
+  }
+
+  #[test]
+  fn test_xor_positions() {
+    let plaintext = b"meet me at the usual place at ten";
+    let cipher: Vec<u8> = plaintext
+      .iter()
+      .zip(b"KEY".iter().cycle())
+      .map(|(p, k)| p ^ k)
+      .collect();
+
+    let positions = Crib::xor(&cipher, b"the usual", 8);
+    assert_eq!(positions[0].offset, 11);
+    assert_eq!(positions[0].key.as_deref(), Some("4b4559"));
+    assert_eq!(positions[0].text.as_bytes(), plaintext);
+  }
+
+  #[test]
+  fn test_enigma_positions() {
+    let positions = Crib::enigma("abcde", "AB");
+    let offsets: Vec<usize> = positions.iter().map(|p| p.offset).collect();
+    assert_eq!(offsets, [1, 2, 3]);
+    assert_eq!(positions[0].text, "BC");
+    assert!(Crib::enigma("AAAA", "A").is_empty());
+  }
+}
@@ -0,0 +1,426 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{hex::Hex, DesCommand};
+
+/// Block and key size of DES in bytes, where every eighth key bit is a
+/// parity bit that the key schedule drops.
+pub const BLOCK_SIZE: usize = 8;
+
+/// Keys whose sixteen subkeys are all equal, so that encryption is an
+/// involution.
+pub const WEAK_KEYS: [u64; 4] = [
+  0x0101010101010101,
+  0xfefefefefefefefe,
+  0xe0e0e0e0f1f1f1f1,
+  0x1f1f1f1f0e0e0e0e,
+];
+
+const INITIAL_PERMUTATION: [u8; 64] = [
+  58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4, 62, 54, 46, 38,
+  30, 22, 14, 6, 64, 56, 48, 40, 32, 24, 16, 8, 57, 49, 41, 33, 25, 17, 9, 1,
+  59, 51, 43, 35, 27, 19, 11, 3, 61, 53, 45, 37, 29, 21, 13, 5, 63, 55, 47, 39,
+  31, 23, 15, 7,
+];
+
+const FINAL_PERMUTATION: [u8; 64] = [
+  40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31, 38, 6, 46, 14,
+  54, 22, 62, 30, 37, 5, 45, 13, 53, 21, 61, 29, 36, 4, 44, 12, 52, 20, 60, 28,
+  35, 3, 43, 11, 51, 19, 59, 27, 34, 2, 42, 10, 50, 18, 58, 26, 33, 1, 41, 9,
+  49, 17, 57, 25,
+];
+
+/// Expands the 32 bit half block to 48 bits by duplicating edge bits.
+const EXPANSION: [u8; 48] = [
+  32, 1, 2, 3, 4, 5, 4, 5, 6, 7, 8, 9, 8, 9, 10, 11, 12, 13, 12, 13, 14, 15,
+  16, 17, 16, 17, 18, 19, 20, 21, 20, 21, 22, 23, 24, 25, 24, 25, 26, 27, 28,
+  29, 28, 29, 30, 31, 32, 1,
+];
+
+const PERMUTATION: [u8; 32] = [
+  16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10, 2, 8, 24, 14,
+  32, 27, 3, 9, 19, 13, 30, 6, 22, 11, 4, 25,
+];
+
+/// Selects the 56 key bits without parity into the halves C and D.
+const PERMUTED_CHOICE_1: [u8; 56] = [
+  57, 49, 41, 33, 25, 17, 9, 1, 58, 50, 42, 34, 26, 18, 10, 2, 59, 51, 43, 35,
+  27, 19, 11, 3, 60, 52, 44, 36, 63, 55, 47, 39, 31, 23, 15, 7, 62, 54, 46, 38,
+  30, 22, 14, 6, 61, 53, 45, 37, 29, 21, 13, 5, 28, 20, 12, 4,
+];
+
+/// Selects the 48 subkey bits from the rotated halves.
+const PERMUTED_CHOICE_2: [u8; 48] = [
+  14, 17, 11, 24, 1, 5, 3, 28, 15, 6, 21, 10, 23, 19, 12, 4, 26, 8, 16, 7, 27,
+  20, 13, 2, 41, 52, 31, 37, 47, 55, 30, 40, 51, 45, 33, 48, 44, 49, 39, 56,
+  34, 53, 46, 42, 50, 36, 29, 32,
+];
+
+/// Left rotations of C and D per round, which add up to 28.
+const SHIFTS: [u32; 16] = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1];
+
+const SBOXES: [[[u8; 16]; 4]; 8] = [
+  [
+    [14, 4, 13, 1, 2, 15, 11, 8, 3, 10, 6, 12, 5, 9, 0, 7],
+    [0, 15, 7, 4, 14, 2, 13, 1, 10, 6, 12, 11, 9, 5, 3, 8],
+    [4, 1, 14, 8, 13, 6, 2, 11, 15, 12, 9, 7, 3, 10, 5, 0],
+    [15, 12, 8, 2, 4, 9, 1, 7, 5, 11, 3, 14, 10, 0, 6, 13],
+  ],
+  [
+    [15, 1, 8, 14, 6, 11, 3, 4, 9, 7, 2, 13, 12, 0, 5, 10],
+    [3, 13, 4, 7, 15, 2, 8, 14, 12, 0, 1, 10, 6, 9, 11, 5],
+    [0, 14, 7, 11, 10, 4, 13, 1, 5, 8, 12, 6, 9, 3, 2, 15],
+    [13, 8, 10, 1, 3, 15, 4, 2, 11, 6, 7, 12, 0, 5, 14, 9],
+  ],
+  [
+    [10, 0, 9, 14, 6, 3, 15, 5, 1, 13, 12, 7, 11, 4, 2, 8],
+    [13, 7, 0, 9, 3, 4, 6, 10, 2, 8, 5, 14, 12, 11, 15, 1],
+    [13, 6, 4, 9, 8, 15, 3, 0, 11, 1, 2, 12, 5, 10, 14, 7],
+    [1, 10, 13, 0, 6, 9, 8, 7, 4, 15, 14, 3, 11, 5, 2, 12],
+  ],
+  [
+    [7, 13, 14, 3, 0, 6, 9, 10, 1, 2, 8, 5, 11, 12, 4, 15],
+    [13, 8, 11, 5, 6, 15, 0, 3, 4, 7, 2, 12, 1, 10, 14, 9],
+    [10, 6, 9, 0, 12, 11, 7, 13, 15, 1, 3, 14, 5, 2, 8, 4],
+    [3, 15, 0, 6, 10, 1, 13, 8, 9, 4, 5, 11, 12, 7, 2, 14],
+  ],
+  [
+    [2, 12, 4, 1, 7, 10, 11, 6, 8, 5, 3, 15, 13, 0, 14, 9],
+    [14, 11, 2, 12, 4, 7, 13, 1, 5, 0, 15, 10, 3, 9, 8, 6],
+    [4, 2, 1, 11, 10, 13, 7, 8, 15, 9, 12, 5, 6, 3, 0, 14],
+    [11, 8, 12, 7, 1, 14, 2, 13, 6, 15, 0, 9, 10, 4, 5, 3],
+  ],
+  [
+    [12, 1, 10, 15, 9, 2, 6, 8, 0, 13, 3, 4, 14, 7, 5, 11],
+    [10, 15, 4, 2, 7, 12, 9, 5, 6, 1, 13, 14, 0, 11, 3, 8],
+    [9, 14, 15, 5, 2, 8, 12, 3, 7, 0, 4, 10, 1, 13, 11, 6],
+    [4, 3, 2, 12, 9, 5, 15, 10, 11, 14, 1, 7, 6, 0, 8, 13],
+  ],
+  [
+    [4, 11, 2, 14, 15, 0, 8, 13, 3, 12, 9, 7, 5, 10, 6, 1],
+    [13, 0, 11, 7, 4, 9, 1, 10, 14, 3, 5, 12, 2, 15, 8, 6],
+    [1, 4, 11, 13, 12, 3, 7, 14, 10, 15, 6, 8, 0, 5, 9, 2],
+    [6, 11, 13, 8, 1, 4, 10, 7, 9, 5, 0, 15, 14, 2, 3, 12],
+  ],
+  [
+    [13, 2, 8, 4, 6, 15, 11, 1, 10, 9, 3, 14, 5, 0, 12, 7],
+    [1, 15, 13, 8, 10, 3, 7, 4, 12, 5, 6, 11, 0, 14, 9, 2],
+    [7, 11, 4, 1, 9, 12, 14, 2, 0, 6, 10, 13, 15, 3, 5, 8],
+    [2, 1, 14, 7, 4, 10, 8, 13, 15, 12, 9, 0, 3, 5, 6, 11],
+  ],
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DesConfig {
+  /// Key as 16 hex digits.
+  pub key: String,
+  pub decrypt: bool,
+  /// Write the halves after every round before each result.
+  pub trace: bool,
+  /// Plaintext block as 16 hex digits for the property demonstration.
+  pub plaintext: Option<String>,
+}
+
+impl DesConfig {
+  pub fn new(
+    key: String,
+    decrypt: bool,
+    trace: bool,
+    plaintext: Option<String>,
+  ) -> Self {
+    Self {
+      key,
+      decrypt,
+      trace,
+      plaintext,
+    }
+  }
+}
+
+impl From<&DesCommand> for DesConfig {
+  fn from(value: &DesCommand) -> Self {
+    match value {
+      DesCommand::Encrypt { key, trace, .. } => {
+        Self::new(key.clone().unwrap_or_default(), false, *trace, None)
+      }
+      DesCommand::Decrypt { key, trace, .. } => {
+        Self::new(key.clone().unwrap_or_default(), true, *trace, None)
+      }
+      DesCommand::Schedule { key, .. } => {
+        Self::new(key.clone(), false, false, None)
+      }
+      DesCommand::Properties { key, plaintext, .. } => {
+        Self::new(key.clone(), false, false, Some(plaintext.clone()))
+      }
+    }
+  }
+}
+
+/// The halves C and D after the rotation of a round and the subkey
+/// chosen from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DesSubkey {
+  pub c: u32,
+  pub d: u32,
+  pub subkey: u64,
+}
+
+/// The halves after a round together with its subkey and the output of
+/// the round function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DesRound {
+  pub round: usize,
+  pub left: u32,
+  pub right: u32,
+  pub subkey: u64,
+  pub feistel: u32,
+}
+
+/// DES for tracing by hand, which is far too weak for anything else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Des {
+  schedule: [DesSubkey; 16],
+}
+
+impl Des {
+  pub fn new(key: u64) -> Self {
+    Self {
+      schedule: Self::key_schedule(key),
+    }
+  }
+
+  /// Reads hex blocks and writes each encrypted or decrypted block as
+  /// hex on its own line, preceded by its rounds if tracing.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: DesConfig,
+  ) -> Result<()> {
+    let des = Self::new(Self::parse_block(&config.key, "key")?);
+
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let digits: String = content.split_whitespace().collect();
+    let bytes = Hex::parse_hex(&digits)?.bytes;
+
+    if bytes.len() % BLOCK_SIZE != 0 {
+      let message = format!("The input must be whole {BLOCK_SIZE} byte blocks");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    for (index, block) in bytes.chunks_exact(BLOCK_SIZE).enumerate() {
+      let block = u64::from_be_bytes(block.try_into().unwrap());
+      let (result, rounds) = des.rounds(block, config.decrypt);
+
+      if config.trace {
+        writeln!(output, "block {index}")?;
+        let initial = Self::permute(block, 64, &INITIAL_PERMUTATION);
+        writeln!(output, "IP: {initial:016x}")?;
+        for DesRound {
+          round,
+          left,
+          right,
+          subkey,
+          feistel,
+        } in rounds
+        {
+          writeln!(
+            output,
+            "round {round}: L = {left:08x} R = {right:08x} \
+             K = {subkey:012x} f = {feistel:08x}"
+          )?;
+        }
+      }
+
+      writeln!(output, "{result:016x}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Writes C, D and the subkey of every round.
+  pub fn write_schedule<W: Write>(
+    output: &mut W,
+    config: DesConfig,
+  ) -> Result<()> {
+    let key = Self::parse_block(&config.key, "key")?;
+    for (round, DesSubkey { c, d, subkey }) in
+      Self::key_schedule(key).iter().enumerate()
+    {
+      writeln!(
+        output,
+        "round {}: C = {c:07x} D = {d:07x} K = {subkey:012x}",
+        round + 1
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Writes the complementation property `E(~k, ~p) = ~E(k, p)` for the
+  /// key and then for every weak key that encrypting twice gives back
+  /// the plaintext.
+  pub fn properties<W: Write>(output: &mut W, config: DesConfig) -> Result<()> {
+    let key = Self::parse_block(&config.key, "key")?;
+    let plaintext =
+      Self::parse_block(&config.plaintext.unwrap_or_default(), "plaintext")?;
+
+    let ciphertext = Self::new(key).encrypt(plaintext);
+    let complemented = Self::new(!key).encrypt(!plaintext);
+    writeln!(output, "E(k, p) = {ciphertext:016x}")?;
+    writeln!(output, "E(~k, ~p) = {complemented:016x}")?;
+    writeln!(output, "~E(k, p) = {:016x}", !ciphertext)?;
+    match complemented == !ciphertext {
+      true => writeln!(output, "complementation property holds")?,
+      false => writeln!(output, "complementation property fails")?,
+    }
+
+    for weak in WEAK_KEYS {
+      let des = Self::new(weak);
+      let twice = des.encrypt(des.encrypt(plaintext));
+      writeln!(output, "weak key {weak:016x}: E(k, E(k, p)) = {twice:016x}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn encrypt(&self, block: u64) -> u64 {
+    self.rounds(block, false).0
+  }
+
+  pub fn decrypt(&self, block: u64) -> u64 {
+    self.rounds(block, true).0
+  }
+
+  /// Sixteen Feistel rounds between the initial and final permutation.
+  /// Decryption is the same with the subkeys in reverse.
+  pub fn rounds(&self, block: u64, decrypt: bool) -> (u64, Vec<DesRound>) {
+    let initial = Self::permute(block, 64, &INITIAL_PERMUTATION);
+    let (mut left, mut right) = ((initial >> 32) as u32, initial as u32);
+    let mut rounds = Vec::new();
+
+    for round in 0..16 {
+      let subkey = match decrypt {
+        true => self.schedule[15 - round].subkey,
+        false => self.schedule[round].subkey,
+      };
+      let feistel = Self::feistel(right, subkey);
+      (left, right) = (right, left ^ feistel);
+      rounds.push(DesRound {
+        round: round + 1,
+        left,
+        right,
+        subkey,
+        feistel,
+      });
+    }
+
+    let preoutput = (right as u64) << 32 | left as u64;
+    (Self::permute(preoutput, 64, &FINAL_PERMUTATION), rounds)
+  }
+
+  /// Drops the parity bits, splits the key into C and D and rotates both
+  /// left before choosing each subkey.
+  pub fn key_schedule(key: u64) -> [DesSubkey; 16] {
+    let permuted = Self::permute(key, 64, &PERMUTED_CHOICE_1);
+    let mask = (1 << 28) - 1;
+    let (mut c, mut d) = ((permuted >> 28) as u32, permuted as u32 & mask);
+    let rotate =
+      |half: u32, shift| (half << shift | half >> (28 - shift)) & mask;
+
+    SHIFTS.map(|shift| {
+      (c, d) = (rotate(c, shift), rotate(d, shift));
+      let joined = (c as u64) << 28 | d as u64;
+      DesSubkey {
+        c,
+        d,
+        subkey: Self::permute(joined, 56, &PERMUTED_CHOICE_2),
+      }
+    })
+  }
+
+  /// Expands the half, mixes in the subkey, substitutes every six bits by
+  /// four through the S-boxes and permutes the result.
+  fn feistel(half: u32, subkey: u64) -> u32 {
+    let expanded = Self::permute(half as u64, 32, &EXPANSION) ^ subkey;
+
+    let substituted = SBOXES.iter().enumerate().fold(0, |output, (i, sbox)| {
+      let bits = (expanded >> (42 - 6 * i)) as usize & 0x3f;
+      let row = (bits >> 4 & 0b10) | (bits & 1);
+      let column = bits >> 1 & 0xf;
+      output << 4 | sbox[row][column] as u64
+    });
+
+    Self::permute(substituted, 32, &PERMUTATION) as u32
+  }
+
+  /// Bit `i` of the output is bit `table[i]` of the input, counting from
+  /// one at the most significant of `width` bits as in FIPS 46.
+  fn permute(input: u64, width: u32, table: &[u8]) -> u64 {
+    table.iter().fold(0, |output, &position| {
+      output << 1 | (input >> (width - position as u32)) & 1
+    })
+  }
+
+  fn parse_block(hex: &str, name: &str) -> Result<u64> {
+    let bytes = Hex::parse_hex(hex)?.bytes;
+    let bytes: [u8; BLOCK_SIZE] = bytes.try_into().map_err(|_| {
+      let message = format!("The {name} must be {BLOCK_SIZE} bytes");
+      Error::new(ErrorKind::InvalidInput, message)
+    })?;
+    Ok(u64::from_be_bytes(bytes))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const KEY: u64 = 0x133457799bbcdff1;
+
+  #[test]
+  fn test_key_schedule() {
+    let schedule = Des::key_schedule(KEY);
+    assert_eq!(schedule[0].c, 0b1110000110011001010101011111);
+    assert_eq!(schedule[0].subkey, 0x1b02effc7072);
+    assert_eq!(schedule[15].subkey, 0xcb3d8b0e17f5);
+  }
+
+  #[test]
+  fn test_roundtrip() {
+    let des = Des::new(KEY);
+    let ciphertext = des.encrypt(0x0123456789abcdef);
+    assert_eq!(ciphertext, 0x85e813540f0ab405);
+    assert_eq!(des.decrypt(ciphertext), 0x0123456789abcdef);
+  }
+
+  #[test]
+  fn test_complementation() {
+    let plaintext = 0x0123456789abcdef;
+    let ciphertext = Des::new(KEY).encrypt(plaintext);
+    assert_eq!(Des::new(!KEY).encrypt(!plaintext), !ciphertext);
+  }
+
+  #[test]
+  fn test_weak_keys() {
+    for key in WEAK_KEYS {
+      let des = Des::new(key);
+      assert_eq!(
+        des.encrypt(des.encrypt(0x0123456789abcdef)),
+        0x0123456789abcdef
+      );
+    }
+  }
+
+  #[test]
+  fn test_trace() -> Result<()> {
+    let mut output = Vec::new();
+    let config = DesConfig::new("133457799bbcdff1".into(), false, true, None);
+    Des::execute(&mut "0123456789abcdef".as_bytes(), &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("block 0\nIP: cc00ccfff0aaf0aa\n"));
+    assert!(output.contains("round 1: L = f0aaf0aa R = ef4a6544 "));
+    assert!(output.contains("round 16: L = 43423234 R = 0a4cd995 "));
+    assert!(output.ends_with("\n85e813540f0ab405\n"));
+    Ok(())
+  }
+}
@@ -0,0 +1,299 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use rand::{rngs::OsRng, rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{hex::Hex, Command};
+
+use super::BLOCK_SIZE;
+
+/// Number of Feistel rounds of the toy block cipher.
+const ROUNDS: u32 = 8;
+
+/// Odd constant from the golden ratio that mixes the round function.
+const GOLDEN: u64 = 0x9e3779b97f4a7c15;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EcbDemoConfig {
+  /// Key as 32 hex digits, random if missing.
+  pub key: Option<String>,
+  /// CBC initialization vector as 32 hex digits, random if missing.
+  pub iv: Option<String>,
+  /// Seed for a reproducible random key and IV.
+  pub seed: Option<u64>,
+}
+
+impl EcbDemoConfig {
+  pub fn new(key: Option<String>, iv: Option<String>) -> Self {
+    Self {
+      key,
+      iv,
+      seed: None,
+    }
+  }
+}
+
+impl From<&Command> for EcbDemoConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::EcbDemo { key, iv, seed, .. } => Self {
+        seed: *seed,
+        ..Self::new(key.clone(), iv.clone())
+      },
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A toy 128-bit Feistel cipher. It is no more secure than its round
+/// function, but deterministic per block like every block cipher, which
+/// is all the demonstration needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToyBlockCipher {
+  round_keys: [u64; ROUNDS as usize],
+}
+
+impl ToyBlockCipher {
+  pub fn new(key: &[u8; BLOCK_SIZE]) -> Self {
+    let (high, low) = Self::halves(key);
+    let round_keys = std::array::from_fn(|round| {
+      let round = round as u32;
+      high.rotate_left(round * 8)
+        ^ low.rotate_right(round * 3)
+        ^ GOLDEN.wrapping_mul(round as u64 + 1)
+    });
+    Self { round_keys }
+  }
+
+  pub fn encrypt_block(&self, block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let (mut left, mut right) = Self::halves(block);
+    for key in self.round_keys {
+      (left, right) = (right, left ^ Self::round(right, key));
+    }
+    Self::join(left, right)
+  }
+
+  pub fn decrypt_block(&self, block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let (mut left, mut right) = Self::halves(block);
+    for key in self.round_keys.into_iter().rev() {
+      (left, right) = (right ^ Self::round(left, key), left);
+    }
+    Self::join(left, right)
+  }
+
+  fn round(half: u64, key: u64) -> u64 {
+    let mixed = (half ^ key).wrapping_mul(GOLDEN);
+    mixed.rotate_left(29) ^ (mixed >> 17)
+  }
+
+  fn halves(block: &[u8; BLOCK_SIZE]) -> (u64, u64) {
+    let (high, low) = block.split_at(BLOCK_SIZE / 2);
+    (
+      u64::from_be_bytes(high.try_into().unwrap()),
+      u64::from_be_bytes(low.try_into().unwrap()),
+    )
+  }
+
+  fn join(left: u64, right: u64) -> [u8; BLOCK_SIZE] {
+    let mut block = [0; BLOCK_SIZE];
+    block[..BLOCK_SIZE / 2].copy_from_slice(&left.to_be_bytes());
+    block[BLOCK_SIZE / 2..].copy_from_slice(&right.to_be_bytes());
+    block
+  }
+}
+
+pub struct EcbDemo;
+
+impl EcbDemo {
+  /// Encrypts the input under the same key in ECB and CBC mode and
+  /// writes the blocks of both, marking every block that repeats an
+  /// earlier one.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: EcbDemoConfig,
+  ) -> Result<()> {
+    let mut plaintext = Vec::new();
+    input.read_to_end(&mut plaintext)?;
+
+    let (key, iv) = match config.seed {
+      Some(seed) => {
+        Self::key_and_iv(&mut StdRng::seed_from_u64(seed), &config)?
+      }
+      None => Self::key_and_iv(&mut OsRng, &config)?,
+    };
+    let cipher = ToyBlockCipher::new(&key);
+
+    writeln!(output, "key: {}", Hex::new(key.to_vec()))?;
+    writeln!(output, "iv: {}", Hex::new(iv.to_vec()))?;
+
+    let modes = [
+      ("ecb", Self::encrypt_ecb(&cipher, &plaintext)),
+      ("cbc", Self::encrypt_cbc(&cipher, &iv, &plaintext)),
+    ];
+
+    for (mode, ciphertext) in modes {
+      let blocks: Vec<&[u8]> = ciphertext.chunks(BLOCK_SIZE).collect();
+      let mut repetitions = 0;
+
+      for (index, block) in blocks.iter().enumerate() {
+        let hex = Hex::new(block.to_vec());
+        match blocks[..index].iter().position(|earlier| earlier == block) {
+          Some(earlier) => {
+            repetitions += 1;
+            writeln!(output, "{mode} block {index}: {hex} = block {earlier}")?
+          }
+          None => writeln!(output, "{mode} block {index}: {hex}")?,
+        }
+      }
+
+      writeln!(
+        output,
+        "{mode}: {repetitions} of {} blocks are repetitions",
+        blocks.len()
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Every block is encrypted on its own, so equal plaintext blocks give
+  /// equal ciphertext blocks.
+  pub fn encrypt_ecb(cipher: &ToyBlockCipher, plaintext: &[u8]) -> Vec<u8> {
+    Self::pad(plaintext)
+      .chunks_exact(BLOCK_SIZE)
+      .flat_map(|block| cipher.encrypt_block(block.try_into().unwrap()))
+      .collect()
+  }
+
+  /// Every block is XORed with the previous ciphertext block before
+  /// encryption, which hides repetitions behind the chaining value.
+  pub fn encrypt_cbc(
+    cipher: &ToyBlockCipher,
+    iv: &[u8; BLOCK_SIZE],
+    plaintext: &[u8],
+  ) -> Vec<u8> {
+    let mut previous = *iv;
+    let mut ciphertext = Vec::new();
+
+    for block in Self::pad(plaintext).chunks_exact(BLOCK_SIZE) {
+      let chained = std::array::from_fn(|i| block[i] ^ previous[i]);
+      previous = cipher.encrypt_block(&chained);
+      ciphertext.extend_from_slice(&previous);
+    }
+
+    ciphertext
+  }
+
+  /// PKCS#7 padding, which always appends between one and a full block.
+  pub fn pad(plaintext: &[u8]) -> Vec<u8> {
+    let padding = BLOCK_SIZE - plaintext.len() % BLOCK_SIZE;
+    let mut padded = plaintext.to_vec();
+    padded.resize(plaintext.len() + padding, padding as u8);
+    padded
+  }
+
+  /// The configured key and IV, or random ones drawn from `rng`.
+  fn key_and_iv<G: RngCore>(
+    rng: &mut G,
+    config: &EcbDemoConfig,
+  ) -> Result<([u8; BLOCK_SIZE], [u8; BLOCK_SIZE])> {
+    let key = Self::block_or_random(rng, config.key.as_deref(), "key")?;
+    let iv = Self::block_or_random(rng, config.iv.as_deref(), "IV")?;
+    Ok((key, iv))
+  }
+
+  fn block_or_random<G: RngCore>(
+    rng: &mut G,
+    hex: Option<&str>,
+    name: &str,
+  ) -> Result<[u8; BLOCK_SIZE]> {
+    let mut block = [0; BLOCK_SIZE];
+    match hex {
+      Some(hex) => {
+        let bytes = Hex::parse_hex(hex)?.bytes;
+        block = bytes.try_into().map_err(|_| {
+          let message = format!("The {name} must be {BLOCK_SIZE} bytes");
+          Error::new(ErrorKind::InvalidInput, message)
+        })?;
+      }
+      None => rng.fill_bytes(&mut block),
+    }
+    Ok(block)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ecb::Ecb;
+
+  use super::*;
+
+  const KEY: [u8; BLOCK_SIZE] = *b"YELLOW SUBMARINE";
+
+  #[test]
+  fn test_block_roundtrip() {
+    let cipher = ToyBlockCipher::new(&KEY);
+    let block = *b"attack at dawn!!";
+    let encrypted = cipher.encrypt_block(&block);
+    assert_ne!(encrypted, block);
+    assert_eq!(cipher.decrypt_block(&encrypted), block);
+  }
+
+  #[test]
+  fn test_pad() {
+    assert_eq!(EcbDemo::pad(b"abc").len(), 16);
+    assert_eq!(EcbDemo::pad(b"abc")[15], 13);
+    assert_eq!(EcbDemo::pad(&[0; 16]), [[0; 16], [16; 16]].concat());
+  }
+
+  #[test]
+  fn test_modes() {
+    let cipher = ToyBlockCipher::new(&KEY);
+    let plaintext = [b'A'; 48];
+
+    let ecb = EcbDemo::encrypt_ecb(&cipher, &plaintext);
+    assert_eq!(ecb.len(), 64);
+    assert_eq!(
+      Ecb::repeated_blocks(&ecb, BLOCK_SIZE)[0].offsets,
+      [0, 16, 32]
+    );
+
+    let cbc = EcbDemo::encrypt_cbc(&cipher, &[0; BLOCK_SIZE], &plaintext);
+    assert_eq!(cbc.len(), 64);
+    assert!(Ecb::repeated_blocks(&cbc, BLOCK_SIZE).is_empty());
+  }
+
+  #[test]
+  fn test_execute() -> Result<()> {
+    let mut output = Vec::new();
+    let key = Hex::new(KEY.to_vec()).to_string();
+    let config = EcbDemoConfig::new(Some(key), Some("00".repeat(16)));
+    EcbDemo::execute(&mut &[b'A'; 32][..], &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("ecb block 1: "));
+    assert!(output.contains(" = block 0\n"));
+    assert!(output.contains("ecb: 1 of 3 blocks are repetitions\n"));
+    assert!(output.contains("cbc: 0 of 3 blocks are repetitions\n"));
+
+    let config = EcbDemoConfig::new(Some("00".into()), None);
+    assert!(EcbDemo::execute(&mut &b""[..], &mut Vec::new(), config).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_seeded_keys_repeat() -> Result<()> {
+    let execute = |seed| -> Result<Vec<u8>> {
+      let mut output = Vec::new();
+      let config = EcbDemoConfig {
+        seed: Some(seed),
+        ..EcbDemoConfig::default()
+      };
+      EcbDemo::execute(&mut &[b'A'; 32][..], &mut output, config)?;
+      Ok(output)
+    };
+    assert_eq!(execute(42)?, execute(42)?);
+    assert_ne!(execute(42)?, execute(43)?);
+    Ok(())
+  }
+}
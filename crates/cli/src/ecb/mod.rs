@@ -0,0 +1,173 @@
+pub mod demo;
+
+use std::{
+  collections::HashMap,
+  io::{Error, ErrorKind, Read, Result, Write},
+};
+
+use crate::{hex::Hex, Command};
+
+/// Block size of AES, which most ECB ciphertexts in the wild use.
+pub const BLOCK_SIZE: usize = 16;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DetectEcbConfig {
+  pub block_size: usize,
+  /// Whether the input is hex instead of raw bytes.
+  pub hex: bool,
+}
+
+impl DetectEcbConfig {
+  pub fn new(block_size: usize, hex: bool) -> Self {
+    Self { block_size, hex }
+  }
+}
+
+impl Default for DetectEcbConfig {
+  fn default() -> Self {
+    Self::new(BLOCK_SIZE, false)
+  }
+}
+
+impl From<&Command> for DetectEcbConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::DetectEcb {
+        block_size, hex, ..
+      } => Self::new(*block_size, *hex),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A block that occurs more than once together with its byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatedBlock {
+  pub block: Vec<u8>,
+  pub offsets: Vec<usize>,
+}
+
+pub struct Ecb;
+
+impl Ecb {
+  /// Writes every repeated block with its count and offsets, followed by
+  /// the share of blocks that are repetitions.
+  pub fn detect<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: DetectEcbConfig,
+  ) -> Result<()> {
+    if config.block_size == 0 {
+      let message = "The block size must not be zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+
+    if config.hex {
+      let content = String::from_utf8_lossy(&bytes);
+      let digits: String = content.split_whitespace().collect();
+      bytes = Hex::parse_hex(&digits)?.bytes;
+    }
+
+    let repeated = Self::repeated_blocks(&bytes, config.block_size);
+    let blocks = bytes.len() / config.block_size;
+
+    for RepeatedBlock { block, offsets } in &repeated {
+      let offsets: Vec<String> = offsets.iter().map(usize::to_string).collect();
+      writeln!(
+        output,
+        "{} repeated {} times at offsets {}",
+        Hex::new(block.clone()),
+        offsets.len(),
+        offsets.join(", ")
+      )?;
+    }
+
+    let repetitions: usize =
+      repeated.iter().map(|block| block.offsets.len() - 1).sum();
+    match repetitions {
+      0 => writeln!(output, "no repeated blocks in {blocks} blocks"),
+      _ => writeln!(
+        output,
+        "{repetitions} of {blocks} blocks are repetitions, likely ECB"
+      ),
+    }
+  }
+
+  /// Blocks at aligned offsets that occur more than once, ordered by
+  /// their first occurrence. A trailing partial block is ignored.
+  pub fn repeated_blocks(
+    bytes: &[u8],
+    block_size: usize,
+  ) -> Vec<RepeatedBlock> {
+    let mut offsets: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for (index, block) in bytes.chunks_exact(block_size).enumerate() {
+      offsets.entry(block).or_default().push(index * block_size);
+    }
+
+    let mut repeated: Vec<RepeatedBlock> = offsets
+      .into_iter()
+      .filter(|(_, offsets)| offsets.len() > 1)
+      .map(|(block, offsets)| RepeatedBlock {
+        block: block.to_vec(),
+        offsets,
+      })
+      .collect();
+
+    repeated.sort_by_key(|block| block.offsets[0]);
+    repeated
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_repeated_blocks() {
+    let bytes = b"AAAABBBBAAAACCCCBBBBAAAADD";
+    let repeated = Ecb::repeated_blocks(bytes, 4);
+    assert_eq!(
+      repeated,
+      [
+        RepeatedBlock {
+          block: b"AAAA".to_vec(),
+          offsets: vec![0, 8, 20],
+        },
+        RepeatedBlock {
+          block: b"BBBB".to_vec(),
+          offsets: vec![4, 16],
+        },
+      ]
+    );
+    assert!(Ecb::repeated_blocks(b"ABCDEFGH", 4).is_empty());
+  }
+
+  #[test]
+  fn test_detect() -> Result<()> {
+    let block = "00112233445566778899aabbccddeeff";
+    let input = format!("{block}{}\n{block}", "ff".repeat(16));
+
+    let mut output = Vec::new();
+    let config = DetectEcbConfig::new(16, true);
+    Ecb::detect(&mut input.as_bytes(), &mut output, config)?;
+
+    let expected = format!(
+      "{block} repeated 2 times at offsets 0, 32\n\
+       1 of 3 blocks are repetitions, likely ECB\n"
+    );
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_detect_without_repetitions() -> Result<()> {
+    let mut output = Vec::new();
+    let config = DetectEcbConfig::default();
+    Ecb::detect(&mut &[0u8; 20][..], &mut output, config)?;
+    assert_eq!(output, b"no repeated blocks in 1 blocks\n");
+    Ok(())
+  }
+}
@@ -0,0 +1,222 @@
+use std::io::{Error, ErrorKind, Result, Write};
+
+use num_bigint::BigUint;
+use rand::{rngs::OsRng, RngCore};
+
+use crate::prime::Prime;
+
+/// Number of Miller–Rabin rounds for the safe primes.
+const ROUNDS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElgamalPublicKey {
+  /// Safe prime `p = 2q + 1`.
+  pub p: BigUint,
+  /// Generator of `Z_p*`.
+  pub g: BigUint,
+  /// `h = g^x mod p` for the private key `x`.
+  pub h: BigUint,
+}
+
+impl ElgamalPublicKey {
+  pub fn new(p: BigUint, g: BigUint, h: BigUint) -> Self {
+    Self { p, g, h }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElgamalCiphertext {
+  /// `g^k mod p` for a random `k`.
+  pub c1: BigUint,
+  /// `m * h^k mod p`.
+  pub c2: BigUint,
+}
+
+impl ElgamalCiphertext {
+  pub fn new(c1: BigUint, c2: BigUint) -> Self {
+    Self { c1, c2 }
+  }
+
+  /// Componentwise product, which encrypts the product of both
+  /// plaintexts under the sum of both random exponents.
+  pub fn multiply(&self, other: &Self, p: &BigUint) -> Self {
+    Self::new(&self.c1 * &other.c1 % p, &self.c2 * &other.c2 % p)
+  }
+
+  pub fn write<W: Write>(&self, output: &mut W) -> Result<()> {
+    writeln!(output, "c1 = {}", self.c1)?;
+    writeln!(output, "c2 = {}", self.c2)
+  }
+}
+
+/// Textbook ElGamal over the multiplicative group of a prime field.
+pub struct Elgamal;
+
+impl Elgamal {
+  /// A public key over a random safe prime of `bits` bits and its
+  /// private key `x`.
+  pub fn keygen(bits: u64) -> Result<(ElgamalPublicKey, BigUint)> {
+    Self::keygen_with(&mut OsRng, bits)
+  }
+
+  /// A key pair like [`Elgamal::keygen`] drawn from `rng`, e.g. a seeded
+  /// generator for reproducible exercises.
+  pub fn keygen_with<G: RngCore>(
+    rng: &mut G,
+    bits: u64,
+  ) -> Result<(ElgamalPublicKey, BigUint)> {
+    if bits < 4 {
+      let message = "ElGamal needs a prime of at least four bits";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let one = BigUint::from(1u32);
+    let (p, q) = loop {
+      let q = Prime::random_prime_with(rng, bits - 1, ROUNDS)?;
+      let p = &q * 2u32 + &one;
+      if Prime::is_probable_prime_with(rng, &p, ROUNDS) {
+        break (p, q);
+      }
+    };
+
+    let g = Self::generator(rng, &p, &q);
+    let x = Prime::random_below_with(rng, &(&p - 3u32)) + 2u32;
+    let h = g.modpow(&x, &p);
+
+    Ok((ElgamalPublicKey::new(p, g, h), x))
+  }
+
+  /// An element of `Z_p*` of order `p - 1`. Since `p - 1 = 2q`, any
+  /// element whose square and `q`-th power are not one generates it.
+  fn generator<G: RngCore>(rng: &mut G, p: &BigUint, q: &BigUint) -> BigUint {
+    let one = BigUint::from(1u32);
+    loop {
+      let g = Prime::random_below_with(rng, &(p - 3u32)) + 2u32;
+      if g.modpow(&BigUint::from(2u32), p) != one && g.modpow(q, p) != one {
+        return g;
+      }
+    }
+  }
+
+  pub fn encrypt(
+    key: &ElgamalPublicKey,
+    message: &BigUint,
+  ) -> Result<ElgamalCiphertext> {
+    Self::encrypt_with(&mut OsRng, key, message)
+  }
+
+  /// Encrypts like [`Elgamal::encrypt`] with the exponent `k` drawn from
+  /// `rng`.
+  pub fn encrypt_with<G: RngCore>(
+    rng: &mut G,
+    key: &ElgamalPublicKey,
+    message: &BigUint,
+  ) -> Result<ElgamalCiphertext> {
+    if *message == BigUint::ZERO || *message >= key.p {
+      let message = "The message must be in 1..p";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let k = Prime::random_below_with(rng, &(&key.p - 3u32)) + 2u32;
+    let c1 = key.g.modpow(&k, &key.p);
+    let c2 = message * key.h.modpow(&k, &key.p) % &key.p;
+
+    Ok(ElgamalCiphertext::new(c1, c2))
+  }
+
+  /// `m = c2 * (c1^x)^-1 mod p`, where the inverse is `c1^(p - 1 - x)`.
+  pub fn decrypt(
+    p: &BigUint,
+    x: &BigUint,
+    ciphertext: &ElgamalCiphertext,
+  ) -> Result<BigUint> {
+    if *x >= p - 1u32 {
+      let message = "The private key must be below p - 1";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let inverse = ciphertext.c1.modpow(&(p - 1u32 - x), p);
+    Ok(&ciphertext.c2 * inverse % p)
+  }
+
+  /// Encrypts two messages, multiplies the ciphertexts without the key
+  /// and writes all of them together with the decrypted product, which
+  /// equals `m1 * m2 mod p`.
+  pub fn malleability<W: Write>(
+    output: &mut W,
+    key: &ElgamalPublicKey,
+    x: &BigUint,
+    messages: (&BigUint, &BigUint),
+  ) -> Result<()> {
+    let alpha = Self::encrypt(key, messages.0)?;
+    let beta = Self::encrypt(key, messages.1)?;
+    let product = alpha.multiply(&beta, &key.p);
+
+    for (name, ciphertext) in [("m1", &alpha), ("m2", &beta)] {
+      writeln!(output, "Enc({name}):")?;
+      ciphertext.write(output)?;
+    }
+    writeln!(output, "Enc(m1) * Enc(m2):")?;
+    product.write(output)?;
+
+    let decrypted = Self::decrypt(&key.p, x, &product)?;
+    let expected = messages.0 * messages.1 % &key.p;
+    writeln!(output, "Dec(Enc(m1) * Enc(m2)) = {decrypted}")?;
+    writeln!(output, "m1 * m2 mod p = {expected}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::{rngs::StdRng, SeedableRng};
+
+  #[test]
+  fn test_keygen() -> Result<()> {
+    let (key, x) = Elgamal::keygen(32)?;
+    assert_eq!(key.p.bits(), 32);
+    assert!(Prime::is_probable_prime(&((&key.p - 1u32) / 2u32), 20));
+    assert_eq!(key.g.modpow(&x, &key.p), key.h);
+    Ok(())
+  }
+
+  #[test]
+  fn test_roundtrip() -> Result<()> {
+    let (key, x) = Elgamal::keygen(32)?;
+    let message = BigUint::from(123456u32);
+    let ciphertext = Elgamal::encrypt(&key, &message)?;
+    assert_eq!(Elgamal::decrypt(&key.p, &x, &ciphertext)?, message);
+    assert!(Elgamal::encrypt(&key, &key.p).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_seeded_keys_repeat() -> Result<()> {
+    let keygen =
+      |seed| Elgamal::keygen_with(&mut StdRng::seed_from_u64(seed), 32);
+    let (key, x) = keygen(42)?;
+    assert_eq!((key.clone(), x.clone()), keygen(42)?);
+    assert_ne!(key, keygen(43)?.0);
+
+    let message = BigUint::from(123456u32);
+    let encrypt = |seed| {
+      Elgamal::encrypt_with(&mut StdRng::seed_from_u64(seed), &key, &message)
+    };
+    assert_eq!(encrypt(42)?, encrypt(42)?);
+    assert_eq!(Elgamal::decrypt(&key.p, &x, &encrypt(42)?)?, message);
+    Ok(())
+  }
+
+  #[test]
+  fn test_malleability() -> Result<()> {
+    let key = ElgamalPublicKey::new(23u32.into(), 5u32.into(), 8u32.into());
+    let x = BigUint::from(6u32);
+    let (alpha, beta) = (BigUint::from(7u32), BigUint::from(9u32));
+
+    let product = Elgamal::encrypt(&key, &alpha)?
+      .multiply(&Elgamal::encrypt(&key, &beta)?, &key.p);
+    let decrypted = Elgamal::decrypt(&key.p, &x, &product)?;
+    assert_eq!(decrypted, BigUint::from(7u32 * 9 % 23));
+    Ok(())
+  }
+}
@@ -0,0 +1,259 @@
+pub use cryptology_core::base64;
+
+use std::{
+  fmt::Display,
+  io::{Read, Result, Write},
+};
+
+use base64::Base64;
+
+use crate::{hex::Hex, Command};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+  Hex,
+  Base64,
+  Binary,
+  Ascii,
+}
+
+impl Display for Encoding {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      Encoding::Hex => "hex",
+      Encoding::Base64 => "base64",
+      Encoding::Binary => "binary",
+      Encoding::Ascii => "ascii",
+    };
+    write!(f, "{name}")
+  }
+}
+
+/// A possible encoding of the input with a confidence between 0 and 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingGuess {
+  pub encoding: Encoding,
+  pub confidence: f32,
+}
+
+impl Display for EncodingGuess {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}: {:.2}", self.encoding, self.confidence)
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EncodingConfig {
+  pub normalize: bool,
+}
+
+impl EncodingConfig {
+  pub fn new(normalize: bool) -> Self {
+    Self { normalize }
+  }
+}
+
+impl From<&Command> for EncodingConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::DetectEncoding { normalize, .. } => Self::new(*normalize),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct EncodingDetector;
+
+impl EncodingDetector {
+  /// Prints every plausible encoding of the input, most likely first,
+  /// or the bytes decoded with the most likely encoding if `normalize`
+  /// is set.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: EncodingConfig,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let guesses = Self::detect(&content);
+
+    if config.normalize {
+      let encoding = guesses[0].encoding;
+      return output.write_all(&Self::decode(&content, encoding)?);
+    }
+
+    for guess in guesses {
+      writeln!(output, "{guess}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Scores the input as every encoding it could be in.
+  ///
+  /// Structured encodings score higher the more of their alphabet is
+  /// used, since short lowercase words are valid hex or base64 as well.
+  /// The result is sorted by confidence and never empty.
+  pub fn detect(content: &[u8]) -> Vec<EncodingGuess> {
+    let text: Vec<u8> = content
+      .iter()
+      .copied()
+      .filter(|b| !b.is_ascii_whitespace())
+      .collect();
+
+    let printable = content
+      .iter()
+      .filter(|&&b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+      .count();
+    let ratio = match content.len() {
+      0 => 1.0,
+      length => printable as f32 / length as f32,
+    };
+
+    let mut guesses = vec![
+      EncodingGuess {
+        encoding: Encoding::Ascii,
+        confidence: 0.75 * ratio,
+      },
+      EncodingGuess {
+        encoding: Encoding::Binary,
+        confidence: 1.0 - ratio,
+      },
+    ];
+
+    if let Some(confidence) = Self::hex_confidence(&text) {
+      guesses.push(EncodingGuess {
+        encoding: Encoding::Hex,
+        confidence,
+      });
+    }
+
+    if let Some(confidence) = Self::base64_confidence(&text) {
+      guesses.push(EncodingGuess {
+        encoding: Encoding::Base64,
+        confidence,
+      });
+    }
+
+    guesses.retain(|guess| guess.confidence > 0.0);
+    guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    guesses
+  }
+
+  pub fn decode(content: &[u8], encoding: Encoding) -> Result<Vec<u8>> {
+    let text = || -> String {
+      String::from_utf8_lossy(content)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+    };
+
+    match encoding {
+      Encoding::Hex => Ok(Hex::parse_hex(&text())?.bytes),
+      Encoding::Base64 => Base64::decode(&text()),
+      Encoding::Binary | Encoding::Ascii => Ok(content.to_vec()),
+    }
+  }
+
+  fn hex_confidence(text: &[u8]) -> Option<f32> {
+    if text.is_empty()
+      || text.len() % 2 != 0
+      || !text.iter().all(u8::is_ascii_hexdigit)
+    {
+      return None;
+    }
+
+    let digits = text.iter().any(u8::is_ascii_digit);
+    let letters = text.iter().any(u8::is_ascii_alphabetic);
+    let lowercase = text.iter().any(u8::is_ascii_lowercase);
+    let uppercase = text.iter().any(u8::is_ascii_uppercase);
+
+    let confidence = match (digits && letters, lowercase && uppercase) {
+      (true, false) => 0.95,
+      (true, true) => 0.7,
+      (false, _) => 0.6,
+    };
+
+    Some(confidence)
+  }
+
+  fn base64_confidence(text: &[u8]) -> Option<f32> {
+    let data = text.strip_suffix(b"==").or_else(|| text.strip_suffix(b"="));
+    let padded = data.is_some();
+    let data = data.unwrap_or(text);
+
+    if data.is_empty()
+      || !data.iter().all(|&c| Base64::is_base64_char(c))
+      || (padded && text.len() % 4 != 0)
+      || data.len() % 4 == 1
+    {
+      return None;
+    }
+
+    let classes = [
+      data.iter().any(u8::is_ascii_uppercase),
+      data.iter().any(u8::is_ascii_lowercase),
+      data.iter().any(u8::is_ascii_digit),
+    ];
+    let classes = classes.iter().filter(|&&class| class).count() as f32;
+    let padding = if padded || data.len() % 4 == 0 {
+      0.1
+    } else {
+      0.0
+    };
+
+    Some((0.4 + 0.15 * classes + padding).min(0.95))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn best(content: &[u8]) -> Encoding {
+    EncodingDetector::detect(content)[0].encoding
+  }
+
+  #[test]
+  fn test_detect_hex() {
+    assert_eq!(best(b"48656c6c6f2c20576f726c6421\n"), Encoding::Hex);
+  }
+
+  #[test]
+  fn test_detect_base64() {
+    assert_eq!(best(b"SGVsbG8sIFdvcmxkIQ==\n"), Encoding::Base64);
+  }
+
+  #[test]
+  fn test_detect_ascii() {
+    assert_eq!(best(b"Hello, World!\n"), Encoding::Ascii);
+    assert_eq!(best(b"test"), Encoding::Ascii);
+  }
+
+  #[test]
+  fn test_detect_binary() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(best(&bytes), Encoding::Binary);
+  }
+
+  #[test]
+  fn test_report() -> Result<()> {
+    let mut output = Vec::new();
+    let config = EncodingConfig::default();
+    EncodingDetector::execute(&mut "cafe".as_bytes(), &mut output, config)?;
+    let report = String::from_utf8(output).unwrap();
+    assert_eq!(report, "ascii: 0.75\nbase64: 0.65\nhex: 0.60\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_normalize() -> Result<()> {
+    let mut output = Vec::new();
+    let config = EncodingConfig::new(true);
+    let mut input = "SGVsbG8sIFdvcmxkIQ==".as_bytes();
+    EncodingDetector::execute(&mut input, &mut output, config)?;
+    assert_eq!(output, b"Hello, World!");
+    Ok(())
+  }
+}
@@ -1,5 +0,0 @@
-pub mod english;
-
-use std::collections::BTreeMap;
-
-pub type Frequency = BTreeMap<char, usize>;
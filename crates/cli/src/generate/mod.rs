@@ -0,0 +1,138 @@
+use std::fs;
+use std::io::{Result, Write};
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+use clap_mangen::Man;
+
+use crate::{Command, Cryptology};
+
+/// Name of the binary that the completions and pages are written for.
+const BIN_NAME: &str = "cryptology";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CompletionsConfig {
+  pub shell: Option<Shell>,
+}
+
+impl CompletionsConfig {
+  pub fn new(shell: Shell) -> Self {
+    Self { shell: Some(shell) }
+  }
+}
+
+impl From<&Command> for CompletionsConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Completions { shell, .. } => Self::new(*shell),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Completions;
+
+impl Completions {
+  /// Writes the completion script of the configured shell, or of the
+  /// shell in `$SHELL` if none is configured.
+  pub fn execute<W: Write>(
+    output: &mut W,
+    config: CompletionsConfig,
+  ) -> Result<()> {
+    let shell = config.shell.or_else(Shell::from_env).ok_or_else(|| {
+      let message = "Failed to detect the shell, pass it explicitly";
+      std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+    })?;
+    // The generators panic on write errors, so the script is buffered
+    // to report a closed pipe as an error instead.
+    let mut script = Vec::new();
+    let mut command = Cryptology::command();
+    clap_complete::generate(shell, &mut command, BIN_NAME, &mut script);
+    output.write_all(&script)
+  }
+}
+
+pub struct Manpages;
+
+impl Manpages {
+  /// Writes a man page for the tool and one for every subcommand into
+  /// `dir`, named like `cryptology-decrypt-caesar.1`, and lists the
+  /// written files.
+  pub fn execute<W: Write>(output: &mut W, dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut command = Cryptology::command();
+    command.build();
+    for path in Self::write_pages(dir, command.name(BIN_NAME))? {
+      writeln!(output, "{}", path.display())?;
+    }
+    Ok(())
+  }
+
+  /// Renders the page of `command` and of its subcommands. The name of
+  /// a subcommand is prefixed with the names of its parents, as `man`
+  /// expects.
+  pub fn render(command: clap::Command) -> Result<Vec<(String, Vec<u8>)>> {
+    let name = command.get_name().to_string();
+    let mut page = Vec::new();
+    Man::new(command.clone()).render(&mut page)?;
+
+    let mut pages = vec![(name.clone(), page)];
+    for subcommand in command.get_subcommands() {
+      if subcommand.is_hide_set() || subcommand.get_name() == "help" {
+        continue;
+      }
+      let full_name = format!("{name}-{}", subcommand.get_name());
+      pages.extend(Self::render(subcommand.clone().name(full_name))?);
+    }
+    Ok(pages)
+  }
+
+  fn write_pages(dir: &Path, command: clap::Command) -> Result<Vec<PathBuf>> {
+    Self::render(command)?
+      .into_iter()
+      .map(|(name, page)| {
+        let path = dir.join(format!("{name}.1"));
+        fs::write(&path, page)?;
+        Ok(path)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::with_command_stack;
+
+  #[test]
+  fn test_completions() -> Result<()> {
+    let mut output = Vec::new();
+    let config = CompletionsConfig::new(Shell::Bash);
+    with_command_stack(|| Completions::execute(&mut output, config))?;
+    let script = String::from_utf8_lossy(&output);
+    assert!(script.contains("_cryptology()"));
+    assert!(script.contains("--output-format"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_render_manpages() -> Result<()> {
+    let pages = with_command_stack(|| {
+      let mut command = Cryptology::command();
+      command.build();
+      Manpages::render(command.name(BIN_NAME))
+    })?;
+    let names: Vec<&str> =
+      pages.iter().map(|(name, _)| name.as_str()).collect();
+
+    assert_eq!(names[0], "cryptology");
+    assert!(names.contains(&"cryptology-decrypt-caesar"));
+    assert!(!names.iter().any(|name| name.ends_with("-help")));
+
+    let page = String::from_utf8_lossy(&pages[0].1);
+    assert!(page.starts_with(".ie"));
+    assert!(page.contains("cryptology"));
+    Ok(())
+  }
+}
@@ -0,0 +1,199 @@
+use std::{
+  fs,
+  io::{Error, ErrorKind, Read, Result, Write},
+  path::PathBuf,
+};
+
+use crate::{encoding::base64::Base64, hex::Hex, Command};
+
+use super::{DigestFormat, HashAlgorithm};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HmacConfig {
+  pub algorithm: HashAlgorithm,
+  pub format: DigestFormat,
+  pub key: Option<String>,
+  /// File whose bytes are the key, used if no key is given.
+  pub key_file: Option<PathBuf>,
+  /// Expected HMAC in the configured format.
+  pub verify: Option<String>,
+}
+
+impl HmacConfig {
+  pub fn new(
+    algorithm: HashAlgorithm,
+    format: DigestFormat,
+    key: Option<String>,
+    key_file: Option<PathBuf>,
+    verify: Option<String>,
+  ) -> Self {
+    Self {
+      algorithm,
+      format,
+      key,
+      key_file,
+      verify,
+    }
+  }
+
+  fn key(&self) -> Result<Vec<u8>> {
+    match (&self.key, &self.key_file) {
+      (Some(key), _) => Ok(key.as_bytes().to_vec()),
+      (None, Some(path)) => fs::read(path),
+      (None, None) => {
+        let message = "An HMAC requires a key or a key file";
+        Err(Error::new(ErrorKind::InvalidInput, message))
+      }
+    }
+  }
+}
+
+impl From<&Command> for HmacConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Hmac {
+        algorithm,
+        format,
+        key,
+        key_file,
+        verify,
+        ..
+      } => Self::new(
+        *algorithm,
+        *format,
+        key.clone(),
+        key_file.clone(),
+        verify.clone(),
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// Keyed-hash message authentication code as specified in RFC 2104.
+pub struct Hmac;
+
+impl Hmac {
+  /// Writes the HMAC of the input, or checks it against the expected
+  /// one and fails if they differ.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: HmacConfig,
+  ) -> Result<()> {
+    let mut message = Vec::new();
+    input.read_to_end(&mut message)?;
+
+    let mac = Self::compute(config.algorithm, &config.key()?, &message);
+
+    let Some(expected) = &config.verify else {
+      return writeln!(output, "{}", config.format.format(&mac));
+    };
+
+    let expected = match config.format {
+      DigestFormat::Hex => Hex::parse_hex(expected.trim())?.bytes,
+      DigestFormat::Base64 => Base64::decode(expected)?,
+    };
+
+    if !Self::equal(&mac, &expected) {
+      let message = "HMAC verification failed";
+      return Err(Error::new(ErrorKind::InvalidData, message));
+    }
+
+    writeln!(output, "HMAC verified")
+  }
+
+  /// `H((K ^ opad) || H((K ^ ipad) || message))` with the key padded to
+  /// the block size of the hash, or hashed first if it is longer.
+  pub fn compute(
+    algorithm: HashAlgorithm,
+    key: &[u8],
+    message: &[u8],
+  ) -> Vec<u8> {
+    let block_size = algorithm.block_size();
+
+    let mut key = match key.len() > block_size {
+      true => algorithm.digest(key),
+      false => key.to_vec(),
+    };
+    key.resize(block_size, 0);
+
+    let mut inner: Vec<u8> = key.iter().map(|byte| byte ^ 0x36).collect();
+    inner.extend_from_slice(message);
+
+    let mut outer: Vec<u8> = key.iter().map(|byte| byte ^ 0x5c).collect();
+    outer.extend(algorithm.digest(&inner));
+
+    algorithm.digest(&outer)
+  }
+
+  /// Compares in time independent of the position of the first
+  /// difference.
+  fn equal(alpha: &[u8], beta: &[u8]) -> bool {
+    alpha.len() == beta.len()
+      && alpha
+        .iter()
+        .zip(beta)
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const FOX: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+  fn hex(algorithm: HashAlgorithm, key: &[u8], message: &[u8]) -> String {
+    Hex::new(Hmac::compute(algorithm, key, message)).to_string()
+  }
+
+  #[test]
+  fn test_compute() {
+    assert_eq!(
+      hex(HashAlgorithm::Md5, b"key", FOX),
+      "80070713463e7749b90c2dc24911e275"
+    );
+    assert_eq!(
+      hex(HashAlgorithm::Sha1, b"key", FOX),
+      "de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+    );
+    assert_eq!(
+      hex(HashAlgorithm::Sha256, b"key", FOX),
+      "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+    );
+  }
+
+  #[test]
+  fn test_long_key() {
+    let key = [0xaa; 131];
+    let message = b"Test Using Larger Than Block-Size Key - Hash Key First";
+    assert_eq!(
+      hex(HashAlgorithm::Sha256, &key, message),
+      "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+  }
+
+  #[test]
+  fn test_verify() -> Result<()> {
+    let config = |verify: &str| {
+      HmacConfig::new(
+        HashAlgorithm::Md5,
+        DigestFormat::Hex,
+        Some(String::from("key")),
+        None,
+        Some(verify.to_string()),
+      )
+    };
+
+    let mut output = Vec::new();
+    let valid = config("80070713463e7749b90c2dc24911e275");
+    Hmac::execute(&mut &FOX[..], &mut output, valid)?;
+    assert_eq!(output, b"HMAC verified\n");
+
+    let invalid = config("00070713463e7749b90c2dc24911e275");
+    assert!(Hmac::execute(&mut &FOX[..], &mut Vec::new(), invalid).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,187 @@
+pub mod hmac;
+pub mod md5;
+pub mod sha1;
+pub mod sha256;
+
+use std::{
+  fs,
+  io::{Read, Result, Write},
+  path::PathBuf,
+};
+
+use clap::ValueEnum;
+
+use crate::{encoding::base64::Base64, hex::Hex, Command};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HashAlgorithm {
+  Md5,
+  Sha1,
+  #[default]
+  Sha256,
+}
+
+impl HashAlgorithm {
+  pub fn digest(&self, message: &[u8]) -> Vec<u8> {
+    match self {
+      HashAlgorithm::Md5 => md5::digest(message),
+      HashAlgorithm::Sha1 => sha1::digest(message),
+      HashAlgorithm::Sha256 => sha256::digest(message),
+    }
+  }
+
+  /// Size of the blocks the compression function operates on in bytes.
+  pub fn block_size(&self) -> usize {
+    64
+  }
+}
+
+/// Representation of a digest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DigestFormat {
+  #[default]
+  Hex,
+  Base64,
+}
+
+impl DigestFormat {
+  pub fn format(&self, digest: &[u8]) -> String {
+    match self {
+      DigestFormat::Hex => Hex::new(digest.to_vec()).to_string(),
+      DigestFormat::Base64 => Base64::encode(digest),
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HashConfig {
+  pub algorithm: HashAlgorithm,
+  pub format: DigestFormat,
+  /// Files that are hashed instead of the input, one line each.
+  pub files: Vec<PathBuf>,
+}
+
+impl HashConfig {
+  pub fn new(
+    algorithm: HashAlgorithm,
+    format: DigestFormat,
+    files: Vec<PathBuf>,
+  ) -> Self {
+    Self {
+      algorithm,
+      format,
+      files,
+    }
+  }
+}
+
+impl From<&Command> for HashConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Hash {
+        algorithm,
+        format,
+        files,
+        ..
+      } => Self::new(*algorithm, *format, files.clone()),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Hash;
+
+impl Hash {
+  /// Writes the digest of the input, or the digest and path of every
+  /// file in the same layout as `sha256sum`.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: HashConfig,
+  ) -> Result<()> {
+    if config.files.is_empty() {
+      let mut message = Vec::new();
+      input.read_to_end(&mut message)?;
+      let digest = config.algorithm.digest(&message);
+      return writeln!(output, "{}", config.format.format(&digest));
+    }
+
+    for path in &config.files {
+      let digest = config.algorithm.digest(&fs::read(path)?);
+      let digest = config.format.format(&digest);
+      writeln!(output, "{digest}  {}", path.display())?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Pads a message to a multiple of 64 bytes with `0x80`, zeros and the
+/// length of the message in bits as a 64-bit number.
+fn pad(message: &[u8], big_endian: bool) -> Vec<u8> {
+  let bits = (message.len() as u64).wrapping_mul(8);
+  let mut padded = message.to_vec();
+  padded.push(0x80);
+
+  while padded.len() % 64 != 56 {
+    padded.push(0);
+  }
+
+  match big_endian {
+    true => padded.extend(bits.to_be_bytes()),
+    false => padded.extend(bits.to_le_bytes()),
+  }
+  padded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(algorithm: HashAlgorithm, message: &[u8]) -> String {
+    DigestFormat::Hex.format(&algorithm.digest(message))
+  }
+
+  #[test]
+  fn test_md5() {
+    let md5 = HashAlgorithm::Md5;
+    assert_eq!(hex(md5, b""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(
+      hex(md5, b"The quick brown fox jumps over the lazy dog"),
+      "9e107d9d372bb6826bd81d3542a419d6"
+    );
+  }
+
+  #[test]
+  fn test_sha1() {
+    let sha1 = HashAlgorithm::Sha1;
+    assert_eq!(hex(sha1, b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(
+      hex(sha1, b"The quick brown fox jumps over the lazy dog"),
+      "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12"
+    );
+  }
+
+  #[test]
+  fn test_sha256() {
+    let sha256 = HashAlgorithm::Sha256;
+    assert_eq!(
+      hex(sha256, b"abc"),
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+      hex(sha256, &[b'a'; 1000]),
+      "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+    );
+  }
+
+  #[test]
+  fn test_execute_base64() -> Result<()> {
+    let mut output = Vec::new();
+    let config =
+      HashConfig::new(HashAlgorithm::Md5, DigestFormat::Base64, Vec::new());
+    Hash::execute(&mut &b""[..], &mut output, config)?;
+    assert_eq!(output, b"1B2M2Y8AsgTpgAmY7PhCfg==\n");
+    Ok(())
+  }
+}
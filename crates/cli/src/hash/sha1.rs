@@ -0,0 +1,42 @@
+/// SHA-1 as specified in FIPS 180-4.
+pub fn digest(message: &[u8]) -> Vec<u8> {
+  let mut state: [u32; 5] =
+    [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+  for block in super::pad(message, true).chunks_exact(64) {
+    let mut words = [0u32; 80];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+      *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    for i in 16..80 {
+      words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16])
+        .rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = state;
+
+    for (i, word) in words.iter().enumerate() {
+      let (f, k) = match i / 20 {
+        0 => ((b & c) | (!b & d), 0x5a827999),
+        1 => (b ^ c ^ d, 0x6ed9eba1),
+        2 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+        _ => (b ^ c ^ d, 0xca62c1d6),
+      };
+
+      let temp = a
+        .rotate_left(5)
+        .wrapping_add(f)
+        .wrapping_add(e)
+        .wrapping_add(k)
+        .wrapping_add(*word);
+
+      (a, b, c, d, e) = (temp, a, b.rotate_left(30), c, d);
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+      *word = word.wrapping_add(value);
+    }
+  }
+
+  state.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
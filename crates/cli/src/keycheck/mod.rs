@@ -0,0 +1,288 @@
+use std::io::{Read, Result, Write};
+use std::ops::Range;
+
+use crate::ecb::{Ecb, RepeatedBlock};
+use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::hex::Hex;
+use crate::xor::repeating_key::KeyFormat;
+use crate::Command;
+
+/// Fewest bytes for which the byte counts say anything about a bias.
+const MIN_BIAS_BYTES: usize = 256;
+
+/// Share of the most entropy a window can have below which it counts as
+/// low entropy. Random windows of 64 bytes have about 95% of the 6 bits
+/// they can reach, English text about 70%.
+const LOW_ENTROPY_SHARE: f32 = 0.8;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct KeyCheckConfig {
+  pub key_format: KeyFormat,
+  /// Size of the blocks that are checked for repetitions.
+  pub block_size: usize,
+  /// Size of the windows whose entropy is measured.
+  pub window: usize,
+}
+
+impl KeyCheckConfig {
+  pub fn new(key_format: KeyFormat, block_size: usize, window: usize) -> Self {
+    Self {
+      key_format,
+      block_size,
+      window,
+    }
+  }
+}
+
+impl Default for KeyCheckConfig {
+  fn default() -> Self {
+    Self::new(KeyFormat::Ascii, 16, 64)
+  }
+}
+
+impl From<&Command> for KeyCheckConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::KeyCheck {
+        key_format,
+        block_size,
+        window,
+        ..
+      } => Self::new(key_format.key_format(), *block_size, *window),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The statistics of a key that tell whether it is as random as a pad
+/// has to be.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyReport {
+  pub bytes: usize,
+  /// Bits per byte, see [`FrequencyAnalyzer::entropy`].
+  pub entropy: f32,
+  /// Chi-square distance of the byte counts to a uniform distribution.
+  /// Uniformly random bytes average 255 with a deviation of about 23.
+  pub chi_square: f32,
+  /// The most frequent byte and its share of all bytes.
+  pub most_common: (u8, f32),
+  /// Share of printable ASCII and whitespace bytes, about 39% for
+  /// random bytes.
+  pub printable: f32,
+  pub repeated: Vec<RepeatedBlock>,
+  /// Byte ranges of adjacent windows with low entropy and the lowest
+  /// entropy among them.
+  pub low_entropy: Vec<(Range<usize>, f32)>,
+  /// [`FrequencyAnalyzer::confidence`] that the key is English.
+  pub english: Option<f32>,
+}
+
+impl KeyReport {
+  /// The weaknesses of the key, empty if it looks random.
+  pub fn warnings(&self) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if self.printable == 1.0 {
+      let warning = match self.english {
+        Some(confidence) if confidence >= 0.5 => {
+          "the key is printable ASCII and reads like English, it is text \
+           rather than a random pad"
+        }
+        _ => "the key is printable ASCII, random bytes would not be",
+      };
+      warnings.push(warning.to_string());
+    }
+
+    // Four deviations above the average of uniformly random bytes.
+    let degrees = 255.0f32;
+    let limit = degrees + 4.0 * (2.0 * degrees).sqrt();
+    if self.bytes >= MIN_BIAS_BYTES && self.chi_square > limit {
+      let (byte, share) = self.most_common;
+      warnings.push(format!(
+        "the bytes are biased, chi-square {:.1} above {limit:.1}, byte \
+         {byte:02x} makes up {:.1}%",
+        self.chi_square,
+        share * 100.0
+      ));
+    }
+
+    for RepeatedBlock { block, offsets } in &self.repeated {
+      let offsets: Vec<String> = offsets.iter().map(usize::to_string).collect();
+      warnings.push(format!(
+        "the block {} repeats at offsets {}",
+        Hex::new(block.clone()),
+        offsets.join(", ")
+      ));
+    }
+
+    for (range, entropy) in &self.low_entropy {
+      warnings.push(format!(
+        "low entropy of {entropy:.2} bits per byte at bytes {}..{}",
+        range.start, range.end
+      ));
+    }
+
+    warnings
+  }
+}
+
+pub struct KeyCheck;
+
+impl KeyCheck {
+  /// Reads a key or pad and writes its statistics, followed by a warning
+  /// for every weakness that makes it unfit as a one-time pad.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: KeyCheckConfig,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let key = match config.key_format {
+      KeyFormat::Ascii => content,
+      format => {
+        let text = String::from_utf8_lossy(&content);
+        format.parse(&text.split_whitespace().collect::<String>())?
+      }
+    };
+
+    let report = Self::analyze(&key, &config);
+    let (byte, share) = report.most_common;
+    writeln!(output, "bytes: {}", report.bytes)?;
+    writeln!(output, "entropy: {:.2} bits per byte", report.entropy)?;
+    writeln!(output, "chi-square: {:.1}", report.chi_square)?;
+    writeln!(output, "most common: {byte:02x} ({:.1}%)", share * 100.0)?;
+    writeln!(output, "printable: {:.1}%", report.printable * 100.0)?;
+
+    let warnings = report.warnings();
+    if warnings.is_empty() {
+      writeln!(output, "no weaknesses found")?;
+    }
+    for warning in warnings {
+      writeln!(output, "warning: {warning}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn analyze(key: &[u8], config: &KeyCheckConfig) -> KeyReport {
+    let mut counts = [0usize; 256];
+    for &byte in key {
+      counts[byte as usize] += 1;
+    }
+
+    let total = key.len().max(1) as f32;
+    let expected = total / 256.0;
+    let chi_square = counts
+      .iter()
+      .map(|&count| (count as f32 - expected).powi(2) / expected)
+      .sum();
+
+    let (byte, count) = counts
+      .iter()
+      .enumerate()
+      .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+      .unwrap_or((0, &0));
+
+    let printable = key
+      .iter()
+      .filter(|c| c.is_ascii_graphic() || c.is_ascii_whitespace())
+      .count();
+
+    KeyReport {
+      bytes: key.len(),
+      entropy: FrequencyAnalyzer::entropy(key),
+      chi_square,
+      most_common: (byte as u8, *count as f32 / total),
+      printable: printable as f32 / total,
+      repeated: match config.block_size {
+        0 => Vec::new(),
+        size => Ecb::repeated_blocks(key, size),
+      },
+      low_entropy: Self::low_entropy(key, config.window),
+      english: FrequencyAnalyzer::confidence(&String::from_utf8_lossy(key)),
+    }
+  }
+
+  /// Ranges of adjacent windows whose entropy is below
+  /// [`LOW_ENTROPY_SHARE`] of the most they could have. A trailing
+  /// partial window is ignored.
+  fn low_entropy(key: &[u8], window: usize) -> Vec<(Range<usize>, f32)> {
+    if window < 2 {
+      return Vec::new();
+    }
+
+    let limit = LOW_ENTROPY_SHARE * (window.min(256) as f32).log2();
+    let mut regions: Vec<(Range<usize>, f32)> = Vec::new();
+    for (index, chunk) in key.chunks_exact(window).enumerate() {
+      let entropy = FrequencyAnalyzer::entropy(chunk);
+      if entropy >= limit {
+        continue;
+      }
+      let start = index * window;
+      match regions.last_mut() {
+        Some((range, lowest)) if range.end == start => {
+          range.end = start + window;
+          *lowest = lowest.min(entropy);
+        }
+        _ => regions.push((start..start + window, entropy)),
+      }
+    }
+    regions
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Bytes of a xorshift generator, which are close enough to random
+  /// for the checks.
+  fn random(length: usize) -> Vec<u8> {
+    let mut state = 0x2545f4914f6cdd1du64;
+    (0..length)
+      .map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 32) as u8
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_random_key() {
+    let report = KeyCheck::analyze(&random(4096), &KeyCheckConfig::default());
+    assert!(report.entropy > 7.9);
+    assert!(report.warnings().is_empty(), "{:?}", report.warnings());
+  }
+
+  #[test]
+  fn test_text_key() {
+    let text = "it was a bright cold day in april and the clocks were \
+      striking thirteen winston smith his chin nuzzled into his breast in \
+      an effort to escape the vile wind slipped quickly through the glass \
+      doors of victory mansions though not quickly enough to prevent a \
+      swirl of gritty dust from entering along with him";
+    let report = KeyCheck::analyze(text.as_bytes(), &KeyCheckConfig::default());
+    let warnings = report.warnings();
+    assert!(warnings[0].contains("reads like English"));
+    assert!(warnings.iter().any(|warning| warning.contains("biased")));
+    assert!(warnings
+      .iter()
+      .any(|warning| warning.contains("low entropy")));
+  }
+
+  #[test]
+  fn test_repeated_and_low_entropy_regions() {
+    let mut key = random(256);
+    key.extend([0u8; 128]);
+    key.extend_from_within(..32);
+
+    let report = KeyCheck::analyze(&key, &KeyCheckConfig::default());
+    assert_eq!(report.low_entropy, [(256..384, 0.0)]);
+    assert_eq!(report.repeated.len(), 3);
+    assert_eq!(report.repeated[0].offsets, [0, 384]);
+  }
+}
@@ -0,0 +1,156 @@
+use std::io::{Result, Write};
+
+use clap::ValueEnum;
+use rand::{rngs::OsRng, rngs::StdRng, Rng, RngCore, SeedableRng};
+
+use crate::{encoding::base64::Base64, hex::Hex, Command};
+
+/// Representation of a generated key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum KeygenFormat {
+  #[default]
+  Hex,
+  Base64,
+  /// Random printable ASCII characters instead of random bytes.
+  Ascii,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeygenConfig {
+  pub length: usize,
+  pub format: KeygenFormat,
+  /// Seed for reproducible keys, which are then no longer secret.
+  pub seed: Option<u64>,
+}
+
+impl KeygenConfig {
+  pub fn new(length: usize, format: KeygenFormat) -> Self {
+    Self {
+      length,
+      format,
+      seed: None,
+    }
+  }
+}
+
+impl From<&Command> for KeygenConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Keygen {
+        length,
+        format,
+        seed,
+        ..
+      } => Self {
+        seed: *seed,
+        ..Self::new(*length, *format)
+      },
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Keygen;
+
+impl Keygen {
+  /// Writes a random key of `length` bytes in the configured format.
+  pub fn execute<W: Write>(output: &mut W, config: KeygenConfig) -> Result<()> {
+    let (length, format) = (config.length, config.format);
+    let key = match config.seed {
+      Some(seed) => {
+        Self::generate_with(&mut StdRng::seed_from_u64(seed), length, format)
+      }
+      None => Self::generate(length, format),
+    };
+    writeln!(output, "{key}")
+  }
+
+  /// A random key drawn from the random number generator of the
+  /// operating system, which is suitable for one-time pads.
+  pub fn generate(length: usize, format: KeygenFormat) -> String {
+    Self::generate_with(&mut OsRng, length, format)
+  }
+
+  /// A random key drawn from `rng`, e.g. a seeded generator for
+  /// reproducible exercises.
+  pub fn generate_with<G: RngCore>(
+    rng: &mut G,
+    length: usize,
+    format: KeygenFormat,
+  ) -> String {
+    match format {
+      KeygenFormat::Hex => Hex::new(Self::bytes(rng, length)).to_string(),
+      KeygenFormat::Base64 => Base64::encode(&Self::bytes(rng, length)),
+      KeygenFormat::Ascii => (0..length)
+        .map(|_| rng.gen_range(b'!'..=b'~') as char)
+        .collect(),
+    }
+  }
+
+  pub fn random_bytes(length: usize) -> Vec<u8> {
+    Self::bytes(&mut OsRng, length)
+  }
+
+  fn bytes<G: RngCore>(rng: &mut G, length: usize) -> Vec<u8> {
+    let mut bytes = vec![0; length];
+    rng.fill_bytes(&mut bytes);
+    bytes
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_generate_formats() -> Result<()> {
+    let hex = Keygen::generate(16, KeygenFormat::Hex);
+    assert_eq!(Hex::parse_hex(&hex)?.bytes.len(), 16);
+
+    let base64 = Keygen::generate(16, KeygenFormat::Base64);
+    assert_eq!(Base64::decode(&base64)?.len(), 16);
+
+    let ascii = Keygen::generate(16, KeygenFormat::Ascii);
+    assert_eq!(ascii.len(), 16);
+    assert!(ascii.bytes().all(|b| b.is_ascii_graphic()));
+    Ok(())
+  }
+
+  #[test]
+  fn test_keys_differ() {
+    assert_ne!(Keygen::random_bytes(32), Keygen::random_bytes(32));
+    assert!(Keygen::random_bytes(0).is_empty());
+  }
+
+  #[test]
+  fn test_seeded_keys_repeat() -> Result<()> {
+    let config = || KeygenConfig {
+      seed: Some(42),
+      ..KeygenConfig::new(16, KeygenFormat::Hex)
+    };
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    Keygen::execute(&mut first, config())?;
+    Keygen::execute(&mut second, config())?;
+    assert_eq!(first, second);
+
+    let mut other = Vec::new();
+    Keygen::execute(
+      &mut other,
+      KeygenConfig {
+        seed: Some(43),
+        ..config()
+      },
+    )?;
+    assert_ne!(first, other);
+    Ok(())
+  }
+
+  #[test]
+  fn test_execute() -> Result<()> {
+    let mut output = Vec::new();
+    Keygen::execute(&mut output, KeygenConfig::new(4, KeygenFormat::Hex))?;
+    assert_eq!(output.len(), 9);
+    assert_eq!(output.last(), Some(&b'\n'));
+    Ok(())
+  }
+}
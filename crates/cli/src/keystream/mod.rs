@@ -0,0 +1,199 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  error::CryptologyError, hex::Hex, xor::repeating_key::KeyFormat, Command,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeystreamConfig {
+  /// Index of the ciphertext whose plaintext is partly known.
+  pub target: usize,
+  pub known: String,
+  pub known_format: KeyFormat,
+  /// Position of the known plaintext in the target.
+  pub offset: usize,
+  /// Positions of the other ciphertexts the keystream is applied at,
+  /// the offset of the known plaintext if empty.
+  pub apply_offsets: Vec<usize>,
+}
+
+impl KeystreamConfig {
+  pub fn new(
+    target: usize,
+    known: String,
+    known_format: KeyFormat,
+    offset: usize,
+    apply_offsets: Vec<usize>,
+  ) -> Self {
+    Self {
+      target,
+      known,
+      known_format,
+      offset,
+      apply_offsets,
+    }
+  }
+}
+
+impl From<&Command> for KeystreamConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Keystream {
+        target,
+        known,
+        known_format,
+        offset,
+        apply_offset,
+        ..
+      } => Self::new(
+        *target,
+        known.clone(),
+        *known_format,
+        *offset,
+        apply_offset.clone(),
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Keystream;
+
+impl Keystream {
+  /// Reads hex ciphertexts, one per line, recovers the keystream under
+  /// the known plaintext of the target and writes it as hex, followed by
+  /// the decryption of every other ciphertext at every apply offset.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: KeystreamConfig,
+  ) -> Result<()> {
+    let ciphertexts = Self::read_ciphertexts(input)?;
+    let Some(target) = ciphertexts.get(config.target) else {
+      let message = format!(
+        "There is no ciphertext {}, only {} were read",
+        config.target,
+        ciphertexts.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    };
+
+    let known = config.known_format.parse(&config.known)?;
+    let keystream = Self::recover(target, &known, config.offset)?;
+    writeln!(
+      output,
+      "keystream at {}: {}",
+      config.offset,
+      Hex::new(keystream.clone())
+    )?;
+
+    let offsets = match config.apply_offsets.is_empty() {
+      true => vec![config.offset],
+      false => config.apply_offsets,
+    };
+    for (index, ciphertext) in ciphertexts.iter().enumerate() {
+      if index == config.target {
+        continue;
+      }
+      for &offset in &offsets {
+        let plaintext = Self::apply(ciphertext, &keystream, offset);
+        writeln!(
+          output,
+          "{index} at {offset}: {}",
+          Hex::new(plaintext).to_ascii()
+        )?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The keystream bytes under the known plaintext, which starts at
+  /// `offset` of the ciphertext.
+  pub fn recover(
+    ciphertext: &[u8],
+    known: &[u8],
+    offset: usize,
+  ) -> Result<Vec<u8>> {
+    let Some(segment) = ciphertext.get(offset..offset + known.len()) else {
+      let message = format!(
+        "The known plaintext of {} bytes at offset {offset} reaches past the \
+         ciphertext of {} bytes",
+        known.len(),
+        ciphertext.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    };
+
+    Ok(segment.iter().zip(known).map(|(c, p)| c ^ p).collect())
+  }
+
+  /// The plaintext of the ciphertext bytes from `offset` on that the
+  /// keystream covers. It is cut short where the ciphertext ends.
+  pub fn apply(ciphertext: &[u8], keystream: &[u8], offset: usize) -> Vec<u8> {
+    ciphertext
+      .iter()
+      .skip(offset)
+      .zip(keystream)
+      .map(|(c, k)| c ^ k)
+      .collect()
+  }
+
+  fn read_ciphertexts<R: Read>(input: &mut R) -> Result<Vec<Vec<u8>>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let ciphertexts = content
+      .lines()
+      .enumerate()
+      .map(|(index, line)| (index + 1, line.trim()))
+      .filter(|(_, line)| !line.is_empty())
+      .map(|(number, line)| match Hex::parse_hex(line) {
+        Ok(hex) => Ok(hex.bytes),
+        Err(error) => Err(CryptologyError::from(error).at_line(number).into()),
+      })
+      .collect::<Result<Vec<Vec<u8>>>>()?;
+
+    Ok(ciphertexts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  fn encrypt(plaintext: &[u8], pad: &[u8]) -> String {
+    let bytes = plaintext.iter().zip(pad).map(|(p, k)| p ^ k).collect();
+    Hex::new(bytes).to_string()
+  }
+
+  #[test]
+  fn test_recover_and_apply() -> Result<()> {
+    let pad: Vec<u8> = (0..32).map(|i| (i * 37 + 11) as u8).collect();
+    let alpha = encrypt(b"the attack starts at noon", &pad);
+    let beta = encrypt(b"retreat behind the river", &pad);
+    let input = format!("{alpha}\n{beta}\n");
+
+    let config =
+      KeystreamConfig::new(0, "attack".into(), KeyFormat::Ascii, 4, vec![]);
+    let mut output = Vec::new();
+    Keystream::execute(&mut Cursor::new(input), &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(
+      output,
+      format!(
+        "keystream at 4: {}\n1 at 4: eat be\n",
+        Hex::new(pad[4..10].to_vec())
+      )
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_offsets_past_the_end() {
+    assert!(Keystream::recover(b"abc", b"abcd", 0).is_err());
+    assert_eq!(Keystream::apply(b"abc", &[0, 0, 0], 2), b"c");
+  }
+}
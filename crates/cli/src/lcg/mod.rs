@@ -0,0 +1,239 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::{prime::factor::gcd, LcgCommand};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LcgConfig {
+  pub modulus: Option<u64>,
+  pub multiplier: u64,
+  pub increment: u64,
+  pub seed: u64,
+  pub count: usize,
+}
+
+impl From<&LcgCommand> for LcgConfig {
+  fn from(value: &LcgCommand) -> Self {
+    match value {
+      LcgCommand::Generate {
+        modulus,
+        multiplier,
+        increment,
+        seed,
+        count,
+        ..
+      } => Self {
+        modulus: Some(*modulus),
+        multiplier: *multiplier,
+        increment: *increment,
+        seed: *seed,
+        count: *count,
+      },
+      LcgCommand::Recover { modulus, .. } => Self {
+        modulus: *modulus,
+        ..Self::default()
+      },
+    }
+  }
+}
+
+/// A linear congruential generator `x' = (a * x + c) mod m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lcg {
+  pub modulus: u64,
+  pub multiplier: u64,
+  pub increment: u64,
+  pub state: u64,
+}
+
+impl Lcg {
+  pub fn new(
+    modulus: u64,
+    multiplier: u64,
+    increment: u64,
+    seed: u64,
+  ) -> Result<Self> {
+    if modulus < 2 {
+      let message = "The modulus of an LCG must be at least 2";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(Self {
+      modulus,
+      multiplier: multiplier % modulus,
+      increment: increment % modulus,
+      state: seed % modulus,
+    })
+  }
+
+  /// Writes `count` consecutive outputs, one per line.
+  pub fn generate<W: Write>(output: &mut W, config: LcgConfig) -> Result<()> {
+    let modulus = config.modulus.unwrap_or_default();
+    let mut lcg =
+      Self::new(modulus, config.multiplier, config.increment, config.seed)?;
+
+    for _ in 0..config.count {
+      writeln!(output, "{}", lcg.next_value())?;
+    }
+
+    Ok(())
+  }
+
+  /// Reads consecutive outputs separated by whitespace and writes the
+  /// recovered parameters and the predicted next output.
+  pub fn recover_from_input<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: LcgConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let values = content
+      .split_whitespace()
+      .map(|value| {
+        value.parse().map_err(|_| {
+          let message = format!("Invalid LCG output {value}");
+          Error::new(ErrorKind::InvalidInput, message)
+        })
+      })
+      .collect::<Result<Vec<u64>>>()?;
+
+    let mut lcg = Self::recover(&values, config.modulus)?;
+    writeln!(output, "modulus = {}", lcg.modulus)?;
+    writeln!(output, "multiplier = {}", lcg.multiplier)?;
+    writeln!(output, "increment = {}", lcg.increment)?;
+    writeln!(output, "next = {}", lcg.next_value())
+  }
+
+  /// Recovers the generator from consecutive outputs, with its state at
+  /// the last output.
+  ///
+  /// The differences `t = x' - x` satisfy `t' = a * t mod m`, so
+  /// `t'' * t - t'^2` is a multiple of `m`. The gcd of a few of them is
+  /// the modulus, unless it is known already. Then two differences give
+  /// `a = t' / t mod m` and one output gives `c = x' - a * x mod m`.
+  pub fn recover(values: &[u64], modulus: Option<u64>) -> Result<Self> {
+    let needed = if modulus.is_some() { 3 } else { 6 };
+    if values.len() < needed {
+      let message = format!("Recovery needs at least {needed} outputs");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let signed = |value: u64| BigInt::from(value);
+    let differences: Vec<BigInt> = values
+      .windows(2)
+      .map(|pair| signed(pair[1]) - signed(pair[0]))
+      .collect();
+
+    let modulus = match modulus {
+      Some(modulus) => BigUint::from(modulus),
+      None => differences
+        .windows(3)
+        .map(|t| (&t[2] * &t[0] - &t[1] * &t[1]).magnitude().clone())
+        .fold(BigUint::ZERO, |modulus, multiple| gcd(&modulus, &multiple)),
+    };
+
+    let invalid = || {
+      let message = "The outputs do not come from a recoverable LCG";
+      Error::new(ErrorKind::InvalidInput, message)
+    };
+
+    let to_unsigned = |value: &BigInt| -> BigUint {
+      let modulus = BigInt::from_biguint(Sign::Plus, modulus.clone());
+      ((value % &modulus + &modulus) % &modulus)
+        .magnitude()
+        .clone()
+    };
+
+    let (t0, t1) = (to_unsigned(&differences[0]), to_unsigned(&differences[1]));
+    let multiplier = t0.modinv(&modulus).ok_or_else(invalid)? * t1 % &modulus;
+
+    let product = &multiplier * values[0];
+    let increment = to_unsigned(&(signed(values[1]) - BigInt::from(product)));
+
+    let narrow = |value: &BigUint| u64::try_from(value).map_err(|_| invalid());
+    let lcg = Self::new(
+      narrow(&modulus)?,
+      narrow(&multiplier)?,
+      narrow(&increment)?,
+      values[0],
+    )?;
+
+    let mut check = lcg;
+    let consistent = values[1..].iter().all(|&x| check.next_value() == x);
+    if !consistent {
+      return Err(invalid());
+    }
+
+    Ok(check)
+  }
+
+  pub fn next_value(&mut self) -> u64 {
+    let next = (self.multiplier as u128 * self.state as u128
+      + self.increment as u128)
+      % self.modulus as u128;
+    self.state = next as u64;
+    self.state
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn outputs(lcg: &mut Lcg, count: usize) -> Vec<u64> {
+    (0..count).map(|_| lcg.next_value()).collect()
+  }
+
+  #[test]
+  fn test_generate() -> Result<()> {
+    let mut lcg = Lcg::new(16, 5, 3, 7)?;
+    assert_eq!(outputs(&mut lcg, 4), [6, 1, 8, 11]);
+    assert!(Lcg::new(1, 1, 1, 1).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_recover() -> Result<()> {
+    let mut lcg = Lcg::new(2147483647, 48271, 12345, 42)?;
+    let values = outputs(&mut lcg, 10);
+
+    let mut recovered = Lcg::recover(&values, None)?;
+    assert_eq!(recovered.modulus, 2147483647);
+    assert_eq!(recovered.multiplier, 48271);
+    assert_eq!(recovered.increment, 12345);
+    assert_eq!(recovered.next_value(), lcg.next_value());
+    Ok(())
+  }
+
+  #[test]
+  fn test_recover_with_known_modulus() -> Result<()> {
+    let mut lcg = Lcg::new(1000003, 1103515245, 12345, 1)?;
+    let values = outputs(&mut lcg, 3);
+    let recovered = Lcg::recover(&values, Some(1000003))?;
+    assert_eq!(recovered, lcg);
+    assert!(Lcg::recover(&values[..2], Some(1000003)).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_recover_from_input() -> Result<()> {
+    let mut lcg = Lcg::new(1009, 71, 3, 1)?;
+    let values: Vec<String> =
+      outputs(&mut lcg, 8).iter().map(u64::to_string).collect();
+    let input = values.join(" ");
+
+    let mut output = Vec::new();
+    let config = LcgConfig::default();
+    Lcg::recover_from_input(&mut input.as_bytes(), &mut output, config)?;
+
+    let expected = format!(
+      "modulus = 1009\nmultiplier = 71\nincrement = 3\nnext = {}\n",
+      lcg.next_value()
+    );
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+    Ok(())
+  }
+}
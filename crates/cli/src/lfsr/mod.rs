@@ -0,0 +1,363 @@
+use std::{
+  collections::VecDeque,
+  io::{Error, ErrorKind, Read, Result, Write},
+};
+
+use crate::{hex::Hex, xor::Xor, LfsrCommand};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LfsrConfig {
+  pub taps: Vec<usize>,
+  pub state: String,
+  pub length: usize,
+  pub raw: bool,
+  pub bits: bool,
+}
+
+impl LfsrConfig {
+  pub fn new(
+    taps: Vec<usize>,
+    state: &str,
+    length: usize,
+    raw: bool,
+    bits: bool,
+  ) -> Self {
+    Self {
+      taps,
+      state: state.to_string(),
+      length,
+      raw,
+      bits,
+    }
+  }
+}
+
+impl From<&LfsrCommand> for LfsrConfig {
+  fn from(value: &LfsrCommand) -> Self {
+    match value {
+      LfsrCommand::Keystream {
+        taps,
+        state,
+        length,
+        bits,
+        ..
+      } => Self::new(taps.clone(), state, *length, false, *bits),
+      LfsrCommand::Xor {
+        taps, state, raw, ..
+      } => Self::new(taps.clone(), state, 0, *raw, false),
+      LfsrCommand::Recover { bits, .. } => {
+        Self::new(Vec::new(), "", 0, false, *bits)
+      }
+    }
+  }
+}
+
+/// A Fibonacci linear feedback shift register over GF(2).
+///
+/// With `n` state bits and taps `t`, the register produces the
+/// sequence `s[k] = s[k - t1] ^ s[k - t2] ^ ...`, where the initial
+/// state holds `s[0]..s[n - 1]` and `s[0]` is output first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+  taps: Vec<usize>,
+  state: VecDeque<bool>,
+}
+
+impl Lfsr {
+  pub fn new(taps: &[usize], state: &[bool]) -> Result<Self> {
+    if let Some(tap) = taps.iter().find(|&&tap| tap == 0 || tap > state.len()) {
+      let message = format!(
+        "Tap {tap} is outside of the register of length {}",
+        state.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(Self {
+      taps: taps.to_vec(),
+      state: state.iter().copied().collect(),
+    })
+  }
+
+  /// Parses a string of `0` and `1`, ignoring whitespace and underscores.
+  pub fn parse_bits(bits: &str) -> Result<Vec<bool>> {
+    bits
+      .chars()
+      .filter(|c| !c.is_whitespace() && *c != '_')
+      .map(|c| match c {
+        '0' => Ok(false),
+        '1' => Ok(true),
+        _ => {
+          let message = format!("Invalid bit '{c}', expected 0 or 1");
+          Err(Error::new(ErrorKind::InvalidInput, message))
+        }
+      })
+      .collect()
+  }
+
+  pub fn format_bits(bits: &[bool]) -> String {
+    bits
+      .iter()
+      .map(|&bit| if bit { '1' } else { '0' })
+      .collect()
+  }
+
+  pub fn taps(&self) -> &[usize] {
+    &self.taps
+  }
+
+  pub fn state(&self) -> Vec<bool> {
+    self.state.iter().copied().collect()
+  }
+
+  pub fn next_bit(&mut self) -> bool {
+    let length = self.state.len();
+    let feedback = self
+      .taps
+      .iter()
+      .fold(false, |bit, &tap| bit ^ self.state[length - tap]);
+
+    match self.state.pop_front() {
+      Some(bit) => {
+        self.state.push_back(feedback);
+        bit
+      }
+      None => false,
+    }
+  }
+
+  pub fn bits(&mut self, length: usize) -> Vec<bool> {
+    (0..length).map(|_| self.next_bit()).collect()
+  }
+
+  /// Produces keystream bytes, packing the bits most significant first.
+  pub fn bytes(&mut self, length: usize) -> Vec<u8> {
+    (0..length)
+      .map(|_| {
+        self
+          .bits(8)
+          .into_iter()
+          .fold(0, |byte, bit| byte << 1 | u8::from(bit))
+      })
+      .collect()
+  }
+
+  pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+    bytes
+      .iter()
+      .flat_map(|byte| (0..8).rev().map(move |bit| byte >> bit & 1 == 1))
+      .collect()
+  }
+
+  /// Finds the shortest LFSR producing the sequence.
+  ///
+  /// Returns the linear complexity `L` and the connection polynomial
+  /// `C(x) = 1 + c1 x + ... + cL x^L` as coefficients `c0..=cL`.
+  /// Only `2L` bits of keystream are needed to recover a register of
+  /// length `L`, which is why an LFSR alone is not a secure cipher.
+  pub fn berlekamp_massey(sequence: &[bool]) -> (usize, Vec<bool>) {
+    let mut connection = vec![true];
+    let mut previous = vec![true];
+    let mut complexity = 0;
+    let mut shift = 1;
+
+    for n in 0..sequence.len() {
+      let discrepancy = (1..=complexity)
+        .filter(|&i| connection.get(i).copied().unwrap_or(false))
+        .fold(sequence[n], |d, i| d ^ sequence[n - i]);
+
+      if !discrepancy {
+        shift += 1;
+        continue;
+      }
+
+      let last = connection.clone();
+      let length = connection.len().max(previous.len() + shift);
+      connection.resize(length, false);
+
+      for (i, &bit) in previous.iter().enumerate() {
+        connection[i + shift] ^= bit;
+      }
+
+      if 2 * complexity <= n {
+        complexity = n + 1 - complexity;
+        previous = last;
+        shift = 1;
+      } else {
+        shift += 1;
+      }
+    }
+
+    connection.resize(complexity + 1, false);
+    (complexity, connection)
+  }
+
+  /// Recovers a register that reproduces the observed keystream bits.
+  pub fn recover(sequence: &[bool]) -> Result<Self> {
+    let (complexity, connection) = Self::berlekamp_massey(sequence);
+    let taps: Vec<usize> =
+      (1..=complexity).rev().filter(|&i| connection[i]).collect();
+    Self::new(&taps, &sequence[..complexity])
+  }
+
+  pub fn keystream<W: Write>(output: &mut W, config: LfsrConfig) -> Result<()> {
+    let mut lfsr = Self::from_config(&config)?;
+
+    if config.bits {
+      let bits = lfsr.bits(config.length);
+      write!(output, "{}", Self::format_bits(&bits))
+    } else {
+      let hex = Hex::new(lfsr.bytes(config.length));
+      write!(output, "{hex}")
+    }
+  }
+
+  pub fn xor<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: LfsrConfig,
+  ) -> Result<()> {
+    let mut lfsr = Self::from_config(&config)?;
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let data = if config.raw {
+      let content = String::from_utf8_lossy(&content);
+      Hex::parse_hex(content.trim())?.bytes
+    } else {
+      content
+    };
+
+    let keystream = lfsr.bytes(data.len());
+    let xor = Xor::xor_bytes(&data, &keystream);
+    write!(output, "{xor}")
+  }
+
+  pub fn recover_from_input<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: LfsrConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let sequence = if config.bits {
+      Self::parse_bits(&content)?
+    } else {
+      Self::bytes_to_bits(&Hex::parse_hex(content.trim())?.bytes)
+    };
+
+    let lfsr = Self::recover(&sequence)?;
+    let taps: Vec<String> =
+      lfsr.taps.iter().map(|tap| tap.to_string()).collect();
+
+    writeln!(output, "linear complexity: {}", lfsr.state.len())?;
+    writeln!(output, "taps: {}", taps.join(","))?;
+    writeln!(output, "state: {}", Self::format_bits(&lfsr.state()))
+  }
+
+  fn from_config(config: &LfsrConfig) -> Result<Self> {
+    let state = Self::parse_bits(&config.state)?;
+    Self::new(&config.taps, &state)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_maximal_length_sequence() {
+    let state = Lfsr::parse_bits("1000").unwrap();
+    let mut lfsr = Lfsr::new(&[4, 3], &state).unwrap();
+    let first = lfsr.bits(15);
+    let second = lfsr.bits(15);
+
+    assert_eq!(Lfsr::format_bits(&first), "100010011010111");
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn test_rejects_invalid_taps() {
+    let state = Lfsr::parse_bits("101").unwrap();
+    assert!(Lfsr::new(&[4], &state).is_err());
+    assert!(Lfsr::new(&[0], &state).is_err());
+  }
+
+  #[test]
+  fn test_parse_bits() {
+    let bits = Lfsr::parse_bits("10 1_0").unwrap();
+    assert_eq!(bits, vec![true, false, true, false]);
+    assert!(Lfsr::parse_bits("102").is_err());
+  }
+
+  #[test]
+  fn test_bytes_round_trip_bits() {
+    let bits = Lfsr::bytes_to_bits(&[0xa5]);
+    assert_eq!(Lfsr::format_bits(&bits), "10100101");
+  }
+
+  #[test]
+  fn test_berlekamp_massey() {
+    let sequence = Lfsr::parse_bits("100010011010111").unwrap();
+    let (complexity, connection) = Lfsr::berlekamp_massey(&sequence);
+
+    assert_eq!(complexity, 4);
+    assert_eq!(connection, vec![true, false, false, true, true]);
+  }
+
+  #[test]
+  fn test_recover_from_known_keystream() {
+    let taps = [16, 14, 13, 11];
+    let state = Lfsr::parse_bits("1010110011100001").unwrap();
+    let mut lfsr = Lfsr::new(&taps, &state).unwrap();
+    let keystream = lfsr.bits(32);
+
+    let mut recovered = Lfsr::recover(&keystream).unwrap();
+
+    assert_eq!(recovered.taps(), &taps);
+    assert_eq!(recovered.state(), state);
+    assert_eq!(recovered.bits(32), keystream);
+    assert_eq!(recovered.bits(100), lfsr.bits(100));
+  }
+
+  #[test]
+  fn test_recover_all_zero_sequence() {
+    let mut lfsr = Lfsr::recover(&[false; 8]).unwrap();
+    assert!(lfsr.taps().is_empty());
+    assert_eq!(lfsr.bits(3), vec![false; 3]);
+  }
+
+  #[test]
+  fn test_xor_round_trip() -> Result<()> {
+    let config = || LfsrConfig::new(vec![4, 3], "1000", 0, false, false);
+    let mut cipher = Vec::new();
+    Lfsr::xor(&mut "attack at dawn".as_bytes(), &mut cipher, config())?;
+
+    let mut plaintext = Vec::new();
+    let config = LfsrConfig {
+      raw: true,
+      ..config()
+    };
+    Lfsr::xor(&mut cipher.as_slice(), &mut plaintext, config)?;
+
+    let plaintext = Hex::parse_hex(&String::from_utf8(plaintext).unwrap())?;
+    assert_eq!(plaintext.to_ascii(), "attack at dawn");
+    Ok(())
+  }
+
+  #[test]
+  fn test_recover_from_input() -> Result<()> {
+    let mut keystream = Vec::new();
+    let config = LfsrConfig::new(vec![5, 3], "10011", 4, false, false);
+    Lfsr::keystream(&mut keystream, config)?;
+
+    let mut output = Vec::new();
+    let config = LfsrConfig::default();
+    Lfsr::recover_from_input(&mut keystream.as_slice(), &mut output, config)?;
+
+    let report = String::from_utf8(output).unwrap();
+    assert_eq!(report, "linear complexity: 5\ntaps: 5,3\nstate: 10011\n");
+    Ok(())
+  }
+}
@@ -1,24 +1,148 @@
-pub mod caesar;
-pub mod frequency_analysis;
+pub mod adfgvx;
+pub mod aes;
+pub mod affine;
+pub mod anagram;
+pub mod autocorrelation;
+pub mod bacon;
+pub mod book_cipher;
+pub mod brute;
+pub mod bytes;
+pub mod cadenus;
+pub mod checksum;
+pub mod crib;
+pub mod des;
+pub mod ecb;
+pub mod elgamal;
+pub mod encoding;
+pub mod generate;
+pub mod hash;
 pub mod hex;
+pub mod keycheck;
+pub mod keygen;
+pub mod keystream;
+pub mod lcg;
+pub mod lfsr;
+pub mod m209;
+pub mod many_time_pad;
 pub mod monoalphabetic_substitution;
+pub mod multiplicative;
+pub mod nato;
+pub mod null_cipher;
 pub mod one_time_pad;
+pub mod password_entropy;
+pub mod prime;
+pub mod radix;
+pub mod rail_fence;
+pub mod recover_key;
+pub mod rot47;
+pub mod route;
+pub mod rsa;
+pub mod score;
+pub mod scytale;
+pub mod solve;
+pub mod solver;
+pub mod stego;
+pub mod strings;
+pub mod text;
+pub mod toyhash;
+pub mod transposition;
+pub mod triage;
 pub mod vigenere;
+pub mod wordlist;
+pub mod workbench;
 pub mod xor;
 
+pub use cryptology_core::{
+  alphabet, caesar, dictionary_attack, error, frequency_analysis, ngram,
+  progress, report,
+};
+
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 use hex::Hex;
-use xor::Xor;
+use lcg::Lcg;
+use lfsr::Lfsr;
+use m209::M209;
+use num_bigint::BigUint;
+use rand::{rngs::StdRng, SeedableRng};
+use xor::XorConfig;
 
-use std::fs::File;
+use std::env;
+use std::fs::{self, File};
 use std::io::{self, Cursor, Read, Result, Write};
-use std::path::PathBuf;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
-use caesar::Caesar;
-use frequency_analysis::FrequencyAnalyzer;
-use monoalphabetic_substitution::MonoalphabeticSubstition;
-use one_time_pad::OneTimePad;
-use vigenere::Vigenere;
+use adfgvx::{Adfgvx, AdfgvxVariant};
+use aes::Aes;
+use affine::{Affine, AffineConfig};
+use alphabet::Alphabet;
+use anagram::Anagram;
+use autocorrelation::Autocorrelation;
+use bacon::{Bacon, BaconVariant};
+use book_cipher::{BookCipher, BookReferenceFormat};
+use brute::{Brute, BruteMethod};
+use bytes::{ByteOperation, Bytes};
+use cadenus::Cadenus;
+use caesar::{Caesar, CaesarConfig};
+use checksum::{Checksum, ChecksumAlgorithm};
+use crib::{Crib, CribCipher};
+use des::Des;
+use dictionary_attack::{CaseRule, DictionaryAttackConfig, Mangling};
+use ecb::demo::EcbDemo;
+use ecb::Ecb;
+use elgamal::{Elgamal, ElgamalCiphertext, ElgamalPublicKey};
+use encoding::EncodingDetector;
+use error::CryptologyError;
+use frequency_analysis::window::{WindowAnalyzer, WindowConfig};
+use frequency_analysis::{
+  FrequencyAnalyzer, FrequencyConfig, FrequencySort, Language,
+};
+use generate::{Completions, Manpages};
+use hash::hmac::Hmac;
+use hash::{DigestFormat, Hash, HashAlgorithm};
+use keycheck::KeyCheck;
+use keygen::{Keygen, KeygenFormat};
+use keystream::Keystream;
+use many_time_pad::{ManyTimePad, ManyTimePadConfig};
+use monoalphabetic_substitution::refine::Refinement;
+use monoalphabetic_substitution::substitution_map::SubstitutionMap;
+use monoalphabetic_substitution::{
+  MonoalphabeticSubstition, MonoalphabeticSubstitutionEncryptConfig,
+};
+use multiplicative::Multiplicative;
+use nato::Nato;
+use null_cipher::{NullCipher, NullUnit};
+use one_time_pad::reuse::{PadReuse, REUSE_THRESHOLD};
+use one_time_pad::{OneTimePad, OneTimePadEncryptConfig};
+use password_entropy::PasswordEntropy;
+use prime::factor::{Factor, FactorMethod};
+use prime::Prime;
+use radix::{Base, Radix};
+use rail_fence::RailFence;
+use recover_key::{RecoverCipher, RecoverKey};
+use report::{CrackReport, OutputFormat};
+use rot47::Rot47;
+use route::{Route, RouteConfig, RouteKind};
+use rsa::attack::RsaAttacks;
+use score::Score;
+use scytale::Scytale;
+use solve::{AutoSolve, SolveMethod};
+use solver::SolverKind;
+use stego::image::ImageStego;
+use stego::whitespace::{WhitespaceMethod, WhitespaceStego};
+use strings::{StringEncoding, Strings};
+use text::spacing::SpacingWriter;
+use text::{GroupWriter, TextFormat};
+use toyhash::rainbow::Rainbow;
+use toyhash::{Compression, ToyHash};
+use transposition::{ColumnarTransposition, TranspositionConfig};
+use triage::Triage;
+use vigenere::{Vigenere, VigenereDecryptConfig, VigenereEncryptConfig};
+use wordlist::Wordlist;
+use workbench::{Workbench, WorkbenchMode};
+use xor::repeating_key::{KeyFormat, RepeatingKeyXor, RepeatingKeyXorConfig};
+use xor::PadSide;
 
 /// Cryptology CLI tool for cryptographic operations.
 ///
@@ -45,12 +169,99 @@ use vigenere::Vigenere;
 pub struct Cryptology {
   #[command(subcommand)]
   pub command: Command,
+
+  /// Format of the results of the commands that crack a cipher.
+  ///
+  /// JSON reports the plaintext, key, score and method of every
+  /// ciphertext. Other commands always write text.
+  #[arg(
+    long,
+    global = true,
+    value_enum,
+    value_name = "FORMAT",
+    default_value_t = OutputFormat::Text,
+    help = "Write the results of cracking as text or JSON"
+  )]
+  pub output_format: OutputFormat,
+
+  /// Number of threads of the parallel solvers and crackers.
+  ///
+  /// Without it the `RAYON_NUM_THREADS` environment variable is used,
+  /// and one thread per CPU if that is not set either.
+  #[arg(
+    long,
+    global = true,
+    value_name = "N",
+    help = "Number of threads used by parallel solvers"
+  )]
+  pub threads: Option<NonZeroUsize>,
 }
 
 impl Cryptology {
-  pub fn execute() -> Result<()> {
-    let cli = Self::parse();
-    cli.command.execute()
+  pub fn execute() -> std::result::Result<(), CryptologyError> {
+    let mut cli = Self::parse();
+    if let Some(threads) = cli.threads {
+      Self::configure_threads(threads)?;
+    }
+    cli.command.read_key_files()?;
+
+    let batch = match cli.command.default_args_mut() {
+      Some(default_args) => default_args.batch()?,
+      None => None,
+    };
+    match batch {
+      Some(inputs) => cli.execute_batch(inputs),
+      None => Ok(cli.command.execute(cli.output_format)?),
+    }
+  }
+
+  /// Runs the command once per input file. A failing file does not stop
+  /// the others, its error is printed and the exit code of the last one
+  /// is kept.
+  fn execute_batch(
+    &mut self,
+    inputs: Vec<PathBuf>,
+  ) -> std::result::Result<(), CryptologyError> {
+    let total = inputs.len();
+    let mut failed = 0;
+    let mut last = None;
+
+    for input in inputs {
+      let Some(default_args) = self.command.default_args_mut() else {
+        break;
+      };
+      if let Some(dir) = &default_args.output_dir {
+        fs::create_dir_all(dir)?;
+        let name = input.file_name().unwrap_or(input.as_os_str());
+        default_args.output = Some(dir.join(name));
+      }
+      default_args.input = vec![input.clone()];
+
+      if let Err(error) = self.command.execute(self.output_format) {
+        let error = CryptologyError::from(error);
+        eprintln!("Error: {}: {error}", input.display());
+        failed += 1;
+        last = Some(error);
+      }
+    }
+
+    match last {
+      Some(last) => Err(CryptologyError::Batch {
+        failed,
+        total,
+        last: Box::new(last),
+      }),
+      None => Ok(()),
+    }
+  }
+
+  /// Sizes the global thread pool, which has to happen before the first
+  /// parallel iterator runs.
+  fn configure_threads(threads: NonZeroUsize) -> Result<()> {
+    rayon::ThreadPoolBuilder::new()
+      .num_threads(threads.get())
+      .build_global()
+      .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
   }
 }
 
@@ -58,14 +269,24 @@ impl Cryptology {
 pub struct CryptologyDefaultArgs {
   /// Path to the input file.
   ///
-  /// If not provided, reads from standard input.
+  /// If not provided, reads from standard input. If given more than
+  /// once, every file is processed on its own in batch mode.
   #[arg(
     short = 'i',
     long = "input",
     value_name = "INPUT",
-    help = "Specify the input file"
+    help = "Specify the input file, repeat it for batch mode"
+  )]
+  input: Vec<PathBuf>,
+
+  /// Glob of input files that are processed on their own in batch mode,
+  /// like `challenges/*.txt`.
+  #[arg(
+    long = "input-glob",
+    value_name = "GLOB",
+    help = "Process every file matching a glob in batch mode"
   )]
-  input: Option<PathBuf>,
+  input_glob: Option<String>,
 
   /// Path to the output file for saving results.
   ///
@@ -77,6 +298,53 @@ pub struct CryptologyDefaultArgs {
     help = "Specify the output file for saving result."
   )]
   output: Option<PathBuf>,
+
+  /// Directory for the outputs of batch mode, each named like its
+  /// input. Without it the outputs follow each other on standard output.
+  #[arg(
+    long = "output-dir",
+    value_name = "DIR",
+    conflicts_with = "output",
+    help = "Write the output of every input file into a directory"
+  )]
+  output_dir: Option<PathBuf>,
+}
+
+impl CryptologyDefaultArgs {
+  /// The input files of batch mode, or `None` for a single input.
+  fn batch(&self) -> Result<Option<Vec<PathBuf>>> {
+    if self.input.len() < 2
+      && self.input_glob.is_none()
+      && self.output_dir.is_none()
+    {
+      return Ok(None);
+    }
+
+    let mut inputs = self.input.clone();
+    if let Some(pattern) = &self.input_glob {
+      let invalid = |error| io::Error::new(io::ErrorKind::InvalidInput, error);
+      let paths = glob::glob(pattern).map_err(invalid)?;
+      let mut matches = Vec::new();
+      for path in paths {
+        let path = path.map_err(io::Error::from)?;
+        if path.is_file() {
+          matches.push(path);
+        }
+      }
+      if matches.is_empty() {
+        let message = format!("No input files match {pattern}");
+        return Err(io::Error::new(io::ErrorKind::NotFound, message));
+      }
+      matches.sort();
+      inputs.extend(matches);
+    }
+
+    if self.output.is_some() {
+      let message = "Batch mode writes to --output-dir or standard output";
+      return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+    }
+    Ok(Some(inputs))
+  }
 }
 
 #[derive(Debug, Parser)]
@@ -86,9 +354,86 @@ pub struct CryptologyEncryptKeyArg {
     short = 'k',
     long = "key",
     value_name = "KEY",
+    required_unless_present_any = ["key_file", "key_env"],
+    conflicts_with_all = ["key_file", "key_env"],
     help = "Key used for encryption"
   )]
-  key: String,
+  key: Option<String>,
+
+  /// Path to a file with the key. A text file holds the key itself,
+  /// without the line break at its end. The bytes of any other file
+  /// are the key and are passed on hex encoded.
+  #[arg(
+    long = "key-file",
+    value_name = "KEY_FILE",
+    conflicts_with = "key_env",
+    help = "Read the key from a file, hex encoded if it is binary"
+  )]
+  key_file: Option<PathBuf>,
+
+  /// Environment variable with the key, which keeps the key out of the
+  /// shell history and the process list.
+  #[arg(
+    long = "key-env",
+    value_name = "VAR",
+    help = "Read the key from an environment variable"
+  )]
+  key_env: Option<String>,
+}
+
+impl CryptologyEncryptKeyArg {
+  fn key(&self) -> &str {
+    self.key.as_deref().unwrap_or_default()
+  }
+
+  /// Replaces the key with the content of the key file or environment
+  /// variable, if there is one. Returns whether the key is hex encoded.
+  fn read_key_file(&mut self) -> Result<bool> {
+    let key_file = KeyFile::from_args(&self.key_file, &self.key_env)?;
+    let Some(key_file) = key_file else {
+      return Ok(false);
+    };
+    self.key = Some(key_file.key);
+    Ok(key_file.hex)
+  }
+}
+
+/// How a byte key is given, the same for every cipher with one.
+#[derive(Debug, Parser)]
+pub struct CryptologyKeyFormatArg {
+  /// Format of the key: ascii text, hex, base64 or raw binary.
+  ///
+  /// Ascii if not provided, except for key files of the one-time pad,
+  /// which are hex like the pads it writes.
+  #[arg(
+    long = "key-format",
+    value_name = "FORMAT",
+    value_enum,
+    help = "Format of the key: ascii, hex, base64 or binary [default: ascii]"
+  )]
+  key_format: Option<KeyFormat>,
+}
+
+impl CryptologyKeyFormatArg {
+  fn key_format(&self) -> KeyFormat {
+    self.key_format.unwrap_or_default()
+  }
+
+  /// Binary key files are read as hex, whatever format was given.
+  fn read_key_file(&mut self, hex: bool) {
+    if hex {
+      self.key_format = Some(KeyFormat::Hex);
+    }
+  }
+
+  /// Text key files of the one-time pad are read as hex unless another
+  /// format is given, since `--key-output` and `keygen` write hex pads.
+  fn read_pad_file(&mut self, path: &Option<PathBuf>, hex: bool) {
+    if path.is_some() && self.key_format.is_none() {
+      self.key_format = Some(KeyFormat::Hex);
+    }
+    self.read_key_file(hex);
+  }
 }
 
 #[derive(Debug, Parser)]
@@ -98,9 +443,479 @@ pub struct CryptologyDecryptKeyArg {
     short = 'k',
     long = "key",
     value_name = "KEY",
+    conflicts_with_all = ["key_file", "key_env"],
     help = "The decryption key if known"
   )]
   key: Option<String>,
+
+  /// Path to a file with the key. A text file holds the key itself,
+  /// without the line break at its end. The bytes of any other file
+  /// are the key and are passed on hex encoded.
+  #[arg(
+    long = "key-file",
+    value_name = "KEY_FILE",
+    conflicts_with = "key_env",
+    help = "Read the key from a file, hex encoded if it is binary"
+  )]
+  key_file: Option<PathBuf>,
+
+  /// Environment variable with the key, which keeps the key out of the
+  /// shell history and the process list.
+  #[arg(
+    long = "key-env",
+    value_name = "VAR",
+    help = "Read the key from an environment variable"
+  )]
+  key_env: Option<String>,
+}
+
+impl CryptologyDecryptKeyArg {
+  /// Replaces the key with the content of the key file or environment
+  /// variable, if there is one. Returns whether the key is hex encoded.
+  fn read_key_file(&mut self) -> Result<bool> {
+    let key_file = KeyFile::from_args(&self.key_file, &self.key_env)?;
+    let Some(key_file) = key_file else {
+      return Ok(false);
+    };
+    self.key = Some(key_file.key);
+    Ok(key_file.hex)
+  }
+}
+
+/// A key needed for decryption, the same as [`CryptologyEncryptKeyArg`]
+/// for ciphers that cannot be cracked.
+#[derive(Debug, Parser)]
+pub struct CryptologyRequiredDecryptKeyArg {
+  /// Key used for decryption.
+  #[arg(
+    short = 'k',
+    long = "key",
+    value_name = "KEY",
+    required_unless_present_any = ["key_file", "key_env"],
+    conflicts_with_all = ["key_file", "key_env"],
+    help = "Key used for decryption"
+  )]
+  key: Option<String>,
+
+  /// Path to a file with the key. A text file holds the key itself,
+  /// without the line break at its end. The bytes of any other file
+  /// are the key and are passed on hex encoded.
+  #[arg(
+    long = "key-file",
+    value_name = "KEY_FILE",
+    conflicts_with = "key_env",
+    help = "Read the key from a file, hex encoded if it is binary"
+  )]
+  key_file: Option<PathBuf>,
+
+  /// Environment variable with the key, which keeps the key out of the
+  /// shell history and the process list.
+  #[arg(
+    long = "key-env",
+    value_name = "VAR",
+    help = "Read the key from an environment variable"
+  )]
+  key_env: Option<String>,
+}
+
+impl CryptologyRequiredDecryptKeyArg {
+  fn key(&self) -> &str {
+    self.key.as_deref().unwrap_or_default()
+  }
+
+  /// Replaces the key with the content of the key file or environment
+  /// variable, if there is one. Returns whether the key is hex encoded.
+  fn read_key_file(&mut self) -> Result<bool> {
+    let key_file = KeyFile::from_args(&self.key_file, &self.key_env)?;
+    let Some(key_file) = key_file else {
+      return Ok(false);
+    };
+    self.key = Some(key_file.key);
+    Ok(key_file.hex)
+  }
+}
+
+/// A key read from a file or an environment variable instead of the
+/// command line.
+///
+/// A text file holds the key itself, without the line break at its end.
+/// The bytes of any other file are the key, which is hex encoded so that
+/// commands that accept hex keys can use it. Environment variables
+/// always hold text keys.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KeyFile {
+  pub key: String,
+  /// Whether the file was binary and the key is its hex encoding.
+  pub hex: bool,
+}
+
+impl KeyFile {
+  pub fn read(path: &Path) -> Result<Self> {
+    let bytes = fs::read(path).map_err(|source| CryptologyError::Open {
+      path: path.to_path_buf(),
+      source,
+    })?;
+    Ok(Self::parse(bytes))
+  }
+
+  /// The key of a `--key-file` or else of a `--key-env` argument, if
+  /// either is given.
+  fn from_args(
+    path: &Option<PathBuf>,
+    var: &Option<String>,
+  ) -> Result<Option<Self>> {
+    match (path, var) {
+      (Some(path), _) => Self::read(path).map(Some),
+      (None, Some(var)) => Self::env(var).map(Some),
+      (None, None) => Ok(None),
+    }
+  }
+
+  pub fn env(var: &str) -> Result<Self> {
+    match env::var(var) {
+      Ok(key) => Ok(Self { key, hex: false }),
+      Err(env::VarError::NotPresent) => {
+        let message = format!("The environment variable {var} is not set");
+        Err(io::Error::new(io::ErrorKind::InvalidInput, message))
+      }
+      Err(env::VarError::NotUnicode(_)) => {
+        let message = format!("The environment variable {var} is not UTF-8");
+        Err(io::Error::new(io::ErrorKind::InvalidData, message))
+      }
+    }
+  }
+
+  pub fn parse(bytes: Vec<u8>) -> Self {
+    match String::from_utf8(bytes) {
+      Ok(text) if !text.contains('\0') => Self {
+        key: text.trim_end_matches(['\r', '\n']).to_string(),
+        hex: false,
+      },
+      Ok(text) => Self::binary(text.as_bytes()),
+      Err(error) => Self::binary(error.as_bytes()),
+    }
+  }
+
+  fn binary(bytes: &[u8]) -> Self {
+    Self {
+      key: Hex::new(bytes.to_vec()).to_string(),
+      hex: true,
+    }
+  }
+
+  /// The shift of a text key file.
+  fn shift(path: &Path) -> Result<usize> {
+    let key_file = Self::read(path)?;
+    Self::text(key_file.hex)?;
+    key_file.key.trim().parse().map_err(|_| {
+      let message =
+        format!("The key file {} does not hold a shift", path.display());
+      io::Error::new(io::ErrorKind::InvalidData, message)
+    })
+  }
+
+  /// The keys on the lines of a text key file, skipping empty lines.
+  fn lines(path: &Path) -> Result<Vec<String>> {
+    let key_file = Self::read(path)?;
+    Self::text(key_file.hex)?;
+    let keys = key_file.key.lines().map(str::trim);
+    Ok(
+      keys
+        .filter(|key| !key.is_empty())
+        .map(String::from)
+        .collect(),
+    )
+  }
+
+  /// Fails for key files that are binary, for ciphers over letters.
+  fn text(hex: bool) -> Result<()> {
+    match hex {
+      true => {
+        let message = "The key file is binary, but the cipher needs a text key";
+        Err(io::Error::new(io::ErrorKind::InvalidData, message))
+      }
+      false => Ok(()),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub struct CryptologyAlphabetArgs {
+  /// Symbols of the alphabet in order, like
+  /// `ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789`. Defaults to `A..=Z`.
+  #[arg(
+    long = "alphabet",
+    value_name = "SYMBOLS",
+    help = "Symbols of the alphabet in order, A to Z by default"
+  )]
+  alphabet: Option<String>,
+
+  /// Path to a file with the symbols of the alphabet in order.
+  /// Whitespace in the file is ignored.
+  #[arg(
+    long = "alphabet-file",
+    value_name = "PATH",
+    conflicts_with = "alphabet",
+    help = "Read the symbols of the alphabet from a file"
+  )]
+  alphabet_file: Option<PathBuf>,
+
+  /// Language of the plaintext. Selects the letter frequencies that
+  /// ciphertexts are cracked with, and the alphabet unless one is given.
+  #[arg(
+    long = "language",
+    value_name = "LANGUAGE",
+    value_enum,
+    default_value_t = Language::English,
+    help = "Language of the plaintext and its alphabet"
+  )]
+  language: Language,
+}
+
+impl CryptologyAlphabetArgs {
+  fn alphabet(&self) -> Result<Alphabet> {
+    match (&self.alphabet, &self.alphabet_file) {
+      (Some(symbols), _) => Ok(Alphabet::new(symbols)?),
+      (None, Some(path)) => {
+        let symbols =
+          fs::read_to_string(path).map_err(|source| CryptologyError::Open {
+            path: path.clone(),
+            source,
+          })?;
+        Ok(Alphabet::new(&symbols)?)
+      }
+      (None, None) => Ok(self.language.alphabet()),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub struct CryptologyTextArgs {
+  /// Remove everything but letters from the plaintext, including spaces
+  /// and line breaks.
+  #[arg(
+    long = "strip-non-alpha",
+    global = true,
+    help = "Remove everything but letters from the plaintext"
+  )]
+  strip_non_alpha: bool,
+
+  /// Uppercase the plaintext before it is encrypted.
+  #[arg(
+    long = "uppercase",
+    global = true,
+    help = "Uppercase the plaintext before it is encrypted"
+  )]
+  uppercase: bool,
+
+  /// Write the ciphertext in blocks of N symbols separated by spaces.
+  #[arg(
+    long = "group",
+    value_name = "N",
+    global = true,
+    help = "Write the ciphertext in blocks of N symbols"
+  )]
+  group: Option<NonZeroUsize>,
+}
+
+impl CryptologyTextArgs {
+  fn format(&self) -> TextFormat {
+    TextFormat::new(self.strip_non_alpha, self.uppercase, self.group)
+  }
+
+  /// The files of [`Command::get_files`], with the plaintext normalized
+  /// and the ciphertext grouped as configured.
+  fn get_files(
+    &self,
+    default_args: &CryptologyDefaultArgs,
+  ) -> std::result::Result<Files, CryptologyError> {
+    let (mut input, output) = Command::get_files(default_args)?;
+    let format = self.format();
+
+    if format.normalizes() {
+      let mut content = String::new();
+      input.read_to_string(&mut content)?;
+      input = Box::new(Cursor::new(format.normalize(&content).into_bytes()));
+    }
+
+    let output: Box<dyn Write> = match format.group {
+      Some(size) => Box::new(GroupWriter::new(output, size)),
+      None => output,
+    };
+    Ok((input, output))
+  }
+}
+
+#[derive(Debug, Parser)]
+pub struct CryptologySpacingArgs {
+  /// Split lines of plaintext without spaces into likely English words.
+  #[arg(
+    long = "restore-spacing",
+    global = true,
+    help = "Insert spaces between the likely words of the plaintext"
+  )]
+  restore_spacing: bool,
+}
+
+impl CryptologySpacingArgs {
+  /// The files of [`Command::get_files`], restoring the spacing of the
+  /// plaintext if configured. JSON reports are never changed.
+  fn get_files(
+    &self,
+    default_args: &CryptologyDefaultArgs,
+    format: OutputFormat,
+  ) -> std::result::Result<Files, CryptologyError> {
+    let (input, output) = Command::get_files(default_args)?;
+    let output: Box<dyn Write> = match (self.restore_spacing, format) {
+      (true, OutputFormat::Text) => Box::new(SpacingWriter::new(output)),
+      _ => output,
+    };
+    Ok((input, output))
+  }
+}
+
+#[derive(Debug, Parser)]
+pub struct CryptologySolverArgs {
+  /// Search the key with a stochastic solver.
+  #[arg(
+    long = "solver",
+    value_name = "SOLVER",
+    value_enum,
+    help = "Search the key with a stochastic solver"
+  )]
+  solver: Option<SolverKind>,
+
+  /// Number of candidate keys tried by each search.
+  #[arg(
+    long = "iterations",
+    value_name = "ITERATIONS",
+    default_value_t = 20_000,
+    help = "Number of candidate keys tried by each search"
+  )]
+  iterations: usize,
+
+  /// Number of independent searches. Defaults to the number of threads.
+  #[arg(
+    long = "restarts",
+    value_name = "RESTARTS",
+    help = "Number of independent searches, one per thread by default"
+  )]
+  restarts: Option<usize>,
+
+  /// Iterations without improvement before a search starts over.
+  #[arg(
+    long = "patience",
+    value_name = "PATIENCE",
+    help = "Iterations without improvement before a search starts over"
+  )]
+  patience: Option<usize>,
+
+  /// Seed for reproducible searches.
+  #[arg(
+    long = "seed",
+    value_name = "SEED",
+    help = "Seed for reproducible searches"
+  )]
+  seed: Option<u64>,
+
+  /// Number of keys per generation of the genetic algorithm.
+  #[arg(
+    long = "population",
+    value_name = "POPULATION",
+    default_value_t = 100,
+    help = "Number of keys per generation of the genetic algorithm"
+  )]
+  population: usize,
+
+  /// Print the candidates tried, the best score so far and the
+  /// estimated remaining time to standard error.
+  #[arg(
+    long = "progress",
+    help = "Report the progress of the search on standard error"
+  )]
+  progress: bool,
+
+  /// Path to n-gram statistics, one n-gram and its count per line like
+  /// the quadgram files of practicalcryptography.com.
+  ///
+  /// If not provided, candidates are scored by English bigrams.
+  #[arg(
+    long = "ngrams",
+    value_name = "NGRAMS",
+    help = "Score candidates with n-gram statistics from a file"
+  )]
+  ngrams: Option<PathBuf>,
+}
+
+/// The wordlist of a dictionary attack and how its words are mangled,
+/// the same for every cracker that takes one.
+#[derive(Debug, Parser)]
+pub struct CryptologyDictionaryArgs {
+  /// Path to a wordlist whose words are tried as keys first.
+  #[arg(
+    short = 'w',
+    long = "wordlist",
+    value_name = "WORDLIST",
+    help = "Try the words of a wordlist as keys before cracking"
+  )]
+  wordlist: Option<PathBuf>,
+
+  /// Case changes of every word that are tried as well.
+  #[arg(
+    long = "case",
+    value_name = "RULES",
+    value_enum,
+    value_delimiter = ',',
+    requires = "wordlist",
+    help = "Case changes of the words that are tried as well"
+  )]
+  case: Vec<CaseRule>,
+
+  /// Try every combination of leet substitutions of every word.
+  #[arg(
+    long = "leet",
+    requires = "wordlist",
+    help = "Try the leet spellings of the words as well"
+  )]
+  leet: bool,
+
+  /// Most digits appended to every word, all numbers up to them.
+  #[arg(
+    long = "digits",
+    value_name = "DIGITS",
+    default_value_t = 0,
+    requires = "wordlist",
+    help = "Most digits appended to the words"
+  )]
+  digits: usize,
+
+  /// Confidence at which no further words are tried.
+  #[arg(
+    long = "stop-at",
+    value_name = "CONFIDENCE",
+    default_value_t = 0.9,
+    help = "Confidence at which no further words are tried"
+  )]
+  stop_at: f32,
+}
+
+impl CryptologyDictionaryArgs {
+  fn read_wordlist(&self) -> Result<Option<String>> {
+    let read = |path: &PathBuf| {
+      fs::read_to_string(path).map_err(|source| CryptologyError::Open {
+        path: path.clone(),
+        source,
+      })
+    };
+    Ok(self.wordlist.as_ref().map(read).transpose()?)
+  }
+}
+
+impl From<&CryptologyDictionaryArgs> for DictionaryAttackConfig {
+  fn from(value: &CryptologyDictionaryArgs) -> Self {
+    let mangling = Mangling::new(value.case.clone(), value.leet, value.digits);
+    Self::new(mangling, Some(value.stop_at), 1)
+  }
 }
 
 #[derive(Debug, Subcommand)]
@@ -112,43 +927,156 @@ pub enum Command {
   ///
   /// Input can be provided from a file or standard input, and
   /// output can be directed to a file or standard output.
-  #[command(name = "frequency-analysis", visible_aliases = ["freq", "fa"])]
+  #[command(
+    name = "frequency-analysis",
+    visible_aliases = ["freq", "fa"],
+    args_conflicts_with_subcommands = true
+  )]
   FrequencyAnalysis {
+    #[command(subcommand)]
+    command: Option<FrequencyCommand>,
+
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
+
+    /// Analyse every file below a directory instead of the input, with
+    /// a summary per file and one of all files together.
+    #[arg(
+      short = 'r',
+      long = "recursive",
+      value_name = "DIR",
+      conflicts_with_all = ["input", "input_glob"],
+      help = "Summarize every file below a directory"
+    )]
+    recursive: Option<PathBuf>,
+
+    /// Analyse windows of N letters and mark the windows whose index of
+    /// coincidence or chi-square score deviates from the rest.
+    #[arg(
+      short = 'w',
+      long = "window",
+      value_name = "N",
+      conflicts_with = "recursive",
+      help = "Analyse windows of N letters and mark deviating windows"
+    )]
+    window: Option<NonZeroUsize>,
+
+    /// Number of letters between the starts of two windows, the window
+    /// size by default.
+    #[arg(
+      short = 's',
+      long = "step",
+      value_name = "M",
+      requires = "window",
+      help = "Start a window every M letters, the window size by default"
+    )]
+    step: Option<NonZeroUsize>,
+
+    /// Add a bar chart column of the relative frequencies.
+    #[arg(
+      long = "histogram",
+      value_name = "HISTOGRAM",
+      help = "Add a bar chart column of the relative frequencies"
+    )]
+    histogram: bool,
+
+    /// Write the frequencies as an SVG bar chart to a file.
+    #[arg(
+      long = "svg",
+      value_name = "SVG",
+      conflicts_with_all = ["recursive", "window"],
+      help = "Write the frequencies as an SVG bar chart to a file"
+    )]
+    svg: Option<PathBuf>,
+
+    /// Order of the letters.
+    #[arg(
+      long = "sort-by",
+      value_name = "SORT_BY",
+      value_enum,
+      default_value_t = FrequencySort::Count,
+      help = "Order the letters by letter or by count"
+    )]
+    sort_by: FrequencySort,
+
+    /// Only print the N most frequent letters.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      help = "Only print the N most frequent letters"
+    )]
+    top: Option<usize>,
+
+    /// Only print letters that occur at least this often.
+    #[arg(
+      long = "min-count",
+      value_name = "MIN_COUNT",
+      default_value_t = 0,
+      help = "Only print letters that occur at least this often"
+    )]
+    min_count: usize,
   },
 
-  /// Perform an XOR operation on two readable input streams
+  /// Perform an XOR operation on two or more readable input streams
   ///
   /// Input can be provided from a file or standard input, and
   /// output can be directed to a file or standard output.
   #[command(name = "xor")]
   Xor {
-    /// Path to the alpha input file.
+    /// Path to the alpha input file, `-` for standard input.
     #[arg(
       short = 'a',
       long = "alpha",
       value_name = "ALPHA",
-      help = "Specify the alpha input file"
+      required_unless_present_any = ["alpha_value", "inputs"],
+      help = "Specify the alpha input file, - for stdin"
     )]
-    alpha: PathBuf,
+    alpha: Option<PathBuf>,
 
-    /// Path to the beta input file.
+    /// Path to the beta input file, `-` for standard input.
     #[arg(
       short = 'b',
       long = "beta",
       value_name = "BETA",
-      help = "Specify the beta input file"
+      required_unless_present_any = ["beta_value", "inputs"],
+      help = "Specify the beta input file, - for stdin"
     )]
-    beta: PathBuf,
+    beta: Option<PathBuf>,
 
-    /// Path to the output file for saving results.
-    ///
-    /// If not provided, outputs to standard output.
+    /// Alpha given inline as hex string.
     #[arg(
-      short = 'o',
-      long = "output",
-      value_name = "OUTPUT",
+      long = "alpha-value",
+      value_name = "HEX",
+      conflicts_with = "alpha",
+      help = "Specify the alpha as inline hex"
+    )]
+    alpha_value: Option<String>,
+
+    /// Beta given inline as hex string.
+    #[arg(
+      long = "beta-value",
+      value_name = "HEX",
+      conflicts_with = "beta",
+      help = "Specify the beta as inline hex"
+    )]
+    beta_value: Option<String>,
+
+    /// Further input files that are XORed with alpha and beta.
+    #[arg(
+      short = 'i',
+      long = "input",
+      value_name = "INPUT",
+      help = "Specify another input file, repeat for more"
+    )]
+    inputs: Vec<PathBuf>,
+
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
       help = "Specify the output file for saving result."
     )]
     output: Option<PathBuf>,
@@ -156,7 +1084,7 @@ pub enum Command {
     /// Treat alpha as raw hex string
     #[arg(
       short = 'r',
-      long = "raw",
+      long = "raw-alpha",
       value_name = "RAW ALPHA",
       help = "Treats the alpha as raw hex"
     )]
@@ -165,11 +1093,99 @@ pub enum Command {
     /// Treat beta as raw hex string
     #[arg(
       short = 'y',
-      long = "raw",
+      long = "raw-beta",
       value_name = "RAW BETA",
       help = "Treats the beta as raw hex"
     )]
     raw_beta: bool,
+
+    /// Treat the further inputs as raw hex strings
+    #[arg(
+      long = "raw-inputs",
+      value_name = "RAW INPUTS",
+      help = "Treats the further inputs as raw hex"
+    )]
+    raw_inputs: bool,
+
+    /// Byte used to pad shorter inputs to the length of the longest.
+    #[arg(
+      long = "pad-byte",
+      value_name = "BYTE",
+      default_value_t = 0,
+      help = "Byte used to pad shorter inputs"
+    )]
+    pad_byte: u8,
+
+    /// Side on which shorter inputs are padded.
+    #[arg(
+      long = "pad-side",
+      value_name = "SIDE",
+      value_enum,
+      default_value_t = PadSide::Right,
+      help = "Pad shorter inputs on the left or the right"
+    )]
+    pad_side: PadSide,
+
+    /// Truncate all inputs to the shortest one instead of padding.
+    #[arg(
+      long = "truncate",
+      value_name = "TRUNCATE",
+      conflicts_with = "pad_byte",
+      help = "Truncate all inputs to the shortest one"
+    )]
+    truncate: bool,
+
+    /// Print the result as text, with unprintable bytes as dots.
+    #[arg(
+      long = "to-ascii",
+      value_name = "TO_ASCII",
+      help = "Print the result as text instead of hex"
+    )]
+    to_ascii: bool,
+
+    /// Write the bytes of the result as they are, for piping.
+    #[arg(
+      long = "binary-output",
+      value_name = "BINARY_OUTPUT",
+      conflicts_with = "to_ascii",
+      help = "Write the result as raw bytes instead of hex"
+    )]
+    binary_output: bool,
+  },
+
+  /// Print a shell completion script
+  ///
+  /// The script is generated from the current command line, so
+  /// packagers can install it during the build, for example with
+  /// `cryptology completions zsh > _cryptology`.
+  #[command(name = "completions")]
+  Completions {
+    /// Path to the output file for saving the script.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Shell to generate the completions for.
+    #[arg(value_name = "SHELL", value_enum, help = "Shell to complete")]
+    shell: Shell,
+  },
+
+  /// Write man pages for the tool and all of its subcommands
+  ///
+  /// Every subcommand gets its own page, named like
+  /// `cryptology-decrypt-caesar.1`. The paths of the written pages are
+  /// printed.
+  #[command(name = "manpages")]
+  Manpages {
+    /// Directory the pages are written to, created if missing.
+    #[arg(value_name = "DIR", help = "Directory to write the pages to")]
+    dir: PathBuf,
   },
 
   /// Display and parse an input as a hexadecimal string
@@ -198,245 +1214,4662 @@ pub enum Command {
       help = "Print the result as ASCII"
     )]
     to_ascii: bool,
-  },
 
-  /// Encrypt text using a specified cipher.
-  #[command(name = "encrypt", visible_aliases = ["enc", "e"])]
-  Encrypt {
-    #[command(subcommand)]
-    cipher: EncryptCipher,
-  },
+    /// Print an xxd-style dump with offsets and an ASCII gutter
+    #[arg(
+      short = 'd',
+      long = "dump",
+      value_name = "DUMP",
+      conflicts_with = "to_ascii",
+      help = "Print a dump with offsets, grouped bytes and ASCII"
+    )]
+    dump: bool,
 
-  /// Decrypt text using a specified cipher.
-  #[command(name = "decrypt", visible_aliases = ["dec", "d"])]
-  Decrypt {
-    #[command(subcommand)]
-    cipher: DecryptCipher,
+    /// Number of bytes per line of the dump
+    #[arg(
+      short = 'c',
+      long = "columns",
+      value_name = "COLUMNS",
+      default_value_t = NonZeroUsize::new(16).unwrap(),
+      help = "Number of bytes per line of the dump"
+    )]
+    columns: NonZeroUsize,
+
+    /// Skip this many bytes of the input
+    #[arg(
+      long = "offset",
+      value_name = "OFFSET",
+      default_value_t = 0,
+      help = "Skip this many bytes of the input"
+    )]
+    offset: usize,
+
+    /// Only use this many bytes after the offset
+    #[arg(
+      long = "length",
+      value_name = "LENGTH",
+      help = "Only use this many bytes after the offset"
+    )]
+    length: Option<usize>,
   },
-}
 
-#[derive(Debug, Subcommand)]
-pub enum EncryptCipher {
-  /// Use the Caesar cipher for encryption.
-  #[command(name = "caesar", visible_alias = "c")]
-  Caesar {
+  /// Extract runs of printable ASCII from binary input
+  ///
+  /// Prints every run of at least the minimum length on its own line,
+  /// which is often the first step before analysing a binary file.
+  #[command(name = "strings")]
+  Strings {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
+
+    /// Shortest run of printable characters that is printed
+    #[arg(
+      short = 'n',
+      long = "min-length",
+      value_name = "MIN_LENGTH",
+      default_value_t = 4,
+      help = "Shortest run of printable characters that is printed"
+    )]
+    min_length: usize,
+
+    /// Encoding of the characters
+    #[arg(
+      short = 'e',
+      long = "encoding",
+      value_name = "ENCODING",
+      value_enum,
+      default_value_t = StringEncoding::Ascii,
+      help = "Encoding of the characters"
+    )]
+    encoding: StringEncoding,
+
+    /// Print the byte offset of every string in hex
+    #[arg(
+      long = "offsets",
+      value_name = "OFFSETS",
+      help = "Print the byte offset of every string in hex"
+    )]
+    offsets: bool,
   },
 
-  /// Use the Vigenere cipher for encryption.
-  #[command(name = "vigenere", visible_alias = "v")]
-  Vigenere {
+  /// Detect the cipher of the input and crack it in one step
+  ///
+  /// The ciphers are tried in the order their statistics suggest until
+  /// one yields a confident plaintext. The best plaintext is printed
+  /// after the cipher and the key that produced it.
+  #[command(name = "solve")]
+  Solve {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
 
-    #[command(flatten)]
-    key: CryptologyEncryptKeyArg,
+    /// Ciphers that are tried, all of them by default
+    #[arg(
+      short = 'm',
+      long = "methods",
+      value_name = "METHODS",
+      value_enum,
+      value_delimiter = ',',
+      help = "Ciphers that are tried, all of them by default"
+    )]
+    methods: Vec<SolveMethod>,
   },
 
-  /// Use the One Time Pad cipher for encryption.
-  #[command(name = "one-time-pad", visible_alias = "otp")]
-  OneTimePad {
+  /// Try every key of several cheap ciphers and rank all candidates
+  ///
+  /// All Caesar shifts, affine keys, atbash and rail fences up to a
+  /// number of rails are tried on text, all single-byte XOR keys on hex
+  /// or binary input. The candidates of all ciphers are ranked together
+  /// by their confidence.
+  #[command(name = "brute")]
+  Brute {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
 
+    /// Ciphers that are tried, all that fit the input by default
+    #[arg(
+      short = 'm',
+      long = "methods",
+      value_name = "METHODS",
+      value_enum,
+      value_delimiter = ',',
+      help = "Ciphers that are tried, all that fit the input by default"
+    )]
+    methods: Vec<BruteMethod>,
+
+    /// Most rails of the rail fence.
+    #[arg(
+      long = "rails",
+      value_name = "RAILS",
+      default_value_t = 10,
+      help = "Most rails of the rail fence"
+    )]
+    rails: usize,
+
+    /// Number of candidates printed.
+    #[arg(
+      short = 't',
+      long = "top",
+      value_name = "N",
+      default_value_t = NonZeroUsize::new(10).unwrap(),
+      help = "Number of candidates printed"
+    )]
+    top: NonZeroUsize,
+  },
+
+  /// Extract a message hidden in the letters of a cover text
+  ///
+  /// Takes the letter at a position of every word or line, such as the
+  /// first letters of the lines of an acrostic. A list of positions is
+  /// repeated as a pattern over the words or lines.
+  #[command(name = "null-cipher", visible_alias = "null")]
+  NullCipher {
     #[command(flatten)]
-    key: CryptologyEncryptKeyArg,
+    default_args: CryptologyDefaultArgs,
 
-    /// Treat input as raw hex string
+    /// Whether the letters are taken from words or lines
     #[arg(
-      short = 'r',
-      long = "raw-input",
-      value_name = "RAW INPUT",
-      help = "Treats the input as raw hex"
+      short = 'u',
+      long = "unit",
+      value_name = "UNIT",
+      value_enum,
+      default_value_t = NullUnit::Word,
+      help = "Take the letters from words or lines"
     )]
-    raw_input: bool,
+    unit: NullUnit,
 
-    /// Treat key as raw hex string
+    /// One-based positions of the letters, repeated over the units
     #[arg(
-      short = 'y',
-      long = "raw-key",
-      value_name = "RAW KEY",
-      help = "Treats the key as raw hex"
+      short = 'p',
+      long = "positions",
+      value_name = "POSITIONS",
+      value_delimiter = ',',
+      default_value = "1",
+      help = "Positions of the letters, like 1,2,3"
+    )]
+    positions: Vec<usize>,
+
+    /// Take a letter from every nth word or line only
+    #[arg(
+      short = 'e',
+      long = "every",
+      value_name = "EVERY",
+      default_value_t = 1,
+      help = "Take a letter from every nth word or line only"
+    )]
+    every: usize,
+
+    /// Count the positions from the last letter
+    #[arg(
+      long = "from-end",
+      help = "Count the positions from the last letter"
     )]
-    raw_key: bool,
+    from_end: bool,
   },
-}
 
-#[derive(Debug, Subcommand)]
-pub enum DecryptCipher {
-  /// Use monoalphabetic substitution cipher for decryption.
-  #[command(name = "monoalphabetic-substitution", visible_aliases = ["monosub", "ms"])]
-  MonoalphabeticSubstitution {
+  /// Hide messages in cover texts and find hidden ones
+  #[command(name = "stego")]
+  Stego {
+    #[command(subcommand)]
+    command: StegoCommand,
+  },
+
+  /// Find words and phrases formed by the letters of the input
+  ///
+  /// Every line is a multiset of letters. Phrases that use all of them
+  /// are printed, or in partial mode also those that leave letters over,
+  /// which helps with jumbled fragments of a transposition.
+  #[command(name = "anagram")]
+  Anagram {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
+
+    /// Path to a wordlist, common English words by default
+    #[arg(
+      short = 'd',
+      long = "dictionary",
+      value_name = "DICTIONARY",
+      help = "Wordlist the words are taken from"
+    )]
+    dictionary: Option<PathBuf>,
+
+    /// Also print phrases that leave letters unused, with the unused
+    /// letters in brackets
+    #[arg(
+      short = 'p',
+      long = "partial",
+      value_name = "PARTIAL",
+      help = "Also print phrases that leave letters unused"
+    )]
+    partial: bool,
+
+    /// Most words of a phrase
+    #[arg(
+      short = 'w',
+      long = "max-words",
+      value_name = "WORDS",
+      default_value_t = 3,
+      help = "Most words of a phrase"
+    )]
+    max_words: usize,
+
+    /// Shortest word of a phrase
+    #[arg(
+      long = "min-length",
+      value_name = "LETTERS",
+      default_value_t = 1,
+      help = "Shortest word of a phrase"
+    )]
+    min_length: usize,
+
+    /// Most phrases printed per line of the input
+    #[arg(
+      short = 'l',
+      long = "limit",
+      value_name = "LIMIT",
+      default_value_t = 50,
+      help = "Most phrases printed per line of the input"
+    )]
+    limit: usize,
   },
 
-  /// Use the Caesar cipher for decryption.
-  #[command(name = "caesar", visible_alias = "c")]
-  Caesar {
+  /// Print the statistics that tell how much a text looks like English
+  ///
+  /// Reports the chi-square distance, index of coincidence, bigram
+  /// score, n-gram fitness, entropy and share of dictionary words, so
+  /// candidate decryptions can be compared objectively.
+  #[command(name = "score")]
+  Score {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
+
+    /// Path to n-gram statistics for the fitness, like the quadgram
+    /// files of practicalcryptography.com
+    #[arg(
+      long = "ngrams",
+      value_name = "NGRAMS",
+      help = "N-gram statistics the fitness is computed with"
+    )]
+    ngrams: Option<PathBuf>,
+
+    /// Path to a wordlist, common English words by default
+    #[arg(
+      short = 'd',
+      long = "dictionary",
+      value_name = "DICTIONARY",
+      help = "Wordlist the words are looked up in"
+    )]
+    dictionary: Option<PathBuf>,
   },
 
-  /// Use the Vigenere cipher for decryption.
-  #[command(name = "vigenere", visible_alias = "v")]
-  Vigenere {
+  /// Count coincidences of the input with shifted copies of itself
+  ///
+  /// Peaks at multiples of a shift reveal the period of a
+  /// polyalphabetic cipher such as Vigenere.
+  #[command(name = "autocorrelation", visible_alias = "ac")]
+  Autocorrelation {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
 
+    /// Largest shift to count coincidences for
+    #[arg(
+      short = 'm',
+      long = "max-shift",
+      value_name = "MAX_SHIFT",
+      default_value_t = 20,
+      help = "Largest shift to count coincidences for"
+    )]
+    max_shift: usize,
+  },
+
+  /// Guess the encoding of an input
+  ///
+  /// Scores the input as hex, base64, binary data and plain ASCII,
+  /// or decodes it with the most likely encoding.
+  #[command(name = "detect-encoding")]
+  DetectEncoding {
     #[command(flatten)]
-    key: CryptologyDecryptKeyArg,
+    default_args: CryptologyDefaultArgs,
 
-    /// Specify the key length if known.
+    /// Decode the input with the most likely encoding
     #[arg(
       short = 'n',
-      long = "key-length",
-      value_name = "KEY_LENGTH",
-      help = "The key length if known"
+      long = "normalize",
+      value_name = "NORMALIZE",
+      help = "Print the decoded bytes instead of the guesses"
     )]
-    key_length: Option<u8>,
+    normalize: bool,
 
-    /// Specify the maximum length for the key. Defaults to 20.
+    /// Analyse every file below a directory instead of the input, with
+    /// a summary per file and one of all files together.
     #[arg(
-      short = 'l',
-      long = "max-key-length",
-      value_name = "MAX_KEY_LENGTH",
-      help = "Specify the maximum length for the key. 20 by default"
+      short = 'r',
+      long = "recursive",
+      value_name = "DIR",
+      conflicts_with_all = ["input", "input_glob"],
+      help = "Summarize every file below a directory"
     )]
-    max_key_length: Option<u8>,
+    recursive: Option<PathBuf>,
   },
 
-  /// Use the One-Time-Pad cipher for decryption.
-  #[command(name = "one-time-pad", visible_alias = "otp")]
-  OneTimePad {
+  /// Encrypt or decrypt hex blocks with AES-128
+  ///
+  /// Every 16 byte block is processed on its own. The trace prints the
+  /// state matrix after each operation to check calculations by hand.
+  #[command(name = "aes")]
+  Aes {
     #[command(flatten)]
     default_args: CryptologyDefaultArgs,
 
-    #[command(flatten)]
-    key: CryptologyDecryptKeyArg,
+    /// Key as 32 hex digits.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present = "key_file",
+      conflicts_with = "key_file",
+      help = "Key as 32 hex digits"
+    )]
+    key: Option<String>,
 
-    /// Treat input as raw hex string
+    /// Path to a file with the key as hex digits, or its 16 bytes.
     #[arg(
-      short = 'r',
-      long = "raw-input",
-      value_name = "RAW INPUT",
-      help = "Treats the input as raw hex"
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the key from a file, as hex or binary"
     )]
-    raw_input: bool,
+    key_file: Option<PathBuf>,
 
-    /// Treat key as raw hex string
+    /// Decrypts instead of encrypting.
+    #[arg(short = 'd', long = "decrypt", help = "Decrypt the input")]
+    decrypt: bool,
+
+    /// Prints the state after every step of every round.
     #[arg(
-      short = 'y',
-      long = "raw-key",
-      value_name = "RAW KEY",
-      help = "Treats the key as raw hex"
+      short = 't',
+      long = "trace",
+      help = "Print the state matrix after every step"
     )]
-    raw_key: bool,
+    trace: bool,
   },
-}
-
-impl Command {
-  pub fn execute(&self) -> Result<()> {
-    match self {
-      Command::FrequencyAnalysis { default_args } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        FrequencyAnalyzer::analyze(&mut input, &mut output)?;
-        Ok(())
-      }
-      Command::Xor { output, .. } => {
-        let config = self.into();
-        let mut output = Self::create_output(output);
-        Xor::xor(config, &mut output)
-      }
-      Command::Encrypt { cipher } => cipher.execute(),
-      Command::Decrypt { cipher } => cipher.execute(),
-      Command::Hex { default_args, .. } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        let config = self.into();
-        Hex::parse(&mut input, &mut output, config)?;
-        Ok(())
-      }
-    }
-  }
-}
 
-impl EncryptCipher {
-  pub fn execute(&self) -> Result<()> {
-    match self {
-      EncryptCipher::Caesar { .. } => {
-        todo!()
-      }
-      EncryptCipher::Vigenere { default_args, .. } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        let config = self.into();
-        Vigenere::encrypt(&mut input, &mut output, config)
-      }
-      EncryptCipher::OneTimePad { default_args, .. } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        let mut config = self.into();
-        OneTimePad::encrypt(&mut input, &mut output, &mut config)?;
-        Ok(())
-      }
-    }
-  }
-}
+  /// Encrypt with DES and inspect its key schedule and weaknesses
+  #[command(name = "des")]
+  Des {
+    #[command(subcommand)]
+    command: DesCommand,
+  },
 
-impl DecryptCipher {
-  pub fn execute(&self) -> Result<()> {
-    match self {
-      DecryptCipher::MonoalphabeticSubstitution { default_args } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        MonoalphabeticSubstition::analyze(&mut input, &mut output)?;
-        Ok(())
-      }
-      DecryptCipher::Caesar { default_args } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        Caesar::decrypt(&mut input, &mut output)
-      }
-      DecryptCipher::Vigenere { default_args, .. } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        let config = self.into();
-        Vigenere::decrypt(&mut input, &mut output, config)
-      }
-      DecryptCipher::OneTimePad { default_args, .. } => {
-        let (mut input, mut output) = Command::get_files(default_args);
-        let mut config = self.into();
-        OneTimePad::decrypt(&mut input, &mut output, &mut config)?;
-        Ok(())
-      }
-    }
-  }
-}
+  /// Find repeated ciphertext blocks, which reveal ECB mode
+  ///
+  /// Equal plaintext blocks encrypt to equal ciphertext blocks under
+  /// ECB, so any repetition at an aligned offset is a strong hint.
+  #[command(name = "detect-ecb")]
+  DetectEcb {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
 
-impl Command {
+    /// Block size of the cipher in bytes.
+    #[arg(
+      short = 'b',
+      long = "block-size",
+      value_name = "BLOCK_SIZE",
+      default_value_t = ecb::BLOCK_SIZE,
+      help = "Block size of the cipher in bytes"
+    )]
+    block_size: usize,
+
+    /// Reads the ciphertext as hex instead of raw bytes.
+    #[arg(short = 'x', long = "hex", help = "Read the ciphertext as hex")]
+    hex: bool,
+  },
+
+  /// Encrypt the input in ECB and CBC mode to show what ECB leaks
+  ///
+  /// A toy 128-bit block cipher encrypts the input under the same key
+  /// in both modes. Structured input such as repeated text keeps its
+  /// repetitions under ECB but not under CBC.
+  #[command(name = "ecb-demo")]
+  EcbDemo {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Key as 32 hex digits, random if not provided.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      help = "Key as 32 hex digits"
+    )]
+    key: Option<String>,
+
+    /// Initialization vector of CBC as 32 hex digits, random if not
+    /// provided.
+    #[arg(
+      long = "iv",
+      value_name = "IV",
+      help = "CBC initialization vector as 32 hex digits"
+    )]
+    iv: Option<String>,
+
+    /// Seed for a reproducible key and IV in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for a reproducible, insecure key and IV"
+    )]
+    seed: Option<u64>,
+  },
+
+  /// Solve a substitution or Vigenère cipher in an interactive terminal UI
+  ///
+  /// The plaintext preview, its bigram score and the letter frequencies
+  /// update with every change of the key. Tab switches between the two
+  /// ciphers. The final key and plaintext are written on exit.
+  #[command(name = "workbench")]
+  Workbench {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Cipher the workbench starts with.
+    #[arg(
+      short = 'm',
+      long = "mode",
+      value_enum,
+      value_name = "MODE",
+      default_value_t = WorkbenchMode::Substitution,
+      help = "Cipher the workbench starts with"
+    )]
+    mode: WorkbenchMode,
+  },
+
+  /// Convert between binary, octal, decimal and hex representations
+  ///
+  /// Byte streams are converted byte by byte, while integer mode
+  /// converts the whole input as a single number of arbitrary size.
+  #[command(name = "radix")]
+  Radix {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Base of the input
+    #[arg(
+      short = 'f',
+      long = "from",
+      value_name = "BASE",
+      value_enum,
+      default_value_t = Base::Raw,
+      help = "Base of the input"
+    )]
+    from: Base,
+
+    /// Base of the output
+    #[arg(
+      short = 't',
+      long = "to",
+      value_name = "BASE",
+      value_enum,
+      default_value_t = Base::Hex,
+      help = "Base of the output"
+    )]
+    to: Base,
+
+    /// Treat the input as a single big integer
+    #[arg(
+      short = 'n',
+      long = "integer",
+      value_name = "INTEGER",
+      help = "Convert the input as a single integer instead of bytes"
+    )]
+    integer: bool,
+  },
+
+  /// Reorder and manipulate the bits and bytes of an input
+  ///
+  /// The operations are applied in the given order, e.g. to undo the
+  /// byte order or bit tricks of a puzzle before XORing or decoding it.
+  #[command(name = "bytes")]
+  Bytes {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Operations applied to the bytes in order
+    #[arg(
+      value_name = "OPERATION",
+      value_enum,
+      required = true,
+      help = "Operations applied to the bytes in order"
+    )]
+    operations: Vec<ByteOperation>,
+
+    /// Number of bits rotl and rotr rotate every byte by
+    #[arg(
+      short = 'b',
+      long = "bits",
+      value_name = "BITS",
+      default_value_t = 1,
+      help = "Number of bits rotl and rotr rotate every byte by"
+    )]
+    bits: u32,
+
+    /// Base of the input
+    #[arg(
+      short = 'f',
+      long = "from",
+      value_name = "BASE",
+      value_enum,
+      default_value_t = Base::Raw,
+      help = "Base of the input"
+    )]
+    from: Base,
+
+    /// Base of the output
+    #[arg(
+      short = 't',
+      long = "to",
+      value_name = "BASE",
+      value_enum,
+      default_value_t = Base::Hex,
+      help = "Base of the output"
+    )]
+    to: Base,
+  },
+
+  /// Generate a random key, e.g. a one-time pad
+  ///
+  /// The key is drawn from the random number generator of the
+  /// operating system.
+  #[command(name = "keygen")]
+  Keygen {
+    /// Path to the output file for saving the key.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Number of random bytes or characters.
+    #[arg(
+      short = 'n',
+      long = "length",
+      value_name = "LENGTH",
+      help = "Number of random bytes of the key"
+    )]
+    length: usize,
+
+    /// Representation of the key.
+    #[arg(
+      short = 'f',
+      long = "format",
+      value_name = "FORMAT",
+      value_enum,
+      default_value_t = KeygenFormat::Hex,
+      help = "Representation of the key"
+    )]
+    format: KeygenFormat,
+
+    /// Seed for reproducible keys in exercises and tests.
+    ///
+    /// Seeded keys are predictable and must not protect real secrets.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for reproducible, insecure keys"
+    )]
+    seed: Option<u64>,
+  },
+
+  /// Check whether two ciphertexts were encrypted with the same pad
+  ///
+  /// The XOR of two ciphertexts sharing a pad is the XOR of their
+  /// plaintexts, which is far from random for English text.
+  #[command(name = "check-reuse")]
+  CheckReuse {
+    /// Path to the first hex ciphertext, `-` for standard input.
+    #[arg(
+      short = 'a',
+      long = "alpha",
+      value_name = "ALPHA",
+      help = "Specify the first hex ciphertext, - for stdin"
+    )]
+    alpha: PathBuf,
+
+    /// Path to the second hex ciphertext, `-` for standard input.
+    #[arg(
+      short = 'b',
+      long = "beta",
+      value_name = "BETA",
+      help = "Specify the second hex ciphertext, - for stdin"
+    )]
+    beta: PathBuf,
+
+    /// Path to the output file for saving the result.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Share of plaintext-like XOR bytes from which reuse is reported.
+    #[arg(
+      short = 't',
+      long = "threshold",
+      value_name = "THRESHOLD",
+      default_value_t = REUSE_THRESHOLD,
+      help = "Score from which the pad is reported as reused"
+    )]
+    threshold: f32,
+  },
+
+  /// Group ciphertexts by the pad they were probably encrypted with
+  ///
+  /// The hex ciphertexts are read one per line. Every pair that likely
+  /// shares a pad is reported and connected pairs form a group, whose
+  /// ciphertexts can be passed on to `decrypt many-time-pad`.
+  #[command(name = "detect-reuse")]
+  DetectReuse {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Share of plaintext-like XOR bytes from which reuse is reported.
+    #[arg(
+      short = 't',
+      long = "threshold",
+      value_name = "THRESHOLD",
+      default_value_t = REUSE_THRESHOLD,
+      help = "Score from which the pad is reported as reused"
+    )]
+    threshold: f32,
+
+    /// Writes only the ciphertexts of the group with this index.
+    #[arg(
+      short = 'g',
+      long = "group",
+      value_name = "GROUP",
+      help = "Write only the ciphertexts of this group, one per line"
+    )]
+    group: Option<usize>,
+  },
+
+  /// Derive the key of a cipher from a plaintext and its ciphertext
+  ///
+  /// The ciphertext is read from the input, as hex for XOR and the
+  /// one-time pad. Keys that repeat are reduced to their shortest
+  /// period, and an error tells if the texts do not fit the cipher.
+  #[command(name = "recover-key")]
+  RecoverKey {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Cipher the ciphertext was encrypted with
+    #[arg(
+      short = 'c',
+      long = "cipher",
+      value_name = "CIPHER",
+      value_enum,
+      default_value_t = RecoverCipher::Caesar,
+      help = "Cipher the ciphertext was encrypted with"
+    )]
+    cipher: RecoverCipher,
+
+    /// Path to the known plaintext of the ciphertext
+    #[arg(
+      short = 'p',
+      long = "plaintext",
+      value_name = "PLAINTEXT",
+      help = "Path to the known plaintext of the ciphertext"
+    )]
+    plaintext: PathBuf,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Slide a known phrase over a ciphertext and rank where it fits
+  ///
+  /// At every offset the crib implies key material, which has to repeat
+  /// for Vigenere and XOR. For Enigma no letter of the crib may lie on
+  /// itself. The offsets that fit are printed best first.
+  #[command(name = "crib")]
+  Crib {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Known plaintext that occurs somewhere in the message
+    #[arg(
+      short = 'c',
+      long = "crib",
+      value_name = "CRIB",
+      help = "Known plaintext that occurs somewhere in the message"
+    )]
+    crib: String,
+
+    /// Cipher the ciphertext was encrypted with, its constraints decide
+    /// where the crib fits
+    #[arg(
+      long = "cipher",
+      value_name = "CIPHER",
+      value_enum,
+      default_value_t = CribCipher::Vigenere,
+      help = "Cipher whose constraints the crib has to satisfy"
+    )]
+    cipher: CribCipher,
+
+    /// Longest repeating key of Vigenere and XOR, which the crib has to
+    /// cover twice
+    #[arg(
+      short = 'l',
+      long = "max-key-length",
+      value_name = "MAX_KEY_LENGTH",
+      default_value_t = 20,
+      help = "Longest repeating key that is considered"
+    )]
+    max_key_length: usize,
+
+    /// Only print the N best offsets.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      help = "Only print the N best offsets"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Recover a keystream from known plaintext and apply it to other
+  /// ciphertexts
+  ///
+  /// The hex ciphertexts are read one per line. The keystream under the
+  /// known plaintext of the target is printed as hex, followed by what
+  /// it decrypts in every other ciphertext, which works for any stream
+  /// cipher whose keystream was reused.
+  #[command(name = "keystream")]
+  Keystream {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Index of the ciphertext whose plaintext is partly known.
+    #[arg(
+      short = 't',
+      long = "target",
+      value_name = "INDEX",
+      default_value_t = 0,
+      help = "Index of the ciphertext whose plaintext is partly known"
+    )]
+    target: usize,
+
+    /// Known plaintext of the target.
+    #[arg(
+      short = 'k',
+      long = "known",
+      value_name = "PLAINTEXT",
+      help = "Known plaintext of the target"
+    )]
+    known: String,
+
+    /// Format of the known plaintext: ascii text, hex, base64 or raw
+    /// binary.
+    #[arg(
+      long = "known-format",
+      value_name = "FORMAT",
+      value_enum,
+      default_value_t = KeyFormat::Ascii,
+      help = "Read the known plaintext as ascii, hex, base64 or binary"
+    )]
+    known_format: KeyFormat,
+
+    /// Byte offset of the known plaintext in the target.
+    #[arg(
+      long = "offset",
+      value_name = "OFFSET",
+      default_value_t = 0,
+      help = "Byte offset of the known plaintext in the target"
+    )]
+    offset: usize,
+
+    /// Byte offsets of the other ciphertexts the keystream is applied
+    /// at, the offset of the known plaintext by default.
+    #[arg(
+      short = 'a',
+      long = "apply-offset",
+      value_name = "OFFSET",
+      help = "Offset the keystream is applied at, repeat for several"
+    )]
+    apply_offset: Vec<usize>,
+  },
+
+  /// Check whether a key or pad is as random as it has to be
+  ///
+  /// Reports the entropy and byte bias of the key and warns about
+  /// repeated blocks, regions of low entropy and keys that are printable
+  /// text, which is what makes attacks on reused or weak pads succeed.
+  #[command(name = "keycheck")]
+  KeyCheck {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key_format: CryptologyKeyFormatArg,
+
+    /// Size of the blocks that are checked for repetitions.
+    #[arg(
+      short = 'b',
+      long = "block-size",
+      value_name = "BYTES",
+      default_value_t = 16,
+      help = "Size of the blocks that are checked for repetitions"
+    )]
+    block_size: usize,
+
+    /// Size of the windows whose entropy is measured.
+    #[arg(
+      short = 'w',
+      long = "window",
+      value_name = "BYTES",
+      default_value_t = 64,
+      help = "Size of the windows whose entropy is measured"
+    )]
+    window: usize,
+  },
+
+  /// Estimate the entropy of passwords, one per line
+  ///
+  /// Every password is split into dictionary words, which may be
+  /// capitalized or written in leetspeak, and single characters, so that
+  /// the bits an attacker has to guess are lowest. The bits of random
+  /// characters from the same character classes are printed alongside.
+  #[command(name = "password-entropy")]
+  PasswordEntropy {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to a wordlist ordered by frequency, common passwords and
+    /// English words by default
+    #[arg(
+      short = 'd',
+      long = "dictionary",
+      value_name = "DICTIONARY",
+      help = "Wordlist ordered by frequency the words are looked up in"
+    )]
+    dictionary: Option<PathBuf>,
+  },
+
+  /// Expand a wordlist into candidate keys for dictionary attacks
+  ///
+  /// Every base word is written with the selected case changes and leet
+  /// spellings, each of them followed by every number of up to the given
+  /// digits. Words outside the length limits are dropped and every word
+  /// is written once.
+  #[command(name = "wordlist")]
+  Wordlist {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Case changes whose words are added
+    #[arg(
+      short = 'c',
+      long = "case",
+      value_name = "RULES",
+      value_enum,
+      value_delimiter = ',',
+      help = "Case changes whose words are added"
+    )]
+    case: Vec<CaseRule>,
+
+    /// Add every combination of leet substitutions
+    #[arg(
+      short = 'l',
+      long = "leet",
+      help = "Add every combination of leet substitutions"
+    )]
+    leet: bool,
+
+    /// Most digits appended to every word, all numbers up to them
+    #[arg(
+      short = 'd',
+      long = "digits",
+      value_name = "DIGITS",
+      default_value_t = 0,
+      help = "Most digits appended to every word"
+    )]
+    digits: usize,
+
+    /// Fewest characters of a word that is written.
+    #[arg(
+      long = "min-length",
+      value_name = "LENGTH",
+      default_value_t = 0,
+      help = "Fewest characters of a word that is written"
+    )]
+    min_length: usize,
+
+    /// Most characters of a word that is written.
+    #[arg(
+      long = "max-length",
+      value_name = "LENGTH",
+      help = "Most characters of a word that is written"
+    )]
+    max_length: Option<usize>,
+  },
+
+  /// Hash the input with a toy Merkle–Damgård construction
+  ///
+  /// The padded message is split into blocks that are compressed into
+  /// a chaining value one after another, which can be traced per block.
+  #[command(name = "toyhash")]
+  ToyHash {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Size of the blocks and of the digest in bytes.
+    #[arg(
+      short = 'b',
+      long = "block-size",
+      value_name = "BLOCK_SIZE",
+      default_value_t = 8,
+      help = "Size of the blocks and the digest in bytes"
+    )]
+    block_size: usize,
+
+    /// Compression function of the construction.
+    #[arg(
+      short = 'c',
+      long = "compression",
+      value_name = "COMPRESSION",
+      value_enum,
+      default_value_t = Compression::Mix,
+      help = "Compression function applied to every block"
+    )]
+    compression: Compression,
+
+    /// Initial chaining value as hex with the length of a block.
+    #[arg(
+      long = "iv",
+      value_name = "IV",
+      help = "Initial chaining value as hex"
+    )]
+    iv: Option<String>,
+
+    /// Print the chaining value after every block.
+    #[arg(
+      short = 't',
+      long = "trace",
+      value_name = "TRACE",
+      help = "Print the chaining value after every block"
+    )]
+    trace: bool,
+  },
+
+  /// Compute the MD5, SHA-1 or SHA-256 digest of the input
+  ///
+  /// Input can be provided from a file or standard input. Additional
+  /// files are hashed one per line together with their path.
+  #[command(name = "hash")]
+  Hash {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Digest algorithm.
+    #[arg(
+      short = 'a',
+      long = "algorithm",
+      value_name = "ALGORITHM",
+      value_enum,
+      default_value_t = HashAlgorithm::Sha256,
+      help = "Digest algorithm"
+    )]
+    algorithm: HashAlgorithm,
+
+    /// Representation of the digest.
+    #[arg(
+      short = 'f',
+      long = "format",
+      value_name = "FORMAT",
+      value_enum,
+      default_value_t = DigestFormat::Hex,
+      help = "Representation of the digest"
+    )]
+    format: DigestFormat,
+
+    /// Files to hash instead of the input.
+    #[arg(value_name = "FILES", help = "Files to hash instead of the input")]
+    files: Vec<PathBuf>,
+  },
+
+  /// Compute or verify the HMAC of the input
+  ///
+  /// Supports the same digest algorithms as the `hash` command.
+  #[command(name = "hmac")]
+  Hmac {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Key of the HMAC.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present_any = ["key_file", "key_env"],
+      conflicts_with_all = ["key_file", "key_env"],
+      help = "Specify the key"
+    )]
+    key: Option<String>,
+
+    /// Path to a file whose bytes are the key.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      conflicts_with = "key_env",
+      help = "Read the key from a file"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Environment variable that holds the key as text.
+    #[arg(
+      long = "key-env",
+      value_name = "VAR",
+      help = "Read the key from an environment variable"
+    )]
+    key_env: Option<String>,
+
+    /// Digest algorithm.
+    #[arg(
+      short = 'a',
+      long = "algorithm",
+      value_name = "ALGORITHM",
+      value_enum,
+      default_value_t = HashAlgorithm::Sha256,
+      help = "Digest algorithm"
+    )]
+    algorithm: HashAlgorithm,
+
+    /// Representation of the HMAC.
+    #[arg(
+      short = 'f',
+      long = "format",
+      value_name = "FORMAT",
+      value_enum,
+      default_value_t = DigestFormat::Hex,
+      help = "Representation of the HMAC"
+    )]
+    format: DigestFormat,
+
+    /// Expected HMAC, which fails the command if it does not match.
+    #[arg(
+      short = 'v',
+      long = "verify",
+      value_name = "MAC",
+      help = "Verify the input against this HMAC"
+    )]
+    verify: Option<String>,
+  },
+
+  /// Compute a CRC-32, Adler-32 or Fletcher checksum of the input
+  ///
+  /// A CRC is linear, so four appended bytes can force any CRC-32,
+  /// which is why it cannot authenticate a message.
+  #[command(name = "checksum")]
+  Checksum {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Checksum algorithm.
+    #[arg(
+      short = 'a',
+      long = "algorithm",
+      value_name = "ALGORITHM",
+      value_enum,
+      default_value_t = ChecksumAlgorithm::Crc32,
+      help = "Checksum algorithm"
+    )]
+    algorithm: ChecksumAlgorithm,
+
+    /// CRC-32 as hex that the input is forged to have.
+    #[arg(
+      long = "forge",
+      value_name = "TARGET",
+      help = "Append four bytes that force this CRC-32"
+    )]
+    forge: Option<String>,
+  },
+
+  /// Reverse toy hash digests with a rainbow table over a dictionary
+  ///
+  /// The hex digests are read one per line. Longer chains store fewer
+  /// entries but need more hashing per lookup.
+  #[command(name = "rainbow")]
+  Rainbow {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the candidate plaintexts, one per line.
+    #[arg(
+      short = 'd',
+      long = "dictionary",
+      value_name = "DICTIONARY",
+      help = "Candidate plaintexts, one per line"
+    )]
+    dictionary: PathBuf,
+
+    /// Number of hashes per chain, where 1 stores every candidate.
+    #[arg(
+      short = 'l',
+      long = "chain-length",
+      value_name = "CHAIN_LENGTH",
+      default_value_t = 100,
+      help = "Number of hashes per chain"
+    )]
+    chain_length: usize,
+
+    /// Block size of the toy hash in bytes.
+    #[arg(
+      short = 'b',
+      long = "block-size",
+      value_name = "BLOCK_SIZE",
+      default_value_t = 8,
+      help = "Block size of the toy hash in bytes"
+    )]
+    block_size: usize,
+
+    /// Compression function of the toy hash.
+    #[arg(
+      short = 'c',
+      long = "compression",
+      value_name = "COMPRESSION",
+      value_enum,
+      default_value_t = Compression::Mix,
+      help = "Compression function of the toy hash"
+    )]
+    compression: Compression,
+
+    /// Print the number of chains of the table.
+    #[arg(
+      short = 's',
+      long = "stats",
+      value_name = "STATS",
+      help = "Print the size of the table"
+    )]
+    stats: bool,
+  },
+
+  /// Encode or decode text with Bacon's cipher
+  ///
+  /// Letters are encoded as groups of five A/B symbols, which can
+  /// optionally be hidden in the letter case of a cover text.
+  #[command(name = "bacon")]
+  Bacon {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Decode the input instead of encoding it
+    #[arg(
+      short = 'd',
+      long = "decode",
+      value_name = "DECODE",
+      help = "Decode the input instead of encoding it"
+    )]
+    decode: bool,
+
+    /// Alphabet used for the codes
+    #[arg(
+      long = "variant",
+      value_name = "VARIANT",
+      value_enum,
+      default_value_t = BaconVariant::Complete,
+      help = "Use the 24- or 26-letter alphabet"
+    )]
+    variant: BaconVariant,
+
+    /// Path to a cover text whose letter case carries the encoding
+    #[arg(
+      short = 'c',
+      long = "cover",
+      value_name = "COVER",
+      help = "Hide the encoding in the letter case of a cover text"
+    )]
+    cover: Option<PathBuf>,
+
+    /// Read the symbols from the letter case of the input
+    #[arg(
+      long = "case",
+      value_name = "CASE",
+      help = "Decode the letter case of the input instead of A/B symbols"
+    )]
+    case: bool,
+  },
+
+  /// Spell text with the NATO phonetic alphabet or read it back
+  ///
+  /// Letters and digits become code words like Alfa Bravo One and
+  /// words are separated by a slash. Decoding ignores case and accepts
+  /// common variants such as Alpha, Juliet or Niner.
+  #[command(name = "nato")]
+  Nato {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Decode code words instead of spelling the input
+    #[arg(
+      short = 'd',
+      long = "decode",
+      value_name = "DECODE",
+      help = "Decode code words instead of spelling the input"
+    )]
+    decode: bool,
+  },
+
+  /// Generate, apply and recover linear feedback shift registers
+  #[command(name = "lfsr")]
+  Lfsr {
+    #[command(subcommand)]
+    command: LfsrCommand,
+  },
+
+  /// Factor an integer by trial division and Pollard's algorithms
+  ///
+  /// Every factor is reported with the method that found it and how
+  /// long that took, which shows which moduli are weak against which
+  /// method. A composite that no method splits is an error.
+  #[command(name = "factor")]
+  Factor {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Number to factor as decimal integer.
+    #[arg(value_name = "NUMBER", help = "Number to factor")]
+    number: BigUint,
+
+    /// Factorization method.
+    #[arg(
+      short = 'm',
+      long = "method",
+      value_name = "METHOD",
+      value_enum,
+      default_value_t = FactorMethod::Auto,
+      help = "Factorization method"
+    )]
+    method: FactorMethod,
+
+    /// Largest divisor tried by trial division.
+    #[arg(
+      long = "trial-bound",
+      value_name = "BOUND",
+      default_value_t = 10_000,
+      help = "Largest divisor tried by trial division"
+    )]
+    trial_bound: u32,
+
+    /// Smoothness bound of Pollard's p-1.
+    #[arg(
+      long = "smoothness-bound",
+      value_name = "BOUND",
+      default_value_t = 100_000,
+      help = "Smoothness bound of Pollard's p-1"
+    )]
+    smoothness_bound: u32,
+
+    /// Number of steps of Pollard's rho per polynomial.
+    #[arg(
+      long = "max-iterations",
+      value_name = "MAX_ITERATIONS",
+      default_value_t = 1_000_000,
+      help = "Number of steps of Pollard's rho per polynomial"
+    )]
+    max_iterations: usize,
+  },
+
+  /// Test numbers for primality or generate random primes.
+  #[command(name = "prime")]
+  Prime {
+    #[command(subcommand)]
+    command: PrimeCommand,
+  },
+
+  /// Textbook ElGamal over a small prime field.
+  #[command(name = "elgamal")]
+  Elgamal {
+    #[command(subcommand)]
+    command: ElgamalCommand,
+  },
+
+  /// Generate outputs of a linear congruential generator or recover
+  /// its parameters from consecutive outputs.
+  #[command(name = "lcg")]
+  Lcg {
+    #[command(subcommand)]
+    command: LcgCommand,
+  },
+
+  /// Attacks on textbook RSA.
+  #[command(name = "rsa")]
+  Rsa {
+    #[command(subcommand)]
+    command: RsaCommand,
+  },
+
+  /// Encrypt text using a specified cipher.
+  #[command(name = "encrypt", visible_aliases = ["enc", "e"])]
+  Encrypt {
+    #[command(flatten)]
+    text: CryptologyTextArgs,
+
+    #[command(subcommand)]
+    cipher: EncryptCipher,
+  },
+
+  /// Decrypt text using a specified cipher.
+  #[command(name = "decrypt", visible_aliases = ["dec", "d"])]
+  Decrypt {
+    #[command(flatten)]
+    spacing: CryptologySpacingArgs,
+
+    #[command(subcommand)]
+    cipher: DecryptCipher,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FrequencyCommand {
+  /// Compare the letter frequencies of the input to a reference text.
+  ///
+  /// Prints the difference of every letter and the Jensen-Shannon
+  /// divergence of both texts, which is close to 0 when a candidate
+  /// decryption matches the language of the reference.
+  #[command(name = "compare")]
+  Compare {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the reference text, such as a corpus of the language.
+    #[arg(
+      short = 'r',
+      long = "reference",
+      value_name = "REFERENCE",
+      help = "Reference text to compare the input to"
+    )]
+    reference: PathBuf,
+  },
+}
+
+impl FrequencyCommand {
+  pub fn execute(&self) -> Result<()> {
+    match self {
+      FrequencyCommand::Compare {
+        default_args,
+        reference,
+      } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let mut reference = Command::open_input(Some(reference))?;
+        FrequencyAnalyzer::compare(&mut input, &mut reference, &mut output)?;
+        Ok(())
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LfsrCommand {
+  /// Print the keystream of a register as hex or bits.
+  #[command(name = "keystream")]
+  Keystream {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Feedback taps, e.g. 16,14,13,11 for x^16 + x^14 + x^13 + x^11 + 1.
+    #[arg(
+      short = 't',
+      long = "taps",
+      value_name = "TAPS",
+      value_delimiter = ',',
+      required = true,
+      help = "Comma separated feedback taps"
+    )]
+    taps: Vec<usize>,
+
+    /// Initial state of the register as bits, first output bit first.
+    #[arg(
+      short = 's',
+      long = "state",
+      value_name = "STATE",
+      help = "Initial state of the register as bits"
+    )]
+    state: String,
+
+    /// Number of keystream bytes, or bits with --bits.
+    #[arg(
+      short = 'n',
+      long = "length",
+      value_name = "LENGTH",
+      help = "Number of keystream bytes to generate"
+    )]
+    length: usize,
+
+    /// Print the keystream as bits instead of hex.
+    #[arg(
+      short = 'b',
+      long = "bits",
+      value_name = "BITS",
+      help = "Print the keystream as bits and count length in bits"
+    )]
+    bits: bool,
+  },
+
+  /// XOR the input with the keystream of a register.
+  #[command(name = "xor")]
+  Xor {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Feedback taps, e.g. 16,14,13,11 for x^16 + x^14 + x^13 + x^11 + 1.
+    #[arg(
+      short = 't',
+      long = "taps",
+      value_name = "TAPS",
+      value_delimiter = ',',
+      required = true,
+      help = "Comma separated feedback taps"
+    )]
+    taps: Vec<usize>,
+
+    /// Initial state of the register as bits, first output bit first.
+    #[arg(
+      short = 's',
+      long = "state",
+      value_name = "STATE",
+      help = "Initial state of the register as bits"
+    )]
+    state: String,
+
+    /// Treat input as raw hex string
+    #[arg(
+      short = 'r',
+      long = "raw",
+      value_name = "RAW",
+      help = "Treats the input as raw hex"
+    )]
+    raw: bool,
+  },
+
+  /// Recover the shortest register from known keystream (Berlekamp-Massey).
+  #[command(name = "recover")]
+  Recover {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Read the keystream as bits instead of hex.
+    #[arg(
+      short = 'b',
+      long = "bits",
+      value_name = "BITS",
+      help = "Read the keystream as bits instead of hex"
+    )]
+    bits: bool,
+  },
+}
+
+impl LfsrCommand {
+  pub fn execute(&self) -> Result<()> {
+    let config = self.into();
+    match self {
+      LfsrCommand::Keystream { output, .. } => {
+        let mut output = Command::create_output(output)?;
+        Lfsr::keystream(&mut output, config)
+      }
+      LfsrCommand::Xor { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        Lfsr::xor(&mut input, &mut output, config)
+      }
+      LfsrCommand::Recover { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        Lfsr::recover_from_input(&mut input, &mut output, config)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StegoCommand {
+  /// Hide a message in the whitespace of a cover text, or extract it.
+  ///
+  /// The input is the cover text. With --message the message is hidden
+  /// in it, otherwise the hidden message is decoded. --detect reports
+  /// trailing whitespace and zero-width characters that give a hidden
+  /// message away.
+  #[command(name = "whitespace")]
+  Whitespace {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Hide the bits in trailing whitespace or zero-width characters.
+    #[arg(
+      short = 'm',
+      long = "method",
+      value_name = "METHOD",
+      value_enum,
+      default_value_t = WhitespaceMethod::Trailing,
+      help = "Hide the bits in trailing whitespace or zero-width characters"
+    )]
+    method: WhitespaceMethod,
+
+    /// Message hidden in the cover text.
+    #[arg(
+      short = 'e',
+      long = "message",
+      value_name = "MESSAGE",
+      conflicts_with = "detect",
+      help = "Hide the message in the cover text instead of decoding"
+    )]
+    message: Option<String>,
+
+    /// Report suspicious whitespace instead of decoding.
+    #[arg(
+      short = 'd',
+      long = "detect",
+      help = "Report suspicious whitespace instead of decoding"
+    )]
+    detect: bool,
+  },
+
+  /// Hide a file in the least significant bits of a PNG or BMP image, or
+  /// extract it.
+  ///
+  /// The input is the image. With --embed the image is written with the
+  /// file hidden in it, otherwise the hidden file is extracted. The bits
+  /// go into the color channels row by row, behind a 32-bit length.
+  #[command(name = "image")]
+  Image {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// File hidden in the image.
+    #[arg(
+      short = 'e',
+      long = "embed",
+      value_name = "PAYLOAD",
+      help = "Hide the file in the image instead of extracting"
+    )]
+    embed: Option<PathBuf>,
+
+    /// Extract every least significant bit without a length header.
+    #[arg(
+      short = 'r',
+      long = "raw",
+      conflicts_with = "embed",
+      help = "Extract every least significant bit without a length header"
+    )]
+    raw: bool,
+  },
+}
+
+impl StegoCommand {
+  pub fn execute(&self) -> Result<()> {
+    match self {
+      StegoCommand::Whitespace { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        WhitespaceStego::execute(&mut input, &mut output, config)
+      }
+      StegoCommand::Image { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        ImageStego::execute(&mut input, &mut output, config)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrimeCommand {
+  /// Test whether a number is probably prime with Miller–Rabin.
+  #[command(name = "test")]
+  Test {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Number to test as decimal integer.
+    #[arg(value_name = "NUMBER", help = "Number to test")]
+    number: BigUint,
+
+    /// Number of Miller–Rabin rounds with random bases.
+    #[arg(
+      short = 'r',
+      long = "rounds",
+      value_name = "ROUNDS",
+      default_value_t = 20,
+      help = "Number of Miller-Rabin rounds"
+    )]
+    rounds: usize,
+
+    /// Seed for reproducible bases in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for reproducible, insecure bases"
+    )]
+    seed: Option<u64>,
+  },
+
+  /// Generate a random probable prime of a given size.
+  #[command(name = "generate")]
+  Generate {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Number of bits of the prime.
+    #[arg(
+      short = 'b',
+      long = "bits",
+      value_name = "BITS",
+      help = "Number of bits of the prime"
+    )]
+    bits: u64,
+
+    /// Number of Miller–Rabin rounds with random bases.
+    #[arg(
+      short = 'r',
+      long = "rounds",
+      value_name = "ROUNDS",
+      default_value_t = 20,
+      help = "Number of Miller-Rabin rounds"
+    )]
+    rounds: usize,
+
+    /// Seed for reproducible primes in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for reproducible, insecure primes"
+    )]
+    seed: Option<u64>,
+  },
+}
+
+impl PrimeCommand {
+  pub fn execute(&self) -> Result<()> {
+    let config = self.into();
+    match self {
+      PrimeCommand::Test { output, number, .. } => {
+        let mut output = Command::create_output(output)?;
+        Prime::test(&mut output, number, config)
+      }
+      PrimeCommand::Generate { output, bits, .. } => {
+        let mut output = Command::create_output(output)?;
+        Prime::generate(&mut output, *bits, config)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ElgamalCommand {
+  /// Generate a safe prime, a generator and a key pair.
+  #[command(name = "keygen")]
+  Keygen {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Number of bits of the prime.
+    #[arg(
+      short = 'b',
+      long = "bits",
+      value_name = "BITS",
+      default_value_t = 32,
+      help = "Number of bits of the prime"
+    )]
+    bits: u64,
+
+    /// Seed for a reproducible key pair in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for a reproducible, insecure key pair"
+    )]
+    seed: Option<u64>,
+  },
+
+  /// Encrypt an integer message in 1..p.
+  #[command(name = "encrypt")]
+  Encrypt {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Safe prime modulus.
+    #[arg(
+      short = 'p',
+      long = "p",
+      value_name = "P",
+      help = "Safe prime modulus"
+    )]
+    p: BigUint,
+
+    /// Generator of the group.
+    #[arg(
+      short = 'g',
+      long = "g",
+      value_name = "G",
+      help = "Generator of the group"
+    )]
+    g: BigUint,
+
+    /// Public key h = g^x mod p.
+    #[arg(long = "h", value_name = "H", help = "Public key h = g^x mod p")]
+    h: BigUint,
+
+    /// Message as decimal integer.
+    #[arg(
+      short = 'm',
+      long = "message",
+      value_name = "MESSAGE",
+      help = "Message as decimal integer"
+    )]
+    message: BigUint,
+
+    /// Seed for a reproducible random exponent in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      help = "Seed for a reproducible, insecure exponent"
+    )]
+    seed: Option<u64>,
+  },
+
+  /// Decrypt a ciphertext pair.
+  #[command(name = "decrypt")]
+  Decrypt {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Safe prime modulus.
+    #[arg(
+      short = 'p',
+      long = "p",
+      value_name = "P",
+      help = "Safe prime modulus"
+    )]
+    p: BigUint,
+
+    /// Private key.
+    #[arg(short = 'x', long = "x", value_name = "X", help = "Private key")]
+    x: BigUint,
+
+    /// First component g^k of the ciphertext.
+    #[arg(
+      long = "c1",
+      value_name = "C1",
+      help = "First component of the ciphertext"
+    )]
+    c1: BigUint,
+
+    /// Second component m * h^k of the ciphertext.
+    #[arg(
+      long = "c2",
+      value_name = "C2",
+      help = "Second component of the ciphertext"
+    )]
+    c2: BigUint,
+  },
+
+  /// Show that multiplying two ciphertexts multiplies their plaintexts.
+  #[command(name = "malleability")]
+  Malleability {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Safe prime modulus.
+    #[arg(
+      short = 'p',
+      long = "p",
+      value_name = "P",
+      help = "Safe prime modulus"
+    )]
+    p: BigUint,
+
+    /// Generator of the group.
+    #[arg(
+      short = 'g',
+      long = "g",
+      value_name = "G",
+      help = "Generator of the group"
+    )]
+    g: BigUint,
+
+    /// Public key h = g^x mod p.
+    #[arg(long = "h", value_name = "H", help = "Public key h = g^x mod p")]
+    h: BigUint,
+
+    /// Private key.
+    #[arg(short = 'x', long = "x", value_name = "X", help = "Private key")]
+    x: BigUint,
+
+    /// First message.
+    #[arg(long = "m1", value_name = "M1", help = "First message")]
+    m1: BigUint,
+
+    /// Second message.
+    #[arg(long = "m2", value_name = "M2", help = "Second message")]
+    m2: BigUint,
+  },
+}
+
+impl ElgamalCommand {
+  pub fn execute(&self) -> Result<()> {
+    match self {
+      ElgamalCommand::Keygen { output, bits, seed } => {
+        let mut output = Command::create_output(output)?;
+        let (key, x) = match seed {
+          Some(seed) => {
+            Elgamal::keygen_with(&mut StdRng::seed_from_u64(*seed), *bits)?
+          }
+          None => Elgamal::keygen(*bits)?,
+        };
+        writeln!(output, "p = {}", key.p)?;
+        writeln!(output, "g = {}", key.g)?;
+        writeln!(output, "h = {}", key.h)?;
+        writeln!(output, "x = {x}")
+      }
+      ElgamalCommand::Encrypt {
+        output,
+        p,
+        g,
+        h,
+        message,
+        seed,
+      } => {
+        let mut output = Command::create_output(output)?;
+        let key = ElgamalPublicKey::new(p.clone(), g.clone(), h.clone());
+        let ciphertext = match seed {
+          Some(seed) => {
+            let mut rng = StdRng::seed_from_u64(*seed);
+            Elgamal::encrypt_with(&mut rng, &key, message)?
+          }
+          None => Elgamal::encrypt(&key, message)?,
+        };
+        ciphertext.write(&mut output)
+      }
+      ElgamalCommand::Decrypt {
+        output,
+        p,
+        x,
+        c1,
+        c2,
+      } => {
+        let mut output = Command::create_output(output)?;
+        let ciphertext = ElgamalCiphertext::new(c1.clone(), c2.clone());
+        let message = Elgamal::decrypt(p, x, &ciphertext)?;
+        writeln!(output, "m = {message}")
+      }
+      ElgamalCommand::Malleability {
+        output,
+        p,
+        g,
+        h,
+        x,
+        m1,
+        m2,
+      } => {
+        let mut output = Command::create_output(output)?;
+        let key = ElgamalPublicKey::new(p.clone(), g.clone(), h.clone());
+        Elgamal::malleability(&mut output, &key, x, (m1, m2))
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DesCommand {
+  /// Encrypt hex blocks of eight bytes.
+  #[command(name = "encrypt")]
+  Encrypt {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Key as 16 hex digits, including the parity bits.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present = "key_file",
+      conflicts_with = "key_file",
+      help = "Key as 16 hex digits"
+    )]
+    key: Option<String>,
+
+    /// Path to a file with the key as hex digits, or its 8 bytes.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the key from a file, as hex or binary"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Prints the halves, subkey and round function output of every
+    /// round.
+    #[arg(
+      short = 't',
+      long = "trace",
+      help = "Print the state after every round"
+    )]
+    trace: bool,
+  },
+
+  /// Decrypt hex blocks of eight bytes.
+  #[command(name = "decrypt")]
+  Decrypt {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Key as 16 hex digits, including the parity bits.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present = "key_file",
+      conflicts_with = "key_file",
+      help = "Key as 16 hex digits"
+    )]
+    key: Option<String>,
+
+    /// Path to a file with the key as hex digits, or its 8 bytes.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the key from a file, as hex or binary"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Prints the halves, subkey and round function output of every
+    /// round.
+    #[arg(
+      short = 't',
+      long = "trace",
+      help = "Print the state after every round"
+    )]
+    trace: bool,
+  },
+
+  /// Print the halves C and D and the subkey of every round.
+  #[command(name = "schedule")]
+  Schedule {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Key as 16 hex digits, including the parity bits.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      help = "Key as 16 hex digits"
+    )]
+    key: String,
+  },
+
+  /// Demonstrate the complementation property and the weak keys.
+  #[command(name = "properties")]
+  Properties {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Key as 16 hex digits, including the parity bits.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      help = "Key as 16 hex digits"
+    )]
+    key: String,
+
+    /// Plaintext block as 16 hex digits.
+    #[arg(
+      short = 'p',
+      long = "plaintext",
+      value_name = "PLAINTEXT",
+      default_value = "0123456789abcdef",
+      help = "Plaintext block as 16 hex digits"
+    )]
+    plaintext: String,
+  },
+}
+
+impl DesCommand {
+  pub fn execute(&self) -> Result<()> {
+    let config = self.into();
+    match self {
+      DesCommand::Encrypt { default_args, .. }
+      | DesCommand::Decrypt { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        Des::execute(&mut input, &mut output, config)
+      }
+      DesCommand::Schedule { output, .. } => {
+        Des::write_schedule(&mut Command::create_output(output)?, config)
+      }
+      DesCommand::Properties { output, .. } => {
+        Des::properties(&mut Command::create_output(output)?, config)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LcgCommand {
+  /// Print consecutive outputs of a generator, one per line.
+  #[command(name = "generate")]
+  Generate {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Modulus m of the generator.
+    #[arg(
+      short = 'm',
+      long = "modulus",
+      value_name = "MODULUS",
+      help = "Modulus of the generator"
+    )]
+    modulus: u64,
+
+    /// Multiplier a of the generator.
+    #[arg(
+      short = 'a',
+      long = "multiplier",
+      value_name = "MULTIPLIER",
+      help = "Multiplier of the generator"
+    )]
+    multiplier: u64,
+
+    /// Increment c of the generator.
+    #[arg(
+      short = 'c',
+      long = "increment",
+      value_name = "INCREMENT",
+      help = "Increment of the generator"
+    )]
+    increment: u64,
+
+    /// Initial state, which is not part of the output.
+    #[arg(
+      short = 's',
+      long = "seed",
+      value_name = "SEED",
+      help = "Initial state of the generator"
+    )]
+    seed: u64,
+
+    /// Number of outputs.
+    #[arg(
+      short = 'n',
+      long = "count",
+      value_name = "COUNT",
+      default_value_t = 10,
+      help = "Number of outputs"
+    )]
+    count: usize,
+  },
+
+  /// Recover modulus, multiplier and increment from consecutive outputs.
+  #[command(name = "recover")]
+  Recover {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Known modulus, which reduces the outputs needed from six to three.
+    #[arg(
+      short = 'm',
+      long = "modulus",
+      value_name = "MODULUS",
+      help = "Known modulus of the generator"
+    )]
+    modulus: Option<u64>,
+  },
+}
+
+impl LcgCommand {
+  pub fn execute(&self) -> Result<()> {
+    let config = self.into();
+    match self {
+      LcgCommand::Generate { output, .. } => {
+        let mut output = Command::create_output(output)?;
+        Lcg::generate(&mut output, config)
+      }
+      LcgCommand::Recover { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        Lcg::recover_from_input(&mut input, &mut output, config)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RsaCommand {
+  /// Recover plaintexts or private keys from weak RSA parameters.
+  #[command(name = "attack")]
+  Attack {
+    #[command(subcommand)]
+    attack: RsaAttackCommand,
+  },
+}
+
+impl RsaCommand {
+  pub fn execute(&self) -> Result<()> {
+    match self {
+      RsaCommand::Attack { attack } => attack.execute(),
+    }
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RsaAttackCommand {
+  /// Take the e-th root of a ciphertext whose plaintext power m^e did
+  /// not exceed the modulus.
+  #[command(name = "cube-root")]
+  CubeRoot {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Ciphertext as decimal integer.
+    #[arg(
+      short = 'c',
+      long = "ciphertext",
+      value_name = "CIPHERTEXT",
+      help = "Ciphertext as decimal integer"
+    )]
+    ciphertext: BigUint,
+
+    /// Public exponent.
+    #[arg(
+      short = 'e',
+      long = "exponent",
+      value_name = "EXPONENT",
+      default_value_t = 3,
+      help = "Public exponent"
+    )]
+    e: u32,
+  },
+
+  /// Combine two encryptions of the same message under one modulus
+  /// with coprime exponents.
+  #[command(name = "common-modulus")]
+  CommonModulus {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Shared modulus as decimal integer.
+    #[arg(
+      short = 'n',
+      long = "modulus",
+      value_name = "MODULUS",
+      help = "Shared modulus as decimal integer"
+    )]
+    n: BigUint,
+
+    /// First public exponent.
+    #[arg(long = "e1", value_name = "E1", help = "First public exponent")]
+    e1: BigUint,
+
+    /// Second public exponent.
+    #[arg(long = "e2", value_name = "E2", help = "Second public exponent")]
+    e2: BigUint,
+
+    /// Ciphertext under the first exponent.
+    #[arg(
+      long = "c1",
+      value_name = "C1",
+      help = "Ciphertext under the first exponent"
+    )]
+    c1: BigUint,
+
+    /// Ciphertext under the second exponent.
+    #[arg(
+      long = "c2",
+      value_name = "C2",
+      help = "Ciphertext under the second exponent"
+    )]
+    c2: BigUint,
+  },
+
+  /// Factor a modulus whose primes are close to each other.
+  #[command(name = "fermat")]
+  Fermat {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Modulus as decimal integer.
+    #[arg(
+      short = 'n',
+      long = "modulus",
+      value_name = "MODULUS",
+      help = "Modulus as decimal integer"
+    )]
+    n: BigUint,
+
+    /// Public exponent.
+    #[arg(
+      short = 'e',
+      long = "exponent",
+      value_name = "EXPONENT",
+      default_value_t = BigUint::from(65537u32),
+      help = "Public exponent"
+    )]
+    e: BigUint,
+
+    /// Ciphertext to decrypt with the recovered key.
+    #[arg(
+      short = 'c',
+      long = "ciphertext",
+      value_name = "CIPHERTEXT",
+      help = "Ciphertext to decrypt with the recovered key"
+    )]
+    ciphertext: Option<BigUint>,
+
+    /// Largest number of squares tried.
+    #[arg(
+      short = 'm',
+      long = "max-iterations",
+      value_name = "MAX_ITERATIONS",
+      default_value_t = 1_000_000,
+      help = "Largest number of squares tried"
+    )]
+    max_iterations: usize,
+  },
+
+  /// Recover a small private exponent from the continued fraction of
+  /// e / n.
+  #[command(name = "wiener")]
+  Wiener {
+    /// Path to the output file for saving results.
+    ///
+    /// If not provided, outputs to standard output.
+    #[arg(
+      short = 'o',
+      long = "output",
+      value_name = "OUTPUT",
+      help = "Specify the output file for saving result."
+    )]
+    output: Option<PathBuf>,
+
+    /// Modulus as decimal integer.
+    #[arg(
+      short = 'n',
+      long = "modulus",
+      value_name = "MODULUS",
+      help = "Modulus as decimal integer"
+    )]
+    n: BigUint,
+
+    /// Public exponent.
+    #[arg(
+      short = 'e',
+      long = "exponent",
+      value_name = "EXPONENT",
+      help = "Public exponent"
+    )]
+    e: BigUint,
+
+    /// Ciphertext to decrypt with the recovered key.
+    #[arg(
+      short = 'c',
+      long = "ciphertext",
+      value_name = "CIPHERTEXT",
+      help = "Ciphertext to decrypt with the recovered key"
+    )]
+    ciphertext: Option<BigUint>,
+  },
+}
+
+impl RsaAttackCommand {
+  pub fn execute(&self) -> Result<()> {
+    let attack = self.into();
+    let output = match self {
+      RsaAttackCommand::CubeRoot { output, .. }
+      | RsaAttackCommand::CommonModulus { output, .. }
+      | RsaAttackCommand::Fermat { output, .. }
+      | RsaAttackCommand::Wiener { output, .. } => output,
+    };
+    let mut output = Command::create_output(output)?;
+    RsaAttacks::execute(&mut output, attack)
+  }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EncryptCipher {
+  /// Use the Caesar cipher for encryption.
+  #[command(name = "caesar", visible_alias = "c")]
+  Caesar {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Number of positions every symbol is shifted forward.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "SHIFT",
+      required_unless_present = "key_file",
+      conflicts_with = "key_file",
+      help = "Number of positions every symbol is shifted forward"
+    )]
+    shift: Option<usize>,
+
+    /// Path to a text file with the shift.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the shift from a file"
+    )]
+    key_file: Option<PathBuf>,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Use the Vigenere cipher for encryption.
+  #[command(name = "vigenere", visible_alias = "v")]
+  Vigenere {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key: CryptologyEncryptKeyArg,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Use the One Time Pad cipher for encryption.
+  #[command(name = "one-time-pad", visible_alias = "otp")]
+  OneTimePad {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Key used for encryption. A random pad is generated without one.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      conflicts_with_all = ["generate_key", "key_file", "key_env"],
+      help = "Key used for encryption, a random pad if omitted"
+    )]
+    key: Option<String>,
+
+    /// Path to a file with the key. A text file holds the key itself,
+    /// without the line break at its end. The bytes of any other file
+    /// are the key and are passed on hex encoded.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      conflicts_with_all = ["generate_key", "key_env"],
+      help = "Read the key from a file, hex encoded if it is binary"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Environment variable that holds the key as text.
+    #[arg(
+      long = "key-env",
+      value_name = "VAR",
+      conflicts_with = "generate_key",
+      help = "Read the key from an environment variable"
+    )]
+    key_env: Option<String>,
+
+    /// Generate a random pad of the length of the message, which is also
+    /// done when no key is given.
+    #[arg(
+      short = 'g',
+      long = "generate-key",
+      value_name = "GENERATE_KEY",
+      help = "Generate a random pad as long as the message"
+    )]
+    generate_key: bool,
+
+    /// Seed for a reproducible pad in exercises and tests.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      conflicts_with_all = ["key", "key_file", "key_env"],
+      help = "Seed for a reproducible, insecure pad"
+    )]
+    seed: Option<u64>,
+
+    /// File the generated pad is written to as hex, which `--key-file`
+    /// reads back as such.
+    ///
+    /// If not provided, the pad is written to standard error.
+    #[arg(
+      long = "key-output",
+      value_name = "KEY_OUTPUT",
+      help = "Write the generated pad as hex to a file instead of stderr"
+    )]
+    key_output: Option<PathBuf>,
+
+    /// Treat input as raw hex string
+    #[arg(
+      short = 'r',
+      long = "raw-input",
+      value_name = "RAW INPUT",
+      help = "Treats the input as raw hex"
+    )]
+    raw_input: bool,
+
+    #[command(flatten)]
+    key_format: CryptologyKeyFormatArg,
+
+    /// Only warn if the key is shorter than the message.
+    #[arg(
+      short = 'f',
+      long = "force",
+      value_name = "FORCE",
+      help = "Only warn if the key is shorter than the message"
+    )]
+    force: bool,
+  },
+
+  /// Use the ADFGVX or ADFGX cipher for encryption.
+  #[command(name = "adfgvx", visible_alias = "adfgx")]
+  Adfgvx {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keyword used to fill the Polybius square.
+    #[arg(
+      short = 'p',
+      long = "polybius-key",
+      value_name = "POLYBIUS_KEY",
+      help = "Keyword used to fill the Polybius square"
+    )]
+    polybius_key: String,
+
+    /// Keyword used for the columnar transposition.
+    #[command(flatten)]
+    key: CryptologyEncryptKeyArg,
+
+    /// Size of the Polybius square.
+    #[arg(
+      long = "variant",
+      value_name = "VARIANT",
+      value_enum,
+      default_value_t = AdfgvxVariant::Adfgvx,
+      help = "Use the 5x5 ADFGX or the 6x6 ADFGVX square"
+    )]
+    variant: AdfgvxVariant,
+  },
+
+  /// Use the Scytale cipher for encryption.
+  #[command(name = "scytale", visible_alias = "s")]
+  Scytale {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Number of letters around the rod.
+    #[arg(
+      short = 'd',
+      long = "diameter",
+      value_name = "DIAMETER",
+      help = "Number of letters around the rod"
+    )]
+    diameter: usize,
+  },
+
+  /// Use the multiplicative cipher for encryption.
+  ///
+  /// Every letter x is replaced by a * x mod 26. The multiplier must be
+  /// coprime to 26.
+  #[command(name = "multiplicative")]
+  Multiplicative {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Multiplier of the letters, coprime to 26.
+    #[arg(
+      short = 'a',
+      long = "multiplier",
+      value_name = "MULTIPLIER",
+      help = "Multiplier of the letters, coprime to 26"
+    )]
+    multiplier: usize,
+  },
+
+  /// Use the affine cipher for encryption.
+  ///
+  /// Every letter x is replaced by a * x + b mod 26. The multiplier must
+  /// be coprime to 26.
+  #[command(name = "affine")]
+  Affine {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Multiplier of the letters, coprime to the size of the alphabet.
+    #[arg(
+      short = 'a',
+      long = "multiplier",
+      value_name = "MULTIPLIER",
+      help = "Multiplier of the letters, coprime to the size of the alphabet"
+    )]
+    multiplier: usize,
+
+    /// Shift added to the letters.
+    #[arg(
+      short = 'b',
+      long = "shift",
+      value_name = "SHIFT",
+      help = "Shift added to the letters"
+    )]
+    shift: usize,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Use the rail fence cipher for encryption.
+  ///
+  /// The text is written in a zigzag over the rails and read off rail by
+  /// rail.
+  #[command(name = "rail-fence")]
+  RailFence {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Number of rails of the zigzag, at least 2.
+    #[arg(
+      short = 'r',
+      long = "rails",
+      value_name = "RAILS",
+      help = "Number of rails of the zigzag, at least 2"
+    )]
+    rails: usize,
+  },
+
+  /// Use the Cadenus cipher for encryption.
+  ///
+  /// Every block of 25 rows as wide as the key is transposed by the key
+  /// and its columns are rotated by their key letters. The last block is
+  /// filled with X.
+  #[command(name = "cadenus")]
+  Cadenus {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keyword that orders and rotates the columns.
+    #[command(flatten)]
+    key: CryptologyEncryptKeyArg,
+  },
+
+  /// Use a route cipher for encryption.
+  ///
+  /// The text is written row by row into a grid and read along a route,
+  /// such as a spiral. The last row is filled with X.
+  #[command(name = "route")]
+  Route {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Route along which the grid is read.
+    #[arg(
+      short = 'r',
+      long = "route",
+      value_name = "ROUTE",
+      value_enum,
+      default_value_t = RouteKind::Spiral,
+      help = "Route along which the grid is read"
+    )]
+    route: RouteKind,
+
+    /// Number of columns of the grid.
+    #[arg(
+      short = 'w',
+      long = "width",
+      value_name = "WIDTH",
+      help = "Number of columns of the grid"
+    )]
+    width: usize,
+  },
+
+  /// Use ROT47 for encryption.
+  ///
+  /// Rotates all printable ASCII characters, not only letters.
+  #[command(name = "rot47")]
+  Rot47 {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+  },
+
+  /// Use a book cipher for encryption.
+  #[command(name = "book-cipher", visible_alias = "book")]
+  BookCipher {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the key text the references point into.
+    #[arg(
+      short = 'k',
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Path to the key text"
+    )]
+    key_file: PathBuf,
+
+    /// Format of the references.
+    #[arg(
+      short = 'f',
+      long = "format",
+      value_name = "FORMAT",
+      value_enum,
+      default_value_t = BookReferenceFormat::Word,
+      help = "Reference words by index or by page, line and word"
+    )]
+    format: BookReferenceFormat,
+
+    /// Number of lines per page of the key text.
+    ///
+    /// If not provided, pages are only separated by form feeds.
+    #[arg(
+      short = 'p',
+      long = "lines-per-page",
+      value_name = "LINES_PER_PAGE",
+      help = "Number of lines per page of the key text"
+    )]
+    lines_per_page: Option<usize>,
+
+    /// Encode whole words instead of single letters.
+    #[arg(
+      short = 'w',
+      long = "words",
+      value_name = "WORDS",
+      help = "Encode whole words instead of single letters"
+    )]
+    words: bool,
+  },
+
+  /// Use monoalphabetic substitution cipher for encryption.
+  #[command(name = "monoalphabetic-substitution", visible_aliases = ["monosub", "ms"])]
+  MonoalphabeticSubstitution {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keyword used to mix the cipher alphabet.
+    #[arg(
+      short = 'k',
+      long = "keyword",
+      value_name = "KEYWORD",
+      required_unless_present_any = ["random", "map"],
+      help = "Keyword used to mix the cipher alphabet"
+    )]
+    keyword: Option<String>,
+
+    /// Generate a random permutation of the alphabet as key.
+    #[arg(
+      short = 'r',
+      long = "random",
+      value_name = "RANDOM",
+      conflicts_with = "keyword",
+      help = "Use a random permutation of the alphabet as key"
+    )]
+    random: bool,
+
+    /// Seed for a reproducible random key.
+    #[arg(
+      long = "seed",
+      value_name = "SEED",
+      requires = "random",
+      help = "Seed for a reproducible random key"
+    )]
+    seed: Option<u64>,
+
+    /// Path to a file the substitution map is written to.
+    ///
+    /// If not provided, a random key is written to standard error.
+    #[arg(
+      long = "key-output",
+      visible_alias = "map-output",
+      value_name = "KEY_OUTPUT",
+      help = "Specify the output file for the substitution map"
+    )]
+    key_output: Option<PathBuf>,
+
+    /// Path to a substitution map to encrypt with, one `plain -> cipher`
+    /// pair per line as written by `--key-output`.
+    #[arg(
+      short = 'm',
+      long = "map",
+      value_name = "MAP",
+      conflicts_with_all = ["keyword", "random"],
+      help = "Encrypt with a substitution map read from a file"
+    )]
+    map: Option<PathBuf>,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Use the columnar transposition cipher for encryption.
+  ///
+  /// Repeat the key to apply several transpositions, e.g. two keys
+  /// for the double transposition cipher.
+  #[command(name = "columnar-transposition", visible_aliases = ["columnar", "ct"])]
+  ColumnarTransposition {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keys used for the transpositions, applied in order.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present = "key_file",
+      conflicts_with = "key_file",
+      help = "Transposition key, repeat for double transposition"
+    )]
+    keys: Vec<String>,
+
+    /// Path to a text file with a key on every line, applied in order.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the keys from a file, one per line"
+    )]
+    key_file: Option<PathBuf>,
+  },
+
+  /// Use the Hagelin M-209 converter for encryption.
+  #[command(name = "m209", visible_alias = "m-209")]
+  M209 {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the file containing the pin and lug settings.
+    #[arg(
+      short = 's',
+      long = "settings",
+      value_name = "SETTINGS",
+      help = "Path to the pin and lug settings"
+    )]
+    settings: PathBuf,
+
+    /// Initial letters of the six key wheels.
+    #[arg(
+      short = 'm',
+      long = "indicator",
+      value_name = "INDICATOR",
+      default_value = "AAAAAA",
+      help = "Initial letters of the six key wheels"
+    )]
+    indicator: String,
+  },
+
+  /// Use repeating-key XOR on bytes for encryption.
+  #[command(name = "xor")]
+  Xor {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key: CryptologyEncryptKeyArg,
+
+    #[command(flatten)]
+    key_format: CryptologyKeyFormatArg,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DecryptCipher {
+  /// Use monoalphabetic substitution cipher for decryption.
+  #[command(name = "monoalphabetic-substitution", visible_aliases = ["monosub", "ms"])]
+  MonoalphabeticSubstitution {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    solver: CryptologySolverArgs,
+
+    /// Path to a wordlist for solving cryptograms by word patterns.
+    #[arg(
+      short = 'd',
+      long = "dictionary",
+      value_name = "DICTIONARY",
+      conflicts_with = "solver",
+      help = "Solve by matching word patterns against a wordlist"
+    )]
+    dictionary: Option<PathBuf>,
+
+    /// Path to a substitution map to decrypt with instead of recovering
+    /// one, one `cipher -> plain` pair per line as written by
+    /// `--map-output`.
+    #[arg(
+      short = 'm',
+      long = "map",
+      value_name = "MAP",
+      conflicts_with_all = ["solver", "dictionary"],
+      help = "Decrypt with a substitution map read from a file"
+    )]
+    map: Option<PathBuf>,
+
+    /// Path to a file the recovered substitution map is written to, so
+    /// it can be corrected by hand and applied with `--map`.
+    #[arg(
+      long = "map-output",
+      value_name = "MAP_OUTPUT",
+      help = "Write the recovered substitution map to a file"
+    )]
+    map_output: Option<PathBuf>,
+
+    /// Refine the recovered map with commands like `swap Q E` read from
+    /// standard input before the plaintext is written.
+    ///
+    /// The plaintext and its fitness are shown on standard error after
+    /// every command. The ciphertext has to be read from a file.
+    #[arg(
+      short = 'I',
+      long = "interactive",
+      requires = "input",
+      help = "Refine the recovered map interactively"
+    )]
+    interactive: bool,
+  },
+
+  /// Use the Caesar cipher for decryption.
+  #[command(name = "caesar", visible_alias = "c")]
+  Caesar {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+
+    /// Write the recovered shift of every line to a file.
+    #[arg(
+      long = "key-output",
+      value_name = "KEY_OUTPUT",
+      help = "Write the recovered shift of every line to a file"
+    )]
+    key_output: Option<PathBuf>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Use the Vigenere cipher for decryption.
+  #[command(name = "vigenere", visible_alias = "v")]
+  Vigenere {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key: CryptologyDecryptKeyArg,
+
+    /// Specify the key length if known.
+    #[arg(
+      short = 'n',
+      long = "key-length",
+      value_name = "KEY_LENGTH",
+      help = "The key length if known"
+    )]
+    key_length: Option<u8>,
+
+    /// Specify the maximum length for the key. Defaults to 20.
+    #[arg(
+      short = 'l',
+      long = "max-key-length",
+      value_name = "MAX_KEY_LENGTH",
+      help = "Specify the maximum length for the key. 20 by default"
+    )]
+    max_key_length: Option<u8>,
+
+    /// Print the key of every line before its plaintext.
+    #[arg(
+      long = "show-key",
+      value_name = "SHOW_KEY",
+      help = "Print the used or recovered key of every line"
+    )]
+    show_key: bool,
+
+    #[command(flatten)]
+    dictionary: CryptologyDictionaryArgs,
+
+    /// Known plaintext to search the keys for.
+    #[arg(
+      short = 'c',
+      long = "crib",
+      value_name = "CRIB",
+      help = "Report the keys that decrypt to a known plaintext"
+    )]
+    crib: Option<String>,
+
+    /// Print the key lengths tried, the best score so far and the
+    /// estimated remaining time to standard error.
+    #[arg(
+      long = "progress",
+      help = "Report the progress of the key length search on standard error"
+    )]
+    progress: bool,
+
+    /// Print the average column index of coincidence and the chi-square
+    /// score of every key length before the plaintext, marking the
+    /// lengths that were tried and the one that was chosen.
+    #[arg(
+      long = "diagnostics",
+      conflicts_with_all = ["key", "crib"],
+      help = "Print the key length table considered while cracking"
+    )]
+    diagnostics: bool,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+
+    /// Write the used or recovered key of every line to a file.
+    #[arg(
+      long = "key-output",
+      value_name = "KEY_OUTPUT",
+      help = "Write the used or recovered key of every line to a file"
+    )]
+    key_output: Option<PathBuf>,
+  },
+
+  /// Use the One-Time-Pad cipher for decryption.
+  #[command(name = "one-time-pad", visible_alias = "otp")]
+  OneTimePad {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key: CryptologyDecryptKeyArg,
+
+    /// Treat input as raw hex string
+    #[arg(
+      short = 'r',
+      long = "raw-input",
+      value_name = "RAW INPUT",
+      help = "Treats the input as raw hex"
+    )]
+    raw_input: bool,
+
+    #[command(flatten)]
+    key_format: CryptologyKeyFormatArg,
+
+    /// Only warn if the key is shorter than the message.
+    #[arg(
+      short = 'f',
+      long = "force",
+      value_name = "FORCE",
+      help = "Only warn if the key is shorter than the message"
+    )]
+    force: bool,
+  },
+
+  /// Recover messages encrypted with the same one-time pad.
+  ///
+  /// The input holds one hex ciphertext per line.
+  #[command(name = "many-time-pad", visible_alias = "mtp")]
+  ManyTimePad {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Index of the ciphertext to decrypt, the last one by default.
+    #[arg(
+      short = 't',
+      long = "target",
+      value_name = "TARGET",
+      conflicts_with = "all",
+      help = "Index of the ciphertext to decrypt, the last by default"
+    )]
+    target: Option<usize>,
+
+    /// Print the plaintexts of all ciphertexts.
+    #[arg(
+      short = 'a',
+      long = "all",
+      value_name = "ALL",
+      help = "Print the plaintexts of all ciphertexts"
+    )]
+    all: bool,
+
+    /// Print the recovered key as hex before the plaintexts.
+    #[arg(
+      long = "show-key",
+      value_name = "SHOW_KEY",
+      help = "Print the recovered key before the plaintexts"
+    )]
+    show_key: bool,
+
+    /// Write the recovered key as hex to a file.
+    #[arg(
+      long = "key-output",
+      value_name = "KEY_OUTPUT",
+      help = "Write the recovered key to a file"
+    )]
+    key_output: Option<PathBuf>,
+
+    /// File of known plaintext fragments, one `INDEX OFFSET TEXT` per line.
+    #[arg(
+      short = 'c',
+      long = "crib-file",
+      value_name = "CRIB_FILE",
+      help = "File of known plaintext lines: INDEX OFFSET TEXT"
+    )]
+    crib_file: Option<PathBuf>,
+
+    /// Characters whose key byte has a lower confidence are printed as `?`.
+    #[arg(
+      short = 'm',
+      long = "min-confidence",
+      value_name = "MIN_CONFIDENCE",
+      default_value_t = 0.5,
+      help = "Print characters below this confidence between 0 and 1 as ?"
+    )]
+    min_confidence: f32,
+
+    /// Wordlist used to complete partially recovered words.
+    #[arg(
+      short = 'w',
+      long = "wordlist",
+      value_name = "WORDLIST",
+      help = "Complete partially recovered words from a wordlist"
+    )]
+    wordlist: Option<PathBuf>,
+  },
+
+  /// Use the ADFGVX or ADFGX cipher for decryption.
+  #[command(name = "adfgvx", visible_alias = "adfgx")]
+  Adfgvx {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keyword used to fill the Polybius square.
+    #[arg(
+      short = 'p',
+      long = "polybius-key",
+      value_name = "POLYBIUS_KEY",
+      help = "Keyword used to fill the Polybius square"
+    )]
+    polybius_key: String,
+
+    /// Keyword used for the columnar transposition.
+    #[command(flatten)]
+    key: CryptologyRequiredDecryptKeyArg,
+
+    /// Size of the Polybius square.
+    #[arg(
+      long = "variant",
+      value_name = "VARIANT",
+      value_enum,
+      default_value_t = AdfgvxVariant::Adfgvx,
+      help = "Use the 5x5 ADFGX or the 6x6 ADFGVX square"
+    )]
+    variant: AdfgvxVariant,
+  },
+
+  /// Use the Scytale cipher for decryption.
+  #[command(name = "scytale", visible_alias = "s")]
+  Scytale {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Number of letters around the rod if known.
+    ///
+    /// If not provided, all plausible diameters are tried.
+    #[arg(
+      short = 'd',
+      long = "diameter",
+      value_name = "DIAMETER",
+      help = "Number of letters around the rod if known"
+    )]
+    diameter: Option<usize>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      conflicts_with_all = ["diameter"],
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Use the multiplicative cipher for decryption.
+  #[command(name = "multiplicative")]
+  Multiplicative {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Multiplier of the letters if known.
+    ///
+    /// If not provided, all 12 multipliers coprime to 26 are tried.
+    #[arg(
+      short = 'a',
+      long = "multiplier",
+      value_name = "MULTIPLIER",
+      help = "Multiplier of the letters if known"
+    )]
+    multiplier: Option<usize>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      conflicts_with_all = ["multiplier"],
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Use the affine cipher for decryption.
+  #[command(name = "affine")]
+  Affine {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Multiplier of the letters if known.
+    ///
+    /// If the key is not provided, every key is tried, 312 for `A..=Z`.
+    #[arg(
+      short = 'a',
+      long = "multiplier",
+      value_name = "MULTIPLIER",
+      requires = "shift",
+      help = "Multiplier of the letters if known"
+    )]
+    multiplier: Option<usize>,
+
+    /// Shift added to the letters if known.
+    #[arg(
+      short = 'b',
+      long = "shift",
+      value_name = "SHIFT",
+      requires = "multiplier",
+      help = "Shift added to the letters if known"
+    )]
+    shift: Option<usize>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      conflicts_with_all = ["multiplier", "shift"],
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    alphabet: CryptologyAlphabetArgs,
+  },
+
+  /// Use the rail fence cipher for decryption.
+  #[command(name = "rail-fence")]
+  RailFence {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Number of rails of the zigzag if known.
+    ///
+    /// If not provided, all numbers up to the maximum are tried.
+    #[arg(
+      short = 'r',
+      long = "rails",
+      value_name = "RAILS",
+      help = "Number of rails of the zigzag if known"
+    )]
+    rails: Option<usize>,
+
+    /// Most rails tried if the number is not known.
+    #[arg(
+      long = "max-rails",
+      value_name = "RAILS",
+      default_value_t = 10,
+      help = "Most rails tried if the number is not known"
+    )]
+    max_rails: usize,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      conflicts_with_all = ["rails"],
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Use the Cadenus cipher for decryption.
+  #[command(name = "cadenus")]
+  Cadenus {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keyword that orders and rotates the columns.
+    #[command(flatten)]
+    key: CryptologyRequiredDecryptKeyArg,
+  },
+
+  /// Use a route cipher for decryption.
+  ///
+  /// Without a route or width, every route and every width that fills
+  /// the grid is tried.
+  #[command(name = "route")]
+  Route {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Route along which the grid was read if known.
+    #[arg(
+      short = 'r',
+      long = "route",
+      value_name = "ROUTE",
+      value_enum,
+      help = "Route along which the grid was read if known"
+    )]
+    route: Option<RouteKind>,
+
+    /// Number of columns of the grid if known.
+    #[arg(
+      short = 'w',
+      long = "width",
+      value_name = "WIDTH",
+      help = "Number of columns of the grid if known"
+    )]
+    width: Option<usize>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+  },
+
+  /// Use ROT47 for decryption.
+  ///
+  /// Rotates all printable ASCII characters, not only letters.
+  #[command(name = "rot47")]
+  Rot47 {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+  },
+
+  /// Use a book cipher for decryption.
+  #[command(name = "book-cipher", visible_alias = "book")]
+  BookCipher {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the key text the references point into.
+    #[arg(
+      short = 'k',
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Path to the key text"
+    )]
+    key_file: PathBuf,
+
+    /// Number of lines per page of the key text.
+    ///
+    /// If not provided, pages are only separated by form feeds.
+    #[arg(
+      short = 'p',
+      long = "lines-per-page",
+      value_name = "LINES_PER_PAGE",
+      help = "Number of lines per page of the key text"
+    )]
+    lines_per_page: Option<usize>,
+
+    /// Decode whole words instead of single letters.
+    #[arg(
+      short = 'w',
+      long = "words",
+      value_name = "WORDS",
+      help = "Decode whole words instead of single letters"
+    )]
+    words: bool,
+  },
+
+  /// Use the columnar transposition cipher for decryption.
+  ///
+  /// Repeat the key to apply several transpositions, e.g. two keys
+  /// for the double transposition cipher.
+  #[command(name = "columnar-transposition", visible_aliases = ["columnar", "ct"])]
+  ColumnarTransposition {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Keys used for the transpositions, applied in order.
+    #[arg(
+      short = 'k',
+      long = "key",
+      value_name = "KEY",
+      required_unless_present_any = ["key_file", "key_length", "wordlist"],
+      conflicts_with = "key_file",
+      help = "Transposition key, repeat for double transposition"
+    )]
+    keys: Vec<String>,
+
+    /// Path to a text file with a key on every line, applied in order.
+    #[arg(
+      long = "key-file",
+      value_name = "KEY_FILE",
+      help = "Read the keys from a file, one per line"
+    )]
+    key_file: Option<PathBuf>,
+
+    /// Number of columns if the key is unknown.
+    #[arg(
+      short = 'n',
+      long = "key-length",
+      value_name = "KEY_LENGTH",
+      conflicts_with_all = ["keys", "key_file"],
+      help = "Search the column order for the given number of columns"
+    )]
+    key_length: Option<usize>,
+
+    #[command(flatten)]
+    solver: CryptologySolverArgs,
+
+    #[command(flatten)]
+    dictionary: CryptologyDictionaryArgs,
+  },
+
+  /// Use the Hagelin M-209 converter for decryption.
+  #[command(name = "m209", visible_alias = "m-209")]
+  M209 {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    /// Path to the file containing the pin and lug settings.
+    #[arg(
+      short = 's',
+      long = "settings",
+      value_name = "SETTINGS",
+      help = "Path to the pin and lug settings"
+    )]
+    settings: PathBuf,
+
+    /// Initial letters of the six key wheels.
+    #[arg(
+      short = 'm',
+      long = "indicator",
+      value_name = "INDICATOR",
+      default_value = "AAAAAA",
+      help = "Initial letters of the six key wheels"
+    )]
+    indicator: String,
+  },
+
+  /// Use repeating-key XOR on bytes for decryption.
+  ///
+  /// Without a key, the key is recovered from English plaintext.
+  #[command(name = "xor")]
+  Xor {
+    #[command(flatten)]
+    default_args: CryptologyDefaultArgs,
+
+    #[command(flatten)]
+    key: CryptologyDecryptKeyArg,
+
+    #[command(flatten)]
+    key_format: CryptologyKeyFormatArg,
+
+    /// Largest key size tried when breaking the cipher without a key.
+    #[arg(
+      short = 'l',
+      long = "max-key-size",
+      value_name = "MAX_KEY_SIZE",
+      default_value_t = 40,
+      help = "Largest key size tried when no key is given"
+    )]
+    max_key_size: usize,
+
+    /// Write the used or recovered key as hex to a file.
+    #[arg(
+      long = "key-output",
+      value_name = "KEY_OUTPUT",
+      help = "Write the used or recovered key as hex to a file"
+    )]
+    key_output: Option<PathBuf>,
+
+    /// Print the N best candidates with their keys instead of only the
+    /// best plaintext.
+    #[arg(
+      long = "top",
+      value_name = "N",
+      conflicts_with_all = ["key", "key_file", "key_env"],
+      help = "Print the N best candidates instead of only the best"
+    )]
+    top: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    dictionary: CryptologyDictionaryArgs,
+  },
+}
+
+impl Command {
+  pub fn execute(&self, format: OutputFormat) -> Result<()> {
+    match self {
+      Command::FrequencyAnalysis {
+        command: Some(command),
+        ..
+      } => command.execute(),
+      Command::FrequencyAnalysis {
+        default_args,
+        recursive: Some(dir),
+        ..
+      } => {
+        let mut output = Self::create_output(&default_args.output)?;
+        Triage::frequency(&mut output, dir)
+      }
+      Command::DetectEncoding {
+        default_args,
+        recursive: Some(dir),
+        ..
+      } => {
+        let mut output = Self::create_output(&default_args.output)?;
+        Triage::encoding(&mut output, dir)
+      }
+      Command::FrequencyAnalysis {
+        default_args,
+        window: Some(window),
+        step,
+        ..
+      } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let step = step.unwrap_or(*window);
+        let config = WindowConfig::new(window.get(), step.get());
+        WindowAnalyzer::execute(&mut input, &mut output, config)?;
+        Ok(())
+      }
+      Command::FrequencyAnalysis {
+        default_args,
+        histogram,
+        svg,
+        sort_by,
+        top,
+        min_count,
+        ..
+      } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = FrequencyConfig {
+          histogram: *histogram,
+          sort_by: *sort_by,
+          top: *top,
+          min_count: *min_count,
+        };
+        let result =
+          FrequencyAnalyzer::execute(&mut input, &mut output, &config)?;
+        if let Some(path) = svg {
+          result.write_svg(&mut File::create(path)?, &config)?;
+        }
+        Ok(())
+      }
+      Command::Xor { output, .. } => {
+        let config: XorConfig = self.into();
+        let mut output = Self::create_output(output)?;
+        Ok(config.xor(&mut output)?)
+      }
+      Command::Bacon { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Bacon::execute(&mut input, &mut output, config)
+      }
+      Command::Anagram { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Anagram::execute(&mut input, &mut output, config)
+      }
+      Command::Nato { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Nato::execute(&mut input, &mut output, config)
+      }
+      Command::Autocorrelation { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Autocorrelation::execute(&mut input, &mut output, config)
+      }
+      Command::DetectEncoding { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        EncodingDetector::execute(&mut input, &mut output, config)
+      }
+      Command::Keygen { output, .. } => {
+        let config = self.into();
+        let mut output = Self::create_output(output)?;
+        Keygen::execute(&mut output, config)
+      }
+      Command::CheckReuse { output, .. } => {
+        let config = self.into();
+        let mut output = Self::create_output(output)?;
+        PadReuse::check(&mut output, config)
+      }
+      Command::DetectReuse { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        PadReuse::detect(&mut input, &mut output, config)
+      }
+      Command::RecoverKey {
+        default_args,
+        alphabet,
+        ..
+      } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let alphabet = alphabet.alphabet()?;
+        let config = self.into();
+        RecoverKey::execute(&mut input, &mut output, &alphabet, config)
+      }
+      Command::Crib { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Crib::execute(&mut input, &mut output, config)
+      }
+      Command::Keystream { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Keystream::execute(&mut input, &mut output, config)
+      }
+      Command::ToyHash { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        ToyHash::execute(&mut input, &mut output, config)
+      }
+      Command::Hash { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Hash::execute(&mut input, &mut output, config)
+      }
+      Command::Hmac { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Hmac::execute(&mut input, &mut output, config)
+      }
+      Command::Checksum { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Checksum::execute(&mut input, &mut output, config)
+      }
+      Command::Rainbow { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Rainbow::execute(&mut input, &mut output, config)
+      }
+      Command::Aes { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Aes::execute(&mut input, &mut output, config)
+      }
+      Command::DetectEcb { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Ecb::detect(&mut input, &mut output, config)
+      }
+      Command::EcbDemo { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        EcbDemo::execute(&mut input, &mut output, config)
+      }
+      Command::Workbench { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Workbench::execute(&mut input, &mut output, config)
+      }
+      Command::Radix { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Radix::convert(&mut input, &mut output, config)
+      }
+      Command::Bytes { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Bytes::execute(&mut input, &mut output, config)
+      }
+      Command::Strings { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Strings::execute(&mut input, &mut output, config)
+      }
+      Command::NullCipher { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        NullCipher::execute(&mut input, &mut output, config)
+      }
+      Command::Score { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Score::execute(&mut input, &mut output, config)?;
+        Ok(())
+      }
+      Command::Solve { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        let report =
+          AutoSolve::execute(&mut input, &mut output, config, format)?;
+        Ok(CrackReport::check_confidence(&[report])?)
+      }
+      Command::KeyCheck { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        KeyCheck::execute(&mut input, &mut output, config)
+      }
+      Command::PasswordEntropy { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        PasswordEntropy::execute(&mut input, &mut output, config)
+      }
+      Command::Wordlist { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Wordlist::execute(&mut input, &mut output, config)
+      }
+      Command::Brute { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Brute::execute(&mut input, &mut output, config, format)
+      }
+      Command::Stego { command } => command.execute(),
+      Command::Lfsr { command } => command.execute(),
+      Command::Factor { output, number, .. } => {
+        let config = self.into();
+        let mut output = Self::create_output(output)?;
+        Factor::execute(&mut output, number, config)
+      }
+      Command::Completions { output, .. } => {
+        let config = self.into();
+        let mut output = Self::create_output(output)?;
+        Completions::execute(&mut output, config)
+      }
+      Command::Manpages { dir } => Manpages::execute(&mut io::stdout(), dir),
+      Command::Prime { command } => command.execute(),
+      Command::Elgamal { command } => command.execute(),
+      Command::Des { command } => command.execute(),
+      Command::Lcg { command } => command.execute(),
+      Command::Rsa { command } => command.execute(),
+      Command::Encrypt { text, cipher } => cipher.execute(text),
+      Command::Decrypt { spacing, cipher } => cipher.execute(format, spacing),
+      Command::Hex { default_args, .. } => {
+        let (mut input, mut output) = Command::get_files(default_args)?;
+        let config = self.into();
+        Hex::parse(&mut input, &mut output, config)?;
+        Ok(())
+      }
+    }
+  }
+}
+
+impl EncryptCipher {
+  pub fn execute(&self, text: &CryptologyTextArgs) -> Result<()> {
+    match self {
+      EncryptCipher::Caesar {
+        default_args,
+        shift,
+        alphabet,
+        ..
+      } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let shift = shift.unwrap_or_default();
+        Caesar::encrypt(&mut input, &mut output, shift, &alphabet.alphabet()?)
+      }
+      EncryptCipher::Vigenere {
+        default_args,
+        alphabet,
+        ..
+      } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = VigenereEncryptConfig {
+          alphabet: alphabet.alphabet()?,
+          ..self.into()
+        };
+        Vigenere::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::OneTimePad {
+        default_args,
+        key_output,
+        ..
+      } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let mut config: OneTimePadEncryptConfig = self.into();
+        let otp = OneTimePad::encrypt(&mut input, &mut output, &mut config)?;
+        if let Some(short_key) = otp.short_key {
+          eprintln!("Warning: {short_key}");
+        }
+        match (config.generate_key, key_output) {
+          (true, Some(path)) => fs::write(path, format!("{}\n", config.key))?,
+          (true, None) => eprintln!("Key: {}", config.key),
+          (false, _) => {}
+        }
+        Ok(())
+      }
+      EncryptCipher::Adfgvx { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        Adfgvx::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Scytale { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        Scytale::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Multiplicative { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        Multiplicative::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Affine {
+        default_args,
+        alphabet,
+        ..
+      } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = AffineConfig {
+          alphabet: alphabet.alphabet()?,
+          ..self.into()
+        };
+        Affine::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::RailFence { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        RailFence::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Cadenus { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        Cadenus::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Route { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        Route::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Rot47 { default_args } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        Rot47::apply(&mut input, &mut output)
+      }
+      EncryptCipher::BookCipher { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        BookCipher::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::MonoalphabeticSubstitution {
+        default_args,
+        alphabet,
+        ..
+      } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = MonoalphabeticSubstitutionEncryptConfig {
+          alphabet: alphabet.alphabet()?,
+          ..self.into()
+        };
+        MonoalphabeticSubstition::encrypt(&mut input, &mut output, config)?;
+        Ok(())
+      }
+      EncryptCipher::ColumnarTransposition { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        ColumnarTransposition::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::M209 { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        M209::encrypt(&mut input, &mut output, config)
+      }
+      EncryptCipher::Xor { default_args, .. } => {
+        let (mut input, mut output) = text.get_files(default_args)?;
+        let config = self.into();
+        RepeatingKeyXor::encrypt(&mut input, &mut output, config)
+      }
+    }
+  }
+}
+
+impl DecryptCipher {
+  pub fn execute(
+    &self,
+    format: OutputFormat,
+    spacing: &CryptologySpacingArgs,
+  ) -> Result<()> {
+    match self {
+      DecryptCipher::MonoalphabeticSubstitution {
+        default_args,
+        solver,
+        dictionary,
+        map,
+        map_output,
+        interactive,
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let mut ciphertext = String::new();
+        input.read_to_string(&mut ciphertext)?;
+        let input = &mut ciphertext.as_bytes();
+        let mut solution = Vec::new();
+        let (map, method) = if let Some(map) = map {
+          let map = MonoalphabeticSubstition::decrypt(
+            input,
+            &mut solution,
+            SubstitutionMap::load(map)?,
+          )?;
+          (map, "map")
+        } else if let Some(dictionary) = dictionary {
+          let map = MonoalphabeticSubstition::solve_cryptogram(
+            input,
+            &mut solution,
+            dictionary,
+          )?;
+          (map, "dictionary")
+        } else if solver.solver.is_some() {
+          let config = solver.into();
+          let map =
+            MonoalphabeticSubstition::solve(input, &mut solution, &config)?;
+          (map, "solver")
+        } else {
+          let map = MonoalphabeticSubstition::analyze(input, &mut solution)?;
+          (map, "frequency analysis")
+        };
+        let map = match interactive {
+          true => {
+            let map = Refinement::new(&ciphertext, map)
+              .run(&mut io::stdin().lock(), &mut io::stderr())?;
+            solution.clear();
+            MonoalphabeticSubstition::decrypt(
+              &mut ciphertext.as_bytes(),
+              &mut solution,
+              map,
+            )?
+          }
+          false => map,
+        };
+        output.write_all(&solution)?;
+        if let Some(map_output) = map_output {
+          map.save(map_output)?;
+        }
+
+        // The solution starts with the map, followed by the plaintext.
+        let map = map.to_string();
+        let plaintext = String::from_utf8_lossy(&solution[map.len()..]);
+        let report =
+          CrackReport::new(plaintext.into_owned(), Some(map), method);
+        Ok(CrackReport::check_confidence(&[report])?)
+      }
+      DecryptCipher::Caesar {
+        default_args,
+        alphabet,
+        key_output,
+        top,
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = CaesarConfig::new(alphabet.alphabet()?, alphabet.language);
+        let reports = match top {
+          Some(top) => {
+            let rank = |line: &str| Caesar::rank(line, &config);
+            Self::write_top(&mut input, &mut output, format, *top, rank)?
+          }
+          None => match format {
+            OutputFormat::Text => {
+              Caesar::decrypt(&mut input, &mut output, config)?
+            }
+            OutputFormat::Json => {
+              let reports = Caesar::crack(&mut input, &config)?;
+              CrackReport::write_json(&mut output, &reports)?;
+              reports
+            }
+          },
+        };
+        Self::write_keys(key_output, &reports)?;
+        Ok(CrackReport::check_confidence(&reports)?)
+      }
+      DecryptCipher::Vigenere {
+        default_args,
+        dictionary,
+        alphabet,
+        key_output,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = VigenereDecryptConfig {
+          wordlist: dictionary.read_wordlist()?,
+          alphabet: alphabet.alphabet()?,
+          language: alphabet.language,
+          ..self.into()
+        };
+        let reports = match (format, &config.crib) {
+          (OutputFormat::Json, None) => {
+            let reports = Vigenere::crack(&mut input, &config)?;
+            CrackReport::write_json(&mut output, &reports)?;
+            reports
+          }
+          _ => Vigenere::decrypt(&mut input, &mut output, config)?,
+        };
+        Self::write_keys(key_output, &reports)?;
+        Ok(CrackReport::check_confidence(&reports)?)
+      }
+      DecryptCipher::OneTimePad { default_args, .. } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let mut config = self.into();
+        for short_key in
+          OneTimePad::decrypt(&mut input, &mut output, &mut config)?
+        {
+          eprintln!("Warning: {short_key}");
+        }
+        Ok(())
+      }
+      DecryptCipher::ManyTimePad {
+        default_args,
+        key_output,
+        crib_file,
+        wordlist,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = ManyTimePadConfig {
+          cribs: crib_file.as_ref().map(fs::read_to_string).transpose()?,
+          wordlist: wordlist.as_ref().map(fs::read_to_string).transpose()?,
+          ..self.into()
+        };
+        let (key, reports) = match format {
+          OutputFormat::Text => {
+            ManyTimePad::decrypt(&mut input, &mut output, config)?
+          }
+          OutputFormat::Json => {
+            let (key, reports) = ManyTimePad::crack(&mut input, &config)?;
+            CrackReport::write_json(&mut output, &reports)?;
+            (key, reports)
+          }
+        };
+        if let Some(path) = key_output {
+          fs::write(path, format!("{}\n", Hex::new(key.bytes)))?;
+        }
+        Ok(CrackReport::check_confidence(&reports)?)
+      }
+      DecryptCipher::Adfgvx { default_args, .. } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = self.into();
+        Adfgvx::decrypt(&mut input, &mut output, config)
+      }
+      DecryptCipher::Scytale {
+        default_args, top, ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        if let Some(top) = top {
+          let rank = Scytale::rank;
+          Self::write_top(&mut input, &mut output, format, *top, rank)?;
+          return Ok(());
+        }
+        let reports = Scytale::crack(&mut input, &self.into())?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::Multiplicative {
+        default_args, top, ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        if let Some(top) = top {
+          let rank = Multiplicative::rank;
+          Self::write_top(&mut input, &mut output, format, *top, rank)?;
+          return Ok(());
+        }
+        let reports = Multiplicative::crack(&mut input, &self.into())?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::Affine {
+        default_args,
+        alphabet,
+        top,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = AffineConfig {
+          alphabet: alphabet.alphabet()?,
+          language: alphabet.language,
+          ..self.into()
+        };
+        if let Some(top) = top {
+          let rank = |line: &str| Affine::rank(line, &config);
+          Self::write_top(&mut input, &mut output, format, *top, rank)?;
+          return Ok(());
+        }
+        let reports = Affine::crack(&mut input, &config)?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::RailFence {
+        default_args,
+        max_rails,
+        top,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        if let Some(top) = top {
+          let rank = |line: &str| RailFence::rank(line, *max_rails);
+          Self::write_top(&mut input, &mut output, format, *top, rank)?;
+          return Ok(());
+        }
+        let reports = RailFence::crack(&mut input, &self.into())?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::Cadenus { default_args, .. } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let reports = Cadenus::crack(&mut input, &self.into())?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::Route {
+        default_args, top, ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config: RouteConfig = self.into();
+        config.width.map(Route::validate).transpose()?;
+        if let Some(top) = top {
+          let rank = |line: &str| Route::rank(line, &config);
+          Self::write_top(&mut input, &mut output, format, *top, rank)?;
+          return Ok(());
+        }
+        let reports = Route::crack(&mut input, &config)?;
+        Self::write_reports(&mut output, format, &reports)
+      }
+      DecryptCipher::Rot47 { default_args } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        Rot47::apply(&mut input, &mut output)
+      }
+      DecryptCipher::BookCipher { default_args, .. } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = self.into();
+        BookCipher::decrypt(&mut input, &mut output, config)
+      }
+      DecryptCipher::ColumnarTransposition {
+        default_args,
+        dictionary,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = TranspositionConfig {
+          wordlist: dictionary.read_wordlist()?,
+          ..self.into()
+        };
+        ColumnarTransposition::decrypt(&mut input, &mut output, config)
+      }
+      DecryptCipher::M209 { default_args, .. } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = self.into();
+        M209::decrypt(&mut input, &mut output, config)
+      }
+      DecryptCipher::Xor {
+        default_args,
+        key_output,
+        top: Some(top),
+        dictionary,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = RepeatingKeyXorConfig {
+          wordlist: dictionary.read_wordlist()?,
+          ..self.into()
+        };
+        let candidates =
+          RepeatingKeyXor::crack_top(&mut input, &config, top.get())?;
+        let reports =
+          Self::write_candidates(&mut output, format, &[candidates])?;
+        Self::write_keys(key_output, &reports)
+      }
+      DecryptCipher::Xor {
+        default_args,
+        key_output,
+        dictionary,
+        ..
+      } => {
+        let (mut input, mut output) =
+          spacing.get_files(default_args, format)?;
+        let config = RepeatingKeyXorConfig {
+          wordlist: dictionary.read_wordlist()?,
+          ..self.into()
+        };
+        let key = match format {
+          OutputFormat::Text => {
+            let key =
+              RepeatingKeyXor::decrypt(&mut input, &mut output, config)?;
+            Hex::new(key).to_string()
+          }
+          OutputFormat::Json => {
+            let report = RepeatingKeyXor::crack(&mut input, &config)?;
+            CrackReport::write_json(&mut output, &[report.clone()])?;
+            report.key.unwrap_or_default()
+          }
+        };
+        if let Some(path) = key_output {
+          fs::write(path, format!("{key}\n"))?;
+        }
+        Ok(())
+      }
+    }
+  }
+
+  /// Ranks the candidates of every line of the input with `rank` and
+  /// writes the `top` best of each like [`DecryptCipher::write_candidates`].
+  /// Returns the best candidate of every line.
+  fn write_top<R: Read, W: Write, C: Into<CrackReport>>(
+    input: &mut R,
+    output: &mut W,
+    format: OutputFormat,
+    top: NonZeroUsize,
+    rank: impl Fn(&str) -> Vec<C>,
+  ) -> Result<Vec<CrackReport>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let candidates: Vec<Vec<CrackReport>> = content
+      .lines()
+      .map(|line| {
+        let candidates = rank(line).into_iter().take(top.get());
+        candidates.map(Into::into).collect()
+      })
+      .collect();
+    Self::write_candidates(output, format, &candidates)
+  }
+
+  /// Writes the ranked candidates of every ciphertext, separated by
+  /// blank lines, or all of them as one JSON array. Returns the best
+  /// candidate of every ciphertext.
+  fn write_candidates<W: Write>(
+    output: &mut W,
+    format: OutputFormat,
+    candidates: &[Vec<CrackReport>],
+  ) -> Result<Vec<CrackReport>> {
+    match format {
+      OutputFormat::Text => {
+        for (index, candidates) in candidates.iter().enumerate() {
+          if index > 0 {
+            writeln!(output)?;
+          }
+          CrackReport::write_candidates(output, candidates)?;
+        }
+      }
+      OutputFormat::Json => {
+        let all: Vec<CrackReport> = candidates.concat();
+        CrackReport::write_json(output, &all)?;
+      }
+    }
+    Ok(
+      candidates
+        .iter()
+        .filter_map(|c| c.first().cloned())
+        .collect(),
+    )
+  }
+
+  /// Writes the plaintext of every report on its own line, or all of the
+  /// reports as one JSON array.
+  fn write_reports<W: Write>(
+    output: &mut W,
+    format: OutputFormat,
+    reports: &[CrackReport],
+  ) -> Result<()> {
+    match format {
+      OutputFormat::Text => reports
+        .iter()
+        .try_for_each(|report| writeln!(output, "{}", report.plaintext)),
+      OutputFormat::Json => CrackReport::write_json(output, reports),
+    }
+  }
+
+  /// Writes the keys of the reports to the key output, if there is one.
+  fn write_keys(
+    key_output: &Option<PathBuf>,
+    reports: &[CrackReport],
+  ) -> Result<()> {
+    match key_output {
+      Some(path) => CrackReport::write_keys(&mut File::create(path)?, reports),
+      None => Ok(()),
+    }
+  }
+}
+
+impl Command {
+  /// Reads the keys of the `--key-file` arguments into their `--key`.
+  fn read_key_files(&mut self) -> Result<()> {
+    match self {
+      Command::Encrypt { cipher, .. } => cipher.read_key_files(),
+      Command::Decrypt { cipher, .. } => cipher.read_key_files(),
+      Command::Hmac {
+        key,
+        key_env: Some(var),
+        ..
+      } => {
+        *key = Some(KeyFile::env(var)?.key);
+        Ok(())
+      }
+      Command::Aes {
+        key,
+        key_file: Some(path),
+        ..
+      } => {
+        *key = Some(KeyFile::read(path)?.key);
+        Ok(())
+      }
+      Command::Des { command } => command.read_key_files(),
+      _ => Ok(()),
+    }
+  }
+
+  /// The input and output arguments, if the command has them.
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      Command::FrequencyAnalysis {
+        command: Some(FrequencyCommand::Compare { default_args, .. }),
+        ..
+      } => Some(default_args),
+      Command::FrequencyAnalysis { default_args, .. }
+      | Command::Hex { default_args, .. }
+      | Command::Autocorrelation { default_args, .. }
+      | Command::DetectEncoding { default_args, .. }
+      | Command::Aes { default_args, .. }
+      | Command::DetectEcb { default_args, .. }
+      | Command::EcbDemo { default_args, .. }
+      | Command::Workbench { default_args, .. }
+      | Command::Radix { default_args, .. }
+      | Command::Bytes { default_args, .. }
+      | Command::Strings { default_args, .. }
+      | Command::Solve { default_args, .. }
+      | Command::Brute { default_args, .. }
+      | Command::Score { default_args, .. }
+      | Command::NullCipher { default_args, .. }
+      | Command::DetectReuse { default_args, .. }
+      | Command::ToyHash { default_args, .. }
+      | Command::Hash { default_args, .. }
+      | Command::Hmac { default_args, .. }
+      | Command::Checksum { default_args, .. }
+      | Command::Rainbow { default_args, .. }
+      | Command::Bacon { default_args, .. }
+      | Command::Nato { default_args, .. }
+      | Command::Anagram { default_args, .. }
+      | Command::RecoverKey { default_args, .. }
+      | Command::Crib { default_args, .. }
+      | Command::Keystream { default_args, .. }
+      | Command::KeyCheck { default_args, .. }
+      | Command::PasswordEntropy { default_args, .. }
+      | Command::Wordlist { default_args, .. } => Some(default_args),
+      Command::Stego { command } => command.default_args_mut(),
+      Command::Lfsr { command } => command.default_args_mut(),
+      Command::Des { command } => command.default_args_mut(),
+      Command::Lcg { command } => command.default_args_mut(),
+      Command::Encrypt { cipher, .. } => cipher.default_args_mut(),
+      Command::Decrypt { cipher, .. } => cipher.default_args_mut(),
+      _ => None,
+    }
+  }
+}
+
+impl LfsrCommand {
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      LfsrCommand::Xor { default_args, .. }
+      | LfsrCommand::Recover { default_args, .. } => Some(default_args),
+      _ => None,
+    }
+  }
+}
+
+impl StegoCommand {
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      StegoCommand::Whitespace { default_args, .. }
+      | StegoCommand::Image { default_args, .. } => Some(default_args),
+    }
+  }
+}
+
+impl DesCommand {
+  fn read_key_files(&mut self) -> Result<()> {
+    match self {
+      DesCommand::Encrypt {
+        key,
+        key_file: Some(path),
+        ..
+      }
+      | DesCommand::Decrypt {
+        key,
+        key_file: Some(path),
+        ..
+      } => {
+        *key = Some(KeyFile::read(path)?.key);
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      DesCommand::Encrypt { default_args, .. }
+      | DesCommand::Decrypt { default_args, .. } => Some(default_args),
+      _ => None,
+    }
+  }
+}
+
+impl LcgCommand {
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      LcgCommand::Recover { default_args, .. } => Some(default_args),
+      _ => None,
+    }
+  }
+}
+
+impl EncryptCipher {
+  fn read_key_files(&mut self) -> Result<()> {
+    match self {
+      EncryptCipher::Caesar {
+        shift,
+        key_file: Some(path),
+        ..
+      } => {
+        *shift = Some(KeyFile::shift(path)?);
+        Ok(())
+      }
+      EncryptCipher::Vigenere { key, .. }
+      | EncryptCipher::Adfgvx { key, .. }
+      | EncryptCipher::Cadenus { key, .. } => {
+        KeyFile::text(key.read_key_file()?)
+      }
+      EncryptCipher::ColumnarTransposition {
+        keys,
+        key_file: Some(path),
+        ..
+      } => {
+        *keys = KeyFile::lines(path)?;
+        Ok(())
+      }
+      EncryptCipher::Xor {
+        key, key_format, ..
+      } => {
+        key_format.read_key_file(key.read_key_file()?);
+        Ok(())
+      }
+      EncryptCipher::OneTimePad {
+        key,
+        key_file: path,
+        key_env,
+        key_format,
+        ..
+      } => {
+        let Some(key_file) = KeyFile::from_args(path, key_env)? else {
+          return Ok(());
+        };
+        *key = Some(key_file.key);
+        key_format.read_pad_file(path, key_file.hex);
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      EncryptCipher::Caesar { default_args, .. }
+      | EncryptCipher::Vigenere { default_args, .. }
+      | EncryptCipher::OneTimePad { default_args, .. }
+      | EncryptCipher::Adfgvx { default_args, .. }
+      | EncryptCipher::Scytale { default_args, .. }
+      | EncryptCipher::Route { default_args, .. }
+      | EncryptCipher::Cadenus { default_args, .. }
+      | EncryptCipher::Multiplicative { default_args, .. }
+      | EncryptCipher::Affine { default_args, .. }
+      | EncryptCipher::RailFence { default_args, .. }
+      | EncryptCipher::Rot47 { default_args }
+      | EncryptCipher::BookCipher { default_args, .. }
+      | EncryptCipher::MonoalphabeticSubstitution { default_args, .. }
+      | EncryptCipher::ColumnarTransposition { default_args, .. }
+      | EncryptCipher::M209 { default_args, .. }
+      | EncryptCipher::Xor { default_args, .. } => Some(default_args),
+    }
+  }
+}
+
+impl DecryptCipher {
+  fn read_key_files(&mut self) -> Result<()> {
+    match self {
+      DecryptCipher::Vigenere { key, .. } => {
+        KeyFile::text(key.read_key_file()?)
+      }
+      DecryptCipher::Adfgvx { key, .. }
+      | DecryptCipher::Cadenus { key, .. } => {
+        KeyFile::text(key.read_key_file()?)
+      }
+      DecryptCipher::ColumnarTransposition {
+        keys,
+        key_file: Some(path),
+        ..
+      } => {
+        *keys = KeyFile::lines(path)?;
+        Ok(())
+      }
+      DecryptCipher::Xor {
+        key, key_format, ..
+      } => {
+        key_format.read_key_file(key.read_key_file()?);
+        Ok(())
+      }
+      DecryptCipher::OneTimePad {
+        key, key_format, ..
+      } => {
+        let hex = key.read_key_file()?;
+        key_format.read_pad_file(&key.key_file, hex);
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  fn default_args_mut(&mut self) -> Option<&mut CryptologyDefaultArgs> {
+    match self {
+      DecryptCipher::MonoalphabeticSubstitution { default_args, .. }
+      | DecryptCipher::Caesar { default_args, .. }
+      | DecryptCipher::Vigenere { default_args, .. }
+      | DecryptCipher::OneTimePad { default_args, .. }
+      | DecryptCipher::ManyTimePad { default_args, .. }
+      | DecryptCipher::Adfgvx { default_args, .. }
+      | DecryptCipher::Scytale { default_args, .. }
+      | DecryptCipher::Route { default_args, .. }
+      | DecryptCipher::Cadenus { default_args, .. }
+      | DecryptCipher::Multiplicative { default_args, .. }
+      | DecryptCipher::Affine { default_args, .. }
+      | DecryptCipher::RailFence { default_args, .. }
+      | DecryptCipher::Rot47 { default_args }
+      | DecryptCipher::BookCipher { default_args, .. }
+      | DecryptCipher::ColumnarTransposition { default_args, .. }
+      | DecryptCipher::M209 { default_args, .. }
+      | DecryptCipher::Xor { default_args, .. } => Some(default_args),
+    }
+  }
+}
+
+/// The input and output of a command.
+type Files = (Box<dyn Read>, Box<dyn Write>);
+
+impl Command {
   fn get_files(
     default_args: &CryptologyDefaultArgs,
-  ) -> (Box<dyn Read>, Box<dyn Write>) {
-    let input_data = Self::open_input(&default_args.input);
-    let output_data = Self::create_output(&default_args.output);
-    (input_data, output_data)
+  ) -> std::result::Result<Files, CryptologyError> {
+    let input_data = Self::open_input(default_args.input.first())?;
+    let output_data = Self::create_output(&default_args.output)?;
+    Ok((input_data, output_data))
   }
 
-  fn open_input(input: &Option<PathBuf>) -> Box<dyn Read> {
+  fn open_input(
+    input: Option<&PathBuf>,
+  ) -> std::result::Result<Box<dyn Read>, CryptologyError> {
     match input {
-      Some(path) => {
-        Box::new(File::open(path).expect("Failed to open input file"))
-      }
-      None => Box::new(io::stdin()),
+      Some(path) => match File::open(path) {
+        Ok(file) => Ok(Box::new(file)),
+        Err(source) => Err(CryptologyError::Open {
+          path: path.clone(),
+          source,
+        }),
+      },
+      None => Ok(Box::new(io::stdin())),
     }
   }
 
-  fn create_output(output: &Option<PathBuf>) -> Box<dyn Write> {
+  fn create_output(
+    output: &Option<PathBuf>,
+  ) -> std::result::Result<Box<dyn Write>, CryptologyError> {
     match output {
-      Some(path) => {
-        Box::new(File::create(path).expect("Failed to create output file"))
-      }
-      None => Box::new(io::stdout()),
+      Some(path) => match File::create(path) {
+        Ok(file) => Ok(Box::new(file)),
+        Err(source) => Err(CryptologyError::Create {
+          path: path.clone(),
+          source,
+        }),
+      },
+      None => Ok(Box::new(io::stdout())),
     }
   }
+}
 
-  fn get_readable(input: &str) -> Cursor<Vec<u8>> {
-    Cursor::new(input.as_bytes().to_vec())
-  }
+/// Runs a test that builds the whole command tree on a thread with a
+/// larger stack. The derived clap builders of an unoptimized build need
+/// more than the 2 MiB of a test thread.
+#[cfg(test)]
+pub(crate) fn with_command_stack<T: Send>(
+  test: impl FnOnce() -> T + Send,
+) -> T {
+  std::thread::scope(|scope| {
+    std::thread::Builder::new()
+      .stack_size(16 * 1024 * 1024)
+      .spawn_scoped(scope, test)
+      .expect("failed to spawn the test thread")
+      .join()
+      .expect("the test thread panicked")
+  })
 }
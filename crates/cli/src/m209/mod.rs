@@ -0,0 +1,423 @@
+use std::{
+  fs,
+  io::{Error, ErrorKind, Read, Result, Write},
+  path::PathBuf,
+  str::FromStr,
+};
+
+use crate::{DecryptCipher, EncryptCipher};
+
+/// Letters printed on the six key wheels, from left to right.
+pub const WHEELS: [&str; 6] = [
+  "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+  "ABCDEFGHIJKLMNOPQRSTUVXYZ",
+  "ABCDEFGHIJKLMNOPQRSTUVX",
+  "ABCDEFGHIJKLMNOPQRSTU",
+  "ABCDEFGHIJKLMNOPQRS",
+  "ABCDEFGHIJKLMNOPQ",
+];
+
+/// Distance between the letter shown in the indicator window and the
+/// pin that is sensed by the drum for every wheel.
+const SENSED_PIN_OFFSETS: [usize; 6] = [15, 14, 13, 12, 11, 10];
+
+/// Number of bars on the drum.
+pub const BARS: usize = 27;
+
+/// Pin and lug settings of an M-209, usually taken from a key list.
+///
+/// The settings file contains one line per wheel listing the letters
+/// of its active pins and one line with the lug positions of the bars,
+/// `0` meaning the lug is in a neutral position:
+///
+/// ```text
+/// # active pins
+/// wheel1: ABDHIKMNSTVW
+/// wheel2: ADEGJKLORSUX
+/// wheel3: ABGHJLMNRSTUX
+/// wheel4: CEFHIMNPSTU
+/// wheel5: BDEFHIMNPS
+/// wheel6: ABDHKNOQ
+/// # lugs of the bars
+/// lugs: 3-6 0-6 1-6 1-5 4-5 0-4 0-4 0-4 0-4 2-0 2-0 2-0 2-0
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct M209Settings {
+  pub pins: [Vec<bool>; 6],
+  pub lugs: Vec<(usize, usize)>,
+}
+
+impl FromStr for M209Settings {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let mut settings = Self {
+      pins: WHEELS.map(|wheel| vec![false; wheel.len()]),
+      lugs: Vec::new(),
+    };
+
+    let lines = s
+      .lines()
+      .map(|line| line.split('#').next().unwrap_or_default().trim())
+      .filter(|line| !line.is_empty());
+
+    for line in lines {
+      let (name, value) = line.split_once(':').ok_or_else(|| {
+        Self::invalid(format!("Expected 'name: value', found '{line}'"))
+      })?;
+      let name = name.trim().to_ascii_lowercase();
+      let value = value.trim();
+
+      if name == "lugs" {
+        settings.lugs = Self::parse_lugs(value)?;
+      } else if let Some(wheel) = name
+        .strip_prefix("wheel")
+        .and_then(|number| number.parse::<usize>().ok())
+        .filter(|number| (1..=6).contains(number))
+      {
+        settings.pins[wheel - 1] = Self::parse_pins(wheel - 1, value)?;
+      } else {
+        return Err(Self::invalid(format!("Unknown setting '{name}'")));
+      }
+    }
+
+    Ok(settings)
+  }
+}
+
+impl M209Settings {
+  fn parse_pins(wheel: usize, value: &str) -> Result<Vec<bool>> {
+    let letters = WHEELS[wheel];
+    let mut pins = vec![false; letters.len()];
+
+    for c in value.chars().filter(|c| c.is_ascii_alphabetic()) {
+      let c = c.to_ascii_uppercase();
+      let position = letters.find(c).ok_or_else(|| {
+        Self::invalid(format!("Wheel {} has no pin '{c}'", wheel + 1))
+      })?;
+      pins[position] = true;
+    }
+
+    Ok(pins)
+  }
+
+  fn parse_lugs(value: &str) -> Result<Vec<(usize, usize)>> {
+    let lugs: Vec<(usize, usize)> = value
+      .split_whitespace()
+      .map(|bar| {
+        let (left, right) = bar.split_once('-').unwrap_or((bar, "0"));
+        match (left.parse::<usize>(), right.parse::<usize>()) {
+          (Ok(left), Ok(right)) if left <= 6 && right <= 6 => Ok((left, right)),
+          _ => Err(Self::invalid(format!("Invalid lug setting '{bar}'"))),
+        }
+      })
+      .collect::<Result<_>>()?;
+
+    if lugs.len() > BARS {
+      let message = format!("The drum only has {BARS} bars");
+      return Err(Self::invalid(message));
+    }
+
+    Ok(lugs)
+  }
+
+  fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct M209Config {
+  pub settings: PathBuf,
+  pub indicator: String,
+}
+
+impl M209Config {
+  pub fn new(settings: PathBuf, indicator: &str) -> Self {
+    Self {
+      settings,
+      indicator: indicator.to_string(),
+    }
+  }
+}
+
+impl From<&EncryptCipher> for M209Config {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::M209 {
+        settings,
+        indicator,
+        ..
+      } => Self::new(settings.clone(), indicator),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for M209Config {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::M209 {
+        settings,
+        indicator,
+        ..
+      } => Self::new(settings.clone(), indicator),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A Hagelin M-209 converter set to a message indicator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct M209 {
+  settings: M209Settings,
+  positions: [usize; 6],
+}
+
+impl M209 {
+  /// Creates a converter whose wheels show the six indicator letters.
+  pub fn new(settings: M209Settings, indicator: &str) -> Result<Self> {
+    let letters: Vec<char> = indicator
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .map(|c| c.to_ascii_uppercase())
+      .collect();
+
+    if letters.len() != WHEELS.len() {
+      let message = "The indicator must consist of six letters";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut positions = [0; 6];
+
+    for (wheel, letter) in letters.into_iter().enumerate() {
+      positions[wheel] = WHEELS[wheel].find(letter).ok_or_else(|| {
+        let message = format!("Wheel {} has no letter '{letter}'", wheel + 1);
+        Error::new(ErrorKind::InvalidInput, message)
+      })?;
+    }
+
+    Ok(Self {
+      settings,
+      positions,
+    })
+  }
+
+  /// Returns the shift for the current wheel positions and steps the wheels.
+  ///
+  /// The shift is the number of bars with at least one lug against a
+  /// wheel whose sensed pin is active.
+  pub fn next_key(&mut self) -> u8 {
+    let active: Vec<bool> = (0..WHEELS.len())
+      .map(|wheel| {
+        let size = WHEELS[wheel].len();
+        let sensed = (self.positions[wheel] + SENSED_PIN_OFFSETS[wheel]) % size;
+        self.settings.pins[wheel][sensed]
+      })
+      .collect();
+
+    let shift = self
+      .settings
+      .lugs
+      .iter()
+      .filter(|(left, right)| {
+        [left, right].iter().any(|&&lug| lug > 0 && active[lug - 1])
+      })
+      .count();
+
+    for (wheel, position) in self.positions.iter_mut().enumerate() {
+      *position = (*position + 1) % WHEELS[wheel].len();
+    }
+
+    shift as u8
+  }
+
+  /// Produces the additive keystream for the next `length` letters.
+  pub fn keystream(&mut self, length: usize) -> Vec<u8> {
+    (0..length).map(|_| self.next_key()).collect()
+  }
+
+  /// Converts one letter with the reciprocal Beaufort rule of the
+  /// print wheel, `c = (25 + k - p) mod 26`.
+  pub fn convert(&mut self, letter: char) -> char {
+    let key = self.next_key() as usize;
+    let letter = (letter.to_ascii_uppercase() as u8 - b'A') as usize;
+    (b'A' + ((25 + key - letter) % 26) as u8) as char
+  }
+
+  /// Encrypts the letters of the text. Spaces are enciphered as `Z`,
+  /// all other characters except newlines are dropped.
+  pub fn encrypt_text(&mut self, text: &str) -> String {
+    text
+      .chars()
+      .filter_map(|c| match c {
+        ' ' => Some(self.convert('Z')),
+        '\n' => Some('\n'),
+        c if c.is_ascii_alphabetic() => Some(self.convert(c)),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Decrypts the letters of the text, printing a deciphered `Z` as space.
+  pub fn decrypt_text(&mut self, text: &str) -> String {
+    text
+      .chars()
+      .filter_map(|c| match c {
+        '\n' => Some('\n'),
+        c if c.is_ascii_alphabetic() => match self.convert(c) {
+          'Z' => Some(' '),
+          plain => Some(plain),
+        },
+        _ => None,
+      })
+      .collect()
+  }
+
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: M209Config,
+  ) -> Result<()> {
+    let mut machine = Self::from_config(&config)?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let cipher = machine.encrypt_text(&content);
+    write!(output, "{cipher}")
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: M209Config,
+  ) -> Result<()> {
+    let mut machine = Self::from_config(&config)?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let plaintext = machine.decrypt_text(&content);
+    write!(output, "{plaintext}")
+  }
+
+  fn from_config(config: &M209Config) -> Result<Self> {
+    let settings = fs::read_to_string(&config.settings)?.parse()?;
+    Self::new(settings, &config.indicator)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SETTINGS: &str = "\
+    wheel1: ABDHIKMNSTVW\n\
+    wheel2: ADEGJKLORSUX\n\
+    wheel3: ABGHJLMNRSTUX\n\
+    wheel4: CEFHIMNPSTU\n\
+    wheel5: BDEFHIMNPS\n\
+    wheel6: ABDHKNOQ # comment\n\
+    lugs: 3-6 0-6 1-6 1-5 4-5 0-4 0-4 0-4 0-4 2-0 2-0 2-0 2-0 2-0 2-0 \
+    2-0 2-0 2-0 2-0 2-0 2-0 2-0 0-0 0-0 0-0 0-0 0-0\n";
+
+  #[test]
+  fn test_parse_settings() {
+    let settings: M209Settings = SETTINGS.parse().unwrap();
+    assert_eq!(settings.lugs.len(), BARS);
+    assert_eq!(settings.lugs[0], (3, 6));
+    assert!(settings.pins[0][0]);
+    assert!(!settings.pins[0][2]);
+    assert_eq!(settings.pins[5].iter().filter(|&&pin| pin).count(), 8);
+  }
+
+  #[test]
+  fn test_parse_settings_rejects_invalid_pin() {
+    let result = "wheel6: Z".parse::<M209Settings>();
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_parse_settings_rejects_invalid_lug() {
+    assert!("lugs: 7-1".parse::<M209Settings>().is_err());
+    assert!("unknown: 1".parse::<M209Settings>().is_err());
+  }
+
+  #[test]
+  fn test_indicator_must_match_wheels() {
+    let settings: M209Settings = SETTINGS.parse().unwrap();
+    assert!(M209::new(settings.clone(), "AAAAA").is_err());
+    assert!(M209::new(settings, "AAAAAR").is_err());
+  }
+
+  #[test]
+  fn test_keystream_counts_bars() {
+    let mut settings = M209Settings {
+      pins: WHEELS.map(|wheel| vec![true; wheel.len()]),
+      lugs: vec![(1, 2), (0, 3), (0, 0)],
+    };
+    let mut machine = M209::new(settings.clone(), "AAAAAA").unwrap();
+    assert_eq!(machine.keystream(3), vec![2, 2, 2]);
+
+    settings.pins[2] = vec![false; WHEELS[2].len()];
+    let mut machine = M209::new(settings, "AAAAAA").unwrap();
+    assert_eq!(machine.keystream(2), vec![1, 1]);
+  }
+
+  #[test]
+  fn test_keystream_uses_sensed_pin() {
+    let mut pins = WHEELS.map(|wheel| vec![false; wheel.len()]);
+    pins[0][15] = true;
+    let settings = M209Settings {
+      pins,
+      lugs: vec![(1, 0)],
+    };
+    let mut machine = M209::new(settings, "AAAAAA").unwrap();
+    assert_eq!(machine.keystream(2), vec![1, 0]);
+  }
+
+  #[test]
+  fn test_convert_is_reciprocal() {
+    let settings: M209Settings = SETTINGS.parse().unwrap();
+    let mut machine = M209::new(settings.clone(), "AAAAAA").unwrap();
+    let cipher = machine.encrypt_text("ATTACK AT DAWN");
+    assert_eq!(cipher.len(), 14);
+
+    let mut machine = M209::new(settings, "AAAAAA").unwrap();
+    assert_eq!(machine.decrypt_text(&cipher), "ATTACK AT DAWN");
+  }
+
+  #[test]
+  fn test_convert_applies_beaufort_rule() {
+    let settings = M209Settings {
+      pins: WHEELS.map(|wheel| vec![true; wheel.len()]),
+      lugs: vec![(1, 0); 3],
+    };
+    let mut machine = M209::new(settings, "AAAAAA").unwrap();
+    assert_eq!(machine.convert('A'), 'C');
+    assert_eq!(machine.convert('C'), 'A');
+    assert_eq!(machine.convert('Z'), 'D');
+  }
+
+  #[test]
+  fn test_encrypt_and_decrypt_through_settings_file() -> Result<()> {
+    let path = std::env::temp_dir().join("cryptology-m209-settings.txt");
+    fs::write(&path, SETTINGS)?;
+
+    let mut input = "MEET AT NOON\nBRING MAPS".as_bytes();
+    let mut cipher = Vec::new();
+    M209::encrypt(
+      &mut input,
+      &mut cipher,
+      M209Config::new(path.clone(), "AGHKQD"),
+    )?;
+
+    let mut output = Vec::new();
+    let config = M209Config::new(path.clone(), "AGHKQD");
+    M209::decrypt(&mut cipher.as_slice(), &mut output, config)?;
+    fs::remove_file(&path)?;
+
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "MEET AT NOON\nBRING MAPS"
+    );
+    Ok(())
+  }
+}
@@ -0,0 +1,22 @@
+pub use cryptology_core::many_time_pad::*;
+
+use crate::DecryptCipher;
+
+impl From<&DecryptCipher> for ManyTimePadConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::ManyTimePad {
+        target,
+        all,
+        show_key,
+        min_confidence,
+        ..
+      } => Self {
+        show_key: *show_key,
+        min_confidence: *min_confidence,
+        ..Self::new(*target, *all)
+      },
+      _ => Self::default(),
+    }
+  }
+}
@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::substitution_map::SubstitutionMap;
+
+/// Maximum number of search nodes before the best assignment so far is
+/// returned.
+const NODE_LIMIT: usize = 1_000_000;
+
+/// Marks ciphertext letters that no dictionary word could resolve.
+pub const UNRESOLVED: char = '_';
+
+/// Letters of a word replaced by the order of their first occurrence,
+/// e.g. `HELLO` becomes `ABCCD`. Substitution preserves this pattern.
+pub fn word_pattern(word: &str) -> String {
+  let mut seen: Vec<char> = Vec::new();
+
+  word
+    .chars()
+    .map(|c| {
+      let index = seen.iter().position(|&s| s == c).unwrap_or_else(|| {
+        seen.push(c);
+        seen.len() - 1
+      });
+      (b'A' + index as u8) as char
+    })
+    .collect()
+}
+
+/// Splits a text into uppercase words of letters only.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+  text
+    .split(|c: char| !c.is_ascii_alphabetic() && c != '\'')
+    .map(|word| {
+      word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>()
+    })
+    .filter(|word| !word.is_empty())
+}
+
+/// Dictionary words grouped by their [`word_pattern`].
+#[derive(Debug, Default)]
+pub struct PatternDictionary {
+  patterns: HashMap<String, Vec<String>>,
+}
+
+impl PatternDictionary {
+  pub fn new(text: &str) -> Self {
+    let mut patterns: HashMap<String, Vec<String>> = HashMap::new();
+
+    for word in words(text) {
+      let candidates = patterns.entry(word_pattern(&word)).or_default();
+      if !candidates.contains(&word) {
+        candidates.push(word);
+      }
+    }
+
+    Self { patterns }
+  }
+
+  pub fn candidates(&self, word: &str) -> &[String] {
+    self
+      .patterns
+      .get(&word_pattern(word))
+      .map(Vec::as_slice)
+      .unwrap_or_default()
+  }
+}
+
+/// A unique ciphertext word with the dictionary words matching its
+/// pattern.
+struct CipherWord<'a> {
+  letters: Vec<usize>,
+  weight: usize,
+  candidates: Vec<&'a [u8]>,
+}
+
+/// Cipher to plain letter assignment and its inverse, kept bijective.
+#[derive(Clone, Default)]
+struct Assignment {
+  plain: [Option<u8>; 26],
+  cipher: [Option<usize>; 26],
+}
+
+impl Assignment {
+  fn allows(&self, letters: &[usize], candidate: &[u8]) -> bool {
+    letters
+      .iter()
+      .zip(candidate)
+      .all(|(&c, &p)| match self.plain[c] {
+        Some(assigned) => assigned == p,
+        None => self.cipher[(p - b'A') as usize].is_none(),
+      })
+  }
+
+  fn assign(&mut self, letters: &[usize], candidate: &[u8]) -> Vec<usize> {
+    let mut assigned = Vec::new();
+
+    for (&c, &p) in letters.iter().zip(candidate) {
+      if self.plain[c].is_none() {
+        self.plain[c] = Some(p);
+        self.cipher[(p - b'A') as usize] = Some(c);
+        assigned.push(c);
+      }
+    }
+
+    assigned
+  }
+
+  fn undo(&mut self, assigned: &[usize]) {
+    for &c in assigned {
+      if let Some(p) = self.plain[c].take() {
+        self.cipher[(p - b'A') as usize] = None;
+      }
+    }
+  }
+}
+
+struct Search<'a> {
+  words: Vec<CipherWord<'a>>,
+  assignment: Assignment,
+  best: Assignment,
+  best_score: usize,
+  nodes: usize,
+}
+
+impl Search<'_> {
+  /// Branch and bound over the words: every word is assigned one of its
+  /// candidates consistent with the letters fixed so far, or skipped if
+  /// it is missing from the dictionary. The score counts the letters
+  /// of all matched words.
+  fn run(&mut self, index: usize, score: usize) {
+    self.nodes += 1;
+
+    if score > self.best_score {
+      self.best_score = score;
+      self.best = self.assignment.clone();
+    }
+
+    if index == self.words.len() || self.nodes > NODE_LIMIT {
+      return;
+    }
+
+    let bound: usize = self.words[index..]
+      .iter()
+      .filter(|word| {
+        word
+          .candidates
+          .iter()
+          .any(|candidate| self.assignment.allows(&word.letters, candidate))
+      })
+      .map(|word| word.weight)
+      .sum();
+
+    if score + bound <= self.best_score {
+      return;
+    }
+
+    let word = &self.words[index];
+    let (letters, weight) = (word.letters.clone(), word.weight);
+    let candidates: Vec<&[u8]> = word
+      .candidates
+      .iter()
+      .copied()
+      .filter(|candidate| self.assignment.allows(&letters, candidate))
+      .collect();
+
+    for candidate in candidates {
+      let assigned = self.assignment.assign(&letters, candidate);
+      self.run(index + 1, score + weight);
+      self.assignment.undo(&assigned);
+    }
+
+    self.run(index + 1, score);
+  }
+}
+
+pub struct CryptogramSolver;
+
+impl CryptogramSolver {
+  /// Solves a cryptogram that keeps its word boundaries.
+  ///
+  /// Every ciphertext word can only be a dictionary word with the same
+  /// letter pattern. Words are matched longest first and each match
+  /// fixes letters for the remaining words, which prunes their
+  /// candidates until a consistent assignment covering as many letters
+  /// as possible is found. Letters of the ciphertext that stay unknown
+  /// are mapped to [`UNRESOLVED`].
+  pub fn solve(text: &str, dictionary: &PatternDictionary) -> SubstitutionMap {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in words(text) {
+      *counts.entry(word).or_insert(0) += 1;
+    }
+
+    let mut cipher_words: Vec<CipherWord> = counts
+      .iter()
+      .map(|(word, &count)| CipherWord {
+        letters: word.bytes().map(|c| (c - b'A') as usize).collect(),
+        weight: word.len() * count,
+        candidates: dictionary
+          .candidates(word)
+          .iter()
+          .map(|candidate| candidate.as_bytes())
+          .collect(),
+      })
+      .filter(|word| !word.candidates.is_empty())
+      .collect();
+
+    cipher_words.sort_by(|a, b| {
+      b.letters
+        .len()
+        .cmp(&a.letters.len())
+        .then(a.candidates.len().cmp(&b.candidates.len()))
+        .then(a.letters.cmp(&b.letters))
+    });
+
+    let mut search = Search {
+      words: cipher_words,
+      assignment: Assignment::default(),
+      best: Assignment::default(),
+      best_score: 0,
+      nodes: 0,
+    };
+    search.run(0, 0);
+
+    let map: BTreeMap<char, char> = text
+      .chars()
+      .filter(char::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase())
+      .map(|c| {
+        let plain = search.best.plain[(c as u8 - b'A') as usize];
+        (c, plain.map(char::from).unwrap_or(UNRESOLVED))
+      })
+      .collect();
+
+    SubstitutionMap::new(map)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::alphabet::Alphabet;
+
+  const DICTIONARY: &str = "a an and as at be but by do for from have he \
+    her his i in is it not of on or she that the their they this to was \
+    we with you all any can had one our out day get has him how man new \
+    now old see two way who boy did its let put say too use love hello \
+    world peace people letter secret puzzle simple simply crack cracked \
+    easily message hidden words patterns pattern letters";
+
+  fn solve(plaintext: &str, keyword: &str) -> String {
+    let cipher_map =
+      SubstitutionMap::from_keyword(keyword, &Alphabet::default());
+    let mut cipher = Vec::new();
+    cipher_map
+      .apply(&mut plaintext.to_ascii_uppercase().as_bytes(), &mut cipher)
+      .unwrap();
+    let cipher = String::from_utf8(cipher).unwrap();
+
+    let dictionary = PatternDictionary::new(DICTIONARY);
+    let map = CryptogramSolver::solve(&cipher, &dictionary);
+    let mut plaintext = Vec::new();
+    map.apply(&mut cipher.as_bytes(), &mut plaintext).unwrap();
+    String::from_utf8(plaintext).unwrap()
+  }
+
+  #[test]
+  fn test_word_pattern() {
+    assert_eq!(word_pattern("HELLO"), "ABCCD");
+    assert_eq!(word_pattern("ABBA"), "ABBA");
+    assert_eq!(word_pattern("PEOPLE"), "ABCADB");
+  }
+
+  #[test]
+  fn test_dictionary_groups_by_pattern() {
+    let dictionary = PatternDictionary::new("Hello, world! hello PEOPLE");
+    assert_eq!(dictionary.candidates("JIFFY"), &["HELLO"]);
+    assert_eq!(dictionary.candidates("XYXZXY").len(), 0);
+    assert_eq!(dictionary.candidates("ABCADB"), &["PEOPLE"]);
+  }
+
+  #[test]
+  fn test_solve_cryptogram() {
+    let plaintext = "The secret message was hidden in simple words, \
+      but the patterns of the letters cracked it easily.";
+    assert_eq!(solve(plaintext, "zebras"), plaintext.to_ascii_uppercase());
+  }
+
+  #[test]
+  fn test_unknown_words_are_skipped() {
+    let plaintext = "Hello world, Zyzzyva was here";
+    assert_eq!(solve(plaintext, "cipher"), "HELLO WORLD, ______A WAS HERE");
+  }
+}
@@ -1,50 +1,352 @@
+pub mod cryptogram;
+pub mod refine;
 pub mod substitution_map;
 
+use std::collections::BTreeMap;
+use std::fs::File;
 use std::io::Result;
+use std::path::PathBuf;
 use std::{io::Read, io::Write};
 
+use rand::{rngs::StdRng, SeedableRng};
+
+use cryptogram::{CryptogramSolver, PatternDictionary};
 use substitution_map::SubstitutionMap;
 
+use crate::alphabet::Alphabet;
 use crate::frequency_analysis::frequencies::english::ENGLISH;
-use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::solver::{Permutations, Seeded, Solver, SolverConfig};
+use crate::EncryptCipher;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MonoalphabeticSubstitutionEncryptConfig {
+  /// Keyword used to mix the alphabet. A random key is generated if absent.
+  pub keyword: Option<String>,
+  /// File the substitution map is written to.
+  pub key_output: Option<PathBuf>,
+  /// The symbols that are substituted, `A..=Z` by default.
+  pub alphabet: Alphabet,
+  /// Seed for a reproducible random key.
+  pub seed: Option<u64>,
+  /// File a substitution map is read from instead of deriving one.
+  pub map: Option<PathBuf>,
+}
+
+impl MonoalphabeticSubstitutionEncryptConfig {
+  pub fn new(keyword: Option<String>, key_output: Option<PathBuf>) -> Self {
+    Self {
+      keyword,
+      key_output,
+      alphabet: Alphabet::default(),
+      seed: None,
+      map: None,
+    }
+  }
+}
+
+impl From<&EncryptCipher> for MonoalphabeticSubstitutionEncryptConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::MonoalphabeticSubstitution {
+        keyword,
+        key_output,
+        seed,
+        map,
+        ..
+      } => Self {
+        seed: *seed,
+        map: map.clone(),
+        ..Self::new(keyword.clone(), key_output.clone())
+      },
+      _ => Self::default(),
+    }
+  }
+}
 
 pub struct MonoalphabeticSubstition;
 
 impl MonoalphabeticSubstition {
-  pub fn analyze<R: Read, W: Write>(
+  /// Encrypts the input with a keyword-mixed or random alphabet, or with
+  /// a substitution map read from a file.
+  ///
+  /// The ciphertext is written in the case of the alphabet, which is
+  /// uppercase by default, the form expected by
+  /// [`MonoalphabeticSubstition::analyze`]. The substitution map is
+  /// written to the key output, or to standard error if a random key
+  /// was generated without one.
+  pub fn encrypt<R: Read, W: Write>(
     input: &mut R,
     output: &mut W,
+    config: MonoalphabeticSubstitutionEncryptConfig,
   ) -> Result<SubstitutionMap> {
     let mut content = String::new();
-    let mut buf = Vec::new();
-    let mut substitution_map = SubstitutionMap::default();
+    input.read_to_string(&mut content)?;
+
+    let alphabet = &config.alphabet;
+    let substitution_map = match (&config.map, &config.keyword, config.seed) {
+      (Some(map), _, _) => SubstitutionMap::load(map)?,
+      (None, Some(keyword), _) => {
+        SubstitutionMap::from_keyword(keyword, alphabet)
+      }
+      (None, None, Some(seed)) => {
+        SubstitutionMap::random(&mut StdRng::seed_from_u64(seed), alphabet)
+      }
+      (None, None, None) => {
+        SubstitutionMap::random(&mut rand::thread_rng(), alphabet)
+      }
+    };
+
+    let content = alphabet.normalize(&content);
+    substitution_map.apply(&mut content.as_bytes(), output)?;
 
+    match &config.key_output {
+      Some(path) => write!(File::create(path)?, "{substitution_map}")?,
+      None if config.keyword.is_none() && config.map.is_none() => {
+        eprint!("{substitution_map}")
+      }
+      None => {}
+    }
+
+    Ok(substitution_map)
+  }
+
+  /// Guesses the key from the letter, bigram and trigram frequencies of
+  /// the ciphertext.
+  ///
+  /// See [`MonoalphabeticSubstition::initial_guess`].
+  pub fn analyze<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+  ) -> Result<SubstitutionMap> {
+    let mut content = String::new();
     input.read_to_string(&mut content)?;
 
-    let fa = FrequencyAnalyzer::analyze(&mut content.as_bytes(), &mut buf)?;
+    let content = content.to_ascii_uppercase();
+    let key = Self::initial_guess(&content);
+    let substitution_map = SubstitutionMap::new(
+      ('A'..='Z')
+        .zip(key.iter().map(|&c| Self::letter(c)))
+        .filter(|(cipher, _)| content.contains(*cipher))
+        .collect(),
+    );
+
+    write!(output, "{substitution_map}")?;
 
-    let mut sorted_frequencies: Vec<_> = fa.frequency.iter().collect();
+    substitution_map.apply(&mut content.as_bytes(), output)?;
+    Ok(substitution_map)
+  }
 
-    sorted_frequencies.sort_by(|a, b| b.1.cmp(a.1));
+  /// Guesses the plaintext letter of every ciphertext letter, given as
+  /// indices `0..26`.
+  ///
+  /// The letters are first matched by their frequency ranks. The ranks
+  /// of all but the most frequent letters are too close to be reliable,
+  /// so the most common trigram is then taken for `THE`, or the most
+  /// common bigram for `TH` if no trigram repeats.
+  pub fn initial_guess(content: &str) -> Vec<usize> {
+    let words: Vec<Vec<usize>> = content
+      .to_ascii_uppercase()
+      .split(|c: char| !c.is_ascii_uppercase())
+      .map(|word| word.bytes().map(|c| (c - b'A') as usize).collect())
+      .collect();
 
-    for (analyzed, english) in sorted_frequencies.iter().zip(ENGLISH.iter()) {
-      substitution_map.insert(*analyzed.0, *english.0);
+    let mut counts = [0; 26];
+    for &c in words.iter().flatten() {
+      counts[c] += 1;
     }
+    let mut cipher: Vec<usize> = (0..26).collect();
+    cipher.sort_by_key(|&c| std::cmp::Reverse(counts[c]));
+    let mut english: Vec<(&char, &usize)> = ENGLISH.iter().collect();
+    english.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    let mut key = vec![0; 26];
+    for (&c, (&plain, _)) in cipher.iter().zip(english) {
+      key[c] = (plain as u8 - b'A') as usize;
+    }
+
+    let most_common = |n: usize| {
+      let mut ngrams = BTreeMap::new();
+      for ngram in words.iter().flat_map(|word| word.windows(n)) {
+        if (1..n).all(|i| !ngram[..i].contains(&ngram[i])) {
+          *ngrams.entry(ngram).or_insert(0) += 1;
+        }
+      }
+      ngrams.into_iter().max_by_key(|&(_, count)| count)
+    };
+
+    let crib = match most_common(3) {
+      Some((ngram, count)) if count > 1 => Some((ngram, "THE")),
+      _ => most_common(2).map(|(ngram, _)| (ngram, "TH")),
+    };
+
+    if let Some((ngram, plaintext)) = crib {
+      for (&c, plain) in ngram.iter().zip(plaintext.bytes()) {
+        let plain = (plain - b'A') as usize;
+        let other = key.iter().position(|&p| p == plain).unwrap_or(c);
+        key.swap(c, other);
+      }
+    }
+
+    key
+  }
+
+  /// Searches the key with the solver, scoring every candidate
+  /// plaintext by its bigrams or the configured n-gram statistics.
+  ///
+  /// The searches start from the guess of
+  /// [`MonoalphabeticSubstition::analyze`], but unlike it the solver
+  /// scores the order of all letters and recovers far more of the key.
+  pub fn solve<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: &SolverConfig,
+  ) -> Result<SubstitutionMap> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let content = content.to_ascii_uppercase();
+    let letters: Vec<usize> = content
+      .bytes()
+      .filter(u8::is_ascii_uppercase)
+      .map(|c| (c - b'A') as usize)
+      .collect();
+
+    let score = config.text_scorer()?;
+    let scorer = |key: &Vec<usize>| {
+      let text: String =
+        letters.iter().map(|&c| Self::letter(key[c])).collect();
+      score(&text)
+    };
+
+    let generator = Seeded {
+      generator: &Permutations { length: 26 },
+      key: Self::initial_guess(&content),
+    };
+    let solution = Solver::solve(&generator, &scorer, config);
+    let substitution_map = SubstitutionMap::new(
+      ('A'..='Z')
+        .zip(solution.key.iter().map(|&c| Self::letter(c)))
+        .collect(),
+    );
+
+    write!(output, "{substitution_map}")?;
+
+    substitution_map.apply(&mut content.as_bytes(), output)?;
+    Ok(substitution_map)
+  }
+
+  /// Solves a cryptogram by matching the word patterns of the
+  /// ciphertext against the words of a dictionary file.
+  pub fn solve_cryptogram<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    dictionary: &PathBuf,
+  ) -> Result<SubstitutionMap> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let dictionary =
+      PatternDictionary::new(&std::fs::read_to_string(dictionary)?);
+    let content = content.to_ascii_uppercase();
+    let substitution_map = CryptogramSolver::solve(&content, &dictionary);
 
     write!(output, "{substitution_map}")?;
 
     substitution_map.apply(&mut content.as_bytes(), output)?;
     Ok(substitution_map)
   }
+
+  /// Decrypts the input with a known substitution map, such as one
+  /// recovered by the analysis and corrected by hand.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    substitution_map: SubstitutionMap,
+  ) -> Result<SubstitutionMap> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let content = content.to_ascii_uppercase();
+
+    write!(output, "{substitution_map}")?;
+
+    substitution_map.apply(&mut content.as_bytes(), output)?;
+    Ok(substitution_map)
+  }
+
+  fn letter(index: usize) -> char {
+    (b'A' + index as u8) as char
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::solver::{RestartPolicy, SolverKind};
   use std::env;
   use std::fs::File;
   use std::path::PathBuf;
 
+  #[test]
+  fn test_encrypt_with_keyword() -> Result<()> {
+    let mut input = "Flee at once, we are discovered!".as_bytes();
+    let mut output = Vec::new();
+    let config = MonoalphabeticSubstitutionEncryptConfig::new(
+      Some(String::from("zebras")),
+      None,
+    );
+
+    MonoalphabeticSubstition::encrypt(&mut input, &mut output, config)?;
+
+    let cipher = String::from_utf8(output).unwrap();
+    assert_eq!(cipher, "SIAA ZQ LKBA, VA ZOA RFPBLUAOAR!");
+    Ok(())
+  }
+
+  #[test]
+  fn test_encrypt_with_random_key_writes_key_output() -> Result<()> {
+    let key_output = env::temp_dir().join("cryptology-monosub-key.txt");
+    let plaintext = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG";
+    let mut output = Vec::new();
+    let config = MonoalphabeticSubstitutionEncryptConfig::new(
+      None,
+      Some(key_output.clone()),
+    );
+
+    let map = MonoalphabeticSubstition::encrypt(
+      &mut plaintext.as_bytes(),
+      &mut output,
+      config,
+    )?;
+
+    let mut key = String::new();
+    File::open(&key_output)?.read_to_string(&mut key)?;
+    std::fs::remove_file(&key_output)?;
+    assert_eq!(key, format!("{map}"));
+    assert_eq!(key.lines().count(), 26);
+
+    let cipher = String::from_utf8(output).unwrap();
+    assert_eq!(cipher.len(), plaintext.len());
+    Ok(())
+  }
+
+  #[test]
+  fn test_seeded_random_key_repeats() -> Result<()> {
+    let encrypt = |seed| {
+      let config = MonoalphabeticSubstitutionEncryptConfig {
+        seed: Some(seed),
+        ..MonoalphabeticSubstitutionEncryptConfig::new(None, None)
+      };
+      let mut input = "ATTACK AT DAWN".as_bytes();
+      let map =
+        MonoalphabeticSubstition::encrypt(&mut input, &mut Vec::new(), config);
+      map.map(|map| map.to_string())
+    };
+    assert_eq!(encrypt(7)?, encrypt(7)?);
+    assert_ne!(encrypt(7)?, encrypt(8)?);
+    Ok(())
+  }
+
   #[test]
   fn test_monoalphabetic_substitution_analysis_output() -> Result<()> {
     let assets = "src/monoalphabetic_substitution/assets";
@@ -66,4 +368,104 @@ mod tests {
 
     Ok(())
   }
+
+  #[test]
+  fn test_solve_recovers_plaintext() -> Result<()> {
+    let assets = "src/monoalphabetic_substitution/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/cli")
+          .join(assets)
+      });
+
+    let mut expected = String::new();
+    File::open(path.join("output.txt"))?.read_to_string(&mut expected)?;
+
+    let restart = RestartPolicy {
+      restarts: 4,
+      patience: None,
+    };
+    let config =
+      SolverConfig::new(SolverKind::HillClimbing, 5_000, restart, Some(7));
+    let mut input = File::open(path.join("input.txt"))?;
+    let mut output = Vec::new();
+    let map =
+      MonoalphabeticSubstition::solve(&mut input, &mut output, &config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    let plaintext = output.strip_prefix(&format!("{map}")).unwrap();
+    let correct = plaintext
+      .chars()
+      .zip(expected.chars())
+      .filter(|(a, b)| a.is_ascii_alphabetic() && a == b)
+      .count();
+    let letters = expected.chars().filter(char::is_ascii_alphabetic).count();
+    assert!(correct * 10 >= letters * 9);
+    Ok(())
+  }
+
+  #[test]
+  fn test_encrypt_and_decrypt_with_saved_map() -> Result<()> {
+    let map_path = env::temp_dir().join("cryptology-monosub-map.txt");
+    let plaintext = "ATTACK AT DAWN";
+    let config = MonoalphabeticSubstitutionEncryptConfig {
+      map: Some(map_path.clone()),
+      ..MonoalphabeticSubstitutionEncryptConfig::new(None, None)
+    };
+    SubstitutionMap::from_keyword("zebras", &Alphabet::default())
+      .save(&map_path)?;
+
+    let mut cipher = Vec::new();
+    MonoalphabeticSubstition::encrypt(
+      &mut plaintext.as_bytes(),
+      &mut cipher,
+      config,
+    )?;
+    std::fs::remove_file(&map_path)?;
+    assert_eq!(String::from_utf8(cipher.clone()).unwrap(), "ZQQZBH ZQ RZVK");
+
+    let inverse: SubstitutionMap =
+      "Z -> A\nQ -> T\nB -> C\nH -> K\nR -> D\nV -> W\nK -> N".parse()?;
+    let mut output = Vec::new();
+    let map = MonoalphabeticSubstition::decrypt(
+      &mut cipher.to_ascii_lowercase().as_slice(),
+      &mut output,
+      inverse,
+    )?;
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.strip_prefix(&format!("{map}")), Some(plaintext));
+    Ok(())
+  }
+
+  #[test]
+  fn test_initial_guess_matches_the() -> Result<()> {
+    let plaintext = "the cat and the dog saw the bird in the tree";
+    let mut cipher = Vec::new();
+    let config = MonoalphabeticSubstitutionEncryptConfig::new(
+      Some(String::from("zebras")),
+      None,
+    );
+    MonoalphabeticSubstition::encrypt(
+      &mut plaintext.as_bytes(),
+      &mut cipher,
+      config,
+    )?;
+
+    let key = MonoalphabeticSubstition::initial_guess(
+      &String::from_utf8_lossy(&cipher),
+    );
+    let map = SubstitutionMap::from_keyword("zebras", &Alphabet::default());
+    for plain in ['T', 'H', 'E'] {
+      let c = (map.get(plain).unwrap() as u8 - b'A') as usize;
+      assert_eq!(MonoalphabeticSubstition::letter(key[c]), plain);
+    }
+
+    let mut sorted = key.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..26).collect::<Vec<_>>());
+    Ok(())
+  }
 }
@@ -0,0 +1,214 @@
+use std::io::{BufRead, Error, ErrorKind, Result, Write};
+use std::str::FromStr;
+
+use super::substitution_map::SubstitutionMap;
+use crate::frequency_analysis::FrequencyAnalyzer;
+
+const HELP: &str = "\
+Commands:
+  swap A B   exchange the plaintext letters A and B
+  set X E    decrypt the ciphertext letter X as E
+  undo       revert the last change
+  map        print the substitution map
+  help       print this help
+  done       finish and write the plaintext
+";
+
+/// A command of the interactive refinement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefineCommand {
+  Swap(char, char),
+  Set(char, char),
+  Undo,
+  Map,
+  Help,
+  Done,
+}
+
+impl FromStr for RefineCommand {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let letter = |word: &str| {
+      let mut chars = word.chars();
+      match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => {
+          Ok(c.to_ascii_uppercase())
+        }
+        _ => Err(Self::invalid(format!("Expected a letter, found '{word}'"))),
+      }
+    };
+
+    match words.as_slice() {
+      ["swap", a, b] => Ok(Self::Swap(letter(a)?, letter(b)?)),
+      ["set", cipher, plain] => Ok(Self::Set(letter(cipher)?, letter(plain)?)),
+      ["undo"] => Ok(Self::Undo),
+      ["map"] => Ok(Self::Map),
+      ["help" | "?"] => Ok(Self::Help),
+      ["done" | "quit" | "q"] => Ok(Self::Done),
+      _ => Err(Self::invalid(format!("Unknown command '{}'", s.trim()))),
+    }
+  }
+}
+
+impl RefineCommand {
+  fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+  }
+}
+
+/// Refines a recovered substitution map by hand.
+///
+/// The frequency analysis and the solvers only get most of the key
+/// right. The plaintext and its fitness are shown again after every
+/// command, so the remaining letters can be fixed one swap at a time.
+pub struct Refinement {
+  ciphertext: String,
+  map: SubstitutionMap,
+  history: Vec<SubstitutionMap>,
+}
+
+impl Refinement {
+  pub fn new(ciphertext: &str, map: SubstitutionMap) -> Self {
+    Self {
+      ciphertext: ciphertext.to_ascii_uppercase(),
+      map,
+      history: Vec::new(),
+    }
+  }
+
+  pub fn plaintext(&self) -> String {
+    self
+      .ciphertext
+      .chars()
+      .map(|c| self.map.get(c).unwrap_or(c))
+      .collect()
+  }
+
+  /// Bigram score of the plaintext, lower is more English-like.
+  pub fn fitness(&self) -> f32 {
+    FrequencyAnalyzer::bigram_score(&self.plaintext())
+  }
+
+  /// Applies a command and returns whether the refinement is done.
+  pub fn apply(&mut self, command: RefineCommand) -> bool {
+    match command {
+      RefineCommand::Swap(a, b) => {
+        self.history.push(self.map.clone());
+        self.map.swap(a, b);
+      }
+      RefineCommand::Set(cipher, plain) => {
+        self.history.push(self.map.clone());
+        self.map.set(cipher, plain);
+      }
+      RefineCommand::Undo => {
+        if let Some(map) = self.history.pop() {
+          self.map = map;
+        }
+      }
+      RefineCommand::Map | RefineCommand::Help => {}
+      RefineCommand::Done => return true,
+    }
+    false
+  }
+
+  /// Reads commands line by line until `done` or the end of the
+  /// commands and writes the plaintext after every change to `screen`.
+  pub fn run<R: BufRead, W: Write>(
+    mut self,
+    commands: &mut R,
+    screen: &mut W,
+  ) -> Result<SubstitutionMap> {
+    self.render(screen)?;
+    write!(screen, "{HELP}> ")?;
+    screen.flush()?;
+
+    let mut line = String::new();
+    while commands.read_line(&mut line)? > 0 {
+      match line.parse() {
+        Ok(command) if self.apply(command) => break,
+        Ok(RefineCommand::Map) => write!(screen, "{}", self.map)?,
+        Ok(RefineCommand::Help) => write!(screen, "{HELP}")?,
+        Ok(_) => self.render(screen)?,
+        Err(_) if line.trim().is_empty() => self.render(screen)?,
+        Err(error) => writeln!(screen, "{error}")?,
+      }
+      write!(screen, "> ")?;
+      screen.flush()?;
+      line.clear();
+    }
+
+    Ok(self.map)
+  }
+
+  fn render<W: Write>(&self, screen: &mut W) -> Result<()> {
+    let mut cipher = String::new();
+    let mut plain = String::new();
+    for c in 'A'..='Z' {
+      cipher.push(c);
+      plain.push(self.map.get(c).unwrap_or('_'));
+    }
+
+    writeln!(screen, "{}", self.plaintext().trim_end())?;
+    writeln!(screen)?;
+    writeln!(screen, "cipher: {cipher}")?;
+    writeln!(screen, "plain:  {plain}")?;
+    writeln!(screen, "fitness: {:.3}", self.fitness())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn refinement() -> Refinement {
+    let map = "A -> H\nB -> T\nC -> E".parse().unwrap();
+    Refinement::new("bac", map)
+  }
+
+  #[test]
+  fn test_parse_commands() {
+    assert_eq!(
+      "swap q e".parse::<RefineCommand>().unwrap(),
+      RefineCommand::Swap('Q', 'E')
+    );
+    assert_eq!(
+      "  set X E ".parse::<RefineCommand>().unwrap(),
+      RefineCommand::Set('X', 'E')
+    );
+    assert!("swap QE".parse::<RefineCommand>().is_err());
+    assert!("swap 1 2".parse::<RefineCommand>().is_err());
+  }
+
+  #[test]
+  fn test_swap_and_undo() {
+    let mut refinement = refinement();
+    assert_eq!(refinement.plaintext(), "THE");
+
+    refinement.apply(RefineCommand::Swap('T', 'E'));
+    assert_eq!(refinement.plaintext(), "EHT");
+
+    refinement.apply(RefineCommand::Set('A', 'T'));
+    assert_eq!(refinement.plaintext(), "ETH");
+
+    refinement.apply(RefineCommand::Undo);
+    refinement.apply(RefineCommand::Undo);
+    assert_eq!(refinement.plaintext(), "THE");
+  }
+
+  #[test]
+  fn test_run_commands() -> Result<()> {
+    let mut commands =
+      "swap T E\nbogus\n\nswap T E\ndone\nswap H E\n".as_bytes();
+    let mut screen = Vec::new();
+    let map = refinement().run(&mut commands, &mut screen)?;
+    assert_eq!(format!("{map}"), "A -> H\nB -> T\nC -> E\n");
+
+    let screen = String::from_utf8(screen).unwrap();
+    assert!(screen.starts_with("THE\n\ncipher: ABCDEF"));
+    assert!(screen.contains("EHT\n"));
+    assert!(screen.contains("Unknown command 'bogus'"));
+    Ok(())
+  }
+}
@@ -1,9 +1,16 @@
+use rand::{seq::SliceRandom, Rng};
+
 use std::collections::BTreeMap;
+
+use crate::alphabet::Alphabet;
 use std::fmt::{Debug, Display};
-use std::io::{Read, Result, Write};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::path::Path;
+use std::str::FromStr;
 
 pub type SubstitionMapType = BTreeMap<char, char>;
 
+#[derive(Clone)]
 pub struct SubstitutionMap(SubstitionMapType);
 
 impl SubstitutionMap {
@@ -14,6 +21,112 @@ impl SubstitutionMap {
   pub fn insert(&mut self, source: char, target: char) {
     self.0.insert(source, target);
   }
+
+  pub fn get(&self, source: char) -> Option<char> {
+    self.0.get(&source).copied()
+  }
+
+  /// Exchanges two target symbols, so every source mapped to one of them
+  /// maps to the other one afterwards.
+  pub fn swap(&mut self, a: char, b: char) {
+    for target in self.0.values_mut() {
+      if *target == a {
+        *target = b;
+      } else if *target == b {
+        *target = a;
+      }
+    }
+  }
+
+  /// Maps a source symbol to a target symbol. The source that mapped to
+  /// the target before takes over the previous target of the symbol, so
+  /// a permutation stays a permutation.
+  pub fn set(&mut self, source: char, target: char) {
+    match self.0.get(&source).copied() {
+      Some(previous) => self.swap(previous, target),
+      None => {
+        self.0.retain(|_, t| *t != target);
+        self.0.insert(source, target);
+      }
+    }
+  }
+
+  /// Builds the classic keyword-mixed alphabet.
+  ///
+  /// The deduplicated symbols of the keyword are followed by the
+  /// remaining symbols of the alphabet, and the plain alphabet is mapped
+  /// onto the result. Symbols of the keyword outside of the alphabet are
+  /// ignored.
+  pub fn from_keyword(keyword: &str, alphabet: &Alphabet) -> Self {
+    let mut mixed = Vec::new();
+    let keyword = keyword
+      .chars()
+      .filter_map(|c| alphabet.index(c))
+      .map(|index| alphabet.symbol(index));
+
+    for c in keyword.chain(alphabet.symbols().iter().copied()) {
+      if !mixed.contains(&c) {
+        mixed.push(c);
+      }
+    }
+
+    Self::new(alphabet.symbols().iter().copied().zip(mixed).collect())
+  }
+
+  /// Maps the plain alphabet onto a random permutation of itself.
+  pub fn random<G: Rng>(rng: &mut G, alphabet: &Alphabet) -> Self {
+    let mut mixed = alphabet.symbols().to_vec();
+    mixed.shuffle(rng);
+    Self::new(alphabet.symbols().iter().copied().zip(mixed).collect())
+  }
+}
+
+/// Parses a map in the format it is displayed in, one `source -> target`
+/// pair per line. Blank lines and everything after a `#` are ignored, so
+/// a saved map can be annotated while it is edited by hand.
+impl FromStr for SubstitutionMap {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let mut map = SubstitionMapType::new();
+
+    let lines = s
+      .lines()
+      .map(|line| line.split('#').next().unwrap_or_default().trim())
+      .filter(|line| !line.is_empty());
+
+    for line in lines {
+      let pair = line.split_once("->").and_then(|(source, target)| {
+        Some((Self::symbol(source)?, Self::symbol(target)?))
+      });
+      let (source, target) = pair.ok_or_else(|| {
+        let message = format!("Expected 'source -> target', found '{line}'");
+        Error::new(ErrorKind::InvalidData, message)
+      })?;
+      if map.insert(source, target).is_some() {
+        let message = format!("The symbol '{source}' is mapped twice");
+        return Err(Error::new(ErrorKind::InvalidData, message));
+      }
+    }
+
+    Ok(Self(map))
+  }
+}
+
+impl SubstitutionMap {
+  /// Reads a map written by [`SubstitutionMap::save`].
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+    std::fs::read_to_string(path)?.parse()
+  }
+
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    std::fs::write(path, self.to_string())
+  }
+
+  fn symbol(s: &str) -> Option<char> {
+    let mut chars = s.trim().chars();
+    chars.next().filter(|_| chars.next().is_none())
+  }
 }
 
 impl Default for SubstitutionMap {
@@ -122,6 +235,44 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_substitution_map_from_keyword() {
+    let map = SubstitutionMap::from_keyword("Secret", &Alphabet::default());
+    let mut output = Vec::new();
+
+    map
+      .apply(&mut "ABCDEFGHIJKLMNOPQRSTUVWXYZ".as_bytes(), &mut output)
+      .unwrap();
+
+    let cipher_alphabet = String::from_utf8(output).unwrap();
+    assert_eq!(cipher_alphabet, "SECRTABDFGHIJKLMNOPQUVWXYZ");
+  }
+
+  #[test]
+  fn test_substitution_map_from_keyword_with_custom_alphabet() {
+    let alphabet = Alphabet::new("ABCDEFGHIKLMNOPQRSTUVWXYZ").unwrap();
+    let map = SubstitutionMap::from_keyword("Jumping", &alphabet);
+    let mut output = Vec::new();
+
+    map
+      .apply(&mut "ABCDEFGHIKLMNOPQRSTUVWXYZ".as_bytes(), &mut output)
+      .unwrap();
+
+    let cipher_alphabet = String::from_utf8(output).unwrap();
+    assert_eq!(cipher_alphabet, "UMPINGABCDEFHKLOQRSTVWXYZ");
+  }
+
+  #[test]
+  fn test_random_substitution_map_is_permutation() {
+    let map =
+      SubstitutionMap::random(&mut rand::thread_rng(), &Alphabet::default());
+    let mut targets: Vec<char> = map.0.values().copied().collect();
+    targets.sort();
+
+    assert_eq!(map.0.keys().copied().collect::<Vec<_>>(), targets);
+    assert_eq!(targets.len(), 26);
+  }
+
   #[test]
   fn test_substitution_map_display_alphabetical_order() {
     let mut map = SubstitionMapType::new();
@@ -136,4 +287,35 @@ mod tests {
 
     assert_eq!(output, expected_output);
   }
+
+  #[test]
+  fn test_substitution_map_round_trip() {
+    let map = SubstitutionMap::from_keyword("zebras", &Alphabet::default());
+    let parsed: SubstitutionMap = map.to_string().parse().unwrap();
+    assert_eq!(parsed.0, map.0);
+  }
+
+  #[test]
+  fn test_parse_edited_substitution_map() {
+    let map: SubstitutionMap =
+      "# guessed\nQ -> E\n\n  G->T  # likely\n".parse().unwrap();
+    assert_eq!(format!("{map}"), "G -> T\nQ -> E\n");
+
+    assert!("Q -> ".parse::<SubstitutionMap>().is_err());
+    assert!("QG -> ET".parse::<SubstitutionMap>().is_err());
+    assert!("Q -> E\nQ -> A".parse::<SubstitutionMap>().is_err());
+  }
+
+  #[test]
+  fn test_swap_and_set_keep_permutation() {
+    let mut map: SubstitutionMap = "A -> X\nB -> Y\nC -> Z".parse().unwrap();
+    map.swap('X', 'Z');
+    assert_eq!(format!("{map}"), "A -> Z\nB -> Y\nC -> X\n");
+
+    map.set('B', 'X');
+    assert_eq!(format!("{map}"), "A -> Z\nB -> X\nC -> Y\n");
+
+    map.set('D', 'Z');
+    assert_eq!(format!("{map}"), "B -> X\nC -> Y\nD -> Z\n");
+  }
 }
@@ -0,0 +1,230 @@
+use rayon::prelude::*;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  frequency_analysis::FrequencyAnalyzer, report::CrackReport, DecryptCipher,
+  EncryptCipher,
+};
+
+/// The multipliers that are coprime to 26 and therefore have an inverse.
+pub const MULTIPLIERS: [usize; 12] =
+  [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MultiplicativeConfig {
+  pub multiplier: Option<usize>,
+}
+
+impl MultiplicativeConfig {
+  pub fn new(multiplier: Option<usize>) -> Self {
+    Self { multiplier }
+  }
+}
+
+impl From<&EncryptCipher> for MultiplicativeConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Multiplicative { multiplier, .. } => {
+        Self::new(Some(*multiplier))
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for MultiplicativeConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Multiplicative { multiplier, .. } => {
+        Self::new(*multiplier)
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A candidate decryption found while brute forcing the multiplier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiplicativeCandidate {
+  pub multiplier: usize,
+  pub plaintext: String,
+  pub score: f32,
+}
+
+impl From<MultiplicativeCandidate> for CrackReport {
+  fn from(candidate: MultiplicativeCandidate) -> Self {
+    let key = Some(candidate.multiplier.to_string());
+    CrackReport::new(candidate.plaintext, key, "frequency analysis")
+  }
+}
+
+pub struct Multiplicative;
+
+impl Multiplicative {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: MultiplicativeConfig,
+  ) -> Result<()> {
+    let multiplier = Self::validate(config.multiplier.unwrap_or_default())?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      writeln!(output, "{}", Self::encrypt_text(line, multiplier))?;
+    }
+
+    Ok(())
+  }
+
+  /// Decrypts every line with the configured multiplier, or with the
+  /// multiplier whose plaintext looks most like English if none is given.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: MultiplicativeConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`Multiplicative::decrypt`] and reports
+  /// the multiplier along with the method that found it.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &MultiplicativeConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let multiplier = config.multiplier.map(Self::validate).transpose()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let reports = content.lines().map(|line| match multiplier {
+      Some(multiplier) => {
+        let plaintext = Self::decrypt_text(line, multiplier);
+        CrackReport::new(plaintext, Some(multiplier.to_string()), "key")
+      }
+      None => match Self::rank(line).into_iter().next() {
+        Some(candidate) => candidate.into(),
+        None => CrackReport::new(String::new(), None, "frequency analysis"),
+      },
+    });
+    Ok(reports.collect())
+  }
+
+  /// Maps every letter `x` to `a * x mod 26`, keeping its case. Other
+  /// characters are left as they are.
+  pub fn encrypt_text(text: &str, multiplier: usize) -> String {
+    text
+      .chars()
+      .map(|c| Self::multiply(c, multiplier))
+      .collect()
+  }
+
+  /// Multiplies every letter with the inverse of the multiplier.
+  pub fn decrypt_text(text: &str, multiplier: usize) -> String {
+    let inverse = Self::inverse(multiplier).unwrap_or(1);
+    Self::encrypt_text(text, inverse)
+  }
+
+  /// Decrypts the text with all 12 valid multipliers and ranks the
+  /// candidates by how close their letters are to English, best first.
+  pub fn rank(text: &str) -> Vec<MultiplicativeCandidate> {
+    let mut candidates: Vec<MultiplicativeCandidate> = MULTIPLIERS
+      .par_iter()
+      .map(|&multiplier| {
+        let plaintext = Self::decrypt_text(text, multiplier);
+        let score = FrequencyAnalyzer::score_text(&mut plaintext.as_bytes())
+          .unwrap_or(f32::MAX);
+        MultiplicativeCandidate {
+          multiplier,
+          plaintext,
+          score,
+        }
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| {
+      a.score
+        .total_cmp(&b.score)
+        .then(a.multiplier.cmp(&b.multiplier))
+    });
+    candidates
+  }
+
+  /// The multiplier `b` with `a * b = 1 mod 26`, if `a` has one.
+  pub fn inverse(multiplier: usize) -> Option<usize> {
+    Self::inverse_mod(multiplier, 26)
+  }
+
+  /// The multiplier `b` with `a * b = 1 mod m`, if `a` has one, for
+  /// alphabets of any size.
+  pub fn inverse_mod(multiplier: usize, modulus: usize) -> Option<usize> {
+    (1..modulus).find(|b| multiplier % modulus * b % modulus == 1)
+  }
+
+  fn multiply(c: char, multiplier: usize) -> char {
+    let base = match c {
+      'a'..='z' => b'a',
+      'A'..='Z' => b'A',
+      _ => return c,
+    };
+    let x = (c as u8 - base) as usize;
+    (base + (x * (multiplier % 26) % 26) as u8) as char
+  }
+
+  fn validate(multiplier: usize) -> Result<usize> {
+    if Self::inverse(multiplier).is_none() {
+      let message = format!(
+        "The multiplier {multiplier} is not coprime to 26, valid \
+         multipliers are {MULTIPLIERS:?} modulo 26"
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    Ok(multiplier)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_encrypt_text() {
+    assert_eq!(Multiplicative::encrypt_text("abc XYZ!", 3), "adg RUX!");
+    assert_eq!(Multiplicative::decrypt_text("adg RUX!", 3), "abc XYZ!");
+  }
+
+  #[test]
+  fn test_inverse_and_validation() {
+    assert_eq!(Multiplicative::inverse(7), Some(15));
+    assert_eq!(Multiplicative::inverse(13), None);
+    assert!(MULTIPLIERS
+      .iter()
+      .all(|&a| Multiplicative::inverse(a).is_some()));
+
+    let mut output = Vec::new();
+    let config = MultiplicativeConfig::new(Some(4));
+    let result =
+      Multiplicative::encrypt(&mut Cursor::new("A"), &mut output, config);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_brute_force_decrypt() -> Result<()> {
+    let text = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG AND RUNS AWAY \
+      FROM THE HUNTER WHO FOLLOWS IT INTO THE FOREST";
+    let cipher = Multiplicative::encrypt_text(text, 11);
+    assert_eq!(Multiplicative::rank(&cipher).len(), 12);
+    assert_eq!(Multiplicative::rank(&cipher)[0].multiplier, 11);
+
+    let mut output = Vec::new();
+    let config = MultiplicativeConfig::default();
+    Multiplicative::decrypt(&mut Cursor::new(cipher), &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}\n"));
+    Ok(())
+  }
+}
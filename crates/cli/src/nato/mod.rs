@@ -0,0 +1,182 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::Command;
+
+/// Code words of the letters A to Z.
+const LETTERS: [&str; 26] = [
+  "Alfa", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel",
+  "India", "Juliett", "Kilo", "Lima", "Mike", "November", "Oscar", "Papa",
+  "Quebec", "Romeo", "Sierra", "Tango", "Uniform", "Victor", "Whiskey",
+  "X-ray", "Yankee", "Zulu",
+];
+
+/// Code words of the digits 0 to 9.
+const DIGITS: [&str; 10] = [
+  "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight",
+  "Nine",
+];
+
+/// Spellings accepted when decoding besides the code words themselves,
+/// such as the English spellings and the ICAO pronunciations of digits.
+const VARIANTS: [(&str, char); 10] = [
+  ("alpha", 'A'),
+  ("juliet", 'J'),
+  ("whisky", 'W'),
+  ("exray", 'X'),
+  ("tree", '3'),
+  ("fower", '4'),
+  ("fife", '5'),
+  ("ait", '8'),
+  ("niner", '9'),
+  ("nought", '0'),
+];
+
+/// Separates the words of the text in the spelled output.
+const WORD_SEPARATOR: &str = "/";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct NatoConfig {
+  pub decode: bool,
+}
+
+impl NatoConfig {
+  pub fn new(decode: bool) -> Self {
+    Self { decode }
+  }
+}
+
+impl From<&Command> for NatoConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Nato { decode, .. } => Self::new(*decode),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Nato;
+
+impl Nato {
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: NatoConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let line = if config.decode {
+        Self::decode(line)?
+      } else {
+        Self::encode(line)
+      };
+      writeln!(output, "{line}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Spells every letter and digit with its code word. Words of the text
+  /// are separated by a slash and other characters are kept.
+  pub fn encode(text: &str) -> String {
+    text
+      .split_whitespace()
+      .map(|word| {
+        word
+          .chars()
+          .map(|c| match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => LETTERS[(c as u8 - b'A') as usize].to_string(),
+            c @ '0'..='9' => DIGITS[(c as u8 - b'0') as usize].to_string(),
+            c => c.to_string(),
+          })
+          .collect::<Vec<_>>()
+          .join(" ")
+      })
+      .collect::<Vec<_>>()
+      .join(&format!(" {WORD_SEPARATOR} "))
+  }
+
+  /// Turns code words back into letters and digits. Case, hyphens and
+  /// common spelling variants are ignored, a slash starts a new word and
+  /// single characters that are not letters are kept.
+  pub fn decode(text: &str) -> Result<String> {
+    let mut decoded = String::new();
+
+    for token in text.split_whitespace() {
+      if token == WORD_SEPARATOR {
+        decoded.push(' ');
+        continue;
+      }
+      match Self::code(token) {
+        Some(c) => decoded.push(c),
+        None => match token.chars().next() {
+          Some(c) if token.len() == 1 && !c.is_ascii_alphabetic() => {
+            decoded.push(c)
+          }
+          _ => {
+            let message = format!("Unknown code word '{token}'");
+            return Err(Error::new(ErrorKind::InvalidData, message));
+          }
+        },
+      }
+    }
+
+    Ok(decoded)
+  }
+
+  /// The character spelled by a code word or one of its variants.
+  fn code(token: &str) -> Option<char> {
+    let word: String = token
+      .chars()
+      .filter(char::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_lowercase())
+      .collect();
+    if word.is_empty() {
+      return None;
+    }
+    let matches = |code: &&str| {
+      code
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase())
+        .eq(word.chars())
+    };
+
+    LETTERS
+      .iter()
+      .position(matches)
+      .map(|i| (b'A' + i as u8) as char)
+      .or_else(|| {
+        DIGITS
+          .iter()
+          .position(matches)
+          .map(|i| (b'0' + i as u8) as char)
+      })
+      .or_else(|| {
+        VARIANTS
+          .iter()
+          .find(|(variant, _)| *variant == word)
+          .map(|&(_, c)| c)
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_encode() {
+    assert_eq!(Nato::encode("Hi x5!"), "Hotel India / X-ray Five !");
+  }
+
+  #[test]
+  fn test_decode_variants() -> Result<()> {
+    let spelled = "alpha BRAVO / juliet X-RAY xray whisky / niner tree Zero";
+    assert_eq!(Nato::decode(spelled)?, "AB JXXW 930");
+    assert_eq!(Nato::decode(&Nato::encode("Meet at 10."))?, "MEET AT 10.");
+    assert!(Nato::decode("Alfa Bravissimo").is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,154 @@
+use clap::ValueEnum;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::Command;
+
+/// The parts of the cover text a letter is taken from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NullUnit {
+  /// Every word, separated by whitespace.
+  #[default]
+  Word,
+  /// Every non-empty line, as in an acrostic.
+  Line,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NullCipherConfig {
+  pub unit: NullUnit,
+  /// One-based positions of the letter taken from each unit, repeated
+  /// as a pattern over the units.
+  pub positions: Vec<usize>,
+  /// Take a letter from every nth unit only.
+  pub every: usize,
+  /// Count the positions from the end of each unit.
+  pub from_end: bool,
+}
+
+impl NullCipherConfig {
+  pub fn new(
+    unit: NullUnit,
+    positions: Vec<usize>,
+    every: usize,
+    from_end: bool,
+  ) -> Self {
+    Self {
+      unit,
+      positions,
+      every,
+      from_end,
+    }
+  }
+}
+
+impl Default for NullCipherConfig {
+  fn default() -> Self {
+    Self::new(NullUnit::default(), vec![1], 1, false)
+  }
+}
+
+impl From<&Command> for NullCipherConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::NullCipher {
+        unit,
+        positions,
+        every,
+        from_end,
+        ..
+      } => Self::new(*unit, positions.clone(), *every, *from_end),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct NullCipher;
+
+impl NullCipher {
+  /// Writes the hidden message of the cover text on a line.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: NullCipherConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    writeln!(output, "{}", Self::extract(&content, &config)?)
+  }
+
+  /// Takes the letter at the next position of the pattern from every
+  /// selected unit. Only letters are counted, so punctuation does not
+  /// shift the positions, and units that are too short are skipped.
+  pub fn extract(text: &str, config: &NullCipherConfig) -> Result<String> {
+    if config.every == 0 || config.positions.contains(&0) {
+      let message = "Positions and the unit step must be greater than zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let units: Vec<&str> = match config.unit {
+      NullUnit::Word => text.split_whitespace().collect(),
+      NullUnit::Line => text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect(),
+    };
+    let mut positions = config.positions.iter().cycle();
+
+    let message = units
+      .iter()
+      .step_by(config.every)
+      .filter_map(|unit| {
+        let position = positions.next().copied().unwrap_or(1);
+        let letters: Vec<char> =
+          unit.chars().filter(char::is_ascii_alphabetic).collect();
+        let index = match config.from_end {
+          true => letters.len().checked_sub(position)?,
+          false => position - 1,
+        };
+        letters.get(index).copied()
+      })
+      .collect();
+
+    Ok(message)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn extract(text: &str, config: NullCipherConfig) -> String {
+    NullCipher::extract(text, &config).unwrap()
+  }
+
+  #[test]
+  fn test_first_letter_of_words() {
+    let text = "Hurry, everyone! Leave; prepare muskets.";
+    assert_eq!(extract(text, NullCipherConfig::default()), "HeLpm");
+  }
+
+  #[test]
+  fn test_acrostic_lines() {
+    let text = "Sunlight fades\n\nOver the hill\nSilent night\n";
+    let config = NullCipherConfig::new(NullUnit::Line, vec![1], 1, false);
+    assert_eq!(extract(text, config), "SOS");
+  }
+
+  #[test]
+  fn test_pattern_and_step() {
+    let text = "abc def ghi jkl mno";
+    let config = NullCipherConfig::new(NullUnit::Word, vec![1, 2], 2, false);
+    assert_eq!(extract(text, config), "ahm");
+
+    let config = NullCipherConfig::new(NullUnit::Word, vec![1], 1, true);
+    assert_eq!(extract("cab it, go", config), "bto");
+  }
+
+  #[test]
+  fn test_zero_position_is_rejected() {
+    let config = NullCipherConfig::new(NullUnit::Word, vec![0], 1, false);
+    assert!(NullCipher::extract("text", &config).is_err());
+  }
+}
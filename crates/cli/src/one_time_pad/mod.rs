@@ -1,26 +1,8 @@
-use std::{
-  fmt::Display,
-  io::{Read, Write},
-};
+pub mod reuse;
 
-use crate::{hex::Hex, xor::Xor, DecryptCipher, EncryptCipher};
+pub use cryptology_core::one_time_pad::*;
 
-#[derive(Default, Debug)]
-pub struct OneTimePadDecryptConfig {
-  pub key: Option<String>,
-  pub raw_input: bool,
-  pub raw_key: bool,
-}
-
-impl OneTimePadDecryptConfig {
-  pub fn new(key: Option<String>, raw_input: bool, raw_key: bool) -> Self {
-    Self {
-      key,
-      raw_input,
-      raw_key,
-    }
-  }
-}
+use crate::{DecryptCipher, EncryptCipher};
 
 impl From<&DecryptCipher> for OneTimePadDecryptConfig {
   fn from(value: &DecryptCipher) -> Self {
@@ -28,199 +10,98 @@ impl From<&DecryptCipher> for OneTimePadDecryptConfig {
       DecryptCipher::OneTimePad {
         key,
         raw_input,
-        raw_key,
+        key_format,
+        force,
         ..
-      } => OneTimePadDecryptConfig::new(key.key.clone(), *raw_input, *raw_key),
+      } => OneTimePadDecryptConfig {
+        force: *force,
+        ..OneTimePadDecryptConfig::new(
+          key.key.clone(),
+          *raw_input,
+          key_format.key_format(),
+        )
+      },
       _ => OneTimePadDecryptConfig::default(),
     }
   }
 }
 
-#[derive(Default, Debug)]
-pub struct OneTimePadEncryptConfig {
-  pub key: String,
-  pub raw_input: bool,
-  pub raw_key: bool,
-}
-
-impl OneTimePadEncryptConfig {
-  pub fn new(key: String, raw_input: bool, raw_key: bool) -> Self {
-    Self {
-      key,
-      raw_input,
-      raw_key,
-    }
-  }
-}
-
 impl From<&EncryptCipher> for OneTimePadEncryptConfig {
   fn from(value: &EncryptCipher) -> Self {
     match value {
       EncryptCipher::OneTimePad {
         key,
         raw_input,
-        raw_key,
+        key_format,
+        generate_key,
+        seed,
+        force,
         ..
-      } => OneTimePadEncryptConfig::new(key.key.clone(), *raw_input, *raw_key),
+      } => OneTimePadEncryptConfig {
+        generate_key: *generate_key || key.is_none(),
+        seed: *seed,
+        force: *force,
+        ..OneTimePadEncryptConfig::new(
+          key.clone().unwrap_or_default(),
+          *raw_input,
+          key_format.key_format(),
+        )
+      },
       _ => OneTimePadEncryptConfig::default(),
     }
   }
 }
 
-#[derive(Default, PartialEq, Eq)]
-pub struct OneTimePad {
-  pub xor: Xor,
-}
-
-impl Display for OneTimePad {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let xor = &self.xor;
-    write!(f, "{xor}")
-  }
-}
-
-impl OneTimePad {
-  pub fn new(xor: Xor) -> Self {
-    Self { xor }
-  }
-
-  pub fn encrypt<R: Read, W: Write>(
-    input: &mut R,
-    output: &mut W,
-    config: &mut OneTimePadEncryptConfig,
-  ) -> std::io::Result<Self> {
-    let mut plaintext = String::new();
-
-    input.read_to_string(&mut plaintext)?;
-
-    let alpha = if config.raw_input {
-      Hex::parse_hex(&plaintext).unwrap()
-    } else {
-      plaintext.try_into().unwrap()
-    };
-
-    let beta = if config.raw_key {
-      Hex::parse_hex(&config.key).unwrap()
-    } else {
-      config.key.as_str().try_into().unwrap()
-    };
-
-    let xor = Xor::xor_bytes_padded(&alpha.bytes, &beta.bytes, 0);
-    let otp = Self::new(xor);
-
-    write!(output, "{otp}")?;
-
-    Ok(otp)
-  }
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
 
-  pub fn decrypt<R: Read, W: Write>(
-    input: &mut R,
-    output: &mut W,
-    config: &mut OneTimePadDecryptConfig,
-  ) -> std::io::Result<()> {
-    for line in Self::decrypt_lines(input, config)? {
-      writeln!(output, "{line}")?;
+  use super::*;
+  use crate::{with_command_stack, Command, Cryptology, KeyFormat};
+
+  fn decrypt_config(args: &[&str]) -> std::io::Result<OneTimePadDecryptConfig> {
+    let cli = with_command_stack(|| Cryptology::try_parse_from(args).unwrap());
+    let mut command = cli.command;
+    command.read_key_files()?;
+    match &command {
+      Command::Decrypt { cipher, .. } => Ok(cipher.into()),
+      _ => unreachable!(),
     }
-    Ok(())
   }
 
-  pub fn decrypt_lines<R: Read>(
-    input: &mut R,
-    config: &mut OneTimePadDecryptConfig,
-  ) -> std::io::Result<Vec<String>> {
-    let mut ciphertext = String::new();
-
-    input.read_to_string(&mut ciphertext)?;
-
-    let decryption: Vec<String> = ciphertext
-      .lines()
-      .map(|line| Self::decrypt_line(line, config).unwrap())
-      .collect();
-
-    Ok(decryption)
-  }
-
-  pub fn decrypt_line(
-    line: &str,
-    config: &mut OneTimePadDecryptConfig,
-  ) -> std::io::Result<String> {
-    let key = config.key.clone().unwrap_or_default();
-
-    let alpha = if config.raw_input {
-      Hex::parse_hex(line).unwrap()
-    } else {
-      line.try_into().unwrap()
-    };
-
-    let beta = if config.raw_key {
-      Hex::parse_hex(key.as_str()).unwrap()
-    } else {
-      key.try_into().unwrap()
-    };
-
-    let xor = Xor::xor_bytes_padded(&alpha.bytes, &beta.bytes, 0);
-    let otp = Self::new(xor);
-    let fmt = format!("{otp}");
-
-    Ok(fmt)
+  fn encrypt_config(args: &[&str]) -> OneTimePadEncryptConfig {
+    let cli = with_command_stack(|| Cryptology::try_parse_from(args).unwrap());
+    match &cli.command {
+      Command::Encrypt { cipher, .. } => cipher.into(),
+      _ => unreachable!(),
+    }
   }
-}
-
-#[cfg(test)]
-mod tests {
-  use crate::Command;
-
-  use super::*;
 
   #[test]
-  fn test_otp() {
-    let mut input = Command::get_readable("Hello");
-    let mut output = Vec::new();
-
-    let mut cfg = OneTimePadDecryptConfig {
-      key: Some(String::from("World")),
-      raw_input: false,
-      raw_key: false,
-    };
-
-    OneTimePad::decrypt(&mut input, &mut output, &mut cfg).unwrap();
-
-    let result = String::from_utf8(output).unwrap();
+  fn test_pad_is_generated_without_key() {
+    let config = encrypt_config(&["cryptology", "encrypt", "otp"]);
+    assert!(config.generate_key);
 
-    assert_eq!(result, "1f0a1e000b\n")
+    let config = encrypt_config(&["cryptology", "encrypt", "otp", "-k", "K"]);
+    assert!(!config.generate_key);
   }
 
   #[test]
-  fn test_otp_example() {
-    let mut input = Command::get_readable("attack at dawn");
-    let mut output = Vec::new();
-
-    let ciphertext = String::from("09e1c5f70a65ac519458e7e53f36");
-
-    let mut cfg = OneTimePadEncryptConfig {
-      key: ciphertext,
-      raw_input: false,
-      raw_key: true,
-    };
-
-    let key = OneTimePad::encrypt(&mut input, &mut output, &mut cfg).unwrap();
-
-    let mut input = Command::get_readable("attack at dusk");
-
-    let key = format!("{key}");
-
-    let mut cfg = OneTimePadEncryptConfig {
-      key,
-      raw_input: false,
-      raw_key: true,
-    };
-
-    let otp = OneTimePad::encrypt(&mut input, &mut output, &mut cfg).unwrap();
-
-    let result = format!("{otp}");
-
-    let expected = "09e1c5f70a65ac519458e7f13b33";
-
-    assert_eq!(result, expected)
+  fn test_pad_file_is_hex() -> std::io::Result<()> {
+    let path = std::env::temp_dir().join("cryptology-otp-pad.txt");
+    std::fs::write(&path, "09e1c5f70a65\n")?;
+    let pad = path.to_str().unwrap();
+
+    let args = ["cryptology", "decrypt", "otp", "--key-file", pad];
+    let config = decrypt_config(&args)?;
+    assert_eq!(config.key.as_deref(), Some("09e1c5f70a65"));
+    assert_eq!(config.key_format, KeyFormat::Hex);
+
+    let args = [&args[..], &["--key-format", "ascii"]].concat();
+    assert_eq!(decrypt_config(&args)?.key_format, KeyFormat::Ascii);
+
+    let args = ["cryptology", "decrypt", "otp", "-k", "09e1c5f70a65"];
+    assert_eq!(decrypt_config(&args)?.key_format, KeyFormat::Ascii);
+    std::fs::remove_file(&path)
   }
 }
@@ -0,0 +1,332 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{hex::Hex, many_time_pad::ManyTimePad, xor::XorInput, Command};
+
+/// Score above which two ciphertexts are reported as sharing a pad.
+pub const REUSE_THRESHOLD: f32 = 0.7;
+
+#[derive(Debug, PartialEq)]
+pub struct PadReuseConfig {
+  pub alpha: Option<XorInput>,
+  pub beta: Option<XorInput>,
+  pub threshold: f32,
+}
+
+impl PadReuseConfig {
+  pub fn new(alpha: XorInput, beta: XorInput, threshold: f32) -> Self {
+    Self {
+      alpha: Some(alpha),
+      beta: Some(beta),
+      threshold,
+    }
+  }
+}
+
+impl Default for PadReuseConfig {
+  fn default() -> Self {
+    Self {
+      alpha: None,
+      beta: None,
+      threshold: REUSE_THRESHOLD,
+    }
+  }
+}
+
+impl From<&Command> for PadReuseConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::CheckReuse {
+        alpha,
+        beta,
+        threshold,
+        ..
+      } => Self::new(
+        XorInput::from_path(alpha),
+        XorInput::from_path(beta),
+        *threshold,
+      ),
+      _ => Self::default(),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DetectReuseConfig {
+  pub threshold: f32,
+  /// Only write the ciphertexts of this group, one hex line each.
+  pub group: Option<usize>,
+}
+
+impl DetectReuseConfig {
+  pub fn new(threshold: f32, group: Option<usize>) -> Self {
+    Self { threshold, group }
+  }
+}
+
+impl Default for DetectReuseConfig {
+  fn default() -> Self {
+    Self::new(REUSE_THRESHOLD, None)
+  }
+}
+
+impl From<&Command> for DetectReuseConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::DetectReuse {
+        threshold, group, ..
+      } => Self::new(*threshold, *group),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// Detection of two ciphertexts encrypted with the same one-time pad.
+pub struct PadReuse;
+
+impl PadReuse {
+  /// Reads two hex ciphertexts and writes whether they likely share a
+  /// pad together with their score.
+  pub fn check<W: Write>(output: &mut W, config: PadReuseConfig) -> Result<()> {
+    let (Some(alpha), Some(beta)) = (&config.alpha, &config.beta) else {
+      let message = "Checking pad reuse requires two ciphertexts";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    };
+
+    if alpha == &XorInput::Stdin && beta == &XorInput::Stdin {
+      let message = "Only one ciphertext can be read from stdin";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let score = Self::score(&alpha.read(true)?, &beta.read(true)?);
+    let verdict = match score >= config.threshold {
+      true => "likely reused",
+      false => "probably independent",
+    };
+
+    writeln!(output, "{verdict} ({score:.2})")
+  }
+
+  /// Reads hex ciphertexts, one per line, and writes every pair that
+  /// likely shares a pad followed by the ciphertexts grouped by pad.
+  ///
+  /// With a group index only the ciphertexts of that group are written
+  /// in the input format of `many-time-pad`.
+  pub fn detect<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: DetectReuseConfig,
+  ) -> Result<()> {
+    let ciphertexts = ManyTimePad::read_ciphertexts(input)?;
+    let groups = Self::groups(&ciphertexts, config.threshold);
+
+    if let Some(index) = config.group {
+      let Some(group) = groups.get(index) else {
+        let message = format!("There are only {} groups", groups.len());
+        return Err(Error::new(ErrorKind::InvalidInput, message));
+      };
+
+      for &cipher in group {
+        writeln!(output, "{}", Hex::new(ciphertexts[cipher].clone()))?;
+      }
+      return Ok(());
+    }
+
+    writeln!(output, "pairs:")?;
+    for (alpha, beta, score) in Self::pairs(&ciphertexts, config.threshold) {
+      writeln!(output, "{alpha} {beta} ({score:.2})")?;
+    }
+
+    writeln!(output, "groups:")?;
+    for (index, group) in groups.iter().enumerate() {
+      let members: Vec<String> = group.iter().map(usize::to_string).collect();
+      writeln!(output, "{index}: {}", members.join(" "))?;
+    }
+
+    Ok(())
+  }
+
+  /// Indices and scores of all pairs of ciphertexts that likely share a
+  /// pad.
+  pub fn pairs(
+    ciphertexts: &[Vec<u8>],
+    threshold: f32,
+  ) -> Vec<(usize, usize, f32)> {
+    let mut pairs = Vec::new();
+
+    for (alpha, first) in ciphertexts.iter().enumerate() {
+      for (beta, second) in ciphertexts.iter().enumerate().skip(alpha + 1) {
+        let score = Self::score(first, second);
+        if score >= threshold {
+          pairs.push((alpha, beta, score));
+        }
+      }
+    }
+
+    pairs
+  }
+
+  /// Partitions the ciphertexts into groups that are connected by likely
+  /// reused pads. Groups are ordered by their first ciphertext.
+  pub fn groups(ciphertexts: &[Vec<u8>], threshold: f32) -> Vec<Vec<usize>> {
+    let mut parents: Vec<usize> = (0..ciphertexts.len()).collect();
+
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+      while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+      }
+      index
+    }
+
+    for (alpha, beta, _) in Self::pairs(ciphertexts, threshold) {
+      let (alpha, beta) = (root(&mut parents, alpha), root(&mut parents, beta));
+      parents[alpha.max(beta)] = alpha.min(beta);
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+
+    for index in 0..ciphertexts.len() {
+      let parent = root(&mut parents, index);
+      match roots.iter().position(|&root| root == parent) {
+        Some(group) => groups[group].push(index),
+        None => {
+          roots.push(parent);
+          groups.push(vec![index]);
+        }
+      }
+    }
+
+    groups
+  }
+
+  /// Share of the XOR of both ciphertexts that looks like the XOR of two
+  /// English texts.
+  ///
+  /// With a shared pad the XOR equals the XOR of the plaintexts. Two
+  /// letters of the same case XOR to a control character and a space
+  /// XORed with a letter flips its case, so most bytes fall into these
+  /// ranges. Independent pads give uniformly random bytes, of which only
+  /// about a third do.
+  pub fn score(alpha: &[u8], beta: &[u8]) -> f32 {
+    let length = alpha.len().min(beta.len());
+    if length == 0 {
+      return 0.0;
+    }
+
+    let matching = alpha
+      .iter()
+      .zip(beta)
+      .map(|(alpha, beta)| alpha ^ beta)
+      .filter(|&byte| byte < 0x20 || byte.is_ascii_alphabetic())
+      .count();
+
+    matching as f32 / length as f32
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+  fn pad(seed: u64) -> Vec<u8> {
+    let mut pad = vec![0; 64];
+    StdRng::seed_from_u64(seed).fill_bytes(&mut pad);
+    pad
+  }
+
+  fn encrypt(plaintext: &[u8], pad: &[u8]) -> Vec<u8> {
+    plaintext
+      .iter()
+      .zip(pad)
+      .map(|(byte, pad)| byte ^ pad)
+      .collect()
+  }
+
+  #[test]
+  fn test_score_detects_reuse() {
+    let shared = pad(0);
+    let alpha = encrypt(
+      b"we will attack the northern bridge at dawn tomorrow",
+      &shared,
+    );
+    let beta = encrypt(
+      b"send more supplies to the camp before it gets dark",
+      &shared,
+    );
+    assert!(PadReuse::score(&alpha, &beta) > REUSE_THRESHOLD);
+
+    let other = pad(1);
+    let beta = encrypt(
+      b"send more supplies to the camp before it gets dark",
+      &other,
+    );
+    assert!(PadReuse::score(&alpha, &beta) < REUSE_THRESHOLD);
+  }
+
+  #[test]
+  fn test_groups() -> Result<()> {
+    let (first, second) = (pad(0), pad(1));
+    let ciphertexts = [
+      encrypt(
+        b"we will attack the northern bridge at dawn tomorrow",
+        &first,
+      ),
+      encrypt(
+        b"the quick brown fox jumps over the lazy dog again",
+        &second,
+      ),
+      encrypt(
+        b"send more supplies to the camp before it gets dark",
+        &first,
+      ),
+      encrypt(
+        b"meet me at the old mill after the sun has gone down",
+        &second,
+      ),
+      encrypt(
+        b"nothing else shares the pad that this message uses",
+        &pad(2),
+      ),
+    ];
+
+    let groups = PadReuse::groups(&ciphertexts, REUSE_THRESHOLD);
+    assert_eq!(groups, [vec![0, 2], vec![1, 3], vec![4]]);
+
+    let input: Vec<String> = ciphertexts
+      .iter()
+      .map(|cipher| Hex::new(cipher.clone()).to_string())
+      .collect();
+    let input = input.join("\n");
+
+    let mut output = Vec::new();
+    let config = DetectReuseConfig::new(REUSE_THRESHOLD, Some(1));
+    PadReuse::detect(&mut input.as_bytes(), &mut output, config)?;
+    let expected = format!(
+      "{}\n{}\n",
+      Hex::new(ciphertexts[1].clone()),
+      Hex::new(ciphertexts[3].clone())
+    );
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+    let config = DetectReuseConfig::new(REUSE_THRESHOLD, Some(3));
+    assert!(
+      PadReuse::detect(&mut input.as_bytes(), &mut Vec::new(), config).is_err()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_check() -> Result<()> {
+    let alpha = XorInput::Value(String::from("0102"));
+    let beta = XorInput::Value(String::from("4803"));
+    let mut output = Vec::new();
+    PadReuse::check(&mut output, PadReuseConfig::new(alpha, beta, 0.7))?;
+    assert_eq!(String::from_utf8(output).unwrap(), "likely reused (1.00)\n");
+
+    let config = PadReuseConfig::new(XorInput::Stdin, XorInput::Stdin, 0.7);
+    assert!(PadReuse::check(&mut Vec::new(), config).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,336 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Result, Write};
+use std::path::PathBuf;
+
+use crate::dictionary_attack::LEET;
+use crate::text::spacing::Spacing;
+use crate::Command;
+
+/// The most common passwords, most common first. They are looked up
+/// before the common English words.
+const COMMON_PASSWORDS: [&str; 30] = [
+  "123456",
+  "password",
+  "123456789",
+  "12345678",
+  "12345",
+  "qwerty",
+  "abc123",
+  "111111",
+  "1234567",
+  "dragon",
+  "123123",
+  "baseball",
+  "iloveyou",
+  "trustno1",
+  "1234567890",
+  "sunshine",
+  "master",
+  "welcome",
+  "shadow",
+  "ashley",
+  "football",
+  "jesus",
+  "monkey",
+  "ninja",
+  "mustang",
+  "letmein",
+  "admin",
+  "princess",
+  "qwertyuiop",
+  "solo",
+];
+
+/// Shortest word that is looked up in the dictionary.
+const MIN_WORD_LENGTH: usize = 3;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PasswordEntropyConfig {
+  /// Wordlist ordered by frequency, instead of the common passwords and
+  /// English words.
+  pub dictionary: Option<PathBuf>,
+}
+
+impl PasswordEntropyConfig {
+  pub fn new(dictionary: Option<PathBuf>) -> Self {
+    Self { dictionary }
+  }
+}
+
+impl From<&Command> for PasswordEntropyConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::PasswordEntropy { dictionary, .. } => {
+        Self::new(dictionary.clone())
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The estimated entropy of a password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordEstimate {
+  /// Bits if every character were drawn at random from all classes the
+  /// password uses.
+  pub classes: f32,
+  /// Bits of the cheapest split of the password into dictionary words,
+  /// possibly capitalized or in leetspeak, and single characters.
+  pub bits: f32,
+  /// The dictionary words of the cheapest split.
+  pub words: Vec<String>,
+}
+
+impl PasswordEstimate {
+  pub fn strength(&self) -> &'static str {
+    match self.bits {
+      bits if bits < 28.0 => "very weak",
+      bits if bits < 36.0 => "weak",
+      bits if bits < 60.0 => "reasonable",
+      bits if bits < 128.0 => "strong",
+      _ => "very strong",
+    }
+  }
+}
+
+/// A wordlist ranked by frequency.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+  /// The rank of every lowercase word, starting at 1.
+  ranks: HashMap<String, usize>,
+  /// Every prefix of every word, for stopping a lookup early.
+  prefixes: HashSet<String>,
+}
+
+impl Dictionary {
+  /// Ranks the words of the wordlist in their order. Words that occur
+  /// again keep their first rank.
+  pub fn new(wordlist: &str) -> Self {
+    let mut dictionary = Self::default();
+    for word in wordlist.split_whitespace().map(str::to_lowercase) {
+      let rank = dictionary.ranks.len() + 1;
+      for (end, _) in word.char_indices().skip(1) {
+        dictionary.prefixes.insert(word[..end].to_string());
+      }
+      dictionary.prefixes.insert(word.clone());
+      dictionary.ranks.entry(word).or_insert(rank);
+    }
+    dictionary
+  }
+}
+
+pub struct PasswordEntropy;
+
+impl PasswordEntropy {
+  /// Estimates the entropy of every password, one per line.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: PasswordEntropyConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let wordlist = match &config.dictionary {
+      Some(path) => fs::read_to_string(path)?,
+      None => COMMON_PASSWORDS
+        .into_iter()
+        .chain(Spacing::words())
+        .collect::<Vec<_>>()
+        .join("\n"),
+    };
+    let dictionary = Dictionary::new(&wordlist);
+
+    for password in content.lines().filter(|line| !line.is_empty()) {
+      let estimate = Self::estimate(password, &dictionary);
+      write!(
+        output,
+        "{password}: {:.1} bits, {} (character classes {:.1}",
+        estimate.bits,
+        estimate.strength(),
+        estimate.classes
+      )?;
+      match estimate.words.is_empty() {
+        true => writeln!(output, ")")?,
+        false => writeln!(output, ", words {})", estimate.words.join(" "))?,
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Splits the password into dictionary words and single characters so
+  /// that the sum of their bits is lowest.
+  ///
+  /// A word costs the bits of its rank, plus one bit if it is
+  /// capitalized or all uppercase, a bit per letter with a mixed case
+  /// and a bit per substituted character. A single character costs the
+  /// bits of the size of its class.
+  pub fn estimate(password: &str, dictionary: &Dictionary) -> PasswordEstimate {
+    let chars: Vec<char> = password.chars().collect();
+    let pool: usize = chars
+      .iter()
+      .map(|&c| Self::class(c))
+      .collect::<BTreeSet<_>>()
+      .into_iter()
+      .sum();
+    let classes = chars.len() as f32 * (pool.max(1) as f32).log2();
+
+    // The bits of the cheapest split of every prefix and the start of
+    // its last word, if it ends in one.
+    let mut cheapest = vec![(f32::INFINITY, None); chars.len() + 1];
+    cheapest[0].0 = 0.0;
+    for start in 0..chars.len() {
+      let bits = cheapest[start].0;
+      let single = bits + (Self::class(chars[start]) as f32).log2();
+      if single < cheapest[start + 1].0 {
+        cheapest[start + 1] = (single, None);
+      }
+      for (end, word) in Self::words(&chars, start, dictionary) {
+        if bits + word < cheapest[end].0 {
+          cheapest[end] = (bits + word, Some(start));
+        }
+      }
+    }
+
+    let mut words = Vec::new();
+    let mut end = chars.len();
+    while end > 0 {
+      match cheapest[end].1 {
+        Some(start) => {
+          words.push(chars[start..end].iter().collect());
+          end = start;
+        }
+        None => end -= 1,
+      }
+    }
+    words.reverse();
+
+    PasswordEstimate {
+      classes,
+      bits: cheapest[chars.len()].0,
+      words,
+    }
+  }
+
+  /// The ends and bits of the dictionary words the password spells from
+  /// `start` on, reading digits and symbols as the letters they commonly
+  /// stand for.
+  fn words(
+    chars: &[char],
+    start: usize,
+    dictionary: &Dictionary,
+  ) -> Vec<(usize, f32)> {
+    let mut found = Vec::new();
+    // The prefixes of dictionary words the characters can spell, with
+    // the number of substitutions each needs.
+    let mut spellings = vec![(String::new(), 0)];
+    for end in start + 1..=chars.len() {
+      let c = chars[end - 1];
+      let mut letters: Vec<(char, usize)> =
+        c.to_lowercase().take(1).map(|c| (c, 0)).collect();
+      letters.extend(Self::leet(c).unwrap_or_default().iter().map(|&c| (c, 1)));
+
+      spellings = spellings
+        .iter()
+        .flat_map(|(word, substitutions)| {
+          letters.iter().map(move |(c, substitution)| {
+            (format!("{word}{c}"), substitutions + substitution)
+          })
+        })
+        .filter(|(word, _)| dictionary.prefixes.contains(word))
+        .collect();
+      if spellings.is_empty() {
+        break;
+      }
+      if end - start < MIN_WORD_LENGTH {
+        continue;
+      }
+
+      let bits = spellings
+        .iter()
+        .filter_map(|(word, substitutions)| {
+          let rank = *dictionary.ranks.get(word)? as f32;
+          Some(rank.log2().max(1.0) + *substitutions as f32)
+        })
+        .min_by(f32::total_cmp);
+      if let Some(bits) = bits {
+        found.push((end, bits + Self::case(&chars[start..end])));
+      }
+    }
+    found
+  }
+
+  /// Bits for the case of the letters of a word.
+  fn case(segment: &[char]) -> f32 {
+    let letters: Vec<&char> =
+      segment.iter().filter(|c| c.is_alphabetic()).collect();
+    let upper = letters.iter().filter(|c| c.is_uppercase()).count();
+    match upper {
+      0 => 0.0,
+      _ if upper == letters.len() => 1.0,
+      1 if letters[0].is_uppercase() => 1.0,
+      _ => letters.len() as f32,
+    }
+  }
+
+  fn leet(c: char) -> Option<&'static [char]> {
+    LEET
+      .iter()
+      .find(|(symbol, _)| *symbol == c)
+      .map(|(_, letters)| *letters)
+  }
+
+  /// Size of the class of a character: lowercase, uppercase, digits,
+  /// printable symbols or anything else.
+  fn class(c: char) -> usize {
+    match c {
+      'a'..='z' | 'A'..='Z' => 26,
+      '0'..='9' => 10,
+      ' '..='~' => 33,
+      _ => 100,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn dictionary() -> Dictionary {
+    Dictionary::new("password dragon monkey correct horse battery staple")
+  }
+
+  #[test]
+  fn test_dictionary_words() {
+    let dictionary = dictionary();
+    let plain = PasswordEntropy::estimate("password", &dictionary);
+    assert_eq!(plain.words, ["password"]);
+    assert_eq!(plain.bits, 1.0);
+    assert!(plain.classes > 37.0);
+
+    let leet = PasswordEntropy::estimate("P@55w0rd!", &dictionary);
+    assert_eq!(leet.words, ["P@55w0rd"]);
+    // Rank 1, capitalized and four substitutions, then a symbol.
+    assert_eq!(leet.bits, 1.0 + 1.0 + 4.0 + 33f32.log2());
+    assert_eq!(leet.strength(), "very weak");
+  }
+
+  #[test]
+  fn test_random_characters() {
+    let estimate = PasswordEntropy::estimate("x9#Qv!2m", &dictionary());
+    assert!(estimate.words.is_empty());
+    let expected = 4.0 * 26f32.log2() + 2.0 * 10f32.log2() + 2.0 * 33f32.log2();
+    assert!((estimate.bits - expected).abs() < 1e-3);
+    assert!(estimate.classes > estimate.bits);
+  }
+
+  #[test]
+  fn test_passphrase() {
+    let estimate =
+      PasswordEntropy::estimate("correcthorsebatterystaple", &dictionary());
+    assert_eq!(estimate.words, ["correct", "horse", "battery", "staple"]);
+    assert_eq!(estimate.strength(), "very weak");
+  }
+}
@@ -0,0 +1,409 @@
+use std::{
+  fmt::Display,
+  io::{Error, ErrorKind, Result, Write},
+  time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use num_bigint::BigUint;
+
+use crate::Command;
+
+use super::Prime;
+
+/// Number of Miller–Rabin rounds for deciding whether to split further.
+const ROUNDS: usize = 20;
+
+/// Number of steps of Pollard's p − 1 between two gcd computations.
+const GCD_INTERVAL: u32 = 100;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FactorMethod {
+  /// Trial division, then Pollard's p − 1 and rho for what remains.
+  #[default]
+  Auto,
+  Trial,
+  Rho,
+  #[value(name = "p-1")]
+  PMinusOne,
+}
+
+impl Display for FactorMethod {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let name = match self {
+      FactorMethod::Auto => "auto",
+      FactorMethod::Trial => "trial division",
+      FactorMethod::Rho => "pollard rho",
+      FactorMethod::PMinusOne => "pollard p-1",
+    };
+    write!(f, "{name}")
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FactorConfig {
+  pub method: FactorMethod,
+  /// Largest divisor tried by trial division.
+  pub trial_bound: u32,
+  /// Smoothness bound of Pollard's p − 1.
+  pub smoothness_bound: u32,
+  /// Number of steps of Pollard's rho per polynomial.
+  pub max_iterations: usize,
+}
+
+impl FactorConfig {
+  pub fn new(
+    method: FactorMethod,
+    trial_bound: u32,
+    smoothness_bound: u32,
+    max_iterations: usize,
+  ) -> Self {
+    Self {
+      method,
+      trial_bound,
+      smoothness_bound,
+      max_iterations,
+    }
+  }
+}
+
+impl Default for FactorConfig {
+  fn default() -> Self {
+    Self::new(FactorMethod::default(), 10_000, 100_000, 1_000_000)
+  }
+}
+
+impl From<&Command> for FactorConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Factor {
+        method,
+        trial_bound,
+        smoothness_bound,
+        max_iterations,
+        ..
+      } => Self::new(*method, *trial_bound, *smoothness_bound, *max_iterations),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A factor that was split off together with how it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+  pub factor: BigUint,
+  pub method: FactorMethod,
+  pub duration: Duration,
+}
+
+/// The factors of a number and the steps that found them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Factorization {
+  /// Prime factors in ascending order.
+  pub primes: Vec<BigUint>,
+  /// Composite factors that no method could split.
+  pub composites: Vec<BigUint>,
+  pub splits: Vec<Split>,
+}
+
+pub struct Factor;
+
+impl Factor {
+  /// Writes every split with its method and time, followed by the
+  /// product of all prime factors. A composite that no method splits is
+  /// an error.
+  pub fn execute<W: Write>(
+    output: &mut W,
+    number: &BigUint,
+    config: FactorConfig,
+  ) -> Result<()> {
+    let factorization = Self::factor(number, &config)?;
+
+    for split in &factorization.splits {
+      let Split {
+        factor,
+        method,
+        duration,
+      } = split;
+      writeln!(output, "{factor} found by {method} in {duration:?}")?;
+    }
+
+    for composite in &factorization.composites {
+      writeln!(output, "{composite} could not be factored")?;
+    }
+    if !factorization.composites.is_empty() {
+      let message = format!("{number} could not be factored completely");
+      return Err(Error::new(ErrorKind::InvalidData, message));
+    }
+
+    let factors: Vec<String> = factorization
+      .primes
+      .iter()
+      .map(BigUint::to_string)
+      .collect();
+
+    writeln!(output, "{number} = {}", factors.join(" * "))
+  }
+
+  pub fn factor(
+    number: &BigUint,
+    config: &FactorConfig,
+  ) -> Result<Factorization> {
+    if *number < BigUint::from(2u32) {
+      let message = "Only numbers from 2 upwards can be factored";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut factorization = Factorization::default();
+    let mut remaining = vec![number.clone()];
+
+    if matches!(config.method, FactorMethod::Auto | FactorMethod::Trial) {
+      let start = Instant::now();
+      let (small, rest) = Self::trial_division(number, config.trial_bound);
+      for factor in small {
+        factorization.splits.push(Split {
+          factor: factor.clone(),
+          method: FactorMethod::Trial,
+          duration: start.elapsed(),
+        });
+        factorization.primes.push(factor);
+      }
+      remaining = vec![rest];
+    }
+
+    while let Some(composite) = remaining.pop() {
+      if composite == BigUint::from(1u32) {
+        continue;
+      }
+      if Prime::is_probable_prime(&composite, ROUNDS) {
+        factorization.primes.push(composite);
+        continue;
+      }
+
+      match Self::split(&composite, config) {
+        Some(split) => {
+          remaining.push(&composite / &split.factor);
+          remaining.push(split.factor.clone());
+          factorization.splits.push(split);
+        }
+        None => factorization.composites.push(composite),
+      }
+    }
+
+    factorization.primes.sort();
+    Ok(factorization)
+  }
+
+  /// Tries the configured methods for a nontrivial factor, and trial
+  /// division for the small factors that both Pollard methods miss.
+  fn split(number: &BigUint, config: &FactorConfig) -> Option<Split> {
+    let methods: &[FactorMethod] = match config.method {
+      FactorMethod::Auto => &[FactorMethod::PMinusOne, FactorMethod::Rho],
+      FactorMethod::Trial => &[],
+      FactorMethod::Rho => &[FactorMethod::Rho],
+      FactorMethod::PMinusOne => &[FactorMethod::PMinusOne],
+    };
+
+    methods
+      .iter()
+      .chain(&[FactorMethod::Trial])
+      .find_map(|&method| {
+        let start = Instant::now();
+        let factor = match method {
+          FactorMethod::PMinusOne => {
+            Self::pollard_p_minus_one(number, config.smoothness_bound)
+          }
+          FactorMethod::Trial => {
+            Self::trial_division(number, config.trial_bound)
+              .0
+              .into_iter()
+              .find(|factor| factor != number)
+          }
+          _ => Self::pollard_rho(number, config.max_iterations),
+        }?;
+
+        Some(Split {
+          factor,
+          method,
+          duration: start.elapsed(),
+        })
+      })
+  }
+
+  /// The prime factors up to the bound and the remaining cofactor.
+  pub fn trial_division(
+    number: &BigUint,
+    bound: u32,
+  ) -> (Vec<BigUint>, BigUint) {
+    let mut factors = Vec::new();
+    let mut rest = number.clone();
+
+    let divisors = std::iter::once(2).chain((3..=bound).step_by(2));
+    for divisor in divisors {
+      let divisor = BigUint::from(divisor);
+      if &divisor * &divisor > rest {
+        if rest != BigUint::from(1u32) {
+          factors.push(std::mem::replace(&mut rest, BigUint::from(1u32)));
+        }
+        break;
+      }
+      while &rest % &divisor == BigUint::ZERO {
+        rest /= &divisor;
+        factors.push(divisor.clone());
+      }
+    }
+
+    (factors, rest)
+  }
+
+  /// Floyd's cycle detection on `x -> x^2 + c mod n`. The sequence
+  /// modulo a prime factor `p` repeats after about `sqrt(p)` steps,
+  /// which the gcd of the difference with `n` reveals.
+  pub fn pollard_rho(
+    number: &BigUint,
+    max_iterations: usize,
+  ) -> Option<BigUint> {
+    let one = BigUint::from(1u32);
+    if number % 2u32 == BigUint::ZERO {
+      return Some(BigUint::from(2u32));
+    }
+
+    for c in 1u32..=10 {
+      let step = |x: &BigUint| (x * x + c) % number;
+      let (mut x, mut y) = (BigUint::from(2u32), BigUint::from(2u32));
+
+      for _ in 0..max_iterations {
+        x = step(&x);
+        y = step(&step(&y));
+
+        let difference = if x > y { &x - &y } else { &y - &x };
+        let divisor = gcd(&difference, number);
+
+        if divisor == *number {
+          break;
+        }
+        if divisor != one {
+          return Some(divisor);
+        }
+      }
+    }
+
+    None
+  }
+
+  /// Computes `2^M mod n` for `M = bound!`. If `p - 1` of a prime factor
+  /// `p` divides `M`, which is likely if all its factors are below the
+  /// bound, then `p` divides `2^M - 1` by Fermat's little theorem.
+  pub fn pollard_p_minus_one(number: &BigUint, bound: u32) -> Option<BigUint> {
+    let one = BigUint::from(1u32);
+    let mut power = BigUint::from(2u32);
+
+    for exponent in 2..=bound {
+      power = power.modpow(&BigUint::from(exponent), number);
+
+      if exponent % GCD_INTERVAL == 0 || exponent == bound {
+        if power == BigUint::ZERO {
+          return None;
+        }
+
+        let divisor = gcd(&(&power - &one), number);
+        if divisor == *number {
+          return None;
+        }
+        if divisor != one {
+          return Some(divisor);
+        }
+      }
+    }
+
+    None
+  }
+}
+
+/// Greatest common divisor by the Euclidean algorithm.
+pub fn gcd(a: &BigUint, b: &BigUint) -> BigUint {
+  let (mut a, mut b) = (a.clone(), b.clone());
+  while b != BigUint::ZERO {
+    (a, b) = (b.clone(), a % b);
+  }
+  a
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn number(digits: &str) -> BigUint {
+    digits.parse().unwrap()
+  }
+
+  fn primes(factorization: &Factorization) -> Vec<String> {
+    factorization
+      .primes
+      .iter()
+      .map(BigUint::to_string)
+      .collect()
+  }
+
+  #[test]
+  fn test_trial_division() {
+    let (factors, rest) = Factor::trial_division(&number("360"), 100);
+    assert_eq!(factors, [2u32, 2, 2, 3, 3, 5].map(BigUint::from));
+    assert_eq!(rest, BigUint::from(1u32));
+  }
+
+  #[test]
+  fn test_pollard_rho() {
+    let n = number("10403");
+    let factor = Factor::pollard_rho(&n, 1000).unwrap();
+    assert!(factor == number("101") || factor == number("103"));
+  }
+
+  #[test]
+  fn test_pollard_p_minus_one() {
+    let p = number("1000000009");
+    let q = number("4294967311");
+    let factor = Factor::pollard_p_minus_one(&(&p * &q), 1000);
+    assert_eq!(factor, Some(p));
+  }
+
+  #[test]
+  fn test_factor() -> Result<()> {
+    let config = FactorConfig::default();
+    let n = number("2") * number("1000000007") * number("1000000009");
+    let factorization = Factor::factor(&n, &config)?;
+    assert_eq!(primes(&factorization), ["2", "1000000007", "1000000009"]);
+    assert!(factorization.composites.is_empty());
+    assert_eq!(factorization.splits[0].method, FactorMethod::Trial);
+
+    let prime = Factor::factor(&number("1000000007"), &config)?;
+    assert_eq!(primes(&prime), ["1000000007"]);
+    assert!(Factor::factor(&number("1"), &config).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_trial_only_leaves_composite() -> Result<()> {
+    let config = FactorConfig::new(FactorMethod::Trial, 100, 0, 0);
+    let n = number("1000000016000000063");
+    let factorization = Factor::factor(&n, &config)?;
+    assert!(factorization.primes.is_empty());
+    assert_eq!(factorization.composites, [n.clone()]);
+
+    let mut output = Vec::new();
+    assert!(Factor::execute(&mut output, &n, config).is_err());
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("{n} could not be factored\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_small_factors_fall_back_to_trial() -> Result<()> {
+    let config = FactorConfig::new(FactorMethod::PMinusOne, 100, 1000, 0);
+    let factorization = Factor::factor(&number("4"), &config)?;
+    assert_eq!(primes(&factorization), ["2", "2"]);
+    assert!(factorization.composites.is_empty());
+    assert_eq!(factorization.splits[0].method, FactorMethod::Trial);
+    Ok(())
+  }
+}
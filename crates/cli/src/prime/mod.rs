@@ -0,0 +1,260 @@
+pub mod factor;
+
+use std::io::{Error, ErrorKind, Result, Write};
+
+use num_bigint::BigUint;
+use rand::{rngs::OsRng, rngs::StdRng, RngCore, SeedableRng};
+
+use crate::PrimeCommand;
+
+/// Primes below 100, which are tested by division before Miller–Rabin.
+const SMALL_PRIMES: [u32; 25] = [
+  2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+  73, 79, 83, 89, 97,
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrimeConfig {
+  pub rounds: usize,
+  /// Seed for reproducible bases and primes, which are then predictable.
+  pub seed: Option<u64>,
+}
+
+impl PrimeConfig {
+  pub fn new(rounds: usize) -> Self {
+    Self { rounds, seed: None }
+  }
+}
+
+impl Default for PrimeConfig {
+  fn default() -> Self {
+    Self::new(20)
+  }
+}
+
+impl From<&PrimeCommand> for PrimeConfig {
+  fn from(value: &PrimeCommand) -> Self {
+    match value {
+      PrimeCommand::Test { rounds, seed, .. }
+      | PrimeCommand::Generate { rounds, seed, .. } => Self {
+        seed: *seed,
+        ..Self::new(*rounds)
+      },
+    }
+  }
+}
+
+pub struct Prime;
+
+impl Prime {
+  /// Writes whether the number is probably prime or composite.
+  pub fn test<W: Write>(
+    output: &mut W,
+    number: &BigUint,
+    config: PrimeConfig,
+  ) -> Result<()> {
+    let rounds = config.rounds;
+    let prime = match config.seed {
+      Some(seed) => {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::is_probable_prime_with(&mut rng, number, rounds)
+      }
+      None => Self::is_probable_prime(number, rounds),
+    };
+    match prime {
+      true => writeln!(output, "{number} is probably prime"),
+      false => writeln!(output, "{number} is composite"),
+    }
+  }
+
+  /// Writes a random probable prime with exactly `bits` bits.
+  pub fn generate<W: Write>(
+    output: &mut W,
+    bits: u64,
+    config: PrimeConfig,
+  ) -> Result<()> {
+    let rounds = config.rounds;
+    let prime = match config.seed {
+      Some(seed) => {
+        Self::random_prime_with(&mut StdRng::seed_from_u64(seed), bits, rounds)?
+      }
+      None => Self::random_prime(bits, rounds)?,
+    };
+    writeln!(output, "{prime}")
+  }
+
+  /// Miller–Rabin with random bases.
+  ///
+  /// With `n - 1 = 2^s * d` for an odd `d`, a prime `n` satisfies either
+  /// `a^d = 1` or `a^(2^r * d) = -1 mod n` for some `r < s` and every
+  /// base `a`. A composite number passes a round for at most a quarter
+  /// of the bases, so the error is below `4^-rounds`.
+  pub fn is_probable_prime(number: &BigUint, rounds: usize) -> bool {
+    Self::is_probable_prime_with(&mut OsRng, number, rounds)
+  }
+
+  /// Miller–Rabin like [`Prime::is_probable_prime`] with bases drawn from
+  /// `rng`.
+  pub fn is_probable_prime_with<G: RngCore>(
+    rng: &mut G,
+    number: &BigUint,
+    rounds: usize,
+  ) -> bool {
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+
+    for prime in SMALL_PRIMES {
+      if *number == BigUint::from(prime) {
+        return true;
+      }
+      if number % prime == BigUint::ZERO {
+        return false;
+      }
+    }
+    if *number < two {
+      return false;
+    }
+
+    let minus_one = number - &one;
+    let s = minus_one.trailing_zeros().unwrap_or_default();
+    let d = &minus_one >> s;
+
+    'rounds: for _ in 0..rounds {
+      let base = Self::random_below_with(rng, &(number - 3u32)) + &two;
+      let mut x = base.modpow(&d, number);
+
+      if x == one || x == minus_one {
+        continue;
+      }
+
+      for _ in 1..s {
+        x = x.modpow(&two, number);
+        if x == minus_one {
+          continue 'rounds;
+        }
+      }
+
+      return false;
+    }
+
+    true
+  }
+
+  /// A random odd number with the highest of `bits` bits set, increased
+  /// until it is a probable prime.
+  pub fn random_prime(bits: u64, rounds: usize) -> Result<BigUint> {
+    Self::random_prime_with(&mut OsRng, bits, rounds)
+  }
+
+  /// A random probable prime like [`Prime::random_prime`] drawn from
+  /// `rng`, e.g. a seeded generator for reproducible exercises.
+  pub fn random_prime_with<G: RngCore>(
+    rng: &mut G,
+    bits: u64,
+    rounds: usize,
+  ) -> Result<BigUint> {
+    if bits < 2 {
+      let message = "A prime needs at least two bits";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    loop {
+      let mut candidate = Self::random_bits(rng, bits);
+      candidate.set_bit(bits - 1, true);
+      candidate.set_bit(0, true);
+
+      while candidate.bits() == bits {
+        if Self::is_probable_prime_with(rng, &candidate, rounds) {
+          return Ok(candidate);
+        }
+        candidate += 2u32;
+      }
+    }
+  }
+
+  /// A uniformly random number in `0..bound`.
+  pub fn random_below(bound: &BigUint) -> BigUint {
+    Self::random_below_with(&mut OsRng, bound)
+  }
+
+  /// A uniformly random number in `0..bound` drawn from `rng`.
+  pub fn random_below_with<G: RngCore>(
+    rng: &mut G,
+    bound: &BigUint,
+  ) -> BigUint {
+    if *bound <= BigUint::from(1u32) {
+      return BigUint::ZERO;
+    }
+
+    loop {
+      let candidate = Self::random_bits(rng, bound.bits());
+      if candidate < *bound {
+        return candidate;
+      }
+    }
+  }
+
+  fn random_bits<G: RngCore>(rng: &mut G, bits: u64) -> BigUint {
+    let mut bytes = vec![0; bits.div_ceil(8) as usize];
+    rng.fill_bytes(&mut bytes);
+
+    let excess = bytes.len() as u64 * 8 - bits;
+    if let Some(first) = bytes.first_mut() {
+      *first &= 0xff >> excess;
+    }
+
+    BigUint::from_bytes_be(&bytes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_small_numbers() {
+    let primes: Vec<u32> = (0..60u32)
+      .filter(|&n| Prime::is_probable_prime(&n.into(), 10))
+      .collect();
+    assert_eq!(
+      primes,
+      [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59]
+    );
+  }
+
+  #[test]
+  fn test_large_numbers() {
+    let mersenne = (BigUint::from(1u32) << 127) - 1u32;
+    assert!(Prime::is_probable_prime(&mersenne, 20));
+
+    let pseudoprime = BigUint::from(151u64 * 751 * 28351);
+    assert!(!Prime::is_probable_prime(&pseudoprime, 20));
+    assert!(!Prime::is_probable_prime(&(&mersenne * &mersenne), 20));
+  }
+
+  #[test]
+  fn test_random_prime() -> Result<()> {
+    let prime = Prime::random_prime(64, 20)?;
+    assert_eq!(prime.bits(), 64);
+    assert!(Prime::is_probable_prime(&prime, 20));
+    assert!(Prime::random_prime(1, 20).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_seeded_primes_repeat() -> Result<()> {
+    let config = |seed| PrimeConfig {
+      seed: Some(seed),
+      ..PrimeConfig::default()
+    };
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    Prime::generate(&mut first, 64, config(42))?;
+    Prime::generate(&mut second, 64, config(42))?;
+    assert_eq!(first, second);
+
+    let mut other = Vec::new();
+    Prime::generate(&mut other, 64, config(43))?;
+    assert_ne!(first, other);
+    Ok(())
+  }
+}
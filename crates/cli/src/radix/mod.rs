@@ -0,0 +1,250 @@
+use clap::ValueEnum;
+use num_bigint::BigUint;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::Command;
+
+/// Representation of the input or output of a conversion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Base {
+  /// Base 2, written as groups of eight bits.
+  Bin,
+  /// Base 8, written as groups of three digits.
+  Oct,
+  /// Base 10, written as numbers from 0 to 255.
+  Dec,
+  /// Base 16, written as pairs of digits.
+  #[default]
+  Hex,
+  /// Raw bytes.
+  Raw,
+}
+
+impl Base {
+  pub fn radix(&self) -> Option<u32> {
+    match self {
+      Base::Bin => Some(2),
+      Base::Oct => Some(8),
+      Base::Dec => Some(10),
+      Base::Hex => Some(16),
+      Base::Raw => None,
+    }
+  }
+
+  fn prefix(&self) -> &'static str {
+    match self {
+      Base::Bin => "0b",
+      Base::Oct => "0o",
+      Base::Hex => "0x",
+      Base::Dec | Base::Raw => "",
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RadixConfig {
+  pub from: Base,
+  pub to: Base,
+  pub integer: bool,
+}
+
+impl RadixConfig {
+  pub fn new(from: Base, to: Base, integer: bool) -> Self {
+    Self { from, to, integer }
+  }
+}
+
+impl From<&Command> for RadixConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Radix {
+        from, to, integer, ..
+      } => Self::new(*from, *to, *integer),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Radix;
+
+impl Radix {
+  pub fn convert<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RadixConfig,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let converted = if config.integer {
+      let integer = Self::parse_integer(&content, config.from)?;
+      Self::format_integer(&integer, config.to)
+    } else {
+      let bytes = Self::parse_bytes(&content, config.from)?;
+      Self::format_bytes(&bytes, config.to)
+    };
+
+    output.write_all(&converted)
+  }
+
+  /// Parses a byte stream.
+  ///
+  /// Hex and binary digits are read continuously, ignoring whitespace,
+  /// while octal and decimal bytes have to be separated by whitespace.
+  pub fn parse_bytes(content: &[u8], from: Base) -> Result<Vec<u8>> {
+    let text = || String::from_utf8_lossy(content);
+
+    match from {
+      Base::Raw => Ok(content.to_vec()),
+      Base::Hex | Base::Bin => {
+        let digits: Vec<char> =
+          text().chars().filter(|c| !c.is_whitespace()).collect();
+        let width = if from == Base::Hex { 2 } else { 8 };
+
+        if digits.len() % width != 0 {
+          let message = format!("Expected groups of {width} digits");
+          return Err(Error::new(ErrorKind::InvalidInput, message));
+        }
+
+        digits
+          .chunks(width)
+          .map(|digits| {
+            Self::parse_byte(&digits.iter().collect::<String>(), from)
+          })
+          .collect()
+      }
+      Base::Oct | Base::Dec => text()
+        .split_whitespace()
+        .map(|digits| Self::parse_byte(digits, from))
+        .collect(),
+    }
+  }
+
+  pub fn format_bytes(bytes: &[u8], to: Base) -> Vec<u8> {
+    let groups: Vec<String> = match to {
+      Base::Raw => return bytes.to_vec(),
+      Base::Hex => {
+        return bytes
+          .iter()
+          .flat_map(|b| format!("{b:02x}").into_bytes())
+          .collect()
+      }
+      Base::Bin => bytes.iter().map(|b| format!("{b:08b}")).collect(),
+      Base::Oct => bytes.iter().map(|b| format!("{b:03o}")).collect(),
+      Base::Dec => bytes.iter().map(|b| b.to_string()).collect(),
+    };
+
+    groups.join(" ").into_bytes()
+  }
+
+  /// Parses a single unsigned integer of arbitrary size.
+  ///
+  /// Raw input is read as big-endian bytes. Otherwise whitespace,
+  /// underscores and a matching `0x`, `0o` or `0b` prefix are ignored.
+  pub fn parse_integer(content: &[u8], from: Base) -> Result<BigUint> {
+    let Some(radix) = from.radix() else {
+      return Ok(BigUint::from_bytes_be(content));
+    };
+
+    let text: String = String::from_utf8_lossy(content)
+      .chars()
+      .filter(|c| !c.is_whitespace() && *c != '_')
+      .collect();
+    let digits = text.strip_prefix(from.prefix()).unwrap_or(&text);
+
+    BigUint::parse_bytes(digits.as_bytes(), radix).ok_or_else(|| {
+      let message = format!("'{text}' is not a base {radix} integer");
+      Error::new(ErrorKind::InvalidInput, message)
+    })
+  }
+
+  pub fn format_integer(integer: &BigUint, to: Base) -> Vec<u8> {
+    match to.radix() {
+      Some(radix) => integer.to_str_radix(radix).into_bytes(),
+      None => integer.to_bytes_be(),
+    }
+  }
+
+  fn parse_byte(digits: &str, from: Base) -> Result<u8> {
+    let radix = from.radix().unwrap_or(16);
+    u8::from_str_radix(digits, radix).map_err(|_| {
+      let message = format!("'{digits}' is not a base {radix} byte");
+      Error::new(ErrorKind::InvalidInput, message)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn convert(input: &str, from: Base, to: Base, integer: bool) -> String {
+    let mut output = Vec::new();
+    let config = RadixConfig::new(from, to, integer);
+    Radix::convert(&mut input.as_bytes(), &mut output, config).unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn test_raw_to_bytes_in_every_base() {
+    assert_eq!(convert("Hi", Base::Raw, Base::Hex, false), "4869");
+    assert_eq!(
+      convert("Hi", Base::Raw, Base::Bin, false),
+      "01001000 01101001"
+    );
+    assert_eq!(convert("Hi", Base::Raw, Base::Oct, false), "110 151");
+    assert_eq!(convert("Hi", Base::Raw, Base::Dec, false), "72 105");
+  }
+
+  #[test]
+  fn test_bytes_back_to_raw() {
+    assert_eq!(convert("48 69", Base::Hex, Base::Raw, false), "Hi");
+    assert_eq!(
+      convert("0100100001101001", Base::Bin, Base::Raw, false),
+      "Hi"
+    );
+    assert_eq!(convert("110 151\n", Base::Oct, Base::Raw, false), "Hi");
+    assert_eq!(convert("72 105", Base::Dec, Base::Raw, false), "Hi");
+  }
+
+  #[test]
+  fn test_invalid_bytes() {
+    let config = || RadixConfig::new(Base::Dec, Base::Hex, false);
+    let mut output = Vec::new();
+    let result = Radix::convert(&mut "256".as_bytes(), &mut output, config());
+    assert!(result.is_err());
+
+    let result = Radix::convert(
+      &mut "abc".as_bytes(),
+      &mut output,
+      RadixConfig::default(),
+    );
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_big_integer_conversion() {
+    let value = "340282366920938463463374607431768211455";
+    assert_eq!(
+      convert(value, Base::Dec, Base::Hex, true),
+      "ffffffffffffffffffffffffffffffff"
+    );
+    assert_eq!(convert("0xff", Base::Hex, Base::Dec, true), "255");
+    assert_eq!(convert("0b1_0000", Base::Bin, Base::Oct, true), "20");
+  }
+
+  #[test]
+  fn test_big_integer_from_raw_bytes() {
+    assert_eq!(convert("Hi", Base::Raw, Base::Dec, true), "18537");
+    assert_eq!(convert("18537", Base::Dec, Base::Raw, true), "Hi");
+  }
+
+  #[test]
+  fn test_invalid_big_integer() {
+    let mut output = Vec::new();
+    let config = RadixConfig::new(Base::Oct, Base::Dec, true);
+    let result = Radix::convert(&mut "19".as_bytes(), &mut output, config);
+    assert!(result.is_err());
+  }
+}
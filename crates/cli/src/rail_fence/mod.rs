@@ -0,0 +1,213 @@
+use rayon::prelude::*;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  frequency_analysis::FrequencyAnalyzer, report::CrackReport, DecryptCipher,
+  EncryptCipher,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RailFenceConfig {
+  pub rails: Option<usize>,
+  /// Most rails tried if the number is not known.
+  pub max_rails: usize,
+}
+
+impl RailFenceConfig {
+  pub fn new(rails: Option<usize>, max_rails: usize) -> Self {
+    Self { rails, max_rails }
+  }
+}
+
+impl From<&EncryptCipher> for RailFenceConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::RailFence { rails, .. } => Self::new(Some(*rails), 0),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for RailFenceConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::RailFence {
+        rails, max_rails, ..
+      } => Self::new(*rails, *max_rails),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A candidate decryption found while brute forcing the number of rails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RailFenceCandidate {
+  pub rails: usize,
+  pub plaintext: String,
+  pub score: f32,
+}
+
+impl From<RailFenceCandidate> for CrackReport {
+  fn from(candidate: RailFenceCandidate) -> Self {
+    let key = Some(candidate.rails.to_string());
+    CrackReport::new(candidate.plaintext, key, "bigram score")
+  }
+}
+
+/// The rail fence transposition, which writes the text in a zigzag over
+/// the rails and reads it off rail by rail.
+pub struct RailFence;
+
+impl RailFence {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RailFenceConfig,
+  ) -> Result<()> {
+    let rails = Self::validate(config.rails.unwrap_or_default())?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      writeln!(output, "{}", Self::encrypt_text(line, rails))?;
+    }
+
+    Ok(())
+  }
+
+  /// Decrypts every line with the configured number of rails, or with
+  /// the number whose plaintext looks most like English if none is given.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RailFenceConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`RailFence::decrypt`] and reports the
+  /// number of rails along with the method that found it.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &RailFenceConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let rails = config.rails.map(Self::validate).transpose()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let reports = content.lines().map(|line| match rails {
+      Some(rails) => {
+        let plaintext = Self::decrypt_text(line, rails);
+        CrackReport::new(plaintext, Some(rails.to_string()), "key")
+      }
+      None => match Self::rank(line, config.max_rails).into_iter().next() {
+        Some(candidate) => candidate.into(),
+        None => CrackReport::new(line.to_string(), None, "bigram score"),
+      },
+    });
+    Ok(reports.collect())
+  }
+
+  pub fn encrypt_text(text: &str, rails: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    Self::order(chars.len(), rails)
+      .into_iter()
+      .map(|i| chars[i])
+      .collect()
+  }
+
+  pub fn decrypt_text(text: &str, rails: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut plaintext = vec![' '; chars.len()];
+    for (&i, &c) in Self::order(chars.len(), rails).iter().zip(&chars) {
+      plaintext[i] = c;
+    }
+    plaintext.into_iter().collect()
+  }
+
+  /// Decrypts the text with 2 up to the most rails, fewer than the text
+  /// has characters, and ranks the candidates by their bigrams, best
+  /// first.
+  pub fn rank(text: &str, max_rails: usize) -> Vec<RailFenceCandidate> {
+    let length = text.chars().count();
+
+    let mut candidates: Vec<RailFenceCandidate> = (2..=max_rails
+      .min(length.saturating_sub(1)))
+      .into_par_iter()
+      .map(|rails| {
+        let plaintext = Self::decrypt_text(text, rails);
+        let score = FrequencyAnalyzer::bigram_score(&plaintext);
+        RailFenceCandidate {
+          rails,
+          plaintext,
+          score,
+        }
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates
+  }
+
+  /// The positions of the plaintext in the order they are read off the
+  /// rails.
+  fn order(length: usize, rails: usize) -> Vec<usize> {
+    let cycle = 2 * rails.max(2) - 2;
+    let rail = |i: usize| {
+      let step = i % cycle;
+      step.min(cycle - step)
+    };
+    let mut positions: Vec<usize> = (0..length).collect();
+    positions.sort_by_key(|&i| rail(i));
+    positions
+  }
+
+  fn validate(rails: usize) -> Result<usize> {
+    if rails < 2 {
+      return Err(Error::new(
+        ErrorKind::InvalidInput,
+        "The rail fence needs at least 2 rails",
+      ));
+    }
+    Ok(rails)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_encrypt_text() {
+    let cipher = RailFence::encrypt_text("WEAREDISCOVEREDFLEEATONCE", 3);
+    assert_eq!(cipher, "WECRLTEERDSOEEFEAOCAIVDEN");
+    assert_eq!(
+      RailFence::decrypt_text(&cipher, 3),
+      "WEAREDISCOVEREDFLEEATONCE"
+    );
+  }
+
+  #[test]
+  fn test_brute_force_decrypt() -> Result<()> {
+    let text = "it was a bright cold day in april and the clocks were \
+      striking thirteen";
+    let cipher = RailFence::encrypt_text(text, 4);
+    assert_eq!(RailFence::rank(&cipher, 10)[0].rails, 4);
+
+    let mut output = Vec::new();
+    let config = RailFenceConfig::new(None, 10);
+    RailFence::decrypt(&mut Cursor::new(cipher), &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}\n"));
+
+    let config = RailFenceConfig::new(Some(1), 10);
+    let result =
+      RailFence::encrypt(&mut Cursor::new("A"), &mut Vec::new(), config);
+    assert!(result.is_err());
+    Ok(())
+  }
+}
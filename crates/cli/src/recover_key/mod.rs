@@ -0,0 +1,309 @@
+use clap::ValueEnum;
+
+use std::fs;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::path::PathBuf;
+
+use crate::{
+  affine::Affine, alphabet::Alphabet, error::CryptologyError, hex::Hex,
+  rail_fence::RailFence, Command,
+};
+
+/// The ciphers whose key can be recovered from a known plaintext.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RecoverCipher {
+  /// A single shift of the alphabet.
+  #[default]
+  Caesar,
+  /// A keyword of shifts repeated over the letters.
+  Vigenere,
+  /// A repeating key XORed with the bytes.
+  Xor,
+  /// A pad as long as the message XORed with the bytes.
+  Otp,
+  /// A multiplier and a shift, `y = a * x + b`.
+  Affine,
+  /// The number of rails of the zigzag.
+  RailFence,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RecoverKeyConfig {
+  pub cipher: RecoverCipher,
+  /// Path to the plaintext of the ciphertext that is read.
+  pub plaintext: PathBuf,
+}
+
+impl RecoverKeyConfig {
+  pub fn new(cipher: RecoverCipher, plaintext: PathBuf) -> Self {
+    Self { cipher, plaintext }
+  }
+}
+
+impl From<&Command> for RecoverKeyConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::RecoverKey {
+        cipher, plaintext, ..
+      } => Self::new(*cipher, plaintext.clone()),
+      _ => Self::default(),
+    }
+  }
+}
+
+fn inconsistent(message: &str) -> Error {
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+pub struct RecoverKey;
+
+impl RecoverKey {
+  /// Reads the ciphertext, hex for XOR and the one-time pad, and writes
+  /// the key that turns the known plaintext into it.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    alphabet: &Alphabet,
+    config: RecoverKeyConfig,
+  ) -> Result<()> {
+    let mut ciphertext = String::new();
+    input.read_to_string(&mut ciphertext)?;
+    let plaintext =
+      fs::read_to_string(&config.plaintext).map_err(|source| {
+        CryptologyError::Open {
+          path: config.plaintext.clone(),
+          source,
+        }
+      })?;
+    let (plaintext, ciphertext) = (plaintext.trim_end(), ciphertext.trim_end());
+
+    let key = match config.cipher {
+      RecoverCipher::Caesar => {
+        Self::caesar(plaintext, ciphertext, alphabet)?.to_string()
+      }
+      RecoverCipher::Vigenere => {
+        Self::vigenere(plaintext, ciphertext, alphabet)?
+      }
+      RecoverCipher::Xor | RecoverCipher::Otp => {
+        let ciphertext = Hex::parse_hex(ciphertext)?.bytes;
+        let pad = Self::pad(plaintext.as_bytes(), &ciphertext);
+        let key = match config.cipher {
+          RecoverCipher::Xor => pad[..Self::period(&pad)].to_vec(),
+          _ => pad,
+        };
+        Hex::new(key).to_string()
+      }
+      RecoverCipher::Affine => {
+        let (a, b) = Self::affine(plaintext, ciphertext, alphabet)?;
+        Affine::key(a, b)
+      }
+      RecoverCipher::RailFence => {
+        Self::rail_fence(plaintext, ciphertext)?.to_string()
+      }
+    };
+
+    writeln!(output, "{key}")
+  }
+
+  /// The shift that maps every letter of the plaintext to the letter of
+  /// the ciphertext at the same position.
+  pub fn caesar(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+  ) -> Result<usize> {
+    let shifts = Self::shifts(plaintext, ciphertext, alphabet)?;
+    match shifts.iter().all(|&shift| shift == shifts[0]) {
+      true => Ok(shifts[0]),
+      false => Err(inconsistent(
+        "The letters are shifted by different amounts, the text is not \
+         encrypted with a single shift",
+      )),
+    }
+  }
+
+  /// The shortest keyword whose repetition shifts the plaintext into the
+  /// ciphertext. Without a repetition the keyword is as long as the text.
+  pub fn vigenere(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+  ) -> Result<String> {
+    let shifts = Self::shifts(plaintext, ciphertext, alphabet)?;
+    let period = Self::period(&shifts);
+    Ok(
+      shifts[..period]
+        .iter()
+        .map(|&shift| alphabet.symbol(shift))
+        .collect(),
+    )
+  }
+
+  /// The bytes that XOR the plaintext into the ciphertext, as long as
+  /// the shorter of the two.
+  pub fn pad(plaintext: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    plaintext
+      .iter()
+      .zip(ciphertext)
+      .map(|(p, c)| p ^ c)
+      .collect()
+  }
+
+  /// The multiplier and shift of `y = a * x + b` over the alphabet,
+  /// found by trying every affine key against all letters.
+  pub fn affine(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+  ) -> Result<(usize, usize)> {
+    let pairs = Self::pairs(plaintext, ciphertext, alphabet)?;
+    let m = alphabet.len();
+
+    let mut keys = Affine::keys(m).filter(|&(a, b)| {
+      pairs.iter().all(|&(x, y)| Affine::apply(x, a, b, m) == y)
+    });
+    let key = keys.next().ok_or_else(|| {
+      inconsistent("The letters do not follow a single affine mapping")
+    })?;
+    if keys.next().is_some() {
+      return Err(inconsistent(
+        "The known letters are too few to solve for the key",
+      ));
+    }
+
+    Ok(key)
+  }
+
+  /// The fewest rails whose zigzag reads the plaintext off as the
+  /// ciphertext.
+  pub fn rail_fence(plaintext: &str, ciphertext: &str) -> Result<usize> {
+    let length = plaintext.chars().count();
+    if length == 0 || length != ciphertext.chars().count() {
+      return Err(Error::new(
+        ErrorKind::InvalidInput,
+        "The plaintext and the ciphertext must be of the same non-zero length",
+      ));
+    }
+
+    (2..length.max(3))
+      .find(|&rails| RailFence::encrypt_text(plaintext, rails) == ciphertext)
+      .ok_or_else(|| {
+        inconsistent(
+          "No number of rails turns the plaintext into the ciphertext",
+        )
+      })
+  }
+
+  /// Shift of every letter of the plaintext to its ciphertext letter.
+  fn shifts(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+  ) -> Result<Vec<usize>> {
+    let m = alphabet.len();
+    Ok(
+      Self::pairs(plaintext, ciphertext, alphabet)?
+        .into_iter()
+        .map(|(x, y)| (y + m - x) % m)
+        .collect(),
+    )
+  }
+
+  /// Positions of the letters of plaintext and ciphertext in the
+  /// alphabet, side by side. Other characters are skipped.
+  fn pairs(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+  ) -> Result<Vec<(usize, usize)>> {
+    let letters = |text: &str| -> Vec<usize> {
+      text.chars().filter_map(|c| alphabet.index(c)).collect()
+    };
+    let (plain, cipher) = (letters(plaintext), letters(ciphertext));
+
+    if plain.is_empty() || plain.len() != cipher.len() {
+      let message = format!(
+        "The plaintext has {} letters and the ciphertext {}, they must be \
+         the same non-zero number",
+        plain.len(),
+        cipher.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(plain.into_iter().zip(cipher).collect())
+  }
+
+  /// Length of the shortest prefix whose repetition yields the values.
+  fn period<T: PartialEq>(values: &[T]) -> usize {
+    (1..values.len())
+      .find(|&period| {
+        values
+          .iter()
+          .enumerate()
+          .all(|(i, value)| *value == values[i % period])
+      })
+      .unwrap_or(values.len())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_caesar_and_vigenere() -> Result<()> {
+    let alphabet = Alphabet::default();
+    assert_eq!(RecoverKey::caesar("Attack!", "Dwwdfn!", &alphabet)?, 3);
+    assert!(RecoverKey::caesar("attack", "dwwdfo", &alphabet).is_err());
+
+    let plaintext = "attack at dawn";
+    let ciphertext = "LXFOPV EF RNHR";
+    assert_eq!(
+      RecoverKey::vigenere(plaintext, ciphertext, &alphabet)?,
+      "LEMON"
+    );
+    assert!(RecoverKey::vigenere("attack", "lxf", &alphabet).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_missing_plaintext() {
+    let path = PathBuf::from("missing-plaintext.txt");
+    let config = RecoverKeyConfig::new(RecoverCipher::Caesar, path);
+    let alphabet = Alphabet::default();
+    let result =
+      RecoverKey::execute(&mut &b"abc"[..], &mut Vec::new(), &alphabet, config);
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("missing-plaintext.txt"));
+  }
+
+  #[test]
+  fn test_xor_key_period() {
+    let pad = RecoverKey::pad(b"hello world", &[0x2b, 0x20, 0x29, 0x2f, 0x2a]);
+    assert_eq!(pad, [0x43, 0x45, 0x45, 0x43, 0x45]);
+    assert_eq!(RecoverKey::period(b"ICEICEIC"), 3);
+    assert_eq!(RecoverKey::period(b"ICY"), 3);
+  }
+
+  #[test]
+  fn test_affine() -> Result<()> {
+    let alphabet = Alphabet::default();
+    // y = 5x + 8: A -> I, F -> H, N -> V, E -> C.
+    assert_eq!(RecoverKey::affine("AFFINE", "IHHWVC", &alphabet)?, (5, 8));
+    assert!(RecoverKey::affine("AB", "AC", &alphabet).is_err());
+    assert!(RecoverKey::affine("AAA", "III", &alphabet).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_rail_fence() -> Result<()> {
+    let plaintext = "WEAREDISCOVEREDFLEEATONCE";
+    let ciphertext = RailFence::encrypt_text(plaintext, 4);
+    assert_eq!(RecoverKey::rail_fence(plaintext, &ciphertext)?, 4);
+    assert!(
+      RecoverKey::rail_fence(plaintext, "EWAREDISCOVEREDFLEEATONCE").is_err()
+    );
+    Ok(())
+  }
+}
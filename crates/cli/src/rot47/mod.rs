@@ -0,0 +1,48 @@
+use std::io::{Read, Result, Write};
+
+/// First and number of the printable ASCII characters ROT47 rotates.
+const FIRST: u8 = b'!';
+const COUNT: u8 = 94;
+
+pub struct Rot47;
+
+impl Rot47 {
+  /// Rotates every printable ASCII character except space by 47 places
+  /// within `!..=~`, so digits and punctuation are encrypted as well.
+  ///
+  /// Since 47 is half of the 94 characters, encryption and decryption
+  /// are the same operation.
+  pub fn apply<R: Read, W: Write>(input: &mut R, output: &mut W) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+    output.write_all(&Self::rotate(&content))
+  }
+
+  pub fn rotate(bytes: &[u8]) -> Vec<u8> {
+    bytes
+      .iter()
+      .map(|&b| match b {
+        b'!'..=b'~' => FIRST + (b - FIRST + COUNT / 2) % COUNT,
+        _ => b,
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rotate() {
+    let cipher = Rot47::rotate(b"The Quick Brown Fox Jumps Over The Lazy Dog.");
+    assert_eq!(cipher, b"%96 \"F:4< qC@H? u@I yF>AD ~G6C %96 {2KJ s@8]");
+  }
+
+  #[test]
+  fn test_rotate_is_an_involution() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(Rot47::rotate(&Rot47::rotate(&bytes)), bytes);
+    assert_eq!(Rot47::rotate(b"!~ \n"), b"PO \n");
+  }
+}
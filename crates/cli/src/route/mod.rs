@@ -0,0 +1,373 @@
+use clap::ValueEnum;
+use rayon::prelude::*;
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  frequency_analysis::FrequencyAnalyzer, report::CrackReport, DecryptCipher,
+  EncryptCipher,
+};
+
+/// Letter that fills the last row of the grid.
+const PADDING: char = 'X';
+
+/// The path along which the grid is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RouteKind {
+  /// Clockwise inwards from the top left corner.
+  Spiral,
+  /// Row by row, turning around at the end of every row.
+  Boustrophedon,
+  /// Along the diagonals from the top left corner, each top to bottom.
+  Diagonal,
+}
+
+impl RouteKind {
+  pub const ALL: [RouteKind; 3] = [
+    RouteKind::Spiral,
+    RouteKind::Boustrophedon,
+    RouteKind::Diagonal,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      RouteKind::Spiral => "spiral",
+      RouteKind::Boustrophedon => "boustrophedon",
+      RouteKind::Diagonal => "diagonal",
+    }
+  }
+
+  /// The indices of the cells of a grid written row by row, in the order
+  /// the route visits them.
+  pub fn path(&self, rows: usize, columns: usize) -> Vec<usize> {
+    let mut path = Vec::with_capacity(rows * columns);
+
+    match self {
+      RouteKind::Spiral => {
+        let (mut top, mut bottom, mut left, mut right) = (0, rows, 0, columns);
+        while top < bottom && left < right {
+          path.extend((left..right).map(|c| top * columns + c));
+          top += 1;
+          path.extend((top..bottom).map(|r| r * columns + right - 1));
+          right -= 1;
+          if top < bottom {
+            path
+              .extend((left..right).rev().map(|c| (bottom - 1) * columns + c));
+            bottom -= 1;
+          }
+          if left < right {
+            path.extend((top..bottom).rev().map(|r| r * columns + left));
+            left += 1;
+          }
+        }
+      }
+      RouteKind::Boustrophedon => {
+        for row in 0..rows {
+          let cells = (0..columns).map(|c| row * columns + c);
+          match row % 2 {
+            0 => path.extend(cells),
+            _ => path.extend(cells.rev()),
+          }
+        }
+      }
+      RouteKind::Diagonal => {
+        for diagonal in 0..rows + columns - 1 {
+          let cells = (0..rows)
+            .filter(|&row| diagonal >= row && diagonal - row < columns)
+            .map(|row| row * columns + diagonal - row);
+          path.extend(cells);
+        }
+      }
+    }
+
+    path
+  }
+}
+
+impl Display for RouteKind {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RouteConfig {
+  pub route: Option<RouteKind>,
+  /// Number of columns of the grid.
+  pub width: Option<usize>,
+}
+
+impl RouteConfig {
+  pub fn new(route: Option<RouteKind>, width: Option<usize>) -> Self {
+    Self { route, width }
+  }
+}
+
+impl From<&EncryptCipher> for RouteConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Route { route, width, .. } => {
+        Self::new(Some(*route), Some(*width))
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for RouteConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Route { route, width, .. } => Self::new(*route, *width),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A candidate decryption found while brute forcing routes and widths.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteCandidate {
+  pub route: RouteKind,
+  pub width: usize,
+  pub plaintext: String,
+  pub score: f32,
+}
+
+impl From<RouteCandidate> for CrackReport {
+  fn from(candidate: RouteCandidate) -> Self {
+    let key = Some(format!("{} {}", candidate.route, candidate.width));
+    CrackReport::new(candidate.plaintext, key, "bigram score")
+  }
+}
+
+pub struct Route;
+
+impl Route {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RouteConfig,
+  ) -> Result<()> {
+    let route = config.route.unwrap_or(RouteKind::Spiral);
+    let width = Self::validate(config.width.unwrap_or_default())?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      writeln!(output, "{}", Self::encrypt_text(line, route, width))?;
+    }
+
+    Ok(())
+  }
+
+  /// Decrypts every line with the configured route and width. Whatever
+  /// is not configured is brute forced.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RouteConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`Route::decrypt`] and reports the route
+  /// and width along with the method that found them.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &RouteConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let width = config.width.map(Self::validate).transpose()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    content
+      .lines()
+      .map(|line| match (config.route, width) {
+        (Some(route), Some(width)) => {
+          let plaintext = Self::decrypt_text(line, route, width)?;
+          let key = Some(format!("{route} {width}"));
+          Ok(CrackReport::new(plaintext, key, "key"))
+        }
+        _ => match Self::rank(line, config).into_iter().next() {
+          Some(candidate) => Ok(candidate.into()),
+          None => Self::unfilled(line, width),
+        },
+      })
+      .collect()
+  }
+
+  /// Writes the text row by row into a grid of `width` columns, fills
+  /// the last row with X and reads the grid along the route. Whitespace
+  /// is removed before encryption.
+  pub fn encrypt_text(text: &str, route: RouteKind, width: usize) -> String {
+    let mut grid: Vec<char> = Self::strip(text).chars().collect();
+    let rows = grid.len().div_ceil(width);
+    grid.resize(rows * width, PADDING);
+
+    route
+      .path(rows, width)
+      .into_iter()
+      .map(|i| grid[i])
+      .collect()
+  }
+
+  /// Fills the grid along the route and reads it row by row. The padding
+  /// of the last row is kept.
+  pub fn decrypt_text(
+    text: &str,
+    route: RouteKind,
+    width: usize,
+  ) -> Result<String> {
+    let cipher: Vec<char> = Self::strip(text).chars().collect();
+    if cipher.len() % width != 0 {
+      let message = format!(
+        "The ciphertext of {} letters does not fill a grid of width {width}",
+        cipher.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut grid = vec![PADDING; cipher.len()];
+    for (&cell, letter) in
+      route.path(cipher.len() / width, width).iter().zip(cipher)
+    {
+      grid[cell] = letter;
+    }
+
+    Ok(grid.into_iter().collect())
+  }
+
+  /// Decrypts the text with every route and every width that fills the
+  /// grid, restricted to the configured ones, and ranks the candidates by
+  /// their bigram score, best first.
+  pub fn rank(text: &str, config: &RouteConfig) -> Vec<RouteCandidate> {
+    let length = Self::strip(text).chars().count();
+    let routes = match config.route {
+      Some(route) => vec![route],
+      None => RouteKind::ALL.to_vec(),
+    };
+    let widths: Vec<usize> = match config.width {
+      Some(0) => Vec::new(),
+      Some(width) => vec![width],
+      None => (2..=length / 2).filter(|w| length % w == 0).collect(),
+    };
+
+    let mut candidates: Vec<RouteCandidate> = routes
+      .iter()
+      .flat_map(|&route| widths.iter().map(move |&width| (route, width)))
+      .collect::<Vec<_>>()
+      .into_par_iter()
+      .filter_map(|(route, width)| {
+        let plaintext = Self::decrypt_text(text, route, width).ok()?;
+        let score = FrequencyAnalyzer::bigram_score(&plaintext);
+        Some(RouteCandidate {
+          route,
+          width,
+          plaintext,
+          score,
+        })
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates
+  }
+
+  /// The error for a ciphertext that fills no grid. An empty line
+  /// decrypts to an empty line.
+  fn unfilled(line: &str, width: Option<usize>) -> Result<CrackReport> {
+    let length = Self::strip(line).chars().count();
+    if length == 0 {
+      return Ok(CrackReport::new(String::new(), None, "bigram score"));
+    }
+    let message = match width {
+      Some(width) => format!(
+        "The ciphertext of {length} letters does not fill a grid of width \
+         {width}"
+      ),
+      None => format!(
+        "No width of at least 2 fills a grid with the {length} letters of \
+         the ciphertext"
+      ),
+    };
+    Err(Error::new(ErrorKind::InvalidInput, message))
+  }
+
+  fn strip(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+  }
+
+  pub fn validate(width: usize) -> Result<usize> {
+    if width == 0 {
+      let message = "Width must be greater than zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    Ok(width)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  const TEXT: &str = "WEAREDISCOVEREDFLEEATONCE";
+
+  #[test]
+  fn test_paths() {
+    assert_eq!(RouteKind::Spiral.path(3, 3), [0, 1, 2, 5, 8, 7, 6, 3, 4]);
+    assert_eq!(RouteKind::Spiral.path(2, 3), [0, 1, 2, 5, 4, 3]);
+    assert_eq!(RouteKind::Boustrophedon.path(2, 3), [0, 1, 2, 5, 4, 3]);
+    assert_eq!(RouteKind::Diagonal.path(2, 3), [0, 1, 3, 2, 4, 5]);
+  }
+
+  #[test]
+  fn test_encrypt_text() {
+    let cipher = Route::encrypt_text("ATTACK AT DAWN", RouteKind::Spiral, 4);
+    assert_eq!(cipher, "ATTATNWADCKA");
+  }
+
+  #[test]
+  fn test_roundtrip() -> Result<()> {
+    for route in RouteKind::ALL {
+      let cipher = Route::encrypt_text(TEXT, route, 6);
+      assert_eq!(cipher.len(), 30);
+      let plaintext = Route::decrypt_text(&cipher, route, 6)?;
+      assert_eq!(plaintext, format!("{TEXT}XXXXX"));
+    }
+    assert!(Route::decrypt_text("ABCDE", RouteKind::Spiral, 2).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_brute_force_decrypt() -> Result<()> {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOGANDRUNSAWAY";
+    let cipher = Route::encrypt_text(text, RouteKind::Diagonal, 6);
+    let best = Route::rank(&cipher, &RouteConfig::default())
+      .into_iter()
+      .next()
+      .unwrap();
+    assert_eq!((best.route, best.width), (RouteKind::Diagonal, 6));
+
+    let mut output = Vec::new();
+    let config = RouteConfig::new(Some(RouteKind::Diagonal), None);
+    Route::decrypt(&mut Cursor::new(cipher), &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}XX\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_width() {
+    let crack = |text: &str, width| {
+      Route::crack(&mut Cursor::new(text), &RouteConfig::new(None, width))
+    };
+    assert!(crack("ABCDEF", Some(0)).is_err());
+    let error = crack("ABCDEF", Some(4)).unwrap_err();
+    assert!(error.to_string().contains("grid of width 4"));
+    assert!(crack("ABCDEFG", None).is_err());
+    assert!(crack("", None).is_ok());
+  }
+}
@@ -0,0 +1,297 @@
+use std::io::{Error, ErrorKind, Result, Write};
+
+use num_bigint::BigUint;
+
+use crate::RsaAttackCommand;
+
+use super::{Rsa, RsaPrivateKey};
+
+/// An attack on RSA with the public values it needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RsaAttack {
+  /// `m^e < n`, so the ciphertext is the plain integer power of `m`.
+  CubeRoot { ciphertext: BigUint, e: u32 },
+  /// The same message encrypted under one modulus with two exponents.
+  CommonModulus {
+    n: BigUint,
+    e1: BigUint,
+    e2: BigUint,
+    c1: BigUint,
+    c2: BigUint,
+  },
+  /// A modulus whose primes are close to each other.
+  Fermat {
+    n: BigUint,
+    e: BigUint,
+    ciphertext: Option<BigUint>,
+    max_iterations: usize,
+  },
+  /// A private exponent `d < n^(1/4) / 3`.
+  Wiener {
+    n: BigUint,
+    e: BigUint,
+    ciphertext: Option<BigUint>,
+  },
+}
+
+impl From<&RsaAttackCommand> for RsaAttack {
+  fn from(value: &RsaAttackCommand) -> Self {
+    match value {
+      RsaAttackCommand::CubeRoot { ciphertext, e, .. } => Self::CubeRoot {
+        ciphertext: ciphertext.clone(),
+        e: *e,
+      },
+      RsaAttackCommand::CommonModulus {
+        n, e1, e2, c1, c2, ..
+      } => Self::CommonModulus {
+        n: n.clone(),
+        e1: e1.clone(),
+        e2: e2.clone(),
+        c1: c1.clone(),
+        c2: c2.clone(),
+      },
+      RsaAttackCommand::Fermat {
+        n,
+        e,
+        ciphertext,
+        max_iterations,
+        ..
+      } => Self::Fermat {
+        n: n.clone(),
+        e: e.clone(),
+        ciphertext: ciphertext.clone(),
+        max_iterations: *max_iterations,
+      },
+      RsaAttackCommand::Wiener {
+        n, e, ciphertext, ..
+      } => Self::Wiener {
+        n: n.clone(),
+        e: e.clone(),
+        ciphertext: ciphertext.clone(),
+      },
+    }
+  }
+}
+
+pub struct RsaAttacks;
+
+impl RsaAttacks {
+  /// Runs the attack and writes the recovered plaintext, or the private
+  /// key followed by the plaintext if a ciphertext is given.
+  pub fn execute<W: Write>(output: &mut W, attack: RsaAttack) -> Result<()> {
+    match attack {
+      RsaAttack::CubeRoot { ciphertext, e } => {
+        let plaintext = Self::integer_root(&ciphertext, e)?;
+        Rsa::write_plaintext(output, &plaintext)
+      }
+      RsaAttack::CommonModulus { n, e1, e2, c1, c2 } => {
+        let plaintext = Self::common_modulus(&n, &e1, &e2, &c1, &c2)?;
+        Rsa::write_plaintext(output, &plaintext)
+      }
+      RsaAttack::Fermat {
+        n,
+        e,
+        ciphertext,
+        max_iterations,
+      } => {
+        let (p, q) = Self::fermat(&n, max_iterations)?;
+        let key = RsaPrivateKey::new(p, q, &e)?;
+        Self::write_key(output, &key, ciphertext)
+      }
+      RsaAttack::Wiener { n, e, ciphertext } => {
+        let key = Self::wiener(&n, &e)?;
+        Self::write_key(output, &key, ciphertext)
+      }
+    }
+  }
+
+  /// The exact `e`-th root of the ciphertext, which is the plaintext if
+  /// `m^e` did not wrap around the modulus.
+  pub fn integer_root(ciphertext: &BigUint, e: u32) -> Result<BigUint> {
+    if e == 0 {
+      let message = "The public exponent must not be zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let root = ciphertext.nth_root(e);
+    if root.pow(e) != *ciphertext {
+      let message = format!("The ciphertext is no perfect {e}-th power");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(root)
+  }
+
+  /// With `a * e1 + b * e2 = 1` the plaintext is `c1^a * c2^b mod n`,
+  /// which requires coprime exponents.
+  pub fn common_modulus(
+    n: &BigUint,
+    e1: &BigUint,
+    e2: &BigUint,
+    c1: &BigUint,
+    c2: &BigUint,
+  ) -> Result<BigUint> {
+    let (a, b, gcd) = Rsa::extended_gcd(e1, e2);
+    if gcd != BigUint::from(1u32) {
+      let message = format!("The exponents share the factor {gcd}");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let power = |c, exponent| {
+      Rsa::signed_modpow(c, exponent, n).ok_or_else(|| {
+        let message = "A ciphertext is not invertible modulo n";
+        Error::new(ErrorKind::InvalidInput, message)
+      })
+    };
+
+    Ok(power(c1, &a)? * power(c2, &b)? % n)
+  }
+
+  /// Writes `n = a^2 - b^2 = (a - b)(a + b)`, searching `a` upwards from
+  /// `sqrt(n)`. Close primes give a small `b` that is found quickly.
+  pub fn fermat(
+    n: &BigUint,
+    max_iterations: usize,
+  ) -> Result<(BigUint, BigUint)> {
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+      a += 1u32;
+    }
+
+    for _ in 0..max_iterations {
+      let square = &a * &a - n;
+      let b = square.sqrt();
+      if &b * &b == square {
+        let p = &a - &b;
+        if p > BigUint::from(1u32) {
+          return Ok((p, &a + &b));
+        }
+      }
+      a += 1u32;
+    }
+
+    let message = format!("No factors found in {max_iterations} iterations");
+    Err(Error::new(ErrorKind::InvalidInput, message))
+  }
+
+  /// Tests the convergents `k / d` of the continued fraction of `e / n`.
+  /// For a small `d` one of them yields `phi(n) = (e * d - 1) / k`, from
+  /// which `p` and `q` are the roots of `x^2 - (n - phi + 1) x + n`.
+  pub fn wiener(n: &BigUint, e: &BigUint) -> Result<RsaPrivateKey> {
+    let zero = BigUint::ZERO;
+    let one = BigUint::from(1u32);
+
+    let (mut numerator, mut denominator) = (e.clone(), n.clone());
+    let (mut k0, mut k1) = (zero.clone(), one.clone());
+    let (mut d0, mut d1) = (one.clone(), zero.clone());
+
+    while denominator != zero {
+      let quotient = &numerator / &denominator;
+      (numerator, denominator) =
+        (denominator.clone(), &numerator - &quotient * &denominator);
+      (k0, k1) = (k1.clone(), &quotient * &k1 + &k0);
+      (d0, d1) = (d1.clone(), &quotient * &d1 + &d0);
+
+      let (k, d) = (&k1, &d1);
+      if *k == zero || (e * d - &one) % k != zero {
+        continue;
+      }
+
+      let phi = (e * d - &one) / k;
+      if phi >= *n {
+        continue;
+      }
+
+      let sum = n - &phi + &one;
+      let square = &sum * &sum;
+      let four_n = n * 4u32;
+      if square < four_n {
+        continue;
+      }
+
+      let discriminant = square - four_n;
+      let root = discriminant.sqrt();
+      if &root * &root == discriminant && (&sum + &root) % 2u32 == zero {
+        let p = (&sum - &root) / 2u32;
+        let q = (&sum + &root) / 2u32;
+        return RsaPrivateKey::new(p, q, e);
+      }
+    }
+
+    let message = "The private exponent is too large for Wiener's attack";
+    Err(Error::new(ErrorKind::InvalidInput, message))
+  }
+
+  fn write_key<W: Write>(
+    output: &mut W,
+    key: &RsaPrivateKey,
+    ciphertext: Option<BigUint>,
+  ) -> Result<()> {
+    key.write(output)?;
+    match ciphertext {
+      Some(ciphertext) => {
+        Rsa::write_plaintext(output, &key.decrypt(&ciphertext))
+      }
+      None => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn number(digits: &str) -> BigUint {
+    digits.parse().unwrap()
+  }
+
+  #[test]
+  fn test_cube_root() -> Result<()> {
+    let plaintext = BigUint::from_bytes_be(b"hi there");
+    let ciphertext = plaintext.pow(3);
+    assert_eq!(RsaAttacks::integer_root(&ciphertext, 3)?, plaintext);
+    assert!(RsaAttacks::integer_root(&(ciphertext + 1u32), 3).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_common_modulus() -> Result<()> {
+    let n = BigUint::from(61u32 * 53);
+    let m = BigUint::from(65u32);
+    let (e1, e2) = (BigUint::from(17u32), BigUint::from(7u32));
+    let (c1, c2) = (m.modpow(&e1, &n), m.modpow(&e2, &n));
+
+    let recovered = RsaAttacks::common_modulus(&n, &e1, &e2, &c1, &c2)?;
+    assert_eq!(recovered, m);
+    Ok(())
+  }
+
+  #[test]
+  fn test_fermat() -> Result<()> {
+    let (p, q) = (number("1000000007"), number("1000000009"));
+    let (a, b) = RsaAttacks::fermat(&(&p * &q), 10)?;
+    assert_eq!((a, b), (p, q));
+    assert!(RsaAttacks::fermat(&BigUint::from(2u32 * 1009), 10).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_wiener() -> Result<()> {
+    let n = number("90581");
+    let e = number("17993");
+    let key = RsaAttacks::wiener(&n, &e)?;
+    assert_eq!(key.d, BigUint::from(5u32));
+    assert_eq!(key.modulus(), n);
+    Ok(())
+  }
+
+  #[test]
+  fn test_execute() -> Result<()> {
+    let mut output = Vec::new();
+    let ciphertext = BigUint::from_bytes_be(b"hi").pow(3);
+    let attack = RsaAttack::CubeRoot { ciphertext, e: 3 };
+    RsaAttacks::execute(&mut output, attack)?;
+    assert_eq!(output, b"m = 26729\ntext = hi\n");
+    Ok(())
+  }
+}
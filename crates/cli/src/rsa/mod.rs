@@ -0,0 +1,121 @@
+pub mod attack;
+
+use std::io::{Error, ErrorKind, Result, Write};
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// An RSA private key recovered from the factors of the modulus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+  pub p: BigUint,
+  pub q: BigUint,
+  pub d: BigUint,
+}
+
+impl RsaPrivateKey {
+  /// The private exponent `d = e^-1 mod (p - 1)(q - 1)`.
+  pub fn new(p: BigUint, q: BigUint, e: &BigUint) -> Result<Self> {
+    let one = BigUint::from(1u32);
+    let phi = (&p - &one) * (&q - &one);
+
+    let d = e.modinv(&phi).ok_or_else(|| {
+      let message = "The public exponent is not invertible modulo phi(n)";
+      Error::new(ErrorKind::InvalidInput, message)
+    })?;
+
+    Ok(Self { p, q, d })
+  }
+
+  pub fn modulus(&self) -> BigUint {
+    &self.p * &self.q
+  }
+
+  pub fn decrypt(&self, ciphertext: &BigUint) -> BigUint {
+    ciphertext.modpow(&self.d, &self.modulus())
+  }
+
+  pub fn write<W: Write>(&self, output: &mut W) -> Result<()> {
+    writeln!(output, "p = {}", self.p)?;
+    writeln!(output, "q = {}", self.q)?;
+    writeln!(output, "d = {}", self.d)
+  }
+}
+
+pub struct Rsa;
+
+impl Rsa {
+  /// Writes a plaintext as integer and, if its bytes are printable, as
+  /// text.
+  pub fn write_plaintext<W: Write>(
+    output: &mut W,
+    plaintext: &BigUint,
+  ) -> Result<()> {
+    writeln!(output, "m = {plaintext}")?;
+
+    let bytes = plaintext.to_bytes_be();
+    let printable = bytes
+      .iter()
+      .all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace());
+
+    if printable {
+      writeln!(output, "text = {}", String::from_utf8_lossy(&bytes))?;
+    }
+
+    Ok(())
+  }
+
+  /// Coefficients `(x, y)` with `a * x + b * y = gcd(a, b)` and the gcd.
+  pub fn extended_gcd(a: &BigUint, b: &BigUint) -> (BigInt, BigInt, BigUint) {
+    let (mut r0, mut r1) = (Self::signed(a), Self::signed(b));
+    let (mut x0, mut x1) = (BigInt::from(1), BigInt::from(0));
+    let (mut y0, mut y1) = (BigInt::from(0), BigInt::from(1));
+
+    while r1 != BigInt::from(0) {
+      let quotient = &r0 / &r1;
+      (r0, r1) = (r1.clone(), &r0 - &quotient * &r1);
+      (x0, x1) = (x1.clone(), &x0 - &quotient * &x1);
+      (y0, y1) = (y1.clone(), &y0 - &quotient * &y1);
+    }
+
+    (x0, y0, r0.magnitude().clone())
+  }
+
+  /// `base^exponent mod modulus` for a possibly negative exponent, using
+  /// the inverse of the base.
+  pub fn signed_modpow(
+    base: &BigUint,
+    exponent: &BigInt,
+    modulus: &BigUint,
+  ) -> Option<BigUint> {
+    let base = match exponent.sign() {
+      Sign::Minus => base.modinv(modulus)?,
+      _ => base.clone(),
+    };
+    Some(base.modpow(exponent.magnitude(), modulus))
+  }
+
+  fn signed(value: &BigUint) -> BigInt {
+    BigInt::from_biguint(Sign::Plus, value.clone())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_private_key() -> Result<()> {
+    let e = BigUint::from(17u32);
+    let key = RsaPrivateKey::new(61u32.into(), 53u32.into(), &e)?;
+    assert_eq!(key.d, BigUint::from(2753u32));
+    assert_eq!(key.decrypt(&2790u32.into()), BigUint::from(65u32));
+    Ok(())
+  }
+
+  #[test]
+  fn test_extended_gcd() {
+    let (x, y, gcd) = Rsa::extended_gcd(&240u32.into(), &46u32.into());
+    assert_eq!(gcd, BigUint::from(2u32));
+    assert_eq!(x * 240 + y * 46, BigInt::from(2));
+  }
+}
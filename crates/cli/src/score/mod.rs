@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::io::{Read, Result, Write};
+use std::path::PathBuf;
+
+use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::ngram::NgramModel;
+use crate::text::spacing::Spacing;
+use crate::Command;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScoreConfig {
+  /// N-gram statistics for the fitness, such as quadgrams.
+  pub ngrams: Option<PathBuf>,
+  /// Wordlist the words are looked up in instead of common English words.
+  pub dictionary: Option<PathBuf>,
+}
+
+impl ScoreConfig {
+  pub fn new(ngrams: Option<PathBuf>, dictionary: Option<PathBuf>) -> Self {
+    Self { ngrams, dictionary }
+  }
+}
+
+impl From<&Command> for ScoreConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Score {
+        ngrams, dictionary, ..
+      } => Self::new(ngrams.clone(), dictionary.clone()),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The statistics of a text that tell how much it looks like English.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextScore {
+  pub letters: usize,
+  /// Chi-square distance of the letter frequencies to English, lower is
+  /// more English-like.
+  pub chi_square: f32,
+  pub index_of_coincidence: f32,
+  /// Average negative log probability of the bigrams, lower is more
+  /// English-like.
+  pub bigram_score: f32,
+  /// Average log probability of the n-grams of the configured
+  /// statistics, higher is more English-like.
+  pub ngram_fitness: Option<f64>,
+  /// Bits per byte.
+  pub entropy: f32,
+  /// Share of the letters that belong to dictionary words.
+  pub word_ratio: f32,
+  /// [`FrequencyAnalyzer::confidence`] of the text.
+  pub confidence: Option<f32>,
+}
+
+impl Display for TextScore {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(f, "letters:              {}", self.letters)?;
+    writeln!(f, "chi-square:           {:.2}", self.chi_square)?;
+    writeln!(f, "index of coincidence: {:.4}", self.index_of_coincidence)?;
+    writeln!(f, "bigram score:         {:.3}", self.bigram_score)?;
+    match self.ngram_fitness {
+      Some(fitness) => writeln!(f, "n-gram fitness:       {fitness:.3}")?,
+      None => writeln!(f, "n-gram fitness:       -")?,
+    }
+    writeln!(f, "entropy:              {:.2} bits", self.entropy)?;
+    writeln!(f, "word ratio:           {:.2}", self.word_ratio)?;
+    match self.confidence {
+      Some(confidence) => writeln!(f, "confidence:           {confidence:.2}"),
+      None => writeln!(f, "confidence:           -"),
+    }
+  }
+}
+
+pub struct Score;
+
+impl Score {
+  /// Writes every statistic of the input, so two candidate decryptions
+  /// can be compared by more than the score of a single cracker.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ScoreConfig,
+  ) -> Result<TextScore> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    let model = config.ngrams.as_ref().map(NgramModel::load).transpose()?;
+    let dictionary = match &config.dictionary {
+      Some(path) => {
+        Some(Self::read_dictionary(&std::fs::read_to_string(path)?))
+      }
+      None => None,
+    };
+
+    let score = Self::score(&content, model.as_deref(), dictionary.as_ref())?;
+    write!(output, "{score}")?;
+    Ok(score)
+  }
+
+  pub fn score(
+    content: &[u8],
+    model: Option<&NgramModel>,
+    dictionary: Option<&HashSet<String>>,
+  ) -> Result<TextScore> {
+    let text = String::from_utf8_lossy(content);
+
+    Ok(TextScore {
+      letters: text.bytes().filter(u8::is_ascii_alphabetic).count(),
+      chi_square: FrequencyAnalyzer::score_text(&mut text.as_bytes())?,
+      index_of_coincidence: FrequencyAnalyzer::index_of_coincidence(&text),
+      bigram_score: FrequencyAnalyzer::bigram_score(&text),
+      ngram_fitness: model.map(|model| model.fitness(&text)),
+      entropy: FrequencyAnalyzer::entropy(content),
+      word_ratio: Self::word_ratio(&text, dictionary),
+      confidence: FrequencyAnalyzer::confidence(&text),
+    })
+  }
+
+  /// Share of the letters that belong to words of the dictionary, or to
+  /// common English words without one. Text without whitespace is split
+  /// into the most likely words first.
+  pub fn word_ratio(text: &str, dictionary: Option<&HashSet<String>>) -> f32 {
+    let spaced = text.contains(char::is_whitespace);
+    let mut letters = 0;
+    let mut known = 0;
+
+    for run in text.split(|c: char| !c.is_ascii_alphabetic()) {
+      let words = match spaced {
+        true => vec![run],
+        false => Spacing::segment(run),
+      };
+      for word in words.iter().filter(|word| !word.is_empty()) {
+        let word = word.to_ascii_lowercase();
+        let found = match dictionary {
+          Some(dictionary) => dictionary.contains(&word),
+          None => Spacing::is_word(&word),
+        };
+        letters += word.len();
+        known += if found { word.len() } else { 0 };
+      }
+    }
+
+    match letters {
+      0 => 0.0,
+      letters => known as f32 / letters as f32,
+    }
+  }
+
+  /// The lowercase words of a wordlist with one word per line.
+  pub fn read_dictionary(wordlist: &str) -> HashSet<String> {
+    wordlist
+      .split_whitespace()
+      .map(|word| word.to_ascii_lowercase())
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_word_ratio() {
+    assert_eq!(Score::word_ratio("Was it the one?", None), 1.0);
+    assert_eq!(Score::word_ratio("THEXQZ", None), 0.5);
+    assert_eq!(Score::word_ratio("", None), 0.0);
+
+    let dictionary = Score::read_dictionary("Attack\ndawn\n");
+    assert_eq!(
+      Score::word_ratio("attack at dawn", Some(&dictionary)),
+      10.0 / 12.0
+    );
+  }
+
+  #[test]
+  fn test_english_scores_better_than_ciphertext() -> Result<()> {
+    let model: NgramModel = "THE 5\nHER 2\nERE 1\n".parse()?;
+    let english = b"there is the hero where the hare went";
+    let cipher = b"wkhuh lv wkh khur zkhuh wkh kduh zhqw";
+    let english = Score::score(english, Some(&model), None)?;
+    let cipher = Score::score(cipher, Some(&model), None)?;
+
+    assert!(english.chi_square < cipher.chi_square);
+    assert!(english.bigram_score < cipher.bigram_score);
+    assert!(english.ngram_fitness > cipher.ngram_fitness);
+    assert!(english.word_ratio > cipher.word_ratio);
+    assert_eq!(english.letters, 30);
+
+    let report = english.to_string();
+    assert!(report.starts_with("letters:              30\n"));
+    assert_eq!(report.lines().count(), 8);
+    Ok(())
+  }
+}
@@ -0,0 +1,231 @@
+use rayon::prelude::*;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  frequency_analysis::FrequencyAnalyzer, report::CrackReport,
+  transposition::ColumnarTransposition, DecryptCipher, EncryptCipher,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScytaleConfig {
+  pub diameter: Option<usize>,
+}
+
+impl ScytaleConfig {
+  pub fn new(diameter: Option<usize>) -> Self {
+    Self { diameter }
+  }
+}
+
+impl From<&EncryptCipher> for ScytaleConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Scytale { diameter, .. } => Self::new(Some(*diameter)),
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for ScytaleConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Scytale { diameter, .. } => Self::new(*diameter),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A candidate decryption found while brute forcing the diameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScytaleCandidate {
+  pub diameter: usize,
+  pub plaintext: String,
+  pub score: f32,
+}
+
+impl From<ScytaleCandidate> for CrackReport {
+  fn from(candidate: ScytaleCandidate) -> Self {
+    let key = Some(candidate.diameter.to_string());
+    CrackReport::new(candidate.plaintext, key, "bigram score")
+  }
+}
+
+pub struct Scytale;
+
+impl Scytale {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ScytaleConfig,
+  ) -> Result<()> {
+    let diameter = Self::validate(config.diameter.unwrap_or_default())?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let cipher = Self::encrypt_text(line, diameter);
+      writeln!(output, "{cipher}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ScytaleConfig,
+  ) -> Result<()> {
+    for report in Self::crack(input, &config)? {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+    Ok(())
+  }
+
+  /// Decrypts every line like [`Scytale::decrypt`] and reports the
+  /// diameter along with the method that found it.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &ScytaleConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let diameter = config.diameter.map(Self::validate).transpose()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let reports = content.lines().map(|line| match diameter {
+      Some(diameter) => {
+        let plaintext = Self::decrypt_text(line, diameter);
+        CrackReport::new(plaintext, Some(diameter.to_string()), "key")
+      }
+      None => match Self::rank(line).into_iter().next() {
+        Some(candidate) => candidate.into(),
+        None => CrackReport::new(Self::strip(line), None, "bigram score"),
+      },
+    });
+    Ok(reports.collect())
+  }
+
+  /// Winds the text around a rod with `diameter` letters per turn.
+  ///
+  /// The text is written row by row into `diameter` rows and read
+  /// column by column. Whitespace is removed before encryption.
+  pub fn encrypt_text(text: &str, diameter: usize) -> String {
+    let text = Self::strip(text);
+    let order = Self::column_order(&text, diameter);
+    ColumnarTransposition::encrypt_columns(&text, &order)
+  }
+
+  pub fn decrypt_text(text: &str, diameter: usize) -> String {
+    let text = Self::strip(text);
+    let order = Self::column_order(&text, diameter);
+    ColumnarTransposition::decrypt_columns(&text, &order)
+  }
+
+  /// Decrypts the text with every plausible diameter and ranks the
+  /// candidates by their bigram score, best first.
+  pub fn rank(text: &str) -> Vec<ScytaleCandidate> {
+    let length = Self::strip(text).chars().count();
+
+    let mut candidates: Vec<ScytaleCandidate> = (2..=length / 2)
+      .into_par_iter()
+      .map(|diameter| {
+        let plaintext = Self::decrypt_text(text, diameter);
+        let score = FrequencyAnalyzer::bigram_score(&plaintext);
+        ScytaleCandidate {
+          diameter,
+          plaintext,
+          score,
+        }
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates
+  }
+
+  fn column_order(text: &str, diameter: usize) -> Vec<usize> {
+    let columns = text.chars().count().div_ceil(diameter);
+    (0..columns).collect()
+  }
+
+  fn strip(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+  }
+
+  fn validate(diameter: usize) -> Result<usize> {
+    if diameter == 0 {
+      let message = "Diameter must be greater than zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    Ok(diameter)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Cursor;
+
+  #[test]
+  fn test_encrypt_text() {
+    let cipher = Scytale::encrypt_text("I AM HURT VERY BADLY HELP", 4);
+    assert_eq!(cipher, "IRYYATBHMVAEHEDLURLP");
+  }
+
+  #[test]
+  fn test_decrypt_text() {
+    let plaintext = Scytale::decrypt_text("IRYYATBHMVAEHEDLURLP", 4);
+    assert_eq!(plaintext, "IAMHURTVERYBADLYHELP");
+  }
+
+  #[test]
+  fn test_roundtrip_with_incomplete_last_row() {
+    let text = "WEAREDISCOVEREDFLEEATONCE";
+    let cipher = Scytale::encrypt_text(text, 3);
+    assert_eq!(Scytale::decrypt_text(&cipher, 3), text);
+  }
+
+  #[test]
+  fn test_rank_finds_diameter() {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    let cipher = Scytale::encrypt_text(text, 5);
+    let best = Scytale::rank(&cipher).into_iter().next().unwrap();
+    assert_eq!(best.diameter, 5);
+    assert_eq!(best.plaintext, text);
+  }
+
+  #[test]
+  fn test_brute_force_decrypt() -> Result<()> {
+    let text = "WEAREDISCOVEREDFLEEATONCEANDMEETATTHEOLDBRIDGE";
+    let mut input = Cursor::new(Scytale::encrypt_text(text, 3));
+    let mut output = Vec::new();
+    Scytale::decrypt(&mut input, &mut output, ScytaleConfig::default())?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_reports_diameter() -> Result<()> {
+    let text = "WEAREDISCOVEREDFLEEATONCEANDMEETATTHEOLDBRIDGE";
+    let cipher = Scytale::encrypt_text(text, 3);
+
+    let config = ScytaleConfig::default();
+    let reports = Scytale::crack(&mut Cursor::new(&cipher), &config)?;
+    assert_eq!(reports[0].key.as_deref(), Some("3"));
+    assert_eq!(reports[0].method, "bigram score");
+
+    let config = ScytaleConfig::new(Some(3));
+    let reports = Scytale::crack(&mut Cursor::new(&cipher), &config)?;
+    assert_eq!(reports[0].plaintext, text);
+    assert_eq!(reports[0].method, "key");
+    Ok(())
+  }
+
+  #[test]
+  fn test_encrypt_rejects_zero_diameter() {
+    let mut input = Cursor::new("text");
+    let mut output = Vec::new();
+    let config = ScytaleConfig::new(Some(0));
+    assert!(Scytale::encrypt(&mut input, &mut output, config).is_err());
+  }
+}
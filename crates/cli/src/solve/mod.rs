@@ -0,0 +1,424 @@
+use clap::ValueEnum;
+
+use std::fmt::{self, Display, Formatter};
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::affine::{Affine, AffineConfig};
+use crate::caesar::{Caesar, CaesarConfig};
+use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::hex::Hex;
+use crate::rail_fence::RailFence;
+use crate::report::{CrackReport, OutputFormat, LOW_CONFIDENCE};
+use crate::scytale::Scytale;
+use crate::solver::{RestartPolicy, SolverConfig};
+use crate::transposition::ColumnarTransposition;
+use crate::vigenere::{Vigenere, VigenereDecryptConfig};
+use crate::xor::repeating_key::RepeatingKeyXor;
+use crate::Command;
+
+/// Index of coincidence below which the letters are probably encrypted
+/// with more than one alphabet. English has about 0.066 and uniformly
+/// random letters about 0.038.
+const POLYALPHABETIC_IC: f32 = 0.055;
+
+/// Confidence at which no further ciphers are tried.
+const SOLVED_CONFIDENCE: f32 = 0.9;
+
+/// Most columns of a columnar transposition that are searched.
+const MAX_COLUMNS: usize = 8;
+
+/// Most rails of a rail fence that are tried.
+const MAX_RAILS: usize = 10;
+
+/// A cipher the solve command can crack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SolveMethod {
+  /// Shift of the alphabet.
+  Caesar,
+  /// Multiplier and shift of the alphabet.
+  Affine,
+  /// Repeating key of shifts.
+  Vigenere,
+  /// Transposition of the letters written around a rod.
+  Scytale,
+  /// Transposition of the letters written in a zigzag over the rails.
+  RailFence,
+  /// Transposition of the columns of a grid with up to 8 columns.
+  Columnar,
+  /// XOR with a single byte, of hex or binary input.
+  Xor,
+}
+
+impl SolveMethod {
+  pub fn name(&self) -> &'static str {
+    match self {
+      SolveMethod::Caesar => "caesar",
+      SolveMethod::Affine => "affine",
+      SolveMethod::Vigenere => "vigenere",
+      SolveMethod::Scytale => "scytale",
+      SolveMethod::RailFence => "rail-fence",
+      SolveMethod::Columnar => "columnar",
+      SolveMethod::Xor => "xor",
+    }
+  }
+}
+
+impl Display for SolveMethod {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SolveConfig {
+  /// The ciphers that are tried. All of them if empty.
+  pub methods: Vec<SolveMethod>,
+}
+
+impl SolveConfig {
+  pub fn new(methods: Vec<SolveMethod>) -> Self {
+    Self { methods }
+  }
+}
+
+impl From<&Command> for SolveConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Solve { methods, .. } => Self::new(methods.clone()),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct AutoSolve;
+
+impl AutoSolve {
+  /// Cracks the input with the most likely cipher and writes the method,
+  /// the key and the plaintext of the best result.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: SolveConfig,
+    format: OutputFormat,
+  ) -> Result<CrackReport> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+    if content.iter().all(u8::is_ascii_whitespace) {
+      let message = "The input is empty, there is nothing to solve";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let report = Self::solve(&content, &config);
+    match format {
+      OutputFormat::Text => {
+        let key = report.key.as_deref().unwrap_or("none");
+        let confidence = report.confidence.unwrap_or_default();
+        writeln!(
+          output,
+          "{}, key {key}, confidence {confidence:.2}",
+          report.method
+        )?;
+        writeln!(output, "{}", report.plaintext)?;
+      }
+      OutputFormat::Json => {
+        CrackReport::write_json(output, std::slice::from_ref(&report))?
+      }
+    }
+
+    Ok(report)
+  }
+
+  /// Tries the ciphers in the order of [`AutoSolve::order`] until one
+  /// yields a confident plaintext, and returns the most confident one.
+  /// The method of the report is the name of the cipher.
+  pub fn solve(content: &[u8], config: &SolveConfig) -> CrackReport {
+    let mut best: Option<CrackReport> = None;
+    let methods = Self::order(content).into_iter().filter(|method| {
+      config.methods.is_empty() || config.methods.contains(method)
+    });
+
+    for method in methods {
+      let Some(report) = Self::attempt(method, content) else {
+        continue;
+      };
+      let confidence = report.confidence.unwrap_or(-1.0);
+      if best
+        .as_ref()
+        .is_none_or(|best| confidence > best.confidence.unwrap_or(-1.0))
+      {
+        best = Some(report);
+      }
+      if confidence >= SOLVED_CONFIDENCE {
+        break;
+      }
+    }
+
+    best.unwrap_or_else(|| {
+      let plaintext = String::from_utf8_lossy(content).into_owned();
+      CrackReport::new(plaintext, None, "none")
+    })
+  }
+
+  /// Orders the ciphers by how well they fit the statistics of the
+  /// input, most likely first.
+  ///
+  /// Binary and hex input is most likely XOR. Letters with a low index
+  /// of coincidence point to a polyalphabetic cipher, otherwise the
+  /// letters are substituted with a single alphabet or transposed.
+  pub fn order(content: &[u8]) -> Vec<SolveMethod> {
+    let text = String::from_utf8_lossy(content);
+    let printable = content
+      .iter()
+      .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace());
+
+    if !printable || Self::hex(content).is_some() {
+      return vec![
+        SolveMethod::Xor,
+        SolveMethod::Caesar,
+        SolveMethod::Affine,
+        SolveMethod::Vigenere,
+        SolveMethod::Scytale,
+        SolveMethod::RailFence,
+        SolveMethod::Columnar,
+      ];
+    }
+
+    if FrequencyAnalyzer::index_of_coincidence(&text) < POLYALPHABETIC_IC {
+      return vec![
+        SolveMethod::Vigenere,
+        SolveMethod::Caesar,
+        SolveMethod::Affine,
+        SolveMethod::Scytale,
+        SolveMethod::RailFence,
+        SolveMethod::Columnar,
+        SolveMethod::Xor,
+      ];
+    }
+
+    vec![
+      SolveMethod::Caesar,
+      SolveMethod::Affine,
+      SolveMethod::Scytale,
+      SolveMethod::RailFence,
+      SolveMethod::Columnar,
+      SolveMethod::Vigenere,
+      SolveMethod::Xor,
+    ]
+  }
+
+  /// Cracks the input with one cipher. Inputs the cipher cannot apply
+  /// to, such as a text too short for a transposition, give `None`.
+  pub fn attempt(method: SolveMethod, content: &[u8]) -> Option<CrackReport> {
+    let text = String::from_utf8_lossy(content);
+    let text = text.trim_end();
+
+    let mut report = match method {
+      SolveMethod::Caesar => Caesar::rank(text, &CaesarConfig::default())
+        .into_iter()
+        .next()?,
+      SolveMethod::Affine => {
+        let config = AffineConfig::default();
+        Affine::rank(text, &config).into_iter().next()?.into()
+      }
+      SolveMethod::Vigenere => {
+        // The key restarts on every line, so the lines are cracked as
+        // one text.
+        let text = text.lines().collect::<Vec<_>>().join(" ");
+        let config = VigenereDecryptConfig::new(None, None, None, false);
+        Vigenere::crack(&mut text.as_bytes(), &config)
+          .ok()?
+          .into_iter()
+          .next()?
+      }
+      SolveMethod::Scytale => Scytale::rank(text).into_iter().next()?.into(),
+      SolveMethod::RailFence => {
+        RailFence::rank(text, MAX_RAILS).into_iter().next()?.into()
+      }
+      SolveMethod::Columnar => Self::columnar(text)?,
+      SolveMethod::Xor => {
+        let bytes = Self::hex(content).unwrap_or_else(|| content.to_vec());
+        if bytes.is_empty() {
+          return None;
+        }
+        let key = RepeatingKeyXor::solve_single_byte(&bytes);
+        let plaintext = RepeatingKeyXor::apply(&bytes, &[key]);
+        let plaintext = String::from_utf8_lossy(&plaintext).into_owned();
+        CrackReport::new(plaintext, Some(format!("{key:02x}")), "")
+      }
+    };
+
+    // Letters of English text in the wrong order still have English
+    // frequencies and many English bigrams, so a failed transposition is
+    // only told apart by the order of its letters.
+    let order = FrequencyAnalyzer::order_confidence(&report.plaintext);
+    report.confidence = match (report.confidence, order) {
+      (Some(confidence), Some(order)) => Some(confidence.min(order)),
+      (confidence, _) => confidence,
+    };
+    report.method = method.name();
+    Some(report)
+  }
+
+  /// Searches the column order for every number of columns up to
+  /// [`MAX_COLUMNS`], fewest first. More columns fit the bigrams of
+  /// English ever better by chance, so the first plaintext that is
+  /// confidently English is taken, and the one with the most English
+  /// order of letters otherwise. The search is seeded, so that the same
+  /// input is always solved alike.
+  fn columnar(text: &str) -> Option<CrackReport> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let config = SolverConfig {
+      iterations: 2_000,
+      restart: RestartPolicy {
+        restarts: 2,
+        patience: None,
+      },
+      seed: Some(0),
+      ..SolverConfig::default()
+    };
+
+    let mut best: Option<(f32, CrackReport)> = None;
+    for width in 2..=MAX_COLUMNS.min(text.chars().count() / 2) {
+      let Ok(solution) = ColumnarTransposition::solve(&text, width, &config)
+      else {
+        continue;
+      };
+      let plaintext =
+        ColumnarTransposition::decrypt_columns(&text, &solution.key);
+      let order =
+        FrequencyAnalyzer::order_confidence(&plaintext).unwrap_or(0.0);
+      let key = solution
+        .key
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+      let report = CrackReport::new(plaintext, Some(key), "");
+      if order >= LOW_CONFIDENCE {
+        return Some(report);
+      }
+      if best.as_ref().is_none_or(|(best, _)| order > *best) {
+        best = Some((order, report));
+      }
+    }
+
+    best.map(|(_, report)| report)
+  }
+
+  /// The bytes of the input if it is hex, ignoring whitespace.
+  pub fn hex(content: &[u8]) -> Option<Vec<u8>> {
+    let hex: String = String::from_utf8_lossy(content)
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect();
+    if hex.is_empty() {
+      return None;
+    }
+    Hex::parse_hex(&hex).ok().map(|hex| hex.bytes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PLAINTEXT: &str = "it was a bright cold day in april and the clocks \
+    were striking thirteen winston smith his chin nuzzled into his breast \
+    in an effort to escape the vile wind slipped quickly through the glass \
+    doors of victory mansions";
+
+  fn solve(ciphertext: &[u8]) -> CrackReport {
+    AutoSolve::solve(ciphertext, &SolveConfig::default())
+  }
+
+  #[test]
+  fn test_solves_caesar() {
+    let alphabet = CaesarConfig::default().alphabet;
+    let ciphertext: String =
+      PLAINTEXT.chars().map(|c| alphabet.shift(c, 7)).collect();
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "caesar");
+    assert_eq!(report.key.as_deref(), Some("7"));
+    assert_eq!(report.plaintext.to_ascii_lowercase(), PLAINTEXT);
+  }
+
+  #[test]
+  fn test_solves_affine() {
+    let ciphertext = Affine::encrypt_text(PLAINTEXT, 5, 8);
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "affine");
+    assert_eq!(report.key.as_deref(), Some("a=5 b=8"));
+    assert_eq!(report.plaintext, PLAINTEXT);
+  }
+
+  #[test]
+  fn test_solves_scytale() {
+    let ciphertext = Scytale::encrypt_text(PLAINTEXT, 5);
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "scytale");
+    assert_eq!(report.key.as_deref(), Some("5"));
+  }
+
+  #[test]
+  fn test_solves_columnar() {
+    let ciphertext =
+      ColumnarTransposition::encrypt_text(&PLAINTEXT.replace(' ', ""), "ZEBRA")
+        .unwrap();
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "columnar");
+    assert_eq!(report.key.as_deref(), Some("4,2,1,3,0"));
+  }
+
+  #[test]
+  fn test_solves_rail_fence() {
+    let ciphertext = RailFence::encrypt_text(PLAINTEXT, 4);
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "rail-fence");
+    assert_eq!(report.key.as_deref(), Some("4"));
+    assert_eq!(report.plaintext, PLAINTEXT);
+  }
+
+  #[test]
+  fn test_failed_transposition_is_not_confident() {
+    let ciphertext =
+      ColumnarTransposition::encrypt_text(&PLAINTEXT.replace(' ', ""), "ZEBRA")
+        .unwrap();
+    let report = AutoSolve::solve(
+      ciphertext.as_bytes(),
+      &SolveConfig::new(vec![SolveMethod::Scytale]),
+    );
+    assert!(CrackReport::check_confidence(&[report]).is_err());
+  }
+
+  #[test]
+  fn test_empty_input() {
+    let config = SolveConfig::default();
+    let mut input = std::io::Cursor::new(" \n");
+    let result = AutoSolve::execute(
+      &mut input,
+      &mut Vec::new(),
+      config,
+      OutputFormat::Text,
+    );
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_solves_single_byte_xor() {
+    let bytes = RepeatingKeyXor::apply(PLAINTEXT.as_bytes(), &[0x5a]);
+    let ciphertext = Hex::new(bytes).to_string();
+    assert_eq!(AutoSolve::order(ciphertext.as_bytes())[0], SolveMethod::Xor);
+
+    let report = solve(ciphertext.as_bytes());
+    assert_eq!(report.method, "xor");
+    assert_eq!(report.key.as_deref(), Some("5a"));
+    assert_eq!(report.plaintext, PLAINTEXT);
+  }
+
+  #[test]
+  fn test_methods_restrict_attempts() {
+    let config = SolveConfig::new(vec![SolveMethod::Vigenere]);
+    let report = AutoSolve::solve(PLAINTEXT.as_bytes(), &config);
+    assert_eq!(report.method, "vigenere");
+  }
+}
@@ -0,0 +1,561 @@
+use std::io::Result;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::frequency_analysis::FrequencyAnalyzer;
+use crate::ngram::NgramModel;
+use crate::{progress::Progress, CryptologySolverArgs};
+
+/// Iterations a search runs between two reports of its progress.
+const PROGRESS_INTERVAL: usize = 1024;
+
+/// Most modifications of the initial key a [`Seeded`] search starts with.
+const SEED_DISTANCE: usize = 3;
+
+/// Search strategy used to improve a key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SolverKind {
+  /// Only accept keys that improve the score.
+  #[value(name = "climb")]
+  HillClimbing,
+  /// Accept worse keys with a probability that decreases over time.
+  #[default]
+  #[value(name = "anneal")]
+  SimulatedAnnealing,
+  /// Evolve a population of keys by selection, crossover and mutation.
+  #[value(name = "ga")]
+  Genetic,
+}
+
+/// Produces the keys explored by the solver.
+pub trait CandidateGenerator: Sync {
+  type Key: Clone + Send;
+
+  /// A random key to start a search from.
+  fn random<G: Rng>(&self, rng: &mut G) -> Self::Key;
+
+  /// A small random modification of the key.
+  fn neighbour<G: Rng>(&self, key: &Self::Key, rng: &mut G) -> Self::Key;
+
+  /// Combines two parent keys into a child for the genetic algorithm.
+  ///
+  /// Keys without a meaningful crossover evolve by mutation only.
+  fn crossover<G: Rng>(
+    &self,
+    first: &Self::Key,
+    _second: &Self::Key,
+    _rng: &mut G,
+  ) -> Self::Key {
+    first.clone()
+  }
+}
+
+/// Rates a key, lower scores are better.
+pub trait Scorer<K>: Sync {
+  fn score(&self, key: &K) -> f32;
+}
+
+impl<K, F: Fn(&K) -> f32 + Sync> Scorer<K> for F {
+  fn score(&self, key: &K) -> f32 {
+    self(key)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+  /// Number of independent searches, which run in parallel.
+  pub restarts: usize,
+  /// Iterations without a new best key after which a search starts
+  /// over from a random key.
+  pub patience: Option<usize>,
+}
+
+impl Default for RestartPolicy {
+  fn default() -> Self {
+    Self {
+      restarts: rayon::current_num_threads(),
+      patience: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolverConfig {
+  pub kind: SolverKind,
+  pub iterations: usize,
+  /// Initial temperature of simulated annealing, cooling linearly to 0.
+  pub temperature: f32,
+  pub restart: RestartPolicy,
+  /// Makes the search reproducible. Each restart derives its own seed.
+  pub seed: Option<u64>,
+  /// Number of keys per generation of the genetic algorithm.
+  pub population: usize,
+  /// Probability that a child of the genetic algorithm is mutated.
+  pub mutation: f32,
+  /// Report the candidates tried and the best score on standard error.
+  pub progress: bool,
+  /// N-gram statistics candidate plaintexts are scored by instead of
+  /// English bigrams.
+  pub ngrams: Option<PathBuf>,
+}
+
+impl SolverConfig {
+  pub fn new(
+    kind: SolverKind,
+    iterations: usize,
+    restart: RestartPolicy,
+    seed: Option<u64>,
+  ) -> Self {
+    Self {
+      kind,
+      iterations,
+      restart,
+      seed,
+      ..Self::default()
+    }
+  }
+}
+
+impl SolverConfig {
+  /// Scores a candidate plaintext, lower is better.
+  ///
+  /// Candidates are scored by the negative average log probability of
+  /// their n-grams if n-gram statistics are configured, and by their
+  /// bigrams otherwise.
+  pub fn text_scorer(&self) -> Result<impl Fn(&str) -> f32 + Sync> {
+    let model = self.ngrams.as_ref().map(NgramModel::load).transpose()?;
+    Ok(move |text: &str| match &model {
+      Some(model) => -model.fitness(text) as f32,
+      None => FrequencyAnalyzer::bigram_score(text),
+    })
+  }
+}
+
+impl Default for SolverConfig {
+  fn default() -> Self {
+    Self {
+      kind: SolverKind::default(),
+      iterations: 20_000,
+      temperature: 0.05,
+      restart: RestartPolicy::default(),
+      seed: None,
+      population: 100,
+      mutation: 0.3,
+      progress: false,
+      ngrams: None,
+    }
+  }
+}
+
+impl From<&CryptologySolverArgs> for SolverConfig {
+  fn from(value: &CryptologySolverArgs) -> Self {
+    let restart = RestartPolicy {
+      restarts: value.restarts.unwrap_or_else(rayon::current_num_threads),
+      patience: value.patience,
+    };
+    Self {
+      population: value.population,
+      progress: value.progress,
+      ngrams: value.ngrams.clone(),
+      ..Self::new(
+        value.solver.unwrap_or_default(),
+        value.iterations,
+        restart,
+        value.seed,
+      )
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<K> {
+  pub key: K,
+  pub score: f32,
+}
+
+pub struct Solver;
+
+impl Solver {
+  /// Runs the configured number of searches and returns the best key.
+  pub fn solve<G: CandidateGenerator, S: Scorer<G::Key>>(
+    generator: &G,
+    scorer: &S,
+    config: &SolverConfig,
+  ) -> Solution<G::Key> {
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let restarts = config.restart.restarts.max(1);
+    let progress =
+      Progress::new("solver", restarts * config.iterations, config.progress);
+
+    let solution = (0..restarts)
+      .into_par_iter()
+      .map(|restart| {
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(restart as u64));
+        match config.kind {
+          SolverKind::Genetic => {
+            Self::evolve(generator, scorer, config, &progress, &mut rng)
+          }
+          _ => Self::search(generator, scorer, config, &progress, &mut rng),
+        }
+      })
+      .min_by(|a, b| a.score.total_cmp(&b.score))
+      .expect("Solver runs at least one search");
+
+    progress.finish();
+    solution
+  }
+
+  fn search<G: CandidateGenerator, S: Scorer<G::Key>>(
+    generator: &G,
+    scorer: &S,
+    config: &SolverConfig,
+    progress: &Progress,
+    rng: &mut StdRng,
+  ) -> Solution<G::Key> {
+    let mut current = generator.random(rng);
+    let mut current_score = scorer.score(&current);
+    let mut best = Solution {
+      key: current.clone(),
+      score: current_score,
+    };
+    let mut stalled = 0;
+
+    for iteration in 0..config.iterations {
+      let candidate = generator.neighbour(&current, rng);
+      let score = scorer.score(&candidate);
+
+      if Self::accept(config, current_score, score, iteration, rng) {
+        current = candidate;
+        current_score = score;
+      }
+
+      if current_score < best.score {
+        best = Solution {
+          key: current.clone(),
+          score: current_score,
+        };
+        stalled = 0;
+      } else {
+        stalled += 1;
+      }
+
+      if config
+        .restart
+        .patience
+        .is_some_and(|patience| stalled >= patience)
+      {
+        current = generator.random(rng);
+        current_score = scorer.score(&current);
+        stalled = 0;
+      }
+
+      if (iteration + 1) % PROGRESS_INTERVAL == 0 {
+        progress.tick(PROGRESS_INTERVAL, best.score);
+      }
+    }
+
+    progress.tick(config.iterations % PROGRESS_INTERVAL, best.score);
+    best
+  }
+
+  /// Runs the genetic algorithm for as many generations as fit into
+  /// the configured number of iterations, keeping the two best keys of
+  /// every generation.
+  fn evolve<G: CandidateGenerator, S: Scorer<G::Key>>(
+    generator: &G,
+    scorer: &S,
+    config: &SolverConfig,
+    progress: &Progress,
+    rng: &mut StdRng,
+  ) -> Solution<G::Key> {
+    const ELITE: usize = 2;
+
+    let size = config.population.max(ELITE + 1);
+    let evaluate = |key: G::Key| Solution {
+      score: scorer.score(&key),
+      key,
+    };
+
+    let mut population: Vec<Solution<G::Key>> =
+      (0..size).map(|_| evaluate(generator.random(rng))).collect();
+
+    for _ in 0..config.iterations / size {
+      population.sort_by(|a, b| a.score.total_cmp(&b.score));
+      let mut next = population[..ELITE].to_vec();
+
+      while next.len() < size {
+        let first = Self::tournament(&population, rng);
+        let second = Self::tournament(&population, rng);
+        let mut child = generator.crossover(&first.key, &second.key, rng);
+
+        if rng.gen::<f32>() < config.mutation {
+          child = generator.neighbour(&child, rng);
+        }
+
+        next.push(evaluate(child));
+      }
+
+      population = next;
+      progress.tick(size, population[0].score);
+    }
+
+    population
+      .into_iter()
+      .min_by(|a, b| a.score.total_cmp(&b.score))
+      .expect("Population is never empty")
+  }
+
+  /// Picks the best of three random members of the population.
+  fn tournament<'a, K>(
+    population: &'a [Solution<K>],
+    rng: &mut StdRng,
+  ) -> &'a Solution<K> {
+    (0..3)
+      .map(|_| &population[rng.gen_range(0..population.len())])
+      .min_by(|a, b| a.score.total_cmp(&b.score))
+      .unwrap_or(&population[0])
+  }
+
+  fn accept(
+    config: &SolverConfig,
+    current: f32,
+    candidate: f32,
+    iteration: usize,
+    rng: &mut StdRng,
+  ) -> bool {
+    let delta = candidate - current;
+
+    if delta <= 0.0 {
+      return true;
+    }
+
+    match config.kind {
+      SolverKind::HillClimbing | SolverKind::Genetic => false,
+      SolverKind::SimulatedAnnealing => {
+        let progress = iteration as f32 / config.iterations as f32;
+        let temperature = config.temperature * (1.0 - progress);
+        temperature > 0.0 && rng.gen::<f32>() < (-delta / temperature).exp()
+      }
+    }
+  }
+}
+
+/// Permutations of `0..length`, modified by swapping two positions.
+pub struct Permutations {
+  pub length: usize,
+}
+
+impl CandidateGenerator for Permutations {
+  type Key = Vec<usize>;
+
+  fn random<G: Rng>(&self, rng: &mut G) -> Self::Key {
+    use rand::seq::SliceRandom;
+
+    let mut key: Vec<usize> = (0..self.length).collect();
+    key.shuffle(rng);
+    key
+  }
+
+  fn neighbour<G: Rng>(&self, key: &Self::Key, rng: &mut G) -> Self::Key {
+    let mut key = key.clone();
+    if self.length > 1 {
+      let a = rng.gen_range(0..self.length);
+      let b = rng.gen_range(0..self.length);
+      key.swap(a, b);
+    }
+    key
+  }
+
+  /// Order crossover: a random slice of the first parent is kept in
+  /// place and the remaining positions are filled in the order of the
+  /// second parent.
+  fn crossover<G: Rng>(
+    &self,
+    first: &Self::Key,
+    second: &Self::Key,
+    rng: &mut G,
+  ) -> Self::Key {
+    if self.length < 2 {
+      return first.clone();
+    }
+
+    let a = rng.gen_range(0..self.length);
+    let b = rng.gen_range(0..self.length);
+    let (start, end) = (a.min(b), a.max(b) + 1);
+    let kept = &first[start..end];
+    let mut rest = second.iter().filter(|value| !kept.contains(value));
+
+    (0..self.length)
+      .map(|i| match i {
+        i if (start..end).contains(&i) => first[i],
+        _ => *rest.next().unwrap_or(&first[i]),
+      })
+      .collect()
+  }
+}
+
+/// Starts the searches from an initial guess instead of random keys.
+///
+/// Every start is the guess modified up to [`SEED_DISTANCE`] times, so
+/// restarts and the population of the genetic algorithm still differ.
+pub struct Seeded<'a, G: CandidateGenerator> {
+  pub generator: &'a G,
+  pub key: G::Key,
+}
+
+impl<G: CandidateGenerator> CandidateGenerator for Seeded<'_, G>
+where
+  G::Key: Sync,
+{
+  type Key = G::Key;
+
+  fn random<R: Rng>(&self, rng: &mut R) -> Self::Key {
+    (0..rng.gen_range(0..=SEED_DISTANCE)).fold(self.key.clone(), |key, _| {
+      self.generator.neighbour(&key, rng)
+    })
+  }
+
+  fn neighbour<R: Rng>(&self, key: &Self::Key, rng: &mut R) -> Self::Key {
+    self.generator.neighbour(key, rng)
+  }
+
+  fn crossover<R: Rng>(
+    &self,
+    first: &Self::Key,
+    second: &Self::Key,
+    rng: &mut R,
+  ) -> Self::Key {
+    self.generator.crossover(first, second, rng)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn misplaced() -> impl Fn(&Vec<usize>) -> f32 + Sync {
+    |key| key.iter().enumerate().filter(|(i, &k)| *i != k).count() as f32
+  }
+
+  fn config(kind: SolverKind) -> SolverConfig {
+    let restart = RestartPolicy {
+      restarts: 2,
+      patience: None,
+    };
+    SolverConfig::new(kind, 2_000, restart, Some(42))
+  }
+
+  #[test]
+  fn test_hill_climbing_sorts_permutation() {
+    let generator = Permutations { length: 12 };
+    let config = config(SolverKind::HillClimbing);
+    let solution = Solver::solve(&generator, &misplaced(), &config);
+    assert_eq!(solution.key, (0..12).collect::<Vec<_>>());
+    assert_eq!(solution.score, 0.0);
+  }
+
+  #[test]
+  fn test_simulated_annealing_sorts_permutation() {
+    let generator = Permutations { length: 12 };
+    let config = config(SolverKind::SimulatedAnnealing);
+    let solution = Solver::solve(&generator, &misplaced(), &config);
+    assert_eq!(solution.score, 0.0);
+  }
+
+  #[test]
+  fn test_genetic_algorithm_sorts_permutation() {
+    let generator = Permutations { length: 12 };
+    let config = SolverConfig {
+      population: 50,
+      iterations: 10_000,
+      ..config(SolverKind::Genetic)
+    };
+    let solution = Solver::solve(&generator, &misplaced(), &config);
+    assert_eq!(solution.score, 0.0);
+  }
+
+  #[test]
+  fn test_order_crossover_yields_permutation() {
+    let generator = Permutations { length: 10 };
+    let mut rng = StdRng::seed_from_u64(0);
+    let first = generator.random(&mut rng);
+    let second = generator.random(&mut rng);
+
+    for _ in 0..100 {
+      let mut child = generator.crossover(&first, &second, &mut rng);
+      child.sort();
+      assert_eq!(child, (0..10).collect::<Vec<_>>());
+    }
+  }
+
+  #[test]
+  fn test_seed_is_reproducible() {
+    let generator = Permutations { length: 30 };
+    let config = SolverConfig {
+      iterations: 50,
+      ..config(SolverKind::SimulatedAnnealing)
+    };
+    let first = Solver::solve(&generator, &misplaced(), &config);
+    let second = Solver::solve(&generator, &misplaced(), &config);
+    assert_eq!(first, second);
+  }
+
+  #[test]
+  fn test_patience_restarts_search() {
+    let generator = Permutations { length: 8 };
+    let config = SolverConfig {
+      restart: RestartPolicy {
+        restarts: 1,
+        patience: Some(10),
+      },
+      ..config(SolverKind::HillClimbing)
+    };
+    let solution = Solver::solve(&generator, &misplaced(), &config);
+    assert_eq!(solution.score, 0.0);
+  }
+
+  #[test]
+  fn test_seeded_search_starts_near_guess() {
+    let generator = Permutations { length: 30 };
+    let mut key: Vec<usize> = (0..30).collect();
+    key.swap(3, 4);
+    let seeded = Seeded {
+      generator: &generator,
+      key,
+    };
+    let config = SolverConfig {
+      iterations: 0,
+      ..config(SolverKind::HillClimbing)
+    };
+    let solution = Solver::solve(&seeded, &misplaced(), &config);
+    assert!(solution.score <= (2 * (SEED_DISTANCE + 1)) as f32);
+  }
+
+  #[test]
+  fn test_text_scorer_uses_ngrams() -> Result<()> {
+    let path = std::env::temp_dir().join("cryptology-solver-bigrams.txt");
+    std::fs::write(&path, "TH 9\nHE 1\n")?;
+    let config = SolverConfig {
+      ngrams: Some(path.clone()),
+      ..SolverConfig::default()
+    };
+    let score = config.text_scorer()?;
+    std::fs::remove_file(&path)?;
+    assert!(score("THE") < score("HET"));
+
+    let config = SolverConfig {
+      ngrams: Some(path),
+      ..SolverConfig::default()
+    };
+    assert!(config.text_scorer().is_ok());
+    let config = SolverConfig {
+      ngrams: Some(PathBuf::from("missing-ngrams.txt")),
+      ..SolverConfig::default()
+    };
+    assert!(config.text_scorer().is_err());
+    Ok(())
+  }
+}
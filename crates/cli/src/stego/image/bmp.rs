@@ -0,0 +1,153 @@
+use std::io::{Error, ErrorKind, Result};
+
+use super::{Container, Image};
+
+fn invalid(message: &str) -> Error {
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32> {
+  bytes
+    .get(offset..offset + 4)
+    .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    .ok_or_else(|| invalid("The BMP header is truncated"))
+}
+
+/// Padded length of a row of pixels in the file.
+fn stride(width: usize, channels: usize) -> usize {
+  (width * channels).div_ceil(4) * 4
+}
+
+/// Decodes an uncompressed BMP with 24 or 32 bits per pixel. Everything
+/// around the pixels is kept as is, so the image is written back with
+/// only the pixels changed.
+pub fn decode(bytes: &[u8]) -> Result<Image> {
+  if !bytes.starts_with(b"BM") {
+    return Err(invalid("The input is not a BMP image"));
+  }
+
+  let offset = u32_at(bytes, 10)? as usize;
+  let width = u32_at(bytes, 18)? as i32;
+  let height = u32_at(bytes, 22)? as i32;
+  let depth = bytes
+    .get(28..30)
+    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+    .ok_or_else(|| invalid("The BMP header is truncated"))?;
+  let compression = u32_at(bytes, 30)?;
+
+  let channels = match (depth, compression) {
+    (24, 0) => 3,
+    (32, 0 | 3) => 4,
+    _ => {
+      let message = "Only uncompressed BMP images with 24 or 32 bits per \
+        pixel are supported";
+      return Err(invalid(message));
+    }
+  };
+  if width <= 0 || height == 0 {
+    return Err(invalid("The BMP image has no pixels"));
+  }
+
+  let (width, bottom_up) = (width as usize, height > 0);
+  let height = height.unsigned_abs() as usize;
+  let stride = stride(width, channels);
+  let end = offset + stride * height;
+  let data = bytes
+    .get(offset..end)
+    .ok_or_else(|| invalid("The BMP image data is truncated"))?;
+
+  let mut pixels = Vec::with_capacity(width * height * channels);
+  let rows: Box<dyn Iterator<Item = &[u8]>> = match bottom_up {
+    true => Box::new(data.chunks(stride).rev()),
+    false => Box::new(data.chunks(stride)),
+  };
+  for row in rows {
+    for pixel in row[..width * channels].chunks(channels) {
+      // BMP stores the channels as BGR(A).
+      pixels.extend([pixel[2], pixel[1], pixel[0]]);
+      pixels.extend(&pixel[3..]);
+    }
+  }
+
+  Ok(Image {
+    width,
+    height,
+    channels,
+    alpha: channels == 4,
+    pixels,
+    container: Container::Bmp {
+      header: bytes[..offset].to_vec(),
+      trailer: bytes[end..].to_vec(),
+      bottom_up,
+    },
+  })
+}
+
+pub fn encode(
+  image: &Image,
+  header: &[u8],
+  trailer: &[u8],
+  bottom_up: bool,
+) -> Vec<u8> {
+  let channels = image.channels;
+  let stride = stride(image.width, channels);
+  let mut rows: Vec<Vec<u8>> = image
+    .pixels
+    .chunks(image.width * channels)
+    .map(|row| {
+      let mut line = Vec::with_capacity(stride);
+      for pixel in row.chunks(channels) {
+        line.extend([pixel[2], pixel[1], pixel[0]]);
+        line.extend(&pixel[3..]);
+      }
+      line.resize(stride, 0);
+      line
+    })
+    .collect();
+  if bottom_up {
+    rows.reverse();
+  }
+
+  let mut bmp = header.to_vec();
+  bmp.extend(rows.concat());
+  bmp.extend(trailer);
+  bmp
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A bottom-up 24-bit BMP of 2x2 pixels, so every row is padded.
+  fn bmp() -> Vec<u8> {
+    let mut bmp = b"BM".to_vec();
+    bmp.extend(70u32.to_le_bytes());
+    bmp.extend([0; 4]);
+    bmp.extend(54u32.to_le_bytes());
+    bmp.extend(40u32.to_le_bytes());
+    bmp.extend(2i32.to_le_bytes());
+    bmp.extend(2i32.to_le_bytes());
+    bmp.extend(1u16.to_le_bytes());
+    bmp.extend(24u16.to_le_bytes());
+    bmp.extend([0; 24]);
+    // The bottom row first, blue green red.
+    bmp.extend([1, 2, 3, 4, 5, 6, 0, 0]);
+    bmp.extend([7, 8, 9, 10, 11, 12, 0, 0]);
+    bmp
+  }
+
+  #[test]
+  fn test_decode() -> Result<()> {
+    let image = decode(&bmp())?;
+    assert_eq!((image.width, image.height, image.channels), (2, 2, 3));
+    assert_eq!(image.pixels, [9, 8, 7, 12, 11, 10, 3, 2, 1, 6, 5, 4]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_round_trip() -> Result<()> {
+    assert_eq!(decode(&bmp())?.encode(), bmp());
+    assert!(decode(&bmp()[..60]).is_err());
+    Ok(())
+  }
+}
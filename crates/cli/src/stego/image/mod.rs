@@ -0,0 +1,245 @@
+pub mod bmp;
+pub mod png;
+pub mod zlib;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::path::PathBuf;
+
+use crate::StegoCommand;
+
+/// Length of the header that precedes an embedded payload.
+const LENGTH_BYTES: usize = 4;
+
+/// The file an image was decoded from, with what is needed to write it
+/// back in the same format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Container {
+  Png {
+    color_type: u8,
+    /// Ancillary chunks by type.
+    chunks: Vec<([u8; 4], Vec<u8>)>,
+  },
+  Bmp {
+    /// Everything before the pixels.
+    header: Vec<u8>,
+    /// Everything after the pixels.
+    trailer: Vec<u8>,
+    bottom_up: bool,
+  },
+}
+
+/// An image with 8 bits per channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+  pub width: usize,
+  pub height: usize,
+  /// Bytes per pixel, the alpha channel included.
+  pub channels: usize,
+  pub alpha: bool,
+  /// The rows from top to bottom, with the channels in RGB(A) order.
+  pub pixels: Vec<u8>,
+  pub container: Container,
+}
+
+impl Image {
+  /// Decodes a PNG or BMP image, told apart by its signature.
+  pub fn decode(bytes: &[u8]) -> Result<Self> {
+    if bytes.starts_with(&png::SIGNATURE) {
+      png::decode(bytes)
+    } else if bytes.starts_with(b"BM") {
+      bmp::decode(bytes)
+    } else {
+      let message = "The input is neither a PNG nor a BMP image";
+      Err(Error::new(ErrorKind::InvalidData, message))
+    }
+  }
+
+  /// Encodes the image in the format it was decoded from.
+  pub fn encode(&self) -> Vec<u8> {
+    match &self.container {
+      Container::Png { color_type, chunks } => {
+        png::encode(self, *color_type, chunks)
+      }
+      Container::Bmp {
+        header,
+        trailer,
+        bottom_up,
+      } => bmp::encode(self, header, trailer, *bottom_up),
+    }
+  }
+
+  /// Channels that carry color. The alpha channel is left alone, since
+  /// changing it in transparent areas makes the payload stand out.
+  fn colors(&self) -> usize {
+    self.channels - self.alpha as usize
+  }
+
+  fn color_bytes(&self) -> impl Iterator<Item = &u8> {
+    let (channels, colors) = (self.channels, self.colors());
+    self
+      .pixels
+      .iter()
+      .enumerate()
+      .filter(move |(i, _)| i % channels < colors)
+      .map(|(_, byte)| byte)
+  }
+
+  fn color_bytes_mut(&mut self) -> impl Iterator<Item = &mut u8> {
+    let (channels, colors) = (self.channels, self.colors());
+    self
+      .pixels
+      .iter_mut()
+      .enumerate()
+      .filter(move |(i, _)| i % channels < colors)
+      .map(|(_, byte)| byte)
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImageStegoConfig {
+  /// File whose bytes are hidden in the image. The input is extracted
+  /// without one.
+  pub embed: Option<PathBuf>,
+  /// Extract every least significant bit instead of a payload with a
+  /// length header, for payloads hidden by other tools.
+  pub raw: bool,
+}
+
+impl ImageStegoConfig {
+  pub fn new(embed: Option<PathBuf>, raw: bool) -> Self {
+    Self { embed, raw }
+  }
+}
+
+impl From<&StegoCommand> for ImageStegoConfig {
+  fn from(value: &StegoCommand) -> Self {
+    match value {
+      StegoCommand::Image { embed, raw, .. } => Self::new(embed.clone(), *raw),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct ImageStego;
+
+impl ImageStego {
+  /// Writes the image with the payload embedded, or the payload
+  /// extracted from the image.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ImageStegoConfig,
+  ) -> Result<()> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let mut image = Image::decode(&bytes)?;
+
+    match &config.embed {
+      Some(path) => {
+        Self::embed(&mut image, &std::fs::read(path)?)?;
+        output.write_all(&image.encode())
+      }
+      None => output.write_all(&Self::extract(&image, config.raw)?),
+    }
+  }
+
+  /// Number of payload bytes the image can hide.
+  pub fn capacity(image: &Image) -> usize {
+    (image.color_bytes().count() / 8).saturating_sub(LENGTH_BYTES)
+  }
+
+  /// Hides the payload behind a big-endian length header in the least
+  /// significant bits of the color channels, row by row.
+  pub fn embed(image: &mut Image, payload: &[u8]) -> Result<()> {
+    let capacity = Self::capacity(image);
+    if payload.len() > capacity || payload.len() > u32::MAX as usize {
+      let message = format!(
+        "The payload of {} bytes exceeds the capacity of {capacity} bytes",
+        payload.len()
+      );
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut data = (payload.len() as u32).to_be_bytes().to_vec();
+    data.extend(payload);
+    for (byte, bit) in image.color_bytes_mut().zip(super::bits(&data)) {
+      *byte = *byte & !1 | bit as u8;
+    }
+    Ok(())
+  }
+
+  pub fn extract(image: &Image, raw: bool) -> Result<Vec<u8>> {
+    let bytes = super::bytes(image.color_bytes().map(|byte| byte & 1 == 1));
+    if raw {
+      return Ok(bytes);
+    }
+
+    let length = bytes
+      .get(..LENGTH_BYTES)
+      .map(|length| u32::from_be_bytes(length.try_into().unwrap()) as usize)
+      .filter(|&length| length <= bytes.len() - LENGTH_BYTES)
+      .ok_or_else(|| {
+        let message = "The image hides no payload with a length header, \
+          try --raw";
+        Error::new(ErrorKind::InvalidData, message)
+      })?;
+    Ok(bytes[LENGTH_BYTES..LENGTH_BYTES + length].to_vec())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn image(alpha: bool) -> Image {
+    let channels = 3 + alpha as usize;
+    Image {
+      width: 8,
+      height: 4,
+      channels,
+      alpha,
+      pixels: (0..32 * channels).map(|i| (i * 37) as u8).collect(),
+      container: Container::Png {
+        color_type: if alpha { 6 } else { 2 },
+        chunks: Vec::new(),
+      },
+    }
+  }
+
+  #[test]
+  fn test_embed_and_extract() -> Result<()> {
+    let mut image = image(false);
+    assert_eq!(ImageStego::capacity(&image), 8);
+
+    let original = image.pixels.clone();
+    ImageStego::embed(&mut image, b"secret")?;
+    assert!(image
+      .pixels
+      .iter()
+      .zip(&original)
+      .all(|(a, b)| a >> 1 == b >> 1));
+
+    let decoded = Image::decode(&image.encode())?;
+    assert_eq!(ImageStego::extract(&decoded, false)?, b"secret");
+    assert_eq!(ImageStego::extract(&decoded, true)?.len(), 12);
+    Ok(())
+  }
+
+  #[test]
+  fn test_alpha_is_untouched() -> Result<()> {
+    let mut image = image(true);
+    let original = image.pixels.clone();
+    ImageStego::embed(&mut image, b"\xff\xff\xff\xff")?;
+    for (pixel, original) in image.pixels.chunks(4).zip(original.chunks(4)) {
+      assert_eq!(pixel[3], original[3]);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_payload_too_large() {
+    let mut image = image(false);
+    assert!(ImageStego::embed(&mut image, &[0; 9]).is_err());
+    assert!(Image::decode(b"GIF89a").is_err());
+  }
+}
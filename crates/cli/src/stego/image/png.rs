@@ -0,0 +1,199 @@
+use std::io::{Error, ErrorKind, Result};
+
+use super::{zlib, Container, Image};
+use crate::checksum::Checksum;
+
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+fn invalid(message: &str) -> Error {
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Decodes a non-interlaced PNG with 8 bits per channel. Chunk checksums
+/// are not verified, since challenge images often have broken ones.
+pub fn decode(bytes: &[u8]) -> Result<Image> {
+  if !bytes.starts_with(&SIGNATURE) {
+    return Err(invalid("The input is not a PNG image"));
+  }
+
+  let mut header = None;
+  let mut data = Vec::new();
+  let mut chunks = Vec::new();
+  let mut position = SIGNATURE.len();
+  while let Some(length) = bytes.get(position..position + 4) {
+    let length = u32::from_be_bytes(length.try_into().unwrap()) as usize;
+    let chunk = bytes
+      .get(position + 4..position + 8 + length)
+      .ok_or_else(|| invalid("The PNG chunk is truncated"))?;
+    let (kind, content) = chunk.split_at(4);
+    position += length + 12;
+
+    match kind {
+      b"IHDR" => header = Some(content),
+      b"IDAT" => data.extend(content),
+      b"IEND" => break,
+      _ => chunks.push((kind.try_into().unwrap(), content.to_vec())),
+    }
+  }
+
+  let header = header
+    .filter(|header| header.len() == 13)
+    .ok_or_else(|| invalid("The PNG header is missing"))?;
+  let width = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+  let height = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+  let (depth, color_type, interlace) = (header[8], header[9], header[12]);
+  let channels = match color_type {
+    0 => 1,
+    2 => 3,
+    4 => 2,
+    6 => 4,
+    _ => return Err(invalid("Palette PNG images are not supported")),
+  };
+  if depth != 8 || interlace != 0 {
+    let message = "Only non-interlaced PNG images with 8-bit channels are \
+      supported";
+    return Err(invalid(message));
+  }
+
+  let pixels = unfilter(&zlib::decompress(&data)?, width, height, channels)?;
+
+  Ok(Image {
+    width,
+    height,
+    channels,
+    alpha: color_type & 4 != 0,
+    pixels,
+    container: Container::Png { color_type, chunks },
+  })
+}
+
+/// Encodes the image unfiltered. Ancillary chunks of the decoded image
+/// are kept after the header.
+pub fn encode(
+  image: &Image,
+  color_type: u8,
+  chunks: &[([u8; 4], Vec<u8>)],
+) -> Vec<u8> {
+  let mut header = Vec::with_capacity(13);
+  header.extend((image.width as u32).to_be_bytes());
+  header.extend((image.height as u32).to_be_bytes());
+  header.extend([8, color_type, 0, 0, 0]);
+
+  let stride = image.width * image.channels;
+  let mut data = Vec::with_capacity((stride + 1) * image.height);
+  for row in image.pixels.chunks(stride.max(1)) {
+    data.push(0);
+    data.extend(row);
+  }
+
+  let mut png = SIGNATURE.to_vec();
+  write_chunk(&mut png, b"IHDR", &header);
+  for (kind, content) in chunks {
+    write_chunk(&mut png, kind, content);
+  }
+  write_chunk(&mut png, b"IDAT", &zlib::compress(&data));
+  write_chunk(&mut png, b"IEND", &[]);
+  png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], content: &[u8]) {
+  png.extend((content.len() as u32).to_be_bytes());
+  let start = png.len();
+  png.extend(kind);
+  png.extend(content);
+  let crc = Checksum::crc32(&png[start..]);
+  png.extend(crc.to_be_bytes());
+}
+
+/// Reverses the filter of every scanline.
+fn unfilter(
+  data: &[u8],
+  width: usize,
+  height: usize,
+  channels: usize,
+) -> Result<Vec<u8>> {
+  let stride = width * channels;
+  if data.len() != (stride + 1) * height {
+    return Err(invalid("The PNG image data does not match its size"));
+  }
+
+  let mut pixels = Vec::with_capacity(stride * height);
+  let mut previous = vec![0u8; stride];
+  for row in data.chunks(stride + 1) {
+    let (filter, mut line) = (row[0], row[1..].to_vec());
+    for i in 0..stride {
+      let a = if i >= channels { line[i - channels] } else { 0 };
+      let b = previous[i];
+      let c = if i >= channels {
+        previous[i - channels]
+      } else {
+        0
+      };
+      let prediction = match filter {
+        0 => 0,
+        1 => a,
+        2 => b,
+        3 => ((a as u16 + b as u16) / 2) as u8,
+        4 => paeth(a, b, c),
+        _ => return Err(invalid("The PNG filter type is invalid")),
+      };
+      line[i] = line[i].wrapping_add(prediction);
+    }
+    pixels.extend(&line);
+    previous = line;
+  }
+
+  Ok(pixels)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+  let p = a as i16 + b as i16 - c as i16;
+  let (pa, pb, pc) = (
+    (p - a as i16).abs(),
+    (p - b as i16).abs(),
+    (p - c as i16).abs(),
+  );
+  if pa <= pb && pa <= pc {
+    a
+  } else if pb <= pc {
+    b
+  } else {
+    c
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_round_trip() -> Result<()> {
+    let image = Image {
+      width: 2,
+      height: 2,
+      channels: 4,
+      alpha: true,
+      pixels: (0..16).collect(),
+      container: Container::Png {
+        color_type: 6,
+        chunks: vec![(*b"tEXt", b"Comment\0hi".to_vec())],
+      },
+    };
+    let png = image.encode();
+    assert!(png.starts_with(&SIGNATURE));
+
+    let decoded = decode(&png)?;
+    assert_eq!(decoded.pixels, image.pixels);
+    assert_eq!(decoded.container, image.container);
+    Ok(())
+  }
+
+  #[test]
+  fn test_unfilter() -> Result<()> {
+    // A sub filtered row followed by a Paeth filtered one.
+    let data = [1, 10, 5, 4, 4, 10, 2, 0];
+    assert_eq!(unfilter(&data, 3, 2, 1)?, [10, 15, 19, 20, 22, 22]);
+    assert!(unfilter(&[5, 0, 0], 2, 1, 1).is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,332 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::checksum::Checksum;
+
+/// Order in which the code length code lengths of a dynamic block are
+/// stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+  16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const LENGTH_BASE: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67,
+  83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u32; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5,
+  5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513,
+  769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u32; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11,
+  11, 12, 12, 13, 13,
+];
+
+fn invalid(message: &str) -> Error {
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Reads the bits of a deflate stream, least significant bit first.
+struct BitReader<'a> {
+  data: &'a [u8],
+  position: usize,
+  buffer: u32,
+  count: u32,
+}
+
+impl<'a> BitReader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    Self {
+      data,
+      position: 0,
+      buffer: 0,
+      count: 0,
+    }
+  }
+
+  fn bits(&mut self, n: u32) -> Result<u32> {
+    while self.count < n {
+      let byte = self
+        .data
+        .get(self.position)
+        .ok_or_else(|| invalid("The compressed data is truncated"))?;
+      self.buffer |= (*byte as u32) << self.count;
+      self.position += 1;
+      self.count += 8;
+    }
+    let value = self.buffer & ((1 << n) - 1);
+    self.buffer >>= n;
+    self.count -= n;
+    Ok(value)
+  }
+
+  /// Drops the bits left of the current byte.
+  fn align(&mut self) {
+    self.buffer = 0;
+    self.count = 0;
+  }
+}
+
+/// A canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+  counts: [u16; 16],
+  symbols: Vec<u16>,
+}
+
+impl Huffman {
+  fn new(lengths: &[u8]) -> Result<Self> {
+    let mut counts = [0u16; 16];
+    for &length in lengths {
+      counts[length as usize] += 1;
+    }
+    counts[0] = 0;
+
+    let mut left = 1i32;
+    for &count in &counts[1..] {
+      left = (left << 1) - count as i32;
+      if left < 0 {
+        return Err(invalid("The Huffman code is over-subscribed"));
+      }
+    }
+
+    let mut offsets = [0u16; 16];
+    for length in 1..15 {
+      offsets[length + 1] = offsets[length] + counts[length];
+    }
+    let mut symbols = vec![0; lengths.len()];
+    for (symbol, &length) in lengths.iter().enumerate() {
+      if length != 0 {
+        symbols[offsets[length as usize] as usize] = symbol as u16;
+        offsets[length as usize] += 1;
+      }
+    }
+
+    Ok(Self { counts, symbols })
+  }
+
+  fn decode(&self, reader: &mut BitReader) -> Result<u16> {
+    let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+    for &count in &self.counts[1..] {
+      code |= reader.bits(1)? as i32;
+      let count = count as i32;
+      if code - count < first {
+        return Ok(self.symbols[(index + code - first) as usize]);
+      }
+      index += count;
+      first = (first + count) << 1;
+      code <<= 1;
+    }
+    Err(invalid("The compressed data has an invalid Huffman code"))
+  }
+}
+
+/// Decompresses a zlib stream and verifies its Adler-32 checksum.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+  let (&method, &flags) = data
+    .first()
+    .zip(data.get(1))
+    .ok_or_else(|| invalid("The zlib header is missing"))?;
+  if method & 0x0f != 8 || (method as u16 * 256 + flags as u16) % 31 != 0 {
+    return Err(invalid("The zlib header is invalid"));
+  }
+  if flags & 0x20 != 0 {
+    return Err(invalid("Preset zlib dictionaries are not supported"));
+  }
+
+  let mut reader = BitReader::new(&data[2..]);
+  let inflated = inflate(&mut reader)?;
+  let trailer = data
+    .get(2 + reader.position..2 + reader.position + 4)
+    .ok_or_else(|| invalid("The zlib checksum is missing"))?;
+  if u32::from_be_bytes(trailer.try_into().unwrap())
+    != Checksum::adler32(&inflated)
+  {
+    return Err(invalid("The zlib checksum does not match"));
+  }
+
+  Ok(inflated)
+}
+
+/// Wraps the data in a zlib stream of stored deflate blocks. The data is
+/// not compressed, which keeps the encoder trivial and every byte of an
+/// image where it was.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+  let mut stream = vec![0x78, 0x01];
+  let mut blocks = data.chunks(u16::MAX as usize).peekable();
+  if blocks.peek().is_none() {
+    stream.extend([1, 0, 0, 0xff, 0xff]);
+  }
+  while let Some(block) = blocks.next() {
+    let length = block.len() as u16;
+    stream.push(blocks.peek().is_none() as u8);
+    stream.extend(length.to_le_bytes());
+    stream.extend((!length).to_le_bytes());
+    stream.extend(block);
+  }
+  stream.extend(Checksum::adler32(data).to_be_bytes());
+  stream
+}
+
+fn inflate(reader: &mut BitReader) -> Result<Vec<u8>> {
+  let mut output = Vec::new();
+
+  loop {
+    let last = reader.bits(1)? == 1;
+    match reader.bits(2)? {
+      0 => stored(reader, &mut output)?,
+      1 => {
+        let mut lengths = [8; 288];
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        let literals = Huffman::new(&lengths)?;
+        let distances = Huffman::new(&[5; 30])?;
+        codes(reader, &mut output, &literals, &distances)?;
+      }
+      2 => {
+        let (literals, distances) = dynamic(reader)?;
+        codes(reader, &mut output, &literals, &distances)?;
+      }
+      _ => return Err(invalid("The deflate block type is invalid")),
+    }
+    if last {
+      return Ok(output);
+    }
+  }
+}
+
+fn stored(reader: &mut BitReader, output: &mut Vec<u8>) -> Result<()> {
+  reader.align();
+  let length = reader.bits(16)?;
+  if length ^ 0xffff != reader.bits(16)? {
+    return Err(invalid("The stored block length is corrupted"));
+  }
+  let start = reader.position;
+  let block = reader
+    .data
+    .get(start..start + length as usize)
+    .ok_or_else(|| invalid("The compressed data is truncated"))?;
+  output.extend(block);
+  reader.position += length as usize;
+  Ok(())
+}
+
+fn dynamic(reader: &mut BitReader) -> Result<(Huffman, Huffman)> {
+  let literal_count = reader.bits(5)? as usize + 257;
+  let distance_count = reader.bits(5)? as usize + 1;
+  let code_count = reader.bits(4)? as usize + 4;
+
+  let mut code_lengths = [0; 19];
+  for &index in &CODE_LENGTH_ORDER[..code_count] {
+    code_lengths[index] = reader.bits(3)? as u8;
+  }
+  let code = Huffman::new(&code_lengths)?;
+
+  let mut lengths = Vec::with_capacity(literal_count + distance_count);
+  while lengths.len() < literal_count + distance_count {
+    let (length, repeat) = match code.decode(reader)? {
+      symbol @ 0..=15 => (symbol as u8, 1),
+      16 => {
+        let previous = *lengths
+          .last()
+          .ok_or_else(|| invalid("A length repeat has no previous length"))?;
+        (previous, 3 + reader.bits(2)?)
+      }
+      17 => (0, 3 + reader.bits(3)?),
+      _ => (0, 11 + reader.bits(7)?),
+    };
+    lengths.extend(std::iter::repeat_n(length, repeat as usize));
+  }
+  if lengths.len() > literal_count + distance_count {
+    return Err(invalid("The code lengths exceed the block header"));
+  }
+
+  let literals = Huffman::new(&lengths[..literal_count])?;
+  let distances = Huffman::new(&lengths[literal_count..])?;
+  Ok((literals, distances))
+}
+
+fn codes(
+  reader: &mut BitReader,
+  output: &mut Vec<u8>,
+  literals: &Huffman,
+  distances: &Huffman,
+) -> Result<()> {
+  loop {
+    let symbol = literals.decode(reader)? as usize;
+    if symbol < 256 {
+      output.push(symbol as u8);
+      continue;
+    }
+    if symbol == 256 {
+      return Ok(());
+    }
+
+    let index = symbol - 257;
+    if index >= LENGTH_BASE.len() {
+      return Err(invalid("The length code is invalid"));
+    }
+    let length =
+      LENGTH_BASE[index] as usize + reader.bits(LENGTH_EXTRA[index])? as usize;
+
+    let index = distances.decode(reader)? as usize;
+    if index >= DISTANCE_BASE.len() {
+      return Err(invalid("The distance code is invalid"));
+    }
+    let distance = DISTANCE_BASE[index] as usize
+      + reader.bits(DISTANCE_EXTRA[index])? as usize;
+    if distance > output.len() {
+      return Err(invalid("The distance reaches before the data"));
+    }
+
+    let start = output.len() - distance;
+    for i in 0..length {
+      output.push(output[start + i]);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+      .step_by(2)
+      .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_fixed_huffman() -> Result<()> {
+    let stream = unhex("789ccb48cdc9c957c8402701680308b1");
+    assert_eq!(decompress(&stream)?, b"hello hello hello hello");
+    Ok(())
+  }
+
+  #[test]
+  fn test_dynamic_huffman() -> Result<()> {
+    let stream = unhex(
+      "78da1dcc510ac3300c04d1abecd5145b444b5cb9482aa6397d4c7e67e0b1b02421\
+       3882a715da1c1d5dfea043bec101f18e32451bb35d89a5a1c80a5ef4730f46a93a\
+       163d6b3af2c3321813cd36e1bffb1edab756f3ad47a8643df4272b95",
+    );
+    let text = "it was a bright cold day in april and the clocks were \
+      striking thirteen winston smith his chin nuzzled into his breast";
+    assert_eq!(decompress(&stream)?, text.as_bytes());
+    Ok(())
+  }
+
+  #[test]
+  fn test_stored_round_trip() -> Result<()> {
+    let data: Vec<u8> = (0..70000).map(|i| (i % 251) as u8).collect();
+    assert_eq!(decompress(&compress(&data))?, data);
+    assert_eq!(decompress(&compress(&[]))?, b"");
+
+    let mut corrupted = compress(b"data");
+    corrupted[8] ^= 1;
+    assert!(decompress(&corrupted).is_err());
+    Ok(())
+  }
+}
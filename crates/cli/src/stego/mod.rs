@@ -0,0 +1,31 @@
+pub mod image;
+pub mod whitespace;
+
+/// The bits of the bytes, most significant bit first.
+pub fn bits(bytes: &[u8]) -> impl Iterator<Item = bool> + '_ {
+  bytes
+    .iter()
+    .flat_map(|byte| (0..8).rev().map(move |bit| byte >> bit & 1 == 1))
+}
+
+/// Packs bits into bytes, most significant bit first. Bits that do not
+/// fill a whole byte are dropped.
+pub fn bytes<I: IntoIterator<Item = bool>>(bits: I) -> Vec<u8> {
+  let bits: Vec<bool> = bits.into_iter().collect();
+  bits
+    .chunks_exact(8)
+    .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | bit as u8))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_bits_round_trip() {
+    let bits: Vec<bool> = bits(b"A").collect();
+    assert_eq!(bits, [false, true, false, false, false, false, false, true]);
+    assert_eq!(bytes(bits.into_iter().chain([true])), b"A");
+  }
+}
@@ -0,0 +1,232 @@
+use clap::ValueEnum;
+
+use std::io::{Read, Result, Write};
+
+use crate::StegoCommand;
+
+/// Zero-width space, encoding a zero bit.
+const ZERO: char = '\u{200b}';
+/// Zero-width non-joiner, encoding a one bit.
+const ONE: char = '\u{200c}';
+/// Invisible characters that have no business in most cover texts.
+const ZERO_WIDTH: [char; 5] = [ZERO, ONE, '\u{200d}', '\u{2060}', '\u{feff}'];
+
+/// Where the bits are hidden in the cover text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WhitespaceMethod {
+  /// A byte per line as eight trailing spaces and tabs, space for 0.
+  #[default]
+  Trailing,
+  /// Zero-width spaces and non-joiners after the first character.
+  ZeroWidth,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WhitespaceConfig {
+  pub method: WhitespaceMethod,
+  /// Message hidden in the cover text. The input is decoded without one.
+  pub message: Option<String>,
+  /// Report suspicious whitespace instead of decoding.
+  pub detect: bool,
+}
+
+impl WhitespaceConfig {
+  pub fn new(
+    method: WhitespaceMethod,
+    message: Option<String>,
+    detect: bool,
+  ) -> Self {
+    Self {
+      method,
+      message,
+      detect,
+    }
+  }
+}
+
+impl From<&StegoCommand> for WhitespaceConfig {
+  fn from(value: &StegoCommand) -> Self {
+    match value {
+      StegoCommand::Whitespace {
+        method,
+        message,
+        detect,
+        ..
+      } => Self::new(*method, message.clone(), *detect),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// Whitespace and invisible characters found in a text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WhitespaceReport {
+  pub lines: usize,
+  /// Lines that end in spaces or tabs.
+  pub trailing_lines: usize,
+  /// Lines whose trailing whitespace mixes spaces and tabs.
+  pub mixed_lines: usize,
+  /// Zero-width characters, which do not show up in an editor.
+  pub zero_width: usize,
+}
+
+impl WhitespaceReport {
+  /// Whether the text probably hides something. Mixed trailing
+  /// whitespace hardly ever comes from an editor, and zero-width
+  /// characters are rare outside of emoji sequences.
+  pub fn suspicious(&self) -> bool {
+    self.mixed_lines > 0 || self.zero_width > 0
+  }
+}
+
+pub struct WhitespaceStego;
+
+impl WhitespaceStego {
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: WhitespaceConfig,
+  ) -> Result<()> {
+    let mut cover = String::new();
+    input.read_to_string(&mut cover)?;
+
+    if config.detect {
+      let report = Self::detect(&cover);
+      writeln!(output, "lines: {}", report.lines)?;
+      writeln!(output, "trailing whitespace: {}", report.trailing_lines)?;
+      writeln!(output, "mixed spaces and tabs: {}", report.mixed_lines)?;
+      writeln!(output, "zero-width characters: {}", report.zero_width)?;
+      let verdict = match report.suspicious() {
+        true => "suspicious",
+        false => "clean",
+      };
+      return writeln!(output, "verdict: {verdict}");
+    }
+
+    match &config.message {
+      Some(message) => {
+        let text = Self::encode(&cover, message.as_bytes(), config.method);
+        write!(output, "{text}")
+      }
+      None => output.write_all(&Self::decode(&cover, config.method)),
+    }
+  }
+
+  pub fn encode(
+    cover: &str,
+    message: &[u8],
+    method: WhitespaceMethod,
+  ) -> String {
+    match method {
+      WhitespaceMethod::Trailing => {
+        let mut lines: Vec<String> = cover
+          .lines()
+          .map(|line| line.trim_end_matches([' ', '\t']).to_string())
+          .collect();
+        lines.resize(lines.len().max(message.len()), String::new());
+
+        for (line, &byte) in lines.iter_mut().zip(message) {
+          line.extend(super::bits(&[byte]).map(|bit| match bit {
+            true => '\t',
+            false => ' ',
+          }));
+        }
+
+        let mut text = lines.join("\n");
+        if cover.ends_with('\n') || lines.len() > cover.lines().count() {
+          text.push('\n');
+        }
+        text
+      }
+      WhitespaceMethod::ZeroWidth => {
+        let hidden: String = super::bits(message)
+          .map(|bit| if bit { ONE } else { ZERO })
+          .collect();
+        let split = cover.chars().next().map_or(0, char::len_utf8);
+        format!("{}{hidden}{}", &cover[..split], &cover[split..])
+      }
+    }
+  }
+
+  pub fn decode(text: &str, method: WhitespaceMethod) -> Vec<u8> {
+    match method {
+      WhitespaceMethod::Trailing => {
+        let bits = text.lines().flat_map(|line| {
+          let content = line.trim_end_matches([' ', '\t']);
+          line[content.len()..].chars().map(|c| c == '\t')
+        });
+        super::bytes(bits)
+      }
+      WhitespaceMethod::ZeroWidth => super::bytes(
+        text
+          .chars()
+          .filter(|&c| c == ZERO || c == ONE)
+          .map(|c| c == ONE),
+      ),
+    }
+  }
+
+  pub fn detect(text: &str) -> WhitespaceReport {
+    let mut report = WhitespaceReport::default();
+
+    for line in text.lines() {
+      report.lines += 1;
+      let content = line.trim_end_matches([' ', '\t']);
+      let trailing = &line[content.len()..];
+      if !trailing.is_empty() {
+        report.trailing_lines += 1;
+      }
+      if trailing.contains(' ') && trailing.contains('\t') {
+        report.mixed_lines += 1;
+      }
+    }
+    report.zero_width = text.chars().filter(|c| ZERO_WIDTH.contains(c)).count();
+
+    report
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const COVER: &str = "Roses are red,\nviolets are blue.  \n";
+
+  #[test]
+  fn test_trailing_round_trip() {
+    let text =
+      WhitespaceStego::encode(COVER, b"Hi!", WhitespaceMethod::Trailing);
+    assert_eq!(text.lines().count(), 3);
+    assert!(text.starts_with("Roses are red, \t  \t   \n"));
+    assert_eq!(
+      WhitespaceStego::decode(&text, WhitespaceMethod::Trailing),
+      b"Hi!"
+    );
+  }
+
+  #[test]
+  fn test_zero_width_round_trip() {
+    let text =
+      WhitespaceStego::encode(COVER, b"Hi", WhitespaceMethod::ZeroWidth);
+    assert_eq!(text.chars().count(), COVER.chars().count() + 16);
+    assert_eq!(text.replace([ZERO, ONE], ""), COVER,);
+    assert_eq!(
+      WhitespaceStego::decode(&text, WhitespaceMethod::ZeroWidth),
+      b"Hi"
+    );
+  }
+
+  #[test]
+  fn test_detect() {
+    let report = WhitespaceStego::detect(COVER);
+    assert_eq!(report.trailing_lines, 1);
+    assert!(!report.suspicious());
+
+    let text = WhitespaceStego::encode(COVER, b"x", WhitespaceMethod::Trailing);
+    assert!(WhitespaceStego::detect(&text).suspicious());
+
+    let text =
+      WhitespaceStego::encode(COVER, b"x", WhitespaceMethod::ZeroWidth);
+    assert_eq!(WhitespaceStego::detect(&text).zero_width, 8);
+  }
+}
@@ -0,0 +1,185 @@
+use clap::ValueEnum;
+
+use std::io::{Read, Result, Write};
+
+use crate::Command;
+
+/// Encoding of the characters of the strings to extract.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StringEncoding {
+  /// One byte per character.
+  #[default]
+  Ascii,
+  /// Two bytes per character, least significant byte first.
+  Utf16le,
+  /// Two bytes per character, most significant byte first.
+  Utf16be,
+}
+
+impl StringEncoding {
+  fn width(&self) -> usize {
+    match self {
+      StringEncoding::Ascii => 1,
+      StringEncoding::Utf16le | StringEncoding::Utf16be => 2,
+    }
+  }
+
+  /// The printable ASCII character encoded by the unit, if any.
+  fn decode(&self, unit: &[u8]) -> Option<u8> {
+    let byte = match (self, unit) {
+      (StringEncoding::Ascii, [byte]) => *byte,
+      (StringEncoding::Utf16le, [byte, 0]) => *byte,
+      (StringEncoding::Utf16be, [0, byte]) => *byte,
+      _ => return None,
+    };
+    (byte.is_ascii_graphic() || byte == b' ' || byte == b'\t').then_some(byte)
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct StringsConfig {
+  /// Shortest run of characters that is printed.
+  pub min_length: usize,
+  pub encoding: StringEncoding,
+  /// Print the byte offset of every string in hex.
+  pub offsets: bool,
+}
+
+impl StringsConfig {
+  pub fn new(
+    min_length: usize,
+    encoding: StringEncoding,
+    offsets: bool,
+  ) -> Self {
+    Self {
+      min_length,
+      encoding,
+      offsets,
+    }
+  }
+}
+
+impl Default for StringsConfig {
+  fn default() -> Self {
+    Self::new(4, StringEncoding::default(), false)
+  }
+}
+
+impl From<&Command> for StringsConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Strings {
+        min_length,
+        encoding,
+        offsets,
+        ..
+      } => Self::new(*min_length, *encoding, *offsets),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Strings;
+
+impl Strings {
+  /// Writes every run of printable characters of the input, one per line.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: StringsConfig,
+  ) -> Result<()> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+
+    for (offset, string) in Self::extract(&content, &config) {
+      if config.offsets {
+        write!(output, "{offset:8x} ")?;
+      }
+      writeln!(output, "{string}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Finds the runs of at least `min_length` printable characters and
+  /// the byte offsets they start at.
+  ///
+  /// Wide encodings are searched at both even and odd offsets, since a
+  /// string does not have to be aligned to two bytes in binary data.
+  pub fn extract(bytes: &[u8], config: &StringsConfig) -> Vec<(usize, String)> {
+    let width = config.encoding.width();
+    let mut strings = Vec::new();
+
+    for alignment in 0..width.min(bytes.len()) {
+      let mut start = alignment;
+      let mut run = String::new();
+      let units = bytes[alignment..].chunks(width);
+
+      for (index, unit) in units.enumerate() {
+        let offset = alignment + index * width;
+        match config.encoding.decode(unit) {
+          Some(byte) => {
+            if run.is_empty() {
+              start = offset;
+            }
+            run.push(byte as char);
+          }
+          None => Self::finish(&mut strings, &mut run, start, config),
+        }
+      }
+      Self::finish(&mut strings, &mut run, start, config);
+    }
+
+    strings.sort_by_key(|&(offset, _)| offset);
+    strings
+  }
+
+  fn finish(
+    strings: &mut Vec<(usize, String)>,
+    run: &mut String,
+    start: usize,
+    config: &StringsConfig,
+  ) {
+    let run = std::mem::take(run);
+    if run.len() >= config.min_length.max(1) {
+      strings.push((start, run));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_extract_ascii() {
+    let bytes = b"\x00\x01flag{xor}\xff\xfeab\x00secret";
+    let config = StringsConfig::default();
+    let strings = Strings::extract(bytes, &config);
+    assert_eq!(
+      strings,
+      vec![(2, "flag{xor}".to_string()), (16, "secret".to_string())]
+    );
+  }
+
+  #[test]
+  fn test_extract_utf16() {
+    let bytes = b"\xffk\x00e\x00y\x00s\x00\x00\x00";
+    let config = StringsConfig::new(4, StringEncoding::Utf16le, false);
+    assert_eq!(Strings::extract(bytes, &config), vec![(1, "keys".into())]);
+
+    let bytes = b"\x00k\x00e\x00y\x00s";
+    let config = StringsConfig::new(4, StringEncoding::Utf16be, false);
+    assert_eq!(Strings::extract(bytes, &config), vec![(0, "keys".into())]);
+  }
+
+  #[test]
+  fn test_offsets() -> Result<()> {
+    let mut output = Vec::new();
+    let config = StringsConfig::new(3, StringEncoding::Ascii, true);
+    let mut input = b"\x00\x00\x00abc\x00de".as_slice();
+    Strings::execute(&mut input, &mut output, config)?;
+    assert_eq!(output, b"       3 abc\n");
+    Ok(())
+  }
+}
@@ -0,0 +1,123 @@
+pub mod spacing;
+
+use std::io::{Result, Write};
+use std::num::NonZeroUsize;
+
+/// How a plaintext is normalized before it is encrypted and how the
+/// ciphertext is laid out, for the conventional formats of exercises.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+  /// Remove everything but letters, including spaces and line breaks.
+  pub strip_non_alpha: bool,
+  pub uppercase: bool,
+  /// Number of symbols per block of the ciphertext.
+  pub group: Option<NonZeroUsize>,
+}
+
+impl TextFormat {
+  pub fn new(
+    strip_non_alpha: bool,
+    uppercase: bool,
+    group: Option<NonZeroUsize>,
+  ) -> Self {
+    Self {
+      strip_non_alpha,
+      uppercase,
+      group,
+    }
+  }
+
+  /// Whether [`TextFormat::normalize`] changes a plaintext.
+  pub fn normalizes(&self) -> bool {
+    self.strip_non_alpha || self.uppercase
+  }
+
+  pub fn normalize(&self, text: &str) -> String {
+    let text: String = match self.strip_non_alpha {
+      true => text.chars().filter(|c| c.is_alphabetic()).collect(),
+      false => text.to_string(),
+    };
+    match self.uppercase {
+      true => text.to_uppercase(),
+      false => text,
+    }
+  }
+}
+
+/// Writes the symbols of a text in blocks separated by spaces, like
+/// `ATTAC KATDA WN`.
+///
+/// Spaces and tabs of the text are dropped, while every line break
+/// starts a new block. Multibyte UTF-8 characters count as one symbol.
+pub struct GroupWriter<W: Write> {
+  inner: W,
+  size: usize,
+  /// Symbols in the current block.
+  count: usize,
+}
+
+impl<W: Write> GroupWriter<W> {
+  pub fn new(inner: W, size: NonZeroUsize) -> Self {
+    Self {
+      inner,
+      size: size.get(),
+      count: 0,
+    }
+  }
+}
+
+impl<W: Write> Write for GroupWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    let mut grouped = Vec::with_capacity(buf.len() + buf.len() / self.size);
+
+    for &byte in buf {
+      match byte {
+        b'\n' => {
+          grouped.push(byte);
+          self.count = 0;
+        }
+        b' ' | b'\t' | b'\r' => {}
+        // Continuation bytes belong to the symbol before them.
+        _ if byte & 0xC0 == 0x80 => grouped.push(byte),
+        _ => {
+          if self.count == self.size {
+            grouped.push(b' ');
+            self.count = 0;
+          }
+          self.count += 1;
+          grouped.push(byte);
+        }
+      }
+    }
+
+    self.inner.write_all(&grouped)?;
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    self.inner.flush()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalize() {
+    let format = TextFormat::new(true, true, None);
+    assert_eq!(format.normalize("Attack at dawn!\n"), "ATTACKATDAWN");
+    assert!(!TextFormat::default().normalizes());
+  }
+
+  #[test]
+  fn test_group_writer() -> Result<()> {
+    let mut output = Vec::new();
+    let mut writer =
+      GroupWriter::new(&mut output, NonZeroUsize::new(5).unwrap());
+    write!(writer, "ATTACK AT")?;
+    write!(writer, "DAWN\nЩИФРЫ")?;
+    assert_eq!(String::from_utf8(output).unwrap(), "ATTAC KATDA WN\nЩИФРЫ");
+    Ok(())
+  }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::io::{Result, Write};
+use std::sync::LazyLock;
+
+/// Common English words, most frequent first.
+const WORDS: &str = include_str!("words.txt");
+
+/// Longest word that is looked up while splitting a run of letters.
+const MAX_WORD_LENGTH: usize = 20;
+
+/// Cost of every word, the negative log of its probability if word
+/// frequencies follow Zipf's law.
+static WORD_COSTS: LazyLock<HashMap<&'static str, f32>> = LazyLock::new(|| {
+  let mut costs = HashMap::new();
+  for (rank, word) in WORDS.split_whitespace().enumerate() {
+    costs.entry(word).or_insert(((rank + 1) as f32).ln() + 1.0);
+  }
+  costs
+});
+
+/// Splits text that was written without spaces back into words.
+pub struct Spacing;
+
+impl Spacing {
+  /// Inserts spaces between the most likely words of every line
+  /// without whitespace, like `attack at dawn` for `attackatdawn`.
+  ///
+  /// Lines with whitespace are returned unchanged, as they already have
+  /// their words. Letters that form no known word stay together.
+  pub fn restore(text: &str) -> String {
+    text
+      .split('\n')
+      .map(|line| match line.contains(char::is_whitespace) {
+        true => line.to_string(),
+        false => Self::restore_line(line),
+      })
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  fn restore_line(line: &str) -> String {
+    let mut restored = String::new();
+    let mut letters = String::new();
+
+    for c in line.chars() {
+      if c.is_ascii_alphabetic() {
+        if letters.is_empty()
+          && restored.ends_with(|c: char| ",.;:!?".contains(c))
+        {
+          restored.push(' ');
+        }
+        letters.push(c);
+      } else {
+        restored.push_str(&Self::segment(&letters).join(" "));
+        letters.clear();
+        restored.push(c);
+      }
+    }
+
+    restored.push_str(&Self::segment(&letters).join(" "));
+    restored
+  }
+
+  /// The words of a run of letters with the lowest total cost.
+  pub fn segment(letters: &str) -> Vec<&str> {
+    let lower = letters.to_ascii_lowercase();
+    let length = lower.len();
+
+    // best[i] is the cost of the best split of the first i letters and
+    // the start of its last word.
+    let mut best = vec![(0.0f32, 0); length + 1];
+    for end in 1..=length {
+      best[end] = (end.saturating_sub(MAX_WORD_LENGTH)..end)
+        .map(|start| (best[start].0 + Self::cost(&lower[start..end]), start))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap_or((0.0, 0));
+    }
+
+    let mut words = Vec::new();
+    let mut end = length;
+    while end > 0 {
+      let start = best[end].1;
+      words.push(&letters[start..end]);
+      end = start;
+    }
+    words.reverse();
+    words
+  }
+
+  /// Whether the lowercase word is one of the common English words.
+  pub fn is_word(word: &str) -> bool {
+    WORD_COSTS.contains_key(word)
+  }
+
+  /// The common English words, most frequent first.
+  pub fn words() -> impl Iterator<Item = &'static str> {
+    WORDS.split_whitespace()
+  }
+
+  /// Unknown words cost more the longer they are, but less than
+  /// splitting them into single unknown letters.
+  fn cost(word: &str) -> f32 {
+    WORD_COSTS
+      .get(word)
+      .copied()
+      .unwrap_or(10.0 + 3.0 * word.len() as f32)
+  }
+}
+
+/// Restores the spacing of every line written through it with
+/// [`Spacing::restore`]. An unfinished last line is written on drop.
+pub struct SpacingWriter<W: Write> {
+  inner: W,
+  line: Vec<u8>,
+}
+
+impl<W: Write> SpacingWriter<W> {
+  pub fn new(inner: W) -> Self {
+    Self {
+      inner,
+      line: Vec::new(),
+    }
+  }
+
+  fn write_line(&mut self) -> Result<()> {
+    let line = String::from_utf8_lossy(&self.line);
+    self.inner.write_all(Spacing::restore(&line).as_bytes())?;
+    self.line.clear();
+    Ok(())
+  }
+}
+
+impl<W: Write> Write for SpacingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    for &byte in buf {
+      self.line.push(byte);
+      if byte == b'\n' {
+        self.write_line()?;
+      }
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    self.inner.flush()
+  }
+}
+
+impl<W: Write> Drop for SpacingWriter<W> {
+  fn drop(&mut self) {
+    if !self.line.is_empty() {
+      let _ = self.write_line();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_restore() {
+    assert_eq!(Spacing::restore("ATTACKATDAWN"), "ATTACK AT DAWN");
+    assert_eq!(
+      Spacing::restore("meetmeatthebridge,bringthekey.\nkey: LEMON"),
+      "meet me at the bridge, bring the key.\nkey: LEMON"
+    );
+    assert_eq!(Spacing::segment("sendxqzhelp"), ["send", "xqz", "help"]);
+  }
+
+  #[test]
+  fn test_spacing_writer() -> Result<()> {
+    let mut output = Vec::new();
+    {
+      let mut writer = SpacingWriter::new(&mut output);
+      write!(writer, "theenemyis")?;
+      write!(writer, "coming\nholdthegate")?;
+    }
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, "the enemy is coming\nhold the gate");
+    Ok(())
+  }
+}
@@ -0,0 +1,292 @@
+pub mod rainbow;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use clap::ValueEnum;
+
+use crate::{hex::Hex, Command};
+
+/// Number of mixing rounds of [`Compression::Mix`].
+const MIX_ROUNDS: usize = 4;
+
+/// Compression function combining a chaining value with a message block
+/// of the same size.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+  /// XOR of the block into the rotated chaining value. Collisions are
+  /// trivial to find, which makes it a good first exercise.
+  Xor,
+  /// Bytewise addition of the block with a carry through the state.
+  Add,
+  /// Rounds of additions, rotations and XORs between neighbouring bytes.
+  #[default]
+  Mix,
+}
+
+impl Compression {
+  pub fn compress(&self, state: &[u8], block: &[u8]) -> Vec<u8> {
+    match self {
+      Compression::Xor => {
+        let mut state = state.to_vec();
+        state.rotate_left(1);
+        state.iter().zip(block).map(|(s, m)| s ^ m).collect()
+      }
+      Compression::Add => {
+        let mut carry = 0u16;
+        state
+          .iter()
+          .zip(block)
+          .map(|(&s, &m)| {
+            let sum = s as u16 + m as u16 + carry;
+            carry = sum >> 8;
+            sum as u8
+          })
+          .collect()
+      }
+      Compression::Mix => {
+        let size = state.len();
+        let mut mixed = state.to_vec();
+
+        for round in 0..MIX_ROUNDS {
+          for i in 0..size {
+            let previous = mixed[(i + size - 1) % size];
+            let byte = mixed[i].wrapping_add(block[(i + round) % size]);
+            mixed[i] =
+              (byte ^ previous).rotate_left(3).wrapping_add(round as u8);
+          }
+        }
+
+        mixed.iter().zip(state).map(|(m, s)| m ^ s).collect()
+      }
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ToyHashConfig {
+  pub block_size: usize,
+  pub compression: Compression,
+  /// Initial chaining value as hex, a fixed one derived from the block
+  /// size if not given.
+  pub iv: Option<String>,
+  pub trace: bool,
+}
+
+impl ToyHashConfig {
+  pub fn new(
+    block_size: usize,
+    compression: Compression,
+    iv: Option<String>,
+    trace: bool,
+  ) -> Self {
+    Self {
+      block_size,
+      compression,
+      iv,
+      trace,
+    }
+  }
+}
+
+impl Default for ToyHashConfig {
+  fn default() -> Self {
+    Self::new(8, Compression::default(), None, false)
+  }
+}
+
+impl From<&Command> for ToyHashConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::ToyHash {
+        block_size,
+        compression,
+        iv,
+        trace,
+        ..
+      } => Self::new(*block_size, *compression, iv.clone(), *trace),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The chaining value after compressing a block.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ToyHashStep {
+  pub block: Vec<u8>,
+  pub state: Vec<u8>,
+}
+
+/// An educational hash following the Merkle–Damgård construction.
+///
+/// The message is padded with `0x80`, zeros and its length in bits as a
+/// big-endian 64-bit number up to a multiple of the block size. Starting
+/// from the initial value, every block is compressed into the chaining
+/// value and the last chaining value is the digest, which is as long as
+/// a block.
+pub struct ToyHash;
+
+impl ToyHash {
+  /// Writes the hex digest of the input, preceded by the chaining value
+  /// after every block when tracing.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ToyHashConfig,
+  ) -> Result<()> {
+    let mut message = Vec::new();
+    input.read_to_end(&mut message)?;
+
+    let steps = Self::steps(&message, &config)?;
+
+    if config.trace {
+      let iv = Self::iv(&config)?;
+      writeln!(output, "iv: {}", Hex::new(iv))?;
+      for (index, step) in steps.iter().enumerate() {
+        let block = Hex::new(step.block.clone());
+        let state = Hex::new(step.state.clone());
+        writeln!(output, "block {index}: {block} -> {state}")?;
+      }
+    }
+
+    let digest = steps.last().map(|step| step.state.clone());
+    writeln!(output, "{}", Hex::new(digest.unwrap_or_default()))
+  }
+
+  pub fn hash(message: &[u8], config: &ToyHashConfig) -> Result<Vec<u8>> {
+    let mut steps = Self::steps(message, config)?;
+    let digest = steps.pop().map(|step| step.state);
+    Ok(digest.unwrap_or_default())
+  }
+
+  /// Compresses every padded block and keeps the chaining values.
+  pub fn steps(
+    message: &[u8],
+    config: &ToyHashConfig,
+  ) -> Result<Vec<ToyHashStep>> {
+    let mut state = Self::iv(config)?;
+
+    let steps = Self::pad(message, config.block_size)
+      .chunks(config.block_size)
+      .map(|block| {
+        state = config.compression.compress(&state, block);
+        ToyHashStep {
+          block: block.to_vec(),
+          state: state.clone(),
+        }
+      })
+      .collect();
+
+    Ok(steps)
+  }
+
+  /// Merkle–Damgård strengthening, which makes the padding of different
+  /// messages differ.
+  pub fn pad(message: &[u8], block_size: usize) -> Vec<u8> {
+    let bits = (message.len() as u64).wrapping_mul(8);
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+
+    while (padded.len() + 8) % block_size != 0 {
+      padded.push(0);
+    }
+
+    padded.extend(bits.to_be_bytes());
+    padded
+  }
+
+  fn iv(config: &ToyHashConfig) -> Result<Vec<u8>> {
+    if config.block_size == 0 {
+      let message = "The block size must be at least one byte";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let iv = match &config.iv {
+      Some(iv) => Some(Hex::parse_hex(iv.trim())?.bytes),
+      None => None,
+    };
+
+    match iv {
+      Some(iv) if iv.len() == config.block_size => Ok(iv),
+      Some(iv) => {
+        let message = format!(
+          "The initial value has {} bytes instead of the block size {}",
+          iv.len(),
+          config.block_size
+        );
+        Err(Error::new(ErrorKind::InvalidInput, message))
+      }
+      None => Ok(
+        (0..config.block_size)
+          .map(|i| (i as u8).wrapping_mul(0x9d) ^ 0x5a)
+          .collect(),
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn config(block_size: usize, compression: Compression) -> ToyHashConfig {
+    ToyHashConfig::new(block_size, compression, None, false)
+  }
+
+  #[test]
+  fn test_pad() {
+    let padded = ToyHash::pad(b"abc", 8);
+    assert_eq!(padded.len(), 16);
+    assert_eq!(&padded[..4], b"abc\x80");
+    assert_eq!(&padded[8..], 24u64.to_be_bytes());
+    assert_eq!(ToyHash::pad(b"", 3).len(), 9);
+  }
+
+  #[test]
+  fn test_hash_depends_on_message() -> Result<()> {
+    for compression in [Compression::Xor, Compression::Add, Compression::Mix] {
+      let config = config(8, compression);
+      let alpha = ToyHash::hash(b"attack at dawn", &config)?;
+      let beta = ToyHash::hash(b"attack at dusk", &config)?;
+      assert_eq!(alpha.len(), 8);
+      assert_eq!(alpha, ToyHash::hash(b"attack at dawn", &config)?);
+      assert_ne!(alpha, beta);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_xor_collision() -> Result<()> {
+    let config = config(4, Compression::Xor);
+    let alpha = ToyHash::hash(b"abcdwxyz", &config)?;
+    let mut swapped = b"abcdwxyz".to_vec();
+    swapped[0] ^= 1;
+    swapped[7] ^= 1;
+    assert_eq!(alpha, ToyHash::hash(&swapped, &config)?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_trace() -> Result<()> {
+    let mut output = Vec::new();
+    let config = ToyHashConfig::new(8, Compression::Mix, None, true);
+    ToyHash::execute(&mut &b"hello world"[..], &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("iv: "));
+    assert!(lines[3].ends_with(lines[4]));
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_iv() {
+    let config = ToyHashConfig::new(
+      4,
+      Compression::Mix,
+      Some(String::from("000000")),
+      false,
+    );
+    assert!(ToyHash::hash(b"", &config).is_err());
+  }
+}
@@ -0,0 +1,240 @@
+use std::{
+  collections::HashMap,
+  fs,
+  io::{Error, ErrorKind, Read, Result, Write},
+  path::PathBuf,
+};
+
+use crate::{hex::Hex, Command};
+
+use super::{ToyHash, ToyHashConfig};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RainbowConfig {
+  pub dictionary: PathBuf,
+  /// Number of hashes per chain, where 1 stores every word.
+  pub chain_length: usize,
+  pub hash: ToyHashConfig,
+  pub stats: bool,
+}
+
+impl RainbowConfig {
+  pub fn new(
+    dictionary: PathBuf,
+    chain_length: usize,
+    hash: ToyHashConfig,
+    stats: bool,
+  ) -> Self {
+    Self {
+      dictionary,
+      chain_length,
+      hash,
+      stats,
+    }
+  }
+}
+
+impl Default for RainbowConfig {
+  fn default() -> Self {
+    Self::new(PathBuf::new(), 100, ToyHashConfig::default(), false)
+  }
+}
+
+impl From<&Command> for RainbowConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Rainbow {
+        dictionary,
+        chain_length,
+        block_size,
+        compression,
+        stats,
+        ..
+      } => {
+        let hash = ToyHashConfig::new(*block_size, *compression, None, false);
+        Self::new(dictionary.clone(), *chain_length, hash, *stats)
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+/// A rainbow table over a dictionary of candidate plaintexts.
+///
+/// A chain alternates between hashing a word and reducing the hash to
+/// another word of the dictionary, with a different reduction at every
+/// step. Only the first and the final word of each chain are stored, so a
+/// table needs about `1 / chain_length` of the memory of a full lookup
+/// table, at the cost of rehashing up to a whole chain per lookup.
+/// Chains that merge cover the same words twice, which lowers the
+/// coverage of the table.
+pub struct RainbowTable {
+  words: Vec<String>,
+  chain_length: usize,
+  hash: ToyHashConfig,
+  /// First words of the chains by the word their last hash reduces to,
+  /// both as indices.
+  chains: HashMap<usize, Vec<usize>>,
+}
+
+impl RainbowTable {
+  /// Builds chains starting at every `chain_length`-th word.
+  pub fn new(
+    words: Vec<String>,
+    chain_length: usize,
+    hash: ToyHashConfig,
+  ) -> Result<Self> {
+    if words.is_empty() || chain_length == 0 {
+      let message = "A rainbow table needs words and a chain length";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut table = Self {
+      words,
+      chain_length,
+      hash,
+      chains: HashMap::new(),
+    };
+
+    for start in (0..table.words.len()).step_by(chain_length) {
+      let mut word = start;
+      for step in 0..chain_length {
+        word = table.reduce(&table.digest(word)?, step);
+      }
+      table.chains.entry(word).or_default().push(start);
+    }
+
+    Ok(table)
+  }
+
+  /// Number of stored chains.
+  pub fn len(&self) -> usize {
+    self.chains.values().map(Vec::len).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.chains.is_empty()
+  }
+
+  /// The word with the digest, if it is covered by a chain.
+  ///
+  /// Assuming the digest is hashed at every position of a chain, the
+  /// rest of the chain is followed to its end. The chains ending there
+  /// are replayed from their first word to find the plaintext, since
+  /// the end may also be reached by a different chain.
+  pub fn lookup(&self, digest: &[u8]) -> Result<Option<&str>> {
+    for position in (0..self.chain_length).rev() {
+      let mut end = self.reduce(digest, position);
+      for step in position + 1..self.chain_length {
+        end = self.reduce(&self.digest(end)?, step);
+      }
+
+      for &start in self.chains.get(&end).into_iter().flatten() {
+        let mut word = start;
+        for step in 0..=position {
+          let hash = self.digest(word)?;
+          if hash == digest {
+            return Ok(Some(&self.words[word]));
+          }
+          word = self.reduce(&hash, step);
+        }
+      }
+    }
+
+    Ok(None)
+  }
+
+  fn digest(&self, word: usize) -> Result<Vec<u8>> {
+    ToyHash::hash(self.words[word].as_bytes(), &self.hash)
+  }
+
+  /// Maps a digest to a word, differently for every step of a chain.
+  fn reduce(&self, digest: &[u8], step: usize) -> usize {
+    let value = digest
+      .iter()
+      .take(8)
+      .fold(0u64, |value, &byte| value << 8 | byte as u64);
+    (value.wrapping_add(step as u64) % self.words.len() as u64) as usize
+  }
+}
+
+pub struct Rainbow;
+
+impl Rainbow {
+  /// Reads hex digests, one per line, and writes every digest with the
+  /// recovered plaintext or `not found`.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RainbowConfig,
+  ) -> Result<()> {
+    let words: Vec<String> = fs::read_to_string(&config.dictionary)?
+      .lines()
+      .map(str::trim)
+      .filter(|word| !word.is_empty())
+      .map(String::from)
+      .collect();
+    let count = words.len();
+
+    let table = RainbowTable::new(words, config.chain_length, config.hash)?;
+
+    if config.stats {
+      writeln!(output, "chains: {} for {count} words", table.len())?;
+    }
+
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+      let digest = Hex::parse_hex(line)?;
+      match table.lookup(&digest.bytes)? {
+        Some(word) => writeln!(output, "{digest} {word}")?,
+        None => writeln!(output, "{digest} not found")?,
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn words() -> Vec<String> {
+    (0..500).map(|i| format!("word{i}")).collect()
+  }
+
+  #[test]
+  fn test_full_table() -> Result<()> {
+    let table = RainbowTable::new(words(), 1, ToyHashConfig::default())?;
+    assert_eq!(table.len(), 500);
+
+    for word in ["word0", "word123", "word499"] {
+      let digest = ToyHash::hash(word.as_bytes(), &ToyHashConfig::default())?;
+      assert_eq!(table.lookup(&digest)?, Some(word));
+    }
+
+    let digest = ToyHash::hash(b"missing", &ToyHashConfig::default())?;
+    assert_eq!(table.lookup(&digest)?, None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_chains_trade_memory_for_time() -> Result<()> {
+    let hash = ToyHashConfig::default();
+    let table = RainbowTable::new(words(), 10, ToyHashConfig::default())?;
+    assert_eq!(table.len(), 50);
+
+    let found = words()
+      .iter()
+      .filter(|word| {
+        let digest = ToyHash::hash(word.as_bytes(), &hash).unwrap();
+        table.lookup(&digest).unwrap() == Some(word.as_str())
+      })
+      .count();
+
+    assert!(found >= table.len());
+    Ok(())
+  }
+}
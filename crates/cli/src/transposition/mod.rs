@@ -0,0 +1,448 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{
+  dictionary_attack::{DictionaryAttack, DictionaryAttackConfig},
+  report::{CrackReport, LOW_CONFIDENCE},
+  solver::{Permutations, Solution, Solver, SolverConfig},
+  DecryptCipher, EncryptCipher,
+};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TranspositionConfig {
+  /// Keys applied one after another, e.g. two keys for double transposition.
+  pub keys: Vec<String>,
+  /// Number of columns to search the order for if no key is known.
+  pub key_length: Option<usize>,
+  pub solver: SolverConfig,
+  /// Wordlist whose words are tried as keys if no key is known.
+  pub wordlist: Option<String>,
+  pub dictionary: DictionaryAttackConfig,
+}
+
+impl TranspositionConfig {
+  pub fn new(keys: Vec<String>) -> Self {
+    Self {
+      keys,
+      ..Self::default()
+    }
+  }
+}
+
+impl From<&EncryptCipher> for TranspositionConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::ColumnarTransposition { keys, .. } => {
+        Self::new(keys.clone())
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for TranspositionConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::ColumnarTransposition {
+        keys,
+        key_length,
+        solver,
+        dictionary,
+        ..
+      } => Self {
+        keys: keys.clone(),
+        key_length: *key_length,
+        solver: solver.into(),
+        wordlist: None,
+        dictionary: dictionary.into(),
+      },
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct ColumnarTransposition;
+
+impl ColumnarTransposition {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: TranspositionConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let text: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+      let cipher = Self::encrypt_text_with_keys(&text, &config.keys)?;
+      writeln!(output, "{cipher}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: TranspositionConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    for line in content.lines() {
+      let text: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+      let plaintext = match config.keys.is_empty() {
+        true => Self::crack(&text, &config)?,
+        false => Self::decrypt_text_with_keys(&text, &config.keys)?,
+      };
+      writeln!(output, "{plaintext}")?;
+    }
+
+    Ok(())
+  }
+
+  /// Tries the words of the wordlist as keys, then searches the column
+  /// order for the key length. The best word is only kept below
+  /// [`LOW_CONFIDENCE`] if there is no key length to search.
+  fn crack(text: &str, config: &TranspositionConfig) -> Result<String> {
+    let report = config.wordlist.as_deref().and_then(|wordlist| {
+      Self::decrypt_with_wordlist(text, wordlist, &config.dictionary)
+    });
+    let confident = |report: &CrackReport| {
+      report
+        .confidence
+        .is_some_and(|confidence| confidence >= LOW_CONFIDENCE)
+    };
+
+    match (report, config.key_length) {
+      (Some(report), None) => Ok(report.plaintext),
+      (Some(report), Some(_)) if confident(&report) => Ok(report.plaintext),
+      (_, Some(width)) => {
+        let solution = Self::solve(text, width, &config.solver)?;
+        Ok(Self::decrypt_columns(text, &solution.key))
+      }
+      (None, None) => Self::decrypt_text_with_keys(text, &config.keys),
+    }
+  }
+
+  /// Decrypts with every word of the wordlist as a single key and
+  /// returns the most confident plaintext.
+  pub fn decrypt_with_wordlist(
+    text: &str,
+    wordlist: &str,
+    config: &DictionaryAttackConfig,
+  ) -> Option<CrackReport> {
+    DictionaryAttack::run(wordlist, config, |key| {
+      let plaintext = Self::decrypt_text(text, key).ok()?;
+      Some(CrackReport::new(
+        plaintext,
+        Some(key.to_string()),
+        "wordlist",
+      ))
+    })
+    .into_iter()
+    .next()
+  }
+
+  /// Returns the indices of the columns in the order they are read out.
+  ///
+  /// The order is given by sorting the characters of the key
+  /// alphabetically, with repeated characters keeping their position.
+  pub fn column_order(key: &str) -> Result<Vec<usize>> {
+    let key: Vec<char> = key
+      .chars()
+      .filter(|c| c.is_ascii_alphanumeric())
+      .map(|c| c.to_ascii_uppercase())
+      .collect();
+
+    if key.is_empty() {
+      let message = "Transposition key must contain a letter or digit";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut order: Vec<usize> = (0..key.len()).collect();
+    order.sort_by_key(|&column| key[column]);
+    Ok(order)
+  }
+
+  pub fn encrypt_text(text: &str, key: &str) -> Result<String> {
+    let order = Self::column_order(key)?;
+    Ok(Self::encrypt_columns(text, &order))
+  }
+
+  pub fn decrypt_text(text: &str, key: &str) -> Result<String> {
+    let order = Self::column_order(key)?;
+    Ok(Self::decrypt_columns(text, &order))
+  }
+
+  /// Applies one columnar transposition per key, in order.
+  pub fn encrypt_text_with_keys(text: &str, keys: &[String]) -> Result<String> {
+    Self::validate_keys(keys)?;
+    keys
+      .iter()
+      .try_fold(text.to_string(), |text, key| Self::encrypt_text(&text, key))
+  }
+
+  /// Undoes the transpositions of the keys in reverse order.
+  pub fn decrypt_text_with_keys(text: &str, keys: &[String]) -> Result<String> {
+    Self::validate_keys(keys)?;
+    keys
+      .iter()
+      .rev()
+      .try_fold(text.to_string(), |text, key| Self::decrypt_text(&text, key))
+  }
+
+  /// Searches the column order of a single transposition with `width`
+  /// columns, scoring every candidate plaintext as configured.
+  pub fn solve(
+    text: &str,
+    width: usize,
+    config: &SolverConfig,
+  ) -> Result<Solution<Vec<usize>>> {
+    if width == 0 {
+      let message = "Key length must be greater than zero";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let score = config.text_scorer()?;
+    let scorer =
+      |order: &Vec<usize>| score(&Self::decrypt_columns(text, order));
+
+    Ok(Solver::solve(
+      &Permutations { length: width },
+      &scorer,
+      config,
+    ))
+  }
+
+  fn validate_keys(keys: &[String]) -> Result<()> {
+    if keys.is_empty() {
+      let message = "At least one transposition key is required";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+    Ok(())
+  }
+
+  /// Writes the text row by row into a grid with one column per entry
+  /// of `order` and reads the columns out in that order.
+  pub fn encrypt_columns(text: &str, order: &[usize]) -> String {
+    if order.is_empty() {
+      return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let width = order.len();
+
+    order
+      .iter()
+      .flat_map(|&column| chars.iter().skip(column).step_by(width))
+      .collect()
+  }
+
+  /// Reverses [`ColumnarTransposition::encrypt_columns`].
+  pub fn decrypt_columns(text: &str, order: &[usize]) -> String {
+    if order.is_empty() {
+      return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let width = order.len();
+    let rows = chars.len() / width;
+    let remainder = chars.len() % width;
+    let mut plaintext = vec!['\0'; chars.len()];
+    let mut cipher = chars.into_iter();
+
+    for &column in order {
+      let height = rows + usize::from(column < remainder);
+      for row in 0..height {
+        if let Some(c) = cipher.next() {
+          plaintext[row * width + column] = c;
+        }
+      }
+    }
+
+    plaintext.into_iter().collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::Parser;
+
+  use super::*;
+  use crate::{with_command_stack, Command, Cryptology};
+
+  fn read_key_files(args: &[&str]) -> Result<Command> {
+    let cli = with_command_stack(|| Cryptology::try_parse_from(args).unwrap());
+    let mut command = cli.command;
+    command.read_key_files()?;
+    Ok(command)
+  }
+
+  #[test]
+  fn test_column_order() {
+    let order = ColumnarTransposition::column_order("PRIVACY").unwrap();
+    assert_eq!(order, vec![4, 5, 2, 0, 1, 3, 6]);
+  }
+
+  #[test]
+  fn test_column_order_with_repeated_letters() {
+    let order = ColumnarTransposition::column_order("BAB").unwrap();
+    assert_eq!(order, vec![1, 0, 2]);
+  }
+
+  #[test]
+  fn test_column_order_rejects_empty_key() {
+    assert!(ColumnarTransposition::column_order("").is_err());
+    assert!(ColumnarTransposition::column_order(" -!").is_err());
+  }
+
+  #[test]
+  fn test_encrypt() {
+    let cipher = ColumnarTransposition::encrypt_text(
+      "WEAREDISCOVEREDFLEEATONCE",
+      "ZEBRAS",
+    )
+    .unwrap();
+    assert_eq!(cipher, "EVLNACDTESEAROFODEECWIREE");
+  }
+
+  #[test]
+  fn test_decrypt() {
+    let plaintext = ColumnarTransposition::decrypt_text(
+      "EVLNACDTESEAROFODEECWIREE",
+      "ZEBRAS",
+    )
+    .unwrap();
+    assert_eq!(plaintext, "WEAREDISCOVEREDFLEEATONCE");
+  }
+
+  #[test]
+  fn test_roundtrip_with_irregular_columns() {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    let cipher = ColumnarTransposition::encrypt_text(text, "KEYWORD").unwrap();
+    let plaintext =
+      ColumnarTransposition::decrypt_text(&cipher, "KEYWORD").unwrap();
+    assert_eq!(plaintext, text);
+  }
+
+  #[test]
+  fn test_double_transposition() {
+    let keys = vec![String::from("ZEBRAS"), String::from("STRIPE")];
+    let cipher = ColumnarTransposition::encrypt_text_with_keys(
+      "WEAREDISCOVEREDFLEEATONCE",
+      &keys,
+    )
+    .unwrap();
+    assert_eq!(cipher, "CAEENSOIAEDRLEFWEDREEVTOC");
+  }
+
+  #[test]
+  fn test_double_transposition_decrypt() {
+    let keys = vec![String::from("ZEBRAS"), String::from("STRIPE")];
+    let plaintext = ColumnarTransposition::decrypt_text_with_keys(
+      "CAEENSOIAEDRLEFWEDREEVTOC",
+      &keys,
+    )
+    .unwrap();
+    assert_eq!(plaintext, "WEAREDISCOVEREDFLEEATONCE");
+  }
+
+  #[test]
+  fn test_transposition_requires_key() {
+    let result = ColumnarTransposition::encrypt_text_with_keys("TEXT", &[]);
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn test_encrypt_and_decrypt_lines() -> Result<()> {
+    let keys = vec![String::from("KEY"), String::from("WORD")];
+    let mut input = "attack at dawn\nretreat at dusk\n".as_bytes();
+    let mut cipher = Vec::new();
+    let config = TranspositionConfig::new(keys.clone());
+    ColumnarTransposition::encrypt(&mut input, &mut cipher, config)?;
+
+    let mut output = Vec::new();
+    let config = TranspositionConfig::new(keys);
+    ColumnarTransposition::decrypt(
+      &mut cipher.as_slice(),
+      &mut output,
+      config,
+    )?;
+
+    let plaintext = String::from_utf8(output).unwrap();
+    assert_eq!(plaintext, "attackatdawn\nretreatatdusk\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_with_wordlist() -> Result<()> {
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOGANDRUNSAWAYINTOTHEFOREST";
+    let cipher = ColumnarTransposition::encrypt_text(text, "Cipher")?;
+    let wordlist = "zebras\nkeyword\ncipher\nstripe\n";
+
+    let dictionary = DictionaryAttackConfig::default();
+    let report = ColumnarTransposition::decrypt_with_wordlist(
+      &cipher,
+      wordlist,
+      &dictionary,
+    )
+    .unwrap();
+    assert_eq!(report.key.as_deref(), Some("cipher"));
+    assert_eq!(report.plaintext, text);
+
+    let mut output = Vec::new();
+    let config = TranspositionConfig {
+      wordlist: Some(wordlist.to_string()),
+      ..TranspositionConfig::default()
+    };
+    let mut input = cipher.as_bytes();
+    ColumnarTransposition::decrypt(&mut input, &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{text}\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_solve_recovers_column_order() {
+    use crate::solver::{RestartPolicy, SolverKind};
+
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOGANDRUNSAWAYINTOTHEFOREST";
+    let cipher = ColumnarTransposition::encrypt_text(text, "CIPHER").unwrap();
+    let restart = RestartPolicy {
+      restarts: 4,
+      patience: Some(200),
+    };
+    let config =
+      SolverConfig::new(SolverKind::HillClimbing, 2_000, restart, Some(1));
+
+    let solution = ColumnarTransposition::solve(&cipher, 6, &config).unwrap();
+    let plaintext =
+      ColumnarTransposition::decrypt_columns(&cipher, &solution.key);
+    assert_eq!(plaintext, text);
+  }
+
+  #[test]
+  fn test_keys_from_key_file() -> Result<()> {
+    let path = std::env::temp_dir().join("cryptology-columnar-keys.txt");
+    std::fs::write(&path, "ZEBRA\n\nCAT\n")?;
+    let key_file = path.to_str().unwrap();
+
+    let args = ["cryptology", "encrypt", "columnar", "--key-file", key_file];
+    let config = match read_key_files(&args)? {
+      Command::Encrypt { cipher, .. } => TranspositionConfig::from(&cipher),
+      _ => unreachable!(),
+    };
+    assert_eq!(config.keys, ["ZEBRA", "CAT"]);
+
+    let args = ["cryptology", "decrypt", "columnar", "--key-file", key_file];
+    let config = match read_key_files(&args)? {
+      Command::Decrypt { cipher, .. } => TranspositionConfig::from(&cipher),
+      _ => unreachable!(),
+    };
+    assert_eq!(config.keys, ["ZEBRA", "CAT"]);
+    std::fs::remove_file(&path)?;
+
+    let error = read_key_files(&args).unwrap_err();
+    assert!(error.to_string().contains("cryptology-columnar-keys.txt"));
+    Ok(())
+  }
+}
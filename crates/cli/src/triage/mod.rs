@@ -0,0 +1,182 @@
+use std::{
+  collections::BTreeMap,
+  fs,
+  io::{Result, Write},
+  path::{Path, PathBuf},
+};
+
+use crate::{
+  encoding::{EncodingDetector, EncodingGuess},
+  frequency_analysis::FrequencyAnalyzer,
+};
+
+/// Number of most frequent letters listed per file.
+const TOP_LETTERS: usize = 5;
+
+/// The statistics of one file that hint at what it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSummary {
+  pub path: PathBuf,
+  pub bytes: usize,
+  pub letters: usize,
+  /// Bits per byte, see [`FrequencyAnalyzer::entropy`].
+  pub entropy: f32,
+  pub index_of_coincidence: f32,
+  /// The most frequent letters, most frequent first.
+  pub top_letters: String,
+  /// The most likely encoding of the file.
+  pub encoding: EncodingGuess,
+}
+
+impl FileSummary {
+  pub fn new(path: PathBuf, content: &[u8]) -> Self {
+    let text = String::from_utf8_lossy(content);
+    let mut counts = [0usize; 26];
+    for c in text.bytes().filter(u8::is_ascii_alphabetic) {
+      counts[(c.to_ascii_uppercase() - b'A') as usize] += 1;
+    }
+
+    let mut letters: Vec<(char, usize)> = ('A'..='Z').zip(counts).collect();
+    letters.sort_by(|a, b| b.1.cmp(&a.1));
+    let top_letters = letters
+      .iter()
+      .filter(|(_, count)| *count > 0)
+      .take(TOP_LETTERS)
+      .map(|(letter, _)| letter)
+      .collect();
+
+    Self {
+      path,
+      bytes: content.len(),
+      letters: counts.iter().sum(),
+      entropy: FrequencyAnalyzer::entropy(content),
+      index_of_coincidence: FrequencyAnalyzer::index_of_coincidence(&text),
+      top_letters,
+      encoding: EncodingDetector::detect(content).remove(0),
+    }
+  }
+}
+
+/// Analyses every file below a directory, for getting an overview of a
+/// dump of unknown files.
+pub struct Triage;
+
+impl Triage {
+  /// Writes the letter statistics of every file, followed by those of
+  /// all files together and their combined frequency table.
+  pub fn frequency<W: Write>(output: &mut W, dir: &Path) -> Result<()> {
+    let mut all = Vec::new();
+
+    for path in Self::files(dir)? {
+      let content = fs::read(&path)?;
+      let summary = FileSummary::new(path, &content);
+      writeln!(
+        output,
+        "{}: {} letters, IC {:.4}, entropy {:.2} bits, top {}",
+        summary.path.display(),
+        summary.letters,
+        summary.index_of_coincidence,
+        summary.entropy,
+        summary.top_letters,
+      )?;
+      all.push(content);
+    }
+
+    let content = all.concat();
+    let total = FileSummary::new(dir.to_path_buf(), &content);
+    writeln!(
+      output,
+      "total: {} files, {} letters, IC {:.4}, entropy {:.2} bits",
+      all.len(),
+      total.letters,
+      total.index_of_coincidence,
+      total.entropy,
+    )?;
+    FrequencyAnalyzer::analyze(&mut content.as_slice(), output)?;
+    Ok(())
+  }
+
+  /// Writes the most likely encoding of every file, followed by the
+  /// number of files per encoding.
+  pub fn encoding<W: Write>(output: &mut W, dir: &Path) -> Result<()> {
+    let mut encodings: BTreeMap<String, usize> = BTreeMap::new();
+    let mut files = 0;
+
+    for path in Self::files(dir)? {
+      let content = fs::read(&path)?;
+      let summary = FileSummary::new(path, &content);
+      writeln!(
+        output,
+        "{}: {}, entropy {:.2} bits, {} bytes",
+        summary.path.display(),
+        summary.encoding,
+        summary.entropy,
+        summary.bytes,
+      )?;
+      *encodings
+        .entry(summary.encoding.encoding.to_string())
+        .or_default() += 1;
+      files += 1;
+    }
+
+    let counts: Vec<String> = encodings
+      .iter()
+      .map(|(encoding, count)| format!("{encoding} {count}"))
+      .collect();
+    writeln!(output, "total: {files} files, {}", counts.join(", "))
+  }
+
+  /// All files below `dir`, sorted by path. Symbolic links to
+  /// directories are not followed, since they may form cycles.
+  pub fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+      for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+          dirs.push(entry.path());
+        } else if entry.path().is_file() {
+          files.push(entry.path());
+        }
+      }
+    }
+
+    files.sort();
+    Ok(files)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::env;
+
+  fn dump() -> Result<PathBuf> {
+    let dir = env::temp_dir().join(format!("triage-{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested"))?;
+    fs::write(dir.join("plain.txt"), "attack at dawn")?;
+    fs::write(dir.join("nested/hex.txt"), "48656c6c6f2c20776f726c6421")?;
+    Ok(dir)
+  }
+
+  #[test]
+  fn test_files_are_found_recursively() -> Result<()> {
+    let dir = dump()?;
+    let files = Triage::files(&dir)?;
+    assert_eq!(files, [dir.join("nested/hex.txt"), dir.join("plain.txt")]);
+
+    let summary = FileSummary::new(files[1].clone(), b"attack at dawn");
+    assert_eq!(summary.letters, 12);
+    assert_eq!(summary.top_letters, "ATCDK");
+
+    let mut output = Vec::new();
+    Triage::encoding(&mut output, &dir)?;
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.ends_with("total: 2 files, ascii 1, hex 1\n"));
+
+    fs::remove_dir_all(dir)
+  }
+}
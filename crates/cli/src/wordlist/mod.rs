@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::io::{Read, Result, Write};
+
+use crate::dictionary_attack::{CaseRule, Mangling, Transform};
+use crate::Command;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WordlistConfig {
+  pub mangling: Mangling,
+  pub min_length: usize,
+  pub max_length: Option<usize>,
+}
+
+impl WordlistConfig {
+  pub fn new(
+    case: Vec<CaseRule>,
+    leet: bool,
+    digits: usize,
+    min_length: usize,
+    max_length: Option<usize>,
+  ) -> Self {
+    Self {
+      mangling: Mangling::new(case, leet, digits),
+      min_length,
+      max_length,
+    }
+  }
+}
+
+impl From<&Command> for WordlistConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Wordlist {
+        case,
+        leet,
+        digits,
+        min_length,
+        max_length,
+        ..
+      } => Self::new(case.clone(), *leet, *digits, *min_length, *max_length),
+      _ => Self::default(),
+    }
+  }
+}
+
+pub struct Wordlist;
+
+impl Wordlist {
+  /// Writes the mangled words of every base word, one per line. Every
+  /// word is written once, at its first occurrence.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: WordlistConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let mut written = HashSet::new();
+    for word in content.split_whitespace() {
+      for mangled in Self::mangle(word, &config) {
+        if written.insert(mangled.clone()) {
+          writeln!(output, "{mangled}")?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The keys of the mangling whose length is within the limits, see
+  /// [`Mangling::keys`].
+  pub fn mangle<'a>(
+    word: &'a str,
+    config: &'a WordlistConfig,
+  ) -> impl Iterator<Item = String> + 'a {
+    config.mangling.keys(word).filter(|word| {
+      let length = word.chars().count();
+      length >= config.min_length
+        && config.max_length.is_none_or(|max| length <= max)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_length_filter() {
+    let config =
+      WordlistConfig::new(vec![CaseRule::Lower], false, 1, 5, Some(5));
+    let words: Vec<String> = Wordlist::mangle("Lemon", &config).collect();
+    assert_eq!(words, ["Lemon", "lemon"]);
+  }
+
+  #[test]
+  fn test_execute_deduplicates() -> Result<()> {
+    let config = WordlistConfig::new(vec![CaseRule::Lower], false, 0, 0, None);
+    let mut output = Vec::new();
+    let mut input = std::io::Cursor::new("KEY key\nice");
+    Wordlist::execute(&mut input, &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), "KEY\nkey\nice\n");
+    Ok(())
+  }
+}
@@ -0,0 +1,393 @@
+use std::io::{Read, Result, Write};
+
+use clap::ValueEnum;
+use ratatui::{
+  crossterm::event::KeyModifiers,
+  crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+  layout::{Constraint, Layout},
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+  widgets::{Block, Paragraph, Wrap},
+  Frame,
+};
+
+use crate::{
+  caesar::Caesar,
+  frequency_analysis::{frequencies::english::ENGLISH, FrequencyAnalyzer},
+  monoalphabetic_substitution::substitution_map::SubstitutionMap,
+  Command,
+};
+
+/// Stands in for a ciphertext letter whose plaintext letter is unknown.
+const UNKNOWN: char = '_';
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WorkbenchMode {
+  /// Assign a plaintext letter to every ciphertext letter.
+  #[default]
+  Substitution,
+  /// Type the key of a Vigenère cipher.
+  Vigenere,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WorkbenchConfig {
+  pub mode: WorkbenchMode,
+}
+
+impl WorkbenchConfig {
+  pub fn new(mode: WorkbenchMode) -> Self {
+    Self { mode }
+  }
+}
+
+impl From<&Command> for WorkbenchConfig {
+  fn from(value: &Command) -> Self {
+    match value {
+      Command::Workbench { mode, .. } => Self::new(*mode),
+      _ => Self::default(),
+    }
+  }
+}
+
+/// Interactive solver for substitution and Vigenère ciphers.
+///
+/// The plaintext preview and its score follow every change of the key,
+/// which lets the key be worked out by hand letter by letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workbench {
+  ciphertext: String,
+  mode: WorkbenchMode,
+  /// Plaintext letter of every ciphertext letter in substitution mode.
+  mapping: [Option<char>; 26],
+  /// Index of the ciphertext letter that is being assigned.
+  selected: usize,
+  /// Key in Vigenère mode.
+  key: String,
+  quit: bool,
+}
+
+impl Workbench {
+  pub fn new(ciphertext: String, mode: WorkbenchMode) -> Self {
+    Self {
+      ciphertext,
+      mode,
+      mapping: [None; 26],
+      selected: 0,
+      key: String::new(),
+      quit: false,
+    }
+  }
+
+  /// Runs the workbench until it is quit and writes the final key and
+  /// plaintext in the format of the matching decrypt command.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: WorkbenchConfig,
+  ) -> Result<()> {
+    let mut ciphertext = String::new();
+    input.read_to_string(&mut ciphertext)?;
+
+    let mut workbench = Self::new(ciphertext, config.mode);
+    let mut terminal = ratatui::try_init()?;
+
+    let result = (|| -> Result<()> {
+      while !workbench.quit {
+        terminal.draw(|frame| workbench.render(frame))?;
+        if let Event::Key(key) = event::read()? {
+          workbench.handle_key(key);
+        }
+      }
+      Ok(())
+    })();
+
+    ratatui::try_restore()?;
+    result?;
+
+    workbench.write_result(output)
+  }
+
+  pub fn handle_key(&mut self, key: KeyEvent) {
+    if key.kind != KeyEventKind::Press {
+      return;
+    }
+
+    let control = key.modifiers.contains(KeyModifiers::CONTROL);
+    match (key.code, self.mode) {
+      (KeyCode::Esc, _) => self.quit = true,
+      (KeyCode::Char('c'), _) if control => self.quit = true,
+      (KeyCode::Tab, WorkbenchMode::Substitution) => {
+        self.mode = WorkbenchMode::Vigenere
+      }
+      (KeyCode::Tab, WorkbenchMode::Vigenere) => {
+        self.mode = WorkbenchMode::Substitution
+      }
+      (KeyCode::Left | KeyCode::Up, WorkbenchMode::Substitution) => {
+        self.selected = (self.selected + 25) % 26
+      }
+      (KeyCode::Right | KeyCode::Down, WorkbenchMode::Substitution) => {
+        self.selected = (self.selected + 1) % 26
+      }
+      (KeyCode::Char(c), WorkbenchMode::Substitution)
+        if c.is_ascii_alphabetic() =>
+      {
+        self.assign(self.selected, c.to_ascii_uppercase());
+        self.selected = (self.selected + 1) % 26;
+      }
+      (KeyCode::Backspace | KeyCode::Delete, WorkbenchMode::Substitution) => {
+        self.mapping[self.selected] = None
+      }
+      (KeyCode::Char(c), WorkbenchMode::Vigenere)
+        if c.is_ascii_alphabetic() =>
+      {
+        self.key.push(c.to_ascii_uppercase())
+      }
+      (KeyCode::Backspace, WorkbenchMode::Vigenere) => {
+        self.key.pop();
+      }
+      _ => {}
+    }
+  }
+
+  /// Maps a ciphertext letter to a plaintext letter and unassigns the
+  /// letter that mapped to it before, so the key stays a permutation.
+  pub fn assign(&mut self, cipher: usize, plain: char) {
+    for target in self.mapping.iter_mut() {
+      if *target == Some(plain) {
+        *target = None;
+      }
+    }
+    self.mapping[cipher] = Some(plain);
+  }
+
+  /// The ciphertext decrypted with the current key. Letters without a
+  /// plaintext letter are shown as [`UNKNOWN`].
+  pub fn plaintext(&self) -> String {
+    match self.mode {
+      WorkbenchMode::Substitution => self
+        .ciphertext
+        .chars()
+        .map(|c| match Self::index(c) {
+          Some(index) => self.mapping[index].unwrap_or(UNKNOWN),
+          None => c,
+        })
+        .collect(),
+      WorkbenchMode::Vigenere if self.key.is_empty() => self.ciphertext.clone(),
+      WorkbenchMode::Vigenere => {
+        let mut key = self.key.chars().cycle();
+        self
+          .ciphertext
+          .chars()
+          .map(|c| match c.is_ascii_alphabetic() {
+            true => Caesar::shift(c, key.next().unwrap_or('A'), -1),
+            false => c,
+          })
+          .collect()
+      }
+    }
+  }
+
+  /// Bigram score of the known part of the plaintext, lower is more
+  /// English-like.
+  pub fn score(&self) -> f32 {
+    FrequencyAnalyzer::bigram_score(&self.plaintext())
+  }
+
+  /// Percentage of every letter in the ciphertext in substitution mode
+  /// or in the plaintext in Vigenère mode, most frequent first.
+  pub fn frequencies(&self) -> Vec<(char, f32)> {
+    let text = match self.mode {
+      WorkbenchMode::Substitution => self.ciphertext.clone(),
+      WorkbenchMode::Vigenere => self.plaintext(),
+    };
+
+    let mut counts = [0usize; 26];
+    for index in text.chars().filter_map(Self::index) {
+      counts[index] += 1;
+    }
+    let total = counts.iter().sum::<usize>().max(1) as f32;
+
+    let mut frequencies: Vec<(char, f32)> = counts
+      .iter()
+      .enumerate()
+      .map(|(index, &count)| {
+        ((b'A' + index as u8) as char, count as f32 * 100.0 / total)
+      })
+      .collect();
+    frequencies.sort_by(|a, b| b.1.total_cmp(&a.1));
+    frequencies
+  }
+
+  pub fn write_result<W: Write>(&self, output: &mut W) -> Result<()> {
+    match self.mode {
+      WorkbenchMode::Substitution => {
+        let map = SubstitutionMap::new(
+          ('A'..='Z')
+            .zip(self.mapping)
+            .filter_map(|(cipher, plain)| Some((cipher, plain?)))
+            .collect(),
+        );
+        write!(output, "{map}")?;
+      }
+      WorkbenchMode::Vigenere => writeln!(output, "key: {}", self.key)?,
+    }
+    write!(output, "{}", self.plaintext())
+  }
+
+  fn render(&self, frame: &mut Frame) {
+    let [main, sidebar] =
+      Layout::horizontal([Constraint::Min(0), Constraint::Length(24)])
+        .areas(frame.area());
+    let [ciphertext, plaintext, key] = Layout::vertical([
+      Constraint::Percentage(50),
+      Constraint::Percentage(50),
+      Constraint::Length(4),
+    ])
+    .areas(main);
+
+    let text = |text: String, title: &str| {
+      Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(title.to_string()))
+    };
+
+    frame
+      .render_widget(text(self.ciphertext.clone(), " Ciphertext "), ciphertext);
+    let title = format!(" Plaintext (score {:.2}) ", self.score());
+    frame.render_widget(text(self.plaintext(), &title), plaintext);
+    frame.render_widget(self.key_widget(), key);
+    frame.render_widget(self.frequency_widget(), sidebar);
+  }
+
+  fn key_widget(&self) -> Paragraph<'_> {
+    let selected = Style::default().add_modifier(Modifier::REVERSED);
+    let lines = match self.mode {
+      WorkbenchMode::Substitution => {
+        let cipher = ('A'..='Z').enumerate().map(|(index, c)| {
+          let style = match index == self.selected {
+            true => selected,
+            false => Style::default(),
+          };
+          Span::styled(c.to_string(), style)
+        });
+        let plain = self.mapping.iter().map(|plain| plain.unwrap_or(UNKNOWN));
+        vec![
+          Line::from(cipher.collect::<Vec<_>>()),
+          Line::from(plain.collect::<String>()),
+        ]
+      }
+      WorkbenchMode::Vigenere => vec![
+        Line::from(format!("key: {}", self.key)),
+        Line::from(format!("length: {}", self.key.len())),
+      ],
+    };
+
+    let help = " Tab: mode, Esc: quit ";
+    let title = match self.mode {
+      WorkbenchMode::Substitution => " Substitution ",
+      WorkbenchMode::Vigenere => " Vigenère ",
+    };
+    Paragraph::new(lines)
+      .block(Block::bordered().title(title).title_bottom(help))
+  }
+
+  fn frequency_widget(&self) -> Paragraph<'_> {
+    let mut english: Vec<(char, usize)> =
+      ENGLISH.iter().map(|(&c, &count)| (c, count)).collect();
+    english.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let lines: Vec<Line> = self
+      .frequencies()
+      .into_iter()
+      .zip(english)
+      .map(|((letter, percent), (english, _))| {
+        let bar = "#".repeat((percent / 2.0).round() as usize);
+        Line::from(vec![
+          Span::raw(format!("{letter} {percent:>4.1} {english} ")),
+          Span::styled(bar, Style::default().fg(Color::Cyan)),
+        ])
+      })
+      .collect();
+
+    Paragraph::new(lines).block(Block::bordered().title(" Frequencies "))
+  }
+
+  fn index(c: char) -> Option<usize> {
+    c.is_ascii_alphabetic()
+      .then(|| (c.to_ascii_uppercase() as u8 - b'A') as usize)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::{backend::TestBackend, Terminal};
+
+  use super::*;
+
+  fn press(workbench: &mut Workbench, code: KeyCode) {
+    workbench.handle_key(KeyEvent::from(code));
+  }
+
+  #[test]
+  fn test_substitution_mapping() {
+    let mut workbench =
+      Workbench::new(String::from("Uif dbu"), WorkbenchMode::Substitution);
+    assert_eq!(workbench.plaintext(), "___ ___");
+
+    workbench.selected = 20;
+    press(&mut workbench, KeyCode::Char('t'));
+    workbench.selected = 8;
+    press(&mut workbench, KeyCode::Char('h'));
+    assert_eq!(workbench.plaintext(), "TH_ __T");
+
+    workbench.assign(1, 'T');
+    assert_eq!(workbench.plaintext(), "_H_ _T_");
+
+    press(&mut workbench, KeyCode::Left);
+    press(&mut workbench, KeyCode::Backspace);
+    assert_eq!(workbench.plaintext(), "___ _T_");
+  }
+
+  #[test]
+  fn test_vigenere_key() -> Result<()> {
+    let mut workbench =
+      Workbench::new(String::from("LXFOPV"), WorkbenchMode::Substitution);
+    press(&mut workbench, KeyCode::Tab);
+    assert_eq!(workbench.plaintext(), "LXFOPV");
+
+    for c in "lemon".chars() {
+      press(&mut workbench, KeyCode::Char(c));
+    }
+    assert_eq!(workbench.plaintext(), "ATTACK");
+
+    let mut output = Vec::new();
+    workbench.write_result(&mut output)?;
+    assert_eq!(output, b"key: LEMON\nATTACK");
+
+    press(&mut workbench, KeyCode::Esc);
+    assert!(workbench.quit);
+    Ok(())
+  }
+
+  #[test]
+  fn test_render() {
+    let workbench =
+      Workbench::new(String::from("EEEA"), WorkbenchMode::Substitution);
+    assert_eq!(workbench.frequencies()[0], ('E', 75.0));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| workbench.render(frame)).unwrap();
+    let screen: String = terminal
+      .backend()
+      .buffer()
+      .content()
+      .iter()
+      .map(|cell| cell.symbol())
+      .collect();
+
+    assert!(screen.contains("Ciphertext"));
+    assert!(screen.contains("Frequencies"));
+    assert!(screen.contains("E 75.0 E"));
+  }
+}
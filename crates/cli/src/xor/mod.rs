@@ -1,27 +1,111 @@
-use std::{fmt::Display, io::Write, iter::repeat, path::PathBuf};
+pub mod repeating_key;
 
-use crate::{hex::Hex, Command};
+use std::{
+  io::{self, Error, ErrorKind, Read, Write},
+  path::{Path, PathBuf},
+};
+
+pub use cryptology_core::xor::*;
+
+use crate::{error::CryptologyError, hex::Hex, Command};
+
+/// One operand of the XOR operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XorInput {
+  File(PathBuf),
+  Stdin,
+  /// An inline hex string.
+  Value(String),
+}
+
+impl XorInput {
+  /// A path of `-` stands for standard input.
+  pub fn new(path: &Option<PathBuf>, value: &Option<String>) -> Option<Self> {
+    match (path, value) {
+      (_, Some(value)) => Some(Self::Value(value.clone())),
+      (Some(path), None) => Some(Self::from_path(path)),
+      (None, None) => None,
+    }
+  }
+
+  pub fn from_path(path: &Path) -> Self {
+    match path.as_os_str() == "-" {
+      true => Self::Stdin,
+      false => Self::File(path.to_path_buf()),
+    }
+  }
+
+  /// Reads the bytes of the operand. Files and standard input are
+  /// parsed as hex if `raw` is set, inline values always are.
+  pub fn read(&self, raw: bool) -> Result<Vec<u8>, CryptologyError> {
+    let bytes = match self {
+      XorInput::File(path) => match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(source) => {
+          let path = path.clone();
+          return Err(CryptologyError::Open { path, source });
+        }
+      },
+      XorInput::Stdin => {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        bytes
+      }
+      XorInput::Value(value) => return Self::parse_hex(value.as_bytes()),
+    };
+
+    match raw {
+      true => Self::parse_hex(&bytes),
+      false => Ok(bytes),
+    }
+  }
+
+  fn parse_hex(bytes: &[u8]) -> Result<Vec<u8>, CryptologyError> {
+    let hex: String = String::from_utf8_lossy(bytes)
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect();
+    Ok(Hex::parse_hex(&hex)?.bytes)
+  }
+}
+
+/// An input of the XOR and whether it is given as hex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XorOperand {
+  pub input: XorInput,
+  pub raw: bool,
+}
+
+impl XorOperand {
+  pub fn new(input: XorInput, raw: bool) -> Self {
+    Self { input, raw }
+  }
+}
+
+/// How the result of the XOR is written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum XorOutput {
+  #[default]
+  Hex,
+  /// Text with unprintable bytes as dots.
+  Ascii,
+  /// The bytes as they are.
+  Binary,
+}
 
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct XorConfig {
-  alpha: PathBuf,
-  beta: PathBuf,
-  raw_alpha: bool,
-  raw_beta: bool,
+  operands: Vec<XorOperand>,
+  padding: XorPadding,
+  output: XorOutput,
 }
 
 impl XorConfig {
-  pub fn new(
-    alpha: PathBuf,
-    beta: PathBuf,
-    raw_alpha: bool,
-    raw_beta: bool,
-  ) -> Self {
+  pub fn new(operands: Vec<XorOperand>, padding: XorPadding) -> Self {
     Self {
-      alpha,
-      beta,
-      raw_alpha,
-      raw_beta,
+      operands,
+      padding,
+      output: XorOutput::default(),
     }
   }
 }
@@ -32,82 +116,78 @@ impl From<&Command> for XorConfig {
       Command::Xor {
         alpha,
         beta,
+        alpha_value,
+        beta_value,
+        inputs,
         raw_alpha,
         raw_beta,
+        raw_inputs,
+        pad_byte,
+        pad_side,
+        truncate,
+        to_ascii,
+        binary_output,
         ..
-      } => Self::new(
-        alpha.to_path_buf(),
-        beta.to_path_buf(),
-        *raw_alpha,
-        *raw_beta,
-      ),
+      } => {
+        let alpha = XorInput::new(alpha, alpha_value)
+          .map(|input| XorOperand::new(input, *raw_alpha));
+        let beta = XorInput::new(beta, beta_value)
+          .map(|input| XorOperand::new(input, *raw_beta));
+        let inputs = inputs
+          .iter()
+          .map(|path| XorOperand::new(XorInput::from_path(path), *raw_inputs));
+
+        let operands = alpha.into_iter().chain(beta).chain(inputs).collect();
+        let padding = XorPadding::new(*pad_byte, *pad_side, *truncate);
+        let output = match (to_ascii, binary_output) {
+          (true, _) => XorOutput::Ascii,
+          (false, true) => XorOutput::Binary,
+          (false, false) => XorOutput::Hex,
+        };
+
+        Self {
+          output,
+          ..Self::new(operands, padding)
+        }
+      }
       _ => Self::default(),
     }
   }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct Xor {
-  pub hex: Hex,
-}
-
-impl Xor {
-  pub fn new(hex: Hex) -> Self {
-    Self { hex }
-  }
-
-  pub fn xor<W: Write>(
-    config: XorConfig,
-    output: &mut W,
-  ) -> std::io::Result<()> {
-    let alpha = std::fs::read(config.alpha)?;
-    let beta = std::fs::read(config.beta)?;
-
-    let alpha = if config.raw_alpha {
-      let alpha = String::from_utf8(alpha).unwrap();
-      Hex::parse_hex(&alpha).unwrap().bytes
-    } else {
-      let hex: Hex = alpha.try_into().unwrap();
-      hex.bytes
-    };
-
-    let beta = if config.raw_beta {
-      let beta = String::from_utf8(beta).unwrap();
-      Hex::parse_hex(&beta).unwrap().bytes
-    } else {
-      let hex: Hex = beta.try_into().unwrap();
-      hex.bytes
-    };
-
-    let xor = Self::xor_bytes_padded(&alpha, &beta, 0);
-
-    write!(output, "{xor}")
-  }
+impl XorConfig {
+  /// XORs all operands together, padding or truncating them to the same
+  /// length first.
+  pub fn xor<W: Write>(&self, output: &mut W) -> Result<(), CryptologyError> {
+    let operands = &self.operands;
+
+    if operands.len() < 2 {
+      let message = "XOR requires at least two inputs";
+      return Err(Error::new(ErrorKind::InvalidInput, message).into());
+    }
 
-  pub fn xor_bytes(alpha: &[u8], beta: &[u8]) -> Self {
-    let bytes = alpha
+    let stdin = operands
       .iter()
-      .zip(beta.iter())
-      .map(|(alpha, beta)| alpha ^ beta)
-      .collect();
-
-    Self::new(Hex::new(bytes))
-  }
+      .filter(|operand| operand.input == XorInput::Stdin)
+      .count();
+    if stdin > 1 {
+      let message = "Only one input can be read from standard input";
+      return Err(Error::new(ErrorKind::InvalidInput, message).into());
+    }
 
-  pub fn xor_bytes_padded(alpha: &[u8], beta: &[u8], pad: u8) -> Self {
-    let max_len = std::cmp::max(alpha.len(), beta.len());
-    let alpha_padded = alpha.iter().chain(repeat(&pad)).take(max_len);
-    let beta_padded = beta.iter().chain(repeat(&pad)).take(max_len);
-    let bytes = alpha_padded.zip(beta_padded).map(|(a, b)| a ^ b).collect();
+    let inputs = operands
+      .iter()
+      .map(|operand| operand.input.read(operand.raw))
+      .collect::<Result<Vec<Vec<u8>>, _>>()?;
 
-    Self::new(Hex::new(bytes))
-  }
-}
+    let xor = Xor::xor_all(&inputs, &self.padding);
 
-impl Display for Xor {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    let hex = &self.hex;
-    write!(f, "{hex}")
+    match self.output {
+      XorOutput::Hex => write!(output, "{xor}")?,
+      XorOutput::Ascii => write!(output, "{}", xor.hex.to_ascii())?,
+      XorOutput::Binary => output.write_all(&xor.hex.bytes)?,
+    }
+    Ok(())
   }
 }
 
@@ -115,67 +195,97 @@ impl Display for Xor {
 mod tests {
   use super::*;
 
-  #[test]
-  fn test_xor() {
-    let alpha = b"Hello";
-    let beta = b"World";
-
-    let result = Xor::xor_bytes(alpha, beta);
-
-    let expected = vec![
-      b'H' ^ b'W',
-      b'e' ^ b'o',
-      b'l' ^ b'r',
-      b'l' ^ b'l',
-      b'o' ^ b'd',
-    ];
+  fn value(hex: &str) -> XorInput {
+    XorInput::Value(String::from(hex))
+  }
 
-    assert_eq!(result.hex.bytes, expected);
+  fn config(values: &[&str]) -> XorConfig {
+    let operands = values
+      .iter()
+      .map(|hex| XorOperand::new(value(hex), false))
+      .collect();
+    XorConfig::new(operands, XorPadding::default())
   }
 
   #[test]
-  fn test_xor_display() {
-    let alpha = b"Hello";
-    let beta = b"Hello";
-
-    let xor = Xor::xor_bytes(alpha, beta);
-
-    let result = format!("{xor}");
-    let expected = String::from("0000000000");
-
-    assert_eq!(result, expected);
+  fn test_xor_inline_values() -> std::io::Result<()> {
+    let config = config(&[
+      "1c0111001f010100061a024b53535009181c",
+      "686974207468652062756c6c277320657965",
+    ]);
+    let mut output = Vec::new();
+    config.xor(&mut output)?;
+
+    let result = String::from_utf8(output).unwrap();
+    assert_eq!(result, "746865206b696420646f6e277420706c6179");
+    Ok(())
   }
 
   #[test]
-  fn test_xor_with_key_longer_than_plaintext() {
-    let plaintext = b"HELLO";
-    let key = b"SECRETKEY";
+  fn test_xor_output_formats() -> std::io::Result<()> {
+    let xor = |output| {
+      let config = XorConfig {
+        output,
+        ..config(&["4849", "000a"])
+      };
+      let mut bytes = Vec::new();
+      config.xor(&mut bytes).map(|_| bytes)
+    };
 
-    let expected = vec![27, 0, 15, 30, 10, 84, 75, 69, 89];
-    let result = Xor::xor_bytes_padded(plaintext, key, 0);
+    assert_eq!(xor(XorOutput::Hex)?, b"4843");
+    assert_eq!(xor(XorOutput::Ascii)?, b"HC");
+    assert_eq!(xor(XorOutput::Binary)?, b"HC");
 
-    assert_eq!(result.hex.bytes, expected);
+    let config = XorConfig {
+      output: XorOutput::Ascii,
+      ..config(&["00ff", "4100"])
+    };
+    let mut bytes = Vec::new();
+    config.xor(&mut bytes)?;
+    assert_eq!(bytes, b"A.");
+    Ok(())
   }
 
   #[test]
-  fn test_xor_with_plaintext_longer_than_key() {
-    let plaintext = b"HELLOTHERE";
-    let key = b"KEY";
+  fn test_xor_input_from_arguments() {
+    let path = Some(PathBuf::from("-"));
+    assert_eq!(XorInput::new(&path, &None), Some(XorInput::Stdin));
+    let path = Some(PathBuf::from("alpha.txt"));
+    let input = XorInput::File(PathBuf::from("alpha.txt"));
+    assert_eq!(XorInput::new(&path, &None), Some(input));
+    let input = Some(value("ff"));
+    assert_eq!(XorInput::new(&None, &Some(String::from("ff"))), input);
+    assert_eq!(XorInput::new(&None, &None), None);
+  }
 
-    let expected = vec![3, 0, 21, 76, 79, 84, 72, 69, 82, 69];
-    let result = Xor::xor_bytes_padded(plaintext, key, 0);
+  #[test]
+  fn test_xor_rejects_two_stdin_inputs() {
+    let stdin = XorOperand::new(XorInput::Stdin, false);
+    let config =
+      XorConfig::new(vec![stdin.clone(), stdin], XorPadding::default());
+    assert!(config.xor(&mut Vec::new()).is_err());
+  }
 
-    assert_eq!(result.hex.bytes, expected);
+  #[test]
+  fn test_xor_requires_two_inputs() {
+    let config = config(&["ff"]);
+    assert!(config.xor(&mut Vec::new()).is_err());
   }
 
   #[test]
-  fn test_xor_with_empty_inputs() {
-    let alpha = b"";
-    let beta = b"";
+  fn test_xor_reports_missing_file() {
+    let missing = XorInput::File(PathBuf::from("missing-operand.txt"));
+    let result = missing.read(false);
+    assert!(matches!(result, Err(CryptologyError::Open { .. })));
+  }
 
-    let expected: Vec<u8> = vec![];
-    let result = Xor::xor_bytes(alpha, beta);
+  #[test]
+  fn test_xor_many_inputs() -> std::io::Result<()> {
+    let config = config(&["0f", "f0ff", "ff00", "3c"]);
+    let mut output = Vec::new();
+    config.xor(&mut output)?;
 
-    assert_eq!(result.hex.bytes, expected);
+    assert_eq!(String::from_utf8(output).unwrap(), "3cff");
+    Ok(())
   }
 }
@@ -0,0 +1,36 @@
+pub use cryptology_core::xor::repeating_key::*;
+
+use crate::{DecryptCipher, EncryptCipher};
+
+impl From<&EncryptCipher> for RepeatingKeyXorConfig {
+  fn from(value: &EncryptCipher) -> Self {
+    match value {
+      EncryptCipher::Xor {
+        key, key_format, ..
+      } => Self {
+        key: Some(key.key().to_string()),
+        key_format: key_format.key_format(),
+        ..Self::default()
+      },
+      _ => Self::default(),
+    }
+  }
+}
+
+impl From<&DecryptCipher> for RepeatingKeyXorConfig {
+  fn from(value: &DecryptCipher) -> Self {
+    match value {
+      DecryptCipher::Xor {
+        key,
+        key_format,
+        max_key_size,
+        dictionary,
+        ..
+      } => Self {
+        dictionary: dictionary.into(),
+        ..Self::new(key.key.clone(), key_format.key_format(), *max_key_size)
+      },
+      _ => Self::default(),
+    }
+  }
+}
@@ -0,0 +1,198 @@
+use std::io::{Error, ErrorKind, Result};
+
+/// The ordered symbols that a classical cipher shifts or substitutes.
+///
+/// Symbols outside of the alphabet pass through unchanged. An alphabet
+/// without lowercase letters matches lowercase input as well and keeps
+/// its case, so the default `A..=Z` works on mixed-case text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+  symbols: Vec<char>,
+  fold_case: bool,
+}
+
+impl Alphabet {
+  pub const LATIN: &'static str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+  /// The Russian alphabet, with `Ё` after `Е`.
+  pub const CYRILLIC: &'static str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+  pub const GREEK: &'static str = "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ";
+
+  /// Creates an alphabet from its symbols in order. Whitespace is
+  /// ignored, so alphabets can be read from files or split into groups.
+  pub fn new(symbols: &str) -> Result<Self> {
+    let symbols: Vec<char> =
+      symbols.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if symbols.len() < 2 {
+      let message = "An alphabet needs at least two symbols";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    if let Some(duplicate) = symbols
+      .iter()
+      .enumerate()
+      .find_map(|(i, c)| symbols[..i].contains(c).then_some(c))
+    {
+      let message = format!("Symbol {duplicate} occurs twice in the alphabet");
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let fold_case = !symbols.iter().any(|c| c.is_lowercase());
+    Ok(Self { symbols, fold_case })
+  }
+
+  pub fn len(&self) -> usize {
+    self.symbols.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.symbols.is_empty()
+  }
+
+  pub fn symbols(&self) -> &[char] {
+    &self.symbols
+  }
+
+  /// The symbol at `index`, wrapping around the end of the alphabet.
+  pub fn symbol(&self, index: usize) -> char {
+    self.symbols[index % self.len()]
+  }
+
+  /// Position of `c` in the alphabet, ignoring its case if the alphabet
+  /// folds case.
+  pub fn index(&self, c: char) -> Option<usize> {
+    let c = match self.fold_case {
+      true => Self::upper(c),
+      false => c,
+    };
+    self.symbols.iter().position(|&symbol| symbol == c)
+  }
+
+  pub fn contains(&self, c: char) -> bool {
+    self.index(c).is_some()
+  }
+
+  /// The symbol `amount` positions after `c`, in the case of `c`.
+  /// Symbols outside of the alphabet are returned unchanged.
+  pub fn shift(&self, c: char, amount: isize) -> char {
+    let Some(index) = self.index(c) else {
+      return c;
+    };
+    let length = self.len() as isize;
+    let shifted =
+      self.symbols[(index as isize + amount).rem_euclid(length) as usize];
+
+    match self.fold_case && c.is_lowercase() {
+      true => shifted.to_lowercase().next().unwrap_or(shifted),
+      false => shifted,
+    }
+  }
+
+  /// The text with every symbol of the alphabet written as in the
+  /// alphabet, which uppercases it if the alphabet folds case.
+  pub fn normalize(&self, text: &str) -> String {
+    text
+      .chars()
+      .map(|c| self.index(c).map_or(c, |index| self.symbols[index]))
+      .collect()
+  }
+
+  /// Positions of the symbols of a key, which must all be in the
+  /// alphabet. Whitespace is skipped, and a key without any symbol is
+  /// rejected.
+  pub fn key_shifts(&self, key: &str) -> Result<Vec<usize>> {
+    let shifts: Option<Vec<usize>> = key
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .map(|c| self.index(c))
+      .collect();
+
+    match shifts {
+      Some(shifts) if shifts.is_empty() => {
+        Err(Error::new(ErrorKind::InvalidInput, "Key must not be empty"))
+      }
+      Some(shifts) => Ok(shifts),
+      None => {
+        let message = format!("Key {key} has symbols outside of the alphabet");
+        Err(Error::new(ErrorKind::InvalidInput, message))
+      }
+    }
+  }
+
+  fn upper(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+      (Some(upper), None) => upper,
+      _ => c,
+    }
+  }
+}
+
+impl Default for Alphabet {
+  fn default() -> Self {
+    Self {
+      symbols: Self::LATIN.chars().collect(),
+      fold_case: true,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_shift_keeps_case_and_other_symbols() {
+    let alphabet = Alphabet::default();
+    assert_eq!(alphabet.shift('a', 3), 'd');
+    assert_eq!(alphabet.shift('Z', 1), 'A');
+    assert_eq!(alphabet.shift('B', -3), 'Y');
+    assert_eq!(alphabet.shift('7', 3), '7');
+    assert_eq!(alphabet.normalize("Attack, é!"), "ATTACK, é!");
+  }
+
+  #[test]
+  fn test_custom_alphabet() -> Result<()> {
+    let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ 0123456789\n")?;
+    assert_eq!(alphabet.len(), 36);
+    assert_eq!(alphabet.shift('z', 1), '0');
+    assert_eq!(alphabet.shift('9', 1), 'A');
+    assert_eq!(alphabet.key_shifts("b2")?, [1, 28]);
+    assert!(alphabet.key_shifts("b-2").is_err());
+
+    let mixed = Alphabet::new("abcABC")?;
+    assert_eq!(mixed.shift('c', 1), 'A');
+    assert_eq!(mixed.index('D'), None);
+    Ok(())
+  }
+
+  #[test]
+  fn test_unicode_alphabets() -> Result<()> {
+    let cyrillic = Alphabet::new(Alphabet::CYRILLIC)?;
+    assert_eq!(cyrillic.len(), 33);
+    assert_eq!(cyrillic.shift('я', 1), 'а');
+    assert_eq!(cyrillic.shift('Е', 1), 'Ё');
+
+    let greek = Alphabet::new(Alphabet::GREEK)?;
+    assert_eq!(greek.len(), 24);
+    assert_eq!(greek.shift('ω', 3), 'γ');
+    assert_eq!(greek.index('ς'), greek.index('Σ'));
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_alphabet() {
+    assert!(Alphabet::new("A").is_err());
+    let error = Alphabet::new("ABCA").unwrap_err();
+    assert_eq!(error.to_string(), "Symbol A occurs twice in the alphabet");
+  }
+
+  #[test]
+  fn test_empty_key() {
+    let alphabet = Alphabet::default();
+    for key in ["", " \t"] {
+      let error = alphabet.key_shifts(key).unwrap_err();
+      assert_eq!(error.to_string(), "Key must not be empty");
+    }
+  }
+}
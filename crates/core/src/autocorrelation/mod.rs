@@ -0,0 +1,160 @@
+use std::io::{Read, Result, Write};
+
+/// Width of the longest bar of the plot.
+const BAR_WIDTH: usize = 50;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AutocorrelationConfig {
+  pub max_shift: usize,
+}
+
+impl AutocorrelationConfig {
+  pub fn new(max_shift: usize) -> Self {
+    Self { max_shift }
+  }
+}
+
+impl Default for AutocorrelationConfig {
+  fn default() -> Self {
+    Self { max_shift: 20 }
+  }
+}
+
+pub struct Autocorrelation;
+
+impl Autocorrelation {
+  /// Plots the number of coincidences for every shift of the input.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: AutocorrelationConfig,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let coincidences = Self::coincidences(&content, config.max_shift);
+    let max = coincidences
+      .iter()
+      .copied()
+      .max()
+      .unwrap_or_default()
+      .max(1);
+
+    writeln!(output, "Shift | Coincidences")?;
+    for (shift, &count) in coincidences.iter().enumerate() {
+      let bar = "#".repeat(count * BAR_WIDTH / max);
+      let line = format!("{:>5} | {count:>12} {bar}", shift + 1);
+      writeln!(output, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+  }
+
+  /// Counts the positions at which the letters of the text equal the
+  /// letters shifted by `1..=max_shift` places.
+  ///
+  /// Shifts that are multiples of the period of a polyalphabetic key
+  /// line up letters encrypted with the same alphabet, so they show
+  /// about as many coincidences as English text.
+  pub fn coincidences(text: &str, max_shift: usize) -> Vec<usize> {
+    let letters: Vec<u8> = text
+      .bytes()
+      .filter(u8::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase())
+      .collect();
+
+    (1..=max_shift)
+      .map(|shift| {
+        letters
+          .iter()
+          .zip(letters.iter().skip(shift))
+          .filter(|(a, b)| a == b)
+          .count()
+      })
+      .collect()
+  }
+
+  /// Estimates the period as the smallest shift whose coincidence rate
+  /// is close to the highest one.
+  ///
+  /// Preferring the smallest shift avoids picking a multiple of the
+  /// period. Returns `None` if there are no coincidences at all.
+  pub fn estimate_period(text: &str, max_shift: usize) -> Option<usize> {
+    let length = text.bytes().filter(u8::is_ascii_alphabetic).count();
+    let rates: Vec<f32> = Self::coincidences(text, max_shift)
+      .into_iter()
+      .enumerate()
+      .map(|(index, count)| {
+        let overlap = length.saturating_sub(index + 1).max(1);
+        count as f32 / overlap as f32
+      })
+      .collect();
+
+    let best = rates.iter().copied().fold(0.0, f32::max);
+    if best == 0.0 {
+      return None;
+    }
+
+    rates
+      .iter()
+      .position(|&rate| rate >= 0.85 * best)
+      .map(|index| index + 1)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::vigenere::{Vigenere, VigenereEncryptConfig};
+  use std::env;
+  use std::fs::File;
+  use std::path::PathBuf;
+
+  fn encrypt(key: &str) -> String {
+    let assets = "src/vigenere/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let mut plaintext = File::open(path.join("output.txt")).unwrap();
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new(key);
+    Vigenere::encrypt(&mut plaintext, &mut cipher, config).unwrap();
+    String::from_utf8(cipher).unwrap()
+  }
+
+  #[test]
+  fn test_coincidences() {
+    assert_eq!(Autocorrelation::coincidences("ABAB", 3), vec![0, 2, 0]);
+    assert_eq!(Autocorrelation::coincidences("a-a a", 1), vec![2]);
+  }
+
+  #[test]
+  fn test_estimate_period() {
+    let cipher = encrypt("LEMON");
+    assert_eq!(Autocorrelation::estimate_period(&cipher, 20), Some(5));
+    let cipher = encrypt("CRYPTOLOGY");
+    assert_eq!(Autocorrelation::estimate_period(&cipher, 20), Some(10));
+    assert_eq!(Autocorrelation::estimate_period("ABCDEF", 3), None);
+  }
+
+  #[test]
+  fn test_plot() -> Result<()> {
+    let mut output = Vec::new();
+    let config = AutocorrelationConfig::new(2);
+    Autocorrelation::execute(&mut "ABAB".as_bytes(), &mut output, config)?;
+
+    let plot = String::from_utf8(output).unwrap();
+    let bar = "#".repeat(BAR_WIDTH);
+    assert_eq!(
+      plot,
+      format!("Shift | Coincidences\n    1 |            0\n    2 |            2 {bar}\n")
+    );
+    Ok(())
+  }
+}
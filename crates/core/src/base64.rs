@@ -0,0 +1,107 @@
+use std::io::{Error, ErrorKind, Result};
+
+const ALPHABET: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 as described in RFC 4648.
+pub struct Base64;
+
+impl Base64 {
+  pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+      let block = chunk
+        .iter()
+        .enumerate()
+        .fold(0u32, |block, (i, &b)| block | (b as u32) << (16 - 8 * i));
+
+      for i in 0..4 {
+        if i <= chunk.len() {
+          let index = (block >> (18 - 6 * i) & 0x3f) as usize;
+          encoded.push(ALPHABET[index] as char);
+        } else {
+          encoded.push('=');
+        }
+      }
+    }
+
+    encoded
+  }
+
+  /// Decodes base64, ignoring whitespace. Padding is optional and the
+  /// URL-safe characters `-` and `_` are accepted as well.
+  pub fn decode(text: &str) -> Result<Vec<u8>> {
+    let text: Vec<u8> =
+      text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    let data = text.strip_suffix(b"==").or_else(|| text.strip_suffix(b"="));
+    let data = data.unwrap_or(&text);
+
+    if data.len() % 4 == 1 {
+      let message = "Base64 has invalid length";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+
+    for chunk in data.chunks(4) {
+      let mut block = 0u32;
+      for (i, &c) in chunk.iter().enumerate() {
+        let value = Self::value(c).ok_or_else(|| {
+          let message = format!("Invalid base64 character '{}'", c as char);
+          Error::new(ErrorKind::InvalidInput, message)
+        })?;
+        block |= (value as u32) << (18 - 6 * i);
+      }
+
+      for i in 0..chunk.len() - 1 {
+        bytes.push((block >> (16 - 8 * i)) as u8);
+      }
+    }
+
+    Ok(bytes)
+  }
+
+  pub fn is_base64_char(c: u8) -> bool {
+    Self::value(c).is_some()
+  }
+
+  fn value(c: u8) -> Option<u8> {
+    match c {
+      b'A'..=b'Z' => Some(c - b'A'),
+      b'a'..=b'z' => Some(c - b'a' + 26),
+      b'0'..=b'9' => Some(c - b'0' + 52),
+      b'+' | b'-' => Some(62),
+      b'/' | b'_' => Some(63),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_encode() {
+    assert_eq!(Base64::encode(b""), "");
+    assert_eq!(Base64::encode(b"f"), "Zg==");
+    assert_eq!(Base64::encode(b"fo"), "Zm8=");
+    assert_eq!(Base64::encode(b"foo"), "Zm9v");
+    assert_eq!(Base64::encode(b"foobar"), "Zm9vYmFy");
+  }
+
+  #[test]
+  fn test_decode() {
+    assert_eq!(Base64::decode("Zg==").unwrap(), b"f");
+    assert_eq!(Base64::decode("Zm8").unwrap(), b"fo");
+    assert_eq!(Base64::decode("Zm9v\nYmFy").unwrap(), b"foobar");
+    assert_eq!(Base64::decode("_-8=").unwrap(), vec![0xff, 0xef]);
+  }
+
+  #[test]
+  fn test_decode_rejects_invalid_input() {
+    assert!(Base64::decode("Zm9vY").is_err());
+    assert!(Base64::decode("Zm9v!A==").is_err());
+  }
+}
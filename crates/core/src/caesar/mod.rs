@@ -0,0 +1,483 @@
+use rayon::prelude::*;
+
+use std::{
+  io::{Cursor, Read, Result, Write},
+  sync::{Arc, Mutex},
+};
+
+use crate::{
+  alphabet::Alphabet,
+  frequency_analysis::{FrequencyAnalyzer, Language},
+  report::CrackReport,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CaesarConfig {
+  /// The symbols that are shifted, `A..=Z` by default.
+  pub alphabet: Alphabet,
+  /// The language whose letter frequencies the shifts are scored with.
+  pub language: Language,
+}
+
+impl CaesarConfig {
+  pub fn new(alphabet: Alphabet, language: Language) -> Self {
+    Self { alphabet, language }
+  }
+}
+
+pub struct Caesar;
+
+impl Caesar {
+  /// Writes the plaintext of every line and returns the reports of
+  /// [`Caesar::crack`].
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: CaesarConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let reports = Self::crack(input, &config)?;
+
+    for report in &reports {
+      writeln!(output, "{}", report.plaintext)?;
+    }
+
+    Ok(reports)
+  }
+
+  /// Shifts every symbol of the alphabet in the input forward by
+  /// `shift` and writes the ciphertext.
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    shift: usize,
+    alphabet: &Alphabet,
+  ) -> Result<()> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let shift = (shift % alphabet.len().max(1)) as isize;
+    let cipher: String =
+      content.chars().map(|c| alphabet.shift(c, shift)).collect();
+    write!(output, "{cipher}")
+  }
+
+  /// Cracks every line like [`Caesar::decrypt`] and reports the shift
+  /// as the key.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &CaesarConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    content
+      .lines()
+      .map(|line| {
+        let mut line = Cursor::new(line);
+        let (plaintext, shift) =
+          Self::find_best_shift(&mut line, &config.alphabet, config.language)?;
+        let key = Some(shift.to_string());
+        let method = "frequency analysis";
+        Ok(CrackReport::new_in(plaintext, key, method, config.language))
+      })
+      .collect()
+  }
+}
+
+impl Caesar {
+  /// Decrypts the text with every shift of the alphabet and ranks the
+  /// candidates by how close their letters are to those of the language,
+  /// best first. The shift is reported as the key.
+  pub fn rank(text: &str, config: &CaesarConfig) -> Vec<CrackReport> {
+    let mut candidates: Vec<(f32, usize, String)> = (0..config.alphabet.len())
+      .into_par_iter()
+      .map(|shift| {
+        let plaintext: String = text
+          .chars()
+          .map(|c| config.alphabet.shift(c, -(shift as isize)))
+          .collect();
+        let mut bytes = plaintext.as_bytes();
+        let score =
+          FrequencyAnalyzer::score_text_in(&mut bytes, config.language)
+            .unwrap_or(f32::MAX);
+        (score, shift, plaintext)
+      })
+      .collect();
+
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+    candidates
+      .into_iter()
+      .map(|(_, shift, plaintext)| {
+        let key = Some(shift.to_string());
+        let method = "frequency analysis";
+        CrackReport::new_in(plaintext, key, method, config.language)
+      })
+      .collect()
+  }
+
+  /// Tries every shift of the alphabet and returns the plaintext whose
+  /// letters are closest to those of `language`, together with its shift.
+  pub fn find_best_shift<R: Read>(
+    input: &mut R,
+    alphabet: &Alphabet,
+    language: Language,
+  ) -> Result<(String, usize)> {
+    let best_score = Arc::new(Mutex::new(f32::MAX));
+    let best_plaintext = Arc::new(Mutex::new(String::new()));
+    let best_shift = Arc::new(Mutex::new(0usize));
+    let mut buf = String::new();
+
+    input.read_to_string(&mut buf)?;
+
+    (0..alphabet.len()).into_par_iter().for_each(|shift| {
+      let copy = buf.clone();
+      let mut cursor = Cursor::new(copy.as_bytes());
+      let candidate =
+        Self::decrypt_cipher(&mut cursor, shift, alphabet).unwrap();
+      let mut buf = Cursor::new(candidate.as_bytes());
+
+      if let Ok(score) = FrequencyAnalyzer::score_text_in(&mut buf, language) {
+        let mut best_score_guard = best_score.lock().unwrap();
+        if score < *best_score_guard {
+          *best_score_guard = score;
+          *best_plaintext.lock().unwrap() = candidate;
+          *best_shift.lock().unwrap() = shift;
+        }
+      }
+    });
+
+    let plaintext = Arc::try_unwrap(best_plaintext)
+      .unwrap()
+      .into_inner()
+      .unwrap();
+
+    let shift = Arc::try_unwrap(best_shift).unwrap().into_inner().unwrap();
+
+    Ok((plaintext, shift))
+  }
+
+  pub fn decrypt_cipher<R: Read>(
+    input: &mut R,
+    shift: usize,
+    alphabet: &Alphabet,
+  ) -> Result<String> {
+    let mut buf = String::new();
+    input.read_to_string(&mut buf)?;
+
+    let plaintext = buf
+      .chars()
+      .map(|c| alphabet.shift(c, -(shift as isize)))
+      .collect();
+
+    Ok(plaintext)
+  }
+
+  pub fn shift(c: char, key_char: char, direction: i8) -> char {
+    let base = if c.is_ascii_lowercase() { b'a' } else { b'A' };
+    let key_shift = key_char as u8 - b'A';
+    let shift = (26 + direction * (key_shift as i8)) % 26;
+    (((c as u8 - base + shift as u8) % 26) + base) as char
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::env;
+  use std::fs::File;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_example_output() -> Result<()> {
+    let assets = "src/caesar/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let input_path = path.join("input.txt");
+    let output_path = path.join("output.txt");
+
+    let mut input_file = File::open(&input_path)?;
+    let mut output_buffer = Vec::new();
+    let config = CaesarConfig::default();
+
+    Caesar::decrypt(&mut input_file, &mut output_buffer, config)?;
+
+    let mut expected_output = String::new();
+    File::open(&output_path)?.read_to_string(&mut expected_output)?;
+
+    let output_string = String::from_utf8(output_buffer).unwrap();
+
+    assert_eq!(output_string, expected_output);
+    Ok(())
+  }
+
+  #[test]
+  fn test_example_output_2() -> Result<()> {
+    let assets = "src/caesar/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let input_path = path.join("input2.txt");
+    let output_path = path.join("output2.txt");
+
+    let mut input_file = File::open(&input_path)?;
+    let mut output_buffer = Vec::new();
+    let config = CaesarConfig::default();
+
+    Caesar::decrypt(&mut input_file, &mut output_buffer, config)?;
+
+    let mut expected_output = String::new();
+    File::open(&output_path)?.read_to_string(&mut expected_output)?;
+
+    let output_string = String::from_utf8(output_buffer).unwrap();
+
+    assert_eq!(output_string, expected_output);
+    Ok(())
+  }
+
+  #[test]
+  fn test_decipher_with_shift_0() -> Result<()> {
+    let mut input = Cursor::new("No shift should keep the text unchanged.");
+    let mut output = Vec::new();
+
+    Caesar::decrypt(&mut input, &mut output, CaesarConfig::default())?;
+
+    let output_string = String::from_utf8(output).unwrap();
+
+    assert_eq!(
+      output_string.trim(),
+      "No shift should keep the text unchanged."
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_decipher_with_known_shift() -> Result<()> {
+    let mut input = Cursor::new("Uif tfdsfu jt tbgf!");
+    let mut output = Vec::new();
+
+    Caesar::decrypt(&mut input, &mut output, CaesarConfig::default())?;
+
+    let output_string = String::from_utf8(output).unwrap();
+
+    assert_eq!(output_string.trim(), "The secret is safe!");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decipher_with_punctuation() -> Result<()> {
+    let mut input = Cursor::new("Efgfoe! B cpoh.");
+    let mut output = Vec::new();
+
+    Caesar::decrypt(&mut input, &mut output, CaesarConfig::default())?;
+
+    let output_string = String::from_utf8(output).unwrap();
+
+    assert_eq!(output_string.trim(), "Defend! A bong.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decipher_handles_empty_input() -> Result<()> {
+    let mut input = Cursor::new("");
+    let mut output = Vec::new();
+
+    Caesar::decrypt(&mut input, &mut output, CaesarConfig::default())?;
+
+    let output_string = String::from_utf8(output).unwrap();
+
+    assert_eq!(output_string.trim(), "");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decipher_handles_non_ascii_characters() -> Result<()> {
+    let mut input = Cursor::new("¡Hola! ¿Cómo estás?");
+    let mut output = Vec::new();
+
+    Caesar::decrypt(&mut input, &mut output, CaesarConfig::default())?;
+
+    let output_string = String::from_utf8(output).unwrap();
+
+    assert_eq!(output_string.trim(), "¡Hola! ¿Cómo estás?");
+    Ok(())
+  }
+
+  #[test]
+  fn test_find_best_shift() -> Result<()> {
+    let mut input = Cursor::new("Dro aesmu lbygx pyh tewzc yfob dro vkji nyq.");
+    let (plaintext, shift) = Caesar::find_best_shift(
+      &mut input,
+      &Alphabet::default(),
+      Language::English,
+    )?;
+
+    assert_eq!(plaintext, "The quick brown fox jumps over the lazy dog.");
+    assert_eq!(shift, 10);
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_shift_0() -> Result<()> {
+    let mut input = Cursor::new("No shift should keep the text unchanged.");
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 0, &Alphabet::default())?;
+
+    assert_eq!(decrypted, "No shift should keep the text unchanged.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_shift_13() -> Result<()> {
+    let mut input = Cursor::new("Gur fhowrpg vf onfrq ba gur cnfg.");
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 13, &Alphabet::default())?;
+
+    assert_eq!(decrypted, "The subject is based on the past.");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_with_mixed_case() -> Result<()> {
+    let mut input =
+      Cursor::new("Uifsf bsf TPNF ipnf ubtl uibu offet pme GFEvsbujpo.");
+
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 1, &Alphabet::default())?;
+
+    assert_eq!(
+      decrypted,
+      "There are SOME home task that needs old FEDuration."
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_empty_string() -> Result<()> {
+    let mut input = Cursor::new("");
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 5, &Alphabet::default())?;
+
+    assert_eq!(decrypted, "");
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_all_letters_shift_13() -> Result<()> {
+    let mut input =
+      Cursor::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 13, &Alphabet::default())?;
+
+    assert_eq!(
+      decrypted,
+      "NOPQRSTUVWXYZABCDEFGHIJKLMnopqrstuvwxyzabcdefghijklm"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_cipher_numbers_and_symbols() -> Result<()> {
+    let mut input = Cursor::new("12345 !@#$%^&*()_+");
+    let decrypted =
+      Caesar::decrypt_cipher(&mut input, 7, &Alphabet::default())?;
+
+    assert_eq!(decrypted, "12345 !@#$%^&*()_+");
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_reports_shift() -> Result<()> {
+    let cipher = "Wkh vhfuhw phvvdjh lv wkdw wkh hqhpb lv dw wkh jdwhv";
+    let reports =
+      Caesar::crack(&mut cipher.as_bytes(), &CaesarConfig::default())?;
+
+    assert_eq!(reports.len(), 1);
+    assert_eq!(
+      reports[0].plaintext,
+      "The secret message is that the enemy is at the gates"
+    );
+    assert_eq!(reports[0].key.as_deref(), Some("3"));
+    assert!(reports[0].score.is_some());
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_with_custom_alphabet() -> Result<()> {
+    let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")?;
+    // Digits are not lowercase, so the case of the plaintext is lost.
+    let plaintext = "THE SECRET MESSAGE IS THAT THE ENEMY IS AT THE GATES";
+    let cipher: String =
+      plaintext.chars().map(|c| alphabet.shift(c, 30)).collect();
+    assert!(cipher.chars().any(|c| c.is_ascii_digit()));
+
+    let config = CaesarConfig::new(alphabet, Language::English);
+    let reports = Caesar::crack(&mut cipher.as_bytes(), &config)?;
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert_eq!(reports[0].key.as_deref(), Some("30"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_russian() -> Result<()> {
+    let alphabet = Language::Russian.alphabet();
+    let plaintext = "Враг будет разбит, победа будет за нами";
+    let cipher: String =
+      plaintext.chars().map(|c| alphabet.shift(c, 7)).collect();
+
+    let config = CaesarConfig::new(alphabet, Language::Russian);
+    let reports = Caesar::crack(&mut cipher.as_bytes(), &config)?;
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert_eq!(reports[0].key.as_deref(), Some("7"));
+    assert!(reports[0].score.is_some());
+    assert!(reports[0].to_json().contains(r#""confidence":0.9"#));
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_greek() -> Result<()> {
+    let alphabet = Language::Greek.alphabet();
+    let plaintext =
+      "ΓΝΩΘΙ ΣΑΥΤΟΝ ΚΑΙ ΜΗΔΕΝ ΑΓΑΝ ΕΙΠΕ Ο ΣΟΦΟΣ ΤΟΙΣ ΜΑΘΗΤΑΙΣ ΑΥΤΟΥ";
+    let cipher: String =
+      plaintext.chars().map(|c| alphabet.shift(c, 5)).collect();
+
+    let config = CaesarConfig::new(alphabet, Language::Greek);
+    let reports = Caesar::crack(&mut cipher.as_bytes(), &config)?;
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert!(reports[0].score.is_some());
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn test_encrypt() -> Result<()> {
+    let mut output = Vec::new();
+    let mut input = Cursor::new("The secret is safe!\n");
+    Caesar::encrypt(&mut input, &mut output, 29, &Alphabet::default())?;
+    assert_eq!(String::from_utf8(output).unwrap(), "Wkh vhfuhw lv vdih!\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_rank() {
+    let config = CaesarConfig::default();
+    let candidates = Caesar::rank("WKH HQHPB DWWDFNV DW GDZQ", &config);
+    assert_eq!(candidates.len(), 26);
+    assert_eq!(candidates[0].plaintext, "THE ENEMY ATTACKS AT DAWN");
+    assert_eq!(candidates[0].key.as_deref(), Some("3"));
+  }
+}
@@ -0,0 +1,448 @@
+use rayon::prelude::*;
+
+use std::iter;
+use std::rc::Rc;
+
+use crate::report::CrackReport;
+
+/// Number of candidate keys that are scored in parallel before the best
+/// of them is checked against the confidence to stop at.
+const BATCH_SIZE: usize = 4096;
+
+/// Letters that digits and symbols commonly stand for.
+pub const LEET: [(char, &[char]); 9] = [
+  ('0', &['o']),
+  ('1', &['i', 'l']),
+  ('3', &['e']),
+  ('4', &['a']),
+  ('5', &['s']),
+  ('7', &['t']),
+  ('@', &['a']),
+  ('$', &['s']),
+  ('!', &['i']),
+];
+
+/// Most letters whose case is toggled, which limits a word to 2^12
+/// case variants.
+pub const MAX_TOGGLE_LETTERS: usize = 12;
+
+/// A way the case of a word is changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum CaseRule {
+  /// All letters lowercase.
+  Lower,
+  /// All letters uppercase.
+  Upper,
+  /// The first letter uppercase, the others lowercase.
+  Capitalize,
+  /// Every combination of lowercase and uppercase letters, which are
+  /// 2^n words for n letters. Words of more than 12 letters are left as
+  /// they are.
+  Toggle,
+}
+
+/// Turns a word of a wordlist into the keys that are tried for it. The
+/// keys are yielded one by one, so that words with many keys are never
+/// held in memory at once.
+pub trait Transform: Sync {
+  fn keys<'a>(&'a self, word: &'a str)
+    -> Box<dyn Iterator<Item = String> + 'a>;
+}
+
+impl<F: Fn(&str) -> Vec<String> + Sync> Transform for F {
+  fn keys<'a>(
+    &'a self,
+    word: &'a str,
+  ) -> Box<dyn Iterator<Item = String> + 'a> {
+    Box::new(self(word).into_iter())
+  }
+}
+
+/// Every word with one of the options at each position, the last
+/// position changing fastest.
+struct Spellings {
+  options: Vec<Vec<char>>,
+  choices: Vec<usize>,
+  done: bool,
+}
+
+impl Spellings {
+  fn new(options: Vec<Vec<char>>) -> Self {
+    let choices = vec![0; options.len()];
+    Self {
+      options,
+      choices,
+      done: false,
+    }
+  }
+}
+
+impl Iterator for Spellings {
+  type Item = String;
+
+  fn next(&mut self) -> Option<String> {
+    if self.done {
+      return None;
+    }
+    let spelling = self
+      .options
+      .iter()
+      .zip(&self.choices)
+      .map(|(options, &choice)| options[choice])
+      .collect();
+
+    self.done = true;
+    for (options, choice) in self.options.iter().zip(&mut self.choices).rev() {
+      *choice += 1;
+      if *choice < options.len() {
+        self.done = false;
+        break;
+      }
+      *choice = 0;
+    }
+
+    Some(spelling)
+  }
+}
+
+/// The common changes people make to a word to turn it into a password.
+/// Without any rule only the word itself is tried.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Mangling {
+  /// The case changes whose words are added.
+  pub case: Vec<CaseRule>,
+  /// Whether every combination of leet substitutions is added.
+  pub leet: bool,
+  /// Most digits that are appended to every word.
+  pub digits: usize,
+}
+
+impl Mangling {
+  pub fn new(case: Vec<CaseRule>, leet: bool, digits: usize) -> Self {
+    Self { case, leet, digits }
+  }
+
+  /// The words a case rule turns the word into.
+  pub fn case(word: &str, rule: CaseRule) -> Vec<String> {
+    match rule {
+      CaseRule::Lower => vec![word.to_lowercase()],
+      CaseRule::Upper => vec![word.to_uppercase()],
+      CaseRule::Capitalize => {
+        let mut chars = word.chars();
+        let capitalized = match chars.next() {
+          Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase)),
+          None => return vec![String::new()],
+        };
+        vec![capitalized.collect()]
+      }
+      CaseRule::Toggle => {
+        let options: Vec<Vec<char>> = word
+          .chars()
+          .map(|c| {
+            let (lower, upper) =
+              (c.to_ascii_lowercase(), c.to_ascii_uppercase());
+            match lower == upper {
+              true => vec![c],
+              false => vec![lower, upper],
+            }
+          })
+          .collect();
+        let letters =
+          options.iter().filter(|options| options.len() > 1).count();
+        match letters > MAX_TOGGLE_LETTERS {
+          true => vec![word.to_string()],
+          false => Spellings::new(options).collect(),
+        }
+      }
+    }
+  }
+
+  /// Every spelling of the word with some of its letters replaced by
+  /// the digits and symbols that commonly stand for them, without the
+  /// word itself.
+  pub fn leet(word: &str) -> impl Iterator<Item = String> {
+    let options = word
+      .chars()
+      .map(|c| {
+        let mut options = vec![c];
+        options.extend(
+          LEET
+            .iter()
+            .filter(|(_, letters)| Self::stands_for(letters, c))
+            .map(|(symbol, _)| *symbol),
+        );
+        options
+      })
+      .collect();
+    Spellings::new(options).skip(1)
+  }
+
+  /// The empty suffix followed by all numbers of one up to `digits`
+  /// digits, including those with leading zeros.
+  pub fn suffixes(digits: usize) -> impl Iterator<Item = String> + Clone {
+    iter::once(String::new()).chain((1..=digits).flat_map(|width| {
+      (0..10usize.pow(width as u32)).map(move |n| format!("{n:0width$}"))
+    }))
+  }
+
+  /// Whether the leet spelling can be made of the word, which holds if
+  /// every character is the same or a symbol for the letter.
+  fn spells(word: &str, spelling: &str) -> bool {
+    word.chars().count() == spelling.chars().count()
+      && word.chars().zip(spelling.chars()).all(|(c, s)| {
+        c == s
+          || LEET.iter().any(|(symbol, letters)| {
+            *symbol == s && Self::stands_for(letters, c)
+          })
+      })
+  }
+
+  fn stands_for(letters: &[char], c: char) -> bool {
+    letters.contains(&c.to_ascii_lowercase())
+  }
+}
+
+impl Transform for Mangling {
+  /// The word itself followed by its case changes, the leet spellings of
+  /// all of them and each of those with every suffix, every key once.
+  ///
+  /// Only the case changes are collected. A leet spelling is skipped if
+  /// an earlier case change spells it as well, and a suffix never makes
+  /// two keys of the same word equal, so no other key is remembered.
+  fn keys<'a>(
+    &'a self,
+    word: &'a str,
+  ) -> Box<dyn Iterator<Item = String> + 'a> {
+    let mut words = vec![word.to_string()];
+    for rule in &self.case {
+      for changed in Self::case(word, *rule) {
+        if !words.contains(&changed) {
+          words.push(changed);
+        }
+      }
+    }
+    let words = Rc::new(words);
+
+    let spellings = (0..words.len()).flat_map(move |index| {
+      let words = words.clone();
+      let word = words[index].clone();
+      let leet = match self.leet {
+        true => Some(Self::leet(&word).filter(move |spelling| {
+          !words[..index]
+            .iter()
+            .any(|word| Self::spells(word, spelling))
+        })),
+        false => None,
+      };
+      iter::once(word.clone()).chain(leet.into_iter().flatten())
+    });
+
+    let suffixes = Self::suffixes(self.digits);
+    Box::new(spellings.flat_map(move |word| {
+      suffixes.clone().map(move |suffix| word.clone() + &suffix)
+    }))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictionaryAttackConfig {
+  /// How every word is turned into keys.
+  pub mangling: Mangling,
+  /// Confidence of the best plaintext at which no further words are
+  /// tried. The whole wordlist is tried without one.
+  pub stop_at: Option<f32>,
+  /// Number of best plaintexts that are kept.
+  pub top: usize,
+}
+
+impl DictionaryAttackConfig {
+  pub fn new(mangling: Mangling, stop_at: Option<f32>, top: usize) -> Self {
+    Self {
+      mangling,
+      stop_at,
+      top,
+    }
+  }
+}
+
+impl Default for DictionaryAttackConfig {
+  fn default() -> Self {
+    Self::new(Mangling::default(), None, 1)
+  }
+}
+
+/// Tries the words of a wordlist as keys of any cipher, which supplies
+/// how a key decrypts the ciphertext.
+pub struct DictionaryAttack;
+
+impl DictionaryAttack {
+  /// The words of a wordlist, separated by whitespace. Lines starting
+  /// with `#` are comments.
+  pub fn words(wordlist: &str) -> impl Iterator<Item = &str> {
+    wordlist
+      .lines()
+      .filter(|line| !line.starts_with('#'))
+      .flat_map(str::split_whitespace)
+  }
+
+  /// The keys the transform turns the words of the wordlist into, in
+  /// the order of the words.
+  pub fn candidates<'a, T: Transform + ?Sized>(
+    wordlist: &'a str,
+    transform: &'a T,
+  ) -> impl Iterator<Item = String> + 'a {
+    Self::words(wordlist).flat_map(|word| transform.keys(word))
+  }
+
+  /// Decrypts with the keys of the wordlist mangled by the config and
+  /// returns the best plaintexts, see [`DictionaryAttack::run_with`].
+  pub fn run<D>(
+    wordlist: &str,
+    config: &DictionaryAttackConfig,
+    decrypt: D,
+  ) -> Vec<CrackReport>
+  where
+    D: Fn(&str) -> Option<CrackReport> + Sync,
+  {
+    Self::run_with(wordlist, &config.mangling, config, decrypt)
+  }
+
+  /// Decrypts with every key the transform yields for the words of the
+  /// wordlist and returns the `top` best plaintexts, most confident
+  /// first. Keys the cipher rejects are skipped by returning `None`.
+  ///
+  /// The keys are scored in parallel batches, and no further batch is
+  /// tried once the best plaintext reaches the confidence to stop at.
+  /// Equally confident plaintexts are ordered by their score and then by
+  /// the position of their key in the wordlist.
+  pub fn run_with<T, D>(
+    wordlist: &str,
+    transform: &T,
+    config: &DictionaryAttackConfig,
+    decrypt: D,
+  ) -> Vec<CrackReport>
+  where
+    T: Transform + ?Sized,
+    D: Fn(&str) -> Option<CrackReport> + Sync,
+  {
+    let mut candidates = Self::candidates(wordlist, transform);
+    let mut best: Vec<CrackReport> = Vec::new();
+
+    loop {
+      let batch: Vec<String> = candidates.by_ref().take(BATCH_SIZE).collect();
+      if batch.is_empty() {
+        break;
+      }
+      best.extend(
+        batch
+          .par_iter()
+          .filter_map(|key| decrypt(key))
+          .collect::<Vec<_>>(),
+      );
+      Self::rank(&mut best);
+      best.truncate(config.top.max(1));
+
+      let confidence = best.first().and_then(|report| report.confidence);
+      if let (Some(stop_at), Some(confidence)) = (config.stop_at, confidence) {
+        if confidence >= stop_at {
+          break;
+        }
+      }
+    }
+
+    best
+  }
+
+  fn rank(reports: &mut [CrackReport]) {
+    reports.sort_by(|a, b| {
+      let confidence = |report: &CrackReport| report.confidence.unwrap_or(-1.0);
+      let score = |report: &CrackReport| report.score.unwrap_or(f32::MAX);
+      confidence(b)
+        .total_cmp(&confidence(a))
+        .then(score(a).total_cmp(&score(b)))
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_mangling() {
+    assert_eq!(Mangling::case("kEY", CaseRule::Capitalize), ["Key"]);
+    assert_eq!(
+      Mangling::case("a1b", CaseRule::Toggle),
+      ["a1b", "a1B", "A1b", "A1B"]
+    );
+
+    let leet: Vec<String> = Mangling::leet("sea").collect();
+    assert_eq!(leet.len(), 17);
+    assert_eq!(&leet[..3], ["se4", "se@", "s3a"]);
+
+    assert_eq!(Mangling::suffixes(2).count(), 111);
+    assert_eq!(Mangling::suffixes(2).nth(11).as_deref(), Some("00"));
+
+    let mangling = Mangling::new(vec![CaseRule::Upper], true, 1);
+    let keys: Vec<String> = mangling.keys("ice").collect();
+    assert_eq!(&keys[..3], ["ice", "ice0", "ice1"]);
+    assert!(keys.contains(&"1C3".to_string()));
+    let keys: Vec<String> = Mangling::default().keys("ice").collect();
+    assert_eq!(keys, ["ice"]);
+  }
+
+  #[test]
+  fn test_mangling_keys_are_unique() {
+    let rules = vec![CaseRule::Lower, CaseRule::Capitalize, CaseRule::Toggle];
+    let keys: Vec<String> = Mangling::new(rules, true, 1).keys("Ice").collect();
+    let unique: std::collections::HashSet<&String> = keys.iter().collect();
+    assert_eq!(keys.len(), unique.len());
+    assert!(keys.contains(&"1cE7".to_string()));
+  }
+
+  #[test]
+  fn test_mangling_long_word_is_lazy() {
+    let word = "password".repeat(8);
+    assert_eq!(Mangling::case(&word, CaseRule::Toggle), [word.clone()]);
+
+    let mangling = Mangling::new(vec![CaseRule::Toggle], true, 4);
+    let keys: Vec<String> = mangling.keys(&word).take(3).collect();
+    assert_eq!(keys[0], word);
+    assert_eq!(keys[2], format!("{word}1"));
+  }
+
+  #[test]
+  fn test_run_stops_at_confidence() {
+    let wordlist =
+      "# keys\nzzz target\nqqq\n".to_string() + &"filler ".repeat(BATCH_SIZE);
+    let decrypt = |key: &str| {
+      let plaintext = match key {
+        "target" => "the enemy is coming at dawn",
+        "qqq" => "qqq xzj vvk pwq",
+        _ => "zzzz qqqq xxxx",
+      };
+      Some(CrackReport::new(
+        plaintext.to_string(),
+        Some(key.into()),
+        "test",
+      ))
+    };
+
+    let config = DictionaryAttackConfig::new(Mangling::default(), None, 2);
+    let reports = DictionaryAttack::run(&wordlist, &config, decrypt);
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].key.as_deref(), Some("target"));
+
+    let calls = std::sync::atomic::AtomicUsize::new(0);
+    let config = DictionaryAttackConfig::new(Mangling::default(), Some(0.9), 1);
+    let reports = DictionaryAttack::run(&wordlist, &config, |key: &str| {
+      calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+      decrypt(key)
+    });
+    assert_eq!(reports[0].key.as_deref(), Some("target"));
+    assert_eq!(calls.into_inner(), BATCH_SIZE);
+  }
+}
@@ -0,0 +1,206 @@
+use std::{
+  fmt::Display,
+  io::{self, ErrorKind},
+  path::PathBuf,
+};
+
+use crate::hex::HexParseError;
+
+/// Errors caused by the input of the user rather than by a bug.
+///
+/// Functions that only read and write streams keep returning
+/// [`io::Result`]. A [`CryptologyError`] converted into an [`io::Error`]
+/// is wrapped rather than flattened, so converting it back recovers the
+/// original variant.
+#[derive(Debug)]
+pub enum CryptologyError {
+  /// An input file could not be opened.
+  Open { path: PathBuf, source: io::Error },
+  /// An output file could not be created.
+  Create { path: PathBuf, source: io::Error },
+  /// Invalid hex, with the line it was found on if the input has lines.
+  Hex {
+    line: Option<usize>,
+    source: HexParseError,
+  },
+  /// A key that is malformed or does not fit the message.
+  Key(String),
+  /// A cracked plaintext that is probably not English. The output is
+  /// still written, and the lowest confidence is kept.
+  LowConfidence(f32),
+  /// Some inputs of a batch failed, each of which was reported already.
+  Batch {
+    failed: usize,
+    total: usize,
+    last: Box<CryptologyError>,
+  },
+  /// Any other error while reading or writing.
+  Io(io::Error),
+}
+
+impl CryptologyError {
+  pub fn key(message: impl Into<String>) -> Self {
+    Self::Key(message.into())
+  }
+
+  /// Attaches a line number to a hex error that has none yet.
+  pub fn at_line(self, line: usize) -> Self {
+    match self {
+      Self::Hex { line: None, source } => Self::Hex {
+        line: Some(line),
+        source,
+      },
+      error => error,
+    }
+  }
+
+  /// Exit code following the sysexits convention of BSD.
+  ///
+  /// A crack with low confidence exits with 3, which is outside of that
+  /// range, so scripts can tell it apart from errors.
+  pub fn exit_code(&self) -> u8 {
+    match self {
+      Self::Open { .. } => 66,
+      Self::Create { .. } => 73,
+      Self::Hex { .. } | Self::Key(_) => 65,
+      Self::LowConfidence(_) => 3,
+      Self::Batch { last, .. } => last.exit_code(),
+      Self::Io(error) => match error.kind() {
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => 65,
+        ErrorKind::NotFound => 66,
+        _ => 74,
+      },
+    }
+  }
+}
+
+impl Display for CryptologyError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Open { path, source } => {
+        write!(f, "Failed to open input file {}: {source}", path.display())
+      }
+      Self::Create { path, source } => {
+        write!(
+          f,
+          "Failed to create output file {}: {source}",
+          path.display()
+        )
+      }
+      Self::Hex {
+        line: Some(line),
+        source,
+      } => write!(f, "Invalid hex on line {line}: {source}"),
+      Self::Hex { line: None, source } => write!(f, "Invalid hex: {source}"),
+      Self::Key(message) => write!(f, "Invalid key: {message}"),
+      Self::LowConfidence(confidence) => write!(
+        f,
+        "Low confidence of {confidence:.2} in the plaintext, the crack probably failed"
+      ),
+      Self::Batch { failed, total, .. } => {
+        write!(f, "{failed} of {total} inputs failed")
+      }
+      Self::Io(error) => write!(f, "{error}"),
+    }
+  }
+}
+
+impl std::error::Error for CryptologyError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      Self::Open { source, .. } | Self::Create { source, .. } => Some(source),
+      Self::Hex { source, .. } => Some(source),
+      Self::Key(_) | Self::LowConfidence(_) => None,
+      Self::Batch { last, .. } => Some(last.as_ref()),
+      Self::Io(error) => error.source(),
+    }
+  }
+}
+
+impl From<io::Error> for CryptologyError {
+  fn from(error: io::Error) -> Self {
+    match error.downcast::<Self>() {
+      Ok(error) => error,
+      Err(error) => Self::Io(error),
+    }
+  }
+}
+
+impl From<HexParseError> for CryptologyError {
+  fn from(source: HexParseError) -> Self {
+    Self::Hex { line: None, source }
+  }
+}
+
+impl From<CryptologyError> for io::Error {
+  fn from(error: CryptologyError) -> Self {
+    let kind = match error {
+      CryptologyError::Io(error) => return error,
+      CryptologyError::Open { ref source, .. }
+      | CryptologyError::Create { ref source, .. } => source.kind(),
+      CryptologyError::Hex { .. } | CryptologyError::Key(_) => {
+        ErrorKind::InvalidInput
+      }
+      CryptologyError::LowConfidence(_) => ErrorKind::Other,
+      CryptologyError::Batch { ref last, .. } => match **last {
+        CryptologyError::Io(ref error) => error.kind(),
+        _ => ErrorKind::Other,
+      },
+    };
+    io::Error::new(kind, error)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_roundtrip_through_io_error() {
+    let error = CryptologyError::Hex {
+      line: Some(3),
+      source: HexParseError::InvalidLength,
+    };
+    let error = io::Error::from(error);
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let error = CryptologyError::from(error);
+    assert!(matches!(error, CryptologyError::Hex { line: Some(3), .. }));
+    assert_eq!(
+      error.to_string(),
+      "Invalid hex on line 3: Hex string must have an even length."
+    );
+  }
+
+  #[test]
+  fn test_at_line() {
+    let error = CryptologyError::from(HexParseError::InvalidHex).at_line(2);
+    assert!(matches!(error, CryptologyError::Hex { line: Some(2), .. }));
+    let error = error.at_line(5);
+    assert!(matches!(error, CryptologyError::Hex { line: Some(2), .. }));
+  }
+
+  #[test]
+  fn test_exit_code() {
+    let open = CryptologyError::Open {
+      path: PathBuf::from("missing.txt"),
+      source: io::Error::from(ErrorKind::NotFound),
+    };
+    assert_eq!(open.exit_code(), 66);
+    assert_eq!(CryptologyError::key("too short").exit_code(), 65);
+
+    let io = CryptologyError::from(io::Error::from(ErrorKind::BrokenPipe));
+    assert_eq!(io.exit_code(), 74);
+
+    let low = io::Error::from(CryptologyError::LowConfidence(0.2));
+    assert_eq!(CryptologyError::from(low).exit_code(), 3);
+
+    let batch = CryptologyError::Batch {
+      failed: 1,
+      total: 2,
+      last: Box::new(open),
+    };
+    assert_eq!(batch.exit_code(), 66);
+    assert_eq!(batch.to_string(), "1 of 2 inputs failed");
+  }
+}
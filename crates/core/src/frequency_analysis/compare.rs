@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::{self, Read, Result, Write};
+
+use super::{FrequencyAnalysis, FrequencyAnalyzer};
+
+/// Relative frequency of a letter in two texts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetterDelta {
+  pub letter: char,
+  /// Percentage of the letter among the letters of the candidate.
+  pub candidate: f64,
+  /// Percentage of the letter among the letters of the reference.
+  pub reference: f64,
+}
+
+impl LetterDelta {
+  pub fn delta(&self) -> f64 {
+    self.candidate - self.reference
+  }
+}
+
+/// Letter frequencies of a candidate text compared to a reference text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyComparison {
+  /// Every letter of either text, the largest differences first.
+  pub deltas: Vec<LetterDelta>,
+  /// Jensen-Shannon divergence of the two distributions in bits, from 0
+  /// for equal frequencies to 1 for texts without a common letter.
+  pub divergence: f64,
+}
+
+impl FrequencyComparison {
+  pub fn new(
+    candidate: &FrequencyAnalysis,
+    reference: &FrequencyAnalysis,
+  ) -> Self {
+    let letters: BTreeSet<char> = candidate
+      .frequency
+      .keys()
+      .chain(reference.frequency.keys())
+      .copied()
+      .collect();
+
+    let share = |analysis: &FrequencyAnalysis, letter: char| {
+      let count = analysis.frequency.get(&letter).copied().unwrap_or(0);
+      match analysis.total_count {
+        0 => 0.0,
+        total => count as f64 / total as f64,
+      }
+    };
+
+    let mut divergence = 0.0;
+    let mut deltas = Vec::with_capacity(letters.len());
+    for letter in letters {
+      let p = share(candidate, letter);
+      let q = share(reference, letter);
+      let m = (p + q) / 2.0;
+      for x in [p, q] {
+        if x > 0.0 {
+          divergence += x * (x / m).log2() / 2.0;
+        }
+      }
+      deltas.push(LetterDelta {
+        letter,
+        candidate: p * 100.0,
+        reference: q * 100.0,
+      });
+    }
+
+    deltas.sort_by(|a, b| b.delta().abs().total_cmp(&a.delta().abs()));
+
+    Self { deltas, divergence }
+  }
+}
+
+impl Display for FrequencyComparison {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    writeln!(f, "| Letter | Candidate  | Reference  | Delta      |")?;
+    writeln!(f, "| ------ | ---------- | ---------- | ---------- |")?;
+    for delta in &self.deltas {
+      writeln!(
+        f,
+        "| {:<6} | {:>8.3} % | {:>8.3} % | {:>+8.3} % |",
+        delta.letter,
+        delta.candidate,
+        delta.reference,
+        delta.delta()
+      )?;
+    }
+    writeln!(f)?;
+    writeln!(f, "Jensen-Shannon divergence: {:.4}", self.divergence)
+  }
+}
+
+impl FrequencyAnalyzer {
+  /// Compares the letter frequencies of a candidate text to those of a
+  /// reference text, such as a decryption to a corpus of the language
+  /// it should be in.
+  pub fn compare<C: Read, R: Read, W: Write>(
+    candidate: &mut C,
+    reference: &mut R,
+    output: &mut W,
+  ) -> Result<FrequencyComparison> {
+    let candidate = Self::analyze(candidate, &mut io::sink())?;
+    let reference = Self::analyze(reference, &mut io::sink())?;
+    let comparison = FrequencyComparison::new(&candidate, &reference);
+    write!(output, "{comparison}")?;
+    Ok(comparison)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn compare(candidate: &str, reference: &str) -> FrequencyComparison {
+    FrequencyAnalyzer::compare(
+      &mut candidate.as_bytes(),
+      &mut reference.as_bytes(),
+      &mut io::sink(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn test_compare_equal_profiles() {
+    let comparison = compare("ABBA", "baab");
+    assert_eq!(comparison.divergence, 0.0);
+    assert!(comparison.deltas.iter().all(|delta| delta.delta() == 0.0));
+  }
+
+  #[test]
+  fn test_compare_disjoint_profiles() {
+    let comparison = compare("AAA", "BB");
+    assert_eq!(comparison.divergence, 1.0);
+    assert_eq!(comparison.deltas[0].candidate, 100.0);
+    assert_eq!(comparison.deltas[1].reference, 100.0);
+  }
+
+  #[test]
+  fn test_compare_output() -> Result<()> {
+    let mut output = Vec::new();
+    FrequencyAnalyzer::compare(
+      &mut "AAAB".as_bytes(),
+      &mut "AB".as_bytes(),
+      &mut output,
+    )?;
+    let expected = "\
+| Letter | Candidate  | Reference  | Delta      |
+| ------ | ---------- | ---------- | ---------- |
+| A      |   75.000 % |   50.000 % |  +25.000 % |
+| B      |   25.000 % |   50.000 % |  -25.000 % |
+
+Jensen-Shannon divergence: 0.0488
+";
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+    Ok(())
+  }
+}
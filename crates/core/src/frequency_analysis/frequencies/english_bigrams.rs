@@ -0,0 +1,45 @@
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use super::BigramFrequency;
+
+/// Occurrences of every English bigram, from `AA` to `ZZ`.
+pub static ENGLISH_BIGRAMS: LazyLock<BigramFrequency> = LazyLock::new(|| {
+  let letters = ('A'..='Z').flat_map(|a| ('A'..='Z').map(move |b| [a, b]));
+  let counts = include_str!("english_bigram.txt")
+    .lines()
+    .filter_map(|line| line.trim().parse().ok());
+
+  letters.zip(counts).collect::<BTreeMap<_, _>>()
+});
+
+/// Log10 probability of every English bigram, indexed by the letters
+/// `0..26`. Unseen bigrams are smoothed to a count of one.
+pub static ENGLISH_BIGRAM_LOG_PROBABILITIES: LazyLock<[[f32; 26]; 26]> =
+  LazyLock::new(|| {
+    let total: usize = ENGLISH_BIGRAMS.values().sum();
+    let mut table = [[0.0; 26]; 26];
+
+    for (&[a, b], &count) in ENGLISH_BIGRAMS.iter() {
+      let (a, b) = ((a as u8 - b'A') as usize, (b as u8 - b'A') as usize);
+      table[a][b] = ((count + 1) as f32 / total as f32).log10();
+    }
+
+    table
+  });
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_english_bigrams_contain_all_pairs() {
+    assert_eq!(ENGLISH_BIGRAMS.len(), 26 * 26);
+  }
+
+  #[test]
+  fn test_english_bigrams_rank_common_pairs_higher() {
+    let th = ENGLISH_BIGRAMS[&['T', 'H']];
+    let qz = ENGLISH_BIGRAMS[&['Q', 'Z']];
+    assert!(th > qz);
+  }
+}
@@ -0,0 +1,49 @@
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use super::Frequency;
+
+/// Letters of modern Greek text per 100 000 letters, with the accents
+/// removed and the final sigma counted as `Σ`.
+pub static GREEK: LazyLock<Frequency> = LazyLock::new(|| {
+  BTreeMap::from([
+    ('Α', 11300),
+    ('Ο', 9850),
+    ('Ι', 8600),
+    ('Ε', 8700),
+    ('Τ', 8200),
+    ('Σ', 7900),
+    ('Ν', 6600),
+    ('Η', 5200),
+    ('Ρ', 4700),
+    ('Υ', 4400),
+    ('Π', 4200),
+    ('Κ', 4100),
+    ('Μ', 3300),
+    ('Λ', 2800),
+    ('Ω', 2000),
+    ('Δ', 1700),
+    ('Γ', 1700),
+    ('Θ', 1300),
+    ('Χ', 1200),
+    ('Φ', 800),
+    ('Β', 500),
+    ('Ξ', 400),
+    ('Ζ', 400),
+    ('Ψ', 150),
+  ])
+});
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_greek_frequency_contains_all_characters() {
+    assert_eq!(GREEK.len(), 24);
+
+    // The final sigma U+03A2 has no uppercase form.
+    for ch in ('Α'..='Ω').filter(|&ch| ch != '\u{03A2}') {
+      assert!(GREEK.contains_key(&ch), "Missing character {ch}");
+    }
+  }
+}
@@ -0,0 +1,9 @@
+pub mod english;
+pub mod english_bigrams;
+pub mod greek;
+pub mod russian;
+
+use std::collections::BTreeMap;
+
+pub type Frequency = BTreeMap<char, usize>;
+pub type BigramFrequency = BTreeMap<[char; 2], usize>;
@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use super::Frequency;
+
+/// Letters of Russian text per 100 000 letters.
+pub static RUSSIAN: LazyLock<Frequency> = LazyLock::new(|| {
+  BTreeMap::from([
+    ('О', 10983),
+    ('Е', 8483),
+    ('А', 7998),
+    ('И', 7367),
+    ('Н', 6700),
+    ('Т', 6318),
+    ('С', 5473),
+    ('Р', 4746),
+    ('В', 4533),
+    ('Л', 4343),
+    ('К', 3486),
+    ('М', 3203),
+    ('Д', 2977),
+    ('П', 2804),
+    ('У', 2615),
+    ('Я', 2001),
+    ('Ы', 1898),
+    ('Ь', 1735),
+    ('Г', 1687),
+    ('З', 1641),
+    ('Б', 1592),
+    ('Ч', 1450),
+    ('Й', 1208),
+    ('Х', 966),
+    ('Ж', 940),
+    ('Ш', 718),
+    ('Ю', 639),
+    ('Ц', 486),
+    ('Щ', 361),
+    ('Э', 331),
+    ('Ф', 267),
+    ('Ъ', 37),
+    ('Ё', 13),
+  ])
+});
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_russian_frequency_contains_all_characters() {
+    assert_eq!(RUSSIAN.len(), 33);
+
+    for ch in ('А'..='Я').chain(['Ё']) {
+      assert!(RUSSIAN.contains_key(&ch), "Missing character {ch}");
+    }
+  }
+}
@@ -0,0 +1,909 @@
+pub mod compare;
+pub mod frequencies;
+pub mod window;
+
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::io::{self, Read, Result, Write};
+use std::sync::LazyLock;
+
+use frequencies::english::ENGLISH;
+use frequencies::english_bigrams::ENGLISH_BIGRAM_LOG_PROBABILITIES;
+use frequencies::greek::GREEK;
+use frequencies::russian::RUSSIAN;
+use frequencies::Frequency;
+
+use crate::alphabet::Alphabet;
+
+/// Log probability of every byte in English text.
+static BYTE_LOG_PROBABILITIES: LazyLock<[f32; 256]> = LazyLock::new(|| {
+  const MAX: f32 = 100_000.0;
+  const SPACE: f32 = 0.18;
+
+  std::array::from_fn(|byte| {
+    let probability = match byte as u8 {
+      b' ' => SPACE,
+      b if b.is_ascii_alphabetic() => {
+        let letter = b.to_ascii_uppercase() as char;
+        let count = ENGLISH.get(&letter).copied().unwrap_or(1);
+        let case = if b.is_ascii_lowercase() { 0.9 } else { 0.1 };
+        (1.0 - SPACE) * case * count as f32 / MAX
+      }
+      b'\n' | b'\r' | b'\t' => 0.01,
+      b if b.is_ascii_graphic() => 0.002,
+      _ => 0.000_001,
+    };
+    probability.ln()
+  })
+});
+
+/// Bigram scores expected of English text and of uniformly random
+/// letters, the bounds of [`FrequencyAnalyzer::confidence`].
+static BIGRAM_SCORE_RANGE: LazyLock<(f32, f32)> = LazyLock::new(|| {
+  let log_probabilities = ENGLISH_BIGRAM_LOG_PROBABILITIES.iter().flatten();
+  let english: f32 = log_probabilities
+    .clone()
+    .map(|&log_probability| -10f32.powf(log_probability) * log_probability)
+    .sum();
+  let random = -log_probabilities.sum::<f32>() / (26.0 * 26.0);
+  (english, random)
+});
+
+/// Standard deviation of the score of a single bigram of English text,
+/// as a share of the range of [`BIGRAM_SCORE_RANGE`].
+static BIGRAM_CONFIDENCE_DEVIATION: LazyLock<f32> = LazyLock::new(|| {
+  let (english, random) = *BIGRAM_SCORE_RANGE;
+  let square: f32 = ENGLISH_BIGRAM_LOG_PROBABILITIES
+    .iter()
+    .flatten()
+    .map(|&log_probability| {
+      10f32.powf(log_probability) * log_probability * log_probability
+    })
+    .sum();
+  (square - english * english).sqrt() / (random - english)
+});
+
+/// Width of the histogram bar of the most frequent letter.
+const BAR_WIDTH: usize = 40;
+
+/// Partial blocks of a histogram bar in eighths of a character.
+const BAR_EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Height of a bar and width of the longest bar of the SVG histogram.
+const SVG_ROW_HEIGHT: usize = 20;
+const SVG_BAR_WIDTH: usize = 400;
+
+/// Order of the letters in the output of a frequency analysis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum FrequencySort {
+  /// Alphabetically.
+  Letter,
+  /// The most frequent letter first.
+  #[default]
+  Count,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FrequencyConfig {
+  /// Add a bar chart column of the relative frequencies.
+  pub histogram: bool,
+  pub sort_by: FrequencySort,
+  /// Only keep the most frequent letters.
+  pub top: Option<usize>,
+  /// Only keep letters that occur at least this often.
+  pub min_count: usize,
+}
+
+/// The language of a plaintext, which selects the alphabet and the
+/// letter frequencies that a ciphertext is cracked with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Language {
+  #[default]
+  English,
+  Russian,
+  Greek,
+}
+
+impl Language {
+  /// Letters per 100 000 letters of the language, in uppercase.
+  pub fn frequencies(self) -> &'static Frequency {
+    match self {
+      Language::English => &ENGLISH,
+      Language::Russian => &RUSSIAN,
+      Language::Greek => &GREEK,
+    }
+  }
+
+  pub fn alphabet(self) -> Alphabet {
+    let symbols = match self {
+      Language::English => Alphabet::LATIN,
+      Language::Russian => Alphabet::CYRILLIC,
+      Language::Greek => Alphabet::GREEK,
+    };
+    Alphabet::new(symbols).expect("The bundled alphabets are valid")
+  }
+}
+
+pub struct FrequencyAnalyzer;
+
+impl FrequencyAnalyzer {
+  pub fn analyze<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+  ) -> Result<FrequencyAnalysis> {
+    Self::analyze_in(input, output, Language::English)
+  }
+
+  /// Counts the letters like [`FrequencyAnalyzer::analyze`] and writes
+  /// them as configured.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: &FrequencyConfig,
+  ) -> Result<FrequencyAnalysis> {
+    let result = Self::analyze(input, &mut io::sink())?;
+    write!(output, "{}", result.table(config))?;
+    Ok(result)
+  }
+
+  /// Counts the letters of the alphabet of `language` like
+  /// [`FrequencyAnalyzer::analyze`] counts `A..=Z`.
+  pub fn analyze_in<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    language: Language,
+  ) -> Result<FrequencyAnalysis> {
+    let mut frequency = Frequency::new();
+    let mut total_count = 0;
+    let mut content = String::new();
+
+    input.read_to_string(&mut content)?;
+
+    let alphabet = language.alphabet();
+    for c in content.chars() {
+      if let Some(index) = alphabet.index(c) {
+        *frequency.entry(alphabet.symbol(index)).or_insert(0) += 1;
+        total_count += 1;
+      }
+    }
+
+    let result = FrequencyAnalysis {
+      frequency,
+      total_count,
+    };
+
+    write!(output, "{result}")?;
+    Ok(result)
+  }
+
+  pub fn chi_square_score(fa: &FrequencyAnalysis) -> f32 {
+    Self::chi_square_score_in(fa, Language::English)
+  }
+
+  /// Chi-square distance of the letter frequencies to those of
+  /// `language`.
+  pub fn chi_square_score_in(
+    fa: &FrequencyAnalysis,
+    language: Language,
+  ) -> f32 {
+    const MAX: f32 = 100_000.0;
+
+    let mut score = 0.0;
+
+    for (letter, &expected_raw_count) in language.frequencies() {
+      let expected_frequency = expected_raw_count as f32 / MAX;
+      let expected_count = expected_frequency * fa.total_count as f32;
+
+      if expected_count > 0.0 {
+        let observed_count = *fa.frequency.get(letter).unwrap_or(&0) as f32;
+        let difference = observed_count - expected_count;
+        let chi_square_component = difference.powi(2) / expected_count;
+        score += chi_square_component;
+      }
+    }
+
+    score
+  }
+
+  pub fn score_text<R: Read>(input: &mut R) -> Result<f32> {
+    Self::score_text_in(input, Language::English)
+  }
+
+  /// Scores a text like [`FrequencyAnalyzer::score_text`] against the
+  /// letter frequencies of `language`.
+  pub fn score_text_in<R: Read>(
+    input: &mut R,
+    language: Language,
+  ) -> Result<f32> {
+    let mut output = Vec::new();
+    let frequency_analysis = Self::analyze_in(input, &mut output, language)?;
+    let score = Self::chi_square_score_in(&frequency_analysis, language);
+
+    Ok(score)
+  }
+
+  /// Scores the order of the letters in a text by the average negative
+  /// log probability of its bigrams. Lower scores are more English-like.
+  ///
+  /// Unlike the chi-square score this distinguishes between permutations
+  /// of the same letters, which makes it suitable for transposition ciphers.
+  pub fn bigram_score(text: &str) -> f32 {
+    let letters: Vec<usize> = text
+      .bytes()
+      .filter(u8::is_ascii_alphabetic)
+      .map(|c| (c.to_ascii_uppercase() - b'A') as usize)
+      .collect();
+
+    Self::bigram_score_indices(&letters)
+  }
+
+  /// How English the text is, normalized from 0 for a bigram score like
+  /// that of uniformly random letters to 1 for the score expected of
+  /// English. Texts with less than two letters have no confidence.
+  pub fn confidence(text: &str) -> Option<f32> {
+    let letters = text.bytes().filter(u8::is_ascii_alphabetic).count();
+    if letters < 2 {
+      return None;
+    }
+
+    let (english, random) = *BIGRAM_SCORE_RANGE;
+    let score = Self::bigram_score(text);
+    Some(((random - score) / (random - english)).clamp(0.0, 1.0))
+  }
+
+  /// Standard error of [`FrequencyAnalyzer::confidence`] for English
+  /// text with as many letters as the text. It shrinks with the square
+  /// root of the number of bigrams, so a few rare bigrams move the
+  /// confidence of a short text a lot.
+  pub fn confidence_error(text: &str) -> Option<f32> {
+    let letters = text.bytes().filter(u8::is_ascii_alphabetic).count();
+    if letters < 2 {
+      return None;
+    }
+    Some(*BIGRAM_CONFIDENCE_DEVIATION / ((letters - 1) as f32).sqrt())
+  }
+
+  /// [`FrequencyAnalyzer::confidence`] of a plaintext in `language`.
+  ///
+  /// There are only English bigrams, so for other languages it is the
+  /// lower of two confidences from 0 for uniformly random letters to 1
+  /// for a text of the language: the chi-square distance of the letters
+  /// to the language beyond the sampling noise, and the index of
+  /// coincidence, which a key fitted to the letter frequencies of every
+  /// column of a Vigenère cipher does not raise much. Texts with less
+  /// than two letters of the alphabet have no confidence.
+  pub fn confidence_in(text: &str, language: Language) -> Option<f32> {
+    if language == Language::English {
+      return Self::confidence(text);
+    }
+    let analysis =
+      Self::analyze_in(&mut text.as_bytes(), &mut io::sink(), language).ok()?;
+    let letters = analysis.total_count;
+    if letters < 2 {
+      return None;
+    }
+
+    let (noise, random) = Self::chi_square_range(language, letters);
+    let chi_square = Self::chi_square_score_in(&analysis, language);
+    let fit = 1.0 - (chi_square - noise).max(0.0) / random;
+
+    let (expected, uniform, _) = Self::coincidence_range(language, letters);
+    let index = Self::index_of_coincidence_in(text, &language.alphabet());
+    let coincidence = (index - uniform) / (expected - uniform);
+
+    Some(fit.min(coincidence).clamp(0.0, 1.0))
+  }
+
+  /// Standard error of [`FrequencyAnalyzer::confidence_in`] for a text
+  /// of the language with as many letters as the text.
+  pub fn confidence_error_in(text: &str, language: Language) -> Option<f32> {
+    if language == Language::English {
+      return Self::confidence_error(text);
+    }
+    let alphabet = language.alphabet();
+    let letters = text.chars().filter(|&c| alphabet.index(c).is_some());
+    let letters = letters.count();
+    if letters < 2 {
+      return None;
+    }
+
+    let (noise, random) = Self::chi_square_range(language, letters);
+    let (expected, uniform, deviation) =
+      Self::coincidence_range(language, letters);
+    let fit = (2.0 * noise).sqrt() / random;
+    Some(fit.max(deviation / (expected - uniform)))
+  }
+
+  /// Chi-square distance expected of a text of `language` with as many
+  /// letters, which is its degrees of freedom, and the distance uniformly
+  /// random letters add to that.
+  fn chi_square_range(language: Language, letters: usize) -> (f32, f32) {
+    let probabilities = Self::probabilities(language);
+    let uniform = 1.0 / probabilities.len() as f32;
+    let random: f32 = probabilities
+      .iter()
+      .filter(|&&expected| expected > 0.0)
+      .map(|&expected| (uniform - expected).powi(2) / expected)
+      .sum();
+    ((probabilities.len() - 1) as f32, random * letters as f32)
+  }
+
+  /// Index of coincidence expected of `language` and of uniformly random
+  /// letters, and its standard deviation for a text of the language with
+  /// as many letters.
+  fn coincidence_range(language: Language, letters: usize) -> (f32, f32, f32) {
+    let probabilities = Self::probabilities(language);
+    let square: f32 = probabilities.iter().map(|p| p * p).sum();
+    let cube: f32 = probabilities.iter().map(|p| p * p * p).sum();
+    let letters = letters as f32;
+    let variance = 4.0 * (cube - square * square) / letters
+      + 2.0 * (square - square * square) / (letters * letters);
+    (square, 1.0 / probabilities.len() as f32, variance.sqrt())
+  }
+
+  /// Relative frequencies of the letters of `language`.
+  fn probabilities(language: Language) -> Vec<f32> {
+    let frequencies = language.frequencies();
+    let total: usize = frequencies.values().sum();
+    frequencies
+      .values()
+      .map(|&count| count as f32 / total as f32)
+      .collect()
+  }
+
+  /// How English the order of the letters is, normalized from 0 for the
+  /// bigram score expected of the same letters in random order to 1 for
+  /// the score expected of English.
+  ///
+  /// Unlike [`FrequencyAnalyzer::confidence`], which starts from
+  /// uniformly random letters, this tells a transposition that failed
+  /// apart from English, since both have English letter frequencies.
+  /// Texts with less than two letters have no confidence.
+  pub fn order_confidence(text: &str) -> Option<f32> {
+    let letters: Vec<usize> = text
+      .bytes()
+      .filter(u8::is_ascii_alphabetic)
+      .map(|c| (c.to_ascii_uppercase() - b'A') as usize)
+      .collect();
+    if letters.len() < 2 {
+      return None;
+    }
+
+    let mut counts = [0usize; 26];
+    for &letter in &letters {
+      counts[letter] += 1;
+    }
+    let total = letters.len() as f32;
+    let shuffled: f32 = (0..26)
+      .flat_map(|a| (0..26).map(move |b| (a, b)))
+      .map(|(a, b)| {
+        let probability = counts[a] as f32 * counts[b] as f32 / (total * total);
+        -probability * ENGLISH_BIGRAM_LOG_PROBABILITIES[a][b]
+      })
+      .sum();
+
+    let (english, _) = *BIGRAM_SCORE_RANGE;
+    if shuffled <= english {
+      return Some(0.0);
+    }
+    let score = Self::bigram_score_indices(&letters);
+    Some(((shuffled - score) / (shuffled - english)).clamp(0.0, 1.0))
+  }
+
+  /// Probability that two letters drawn from the text are equal.
+  ///
+  /// English text has an index of coincidence of about 0.066, while
+  /// uniformly random letters have about 0.038.
+  pub fn index_of_coincidence(text: &str) -> f32 {
+    let mut counts = [0usize; 26];
+    for c in text.bytes().filter(u8::is_ascii_alphabetic) {
+      counts[(c.to_ascii_uppercase() - b'A') as usize] += 1;
+    }
+
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+      return 0.0;
+    }
+
+    let pairs: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    pairs as f32 / (total * (total - 1)) as f32
+  }
+
+  /// Index of coincidence of the symbols of an alphabet in the text.
+  pub fn index_of_coincidence_in(text: &str, alphabet: &Alphabet) -> f32 {
+    let mut counts = vec![0usize; alphabet.len()];
+    for index in text.chars().filter_map(|c| alphabet.index(c)) {
+      counts[index] += 1;
+    }
+
+    let total: usize = counts.iter().sum();
+    if total < 2 {
+      return 0.0;
+    }
+
+    let pairs: usize = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    pairs as f32 / (total * (total - 1)) as f32
+  }
+
+  /// Shannon entropy of the bytes in bits per byte, from 0 for a single
+  /// repeated byte to 8 for uniformly random bytes. Text is usually
+  /// around 4.5, while compressed or encrypted data is close to 8.
+  pub fn entropy(bytes: &[u8]) -> f32 {
+    let mut counts = [0usize; 256];
+    for &byte in bytes {
+      counts[byte as usize] += 1;
+    }
+
+    let total = bytes.len() as f32;
+    counts
+      .iter()
+      .filter(|&&count| count > 0)
+      .map(|&count| {
+        let probability = count as f32 / total;
+        -probability * probability.log2()
+      })
+      .sum()
+  }
+
+  /// Scores bytes as English text by the average negative log
+  /// probability of every byte. Lower scores are more English-like.
+  ///
+  /// Case, spaces, punctuation and unprintable bytes are taken into
+  /// account, which makes this suitable for ciphers operating on bytes.
+  pub fn byte_score(bytes: &[u8]) -> f32 {
+    if bytes.is_empty() {
+      return f32::MAX;
+    }
+
+    let log_probability: f32 = bytes
+      .iter()
+      .map(|&byte| BYTE_LOG_PROBABILITIES[byte as usize])
+      .sum();
+
+    -log_probability / bytes.len() as f32
+  }
+
+  /// Same as [`FrequencyAnalyzer::bigram_score`] for letters given as
+  /// indices `0..26`, which avoids building strings in tight loops.
+  pub fn bigram_score_indices(letters: &[usize]) -> f32 {
+    if letters.len() < 2 {
+      return f32::MAX;
+    }
+
+    let log_probability: f32 = letters
+      .windows(2)
+      .map(|pair| ENGLISH_BIGRAM_LOG_PROBABILITIES[pair[0]][pair[1]])
+      .sum();
+
+    -log_probability / (letters.len() - 1) as f32
+  }
+}
+
+#[derive(PartialEq, Eq)]
+pub struct FrequencyAnalysis {
+  pub frequency: Frequency,
+  pub total_count: usize,
+}
+
+impl FrequencyAnalysis {
+  fn percentage(&self, count: usize) -> f64 {
+    (count as f64 / self.total_count as f64) * 100.0
+  }
+
+  /// The letters and their counts selected and ordered as configured.
+  /// `top` keeps the most frequent letters regardless of the order.
+  fn rows(&self, config: &FrequencyConfig) -> Vec<(char, usize)> {
+    let mut rows: Vec<(char, usize)> = self
+      .frequency
+      .iter()
+      .map(|(&char, &count)| (char, count))
+      .filter(|&(_, count)| count >= config.min_count)
+      .collect();
+
+    rows.sort_by(|a, b| b.1.cmp(&a.1));
+    if let Some(top) = config.top {
+      rows.truncate(top);
+    }
+    if config.sort_by == FrequencySort::Letter {
+      rows.sort_by_key(|&(char, _)| char);
+    }
+    rows
+  }
+
+  /// The table of the letters selected and ordered as configured.
+  pub fn table<'a>(
+    &'a self,
+    config: &'a FrequencyConfig,
+  ) -> FrequencyTable<'a> {
+    FrequencyTable {
+      analysis: self,
+      config,
+    }
+  }
+
+  /// Bar of `count` relative to the most frequent letter, drawn with
+  /// block characters in steps of an eighth of a character.
+  fn bar(&self, count: usize) -> String {
+    let max = self.frequency.values().copied().max().unwrap_or(0).max(1);
+    let eighths = count * BAR_WIDTH * 8 / max;
+    let mut bar = "█".repeat(eighths / 8);
+    if eighths % 8 > 0 {
+      bar.push(BAR_EIGHTHS[eighths % 8]);
+    }
+    bar
+  }
+
+  /// Writes the frequencies as an SVG bar chart, one bar per letter in
+  /// the order of the table.
+  pub fn write_svg<W: Write>(
+    &self,
+    output: &mut W,
+    config: &FrequencyConfig,
+  ) -> Result<()> {
+    let entries = self.rows(config);
+    let max = self.frequency.values().copied().max().unwrap_or(0).max(1);
+    let label_width = 30;
+    let width = label_width + SVG_BAR_WIDTH + 80;
+    let height = SVG_ROW_HEIGHT * entries.len().max(1);
+
+    writeln!(
+      output,
+      r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="14">"#
+    )?;
+    for (row, (char, count)) in entries.into_iter().enumerate() {
+      let y = row * SVG_ROW_HEIGHT;
+      let text_y = y + SVG_ROW_HEIGHT * 3 / 4;
+      let bar_width = count * SVG_BAR_WIDTH / max;
+      let percentage = self.percentage(count);
+      writeln!(output, r#"  <text x="0" y="{text_y}">{char}</text>"#)?;
+      writeln!(
+        output,
+        r#"  <rect x="{label_width}" y="{}" width="{bar_width}" height="{}" fill="steelblue"/>"#,
+        y + 2,
+        SVG_ROW_HEIGHT - 4
+      )?;
+      writeln!(
+        output,
+        r#"  <text x="{}" y="{text_y}">{percentage:.3} %</text>"#,
+        label_width + bar_width + 5
+      )?;
+    }
+    writeln!(output, "</svg>")
+  }
+}
+
+impl Display for FrequencyAnalysis {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    // The alternate form `{:#}` adds a histogram column.
+    let config = FrequencyConfig {
+      histogram: f.alternate(),
+      ..Default::default()
+    };
+    write!(f, "{}", self.table(&config))
+  }
+}
+
+/// A [`FrequencyAnalysis`] displayed as configured by a
+/// [`FrequencyConfig`].
+pub struct FrequencyTable<'a> {
+  analysis: &'a FrequencyAnalysis,
+  config: &'a FrequencyConfig,
+}
+
+impl Display for FrequencyTable<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    let histogram = self.config.histogram;
+    if histogram {
+      let rule = "-".repeat(BAR_WIDTH);
+      writeln!(
+        f,
+        "| Letter | Occurrences | Percentage | {:<BAR_WIDTH$} |",
+        "Histogram"
+      )?;
+      writeln!(f, "| ------ | ----------- | ---------- | {rule} |")?;
+    } else {
+      writeln!(f, "| Letter | Occurrences | Percentage |")?;
+      writeln!(f, "| ------ | ----------- | ---------- |")?;
+    }
+
+    for (char, count) in self.analysis.rows(self.config) {
+      write!(
+        f,
+        "| {:<6} | {:<11} | {:>8.3} % |",
+        char,
+        count,
+        self.analysis.percentage(count)
+      )?;
+      if histogram {
+        write!(f, " {:<BAR_WIDTH$} |", self.analysis.bar(count))?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+impl Debug for FrequencyAnalysis {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "{:?}", self.frequency)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::collections::BTreeMap;
+  use std::env;
+  use std::fs::File;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_example_output() -> Result<()> {
+    let assets = "src/frequency_analysis/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let input_path = path.join("input.txt");
+    let output_path = path.join("output.txt");
+
+    let mut input_file = File::open(&input_path)?;
+    let mut output_buffer = Vec::new();
+
+    FrequencyAnalyzer::analyze(&mut input_file, &mut output_buffer)?;
+
+    let mut expected_output = String::new();
+    File::open(&output_path)?.read_to_string(&mut expected_output)?;
+
+    let output_string = String::from_utf8(output_buffer)
+      .expect("Failed to convert output buffer to UTF-8 string");
+
+    assert_eq!(output_string, expected_output);
+    Ok(())
+  }
+
+  #[test]
+  fn test_histogram() -> Result<()> {
+    let mut output = Vec::new();
+    let config = FrequencyConfig {
+      histogram: true,
+      ..Default::default()
+    };
+    let mut input = "AAAAAAB".as_bytes();
+    FrequencyAnalyzer::execute(&mut input, &mut output, &config)?;
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    let full = "█".repeat(40);
+    let partial = format!("{:<40}", "██████▋");
+    assert_eq!(
+      lines[2],
+      format!("| A      | 6           |   85.714 % | {full} |")
+    );
+    assert_eq!(
+      lines[3],
+      format!("| B      | 1           |   14.286 % | {partial} |")
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_sort_and_filter() -> Result<()> {
+    let fa =
+      FrequencyAnalyzer::analyze(&mut "CCCBBA".as_bytes(), &mut Vec::new())?;
+    let mut config = FrequencyConfig::default();
+    assert_eq!(fa.rows(&config), vec![('C', 3), ('B', 2), ('A', 1)]);
+
+    config.sort_by = FrequencySort::Letter;
+    assert_eq!(fa.rows(&config), vec![('A', 1), ('B', 2), ('C', 3)]);
+
+    config.top = Some(2);
+    assert_eq!(fa.rows(&config), vec![('B', 2), ('C', 3)]);
+
+    config.top = None;
+    config.min_count = 2;
+    assert_eq!(fa.rows(&config), vec![('B', 2), ('C', 3)]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_svg() -> Result<()> {
+    let fa =
+      FrequencyAnalyzer::analyze(&mut "AAB".as_bytes(), &mut Vec::new())?;
+    let mut output = Vec::new();
+    fa.write_svg(&mut output, &FrequencyConfig::default())?;
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains(r#"width="400""#));
+    assert!(svg.contains(r#"width="200""#));
+    assert!(svg.contains("66.667 %"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_bigram_score_prefers_english_order() {
+    let english = FrequencyAnalyzer::bigram_score("ATTACKATDAWN");
+    let shuffled = FrequencyAnalyzer::bigram_score("TAWDANTKCATA");
+    assert!(english < shuffled);
+  }
+
+  #[test]
+  fn test_index_of_coincidence() {
+    assert_eq!(FrequencyAnalyzer::index_of_coincidence("AABB"), 1.0 / 3.0);
+    assert_eq!(FrequencyAnalyzer::index_of_coincidence("ABCD"), 0.0);
+    assert_eq!(FrequencyAnalyzer::index_of_coincidence("A"), 0.0);
+  }
+
+  #[test]
+  fn test_analyze_russian() -> Result<()> {
+    let text = "Съешь же ещё этих мягких французских булок";
+    let fa = FrequencyAnalyzer::analyze_in(
+      &mut text.as_bytes(),
+      &mut Vec::new(),
+      Language::Russian,
+    )?;
+    assert_eq!(fa.total_count, 36);
+    assert_eq!(fa.frequency.get(&'Е'), Some(&3));
+    assert_eq!(fa.frequency.get(&'Ё'), Some(&1));
+
+    let russian = Language::Russian.alphabet();
+    let index = FrequencyAnalyzer::index_of_coincidence_in("ААББ", &russian);
+    assert_eq!(index, 1.0 / 3.0);
+    Ok(())
+  }
+
+  #[test]
+  fn test_byte_score_prefers_english_text() {
+    let english = FrequencyAnalyzer::byte_score(b"attack at dawn");
+    let letters = FrequencyAnalyzer::byte_score(b"AtTaCkAtDaWnXq");
+    let binary = FrequencyAnalyzer::byte_score(&[0x01, 0x9f, 0x00, 0x7f]);
+    assert!(english < letters);
+    assert!(letters < binary);
+  }
+
+  #[test]
+  fn test_entropy() {
+    assert_eq!(FrequencyAnalyzer::entropy(b""), 0.0);
+    assert_eq!(FrequencyAnalyzer::entropy(b"aaaa"), 0.0);
+    assert_eq!(FrequencyAnalyzer::entropy(b"abab"), 1.0);
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(FrequencyAnalyzer::entropy(&bytes), 8.0);
+  }
+
+  #[test]
+  fn test_confidence() {
+    let english = "the enemy will attack the northern gate at dawn";
+    let shifted = "uif fofnz xjmm buubdl uif opsuifso hbuf bu ebxo";
+    assert_eq!(FrequencyAnalyzer::confidence(english), Some(1.0));
+    assert!(FrequencyAnalyzer::confidence(shifted).unwrap() < 0.5);
+    assert_eq!(FrequencyAnalyzer::confidence("a 1234"), None);
+  }
+
+  #[test]
+  fn test_order_confidence() {
+    let english = "it was a bright cold day in april and the clocks were \
+      striking thirteen winston smith his chin nuzzled into his breast";
+    let letters: Vec<char> =
+      english.chars().filter(char::is_ascii_alphabetic).collect();
+    let transposed: String = (0..7)
+      .flat_map(|column| letters.iter().skip(column).step_by(7))
+      .collect();
+
+    let confidence = FrequencyAnalyzer::order_confidence(english).unwrap();
+    let transposed = FrequencyAnalyzer::order_confidence(&transposed).unwrap();
+    assert!(confidence > 0.8, "{confidence}");
+    assert!(transposed < 0.3, "{transposed}");
+    assert!(FrequencyAnalyzer::confidence(english).unwrap() > 0.9);
+    assert_eq!(FrequencyAnalyzer::order_confidence("a"), None);
+  }
+
+  #[test]
+  fn test_bigram_score_of_short_text() {
+    assert_eq!(FrequencyAnalyzer::bigram_score("A"), f32::MAX);
+  }
+
+  #[test]
+  fn test_chi_square_perfect_match() {
+    let observed: Frequency = ENGLISH.clone();
+    let total_count = observed.values().sum();
+    let fa = FrequencyAnalysis {
+      frequency: observed,
+      total_count,
+    };
+    let score = FrequencyAnalyzer::chi_square_score(&fa);
+    assert!(
+      score < 1.0,
+      "Chi-square score should be close to 0 for a perfect match"
+    );
+  }
+
+  #[test]
+  fn test_chi_square_slight_variation() {
+    let observed: Frequency = BTreeMap::from([
+      ('E', 12300),
+      ('T', 8900),
+      ('A', 8100),
+      ('O', 7800),
+      ('N', 6900),
+      ('I', 6800),
+      ('H', 6400),
+      ('S', 6300),
+      ('R', 5700),
+      ('D', 4500),
+      ('L', 4000),
+      ('U', 2700),
+      ('M', 2500),
+      ('C', 2400),
+      ('W', 2300),
+      ('F', 2200),
+      ('Y', 2100),
+      ('G', 2000),
+      ('P', 1600),
+      ('B', 1500),
+      ('V', 1000),
+      ('K', 800),
+      ('X', 150),
+      ('J', 130),
+      ('Q', 100),
+      ('Z', 90),
+    ]);
+    let total_count = observed.values().sum();
+    let fa = FrequencyAnalysis {
+      frequency: observed,
+      total_count,
+    };
+    let score = FrequencyAnalyzer::chi_square_score(&fa);
+    assert!(
+      score > 1.0 && score < 50.0,
+      "Chi-square score should be moderate for slight variations"
+    );
+  }
+
+  #[test]
+  fn test_chi_square_large_variation() {
+    let observed: Frequency = BTreeMap::from([
+      ('E', 5000),
+      ('T', 5000),
+      ('A', 5000),
+      ('O', 5000),
+      ('N', 5000),
+      ('I', 5000),
+      ('H', 5000),
+      ('S', 5000),
+      ('R', 5000),
+      ('D', 5000),
+      ('L', 5000),
+      ('U', 5000),
+      ('M', 5000),
+      ('C', 5000),
+      ('W', 5000),
+      ('F', 5000),
+      ('Y', 5000),
+      ('G', 5000),
+      ('P', 5000),
+      ('B', 5000),
+      ('V', 5000),
+      ('K', 5000),
+      ('X', 5000),
+      ('J', 5000),
+      ('Q', 5000),
+      ('Z', 5000),
+    ]);
+    let total_count = observed.values().sum();
+    let fa = FrequencyAnalysis {
+      frequency: observed,
+      total_count,
+    };
+    let score = FrequencyAnalyzer::chi_square_score(&fa);
+    assert!(
+      score > 1000.0,
+      "Chi-square score should be high for large variations"
+    );
+  }
+}
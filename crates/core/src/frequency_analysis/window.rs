@@ -0,0 +1,255 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use super::frequencies::Frequency;
+use super::{FrequencyAnalysis, FrequencyAnalyzer};
+
+/// Number of standard deviations of the noise between windows at which
+/// the index of coincidence or chi-square score of a window deviates.
+const DEVIATION: f32 = 3.0;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WindowConfig {
+  /// Number of letters in every window.
+  pub window: usize,
+  /// Number of letters between the starts of two windows.
+  pub step: usize,
+}
+
+impl WindowConfig {
+  pub fn new(window: usize, step: usize) -> Self {
+    Self { window, step }
+  }
+}
+
+/// Letter statistics of a window of a text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowStats {
+  /// Offset of the first letter of the window among the letters.
+  pub start: usize,
+  /// Offset after the last letter of the window among the letters.
+  pub end: usize,
+  /// The most frequent letter and its number of occurrences.
+  pub top: Option<(char, usize)>,
+  pub index_of_coincidence: f32,
+  pub chi_square: f32,
+  /// Whether the index of coincidence deviates from the other windows.
+  pub ic_deviates: bool,
+  /// Whether the chi-square score deviates from the other windows.
+  pub chi_square_deviates: bool,
+}
+
+impl WindowStats {
+  pub fn deviates(&self) -> bool {
+    self.ic_deviates || self.chi_square_deviates
+  }
+}
+
+pub struct WindowAnalyzer;
+
+impl WindowAnalyzer {
+  /// Writes the letter statistics of every window of the input and marks
+  /// the windows that deviate from the rest.
+  ///
+  /// A text encrypted with a single key has about the same statistics
+  /// everywhere, so deviating windows point to where the cipher or the
+  /// key changes.
+  pub fn execute<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: WindowConfig,
+  ) -> Result<Vec<WindowStats>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let windows = Self::analyze(&content, &config)?;
+
+    writeln!(
+      output,
+      "| Start  | End    | Top      | IC     | Chi-square | Deviates |"
+    )?;
+    writeln!(
+      output,
+      "| ------ | ------ | -------- | ------ | ---------- | -------- |"
+    )?;
+    for stats in &windows {
+      let top = stats
+        .top
+        .map(|(letter, count)| format!("{letter} {count}"))
+        .unwrap_or_default();
+      let deviates = match (stats.ic_deviates, stats.chi_square_deviates) {
+        (true, true) => "both",
+        (true, false) => "IC",
+        (false, true) => "chi",
+        (false, false) => "",
+      };
+      writeln!(
+        output,
+        "| {:<6} | {:<6} | {top:<8} | {:.4} | {:>10.2} | {deviates:<8} |",
+        stats.start, stats.end, stats.index_of_coincidence, stats.chi_square
+      )?;
+    }
+
+    Ok(windows)
+  }
+
+  /// Computes the statistics of windows of `config.window` letters that
+  /// start every `config.step` letters. A text shorter than a window is
+  /// analysed as a single window.
+  pub fn analyze(
+    text: &str,
+    config: &WindowConfig,
+  ) -> Result<Vec<WindowStats>> {
+    if config.window == 0 || config.step == 0 {
+      return Err(Error::new(
+        ErrorKind::InvalidInput,
+        "The window and the step must not be zero",
+      ));
+    }
+
+    let letters: Vec<char> = text
+      .chars()
+      .filter(char::is_ascii_alphabetic)
+      .map(|c| c.to_ascii_uppercase())
+      .collect();
+
+    let last_start = letters.len().saturating_sub(config.window);
+    let mut windows: Vec<WindowStats> = (0..=last_start)
+      .step_by(config.step)
+      .map(|start| {
+        let end = (start + config.window).min(letters.len());
+        Self::stats(&letters[start..end], start)
+      })
+      .collect();
+
+    let ic: Vec<f32> = windows.iter().map(|w| w.index_of_coincidence).collect();
+    let chi_square: Vec<f32> = windows.iter().map(|w| w.chi_square).collect();
+    let lag = config.window.div_ceil(config.step);
+    let ic_deviates = Self::deviating(&ic, lag);
+    let chi_square_deviates = Self::deviating(&chi_square, lag);
+    for (index, stats) in windows.iter_mut().enumerate() {
+      stats.ic_deviates = ic_deviates[index];
+      stats.chi_square_deviates = chi_square_deviates[index];
+    }
+
+    Ok(windows)
+  }
+
+  fn stats(letters: &[char], start: usize) -> WindowStats {
+    let mut frequency = Frequency::new();
+    for &letter in letters {
+      *frequency.entry(letter).or_insert(0) += 1;
+    }
+
+    let top = frequency
+      .iter()
+      .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+      .map(|(&letter, &count)| (letter, count));
+
+    let text: String = letters.iter().collect();
+    let analysis = FrequencyAnalysis {
+      frequency,
+      total_count: letters.len(),
+    };
+
+    WindowStats {
+      start,
+      end: start + letters.len(),
+      top,
+      index_of_coincidence: FrequencyAnalyzer::index_of_coincidence(&text),
+      chi_square: FrequencyAnalyzer::chi_square_score(&analysis),
+      ic_deviates: false,
+      chi_square_deviates: false,
+    }
+  }
+
+  /// Marks the values that are more than [`DEVIATION`] standard
+  /// deviations of the noise away from the median of all values.
+  ///
+  /// The noise is estimated from the median difference between windows
+  /// `lag` apart, the closest ones that do not overlap. Unlike the
+  /// standard deviation of all values it stays small when the text
+  /// changes only once.
+  fn deviating(values: &[f32], lag: usize) -> Vec<bool> {
+    let median = |mut values: Vec<f32>| {
+      values.sort_by(f32::total_cmp);
+      values.get(values.len() / 2).copied().unwrap_or_default()
+    };
+
+    let center = median(values.to_vec());
+    let later = &values[lag.min(values.len())..];
+    let differences = values
+      .iter()
+      .zip(later)
+      .map(|(a, b)| (b - a).abs())
+      .collect();
+    let noise = median(differences) / (0.6745 * std::f32::consts::SQRT_2);
+
+    values
+      .iter()
+      .map(|value| noise > 0.0 && (value - center).abs() > DEVIATION * noise)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::vigenere::{Vigenere, VigenereEncryptConfig};
+  use std::path::PathBuf;
+  use std::{env, fs};
+
+  const PLAINTEXT: &str = "the quick brown fox jumps over the lazy dog";
+
+  #[test]
+  fn test_analyze_windows() -> Result<()> {
+    let config = WindowConfig::new(10, 5);
+    let windows = WindowAnalyzer::analyze("AAAAAAAAAABBBBBCCCCC", &config)?;
+    let bounds: Vec<(usize, usize)> =
+      windows.iter().map(|w| (w.start, w.end)).collect();
+    assert_eq!(bounds, vec![(0, 10), (5, 15), (10, 20)]);
+    assert_eq!(windows[0].top, Some(('A', 10)));
+    assert_eq!(windows[0].index_of_coincidence, 1.0);
+    assert_eq!(windows[2].top, Some(('B', 5)));
+
+    let windows = WindowAnalyzer::analyze("ABC", &config)?;
+    assert_eq!(windows.len(), 1);
+    assert_eq!((windows[0].start, windows[0].end), (0, 3));
+    Ok(())
+  }
+
+  #[test]
+  fn test_zero_window_is_rejected() {
+    let config = WindowConfig::new(0, 1);
+    assert!(WindowAnalyzer::analyze(PLAINTEXT, &config).is_err());
+  }
+
+  #[test]
+  fn test_locates_key_change() -> Result<()> {
+    let assets = "src/vigenere/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let plaintext = fs::read_to_string(path.join("output.txt"))?;
+    let split = plaintext.char_indices().nth(6000).unwrap().0;
+    let (english, tail) = plaintext.split_at(split);
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("CRYPTOLOGY");
+    Vigenere::encrypt(&mut tail.as_bytes(), &mut cipher, config)?;
+    let text = format!("{english}{}", String::from_utf8_lossy(&cipher));
+
+    let config = WindowConfig::new(400, 200);
+    let windows = WindowAnalyzer::analyze(&text, &config)?;
+    let change = english.bytes().filter(u8::is_ascii_alphabetic).count();
+    assert!(windows.iter().any(WindowStats::deviates));
+    for stats in windows.iter().filter(|w| w.deviates()) {
+      assert!(stats.end > change);
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,400 @@
+use std::{
+  convert::TryFrom,
+  io::{ErrorKind, Read, Write},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HexParseError {
+  InvalidLength,
+  InvalidHex,
+  FileReadError,
+  IOError,
+}
+
+impl From<std::io::Error> for HexParseError {
+  fn from(_value: std::io::Error) -> Self {
+    HexParseError::FileReadError
+  }
+}
+
+impl std::fmt::Display for HexParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      HexParseError::InvalidLength => {
+        write!(f, "Hex string must have an even length.")
+      }
+      HexParseError::InvalidHex => {
+        write!(f, "Failed to parse input as raw hex")
+      }
+      HexParseError::FileReadError => {
+        write!(f, "Failed to read file")
+      }
+      HexParseError::IOError => {
+        write!(f, "Failed to perform I/O")
+      }
+    }
+  }
+}
+
+impl std::error::Error for HexParseError {}
+
+impl From<HexParseError> for std::io::Error {
+  fn from(value: HexParseError) -> Self {
+    match value {
+      HexParseError::InvalidLength => {
+        let message = "Hex has invalid length".to_string();
+        Self::new(ErrorKind::InvalidInput, message)
+      }
+      HexParseError::InvalidHex => {
+        let message = "Invalid hex was detected".to_string();
+        Self::new(ErrorKind::InvalidInput, message)
+      }
+      HexParseError::FileReadError => {
+        let message = "Failed to read file containing hex".to_string();
+        Self::new(ErrorKind::InvalidInput, message)
+      }
+      HexParseError::IOError => {
+        let message = "Could not write or read hex".to_string();
+        Self::new(ErrorKind::InvalidData, message)
+      }
+    }
+  }
+}
+
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct HexConfig {
+  raw: bool,
+  to_ascii: bool,
+  dump: Option<usize>,
+  offset: usize,
+  length: Option<usize>,
+}
+
+impl HexConfig {
+  pub fn new(
+    raw: bool,
+    to_ascii: bool,
+    dump: Option<usize>,
+    offset: usize,
+    length: Option<usize>,
+  ) -> Self {
+    Self {
+      raw,
+      to_ascii,
+      dump,
+      offset,
+      length,
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Hex {
+  pub bytes: Vec<u8>,
+}
+
+impl Hex {
+  pub fn new(bytes: Vec<u8>) -> Self {
+    Self { bytes }
+  }
+
+  pub fn parse<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: HexConfig,
+  ) -> Result<(), HexParseError> {
+    let mut buf = Vec::new();
+
+    input.read_to_end(&mut buf)?;
+
+    let hex = if config.raw {
+      let text =
+        std::str::from_utf8(&buf).map_err(|_| HexParseError::InvalidHex)?;
+      Self::parse_hex(text)?
+    } else {
+      Self::new(buf)
+    };
+    let hex = hex.slice(config.offset, config.length);
+
+    if let Some(columns) = config.dump {
+      write!(output, "{}", hex.dump_at(columns, config.offset))?;
+    } else if config.to_ascii {
+      let ascii = hex.to_ascii();
+      write!(output, "{ascii}")?;
+    } else {
+      write!(output, "{hex}")?;
+    }
+
+    Ok(())
+  }
+
+  pub fn is_valid_hex(s: &str) -> bool {
+    s.len() % 2 == 0 && s.chars().all(|c| c.is_ascii_hexdigit())
+  }
+
+  pub fn parse_hex(value: &str) -> Result<Self, HexParseError> {
+    if Self::is_valid_hex(value) {
+      let mut bytes = Vec::new();
+      for index in (0..value.len()).step_by(2) {
+        let hex_pair = &value[index..index + 2];
+        let byte = u8::from_str_radix(hex_pair, 16)
+          .map_err(|_| HexParseError::InvalidHex)?;
+        bytes.push(byte);
+      }
+      Ok(Self::new(bytes))
+    } else {
+      Err(HexParseError::InvalidHex)
+    }
+  }
+
+  /// The `length` bytes from `offset` on, or all bytes from `offset` on
+  /// without a length. Ranges past the end are cut off like `dd` does.
+  pub fn slice(&self, offset: usize, length: Option<usize>) -> Self {
+    let start = offset.min(self.bytes.len());
+    let end = match length {
+      Some(length) => start.saturating_add(length).min(self.bytes.len()),
+      None => self.bytes.len(),
+    };
+    Self::new(self.bytes[start..end].to_vec())
+  }
+
+  /// Formats the bytes like `xxd`, with an offset, the bytes grouped in
+  /// pairs and an ASCII gutter on every line of `columns` bytes.
+  pub fn dump(&self, columns: usize) -> String {
+    self.dump_at(columns, 0)
+  }
+
+  /// Formats the bytes like [`Hex::dump`] with offsets counted from
+  /// `start`, for bytes sliced out of a larger input.
+  pub fn dump_at(&self, columns: usize, start: usize) -> String {
+    let columns = columns.max(1);
+    let width = columns * 2 + columns.div_ceil(2) - 1;
+    let mut dump = String::new();
+
+    for (line, chunk) in self.bytes.chunks(columns).enumerate() {
+      let groups: Vec<String> = chunk
+        .chunks(2)
+        .map(|group| Hex::new(group.to_vec()).to_string())
+        .collect();
+      let ascii: String = chunk
+        .iter()
+        .map(|&b| {
+          if b.is_ascii_graphic() || b == b' ' {
+            b as char
+          } else {
+            '.'
+          }
+        })
+        .collect();
+
+      dump.push_str(&format!(
+        "{:08x}: {:<width$}  {ascii}\n",
+        start + line * columns,
+        groups.join(" "),
+      ));
+    }
+
+    dump
+  }
+
+  pub fn to_ascii(&self) -> String {
+    self
+      .bytes
+      .iter()
+      .map(|&b| {
+        if b.is_ascii_graphic() || b.is_ascii_whitespace() {
+          b as char
+        } else {
+          '.'
+        }
+      })
+      .collect()
+  }
+}
+
+impl TryFrom<Box<dyn Read>> for Hex {
+  type Error = HexParseError;
+
+  fn try_from(mut value: Box<dyn Read>) -> Result<Self, Self::Error> {
+    let mut buffer = Vec::new();
+    value
+      .read_to_end(&mut buffer)
+      .map_err(|_| HexParseError::FileReadError)?;
+    Ok(Self::new(buffer))
+  }
+}
+
+impl TryFrom<&str> for Hex {
+  type Error = HexParseError;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    Ok(Self::new(value.as_bytes().to_vec()))
+  }
+}
+
+impl TryFrom<Vec<u8>> for Hex {
+  type Error = ();
+
+  fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+    Ok(Self::new(value))
+  }
+}
+
+impl TryFrom<&[u8]> for Hex {
+  type Error = ();
+
+  fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+    Ok(Self::new(value.to_vec()))
+  }
+}
+
+impl TryFrom<String> for Hex {
+  type Error = HexParseError;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    Self::try_from(value.as_str())
+  }
+}
+
+impl std::fmt::Display for Hex {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for byte in &self.bytes {
+      write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_hex() {
+    let input = "48656c6c6f";
+    let hex = Hex::parse_hex(input).unwrap();
+    assert_eq!(hex.bytes, vec![0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+  }
+
+  #[test]
+  fn test_invalid_hex_string() {
+    let input = "48656c6g6f"; // Contains a non-hex character
+    let result = Hex::parse_hex(input).unwrap_err();
+    assert_eq!(result, HexParseError::InvalidHex);
+  }
+
+  #[test]
+  fn test_regular_string_to_hex() {
+    let input = "World";
+    let hex: Hex = input.try_into().unwrap();
+    assert_eq!(hex.bytes, b"World".to_vec());
+    assert_eq!(format!("{hex}"), "576f726c64");
+  }
+
+  #[test]
+  fn test_from_vec_u8() {
+    let bytes = vec![0x01, 0x02, 0x03, 0x04];
+    let hex: Hex = Hex::try_from(bytes.clone()).unwrap();
+    assert_eq!(hex.bytes, bytes);
+  }
+
+  #[test]
+  fn test_from_u8_slice() {
+    let bytes = vec![0x01, 0x02, 0x03, 0x04];
+    let hex: Hex = Hex::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(hex.bytes, bytes.to_vec());
+  }
+
+  #[test]
+  fn test_is_valid_hex() {
+    assert!(Hex::is_valid_hex("deadbeef"));
+    assert!(Hex::is_valid_hex("DEADBEEF"));
+    assert!(!Hex::is_valid_hex("deadbee"));
+    assert!(!Hex::is_valid_hex("deadg123"));
+  }
+
+  #[test]
+  fn test_to_ascii() {
+    let hex = Hex::new(vec![
+      72, 101, 108, 108, 111, 44, 32, 87, 111, 114, 108, 100, 33,
+    ]);
+    assert_eq!(hex.to_ascii(), "Hello, World!");
+
+    let hex = Hex::new(vec![
+      72, 101, 108, 108, 111, 0xFF, 44, 32, 87, 111, 114, 108, 100, 33,
+    ]);
+    assert_eq!(hex.to_ascii(), "Hello., World!");
+
+    let hex = Hex::new(vec![]);
+    assert_eq!(hex.to_ascii(), "");
+
+    let hex = Hex::new(vec![0x80, 0xFF, 0xAB]);
+    assert_eq!(hex.to_ascii(), "...");
+
+    let hex = Hex::new(vec![9, 10, 32, 65, 66, 67]);
+    assert_eq!(hex.to_ascii(), "\t\n ABC");
+  }
+
+  #[test]
+  fn test_dump() {
+    let hex = Hex::new(b"The quick brown fox jumps\n".to_vec());
+    assert_eq!(
+      hex.dump(16),
+      "00000000: 5468 6520 7175 6963 6b20 6272 6f77 6e20  The quick brown \n\
+       00000010: 666f 7820 6a75 6d70 730a                 fox jumps.\n"
+    );
+  }
+
+  #[test]
+  fn test_dump_odd_columns() {
+    let hex = Hex::new(vec![0x00, 0x41, 0x42, 0xff, 0x43]);
+    assert_eq!(
+      hex.dump(3),
+      "00000000: 0041 42  .AB\n00000003: ff43     .C\n"
+    );
+  }
+
+  #[test]
+  fn test_slice() {
+    let hex = Hex::new(b"Hello, World!".to_vec());
+    assert_eq!(hex.slice(7, Some(5)).bytes, b"World");
+    assert_eq!(hex.slice(7, None).bytes, b"World!");
+    assert_eq!(hex.slice(10, Some(100)).bytes, b"ld!");
+    assert_eq!(hex.slice(100, Some(5)).bytes, b"");
+  }
+
+  #[test]
+  fn test_parse_range() -> Result<(), HexParseError> {
+    let mut output = Vec::new();
+    let config = HexConfig::new(true, false, Some(4), 2, Some(3));
+    Hex::parse(&mut "0041424344ff".as_bytes(), &mut output, config)?;
+    assert_eq!(output, b"00000002: 4243 44    BCD\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_dump_binary() -> Result<(), HexParseError> {
+    let mut output = Vec::new();
+    let config = HexConfig::new(false, false, Some(4), 0, None);
+    Hex::parse(
+      &mut [0x80, 0xff, 0x41, 0xc3].as_slice(),
+      &mut output,
+      config,
+    )?;
+    assert_eq!(output, b"00000000: 80ff 41c3  ..A.\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_range_of_binary() -> Result<(), HexParseError> {
+    let mut output = Vec::new();
+    let config = HexConfig::new(false, false, None, 1, Some(2));
+    Hex::parse(
+      &mut [0xde, 0xad, 0xbe, 0xef].as_slice(),
+      &mut output,
+      config,
+    )?;
+    assert_eq!(output, b"adbe");
+    Ok(())
+  }
+}
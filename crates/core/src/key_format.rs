@@ -0,0 +1,90 @@
+use std::io::{Error, ErrorKind, Result};
+
+use crate::{base64::Base64, hex::Hex};
+
+/// How a key given on the command line is turned into bytes. Every
+/// cipher with a byte key parses it with this, so the same key material
+/// means the same bytes everywhere.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum KeyFormat {
+  /// The bytes of the key as typed.
+  #[default]
+  #[cfg_attr(feature = "clap", value(alias = "raw"))]
+  Ascii,
+  Hex,
+  Base64,
+  /// Bits, most significant first, in groups of eight. Whitespace is
+  /// ignored.
+  Binary,
+}
+
+impl KeyFormat {
+  pub fn parse(&self, key: &str) -> Result<Vec<u8>> {
+    let bytes = match self {
+      KeyFormat::Ascii => key.as_bytes().to_vec(),
+      KeyFormat::Hex => Hex::parse_hex(key.trim())?.bytes,
+      KeyFormat::Base64 => Base64::decode(key)?,
+      KeyFormat::Binary => Self::parse_binary(key)?,
+    };
+
+    if bytes.is_empty() {
+      let message = "The key must not be empty";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(bytes)
+  }
+
+  fn parse_binary(key: &str) -> Result<Vec<u8>> {
+    let bits: Vec<u8> = key
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .map(|c| match c {
+        '0' => Ok(0),
+        '1' => Ok(1),
+        _ => {
+          let message = format!("Invalid bit '{c}' in the binary key");
+          Err(Error::new(ErrorKind::InvalidInput, message))
+        }
+      })
+      .collect::<Result<_>>()?;
+
+    if bits.len() % 8 != 0 {
+      let message = "The binary key must be a whole number of bytes";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Ok(
+      bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, bit| acc << 1 | bit))
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_key_formats() -> Result<()> {
+    assert_eq!(KeyFormat::Ascii.parse("ICE")?, b"ICE");
+    assert_eq!(KeyFormat::Hex.parse("494345")?, b"ICE");
+    assert_eq!(KeyFormat::Base64.parse("SUNF")?, b"ICE");
+    assert_eq!(
+      KeyFormat::Binary.parse("01001001 01000011 01000101")?,
+      b"ICE"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_invalid_keys() {
+    assert!(KeyFormat::Hex.parse("49434").is_err());
+    assert!(KeyFormat::Ascii.parse("").is_err());
+    assert!(KeyFormat::Binary.parse("0100100").is_err());
+    assert!(KeyFormat::Binary.parse("0100100x").is_err());
+  }
+}
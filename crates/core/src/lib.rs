@@ -0,0 +1,35 @@
+//! Ciphers and their cryptanalysis, independent of the command line.
+//!
+//! Every algorithm works on readers and writers or on plain bytes and
+//! strings, so it can be embedded without the `cli` crate. The `clap`
+//! feature derives `clap::ValueEnum` for the enums that are used as
+//! command line options.
+
+pub mod alphabet;
+pub mod autocorrelation;
+pub mod base64;
+pub mod caesar;
+pub mod dictionary_attack;
+pub mod error;
+pub mod frequency_analysis;
+pub mod hex;
+pub mod key_format;
+pub mod many_time_pad;
+pub mod ngram;
+pub mod one_time_pad;
+pub mod progress;
+pub mod report;
+pub mod vigenere;
+pub mod xor;
+
+pub use alphabet::Alphabet;
+pub use caesar::Caesar;
+pub use error::CryptologyError;
+pub use frequency_analysis::FrequencyAnalyzer;
+pub use hex::Hex;
+pub use key_format::KeyFormat;
+pub use many_time_pad::ManyTimePad;
+pub use ngram::NgramModel;
+pub use one_time_pad::OneTimePad;
+pub use vigenere::Vigenere;
+pub use xor::Xor;
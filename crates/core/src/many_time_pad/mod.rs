@@ -0,0 +1,549 @@
+pub mod refinement;
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use refinement::Wordlist;
+
+use crate::{
+  error::CryptologyError, hex::Hex, report::CrackReport,
+  xor::repeating_key::RepeatingKeyXor,
+};
+
+/// Replaces plaintext characters whose key byte is uncertain.
+pub const UNCERTAIN: u8 = b'?';
+
+#[derive(Debug, PartialEq)]
+pub struct ManyTimePadConfig {
+  /// Index of the ciphertext to print, the last one by default.
+  pub target: Option<usize>,
+  /// Print the plaintexts of all ciphertexts.
+  pub all: bool,
+  /// Print the recovered key as hex before the plaintexts.
+  pub show_key: bool,
+  /// Known plaintext fragments in the format of [`Crib::parse`].
+  pub cribs: Option<String>,
+  /// Characters whose key byte has a lower confidence are replaced by
+  /// [`UNCERTAIN`].
+  pub min_confidence: f32,
+  /// Wordlist used to complete partially recovered words.
+  pub wordlist: Option<String>,
+}
+
+impl ManyTimePadConfig {
+  pub fn new(target: Option<usize>, all: bool) -> Self {
+    Self {
+      target,
+      all,
+      ..Self::default()
+    }
+  }
+}
+
+impl Default for ManyTimePadConfig {
+  fn default() -> Self {
+    Self {
+      target: None,
+      all: false,
+      show_key: false,
+      cribs: None,
+      min_confidence: 0.5,
+      wordlist: None,
+    }
+  }
+}
+
+/// Known plaintext of one ciphertext starting at an offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crib {
+  pub index: usize,
+  pub offset: usize,
+  pub text: Vec<u8>,
+}
+
+impl Crib {
+  /// Parses cribs given as `INDEX OFFSET TEXT`, one per line. The text is
+  /// everything after the second space, so it may contain spaces itself.
+  pub fn parse(content: &str) -> Result<Vec<Self>> {
+    content
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| {
+        let invalid = || {
+          let message =
+            format!("Invalid crib '{line}', expected INDEX OFFSET TEXT");
+          Error::new(ErrorKind::InvalidInput, message)
+        };
+        let mut parts = line.splitn(3, ' ');
+        let mut number = || -> Result<usize> {
+          parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(invalid)
+        };
+        let (index, offset) = (number()?, number()?);
+        let text =
+          parts.next().filter(|t| !t.is_empty()).ok_or_else(invalid)?;
+
+        Ok(Self {
+          index,
+          offset,
+          text: text.as_bytes().to_vec(),
+        })
+      })
+      .collect()
+  }
+}
+
+/// A recovered key with the confidence between 0 and 1 of every byte.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveredKey {
+  pub bytes: Vec<u8>,
+  pub confidence: Vec<f32>,
+}
+
+/// Ciphertexts of English messages that were encrypted with the same
+/// one-time pad.
+pub struct ManyTimePad;
+
+impl ManyTimePad {
+  /// Reads one hex ciphertext per line, recovers the reused key and
+  /// writes the plaintexts selected by the config, one per line. The
+  /// recovered key is returned along with the reports of
+  /// [`ManyTimePad::crack`].
+  ///
+  /// Cribs fix key bytes first, then the wordlist completes the words
+  /// that are still uncertain.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: ManyTimePadConfig,
+  ) -> std::result::Result<(RecoveredKey, Vec<CrackReport>), CryptologyError>
+  {
+    let (key, plaintexts) = Self::recover(input, &config)?;
+
+    if config.show_key {
+      writeln!(output, "key: {}", Hex::new(key.bytes.clone()))?;
+    }
+
+    for plaintext in &plaintexts {
+      output.write_all(plaintext)?;
+      writeln!(output)?;
+    }
+
+    let reports = Self::reports(&key, &plaintexts);
+    Ok((key, reports))
+  }
+
+  /// Recovers the key like [`ManyTimePad::decrypt`] and reports each
+  /// selected plaintext with the whole key as hex.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &ManyTimePadConfig,
+  ) -> std::result::Result<(RecoveredKey, Vec<CrackReport>), CryptologyError>
+  {
+    let (key, plaintexts) = Self::recover(input, config)?;
+    let reports = Self::reports(&key, &plaintexts);
+    Ok((key, reports))
+  }
+
+  fn reports(key: &RecoveredKey, plaintexts: &[Vec<u8>]) -> Vec<CrackReport> {
+    let hex = Hex::new(key.bytes.clone()).to_string();
+    plaintexts
+      .iter()
+      .map(|plaintext| {
+        let plaintext = String::from_utf8_lossy(plaintext).into_owned();
+        CrackReport::new(plaintext, Some(hex.clone()), "many-time pad")
+      })
+      .collect()
+  }
+
+  /// The recovered key and the selected plaintexts, with uncertain
+  /// characters marked.
+  fn recover<R: Read>(
+    input: &mut R,
+    config: &ManyTimePadConfig,
+  ) -> std::result::Result<(RecoveredKey, Vec<Vec<u8>>), CryptologyError> {
+    let ciphertexts = Self::read_ciphertexts(input)?;
+    let mut key = Self::recover_key(&ciphertexts);
+
+    if let Some(cribs) = &config.cribs {
+      let cribs = Crib::parse(cribs)?;
+      Self::apply_cribs(&mut key, &ciphertexts, &cribs)?;
+    }
+
+    if let Some(wordlist) = &config.wordlist {
+      let wordlist = Wordlist::new(wordlist);
+      let minimum = config.min_confidence;
+      refinement::refine(&mut key, &ciphertexts, &wordlist, minimum);
+    }
+
+    let plaintexts = Self::plaintexts(&ciphertexts, &key.bytes);
+
+    let selected = match (config.all, config.target) {
+      (true, _) => &plaintexts[..],
+      (false, Some(target)) if target < plaintexts.len() => {
+        &plaintexts[target..=target]
+      }
+      (false, Some(target)) => {
+        let message = format!(
+          "Target {target} is out of range for {} ciphertexts",
+          plaintexts.len()
+        );
+        return Err(Error::new(ErrorKind::InvalidInput, message).into());
+      }
+      (false, None) => &plaintexts[plaintexts.len() - 1..],
+    };
+
+    let selected = selected
+      .iter()
+      .map(|plaintext| {
+        Self::mark(plaintext, &key.confidence, config.min_confidence)
+      })
+      .collect();
+
+    Ok((key, selected))
+  }
+
+  /// Reads the hex ciphertexts of all non-empty lines.
+  pub fn read_ciphertexts<R: Read>(
+    input: &mut R,
+  ) -> std::result::Result<Vec<Vec<u8>>, CryptologyError> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let ciphertexts = content
+      .lines()
+      .enumerate()
+      .map(|(index, line)| (index + 1, line.trim()))
+      .filter(|(_, line)| !line.is_empty())
+      .map(|(number, line)| match Hex::parse_hex(line) {
+        Ok(hex) => Ok(hex.bytes),
+        Err(error) => Err(CryptologyError::from(error).at_line(number)),
+      })
+      .collect::<std::result::Result<Vec<Vec<u8>>, _>>()?;
+
+    if ciphertexts.len() < 2 {
+      let message = "A many-time pad needs at least two ciphertexts";
+      return Err(Error::new(ErrorKind::InvalidInput, message).into());
+    }
+
+    Ok(ciphertexts)
+  }
+
+  /// Recovers the key up to the length of the longest ciphertext, using
+  /// the ciphertexts that are long enough at every position.
+  ///
+  /// XORing two ciphertexts cancels the key. A space XORed with a letter
+  /// flips its case, so a ciphertext byte that yields letters with most
+  /// of the other ciphertexts at the same position is probably a space,
+  /// which reveals the key byte. Its confidence is the share of the
+  /// other ciphertexts that agree, which is always above one half.
+  ///
+  /// Positions without such a byte are solved as single-byte XOR over
+  /// all ciphertexts. Their confidence is below one half and grows with
+  /// the share of lowercase letters and spaces in the plaintexts.
+  pub fn recover_key(ciphertexts: &[Vec<u8>]) -> RecoveredKey {
+    let length = ciphertexts.iter().map(Vec::len).max().unwrap_or_default();
+
+    let (bytes, confidence) = (0..length)
+      .map(|position| {
+        let column: Vec<u8> = ciphertexts
+          .iter()
+          .filter_map(|cipher| cipher.get(position).copied())
+          .collect();
+        Self::space_key(&column).unwrap_or_else(|| {
+          let key = RepeatingKeyXor::solve_single_byte(&column);
+          let plain = column
+            .iter()
+            .map(|byte| byte ^ key)
+            .filter(|&b| b == b' ' || b.is_ascii_lowercase())
+            .count();
+          (key, 0.5 * plain as f32 / (column.len() + 1) as f32)
+        })
+      })
+      .unzip();
+
+    RecoveredKey { bytes, confidence }
+  }
+
+  /// Fixes the key bytes covered by known plaintext, overriding what was
+  /// deduced from the ciphertexts alone. Crib bytes beyond the end of
+  /// the key or their ciphertext are ignored.
+  pub fn apply_cribs(
+    key: &mut RecoveredKey,
+    ciphertexts: &[Vec<u8>],
+    cribs: &[Crib],
+  ) -> Result<()> {
+    for crib in cribs {
+      let cipher = ciphertexts.get(crib.index).ok_or_else(|| {
+        let message =
+          format!("Crib refers to missing ciphertext {}", crib.index);
+        Error::new(ErrorKind::InvalidInput, message)
+      })?;
+
+      for (i, &plain) in crib.text.iter().enumerate() {
+        let position = crib.offset + i;
+        if position < key.bytes.len() && position < cipher.len() {
+          key.bytes[position] = cipher[position] ^ plain;
+          key.confidence[position] = 1.0;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The key byte of a column and its confidence if one of its bytes is
+  /// likely a space.
+  fn space_key(column: &[u8]) -> Option<(u8, f32)> {
+    let others = column.len() - 1;
+
+    column
+      .iter()
+      .map(|&candidate| {
+        let letters = column
+          .iter()
+          .map(|&other| candidate ^ other)
+          .filter(|&xor| xor == 0 || xor.is_ascii_alphabetic())
+          .count()
+          - 1;
+        (candidate, letters)
+      })
+      .max_by_key(|&(_, letters)| letters)
+      .filter(|&(_, letters)| letters * 2 > others)
+      .map(|(candidate, letters)| {
+        (candidate ^ b' ', letters as f32 / others as f32)
+      })
+  }
+
+  /// Replaces the characters whose key byte has a confidence below the
+  /// minimum by [`UNCERTAIN`].
+  pub fn mark(plaintext: &[u8], confidence: &[f32], minimum: f32) -> Vec<u8> {
+    plaintext
+      .iter()
+      .zip(confidence)
+      .map(|(&byte, &confidence)| match confidence < minimum {
+        true => UNCERTAIN,
+        false => byte,
+      })
+      .collect()
+  }
+
+  /// The ciphertexts XORed with the key.
+  pub fn plaintexts(ciphertexts: &[Vec<u8>], key: &[u8]) -> Vec<Vec<u8>> {
+    ciphertexts
+      .iter()
+      .map(|cipher| {
+        cipher
+          .iter()
+          .zip(key)
+          .map(|(byte, key)| byte ^ key)
+          .collect()
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::{rngs::StdRng, Rng, SeedableRng};
+
+  const MESSAGES: [&str; 11] = [
+    "we can factor the number fifteen with quantum computers",
+    "euler would probably enjoy that now his theorem becomes",
+    "the nice thing about keeyloq is now we cryptographers can",
+    "the ciphertext produced by a weak encryption algorithm",
+    "you do not want to reuse a one time pad for two messages",
+    "there are two types of cryptography one that allows the",
+    "there are two types of cyptography that which will keep",
+    "we can see the point where the chip is unhappy if a wrong",
+    "a private key encryption scheme states three algorithms",
+    "the concept of public key cryptography was invented for",
+    "the secret message is when using a stream cipher never",
+  ];
+
+  fn ciphertexts() -> String {
+    let mut rng = StdRng::seed_from_u64(0);
+    let key: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+
+    let ciphertexts: Vec<String> = MESSAGES
+      .iter()
+      .map(|message| {
+        Hex::new(RepeatingKeyXor::apply(message.as_bytes(), &key)).to_string()
+      })
+      .collect();
+    ciphertexts.join("\n")
+  }
+
+  fn decrypt(config: ManyTimePadConfig) -> Result<Vec<String>> {
+    let mut output = Vec::new();
+    let input = ciphertexts();
+    ManyTimePad::decrypt(&mut input.as_bytes(), &mut output, config)?;
+    let output = String::from_utf8_lossy(&output);
+    Ok(output.lines().map(String::from).collect())
+  }
+
+  fn correct(recovered: &str, message: &str) -> usize {
+    recovered
+      .bytes()
+      .zip(message.bytes())
+      .filter(|(a, b)| a == b)
+      .count()
+  }
+
+  #[test]
+  fn test_recovers_most_of_the_key() -> Result<()> {
+    let config = ManyTimePadConfig {
+      min_confidence: 0.0,
+      ..ManyTimePadConfig::new(None, true)
+    };
+    let plaintexts = decrypt(config)?;
+    let total: usize = plaintexts
+      .iter()
+      .zip(MESSAGES)
+      .map(|(plaintext, message)| correct(plaintext, message))
+      .sum();
+    let length: usize = MESSAGES.iter().map(|message| message.len()).sum();
+
+    for (plaintext, message) in plaintexts.iter().zip(MESSAGES) {
+      assert_eq!(plaintext.len(), message.len());
+    }
+    assert!(total * 10 >= length * 9);
+    Ok(())
+  }
+
+  #[test]
+  fn test_target_selects_plaintext() -> Result<()> {
+    let all = decrypt(ManyTimePadConfig::new(None, true))?;
+    assert_eq!(all.len(), MESSAGES.len());
+    assert_eq!(decrypt(ManyTimePadConfig::new(Some(4), false))?, all[4..5]);
+    assert_eq!(decrypt(ManyTimePadConfig::default())?, all[10..]);
+    assert!(decrypt(ManyTimePadConfig::new(Some(11), false)).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_show_key() -> Result<()> {
+    let config = ManyTimePadConfig {
+      show_key: true,
+      ..ManyTimePadConfig::default()
+    };
+    let output = decrypt(config)?;
+    let key = output[0].strip_prefix("key: ").unwrap();
+    let key = Hex::parse_hex(key)?.bytes;
+
+    let input = ciphertexts();
+    let ciphertexts = ManyTimePad::read_ciphertexts(&mut input.as_bytes())?;
+    assert_eq!(key, ManyTimePad::recover_key(&ciphertexts).bytes);
+    assert_eq!(output[1..], decrypt(ManyTimePadConfig::default())?);
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack() -> Result<()> {
+    let input = ciphertexts();
+    let config = ManyTimePadConfig::new(None, true);
+    let (key, reports) = ManyTimePad::crack(&mut input.as_bytes(), &config)?;
+
+    assert_eq!(reports.len(), MESSAGES.len());
+    assert_eq!(reports[0].key, Some(Hex::new(key.bytes).to_string()));
+    assert_eq!(reports[4].method, "many-time pad");
+    let target = decrypt(ManyTimePadConfig::new(Some(4), false))?;
+    assert_eq!(target, [reports[4].plaintext.clone()]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_read_ciphertexts_reports_line() {
+    let mut input = "0102\n\n0304\n5".as_bytes();
+    let error = ManyTimePad::read_ciphertexts(&mut input).unwrap_err();
+    assert!(matches!(error, CryptologyError::Hex { line: Some(4), .. }));
+  }
+
+  #[test]
+  fn test_parse_cribs() -> Result<()> {
+    let cribs = Crib::parse("0 3 can factor\n\n4 0 you\n")?;
+    assert_eq!(cribs.len(), 2);
+    assert_eq!(cribs[0].index, 0);
+    assert_eq!(cribs[0].offset, 3);
+    assert_eq!(cribs[0].text, b"can factor");
+    assert!(Crib::parse("0 three can").is_err());
+    assert!(Crib::parse("0 3").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_cribs_fix_key() -> Result<()> {
+    let input = ciphertexts();
+    let ciphertexts = ManyTimePad::read_ciphertexts(&mut input.as_bytes())?;
+    let mut key = ManyTimePad::recover_key(&ciphertexts);
+    let cribs = Crib::parse(&format!("4 0 {}", MESSAGES[4]))?;
+    ManyTimePad::apply_cribs(&mut key, &ciphertexts, &cribs)?;
+
+    let fixed = &key.confidence[..MESSAGES[4].len()];
+    assert!(fixed.iter().all(|&confidence| confidence == 1.0));
+    let plaintexts = ManyTimePad::plaintexts(&ciphertexts, &key.bytes);
+    for (plaintext, message) in plaintexts.iter().zip(MESSAGES) {
+      let length = message.len().min(MESSAGES[4].len());
+      assert_eq!(plaintext[..length], message.as_bytes()[..length]);
+    }
+
+    let cribs = Crib::parse("11 0 missing")?;
+    assert!(ManyTimePad::apply_cribs(&mut key, &ciphertexts, &cribs).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_uncertain_characters_are_marked() -> Result<()> {
+    let input = ciphertexts();
+    let ciphertexts = ManyTimePad::read_ciphertexts(&mut input.as_bytes())?;
+    let key = ManyTimePad::recover_key(&ciphertexts);
+    let plaintexts = ManyTimePad::plaintexts(&ciphertexts, &key.bytes);
+
+    let wrong: Vec<usize> = (0..MESSAGES[0].len())
+      .filter(|&i| plaintexts[0][i] != MESSAGES[0].as_bytes()[i])
+      .collect();
+    let marked: Vec<usize> = (0..MESSAGES[0].len())
+      .filter(|&i| key.confidence[i] < 0.5)
+      .collect();
+    assert!(!wrong.is_empty());
+    assert!(wrong.iter().all(|i| marked.contains(i)));
+
+    let marks = ManyTimePad::mark(b"abc", &[0.9, 0.2, 0.5], 0.5);
+    assert_eq!(marks, b"a?c");
+    Ok(())
+  }
+
+  #[test]
+  fn test_wordlist_refinement() -> Result<()> {
+    let input = ciphertexts();
+    let ciphertexts = ManyTimePad::read_ciphertexts(&mut input.as_bytes())?;
+    let mut key = ManyTimePad::recover_key(&ciphertexts);
+    let wrong = |key: &RecoveredKey| {
+      let plaintexts = ManyTimePad::plaintexts(&ciphertexts, &key.bytes);
+      plaintexts
+        .iter()
+        .zip(MESSAGES)
+        .map(|(plaintext, message)| {
+          plaintext.len()
+            - correct(&String::from_utf8_lossy(plaintext), message)
+        })
+        .sum::<usize>()
+    };
+
+    let before = wrong(&key);
+    let wordlist = Wordlist::new(&MESSAGES.join(" "));
+    refinement::refine(&mut key, &ciphertexts, &wordlist, 0.5);
+
+    assert!(before > 0);
+    assert!(wrong(&key) < before);
+    Ok(())
+  }
+
+  #[test]
+  fn test_requires_two_ciphertexts() {
+    let mut input = "09e1c5f70a65ac519458e7e53f36\n".as_bytes();
+    assert!(ManyTimePad::read_ciphertexts(&mut input).is_err());
+  }
+}
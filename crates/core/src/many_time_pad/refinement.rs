@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+
+use super::RecoveredKey;
+
+/// Confidence of key bytes that were deduced from a dictionary word.
+pub const REFINED_CONFIDENCE: f32 = 0.75;
+
+/// Upper bound of refinement passes over all plaintexts.
+const MAX_PASSES: usize = 10;
+
+/// Lowercase dictionary words grouped by their length.
+#[derive(Debug, Default)]
+pub struct Wordlist {
+  words: HashMap<usize, HashSet<Vec<u8>>>,
+}
+
+impl Wordlist {
+  /// Splits a text into words of letters only.
+  pub fn new(text: &str) -> Self {
+    let mut words: HashMap<usize, HashSet<Vec<u8>>> = HashMap::new();
+
+    for word in text.split(|c: char| !c.is_ascii_alphabetic()) {
+      if !word.is_empty() {
+        let word = word.to_ascii_lowercase().into_bytes();
+        words.entry(word.len()).or_default().insert(word);
+      }
+    }
+
+    Self { words }
+  }
+
+  /// The words that agree with the letters of the pattern wherever it
+  /// is not `None`.
+  fn matching<'a>(
+    &'a self,
+    pattern: &'a [Option<u8>],
+  ) -> impl Iterator<Item = &'a Vec<u8>> + 'a {
+    self
+      .words
+      .get(&pattern.len())
+      .into_iter()
+      .flatten()
+      .filter(move |word| {
+        word.iter().zip(pattern).all(|(&letter, known)| {
+          known.map_or(true, |known| known.to_ascii_lowercase() == letter)
+        })
+      })
+  }
+}
+
+/// A run of letters in one plaintext that may be a word.
+struct Segment {
+  cipher: usize,
+  start: usize,
+  end: usize,
+}
+
+/// Completes partially recovered words with dictionary words.
+///
+/// A word is a run of letters and uncertain characters between two
+/// certain non-letters. If exactly one dictionary word agrees with all
+/// its certain letters, the key bytes at its uncertain positions are set
+/// to produce that word, as long as they decrypt the other ciphertexts
+/// to printable text. Corrected key bytes improve the words of the other
+/// plaintexts, so this is repeated until nothing changes.
+pub fn refine(
+  key: &mut RecoveredKey,
+  ciphertexts: &[Vec<u8>],
+  wordlist: &Wordlist,
+  min_confidence: f32,
+) {
+  for _ in 0..MAX_PASSES {
+    let mut changed = false;
+
+    for segment in segments(key, ciphertexts, min_confidence) {
+      let cipher = &ciphertexts[segment.cipher];
+      let range = segment.start..segment.end;
+
+      let pattern: Vec<Option<u8>> = range
+        .clone()
+        .map(|position| match key.confidence[position] < min_confidence {
+          true => None,
+          false => Some(cipher[position] ^ key.bytes[position]),
+        })
+        .collect();
+
+      if pattern.iter().all(Option::is_some) {
+        continue;
+      }
+
+      let mut candidates = wordlist.matching(&pattern);
+      let (Some(word), None) = (candidates.next(), candidates.next()) else {
+        continue;
+      };
+
+      let bytes: Vec<u8> = range
+        .clone()
+        .zip(word)
+        .map(|(position, &letter)| cipher[position] ^ letter)
+        .collect();
+
+      let printable = range.clone().zip(&bytes).all(|(position, &byte)| {
+        ciphertexts
+          .iter()
+          .filter_map(|other| other.get(position))
+          .all(|&other| {
+            let plain = other ^ byte;
+            plain.is_ascii_graphic() || plain == b' '
+          })
+      });
+
+      if !printable {
+        continue;
+      }
+
+      for (position, byte) in range.zip(bytes) {
+        if key.confidence[position] < min_confidence {
+          key.bytes[position] = byte;
+          key.confidence[position] = REFINED_CONFIDENCE;
+          changed = true;
+        }
+      }
+    }
+
+    if !changed {
+      break;
+    }
+  }
+}
+
+/// The possible words of all plaintexts. Runs that are cut off by the
+/// end of the key are skipped, since their length is unknown.
+fn segments(
+  key: &RecoveredKey,
+  ciphertexts: &[Vec<u8>],
+  min_confidence: f32,
+) -> Vec<Segment> {
+  let length = key.bytes.len();
+  let mut segments = Vec::new();
+
+  for (index, cipher) in ciphertexts.iter().enumerate() {
+    let length = length.min(cipher.len());
+    let letter = |position: usize| {
+      key.confidence[position] < min_confidence
+        || (cipher[position] ^ key.bytes[position]).is_ascii_alphabetic()
+    };
+
+    let mut start = 0;
+    while start < length {
+      if !letter(start) {
+        start += 1;
+        continue;
+      }
+
+      let end = (start..length).find(|&p| !letter(p)).unwrap_or(length);
+      if end < length || end == cipher.len() {
+        segments.push(Segment {
+          cipher: index,
+          start,
+          end,
+        });
+      }
+      start = end;
+    }
+  }
+
+  segments
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_wordlist_matching() {
+    let wordlist = Wordlist::new("The cat, the dog and THE cow.");
+    let pattern = [Some(b'c'), None, Some(b't')];
+    let words: Vec<_> = wordlist.matching(&pattern).collect();
+    assert_eq!(words, [b"cat"]);
+    assert_eq!(wordlist.matching(&[None, None, None]).count(), 5);
+  }
+
+  #[test]
+  fn test_refine_completes_word() {
+    let key = [0x10, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70, 0x80];
+    let encrypt = |text: &[u8]| -> Vec<u8> {
+      text.iter().zip(key).map(|(byte, key)| byte ^ key).collect()
+    };
+    let ciphertexts = [encrypt(b"one cat "), encrypt(b"a dog is")];
+
+    let mut recovered = RecoveredKey {
+      bytes: key.to_vec(),
+      confidence: vec![1.0; key.len()],
+    };
+    recovered.bytes[5] ^= 0x0f;
+    recovered.confidence[5] = 0.1;
+
+    let wordlist = Wordlist::new("one cat dog is");
+    refine(&mut recovered, &ciphertexts, &wordlist, 0.5);
+
+    assert_eq!(recovered.bytes, key);
+    assert_eq!(recovered.confidence[5], REFINED_CONFIDENCE);
+  }
+}
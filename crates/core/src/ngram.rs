@@ -0,0 +1,204 @@
+use std::{
+  collections::HashMap,
+  fmt::{self, Debug, Formatter},
+  io::{Error, ErrorKind, Result},
+  path::{Path, PathBuf},
+  str::FromStr,
+  sync::{Arc, LazyLock, Mutex},
+};
+
+/// Longest n-grams a model is built from, which keeps the table of
+/// every possible n-gram in memory at 26^4 entries.
+pub const MAX_N: usize = 4;
+
+/// Models loaded from files, so every cracker of a run shares them.
+static CACHE: LazyLock<Mutex<HashMap<PathBuf, Arc<NgramModel>>>> =
+  LazyLock::new(Mutex::default);
+
+/// Log probabilities of the n-grams of a language.
+///
+/// The statistics are read from a text file with one n-gram and its
+/// count per line, the format of the quadgram statistics published by
+/// practicalcryptography.com:
+///
+/// ```text
+/// TION 13168375
+/// NTHE 11234972
+/// THER 10218035
+/// ```
+///
+/// N-grams that do not occur in the file get the probability of a
+/// hundredth of a single occurrence.
+pub struct NgramModel {
+  n: usize,
+  /// Base 10 logarithm of the probability of every n-gram, indexed by
+  /// its letters as a base 26 number.
+  log_probabilities: Vec<f64>,
+}
+
+impl NgramModel {
+  /// Loads the model of a file, or returns it from the cache if the file
+  /// was loaded before.
+  pub fn load<P: AsRef<Path>>(path: P) -> Result<Arc<Self>> {
+    let path = path.as_ref();
+    let mut cache = CACHE.lock().unwrap_or_else(|error| error.into_inner());
+    if let Some(model) = cache.get(path) {
+      return Ok(Arc::clone(model));
+    }
+
+    let model = Arc::new(std::fs::read_to_string(path)?.parse::<Self>()?);
+    cache.insert(path.to_path_buf(), Arc::clone(&model));
+    Ok(model)
+  }
+
+  /// Length of the n-grams of the model.
+  pub fn n(&self) -> usize {
+    self.n
+  }
+
+  /// Sum of the log probabilities of all n-grams of the letters of the
+  /// text. Higher scores are more likely, and `0.0` is returned for a
+  /// text shorter than an n-gram.
+  pub fn score(&self, text: &str) -> f64 {
+    let letters: Vec<usize> = text
+      .bytes()
+      .filter(u8::is_ascii_alphabetic)
+      .map(|c| (c.to_ascii_uppercase() - b'A') as usize)
+      .collect();
+
+    self.score_indices(&letters)
+  }
+
+  /// Same as [`NgramModel::score`] for letters given as indices `0..26`.
+  pub fn score_indices(&self, letters: &[usize]) -> f64 {
+    letters
+      .windows(self.n)
+      .map(|ngram| self.log_probabilities[Self::index(ngram)])
+      .sum()
+  }
+
+  /// Average log probability of the n-grams of the text, which unlike
+  /// [`NgramModel::score`] is comparable between texts of different
+  /// lengths. Texts shorter than an n-gram have the lowest fitness.
+  pub fn fitness(&self, text: &str) -> f64 {
+    let letters = text.bytes().filter(u8::is_ascii_alphabetic).count();
+    match letters.checked_sub(self.n) {
+      Some(windows) => self.score(text) / (windows + 1) as f64,
+      None => f64::NEG_INFINITY,
+    }
+  }
+
+  fn index(ngram: &[usize]) -> usize {
+    ngram.iter().fold(0, |index, &c| index * 26 + c)
+  }
+
+  fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+  }
+}
+
+impl FromStr for NgramModel {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    let mut counts = Vec::new();
+    let mut n = None;
+
+    for (number, line) in s.lines().enumerate() {
+      let mut fields = line.split_whitespace();
+      let (Some(ngram), Some(count), None) =
+        (fields.next(), fields.next(), fields.next())
+      else {
+        if line.trim().is_empty() {
+          continue;
+        }
+        let message = format!("Expected 'NGRAM COUNT' on line {}", number + 1);
+        return Err(Self::invalid(message));
+      };
+
+      let letters: Vec<usize> = ngram
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|c| (c.to_ascii_uppercase() - b'A') as usize)
+        .collect();
+      let count: u64 = count.parse().map_err(|_| {
+        Self::invalid(format!("Invalid count '{count}' of '{ngram}'"))
+      })?;
+
+      if letters.len() != ngram.len() || !(1..=MAX_N).contains(&letters.len()) {
+        let message =
+          format!("'{ngram}' is not an n-gram of 1 to {MAX_N} letters");
+        return Err(Self::invalid(message));
+      }
+      if *n.get_or_insert(letters.len()) != letters.len() {
+        let message =
+          format!("'{ngram}' differs in length from the n-grams before");
+        return Err(Self::invalid(message));
+      }
+
+      counts.push((Self::index(&letters), count));
+    }
+
+    let n = n.ok_or_else(|| Self::invalid("No n-grams found".to_string()))?;
+    let total: u64 = counts.iter().map(|&(_, count)| count).sum();
+    let total = total.max(1) as f64;
+    let mut log_probabilities =
+      vec![(0.01 / total).log10(); 26usize.pow(n as u32)];
+    for (index, count) in counts {
+      if count > 0 {
+        log_probabilities[index] = (count as f64 / total).log10();
+      }
+    }
+
+    Ok(Self {
+      n,
+      log_probabilities,
+    })
+  }
+}
+
+impl Debug for NgramModel {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_struct("NgramModel").field("n", &self.n).finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BIGRAMS: &str = "TH 6\nHE 3\n\nIN 1\n";
+
+  #[test]
+  fn test_score() {
+    let model: NgramModel = BIGRAMS.parse().unwrap();
+    assert_eq!(model.n(), 2);
+
+    let expected = (0.6f64).log10() + (0.3f64).log10();
+    assert!((model.score("the") - expected).abs() < 1e-9);
+    assert!((model.fitness("t h e") - expected / 2.0).abs() < 1e-9);
+    assert_eq!(model.score("q"), 0.0);
+    assert_eq!(model.fitness("q"), f64::NEG_INFINITY);
+    assert!(model.score("zz") < model.score("in"));
+  }
+
+  #[test]
+  fn test_invalid_files_are_rejected() {
+    assert!("".parse::<NgramModel>().is_err());
+    assert!("TH 1\nTHE 2".parse::<NgramModel>().is_err());
+    assert!("TH many".parse::<NgramModel>().is_err());
+    assert!("T1 3".parse::<NgramModel>().is_err());
+    assert!("TIONS 3".parse::<NgramModel>().is_err());
+  }
+
+  #[test]
+  fn test_load_is_cached() -> Result<()> {
+    let path = std::env::temp_dir().join("cryptology-bigrams.txt");
+    std::fs::write(&path, BIGRAMS)?;
+    let first = NgramModel::load(&path)?;
+    std::fs::remove_file(&path)?;
+    let second = NgramModel::load(&path)?;
+    assert!(Arc::ptr_eq(&first, &second));
+    Ok(())
+  }
+}
@@ -0,0 +1,388 @@
+use std::{
+  fmt::Display,
+  io::{Read, Write},
+};
+
+use rand::{rngs::OsRng, rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+  error::CryptologyError, hex::Hex, key_format::KeyFormat, xor::Xor,
+};
+
+#[derive(Default, Debug)]
+pub struct OneTimePadDecryptConfig {
+  pub key: Option<String>,
+  pub raw_input: bool,
+  pub key_format: KeyFormat,
+  /// Only warn instead of failing if the key is shorter than a message.
+  pub force: bool,
+}
+
+impl OneTimePadDecryptConfig {
+  pub fn new(
+    key: Option<String>,
+    raw_input: bool,
+    key_format: KeyFormat,
+  ) -> Self {
+    Self {
+      key,
+      raw_input,
+      key_format,
+      ..Self::default()
+    }
+  }
+}
+
+#[derive(Default, Debug)]
+pub struct OneTimePadEncryptConfig {
+  pub key: String,
+  pub raw_input: bool,
+  pub key_format: KeyFormat,
+  /// Encrypt with a random pad as long as the message instead of the key.
+  /// The pad then replaces the key as hex.
+  pub generate_key: bool,
+  /// Seed for a reproducible generated pad, which voids its secrecy.
+  pub seed: Option<u64>,
+  /// Only warn instead of failing if the key is shorter than the message.
+  pub force: bool,
+}
+
+impl OneTimePadEncryptConfig {
+  pub fn new(key: String, raw_input: bool, key_format: KeyFormat) -> Self {
+    Self {
+      key,
+      raw_input,
+      key_format,
+      ..Self::default()
+    }
+  }
+}
+
+/// A key shorter than the message, which `force` accepts. The rest of
+/// the message is then left unencrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortKey {
+  pub key: usize,
+  pub message: usize,
+}
+
+impl Display for ShortKey {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let (key, message) = (self.key, self.message);
+    write!(
+      f,
+      "Key of {key} bytes is shorter than the message of {message}"
+    )
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct OneTimePad {
+  pub xor: Xor,
+  /// Set if `force` accepted a key shorter than the message.
+  pub short_key: Option<ShortKey>,
+}
+
+impl Display for OneTimePad {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let xor = &self.xor;
+    write!(f, "{xor}")
+  }
+}
+
+impl OneTimePad {
+  pub fn new(xor: Xor) -> Self {
+    Self {
+      xor,
+      short_key: None,
+    }
+  }
+
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: &mut OneTimePadEncryptConfig,
+  ) -> Result<Self, CryptologyError> {
+    let mut plaintext = String::new();
+
+    input.read_to_string(&mut plaintext)?;
+
+    let alpha = if config.raw_input {
+      Hex::parse_hex(plaintext.trim_end())?
+    } else {
+      plaintext.try_into()?
+    };
+
+    let beta = if config.generate_key {
+      let mut pad = vec![0; alpha.bytes.len()];
+      match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed).fill_bytes(&mut pad),
+        None => OsRng.fill_bytes(&mut pad),
+      }
+      let pad = Hex::new(pad);
+      config.key = pad.to_string();
+      config.key_format = KeyFormat::Hex;
+      pad
+    } else {
+      Self::parse_key(&config.key, config.key_format)?
+    };
+
+    let short_key = Self::check_key_length(&beta, &alpha, config.force)?;
+
+    let xor = Xor::xor_bytes_padded(&alpha.bytes, &beta.bytes, 0);
+    let otp = Self {
+      short_key,
+      ..Self::new(xor)
+    };
+
+    write!(output, "{otp}")?;
+
+    Ok(otp)
+  }
+
+  /// Decrypts every line and returns the short keys `force` accepted.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: &mut OneTimePadDecryptConfig,
+  ) -> Result<Vec<ShortKey>, CryptologyError> {
+    let lines = Self::decrypt_lines(input, config)?;
+    for line in &lines {
+      writeln!(output, "{line}")?;
+    }
+    Ok(
+      lines
+        .into_iter()
+        .filter_map(|line| line.short_key)
+        .collect(),
+    )
+  }
+
+  /// Decrypts every line on its own. Hex errors report the line they
+  /// were found on.
+  pub fn decrypt_lines<R: Read>(
+    input: &mut R,
+    config: &mut OneTimePadDecryptConfig,
+  ) -> Result<Vec<Self>, CryptologyError> {
+    let mut ciphertext = String::new();
+
+    input.read_to_string(&mut ciphertext)?;
+
+    ciphertext
+      .lines()
+      .enumerate()
+      .map(|(index, line)| {
+        Self::decrypt_line(line, config).map_err(|e| e.at_line(index + 1))
+      })
+      .collect()
+  }
+
+  pub fn decrypt_line(
+    line: &str,
+    config: &mut OneTimePadDecryptConfig,
+  ) -> Result<Self, CryptologyError> {
+    let key = config.key.clone().unwrap_or_default();
+
+    let alpha = if config.raw_input {
+      Hex::parse_hex(line)?
+    } else {
+      line.try_into()?
+    };
+
+    let beta = Self::parse_key(&key, config.key_format)?;
+
+    let short_key = Self::check_key_length(&beta, &alpha, config.force)?;
+
+    let xor = Xor::xor_bytes_padded(&alpha.bytes, &beta.bytes, 0);
+
+    Ok(Self {
+      short_key,
+      ..Self::new(xor)
+    })
+  }
+
+  /// Key errors are reported as such instead of as invalid input.
+  fn parse_key(
+    key: &str,
+    key_format: KeyFormat,
+  ) -> Result<Hex, CryptologyError> {
+    key_format
+      .parse(key)
+      .map(Hex::new)
+      .map_err(|e| CryptologyError::key(e.to_string()))
+  }
+
+  /// A key shorter than the message is padded with zeros, which leaves
+  /// the rest of the message unencrypted. This is an error unless
+  /// `force` is set, in which case the short key is returned for the
+  /// caller to warn about.
+  fn check_key_length(
+    key: &Hex,
+    message: &Hex,
+    force: bool,
+  ) -> Result<Option<ShortKey>, CryptologyError> {
+    let (key, message) = (key.bytes.len(), message.bytes.len());
+
+    if key >= message {
+      return Ok(None);
+    }
+
+    let short_key = ShortKey { key, message };
+
+    if force {
+      Ok(Some(short_key))
+    } else {
+      Err(CryptologyError::Key(short_key.to_string()))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_otp() {
+    let mut input = "Hello".as_bytes();
+    let mut output = Vec::new();
+
+    let mut cfg = OneTimePadDecryptConfig {
+      key: Some(String::from("World")),
+      raw_input: false,
+      key_format: KeyFormat::Ascii,
+      ..Default::default()
+    };
+
+    OneTimePad::decrypt(&mut input, &mut output, &mut cfg).unwrap();
+
+    let result = String::from_utf8(output).unwrap();
+
+    assert_eq!(result, "1f0a1e000b\n")
+  }
+
+  #[test]
+  fn test_otp_example() {
+    let mut input = "attack at dawn".as_bytes();
+    let mut output = Vec::new();
+
+    let ciphertext = String::from("09e1c5f70a65ac519458e7e53f36");
+
+    let mut cfg = OneTimePadEncryptConfig {
+      key: ciphertext,
+      raw_input: false,
+      key_format: KeyFormat::Hex,
+      ..Default::default()
+    };
+
+    let key = OneTimePad::encrypt(&mut input, &mut output, &mut cfg).unwrap();
+
+    let mut input = "attack at dusk".as_bytes();
+
+    let key = format!("{key}");
+
+    let mut cfg = OneTimePadEncryptConfig {
+      key,
+      raw_input: false,
+      key_format: KeyFormat::Hex,
+      ..Default::default()
+    };
+
+    let otp = OneTimePad::encrypt(&mut input, &mut output, &mut cfg).unwrap();
+
+    let result = format!("{otp}");
+
+    let expected = "09e1c5f70a65ac519458e7f13b33";
+
+    assert_eq!(result, expected)
+  }
+
+  #[test]
+  fn test_otp_generate_key() {
+    let mut input = "attack at dawn".as_bytes();
+    let mut output = Vec::new();
+
+    let mut cfg = OneTimePadEncryptConfig {
+      generate_key: true,
+      ..Default::default()
+    };
+
+    OneTimePad::encrypt(&mut input, &mut output, &mut cfg).unwrap();
+
+    let pad = Hex::parse_hex(&cfg.key).unwrap();
+    let cipher = Hex::parse_hex(&String::from_utf8(output).unwrap()).unwrap();
+    let plaintext = Xor::xor_bytes(&cipher.bytes, &pad.bytes);
+
+    assert_eq!(pad.bytes.len(), 14);
+    assert_eq!(plaintext.hex.to_ascii(), "attack at dawn");
+  }
+
+  #[test]
+  fn test_otp_seeded_pad_repeats() {
+    let encrypt = || {
+      let mut cfg = OneTimePadEncryptConfig {
+        generate_key: true,
+        seed: Some(1),
+        ..Default::default()
+      };
+      let mut input = "attack at dawn".as_bytes();
+      OneTimePad::encrypt(&mut input, &mut Vec::new(), &mut cfg).unwrap();
+      cfg.key
+    };
+    assert_eq!(encrypt(), encrypt());
+  }
+
+  #[test]
+  fn test_otp_rejects_short_key() {
+    let mut input = "attack at dawn".as_bytes();
+    let mut cfg = OneTimePadEncryptConfig::new(
+      String::from("key"),
+      false,
+      KeyFormat::Ascii,
+    );
+    let result = OneTimePad::encrypt(&mut input, &mut Vec::new(), &mut cfg);
+    assert!(result.is_err());
+
+    let mut input = "attack at dawn".as_bytes();
+    cfg.force = true;
+    let otp = OneTimePad::encrypt(&mut input, &mut Vec::new(), &mut cfg);
+    let short_key = ShortKey {
+      key: 3,
+      message: 14,
+    };
+    assert_eq!(otp.unwrap().short_key, Some(short_key));
+
+    let mut cfg = OneTimePadDecryptConfig::new(
+      Some(String::from("ab")),
+      true,
+      KeyFormat::Hex,
+    );
+    let result = OneTimePad::decrypt_line("0102", &mut cfg);
+    assert!(result.is_err());
+
+    cfg.force = true;
+    let mut input = "0102\nab\n".as_bytes();
+    let short_keys =
+      OneTimePad::decrypt(&mut input, &mut Vec::new(), &mut cfg).unwrap();
+    assert_eq!(short_keys, [ShortKey { key: 1, message: 2 }]);
+  }
+
+  #[test]
+  fn test_otp_reports_invalid_line() {
+    let mut input = "0102\nzz\n".as_bytes();
+    let mut cfg = OneTimePadDecryptConfig::new(
+      Some(String::from("abcd")),
+      true,
+      KeyFormat::Hex,
+    );
+    let error = OneTimePad::decrypt_lines(&mut input, &mut cfg).unwrap_err();
+    assert!(matches!(error, CryptologyError::Hex { line: Some(2), .. }));
+
+    let mut cfg = OneTimePadDecryptConfig::new(
+      Some(String::from("xy")),
+      true,
+      KeyFormat::Hex,
+    );
+    let error = OneTimePad::decrypt_line("0102", &mut cfg).unwrap_err();
+    assert!(matches!(error, CryptologyError::Key(_)));
+  }
+}
@@ -0,0 +1,168 @@
+use std::{
+  io::{self, IsTerminal, Write},
+  sync::{
+    atomic::{AtomicU32, AtomicUsize, Ordering},
+    Mutex,
+  },
+  time::{Duration, Instant},
+};
+
+/// Minimum time between two status lines.
+const INTERVAL: Duration = Duration::from_millis(500);
+
+/// Status of a long search, written to standard error.
+///
+/// The workers of a parallel search share one `Progress` and report the
+/// candidates they tried with [`Progress::tick`]. At most every half
+/// second a status line with the number of candidates tried, the best
+/// score so far and the estimated remaining time is written. On a
+/// terminal every line overwrites the previous one. A disabled
+/// `Progress` writes nothing.
+#[derive(Debug)]
+pub struct Progress {
+  label: &'static str,
+  total: usize,
+  enabled: bool,
+  tried: AtomicUsize,
+  /// Bits of the lowest score as `f32`, since there is no atomic float.
+  best: AtomicU32,
+  start: Instant,
+  last: Mutex<Instant>,
+}
+
+impl Progress {
+  pub fn new(label: &'static str, total: usize, enabled: bool) -> Self {
+    let start = Instant::now();
+    Self {
+      label,
+      total,
+      enabled,
+      tried: AtomicUsize::new(0),
+      best: AtomicU32::new(f32::INFINITY.to_bits()),
+      start,
+      last: Mutex::new(start),
+    }
+  }
+
+  pub fn disabled() -> Self {
+    Self::new("", 0, false)
+  }
+
+  /// Counts `candidates` more as tried, the best of which scored
+  /// `score`. Lower scores are better.
+  pub fn tick(&self, candidates: usize, score: f32) {
+    if !self.enabled {
+      return;
+    }
+
+    self.tried.fetch_add(candidates, Ordering::Relaxed);
+    let lower =
+      |best| (score < f32::from_bits(best)).then_some(score.to_bits());
+    let _ = self
+      .best
+      .fetch_update(Ordering::Relaxed, Ordering::Relaxed, lower);
+
+    // Workers that find the lock taken skip the line instead of waiting.
+    let Ok(mut last) = self.last.try_lock() else {
+      return;
+    };
+    if last.elapsed() >= INTERVAL {
+      *last = Instant::now();
+      drop(last);
+      self.write(false);
+    }
+  }
+
+  /// Writes the final status line.
+  pub fn finish(&self) {
+    if self.enabled {
+      self.write(true);
+    }
+  }
+
+  pub fn tried(&self) -> usize {
+    self.tried.load(Ordering::Relaxed)
+  }
+
+  /// The lowest score reported so far.
+  pub fn best(&self) -> Option<f32> {
+    Some(f32::from_bits(self.best.load(Ordering::Relaxed)))
+      .filter(|best| best.is_finite())
+  }
+
+  pub fn status(&self) -> String {
+    self.line(false)
+  }
+
+  /// The final line has no estimate, since the search may end before
+  /// all candidates of the estimated total are tried.
+  fn line(&self, done: bool) -> String {
+    let tried = self.tried();
+    let mut status =
+      format!("{}: {tried}/{} candidates", self.label, self.total);
+    if let Some(best) = self.best() {
+      status.push_str(&format!(", best score {best:.4}"));
+    }
+    let eta = Self::eta(tried, self.total, self.start.elapsed());
+    if let Some(eta) = eta.filter(|_| !done) {
+      status.push_str(&format!(", ETA {}s", eta.as_secs()));
+    }
+    status
+  }
+
+  /// Remaining time if the search continues at the rate it had so far.
+  pub fn eta(
+    tried: usize,
+    total: usize,
+    elapsed: Duration,
+  ) -> Option<Duration> {
+    (tried > 0 && tried < total)
+      .then(|| elapsed.mul_f64((total - tried) as f64 / tried as f64))
+  }
+
+  fn write(&self, done: bool) {
+    let status = self.line(done);
+    let mut stderr = io::stderr().lock();
+    let _ = match (stderr.is_terminal(), done) {
+      (true, false) => write!(stderr, "\r{status}\x1b[K"),
+      (true, true) => writeln!(stderr, "\r{status}\x1b[K"),
+      (false, _) => writeln!(stderr, "{status}"),
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_status() {
+    let progress = Progress::new("vigenere", 10, true);
+    assert_eq!(progress.status(), "vigenere: 0/10 candidates");
+
+    progress.tick(2, 0.5);
+    progress.tick(3, 0.25);
+    progress.tick(1, 0.75);
+    assert_eq!(progress.tried(), 6);
+    assert_eq!(progress.best(), Some(0.25));
+    assert!(progress
+      .status()
+      .starts_with("vigenere: 6/10 candidates, best score 0.2500, ETA"));
+
+    let disabled = Progress::disabled();
+    disabled.tick(1, 0.0);
+    assert_eq!(disabled.tried(), 0);
+    assert_eq!(disabled.best(), None);
+  }
+
+  #[test]
+  fn test_eta() {
+    let elapsed = Duration::from_secs(10);
+    assert_eq!(
+      Progress::eta(25, 100, elapsed),
+      Some(Duration::from_secs(30))
+    );
+    assert_eq!(Progress::eta(0, 100, elapsed), None);
+    assert_eq!(Progress::eta(100, 100, elapsed), None);
+  }
+}
@@ -0,0 +1,296 @@
+use std::io::{Result, Write};
+
+use crate::{
+  error::CryptologyError,
+  frequency_analysis::{FrequencyAnalyzer, Language},
+};
+
+/// Confidence below which a cracked plaintext is probably not English.
+pub const LOW_CONFIDENCE: f32 = 0.7;
+
+/// Standard errors of the confidence a short plaintext may fall below
+/// [`LOW_CONFIDENCE`] before the crack counts as failed.
+const LOW_CONFIDENCE_ERRORS: f32 = 1.5;
+
+/// How the results of cracking a cipher are written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum OutputFormat {
+  /// The plain text output of every command.
+  #[default]
+  Text,
+  /// A JSON array with the plaintext, key, score, confidence and method
+  /// of every ciphertext.
+  Json,
+}
+
+/// The result of cracking one ciphertext.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrackReport {
+  pub plaintext: String,
+  /// The key that was given or recovered, if there is one.
+  pub key: Option<String>,
+  /// Chi-square distance of the letter frequencies of the plaintext to
+  /// its language. Lower is better, and it is missing without letters.
+  pub score: Option<f32>,
+  /// [`FrequencyAnalyzer::confidence_in`] of the plaintext from 0 to 1,
+  /// missing with less than two letters.
+  pub confidence: Option<f32>,
+  /// How the plaintext was found.
+  pub method: &'static str,
+  /// The language the plaintext is scored in.
+  pub language: Language,
+}
+
+impl CrackReport {
+  /// Scores an English plaintext, see [`CrackReport::new_in`].
+  pub fn new(
+    plaintext: String,
+    key: Option<String>,
+    method: &'static str,
+  ) -> Self {
+    Self::new_in(plaintext, key, method, Language::English)
+  }
+
+  /// Scores the plaintext with [`FrequencyAnalyzer::score_text_in`] in
+  /// `language`, so the scores of all methods are comparable.
+  pub fn new_in(
+    plaintext: String,
+    key: Option<String>,
+    method: &'static str,
+    language: Language,
+  ) -> Self {
+    let alphabet = language.alphabet();
+    let letters = plaintext.chars().any(|c| alphabet.index(c).is_some());
+    let score =
+      FrequencyAnalyzer::score_text_in(&mut plaintext.as_bytes(), language)
+        .ok()
+        .filter(|score| letters && score.is_finite());
+    let confidence = FrequencyAnalyzer::confidence_in(&plaintext, language);
+    Self {
+      plaintext,
+      key,
+      score,
+      confidence,
+      method,
+      language,
+    }
+  }
+
+  /// Fails with the lowest confidence of the reports that are below
+  /// their [`CrackReport::low_confidence`]. Reports without a confidence
+  /// are skipped.
+  pub fn check_confidence(
+    reports: &[Self],
+  ) -> std::result::Result<(), CryptologyError> {
+    let lowest = reports
+      .iter()
+      .filter_map(|report| {
+        let confidence = report.confidence?;
+        (confidence < report.low_confidence()).then_some(confidence)
+      })
+      .min_by(f32::total_cmp);
+
+    match lowest {
+      Some(confidence) => Err(CryptologyError::LowConfidence(confidence)),
+      None => Ok(()),
+    }
+  }
+
+  /// [`LOW_CONFIDENCE`] lowered by [`LOW_CONFIDENCE_ERRORS`] standard
+  /// errors of the confidence of the plaintext, so that correct cracks
+  /// of short texts with a few rare bigrams are not rejected.
+  pub fn low_confidence(&self) -> f32 {
+    let error =
+      FrequencyAnalyzer::confidence_error_in(&self.plaintext, self.language);
+    LOW_CONFIDENCE - LOW_CONFIDENCE_ERRORS * error.unwrap_or_default()
+  }
+
+  /// Writes the key of every report on its own line, so the keys line
+  /// up with the ciphertexts. Reports without a key leave a blank line.
+  pub fn write_keys<W: Write>(output: &mut W, reports: &[Self]) -> Result<()> {
+    for report in reports {
+      writeln!(output, "{}", report.key.as_deref().unwrap_or_default())?;
+    }
+    Ok(())
+  }
+
+  /// Writes the candidates of one ciphertext in the given order, best
+  /// first, one per line with their rank, key and confidence. Control
+  /// characters of the plaintext are escaped to keep one line each.
+  pub fn write_candidates<W: Write>(
+    output: &mut W,
+    candidates: &[Self],
+  ) -> Result<()> {
+    for (rank, candidate) in candidates.iter().enumerate() {
+      let key = candidate.key.as_deref().unwrap_or("-");
+      let confidence = match candidate.confidence {
+        Some(confidence) => format!("{confidence:.2}"),
+        None => String::from("-"),
+      };
+      let plaintext: String = candidate
+        .plaintext
+        .chars()
+        .flat_map(|c| match c.is_control() {
+          true => c.escape_default().collect::<Vec<char>>(),
+          false => vec![c],
+        })
+        .collect();
+      writeln!(
+        output,
+        "{}. key {key}, confidence {confidence}: {plaintext}",
+        rank + 1
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Writes the reports as a JSON array on a single line.
+  pub fn write_json<W: Write>(output: &mut W, reports: &[Self]) -> Result<()> {
+    let objects: Vec<String> = reports.iter().map(Self::to_json).collect();
+    writeln!(output, "[{}]", objects.join(","))
+  }
+
+  pub fn to_json(&self) -> String {
+    let key = match &self.key {
+      Some(key) => Self::json_string(key),
+      None => String::from("null"),
+    };
+    let number = |value: Option<f32>| match value {
+      Some(value) => value.to_string(),
+      None => String::from("null"),
+    };
+    let (score, confidence) = (number(self.score), number(self.confidence));
+    format!(
+      "{{\"plaintext\":{},\"key\":{key},\"score\":{score},\"confidence\":{confidence},\"method\":{}}}",
+      Self::json_string(&self.plaintext),
+      Self::json_string(self.method)
+    )
+  }
+
+  fn json_string(value: &str) -> String {
+    let mut json = String::from('"');
+    for c in value.chars() {
+      match c {
+        '"' => json.push_str("\\\""),
+        '\\' => json.push_str("\\\\"),
+        '\n' => json.push_str("\\n"),
+        '\r' => json.push_str("\\r"),
+        '\t' => json.push_str("\\t"),
+        c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+        c => json.push(c),
+      }
+    }
+    json.push('"');
+    json
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{alphabet::Alphabet, caesar::Caesar};
+
+  #[test]
+  fn test_to_json() {
+    let report = CrackReport {
+      plaintext: String::from("say \"hi\"\n\u{1}"),
+      key: None,
+      score: Some(1.5),
+      confidence: Some(0.25),
+      method: "key",
+      language: Language::English,
+    };
+    assert_eq!(
+      report.to_json(),
+      r#"{"plaintext":"say \"hi\"\n\u0001","key":null,"score":1.5,"confidence":0.25,"method":"key"}"#
+    );
+  }
+
+  #[test]
+  fn test_write_keys() -> Result<()> {
+    let reports = [
+      CrackReport::new(String::from("HELLO"), Some("3".into()), "key"),
+      CrackReport::new(String::from("WORLD"), None, "key"),
+    ];
+    let mut output = Vec::new();
+    CrackReport::write_keys(&mut output, &reports)?;
+    assert_eq!(output, b"3\n\n");
+    Ok(())
+  }
+
+  #[test]
+  fn test_write_candidates() -> Result<()> {
+    let candidates = [
+      CrackReport::new(String::from("attack at dawn"), Some("3".into()), "key"),
+      CrackReport::new(String::from("\u{1}\n"), None, "key"),
+    ];
+    let mut output = Vec::new();
+    CrackReport::write_candidates(&mut output, &candidates)?;
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "1. key 3, confidence 0.82: attack at dawn\n2. key -, confidence -: \\u{1}\\n\n"
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_write_json() -> Result<()> {
+    let report =
+      CrackReport::new(String::from("1234"), Some("k".into()), "key");
+    assert_eq!(report.score, None);
+
+    let mut output = Vec::new();
+    CrackReport::write_json(&mut output, &[report.clone(), report])?;
+    let line = r#"{"plaintext":"1234","key":"k","score":null,"confidence":null,"method":"key"}"#;
+    assert_eq!(
+      String::from_utf8_lossy(&output),
+      format!("[{line},{line}]\n")
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn test_check_confidence() {
+    let english = String::from("the enemy will attack the northern gate");
+    let garbage = String::from("xqzj vkwq pxjz qvkx");
+    let reports = [
+      CrackReport::new(english, None, "key"),
+      CrackReport::new(String::from("42"), None, "key"),
+    ];
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+
+    let reports = [CrackReport::new(garbage, None, "key")];
+    let error = CrackReport::check_confidence(&reports).unwrap_err();
+    assert!(
+      matches!(error, CryptologyError::LowConfidence(c) if c < LOW_CONFIDENCE)
+    );
+  }
+
+  #[test]
+  fn test_check_confidence_in_language() {
+    let russian = String::from("Враг будет разбит, победа будет за нами");
+    let garbage = Alphabet::CYRILLIC.repeat(8);
+    let reports =
+      [CrackReport::new_in(russian, None, "key", Language::Russian)];
+    assert!(reports[0].score.is_some());
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+
+    let reports =
+      [CrackReport::new_in(garbage, None, "key", Language::Russian)];
+    assert!(CrackReport::check_confidence(&reports).is_err());
+  }
+
+  #[test]
+  fn test_check_confidence_of_short_crack() -> Result<()> {
+    let cipher = "Wkh txlfn eurzq ira mxpsv ryhu wkh odcb grj";
+    let reports = Caesar::crack(&mut cipher.as_bytes(), &Default::default())?;
+    assert_eq!(
+      reports[0].plaintext,
+      "The quick brown fox jumps over the lazy dog"
+    );
+    assert!(reports[0].confidence < Some(LOW_CONFIDENCE));
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+    Ok(())
+  }
+}
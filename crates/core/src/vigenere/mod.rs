@@ -0,0 +1,1152 @@
+use rayon::prelude::*;
+
+use std::{
+  io::{Cursor, Error, ErrorKind, Read, Result, Write},
+  sync::{Arc, Mutex},
+};
+
+use crate::{
+  alphabet::Alphabet,
+  autocorrelation::Autocorrelation,
+  caesar::Caesar,
+  dictionary_attack::{DictionaryAttack, DictionaryAttackConfig},
+  frequency_analysis::{FrequencyAnalyzer, Language},
+  progress::Progress,
+  report::CrackReport,
+};
+
+pub struct VigenereDecryptConfig {
+  pub key: Option<String>,
+  pub key_length: Option<u8>,
+  pub max_key_length: u8,
+  pub show_key: bool,
+  /// Candidate keys separated by whitespace.
+  pub wordlist: Option<String>,
+  /// How the words of the wordlist are tried as keys.
+  pub dictionary: DictionaryAttackConfig,
+  pub crib: Option<String>,
+  /// Report the progress of the key length search on standard error.
+  pub progress: bool,
+  /// Write the table of [`Vigenere::diagnose`] before every plaintext
+  /// whose key is cracked.
+  pub diagnostics: bool,
+  /// The symbols that are shifted, `A..=Z` by default.
+  pub alphabet: Alphabet,
+  /// The language whose letter frequencies the key is recovered with.
+  /// Wordlist candidates are always scored as English.
+  pub language: Language,
+}
+
+impl VigenereDecryptConfig {
+  /// Creates a new `VigenereDecryptConfig`.
+  ///
+  /// - `key`: The decryption key, if known.
+  /// - `key_length`: The key length, if known.
+  /// - `max_key_length`: The upper bound for key length to attempt a full crack. Defaults to 20.
+  /// - `show_key`: Whether the key of every line is printed before its plaintext.
+  pub fn new(
+    key: Option<String>,
+    key_length: Option<u8>,
+    max_key_length: Option<u8>,
+    show_key: bool,
+  ) -> Self {
+    Self {
+      key,
+      key_length,
+      max_key_length: max_key_length.unwrap_or(20),
+      show_key,
+      wordlist: None,
+      dictionary: DictionaryAttackConfig::default(),
+      crib: None,
+      progress: false,
+      diagnostics: false,
+      alphabet: Alphabet::default(),
+      language: Language::English,
+    }
+  }
+
+  /// Rejects key lengths no key can be recovered for. Without a key
+  /// length the lengths from 2 up to the maximum are searched.
+  fn validate(&self) -> Result<()> {
+    let message = match (&self.key, self.key_length) {
+      (None, Some(0)) => "Key length must be at least 1",
+      (None, None) if self.max_key_length < 2 => {
+        "Maximum key length must be at least 2"
+      }
+      _ => return Ok(()),
+    };
+    Err(Error::new(ErrorKind::InvalidInput, message))
+  }
+}
+
+impl Default for VigenereDecryptConfig {
+  fn default() -> Self {
+    Self {
+      key: None,
+      key_length: None,
+      max_key_length: 20,
+      show_key: false,
+      wordlist: None,
+      dictionary: DictionaryAttackConfig::default(),
+      crib: None,
+      progress: false,
+      diagnostics: false,
+      alphabet: Alphabet::default(),
+      language: Language::English,
+    }
+  }
+}
+
+pub struct VigenereEncryptConfig {
+  pub key: String,
+  /// The symbols that are shifted, `A..=Z` by default.
+  pub alphabet: Alphabet,
+}
+
+impl VigenereEncryptConfig {
+  pub fn new(key: &str) -> Self {
+    Self {
+      key: key.to_string(),
+      alphabet: Alphabet::default(),
+    }
+  }
+}
+
+impl Default for VigenereEncryptConfig {
+  fn default() -> Self {
+    Self::new("key")
+  }
+}
+
+/// Highest bigram score at which a plaintext decrypted with a word of
+/// the wordlist is accepted as English.
+const WORDLIST_THRESHOLD: f32 = 2.8;
+
+/// Number of key lengths with the highest index of coincidence that
+/// are fully solved when the key length is unknown, in addition to the
+/// period estimated by autocorrelation.
+const KEY_LENGTH_CANDIDATES: usize = 3;
+
+/// Share of the index of coincidence above that of random letters that
+/// a divisor of a key length has to keep to be tried in its place.
+///
+/// Multiples of the period have columns of fewer letters, which rank
+/// them above the period on short texts, while a divisor of a longer
+/// key mixes several shifts and keeps only part of the index.
+const KEY_LENGTH_TOLERANCE: f32 = 0.7;
+
+/// A key that reproduces a crib at an offset of the ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CribMatch {
+  /// Position of the crib among the letters of the ciphertext.
+  pub offset: usize,
+  /// The key, aligned to the start of the ciphertext.
+  pub key: String,
+}
+
+/// How well a key length explains a ciphertext, as weighed while
+/// cracking it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyLengthDiagnostic {
+  pub key_length: u8,
+  /// Average index of coincidence of the Caesar columns.
+  pub index_of_coincidence: f32,
+  /// Chi-square score of the plaintext decrypted with the key solved
+  /// for this length, lower is better.
+  pub chi_square: f32,
+  pub key: String,
+  /// Whether the cracker solves this length fully.
+  pub tried: bool,
+  /// Whether the cracker picks this length.
+  pub chosen: bool,
+}
+
+pub struct Vigenere;
+
+impl Vigenere {
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: VigenereEncryptConfig,
+  ) -> Result<()> {
+    let alphabet = &config.alphabet;
+    let shifts = alphabet.key_shifts(&config.key)?;
+    let mut content = String::new();
+    let mut shifts = shifts.into_iter().cycle();
+    input.read_to_string(&mut content)?;
+
+    let cipher: String = content
+      .chars()
+      .map(|c| match alphabet.contains(c) {
+        true => alphabet.shift(c, shifts.next().unwrap() as isize),
+        false => c,
+      })
+      .collect();
+
+    write!(output, "{cipher}")?;
+    Ok(())
+  }
+
+  /// Writes the plaintext of every line, preceded by its key if
+  /// configured, and returns the reports of [`Vigenere::crack`]. With a
+  /// crib the matching keys are written instead and nothing is reported.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: VigenereDecryptConfig,
+  ) -> Result<Vec<CrackReport>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    if let Some(crib) = &config.crib {
+      let matches = Self::crib_keys_in(
+        &content,
+        crib,
+        config.max_key_length,
+        &config.alphabet,
+      );
+      for CribMatch { offset, key } in matches {
+        writeln!(output, "offset {offset}: {key}")?;
+      }
+      return Ok(Vec::new());
+    }
+
+    let reports = Self::crack(&mut content.as_bytes(), &config)?;
+    for (line, report) in content.lines().zip(&reports) {
+      if config.diagnostics && config.key.is_none() {
+        let diagnostics = Self::diagnose(line, &config)?;
+        Self::write_diagnostics(output, &diagnostics)?;
+      }
+      if config.show_key {
+        let key = report.key.as_deref().unwrap_or_default();
+        writeln!(output, "key: {key}")?;
+      }
+      writeln!(output, "{}", report.plaintext)?;
+    }
+
+    Ok(reports)
+  }
+
+  /// Decrypts every line like [`Vigenere::decrypt`] and reports the key
+  /// along with the method that found it.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &VigenereDecryptConfig,
+  ) -> Result<Vec<CrackReport>> {
+    config.validate()?;
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let method = match (&config.key, config.key_length) {
+      (Some(_), _) => "key",
+      (None, Some(_)) => "frequency analysis",
+      (None, None) => "autocorrelation",
+    };
+
+    // Every line tries the best ranked key lengths and the period
+    // estimated by autocorrelation.
+    let searches = match (&config.key, config.key_length) {
+      (None, None) => content.lines().count(),
+      _ => 0,
+    };
+    let candidates = KEY_LENGTH_CANDIDATES
+      .min(config.max_key_length.saturating_sub(1) as usize)
+      + 1;
+    let progress =
+      Progress::new("vigenere", searches * candidates, config.progress);
+
+    let reports = content
+      .lines()
+      .map(|line| {
+        let attack = match (&config.key, &config.wordlist) {
+          (None, Some(wordlist)) => {
+            Self::decrypt_with_wordlist(line, wordlist, config)
+          }
+          _ => None,
+        };
+        match attack {
+          Some(report) => report,
+          None => {
+            let (plaintext, key) = Self::decrypt_line(line, config, &progress);
+            CrackReport::new_in(plaintext, key, method, config.language)
+          }
+        }
+      })
+      .collect();
+
+    if searches > 0 {
+      progress.finish();
+    }
+    Ok(reports)
+  }
+
+  /// Decrypts a line and returns its plaintext along with the key that
+  /// was used or derived, if decryption succeeded.
+  fn decrypt_line(
+    line: &str,
+    config: &VigenereDecryptConfig,
+    progress: &Progress,
+  ) -> (String, Option<String>) {
+    let mut input = Cursor::new(line.as_bytes());
+    let mut output = Vec::new();
+
+    let alphabet = &config.alphabet;
+
+    let result = if let Some(key) = &config.key {
+      Self::decrypt_with_key(&mut input, &mut output, key, alphabet)
+    } else if let Some(key_length) = config.key_length {
+      Self::decrypt_with_key_length(
+        &mut input,
+        &mut output,
+        key_length,
+        alphabet,
+        config.language,
+      )
+    } else {
+      Self::decrypt_with_max_key_length(
+        &mut input,
+        &mut output,
+        config.max_key_length,
+        alphabet,
+        config.language,
+        progress,
+      )
+    };
+
+    match result {
+      Ok(key) => match String::from_utf8(output) {
+        Ok(plaintext) => (plaintext, Some(key)),
+        Err(_) => (line.into(), None),
+      },
+      Err(_) => (line.into(), None),
+    }
+  }
+
+  /// Decrypts with a known key and returns the key as written in the
+  /// alphabet, which is uppercase for alphabets without lowercase.
+  fn decrypt_with_key<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    key: &str,
+    alphabet: &Alphabet,
+  ) -> Result<String> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let shifts = alphabet.key_shifts(key)?;
+    let key = Self::derive_key(&shifts, alphabet);
+    let mut shifts = shifts.into_iter().cycle();
+
+    let plaintext: String = content
+      .chars()
+      .map(|c| match alphabet.contains(c) {
+        true => alphabet.shift(c, -(shifts.next().unwrap() as isize)),
+        false => c,
+      })
+      .collect();
+
+    write!(output, "{plaintext}")?;
+    Ok(key)
+  }
+
+  fn decrypt_with_key_length<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    key_length: u8,
+    alphabet: &Alphabet,
+    language: Language,
+  ) -> Result<String> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let mut shifts = Vec::new();
+    let mut buf = Cursor::new(content.as_bytes());
+    let caesars = Vigenere::caesar_segments(&mut buf, key_length, alphabet)?;
+
+    for caesar in &caesars {
+      let mut buf = Cursor::new(caesar.as_bytes());
+      let (_, shift) = Caesar::find_best_shift(&mut buf, alphabet, language)?;
+      shifts.push(shift);
+    }
+
+    let mut input = Cursor::new(content.as_bytes());
+    let mut buf = Vec::new();
+    let key = Self::derive_key(&shifts, alphabet);
+    Vigenere::decrypt_with_key(&mut input, &mut buf, &key, alphabet)?;
+    let plaintext = String::from_utf8(buf).unwrap();
+    write!(output, "{plaintext}")?;
+    Ok(key)
+  }
+
+  fn decrypt_with_max_key_length<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    max_key_length: u8,
+    alphabet: &Alphabet,
+    language: Language,
+    progress: &Progress,
+  ) -> Result<String> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+
+    let key_lengths =
+      Self::key_length_candidates(&content, max_key_length, alphabet)?;
+
+    let content = Arc::new(content);
+    let best_result =
+      Arc::new(Mutex::new((String::new(), String::new(), f32::MAX)));
+
+    key_lengths.into_par_iter().for_each(|key_length| {
+      let mut shifts = vec![0; key_length as usize];
+      let mut input = Cursor::new(content.as_bytes());
+      let caesars =
+        Self::caesar_segments(&mut input, key_length, alphabet).unwrap();
+
+      caesars.into_iter().enumerate().for_each(|(index, caesar)| {
+        let mut buf = Cursor::new(caesar.as_bytes());
+        let (_, s) =
+          Caesar::find_best_shift(&mut buf, alphabet, language).unwrap();
+        shifts[index] = s;
+      });
+
+      let key = Self::derive_key(&shifts, alphabet);
+      let mut buf = Vec::new();
+      let mut input = Cursor::new(content.as_bytes());
+      Self::decrypt_with_key(&mut input, &mut buf, &key, alphabet).unwrap();
+      let candidate = String::from_utf8(buf).unwrap();
+      let mut input = Cursor::new(candidate.as_bytes());
+
+      if let Ok(score) = FrequencyAnalyzer::score_text_in(&mut input, language)
+      {
+        progress.tick(1, score);
+        let local_best_result = (candidate.clone(), key, score);
+
+        let mut best_result = best_result.lock().unwrap();
+        if local_best_result.2 < best_result.2 {
+          *best_result = local_best_result;
+        }
+      }
+    });
+
+    let (plaintext, key, _) = best_result.lock().unwrap().clone();
+    write!(output, "{plaintext}")?;
+    Ok(Self::shortest_period(&key))
+  }
+
+  /// The key lengths that are solved fully when cracking: the best
+  /// ranked ones and the period estimated by autocorrelation, each
+  /// collapsed to its smallest divisor within [`KEY_LENGTH_TOLERANCE`].
+  fn key_length_candidates(
+    content: &str,
+    max_key_length: u8,
+    alphabet: &Alphabet,
+  ) -> Result<Vec<u8>> {
+    let ranking = Self::rank_key_lengths_in(content, max_key_length, alphabet)?;
+    let random = 1.0 / alphabet.len() as f32;
+    let index = |key_length: u8| {
+      ranking
+        .iter()
+        .find(|(length, _)| *length == key_length)
+        .map_or(random, |(_, index)| *index)
+    };
+    let collapse = |key_length: u8| {
+      let excess = index(key_length) - random;
+      (2..key_length)
+        .filter(|divisor| key_length % divisor == 0)
+        .find(|&divisor| {
+          index(divisor) - random >= KEY_LENGTH_TOLERANCE * excess
+        })
+        .unwrap_or(key_length)
+    };
+
+    let mut key_lengths = Vec::new();
+    for &(key_length, _) in &ranking {
+      if key_lengths.len() == KEY_LENGTH_CANDIDATES {
+        break;
+      }
+      let key_length = collapse(key_length);
+      if !key_lengths.contains(&key_length) {
+        key_lengths.push(key_length);
+      }
+    }
+
+    let period =
+      Autocorrelation::estimate_period(content, max_key_length as usize);
+    if let Some(period) = period.and_then(|period| u8::try_from(period).ok()) {
+      let period = collapse(period);
+      if period >= 2 && !key_lengths.contains(&period) {
+        key_lengths.push(period);
+      }
+    }
+
+    Ok(key_lengths)
+  }
+
+  /// Weighs every key length `2..=max_key_length` of a line like the
+  /// cracker does, so a surprising choice can be understood and the key
+  /// length overridden. A known key length is the only one tried.
+  pub fn diagnose(
+    line: &str,
+    config: &VigenereDecryptConfig,
+  ) -> Result<Vec<KeyLengthDiagnostic>> {
+    config.validate()?;
+    let alphabet = &config.alphabet;
+    let max_key_length = config
+      .max_key_length
+      .max(config.key_length.unwrap_or_default());
+    let tried = match config.key_length {
+      Some(key_length) => vec![key_length],
+      None => Self::key_length_candidates(line, max_key_length, alphabet)?,
+    };
+
+    let mut diagnostics =
+      Self::rank_key_lengths_in(line, max_key_length, alphabet)?
+        .into_par_iter()
+        .map(|(key_length, index_of_coincidence)| {
+          let mut input = Cursor::new(line.as_bytes());
+          let mut output = Vec::new();
+          let key = Self::decrypt_with_key_length(
+            &mut input,
+            &mut output,
+            key_length,
+            alphabet,
+            config.language,
+          )?;
+          let chi_square = FrequencyAnalyzer::score_text_in(
+            &mut output.as_slice(),
+            config.language,
+          )
+          .unwrap_or(f32::MAX);
+          Ok(KeyLengthDiagnostic {
+            key_length,
+            index_of_coincidence,
+            chi_square,
+            key,
+            tried: tried.contains(&key_length),
+            chosen: false,
+          })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.key_length);
+    if let Some(best) = diagnostics
+      .iter_mut()
+      .filter(|diagnostic| diagnostic.tried)
+      .min_by(|a, b| a.chi_square.total_cmp(&b.chi_square))
+    {
+      best.chosen = true;
+    }
+    Ok(diagnostics)
+  }
+
+  /// Writes diagnostics as a table. The chosen key length is marked
+  /// with `*` and the other lengths that were tried with `+`.
+  pub fn write_diagnostics<W: Write>(
+    output: &mut W,
+    diagnostics: &[KeyLengthDiagnostic],
+  ) -> Result<()> {
+    writeln!(output, "  length  column ic  chi-square  key")?;
+    for diagnostic in diagnostics {
+      let mark = match (diagnostic.chosen, diagnostic.tried) {
+        (true, _) => '*',
+        (false, true) => '+',
+        (false, false) => ' ',
+      };
+      writeln!(
+        output,
+        "{mark} {:>6}  {:>9.4}  {:>10.2}  {}",
+        diagnostic.key_length,
+        diagnostic.index_of_coincidence,
+        diagnostic.chi_square,
+        diagnostic.key
+      )?;
+    }
+    Ok(())
+  }
+
+  /// Ranks the key lengths `2..=max_key_length` by the average index of
+  /// coincidence of their Caesar columns, highest first.
+  ///
+  /// Columns encrypted with a single shift keep the index of English,
+  /// so the true key length and its multiples rank at the top.
+  pub fn rank_key_lengths(
+    content: &str,
+    max_key_length: u8,
+  ) -> Result<Vec<(u8, f32)>> {
+    Self::rank_key_lengths_in(content, max_key_length, &Alphabet::default())
+  }
+
+  /// Ranks the key lengths like [`Vigenere::rank_key_lengths`] for a
+  /// ciphertext over another alphabet.
+  pub fn rank_key_lengths_in(
+    content: &str,
+    max_key_length: u8,
+    alphabet: &Alphabet,
+  ) -> Result<Vec<(u8, f32)>> {
+    let mut ranking = Vec::new();
+
+    for key_length in 2..=max_key_length {
+      let mut input = Cursor::new(content.as_bytes());
+      let caesars = Self::caesar_segments(&mut input, key_length, alphabet)?;
+      let index: f32 = caesars
+        .iter()
+        .map(|caesar| {
+          FrequencyAnalyzer::index_of_coincidence_in(caesar, alphabet)
+        })
+        .sum();
+      ranking.push((key_length, index / key_length as f32));
+    }
+
+    ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Ok(ranking)
+  }
+
+  /// Reduces a key like `LEMONLEMON` that repeats itself to `LEMON`.
+  fn shortest_period(key: &str) -> String {
+    let symbols: Vec<char> = key.chars().collect();
+    let period = (1..symbols.len())
+      .filter(|period| symbols.len() % period == 0)
+      .find(|&period| {
+        (period..symbols.len()).all(|i| symbols[i] == symbols[i - period])
+      })
+      .unwrap_or(symbols.len());
+    symbols[..period].iter().collect()
+  }
+
+  /// The candidate key of a word of the wordlist.
+  ///
+  /// Only the symbols of the alphabet are kept of the word, and only
+  /// words of the given key length if it is known.
+  fn wordlist_key(
+    word: &str,
+    key_length: Option<u8>,
+    alphabet: &Alphabet,
+  ) -> Option<String> {
+    let key: String = word
+      .chars()
+      .filter_map(|c| alphabet.index(c))
+      .map(|index| alphabet.symbol(index))
+      .collect();
+    let length = key.chars().count();
+    match key_length {
+      _ if length == 0 => None,
+      Some(key_length) if length != key_length as usize => None,
+      _ => Some(key),
+    }
+  }
+
+  /// Tries the words of the wordlist as keys with a
+  /// [`DictionaryAttack`] and reports the most English-like plaintext,
+  /// if it is English enough.
+  pub fn decrypt_with_wordlist(
+    line: &str,
+    wordlist: &str,
+    config: &VigenereDecryptConfig,
+  ) -> Option<CrackReport> {
+    let alphabet = &config.alphabet;
+    DictionaryAttack::run(wordlist, &config.dictionary, |word| {
+      let key = Self::wordlist_key(word, config.key_length, alphabet)?;
+      let mut input = Cursor::new(line.as_bytes());
+      let mut output = Vec::new();
+      Self::decrypt_with_key(&mut input, &mut output, &key, alphabet).ok()?;
+      let plaintext = String::from_utf8(output).ok()?;
+      let score = FrequencyAnalyzer::bigram_score(&plaintext);
+      (score <= WORDLIST_THRESHOLD).then(|| {
+        let key = Some(key);
+        CrackReport::new_in(plaintext, key, "wordlist", config.language)
+      })
+    })
+    .into_iter()
+    .next()
+  }
+
+  /// Slides a known plaintext across the letters of the ciphertext.
+  ///
+  /// At every offset the difference between ciphertext and crib yields
+  /// a fragment of the running key. If the fragment repeats with a
+  /// period of at most `max_key_length` that it covers at least twice,
+  /// it is reported as a key. The shortest period is used per offset,
+  /// and matches are sorted by key length and offset.
+  pub fn crib_keys(
+    cipher: &str,
+    crib: &str,
+    max_key_length: u8,
+  ) -> Vec<CribMatch> {
+    Self::crib_keys_in(cipher, crib, max_key_length, &Alphabet::default())
+  }
+
+  /// Finds the keys of a crib like [`Vigenere::crib_keys`] for a
+  /// ciphertext over another alphabet.
+  pub fn crib_keys_in(
+    cipher: &str,
+    crib: &str,
+    max_key_length: u8,
+    alphabet: &Alphabet,
+  ) -> Vec<CribMatch> {
+    let letters = |text: &str| -> Vec<usize> {
+      text.chars().filter_map(|c| alphabet.index(c)).collect()
+    };
+    let length = alphabet.len();
+    let cipher = letters(cipher);
+    let crib = letters(crib);
+
+    if crib.is_empty() || crib.len() > cipher.len() {
+      return Vec::new();
+    }
+
+    let max_period = (max_key_length as usize).min(crib.len() / 2);
+    let mut matches: Vec<CribMatch> = (0..=cipher.len() - crib.len())
+      .filter_map(|offset| {
+        let fragment: Vec<usize> = crib
+          .iter()
+          .zip(&cipher[offset..])
+          .map(|(&p, &c)| (c + length - p) % length)
+          .collect();
+
+        let period = (1..=max_period).find(|&period| {
+          (period..fragment.len()).all(|i| fragment[i] == fragment[i - period])
+        })?;
+
+        let key = (0..period)
+          .map(|j| fragment[(j + period - offset % period) % period])
+          .map(|shift| alphabet.symbol(shift))
+          .collect();
+
+        Some(CribMatch { offset, key })
+      })
+      .collect();
+
+    matches.sort_by_key(|m| (m.key.len(), m.offset));
+    matches
+  }
+
+  fn caesar_segments<R: Read>(
+    input: &mut R,
+    key_length: u8,
+    alphabet: &Alphabet,
+  ) -> Result<Vec<String>> {
+    let mut caesars = vec![String::new(); key_length as usize];
+    let mut index = 0;
+    let mut text = String::new();
+    input.read_to_string(&mut text)?;
+
+    for c in text.chars() {
+      if alphabet.contains(c) {
+        let group_index = index % key_length as usize;
+        caesars[group_index].push(c);
+        index += 1;
+      }
+    }
+
+    Ok(caesars)
+  }
+
+  fn derive_key(shifts: &[usize], alphabet: &Alphabet) -> String {
+    shifts.iter().map(|&shift| alphabet.symbol(shift)).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::env;
+  use std::fs::File;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_example_output() -> Result<()> {
+    let assets = "src/vigenere/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    let input_path = path.join("input.txt");
+    let output_path = path.join("output.txt");
+    let mut input_file = File::open(&input_path)?;
+    let mut output_buffer = Vec::new();
+    let config = VigenereDecryptConfig::default();
+    Vigenere::decrypt(&mut input_file, &mut output_buffer, config)?;
+    let mut expected_output = String::new();
+    File::open(&output_path)?.read_to_string(&mut expected_output)?;
+    let output_string = String::from_utf8(output_buffer).unwrap();
+    assert_eq!(output_string, expected_output);
+    Ok(())
+  }
+
+  #[test]
+  fn test_show_key() -> Result<()> {
+    let plaintext = "It was the best of times, it was the worst of times, \
+      it was the age of wisdom, it was the age of foolishness, it was the \
+      epoch of belief, it was the epoch of incredulity, it was the season \
+      of light, it was the season of darkness";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+
+    let mut output = Vec::new();
+    let config = VigenereDecryptConfig::new(None, Some(5), None, true);
+    Vigenere::decrypt(&mut cipher.as_slice(), &mut output, config)?;
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, format!("key: LEMON\n{plaintext}\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_with_wordlist() {
+    let words = "KEY LEMON SECRET PASSWORD";
+    let config = VigenereDecryptConfig::default();
+
+    let cipher = "sxvrgd sx frag, llg vrxec kj ghempx";
+    let report =
+      Vigenere::decrypt_with_wordlist(cipher, words, &config).unwrap();
+    assert_eq!(report.key.as_deref(), Some("SECRET"));
+    assert_eq!(report.plaintext, "attack at dawn, the enemy is coming");
+
+    assert!(
+      Vigenere::decrypt_with_wordlist("XQZVJ KWPLM", words, &config).is_none()
+    );
+
+    let config = VigenereDecryptConfig {
+      key_length: Some(5),
+      ..Default::default()
+    };
+    assert!(Vigenere::decrypt_with_wordlist(cipher, words, &config).is_none());
+  }
+
+  #[test]
+  fn test_rank_key_lengths() -> Result<()> {
+    let plaintext = "It was the best of times, it was the worst of times, \
+      it was the age of wisdom, it was the age of foolishness, it was the \
+      epoch of belief, it was the epoch of incredulity, it was the season \
+      of light, it was the season of darkness";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+
+    let cipher = String::from_utf8(cipher).unwrap();
+    let ranking = Vigenere::rank_key_lengths(&cipher, 12)?;
+    let mut top: Vec<u8> =
+      ranking.iter().take(2).map(|(length, _)| *length).collect();
+    top.sort();
+    assert_eq!(top, vec![5, 10]);
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_short_text_prefers_period_to_multiples() -> Result<()> {
+    let plaintext = "It was the best of times, it was the worst of times, \
+      it was the age of wisdom, it was the age of foolishness, it was the \
+      epoch of belief, it was the epoch of incredulity, it was the season \
+      of light, it was the season of darkness, it was the spring of hope, \
+      it was the winter of despair, we had everything before us, we had \
+      nothing before us";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+
+    let config = VigenereDecryptConfig::default();
+    let reports = Vigenere::crack(&mut cipher.as_slice(), &config)?;
+    assert_eq!(reports[0].key.as_deref(), Some("LEMON"));
+    assert_eq!(reports[0].plaintext, plaintext);
+    Ok(())
+  }
+
+  #[test]
+  fn test_diagnose() -> Result<()> {
+    let plaintext = "It was the best of times, it was the worst of times, \
+      it was the age of wisdom, it was the age of foolishness, it was the \
+      epoch of belief, it was the epoch of incredulity";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+    let cipher = String::from_utf8(cipher).unwrap();
+
+    let config = VigenereDecryptConfig::new(None, None, Some(8), false);
+    let diagnostics = Vigenere::diagnose(&cipher, &config)?;
+    assert_eq!(diagnostics.len(), 7);
+    let chosen: Vec<_> = diagnostics.iter().filter(|d| d.chosen).collect();
+    assert_eq!(chosen.len(), 1);
+    assert_eq!((chosen[0].key_length, chosen[0].key.as_str()), (5, "LEMON"));
+    assert!(chosen[0].tried);
+
+    let mut output = Vec::new();
+    Vigenere::write_diagnostics(&mut output, &diagnostics)?;
+    let table = String::from_utf8(output).unwrap();
+    assert!(table.lines().any(|line| line.starts_with("*      5")));
+    Ok(())
+  }
+
+  #[test]
+  fn test_shortest_period() {
+    assert_eq!(Vigenere::shortest_period("LEMONLEMON"), "LEMON");
+    assert_eq!(Vigenere::shortest_period("ABAB"), "AB");
+    assert_eq!(Vigenere::shortest_period("ABA"), "ABA");
+  }
+
+  #[test]
+  fn test_crib_keys() -> Result<()> {
+    let plaintext = "We will meet at the old bridge, attack at dawn as planned";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+    let cipher = String::from_utf8(cipher).unwrap();
+
+    let matches = Vigenere::crib_keys(&cipher, "attack at dawn", 20);
+    let expected = CribMatch {
+      offset: 24,
+      key: String::from("LEMON"),
+    };
+    assert_eq!(matches.first(), Some(&expected));
+    Ok(())
+  }
+
+  #[test]
+  fn test_crib_keys_in_alphabet() -> Result<()> {
+    let alphabet = Language::Russian.alphabet();
+    let plaintext = "Враг будет разбит, победа будет за нами";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig {
+      alphabet: alphabet.clone(),
+      ..VigenereEncryptConfig::new("ЛЕС")
+    };
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+    let cipher = String::from_utf8(cipher).unwrap();
+
+    let matches = Vigenere::crib_keys_in(&cipher, "победа", 20, &alphabet);
+    let expected = CribMatch {
+      offset: 15,
+      key: String::from("ЛЕС"),
+    };
+    assert_eq!(matches.first(), Some(&expected));
+    Ok(())
+  }
+
+  #[test]
+  fn test_crib_longer_than_cipher() {
+    assert!(Vigenere::crib_keys("ABC", "ATTACK", 20).is_empty());
+  }
+
+  #[test]
+  fn test_vigenere_encrypt() {
+    let input_text = "HELLO WORLD";
+    let key = "KEY";
+    let mut input = Cursor::new(input_text);
+    let mut output = Vec::new();
+    let config = VigenereEncryptConfig::new(key);
+    Vigenere::encrypt(&mut input, &mut output, config).unwrap();
+    let encrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(encrypted_text, "RIJVS UYVJN");
+  }
+
+  #[test]
+  fn test_vigenere_encrypt_with_lowercase() {
+    let input_text = "hello world";
+    let key = "key";
+    let mut input = Cursor::new(input_text);
+    let mut output = Vec::new();
+    let config = VigenereEncryptConfig::new(key);
+    Vigenere::encrypt(&mut input, &mut output, config).unwrap();
+    let encrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(encrypted_text, "rijvs uyvjn");
+  }
+
+  #[test]
+  fn test_vigenere_encrypt_with_non_alpha_chars() {
+    let input_text = "HELLO, WORLD!";
+    let key = "KEY";
+    let mut input = Cursor::new(input_text);
+    let mut output = Vec::new();
+    let config = VigenereEncryptConfig::new(key);
+    Vigenere::encrypt(&mut input, &mut output, config).unwrap();
+    let encrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(encrypted_text, "RIJVS, UYVJN!");
+  }
+
+  #[test]
+  fn test_decrypt_with_known_key() {
+    let encrypted_text = "RIJVS UYVJN";
+    let key = "KEY";
+    let mut input = Cursor::new(encrypted_text);
+    let mut output = Vec::new();
+    Vigenere::decrypt_with_key(
+      &mut input,
+      &mut output,
+      key,
+      &Alphabet::default(),
+    )
+    .unwrap();
+    let decrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(decrypted_text, "HELLO WORLD");
+  }
+
+  #[test]
+  fn test_decrypt_with_known_key_lowercase() {
+    let encrypted_text = "rijvs uyvjn";
+    let key = "key";
+    let mut input = Cursor::new(encrypted_text);
+    let mut output = Vec::new();
+    Vigenere::decrypt_with_key(
+      &mut input,
+      &mut output,
+      key,
+      &Alphabet::default(),
+    )
+    .unwrap();
+    let decrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(decrypted_text, "hello world");
+  }
+
+  #[test]
+  fn test_decrypt_with_known_key_mixed_case() {
+    let encrypted_text = "RiJvS UyVjN";
+    let key = "KeY";
+    let mut input = Cursor::new(encrypted_text);
+    let mut output = Vec::new();
+    Vigenere::decrypt_with_key(
+      &mut input,
+      &mut output,
+      key,
+      &Alphabet::default(),
+    )
+    .unwrap();
+    let decrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(decrypted_text, "HeLlO WoRlD");
+  }
+
+  #[test]
+  fn test_decrypt_with_known_key_special_chars() {
+    let encrypted_text = "RIJVS, UYVJN!";
+    let key = "KEY";
+    let mut input = Cursor::new(encrypted_text);
+    let mut output = Vec::new();
+    Vigenere::decrypt_with_key(
+      &mut input,
+      &mut output,
+      key,
+      &Alphabet::default(),
+    )
+    .unwrap();
+    let decrypted_text = String::from_utf8(output).unwrap();
+    assert_eq!(decrypted_text, "HELLO, WORLD!");
+  }
+
+  #[test]
+  fn test_create_caesars() {
+    let mut text = Cursor::new("VIGENERE");
+    let key_length = 3;
+    let caesars =
+      Vigenere::caesar_segments(&mut text, key_length, &Alphabet::default())
+        .unwrap();
+    assert_eq!(
+      caesars,
+      vec!["VER".to_string(), "INE".to_string(), "GE".to_string()]
+    );
+  }
+
+  #[test]
+  fn test_crack_reports_method() -> Result<()> {
+    let plaintext = "the enemy will attack the northern gate at dawn";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig::new("LEMON");
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+
+    let config = VigenereDecryptConfig {
+      key: Some(String::from("lemon")),
+      ..Default::default()
+    };
+    let reports = Vigenere::crack(&mut cipher.as_slice(), &config)?;
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert_eq!(reports[0].key.as_deref(), Some("LEMON"));
+    assert_eq!(reports[0].method, "key");
+
+    let config = VigenereDecryptConfig {
+      wordlist: Some(String::from("apple LEMON melon")),
+      ..Default::default()
+    };
+    let reports = Vigenere::crack(&mut cipher.as_slice(), &config)?;
+    assert_eq!(reports[0].method, "wordlist");
+    Ok(())
+  }
+
+  #[test]
+  fn test_custom_alphabet() -> Result<()> {
+    let alphabet = Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789")?;
+    let plaintext = "MEET AT PIER 42 AT 9";
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig {
+      alphabet: alphabet.clone(),
+      ..VigenereEncryptConfig::new("K3Y")
+    };
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+    assert_eq!(
+      String::from_utf8(cipher.clone()).unwrap(),
+      "W723 3H ZB21 XQ KM X"
+    );
+
+    let config = VigenereDecryptConfig {
+      key: Some(String::from("k3y")),
+      alphabet,
+      ..Default::default()
+    };
+    let reports = Vigenere::crack(&mut cipher.as_slice(), &config)?;
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert_eq!(reports[0].key.as_deref(), Some("K3Y"));
+
+    let config = VigenereEncryptConfig::new("K3Y");
+    let error =
+      Vigenere::encrypt(&mut plaintext.as_bytes(), &mut Vec::new(), config);
+    assert!(error.is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_russian() -> Result<()> {
+    let plaintext = "В начале было Слово, и Слово было у Бога, и Слово было \
+      Бог. Оно было в начале у Бога. Все через Него начало быть, и без \
+      Него ничто не начало быть, что начало быть. В Нем была жизнь, и \
+      жизнь была свет человеков. И свет во тьме светит, и тьма не \
+      объяла его";
+    let alphabet = Language::Russian.alphabet();
+    let mut cipher = Vec::new();
+    let config = VigenereEncryptConfig {
+      alphabet: alphabet.clone(),
+      ..VigenereEncryptConfig::new("ЛЕС")
+    };
+    Vigenere::encrypt(&mut plaintext.as_bytes(), &mut cipher, config)?;
+
+    let config = VigenereDecryptConfig {
+      key_length: Some(3),
+      alphabet,
+      language: Language::Russian,
+      ..Default::default()
+    };
+    let reports = Vigenere::crack(&mut cipher.as_slice(), &config)?;
+    assert_eq!(reports[0].key.as_deref(), Some("ЛЕС"));
+    assert_eq!(reports[0].plaintext, plaintext);
+    assert!(reports[0].score.is_some());
+    assert!(CrackReport::check_confidence(&reports).is_ok());
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_rejects_zero_key_length() {
+    let config = VigenereDecryptConfig::new(None, Some(0), None, false);
+    let error = Vigenere::crack(&mut "LXFOPV".as_bytes(), &config).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+  }
+
+  #[test]
+  fn test_crack_rejects_max_key_length_below_two() {
+    for max_key_length in [0, 1] {
+      let config =
+        VigenereDecryptConfig::new(None, None, Some(max_key_length), false);
+      let error =
+        Vigenere::crack(&mut "LXFOPV".as_bytes(), &config).unwrap_err();
+      assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+  }
+}
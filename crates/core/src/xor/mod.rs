@@ -0,0 +1,208 @@
+pub mod repeating_key;
+
+use std::{fmt::Display, iter::repeat};
+
+use crate::hex::Hex;
+
+/// Side on which shorter inputs are padded to the longest one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum PadSide {
+  /// Inputs are aligned at their last byte.
+  Left,
+  /// Inputs are aligned at their first byte.
+  #[default]
+  Right,
+}
+
+/// How inputs of different lengths are combined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct XorPadding {
+  pub byte: u8,
+  pub side: PadSide,
+  /// Cut all inputs to the shortest one instead of padding.
+  pub truncate: bool,
+}
+
+impl XorPadding {
+  pub fn new(byte: u8, side: PadSide, truncate: bool) -> Self {
+    Self {
+      byte,
+      side,
+      truncate,
+    }
+  }
+
+  /// The input padded or truncated to `length` bytes.
+  fn align(&self, input: &[u8], length: usize) -> Vec<u8> {
+    match self.side {
+      PadSide::Right => input
+        .iter()
+        .copied()
+        .chain(repeat(self.byte))
+        .take(length)
+        .collect(),
+      PadSide::Left => {
+        let skip = input.len().saturating_sub(length);
+        let fill = length.saturating_sub(input.len());
+        repeat(self.byte)
+          .take(fill)
+          .chain(input[skip..].iter().copied())
+          .collect()
+      }
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Xor {
+  pub hex: Hex,
+}
+
+impl Xor {
+  pub fn new(hex: Hex) -> Self {
+    Self { hex }
+  }
+
+  /// XORs all inputs after aligning them as described by the padding.
+  pub fn xor_all(inputs: &[Vec<u8>], padding: &XorPadding) -> Self {
+    let lengths = inputs.iter().map(Vec::len);
+    let length = match padding.truncate {
+      true => lengths.min(),
+      false => lengths.max(),
+    };
+    let length = length.unwrap_or_default();
+
+    let mut bytes = vec![0; length];
+    for input in inputs {
+      let aligned = padding.align(input, length);
+      bytes
+        .iter_mut()
+        .zip(aligned)
+        .for_each(|(byte, b)| *byte ^= b);
+    }
+
+    Self::new(Hex::new(bytes))
+  }
+
+  pub fn xor_bytes(alpha: &[u8], beta: &[u8]) -> Self {
+    let bytes = alpha
+      .iter()
+      .zip(beta.iter())
+      .map(|(alpha, beta)| alpha ^ beta)
+      .collect();
+
+    Self::new(Hex::new(bytes))
+  }
+
+  pub fn xor_bytes_padded(alpha: &[u8], beta: &[u8], pad: u8) -> Self {
+    let max_len = std::cmp::max(alpha.len(), beta.len());
+    let alpha_padded = alpha.iter().chain(repeat(&pad)).take(max_len);
+    let beta_padded = beta.iter().chain(repeat(&pad)).take(max_len);
+    let bytes = alpha_padded.zip(beta_padded).map(|(a, b)| a ^ b).collect();
+
+    Self::new(Hex::new(bytes))
+  }
+}
+
+impl Display for Xor {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let hex = &self.hex;
+    write!(f, "{hex}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_xor() {
+    let alpha = b"Hello";
+    let beta = b"World";
+
+    let result = Xor::xor_bytes(alpha, beta);
+
+    let expected = vec![
+      b'H' ^ b'W',
+      b'e' ^ b'o',
+      b'l' ^ b'r',
+      b'l' ^ b'l',
+      b'o' ^ b'd',
+    ];
+
+    assert_eq!(result.hex.bytes, expected);
+  }
+
+  #[test]
+  fn test_xor_display() {
+    let alpha = b"Hello";
+    let beta = b"Hello";
+
+    let xor = Xor::xor_bytes(alpha, beta);
+
+    let result = format!("{xor}");
+    let expected = String::from("0000000000");
+
+    assert_eq!(result, expected);
+  }
+
+  #[test]
+  fn test_xor_with_key_longer_than_plaintext() {
+    let plaintext = b"HELLO";
+    let key = b"SECRETKEY";
+
+    let expected = vec![27, 0, 15, 30, 10, 84, 75, 69, 89];
+    let result = Xor::xor_bytes_padded(plaintext, key, 0);
+
+    assert_eq!(result.hex.bytes, expected);
+  }
+
+  #[test]
+  fn test_xor_with_plaintext_longer_than_key() {
+    let plaintext = b"HELLOTHERE";
+    let key = b"KEY";
+
+    let expected = vec![3, 0, 21, 76, 79, 84, 72, 69, 82, 69];
+    let result = Xor::xor_bytes_padded(plaintext, key, 0);
+
+    assert_eq!(result.hex.bytes, expected);
+  }
+
+  #[test]
+  fn test_xor_all_is_order_independent() {
+    let inputs = vec![b"KEY".to_vec(), b"HELLO".to_vec(), b"SECRETS".to_vec()];
+    let mut reversed = inputs.clone();
+    reversed.reverse();
+    let padding = XorPadding::default();
+    let xor = Xor::xor_all(&inputs, &padding);
+    assert_eq!(xor, Xor::xor_all(&reversed, &padding));
+    assert_eq!(xor.hex.bytes.len(), 7);
+  }
+
+  #[test]
+  fn test_xor_all_padding() {
+    let inputs = vec![vec![0x0f, 0xf0, 0xaa], vec![0xff]];
+    let xor = |byte, side, truncate| {
+      let padding = XorPadding::new(byte, side, truncate);
+      Xor::xor_all(&inputs, &padding).hex.bytes
+    };
+
+    assert_eq!(xor(0x00, PadSide::Right, false), vec![0xf0, 0xf0, 0xaa]);
+    assert_eq!(xor(0x00, PadSide::Left, false), vec![0x0f, 0xf0, 0x55]);
+    assert_eq!(xor(0x20, PadSide::Right, false), vec![0xf0, 0xd0, 0x8a]);
+    assert_eq!(xor(0x00, PadSide::Right, true), vec![0xf0]);
+    assert_eq!(xor(0x00, PadSide::Left, true), vec![0x55]);
+  }
+
+  #[test]
+  fn test_xor_with_empty_inputs() {
+    let alpha = b"";
+    let beta = b"";
+
+    let expected: Vec<u8> = vec![];
+    let result = Xor::xor_bytes(alpha, beta);
+
+    assert_eq!(result.hex.bytes, expected);
+  }
+}
@@ -0,0 +1,475 @@
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+pub use crate::key_format::KeyFormat;
+use crate::{
+  dictionary_attack::{DictionaryAttack, DictionaryAttackConfig},
+  frequency_analysis::FrequencyAnalyzer,
+  hex::Hex,
+  report::CrackReport,
+};
+
+/// Number of the most likely key sizes that are solved when breaking.
+const KEY_SIZE_CANDIDATES: usize = 3;
+
+#[derive(Debug, PartialEq)]
+pub struct RepeatingKeyXorConfig {
+  pub key: Option<String>,
+  pub key_format: KeyFormat,
+  pub max_key_size: usize,
+  /// Wordlist whose words are tried as keys before breaking the cipher.
+  pub wordlist: Option<String>,
+  pub dictionary: DictionaryAttackConfig,
+}
+
+impl RepeatingKeyXorConfig {
+  pub fn new(
+    key: Option<String>,
+    key_format: KeyFormat,
+    max_key_size: usize,
+  ) -> Self {
+    Self {
+      key,
+      key_format,
+      max_key_size,
+      wordlist: None,
+      dictionary: DictionaryAttackConfig::default(),
+    }
+  }
+
+  fn key(&self) -> Result<Vec<u8>> {
+    match &self.key {
+      Some(key) => self.key_format.parse(key),
+      None => {
+        let message = "Repeating-key XOR requires a key";
+        Err(Error::new(ErrorKind::InvalidInput, message))
+      }
+    }
+  }
+}
+
+impl Default for RepeatingKeyXorConfig {
+  fn default() -> Self {
+    Self::new(None, KeyFormat::default(), 40)
+  }
+}
+
+/// XOR of the input with a key that is repeated for its whole length.
+pub struct RepeatingKeyXor;
+
+impl RepeatingKeyXor {
+  /// Encrypts the bytes of the input and writes the ciphertext as hex.
+  pub fn encrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RepeatingKeyXorConfig,
+  ) -> Result<()> {
+    let key = config.key()?;
+    let mut plaintext = Vec::new();
+    input.read_to_end(&mut plaintext)?;
+
+    let cipher = Hex::new(Self::apply(&plaintext, &key));
+    writeln!(output, "{cipher}")
+  }
+
+  /// Decrypts a hex ciphertext and writes the plaintext bytes.
+  ///
+  /// Without a key the words of the wordlist are tried and otherwise the
+  /// cipher is broken, and the recovered key is written as hex on the
+  /// line before the plaintext. Returns the key.
+  pub fn decrypt<R: Read, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    config: RepeatingKeyXorConfig,
+  ) -> Result<Vec<u8>> {
+    let cipher = Self::read_cipher(input)?;
+
+    let key = match config.key {
+      Some(_) => config.key()?,
+      None => {
+        let (key, _) = Self::find_key(&cipher, &config)?;
+        writeln!(output, "key: {}", Hex::new(key.clone()))?;
+        key
+      }
+    };
+
+    output.write_all(&Self::apply(&cipher, &key))?;
+    Ok(key)
+  }
+
+  /// Decrypts like [`RepeatingKeyXor::decrypt`] and reports the key as
+  /// hex, breaking the cipher if no key is given.
+  pub fn crack<R: Read>(
+    input: &mut R,
+    config: &RepeatingKeyXorConfig,
+  ) -> Result<CrackReport> {
+    let cipher = Self::read_cipher(input)?;
+
+    let (key, method) = match config.key {
+      Some(_) => (config.key()?, "key"),
+      None => Self::find_key(&cipher, config)?,
+    };
+
+    let plaintext = Self::apply(&cipher, &key);
+    let plaintext = String::from_utf8_lossy(&plaintext).into_owned();
+    let key = Some(Hex::new(key).to_string());
+    Ok(CrackReport::new(plaintext, key, method))
+  }
+
+  /// The key of the best plaintext of the wordlist, or the key of
+  /// [`RepeatingKeyXor::break_cipher`] if no word decrypts to text, with
+  /// the method that found it.
+  fn find_key(
+    cipher: &[u8],
+    config: &RepeatingKeyXorConfig,
+  ) -> Result<(Vec<u8>, &'static str)> {
+    let report = config.wordlist.as_deref().and_then(|wordlist| {
+      Self::decrypt_with_wordlist(cipher, wordlist, &config.dictionary)
+        .into_iter()
+        .next()
+    });
+    match report.and_then(|report| report.key) {
+      Some(key) => Ok((Hex::parse_hex(&key)?.bytes, "wordlist")),
+      None => {
+        let key = Self::break_cipher(cipher, config.max_key_size)?;
+        Ok((key, "hamming distance"))
+      }
+    }
+  }
+
+  /// Decrypts with the words of the wordlist as keys and returns the
+  /// best plaintexts of the dictionary attack with the keys as hex.
+  /// Words whose plaintext is not printable text are skipped.
+  pub fn decrypt_with_wordlist(
+    cipher: &[u8],
+    wordlist: &str,
+    config: &DictionaryAttackConfig,
+  ) -> Vec<CrackReport> {
+    DictionaryAttack::run(wordlist, config, |word| {
+      let plaintext = Self::apply(cipher, word.as_bytes());
+      let printable = plaintext
+        .iter()
+        .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace());
+      printable.then(|| {
+        let plaintext = String::from_utf8_lossy(&plaintext).into_owned();
+        let key = Some(Hex::new(word.as_bytes().to_vec()).to_string());
+        CrackReport::new(plaintext, key, "wordlist")
+      })
+    })
+  }
+
+  /// Recovers the key of a repeating-key XOR ciphertext of English text.
+  ///
+  /// The most likely key sizes are the ones whose blocks have the lowest
+  /// normalized Hamming distance to each other. For each of them the
+  /// ciphertext is transposed into columns encrypted with the same key
+  /// byte, every column is solved as single-byte XOR and the key whose
+  /// plaintext scores best is returned.
+  pub fn break_cipher(cipher: &[u8], max_key_size: usize) -> Result<Vec<u8>> {
+    if cipher.is_empty() {
+      let message = "Cannot break an empty ciphertext";
+      return Err(Error::new(ErrorKind::InvalidInput, message));
+    }
+
+    Self::rank_key_sizes(cipher, max_key_size)
+      .into_iter()
+      .take(KEY_SIZE_CANDIDATES)
+      .map(|size| Self::shortest_period(&Self::solve_key(cipher, size)))
+      .map(|key| {
+        let score = FrequencyAnalyzer::byte_score(&Self::apply(cipher, &key));
+        (key, score)
+      })
+      .min_by(|a, b| a.1.total_cmp(&b.1))
+      .map(|(key, _)| key)
+      .ok_or_else(|| {
+        let message = "Failed to find a key size";
+        Error::new(ErrorKind::InvalidInput, message)
+      })
+  }
+
+  /// Candidate keys ranked by how much their plaintext looks like
+  /// English, best first: every single byte and the keys solved for the
+  /// most likely longer key sizes, as [`RepeatingKeyXor::break_cipher`]
+  /// tries them.
+  pub fn rank(cipher: &[u8], max_key_size: usize) -> Vec<Vec<u8>> {
+    let single_bytes = (0..=u8::MAX).map(|key| vec![key]);
+    let sized = Self::rank_key_sizes(cipher, max_key_size)
+      .into_iter()
+      .filter(|&size| size > 1)
+      .take(KEY_SIZE_CANDIDATES)
+      .map(|size| Self::shortest_period(&Self::solve_key(cipher, size)));
+
+    let mut keys: Vec<(Vec<u8>, f32)> = single_bytes
+      .chain(sized)
+      .map(|key| {
+        let score = FrequencyAnalyzer::byte_score(&Self::apply(cipher, &key));
+        (key, score)
+      })
+      .collect();
+
+    keys.sort_by(|a, b| a.1.total_cmp(&b.1));
+    keys.dedup_by(|a, b| a.0 == b.0);
+    keys.into_iter().map(|(key, _)| key).collect()
+  }
+
+  /// Ranks the keys of a hex ciphertext like [`RepeatingKeyXor::rank`]
+  /// and reports the `top` best decryptions with the keys as hex. The
+  /// best words of the wordlist come first.
+  pub fn crack_top<R: Read>(
+    input: &mut R,
+    config: &RepeatingKeyXorConfig,
+    top: usize,
+  ) -> Result<Vec<CrackReport>> {
+    let cipher = Self::read_cipher(input)?;
+    let dictionary = DictionaryAttackConfig {
+      top,
+      ..config.dictionary.clone()
+    };
+    let mut reports = match &config.wordlist {
+      Some(wordlist) => {
+        Self::decrypt_with_wordlist(&cipher, wordlist, &dictionary)
+      }
+      None => Vec::new(),
+    };
+
+    let ranked = Self::rank(&cipher, config.max_key_size)
+      .into_iter()
+      .map(|key| {
+        let plaintext = Self::apply(&cipher, &key);
+        let plaintext = String::from_utf8_lossy(&plaintext).into_owned();
+        let key = Some(Hex::new(key).to_string());
+        CrackReport::new(plaintext, key, "hamming distance")
+      })
+      .filter(|report| reports.iter().all(|word| word.key != report.key))
+      .take(top.saturating_sub(reports.len()))
+      .collect::<Vec<_>>();
+    reports.extend(ranked);
+    Ok(reports)
+  }
+
+  /// Key sizes from `1..=max_key_size` sorted by the average Hamming
+  /// distance between consecutive blocks of that size, normalized by the
+  /// size. Plaintext XORed with the same key bytes keeps the small
+  /// distance of English text, while misaligned blocks look random.
+  pub fn rank_key_sizes(cipher: &[u8], max_key_size: usize) -> Vec<usize> {
+    let max_key_size = max_key_size.min(cipher.len() / 2).max(1);
+
+    let mut sizes: Vec<(usize, f32)> = (1..=max_key_size)
+      .map(|size| {
+        let blocks: Vec<&[u8]> = cipher.chunks_exact(size).collect();
+        let pairs = blocks.len().saturating_sub(1).max(1);
+        let distance: u32 = blocks
+          .windows(2)
+          .map(|pair| Self::hamming_distance(pair[0], pair[1]))
+          .sum();
+        (size, distance as f32 / pairs as f32 / size as f32)
+      })
+      .collect();
+
+    sizes.sort_by(|a, b| a.1.total_cmp(&b.1));
+    sizes.into_iter().map(|(size, _)| size).collect()
+  }
+
+  /// Number of differing bits between two byte strings.
+  pub fn hamming_distance(alpha: &[u8], beta: &[u8]) -> u32 {
+    alpha
+      .iter()
+      .zip(beta)
+      .map(|(alpha, beta)| (alpha ^ beta).count_ones())
+      .sum()
+  }
+
+  /// The key byte whose decryption of the bytes looks most like English.
+  pub fn solve_single_byte(bytes: &[u8]) -> u8 {
+    (0..=u8::MAX)
+      .map(|key| {
+        let score = FrequencyAnalyzer::byte_score(&Self::apply(bytes, &[key]));
+        (key, score)
+      })
+      .min_by(|a, b| a.1.total_cmp(&b.1))
+      .map(|(key, _)| key)
+      .unwrap_or_default()
+  }
+
+  fn solve_key(cipher: &[u8], size: usize) -> Vec<u8> {
+    (0..size)
+      .map(|offset| {
+        let column: Vec<u8> =
+          cipher.iter().skip(offset).step_by(size).copied().collect();
+        Self::solve_single_byte(&column)
+      })
+      .collect()
+  }
+
+  /// Reduces a key that repeats itself, since every multiple of the key
+  /// size decrypts the ciphertext as well.
+  fn shortest_period(key: &[u8]) -> Vec<u8> {
+    let period = (1..key.len())
+      .filter(|period| key.len() % period == 0)
+      .find(|&period| (period..key.len()).all(|i| key[i] == key[i - period]))
+      .unwrap_or(key.len());
+    key[..period].to_vec()
+  }
+
+  /// Reads a hex ciphertext, ignoring whitespace and line breaks.
+  pub fn read_cipher<R: Read>(input: &mut R) -> Result<Vec<u8>> {
+    let mut content = String::new();
+    input.read_to_string(&mut content)?;
+    let hex: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+    Ok(Hex::parse_hex(&hex)?.bytes)
+  }
+
+  /// XORs every byte with the key byte at the same position modulo the
+  /// key length, which makes encryption and decryption the same.
+  pub fn apply(bytes: &[u8], key: &[u8]) -> Vec<u8> {
+    bytes
+      .iter()
+      .zip(key.iter().cycle())
+      .map(|(byte, key)| byte ^ key)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::dictionary_attack::{CaseRule, Mangling};
+  use std::env;
+  use std::fs;
+  use std::path::PathBuf;
+
+  const PLAINTEXT: &str = "Burning 'em, if you ain't quick and nimble\n\
+    I go crazy when I hear a cymbal";
+
+  const CIPHER: &str = "0b3637272a2b2e63622c2e69692a23693a2a3c6324202d623d63\
+    343c2a26226324272765272a282b2f20430a652e2c652a3124333a653e2b2027630c692b\
+    20283165286326302e27282f";
+
+  fn config(key: &str, key_format: KeyFormat) -> RepeatingKeyXorConfig {
+    RepeatingKeyXorConfig {
+      key: Some(key.to_string()),
+      key_format,
+      ..RepeatingKeyXorConfig::default()
+    }
+  }
+
+  fn plaintext() -> Vec<u8> {
+    let assets = "src/vigenere/assets";
+    let path = env::var("CARGO_MANIFEST_DIR")
+      .map(|dir| PathBuf::from(dir).join(assets))
+      .unwrap_or_else(|_| {
+        env::current_dir()
+          .expect("Failed to get current directory")
+          .join("crates/core")
+          .join(assets)
+      });
+
+    fs::read(path.join("output.txt")).unwrap()
+  }
+
+  #[test]
+  fn test_encrypt() -> Result<()> {
+    let mut output = Vec::new();
+    let config = config("ICE", KeyFormat::Ascii);
+    RepeatingKeyXor::encrypt(&mut PLAINTEXT.as_bytes(), &mut output, config)?;
+    assert_eq!(String::from_utf8(output).unwrap(), format!("{CIPHER}\n"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt() -> Result<()> {
+    let mut output = Vec::new();
+    let config = config("494345", KeyFormat::Hex);
+    RepeatingKeyXor::decrypt(&mut CIPHER.as_bytes(), &mut output, config)?;
+    assert_eq!(output, PLAINTEXT.as_bytes());
+    Ok(())
+  }
+
+  #[test]
+  fn test_hamming_distance() {
+    let distance =
+      RepeatingKeyXor::hamming_distance(b"this is a test", b"wokka wokka!!!");
+    assert_eq!(distance, 37);
+  }
+
+  #[test]
+  fn test_solve_single_byte() {
+    let cipher =
+      RepeatingKeyXor::apply(b"Cooking MC's like a pound of bacon", b"X");
+    assert_eq!(RepeatingKeyXor::solve_single_byte(&cipher), b'X');
+  }
+
+  #[test]
+  fn test_crack_top() -> Result<()> {
+    let cipher =
+      RepeatingKeyXor::apply(b"Cooking MC's like a pound of bacon", b"X");
+    let cipher = Hex::new(cipher).to_string();
+    let config = RepeatingKeyXorConfig {
+      max_key_size: 1,
+      ..RepeatingKeyXorConfig::default()
+    };
+    let reports =
+      RepeatingKeyXor::crack_top(&mut cipher.as_bytes(), &config, 3)?;
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0].plaintext, "Cooking MC's like a pound of bacon");
+    assert_eq!(reports[0].key.as_deref(), Some("58"));
+    Ok(())
+  }
+
+  #[test]
+  fn test_break_cipher() -> Result<()> {
+    let plaintext = plaintext();
+    for key in [&b"ICE"[..], b"Terminator X: Bring the noise", &[0x8f, 0x02]] {
+      let cipher = RepeatingKeyXor::apply(&plaintext, key);
+      assert_eq!(RepeatingKeyXor::break_cipher(&cipher, 40)?, key);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn test_decrypt_without_key() -> Result<()> {
+    let cipher = Hex::new(RepeatingKeyXor::apply(&plaintext(), b"LEMON"));
+    let mut output = Vec::new();
+    let config = RepeatingKeyXorConfig::default();
+    let input = cipher.to_string();
+    RepeatingKeyXor::decrypt(&mut input.as_bytes(), &mut output, config)?;
+
+    let mut expected = b"key: 4c454d4f4e\n".to_vec();
+    expected.extend(plaintext());
+    assert_eq!(output, expected);
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack_with_wordlist() -> Result<()> {
+    let plaintext = b"Attack at dawn, the bridge is clear";
+    let input = Hex::new(RepeatingKeyXor::apply(plaintext, b"Secret"));
+    let config = RepeatingKeyXorConfig {
+      wordlist: Some("password\nsecret\ndragon\n".to_string()),
+      dictionary: DictionaryAttackConfig {
+        mangling: Mangling::new(vec![CaseRule::Capitalize], false, 0),
+        ..DictionaryAttackConfig::default()
+      },
+      ..RepeatingKeyXorConfig::default()
+    };
+    let report =
+      RepeatingKeyXor::crack(&mut input.to_string().as_bytes(), &config)?;
+
+    assert_eq!(report.key.as_deref(), Some("536563726574"));
+    assert_eq!(report.plaintext.as_bytes(), plaintext);
+    assert_eq!(report.method, "wordlist");
+    Ok(())
+  }
+
+  #[test]
+  fn test_crack() -> Result<()> {
+    let cipher = Hex::new(RepeatingKeyXor::apply(&plaintext(), b"LEMON"));
+    let input = cipher.to_string();
+    let config = RepeatingKeyXorConfig::default();
+    let report = RepeatingKeyXor::crack(&mut input.as_bytes(), &config)?;
+
+    assert_eq!(report.key.as_deref(), Some("4c454d4f4e"));
+    assert_eq!(report.plaintext.as_bytes(), plaintext());
+    assert_eq!(report.method, "hamming distance");
+    Ok(())
+  }
+}
@@ -1,5 +1,13 @@
+use std::process::ExitCode;
+
 use cli::Cryptology;
 
-fn main() -> std::io::Result<()> {
-  Cryptology::execute()
+fn main() -> ExitCode {
+  match Cryptology::execute() {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(error) => {
+      eprintln!("Error: {error}");
+      ExitCode::from(error.exit_code())
+    }
+  }
 }
@@ -0,0 +1,34 @@
+use std::{env, path::PathBuf};
+
+/// Generates `cryptology.h` from the `extern "C"` functions into the
+/// build directory. The checked-in `include/cryptology.h` is compared
+/// with it by a test and only rewritten when asked to, see
+/// `test_header_is_current`.
+fn main() {
+  println!("cargo:rerun-if-changed=src");
+  println!("cargo:rerun-if-changed=cbindgen.toml");
+
+  let (Some(crate_dir), Some(out_dir)) =
+    (env::var_os("CARGO_MANIFEST_DIR"), env::var_os("OUT_DIR"))
+  else {
+    println!("cargo:warning=Cargo did not set the crate or output directory");
+    return;
+  };
+  let crate_dir = PathBuf::from(crate_dir);
+
+  let header = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+    .map_err(|error| error.to_string())
+    .and_then(|config| {
+      cbindgen::generate_with_config(&crate_dir, config)
+        .map_err(|error| error.to_string())
+    });
+
+  match header {
+    Ok(header) => {
+      header.write_to_file(PathBuf::from(out_dir).join("cryptology.h"));
+    }
+    Err(error) => {
+      println!("cargo:warning=Failed to generate the C header: {error}");
+    }
+  }
+}
@@ -0,0 +1,149 @@
+#ifndef CRYPTOLOGY_H
+#define CRYPTOLOGY_H
+
+/* Generated by cbindgen from crates/ffi, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every function, zero on success.
+typedef enum CryptologyStatus {
+  CRYPTOLOGY_STATUS_OK = 0,
+  // A required pointer was null.
+  CRYPTOLOGY_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8.
+  CRYPTOLOGY_STATUS_INVALID_UTF8 = 2,
+  // The input was not valid hex.
+  CRYPTOLOGY_STATUS_INVALID_HEX = 3,
+  // The key was missing, malformed or too short.
+  CRYPTOLOGY_STATUS_INVALID_KEY = 4,
+  // The input was rejected for another reason.
+  CRYPTOLOGY_STATUS_INVALID_INPUT = 5,
+  // Reading or writing failed.
+  CRYPTOLOGY_STATUS_IO = 6,
+  // The algorithm panicked, which is a bug.
+  CRYPTOLOGY_STATUS_PANIC = 7,
+  // The cracked plaintext is probably not English.
+  CRYPTOLOGY_STATUS_LOW_CONFIDENCE = 8,
+} CryptologyStatus;
+
+// Bytes owned by the library. An empty buffer has a null pointer.
+typedef struct CryptologyBuffer {
+  uint8_t *data;
+  size_t len;
+} CryptologyBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Releases a buffer written by this library. Freeing an empty buffer
+// does nothing.
+//
+// # Safety
+//
+// The buffer must have been written by this library and must not have
+// been freed before.
+void cryptology_buffer_free(struct CryptologyBuffer buffer);
+
+// A static, null-terminated description of a status.
+const char *cryptology_status_message(enum CryptologyStatus status);
+
+// Cracks every line of the ciphertext as a Caesar cipher by letter
+// frequencies and writes the plaintext lines.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes and `output` to a writable
+// buffer.
+enum CryptologyStatus cryptology_caesar_crack(const uint8_t *input,
+                                              size_t len,
+                                              struct CryptologyBuffer *output);
+
+// Encrypts the text with a Vigenère key.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, `key` to a
+// null-terminated string and `output` to a writable buffer.
+enum CryptologyStatus cryptology_vigenere_encrypt(const uint8_t *input,
+                                                  size_t len,
+                                                  const char *key,
+                                                  struct CryptologyBuffer *output);
+
+// Decrypts every line of the text with a Vigenère key, or cracks it if
+// `key` is null.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, `key` must be null or
+// point to a null-terminated string and `output` to a writable buffer.
+enum CryptologyStatus cryptology_vigenere_decrypt(const uint8_t *input,
+                                                  size_t len,
+                                                  const char *key,
+                                                  struct CryptologyBuffer *output);
+
+// XORs two byte strings, truncating to the shorter one.
+//
+// # Safety
+//
+// `alpha` and `beta` must point to `alpha_len` and `beta_len` readable
+// bytes and `output` to a writable buffer.
+enum CryptologyStatus cryptology_xor(const uint8_t *alpha,
+                                     size_t alpha_len,
+                                     const uint8_t *beta,
+                                     size_t beta_len,
+                                     struct CryptologyBuffer *output);
+
+// Decrypts repeating-key XOR. Without a key, `key` being null, the key
+// is recovered first.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes, `key` must be null or
+// point to `key_len` readable bytes and `output` to a writable buffer.
+enum CryptologyStatus cryptology_repeating_key_xor_decrypt(const uint8_t *input,
+                                                           size_t len,
+                                                           const uint8_t *key,
+                                                           size_t key_len,
+                                                           struct CryptologyBuffer *output);
+
+// Recovers the key of hex ciphertexts that reuse a one-time pad, one
+// per line, and writes all plaintexts, one per line.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes and `output` to a writable
+// buffer.
+enum CryptologyStatus cryptology_many_time_pad_decrypt(const uint8_t *input,
+                                                       size_t len,
+                                                       struct CryptologyBuffer *output);
+
+// Encodes bytes as lowercase hex.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes and `output` to a writable
+// buffer.
+enum CryptologyStatus cryptology_hex_encode(const uint8_t *input,
+                                            size_t len,
+                                            struct CryptologyBuffer *output);
+
+// Decodes hex into bytes, ignoring surrounding whitespace.
+//
+// # Safety
+//
+// `input` must point to `len` readable bytes and `output` to a writable
+// buffer.
+enum CryptologyStatus cryptology_hex_decode(const uint8_t *input,
+                                            size_t len,
+                                            struct CryptologyBuffer *output);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CRYPTOLOGY_H */
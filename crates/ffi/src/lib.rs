@@ -0,0 +1,495 @@
+//! C interface to the algorithms of `cryptology-core`.
+//!
+//! Every function takes its input as a pointer and a length and writes
+//! its result into a [`CryptologyBuffer`] that the caller releases with
+//! [`cryptology_buffer_free`]. The returned [`CryptologyStatus`] tells
+//! whether the buffer was written. The header `include/cryptology.h` is
+//! generated from this file by the build script.
+
+use std::{
+  ffi::{c_char, CStr},
+  io::ErrorKind,
+  panic::{self, AssertUnwindSafe},
+  ptr, slice,
+};
+
+use cryptology_core::{
+  caesar::CaesarConfig,
+  error::CryptologyError,
+  hex::Hex,
+  many_time_pad::{ManyTimePad, ManyTimePadConfig},
+  vigenere::{Vigenere, VigenereDecryptConfig, VigenereEncryptConfig},
+  xor::repeating_key::RepeatingKeyXor,
+  Caesar, Xor,
+};
+
+/// Largest key size that is tried when breaking repeating-key XOR.
+const MAX_KEY_SIZE: usize = 40;
+
+/// Result of every function, zero on success.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptologyStatus {
+  Ok = 0,
+  /// A required pointer was null.
+  NullPointer = 1,
+  /// A string argument was not valid UTF-8.
+  InvalidUtf8 = 2,
+  /// The input was not valid hex.
+  InvalidHex = 3,
+  /// The key was missing, malformed or too short.
+  InvalidKey = 4,
+  /// The input was rejected for another reason.
+  InvalidInput = 5,
+  /// Reading or writing failed.
+  Io = 6,
+  /// The algorithm panicked, which is a bug.
+  Panic = 7,
+  /// The cracked plaintext is probably not English.
+  LowConfidence = 8,
+}
+
+impl From<&CryptologyError> for CryptologyStatus {
+  fn from(error: &CryptologyError) -> Self {
+    match error {
+      CryptologyError::Hex { .. } => Self::InvalidHex,
+      CryptologyError::Key(_) => Self::InvalidKey,
+      CryptologyError::LowConfidence(_) => Self::LowConfidence,
+      CryptologyError::Batch { last, .. } => Self::from(last.as_ref()),
+      CryptologyError::Io(error) => match error.kind() {
+        ErrorKind::InvalidInput | ErrorKind::InvalidData => Self::InvalidInput,
+        _ => Self::Io,
+      },
+      CryptologyError::Open { .. } | CryptologyError::Create { .. } => Self::Io,
+    }
+  }
+}
+
+/// Bytes owned by the library. An empty buffer has a null pointer.
+#[repr(C)]
+#[derive(Debug)]
+pub struct CryptologyBuffer {
+  pub data: *mut u8,
+  pub len: usize,
+}
+
+impl CryptologyBuffer {
+  fn empty() -> Self {
+    Self {
+      data: ptr::null_mut(),
+      len: 0,
+    }
+  }
+
+  fn new(bytes: Vec<u8>) -> Self {
+    match bytes.is_empty() {
+      true => Self::empty(),
+      false => {
+        let len = bytes.len();
+        let data = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+        Self { data, len }
+      }
+    }
+  }
+}
+
+/// Releases a buffer written by this library. Freeing an empty buffer
+/// does nothing.
+///
+/// # Safety
+///
+/// The buffer must have been written by this library and must not have
+/// been freed before.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_buffer_free(buffer: CryptologyBuffer) {
+  if !buffer.data.is_null() {
+    let bytes = ptr::slice_from_raw_parts_mut(buffer.data, buffer.len);
+    drop(Box::from_raw(bytes));
+  }
+}
+
+/// A static, null-terminated description of a status.
+#[no_mangle]
+pub extern "C" fn cryptology_status_message(
+  status: CryptologyStatus,
+) -> *const c_char {
+  let message: &'static CStr = match status {
+    CryptologyStatus::Ok => c"Success",
+    CryptologyStatus::NullPointer => c"A required pointer was null",
+    CryptologyStatus::InvalidUtf8 => c"A string was not valid UTF-8",
+    CryptologyStatus::InvalidHex => c"The input was not valid hex",
+    CryptologyStatus::InvalidKey => c"The key was missing or invalid",
+    CryptologyStatus::InvalidInput => c"The input was invalid",
+    CryptologyStatus::Io => c"Reading or writing failed",
+    CryptologyStatus::Panic => c"The algorithm panicked",
+    CryptologyStatus::LowConfidence => c"The plaintext is probably wrong",
+  };
+  message.as_ptr()
+}
+
+/// Cracks every line of the ciphertext as a Caesar cipher by letter
+/// frequencies and writes the plaintext lines.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes and `output` to a writable
+/// buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_caesar_crack(
+  input: *const u8,
+  len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let config = CaesarConfig::default();
+    let reports = Caesar::crack(&mut bytes(input, len)?, &config)?;
+    let lines: Vec<String> =
+      reports.into_iter().map(|report| report.plaintext).collect();
+    Ok(lines.join("\n").into_bytes())
+  })
+}
+
+/// Encrypts the text with a Vigenère key.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, `key` to a
+/// null-terminated string and `output` to a writable buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_vigenere_encrypt(
+  input: *const u8,
+  len: usize,
+  key: *const c_char,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let config = VigenereEncryptConfig::new(string(key)?.ok_or_else(null)?);
+    let mut plaintext = Vec::new();
+    Vigenere::encrypt(&mut bytes(input, len)?, &mut plaintext, config)?;
+    Ok(plaintext)
+  })
+}
+
+/// Decrypts every line of the text with a Vigenère key, or cracks it if
+/// `key` is null.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, `key` must be null or
+/// point to a null-terminated string and `output` to a writable buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_vigenere_decrypt(
+  input: *const u8,
+  len: usize,
+  key: *const c_char,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let config = VigenereDecryptConfig {
+      key: string(key)?.map(String::from),
+      ..Default::default()
+    };
+    let reports = Vigenere::crack(&mut bytes(input, len)?, &config)?;
+    let lines: Vec<String> =
+      reports.into_iter().map(|report| report.plaintext).collect();
+    Ok(lines.join("\n").into_bytes())
+  })
+}
+
+/// XORs two byte strings, truncating to the shorter one.
+///
+/// # Safety
+///
+/// `alpha` and `beta` must point to `alpha_len` and `beta_len` readable
+/// bytes and `output` to a writable buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_xor(
+  alpha: *const u8,
+  alpha_len: usize,
+  beta: *const u8,
+  beta_len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let xor = Xor::xor_bytes(bytes(alpha, alpha_len)?, bytes(beta, beta_len)?);
+    Ok(xor.hex.bytes)
+  })
+}
+
+/// Decrypts repeating-key XOR. Without a key, `key` being null, the key
+/// is recovered first.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes, `key` must be null or
+/// point to `key_len` readable bytes and `output` to a writable buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_repeating_key_xor_decrypt(
+  input: *const u8,
+  len: usize,
+  key: *const u8,
+  key_len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let cipher = bytes(input, len)?;
+    let key = match key.is_null() {
+      true => RepeatingKeyXor::break_cipher(cipher, MAX_KEY_SIZE)?,
+      false => bytes(key, key_len)?.to_vec(),
+    };
+    Ok(RepeatingKeyXor::apply(cipher, &key))
+  })
+}
+
+/// Recovers the key of hex ciphertexts that reuse a one-time pad, one
+/// per line, and writes all plaintexts, one per line.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes and `output` to a writable
+/// buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_many_time_pad_decrypt(
+  input: *const u8,
+  len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let mut plaintexts = Vec::new();
+    let config = ManyTimePadConfig::new(None, true);
+    ManyTimePad::decrypt(&mut bytes(input, len)?, &mut plaintexts, config)?;
+    Ok(plaintexts)
+  })
+}
+
+/// Encodes bytes as lowercase hex.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes and `output` to a writable
+/// buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_hex_encode(
+  input: *const u8,
+  len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    Ok(
+      Hex::new(bytes(input, len)?.to_vec())
+        .to_string()
+        .into_bytes(),
+    )
+  })
+}
+
+/// Decodes hex into bytes, ignoring surrounding whitespace.
+///
+/// # Safety
+///
+/// `input` must point to `len` readable bytes and `output` to a writable
+/// buffer.
+#[no_mangle]
+pub unsafe extern "C" fn cryptology_hex_decode(
+  input: *const u8,
+  len: usize,
+  output: *mut CryptologyBuffer,
+) -> CryptologyStatus {
+  run(output, || {
+    let hex = std::str::from_utf8(bytes(input, len)?).map_err(|_| utf8())?;
+    Ok(Hex::parse_hex(hex.trim())?.bytes)
+  })
+}
+
+/// The reason a function failed before it reached the algorithm.
+enum Failure {
+  Status(CryptologyStatus),
+  Error(CryptologyError),
+}
+
+impl<E: Into<CryptologyError>> From<E> for Failure {
+  fn from(error: E) -> Self {
+    Self::Error(error.into())
+  }
+}
+
+fn null() -> Failure {
+  Failure::Status(CryptologyStatus::NullPointer)
+}
+
+fn utf8() -> Failure {
+  Failure::Status(CryptologyStatus::InvalidUtf8)
+}
+
+/// Runs an algorithm and writes its result into `output`. Panics are
+/// caught, since unwinding into C is undefined behavior.
+unsafe fn run(
+  output: *mut CryptologyBuffer,
+  algorithm: impl FnOnce() -> Result<Vec<u8>, Failure>,
+) -> CryptologyStatus {
+  if output.is_null() {
+    return CryptologyStatus::NullPointer;
+  }
+  output.write(CryptologyBuffer::empty());
+
+  match panic::catch_unwind(AssertUnwindSafe(algorithm)) {
+    Ok(Ok(bytes)) => {
+      output.write(CryptologyBuffer::new(bytes));
+      CryptologyStatus::Ok
+    }
+    Ok(Err(Failure::Status(status))) => status,
+    Ok(Err(Failure::Error(error))) => CryptologyStatus::from(&error),
+    Err(_) => CryptologyStatus::Panic,
+  }
+}
+
+/// A null pointer is only allowed for an empty input.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], Failure> {
+  match (data.is_null(), len) {
+    (true, 0) => Ok(&[]),
+    (true, _) => Err(null()),
+    (false, _) => Ok(slice::from_raw_parts(data, len)),
+  }
+}
+
+/// An optional null-terminated UTF-8 string.
+unsafe fn string<'a>(data: *const c_char) -> Result<Option<&'a str>, Failure> {
+  if data.is_null() {
+    return Ok(None);
+  }
+  CStr::from_ptr(data).to_str().map(Some).map_err(|_| utf8())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn call(
+    function: impl FnOnce(*mut CryptologyBuffer) -> CryptologyStatus,
+  ) -> Result<Vec<u8>, CryptologyStatus> {
+    let mut buffer = CryptologyBuffer::empty();
+    let status = function(&mut buffer);
+    let bytes = match buffer.data.is_null() {
+      true => Vec::new(),
+      false => {
+        unsafe { slice::from_raw_parts(buffer.data, buffer.len) }.to_vec()
+      }
+    };
+    unsafe { cryptology_buffer_free(buffer) };
+    match status {
+      CryptologyStatus::Ok => Ok(bytes),
+      status => Err(status),
+    }
+  }
+
+  #[test]
+  fn test_hex_roundtrip() {
+    let input = b"\x00\xffcryptology";
+    let hex = call(|out| unsafe {
+      cryptology_hex_encode(input.as_ptr(), input.len(), out)
+    })
+    .unwrap();
+    assert_eq!(hex, b"00ff63727970746f6c6f6779");
+
+    let bytes = call(|out| unsafe {
+      cryptology_hex_decode(hex.as_ptr(), hex.len(), out)
+    });
+    assert_eq!(bytes.unwrap(), input);
+
+    let invalid = call(|out| unsafe {
+      cryptology_hex_decode(c"f".as_ptr().cast(), 1, out)
+    });
+    assert_eq!(invalid, Err(CryptologyStatus::InvalidHex));
+  }
+
+  #[test]
+  fn test_vigenere() {
+    let plaintext = b"attack at dawn";
+    let key = c"LEMON".as_ptr();
+    let cipher = call(|out| unsafe {
+      cryptology_vigenere_encrypt(plaintext.as_ptr(), plaintext.len(), key, out)
+    })
+    .unwrap();
+    assert_eq!(cipher, b"lxfopv ef rnhr");
+
+    let decrypted = call(|out| unsafe {
+      cryptology_vigenere_decrypt(cipher.as_ptr(), cipher.len(), key, out)
+    });
+    assert_eq!(decrypted.unwrap(), plaintext);
+
+    let missing = call(|out| unsafe {
+      cryptology_vigenere_encrypt(plaintext.as_ptr(), 3, ptr::null(), out)
+    });
+    assert_eq!(missing, Err(CryptologyStatus::NullPointer));
+  }
+
+  #[test]
+  fn test_xor() {
+    let (alpha, beta) = (b"\x0f\xf0\xff", b"\xff\xff");
+    let xor = call(|out| unsafe {
+      cryptology_xor(alpha.as_ptr(), 3, beta.as_ptr(), 2, out)
+    });
+    assert_eq!(xor.unwrap(), b"\xf0\x0f");
+
+    let cipher =
+      RepeatingKeyXor::apply(b"Burning 'em, if you ain't quick", b"ICE");
+    let plaintext = call(|out| unsafe {
+      cryptology_repeating_key_xor_decrypt(
+        cipher.as_ptr(),
+        cipher.len(),
+        b"ICE".as_ptr(),
+        3,
+        out,
+      )
+    });
+    assert_eq!(plaintext.unwrap(), b"Burning 'em, if you ain't quick");
+  }
+
+  #[test]
+  fn test_errors() {
+    let status =
+      unsafe { cryptology_hex_encode(ptr::null(), 0, ptr::null_mut()) };
+    assert_eq!(status, CryptologyStatus::NullPointer);
+
+    let empty =
+      call(|out| unsafe { cryptology_hex_encode(ptr::null(), 0, out) });
+    assert_eq!(empty, Ok(Vec::new()));
+
+    let input = b"0102\n0304";
+    let single = call(|out| unsafe {
+      cryptology_repeating_key_xor_decrypt(
+        input.as_ptr(),
+        0,
+        ptr::null(),
+        0,
+        out,
+      )
+    });
+    assert_eq!(single, Err(CryptologyStatus::InvalidInput));
+
+    let message = unsafe {
+      CStr::from_ptr(cryptology_status_message(CryptologyStatus::InvalidKey))
+    };
+    assert_eq!(message, c"The key was missing or invalid");
+  }
+
+  /// Fails if the checked-in header differs from the generated one.
+  /// Running the test with `CRYPTOLOGY_UPDATE_HEADER=1` rewrites it.
+  #[test]
+  fn test_header_is_current() {
+    let generated = concat!(env!("OUT_DIR"), "/cryptology.h");
+    let generated = std::fs::read_to_string(generated)
+      .expect("The build script failed to generate the C header");
+    let header = concat!(env!("CARGO_MANIFEST_DIR"), "/include/cryptology.h");
+
+    if std::env::var_os("CRYPTOLOGY_UPDATE_HEADER").is_some() {
+      std::fs::write(header, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(header).unwrap_or_default();
+    assert!(
+      committed == generated,
+      "include/cryptology.h is outdated, run \
+       `CRYPTOLOGY_UPDATE_HEADER=1 cargo test -p cryptology-ffi`"
+    );
+  }
+}
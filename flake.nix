@@ -118,6 +118,8 @@
                 ./crates/cryptology/Cargo.toml
                 ./crates/cli/src
                 ./crates/cli/Cargo.toml
+                ./crates/core/src
+                ./crates/core/Cargo.toml
               ];
             });
 
@@ -128,6 +130,8 @@
               src = fileSetForCrate [
                 ./crates/cli/src
                 ./crates/cli/Cargo.toml
+                ./crates/core/src
+                ./crates/core/Cargo.toml
               ];
             });
         in {